@@ -18,6 +18,10 @@ pub enum AccountError {
     InvalidPassword(AccountAddress),
     #[error("invalid private key")]
     InvalidPrivateKey,
+    #[error("cannot rotate the key of account {0}, {1}")]
+    InvalidKeyRotation(AccountAddress, String),
+    #[error("{0} is disabled, the account service is in public mode")]
+    PublicModeForbidden(String),
 
     // logic error
     #[error("transaction sign error, {0:?}")]
@@ -3,10 +3,11 @@
 
 use crate::AccountInfo;
 use anyhow::Result;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
 use starcoin_service_registry::ServiceRequest;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::sign_message::{SigningMessage, TypedSigningMessage};
 use starcoin_types::transaction::authenticator::AccountSignature;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 use std::time::Duration;
@@ -26,6 +27,10 @@ pub enum AccountRequest {
         signer: AccountAddress,
         message: SigningMessage,
     },
+    SignTypedMessage {
+        signer: AccountAddress,
+        message: TypedSigningMessage,
+    },
     AccountAcceptedTokens {
         address: AccountAddress,
     },
@@ -40,6 +45,11 @@ pub enum AccountRequest {
         address: AccountAddress,
         password: String,
     },
+    RotateMultisigKey {
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    },
     ChangePassword {
         address: AccountAddress,
         new_password: String,
@@ -4,11 +4,11 @@
 use crate::message::{AccountRequest, AccountResponse};
 use crate::AccountInfo;
 use anyhow::Result;
-use starcoin_crypto::multi_ed25519::MultiEd25519Signature;
+use starcoin_crypto::multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature};
 use starcoin_service_registry::{ActorService, ServiceHandler, ServiceRef};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::sign_message::{SigningMessage, TypedSigningMessage};
 use starcoin_types::transaction::authenticator::AccountSignature;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 
@@ -31,6 +31,13 @@ pub trait AccountAsyncService:
         message: SigningMessage,
     ) -> Result<Vec<u8>>;
 
+    /// Signs the typed message with given address, the domain is checked by the caller.
+    async fn sign_typed_message(
+        &self,
+        address: AccountAddress,
+        message: TypedSigningMessage,
+    ) -> Result<AccountSignature>;
+
     async fn sign_txn(
         &self,
         raw_txn: RawUserTransaction,
@@ -53,6 +60,15 @@ pub trait AccountAsyncService:
     /// Return the private key as bytes for `address`
     async fn export_account(&self, address: AccountAddress, password: String) -> Result<Vec<u8>>;
 
+    /// Update the key shard of multisig account `address` to `new_public_key`,
+    /// keep the address unchanged.
+    async fn rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    ) -> Result<AccountInfo>;
+
     async fn accepted_tokens(&self, address: AccountAddress) -> Result<Vec<TokenCode>>;
 
     // change account password, user need to unlock account first.
@@ -138,12 +154,35 @@ where
                     bytes.extend(Into::<MultiEd25519Signature>::into(s).to_bytes().to_vec());
                     bytes
                 }
+                AccountSignature::Bls(p, s) => {
+                    let mut bytes = p.to_bytes();
+                    bytes.extend(s.to_bytes());
+                    bytes
+                }
             })
         } else {
             panic!("Unexpected response type.")
         }
     }
 
+    async fn sign_typed_message(
+        &self,
+        address: AccountAddress,
+        message: TypedSigningMessage,
+    ) -> Result<AccountSignature> {
+        let response = self
+            .send(AccountRequest::SignTypedMessage {
+                signer: address,
+                message,
+            })
+            .await??;
+        if let AccountResponse::MessageSignature(signature) = response {
+            Ok(*signature)
+        } else {
+            panic!("Unexpected response type.")
+        }
+    }
+
     async fn sign_txn(
         &self,
         raw_txn: RawUserTransaction,
@@ -218,6 +257,26 @@ where
         }
     }
 
+    async fn rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    ) -> Result<AccountInfo> {
+        let response = self
+            .send(AccountRequest::RotateMultisigKey {
+                address,
+                new_public_key,
+                password,
+            })
+            .await??;
+        if let AccountResponse::AccountInfo(account) = response {
+            Ok(*account)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
     async fn accepted_tokens(&self, address: AccountAddress) -> Result<Vec<TokenCode>> {
         let response = self
             .send(AccountRequest::AccountAcceptedTokens { address })
@@ -3,7 +3,9 @@
 
 use anyhow::Result;
 use starcoin_account::{account_storage::AccountStorage, AccountManager};
+use starcoin_account_api::error::AccountError;
 use starcoin_account_api::message::{AccountRequest, AccountResponse};
+use starcoin_account_api::AccountResult;
 use starcoin_config::NodeConfig;
 use starcoin_crypto::ValidCryptoMaterial;
 use starcoin_logger::prelude::*;
@@ -17,6 +19,8 @@ pub const DEFAULT_ACCOUNT_PASSWORD: &str = "";
 
 pub struct AccountService {
     manager: AccountManager,
+    /// In public mode, private keys can not be created or loaded, and signing is disabled.
+    public_mode: bool,
 }
 
 impl AccountService {
@@ -24,7 +28,17 @@ impl AccountService {
         let manager = AccountManager::new(AccountStorage::mock())?;
         //auto create default account.
         manager.create_account("")?;
-        Ok(Self { manager })
+        Ok(Self {
+            manager,
+            public_mode: false,
+        })
+    }
+
+    fn ensure_not_public_mode(&self, operation: &str) -> AccountResult<()> {
+        if self.public_mode {
+            return Err(AccountError::PublicModeForbidden(operation.to_string()));
+        }
+        Ok(())
     }
 }
 
@@ -44,6 +58,10 @@ impl MockHandler<AccountService> for AccountService {
 
 impl ActorService for AccountService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.public_mode {
+            info!("Account service is in public mode, skip default account and association account init.");
+            return Ok(());
+        }
         let account = self.manager.default_account_info()?;
 
         if account.is_none() {
@@ -78,8 +96,12 @@ impl ActorService for AccountService {
 impl ServiceFactory<AccountService> for AccountService {
     fn create(ctx: &mut ServiceContext<AccountService>) -> Result<AccountService> {
         let account_storage = ctx.get_shared::<AccountStorage>()?;
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
         let manager = AccountManager::new(account_storage)?;
-        Ok(Self { manager })
+        Ok(Self {
+            manager,
+            public_mode: config.vault.public_mode(),
+        })
     }
 }
 
@@ -90,9 +112,12 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
         _ctx: &mut ServiceContext<Self>,
     ) -> Result<AccountResponse> {
         let response = match msg {
-            AccountRequest::CreateAccount(password) => AccountResponse::AccountInfo(Box::new(
-                self.manager.create_account(password.as_str())?.info(),
-            )),
+            AccountRequest::CreateAccount(password) => {
+                self.ensure_not_public_mode("create account")?;
+                AccountResponse::AccountInfo(Box::new(
+                    self.manager.create_account(password.as_str())?.info(),
+                ))
+            }
             AccountRequest::GetDefaultAccount() => {
                 AccountResponse::AccountInfoOption(Box::new(self.manager.default_account_info()?))
             }
@@ -114,11 +139,24 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
             AccountRequest::SignTxn {
                 txn: raw_txn,
                 signer,
-            } => AccountResponse::SignedTxn(Box::new(self.manager.sign_txn(signer, *raw_txn)?)),
-            AccountRequest::SignMessage { message, signer } => AccountResponse::MessageSignature(
-                Box::new(self.manager.sign_message(signer, message)?),
-            ),
+            } => {
+                self.ensure_not_public_mode("sign txn")?;
+                AccountResponse::SignedTxn(Box::new(self.manager.sign_txn(signer, *raw_txn)?))
+            }
+            AccountRequest::SignMessage { message, signer } => {
+                self.ensure_not_public_mode("sign message")?;
+                AccountResponse::MessageSignature(Box::new(
+                    self.manager.sign_message(signer, message)?,
+                ))
+            }
+            AccountRequest::SignTypedMessage { message, signer } => {
+                self.ensure_not_public_mode("sign typed message")?;
+                AccountResponse::MessageSignature(Box::new(
+                    self.manager.sign_typed_message(signer, message)?,
+                ))
+            }
             AccountRequest::UnlockAccount(address, password, duration) => {
+                self.ensure_not_public_mode("unlock account")?;
                 self.manager
                     .unlock_account(address, password.as_str(), duration)?;
                 AccountResponse::UnlockAccountResponse
@@ -128,6 +166,7 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
                 AccountResponse::None
             }
             AccountRequest::ExportAccount { address, password } => {
+                self.ensure_not_public_mode("export account")?;
                 let data = self.manager.export_account(address, password.as_str())?;
                 AccountResponse::ExportAccountResponse(data)
             }
@@ -136,11 +175,25 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
                 password,
                 private_key,
             } => {
+                self.ensure_not_public_mode("import account")?;
                 let wallet =
                     self.manager
                         .import_account(address, private_key, password.as_str())?;
                 AccountResponse::AccountInfo(Box::new(wallet.info()))
             }
+            AccountRequest::RotateMultisigKey {
+                address,
+                new_public_key,
+                password,
+            } => {
+                self.ensure_not_public_mode("rotate multisig key")?;
+                let account = self.manager.rotate_multisig_key(
+                    address,
+                    new_public_key,
+                    password.as_str(),
+                )?;
+                AccountResponse::AccountInfo(Box::new(account.info()))
+            }
             AccountRequest::AccountAcceptedTokens { address } => {
                 let mut tokens = self.manager.accepted_tokens(address)?;
                 //auto add STC to accepted tokens.
@@ -153,6 +206,7 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
                 address,
                 new_password,
             } => {
+                self.ensure_not_public_mode("change password")?;
                 self.manager.change_password(address, new_password)?;
                 AccountResponse::None
             }
@@ -165,8 +219,9 @@ impl ServiceHandler<AccountService, AccountRequest> for AccountService {
 mod tests {
     use super::*;
     use starcoin_account_api::AccountAsyncService;
-    use starcoin_config::NodeConfig;
+    use starcoin_config::{NodeConfig, StarcoinOpt};
     use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+    use structopt::StructOpt;
 
     #[stest::test]
     async fn test_actor_launch() -> Result<()> {
@@ -183,4 +238,34 @@ mod tests {
         assert!(account.is_some());
         Ok(())
     }
+
+    #[stest::test]
+    async fn test_public_mode() -> Result<()> {
+        let opt = StarcoinOpt::from_iter_safe(vec![
+            "starcoin",
+            "-n",
+            "test",
+            "--vault-public-mode",
+            "true",
+        ])?;
+        let config = Arc::new(NodeConfig::load_with_opt(&opt)?);
+        assert!(config.vault.public_mode());
+        let registry = RegistryService::launch();
+        let account_storage =
+            AccountStorage::create_from_path(config.vault.dir(), config.storage.rocksdb_config())?;
+        registry.put_shared(config).await?;
+        registry.put_shared(account_storage).await?;
+        let service_ref = registry.register::<AccountService>().await?;
+        //default account will not be created in public mode.
+        assert!(service_ref.get_default_account().await?.is_none());
+        let err = service_ref
+            .create_account("".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<AccountError>()?,
+            AccountError::PublicModeForbidden(_)
+        ));
+        Ok(())
+    }
 }
@@ -10,7 +10,7 @@ use starcoin_crypto::{PrivateKey, ValidCryptoMaterial};
 use starcoin_storage::storage::StorageInstance;
 use starcoin_types::account_address;
 use starcoin_types::account_address::AccountAddress;
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::sign_message::{SigningMessage, TypedSigningMessage};
 use starcoin_types::transaction::authenticator::{AccountSignature, AuthenticationKey};
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 
@@ -75,6 +75,10 @@ impl Account {
         self.private_key.sign_message(message)
     }
 
+    pub fn sign_typed_message(&self, message: &TypedSigningMessage) -> AccountSignature {
+        self.private_key.sign(message)
+    }
+
     pub fn sign_txn(&self, raw_txn: RawUserTransaction) -> Result<SignedUserTransaction> {
         //TODO handle multi signature
         let signature = self.private_key.sign(&raw_txn);
@@ -4,13 +4,16 @@
 use crate::account::Account;
 use crate::account_storage::AccountStorage;
 
+use anyhow::{ensure, Result};
 use parking_lot::RwLock;
 use rand::prelude::*;
 use starcoin_account_api::error::AccountError;
 use starcoin_account_api::{AccountInfo, AccountPrivateKey, AccountResult};
 use starcoin_crypto::ed25519::Ed25519PrivateKey;
-use starcoin_crypto::{Uniform, ValidCryptoMaterial};
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_crypto::multi_ed25519::multi_shard::MultiEd25519KeyShard;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+use starcoin_crypto::{PrivateKey, Uniform, ValidCryptoMaterial};
+use starcoin_types::sign_message::{SigningMessage, TypedSigningMessage};
 use starcoin_types::transaction::authenticator::AccountSignature;
 use starcoin_types::{
     account_address::AccountAddress,
@@ -137,6 +140,36 @@ impl AccountManager {
         Ok(account.private_key().to_bytes().to_vec())
     }
 
+    /// Update the key shard of a multisig account to `new_public_key` after its authentication key
+    /// is rotated on chain, the private keys held by the current shard are kept.
+    /// The account address keeps unchanged, and the new shard is encrypted by the same password.
+    pub fn rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: &str,
+    ) -> AccountResult<Account> {
+        let account = Account::load(address, password, self.store.clone())?
+            .ok_or(AccountError::AccountNotExist(address))?;
+        let shard = match account.private_key() {
+            AccountPrivateKey::Multi(shard) => shard,
+            _ => {
+                return Err(AccountError::InvalidKeyRotation(
+                    address,
+                    "it is not a multisig account".to_string(),
+                ))
+            }
+        };
+        let new_shard = rotate_key_shard(shard, &new_public_key)
+            .map_err(|e| AccountError::InvalidKeyRotation(address, e.to_string()))?;
+        Account::create(
+            AccountPrivateKey::Multi(new_shard),
+            Some(address),
+            password.to_string(),
+            self.store.clone(),
+        )
+    }
+
     pub fn contains(&self, address: &AccountAddress) -> AccountResult<bool> {
         self.store
             .contain_address(*address)
@@ -201,6 +234,22 @@ impl AccountManager {
         }
     }
 
+    pub fn sign_typed_message(
+        &self,
+        signer_address: AccountAddress,
+        message: TypedSigningMessage,
+    ) -> AccountResult<AccountSignature> {
+        let pass = self.key_cache.write().get_pass(&signer_address);
+        match pass {
+            None => Err(AccountError::AccountLocked(signer_address)),
+            Some(p) => {
+                let account = Account::load(signer_address, p.as_str(), self.store.clone())?
+                    .ok_or(AccountError::AccountNotExist(signer_address))?;
+                Ok(account.sign_typed_message(&message))
+            }
+        }
+    }
+
     pub fn sign_txn(
         &self,
         signer_address: AccountAddress,
@@ -275,6 +324,47 @@ impl AccountManager {
     }
 }
 
+/// Build the key shard of `new_public_key` from the private keys held by `shard`.
+/// The private keys in a shard must be sequential in the public keys, so the held keys
+/// which still in the new signers must be adjacent after sort.
+fn rotate_key_shard(
+    shard: &MultiEd25519KeyShard,
+    new_public_key: &MultiEd25519PublicKey,
+) -> Result<MultiEd25519KeyShard> {
+    let mut held_keys = vec![];
+    for private_key in shard.private_keys() {
+        if let Some(pos) = new_public_key
+            .public_keys()
+            .iter()
+            .position(|k| k == &private_key.public_key())
+        {
+            held_keys.push((
+                pos,
+                Ed25519PrivateKey::try_from(private_key.to_bytes().as_slice())?,
+            ));
+        }
+    }
+    ensure!(
+        !held_keys.is_empty(),
+        "none of the local private keys is in the new signers"
+    );
+    held_keys.sort_by_key(|(pos, _)| *pos);
+    let index = held_keys[0].0;
+    ensure!(
+        held_keys
+            .iter()
+            .enumerate()
+            .all(|(i, (pos, _))| *pos == index + i),
+        "the local private keys are not adjacent in the new signers"
+    );
+    Ok(MultiEd25519KeyShard::new_multi(
+        new_public_key.public_keys().clone(),
+        *new_public_key.threshold(),
+        held_keys.into_iter().map(|(_, key)| key).collect(),
+        index as u8,
+    )?)
+}
+
 pub(crate) fn gen_private_key() -> Ed25519PrivateKey {
     let mut seed_rng = rand::rngs::OsRng;
     let seed_buf: [u8; 32] = seed_rng.gen();
@@ -111,6 +111,60 @@ pub fn test_wallet_unlock() -> Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_rotate_multisig_account_key() -> Result<()> {
+    use starcoin_crypto::ed25519::Ed25519PrivateKey;
+    use starcoin_crypto::multi_ed25519::multi_shard::MultiEd25519KeyShard;
+    use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+    use starcoin_crypto::{PrivateKey, Uniform};
+    use std::convert::TryFrom;
+
+    let tempdir = tempfile::tempdir()?;
+    let storage = AccountStorage::create_from_path(tempdir.path(), RocksdbConfig::default())?;
+    let manager = AccountManager::new(storage)?;
+
+    let mut rng = rand::thread_rng();
+    let mut shards = MultiEd25519KeyShard::generate(&mut rng, 3, 2)?;
+    let shard = shards.remove(0);
+    let address = shard.public_key().derived_address();
+    manager.import_account(address, shard.to_bytes(), "hello")?;
+
+    // keep the local signer, replace the other two signers, and raise the threshold.
+    let local_key = Ed25519PrivateKey::try_from(shard.private_keys()[0].to_bytes().as_slice())?;
+    let mut public_keys = vec![local_key.public_key()];
+    public_keys.push(Ed25519PrivateKey::generate(&mut rng).public_key());
+    public_keys.push(Ed25519PrivateKey::generate(&mut rng).public_key());
+    let new_public_key = MultiEd25519PublicKey::new(public_keys, 3)?;
+
+    let result = manager.rotate_multisig_key(address, new_public_key.clone(), "hell0");
+    assert!(result.is_err());
+
+    // none of the local private keys is in the signers.
+    let other_public_key = MultiEd25519PublicKey::new(
+        vec![
+            Ed25519PrivateKey::generate(&mut rng).public_key(),
+            Ed25519PrivateKey::generate(&mut rng).public_key(),
+        ],
+        2,
+    )?;
+    let result = manager.rotate_multisig_key(address, other_public_key, "hello");
+    assert!(matches!(
+        result.err().unwrap(),
+        AccountError::InvalidKeyRotation(addr, _) if addr == address
+    ));
+
+    let account = manager.rotate_multisig_key(address, new_public_key.clone(), "hello")?;
+    assert_eq!(account.address(), &address);
+    let info = manager
+        .account_info(address)?
+        .expect("account should exist");
+    assert_eq!(
+        info.public_key.as_multi().map(|k| k.to_bytes()),
+        Some(new_public_key.to_bytes())
+    );
+    Ok(())
+}
+
 // ignore for now.
 #[ignore]
 #[test]
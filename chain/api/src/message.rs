@@ -7,7 +7,7 @@ use starcoin_service_registry::ServiceRequest;
 use starcoin_types::block::BlockSummary;
 use starcoin_types::block::EpochUncleSummary;
 use starcoin_types::stress_test::TPS;
-use starcoin_types::transaction::BlockTransactionInfo;
+use starcoin_types::transaction::{BlockTransactionInfo, TransactionInfoWithProof};
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockTemplate},
     contract_event::ContractEventInfo,
@@ -44,6 +44,10 @@ pub enum ChainRequest {
     GetEventsByTxnHash {
         txn_hash: HashValue,
     },
+    GetTransactionProof {
+        block_hash: HashValue,
+        txn_idx: u64,
+    },
     GetBlocksByNumber(Option<BlockNumber>, u64),
     MainEvents(Filter),
     GetBlockIds {
@@ -80,6 +84,7 @@ pub enum ChainResponse {
     BlockHeaderVec(Vec<BlockHeader>),
     TransactionInfos(Vec<BlockTransactionInfo>),
     TransactionInfo(Option<BlockTransactionInfo>),
+    TransactionProof(Box<Option<TransactionInfoWithProof>>),
     Events(Vec<ContractEventInfo>),
     MainEvents(Vec<ContractEventInfo>),
     None,
@@ -9,7 +9,7 @@ use starcoin_types::block::{BlockSummary, EpochUncleSummary};
 use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
 use starcoin_types::filter::Filter;
 use starcoin_types::startup_info::ChainStatus;
-use starcoin_types::transaction::{BlockTransactionInfo, Transaction};
+use starcoin_types::transaction::{BlockTransactionInfo, Transaction, TransactionInfoWithProof};
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
     startup_info::StartupInfo,
@@ -31,6 +31,13 @@ pub trait ReadableChainService {
         block_id: HashValue,
         idx: u64,
     ) -> Result<Option<BlockTransactionInfo>>;
+    /// Get the txn info at `idx` of the block, with the proof of its inclusion in the txn
+    /// accumulator of the block.
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        idx: u64,
+    ) -> Result<Option<TransactionInfoWithProof>>;
     fn get_events_by_txn_info_hash(
         &self,
         txn_info_id: HashValue,
@@ -94,6 +101,11 @@ pub trait ChainAsyncService:
         block_hash: HashValue,
         idx: u64,
     ) -> Result<Option<BlockTransactionInfo>>;
+    async fn get_transaction_proof(
+        &self,
+        block_hash: HashValue,
+        idx: u64,
+    ) -> Result<Option<TransactionInfoWithProof>>;
     async fn get_events_by_txn_hash(&self, txn_hash: HashValue) -> Result<Vec<ContractEventInfo>>;
     /// for main
     async fn main_head_header(&self) -> Result<BlockHeader>;
@@ -261,6 +273,23 @@ where
             bail!("get txn info by block and idx error.")
         }
     }
+    async fn get_transaction_proof(
+        &self,
+        block_hash: HashValue,
+        idx: u64,
+    ) -> Result<Option<TransactionInfoWithProof>> {
+        let response = self
+            .send(ChainRequest::GetTransactionProof {
+                block_hash,
+                txn_idx: idx,
+            })
+            .await??;
+        if let ChainResponse::TransactionProof(proof) = response {
+            Ok(*proof)
+        } else {
+            bail!("get transaction proof error.")
+        }
+    }
     async fn get_events_by_txn_hash(&self, txn_hash: HashValue) -> Result<Vec<ContractEventInfo>> {
         let response = self
             .send(ChainRequest::GetEventsByTxnHash { txn_hash })
@@ -88,13 +88,23 @@ impl ChainNotifyHandlerService {
                 .get_transaction_info(txn_info_id)?
                 .map(|info| info.transaction_hash())
                 .ok_or_else(|| format_err!("cannot find txn info by it's id {}", &txn_info_id))?;
+            // the first txn of the block is the block metadata txn, which has no sender.
+            let sender = i
+                .checked_sub(1)
+                .and_then(|i| block.transactions().get(i))
+                .map(|txn| txn.sender());
             // get events directly by txn_info_id
             let events = store.get_contract_events(txn_info_id)?.unwrap_or_default();
-            all_events.extend(
-                events
-                    .into_iter()
-                    .map(|evt| Event::new(block_id, block_number, txn_hash, Some(i as u32), evt)),
-            );
+            all_events.extend(events.into_iter().map(|evt| {
+                Event::new(
+                    block_id,
+                    block_number,
+                    txn_hash,
+                    Some(i as u32),
+                    sender,
+                    evt,
+                )
+            }));
         }
         let events_notification: ContractEventNotification = Notification(all_events.into());
         ctx.broadcast(events_notification);
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::BlockHeader;
 use starcoin_types::{block::BlockNumber, contract_event::ContractEvent};
 use std::sync::Arc;
@@ -19,6 +20,8 @@ pub struct Event {
     pub transaction_hash: HashValue,
     // txn index in block
     pub transaction_index: Option<u32>,
+    /// The sender of the txn, None for the block metadata txn.
+    pub sender: Option<AccountAddress>,
     pub contract_event: ContractEvent,
 }
 
@@ -28,6 +31,7 @@ impl Event {
         block_number: BlockNumber,
         transaction_hash: HashValue,
         transaction_index: Option<u32>,
+        sender: Option<AccountAddress>,
         contract_event: ContractEvent,
     ) -> Self {
         Self {
@@ -35,6 +39,7 @@ impl Event {
             block_number,
             transaction_hash,
             transaction_index,
+            sender,
             contract_event,
         }
     }
@@ -12,6 +12,7 @@ use starcoin_genesis::Genesis;
 use starcoin_storage::Storage;
 use starcoin_types::block::{Block, BlockHeader};
 use starcoin_types::startup_info::ChainInfo;
+use starcoin_types::transaction::SignedUserTransaction;
 use starcoin_vm_types::on_chain_config::GlobalTimeOnChain;
 use std::sync::Arc;
 
@@ -107,11 +108,16 @@ impl MockChain {
     }
 
     pub fn produce(&self) -> Result<Block> {
+        self.produce_with_txns(vec![])
+    }
+
+    /// Produce a block with the user txns, the txns which can not be included are skipped.
+    pub fn produce_with_txns(&self, user_txns: Vec<SignedUserTransaction>) -> Result<Block> {
         let (template, _) = self.head.create_block_template(
             *self.miner.address(),
             Some(self.miner.public_key.authentication_key()),
             None,
-            vec![],
+            user_txns,
             vec![],
             None,
         )?;
@@ -1,7 +1,9 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{format_err, Error, Result};
+use anyhow::{ensure, format_err, Error, Result};
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::message::{ChainRequest, ChainResponse};
 use starcoin_chain_api::{ChainReader, ChainWriter, ReadableChainService};
@@ -16,7 +18,7 @@ use starcoin_types::block::{BlockSummary, EpochUncleSummary, ExecutedBlock, Uncl
 use starcoin_types::contract_event::ContractEventInfo;
 use starcoin_types::filter::Filter;
 use starcoin_types::system_events::NewHeadBlock;
-use starcoin_types::transaction::BlockTransactionInfo;
+use starcoin_types::transaction::{BlockTransactionInfo, TransactionInfoWithProof};
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
     contract_event::ContractEvent,
@@ -159,6 +161,12 @@ impl ServiceHandler<Self, ChainRequest> for ChainReaderService {
                 self.inner
                     .get_txn_info_by_block_and_index(block_id, txn_idx)?,
             )),
+            ChainRequest::GetTransactionProof {
+                block_hash,
+                txn_idx,
+            } => Ok(ChainResponse::TransactionProof(Box::new(
+                self.inner.get_transaction_proof(block_hash, txn_idx)?,
+            ))),
             ChainRequest::GetEventsByTxnHash { txn_hash } => {
                 let txn_info = self
                     .inner
@@ -349,6 +357,52 @@ impl ReadableChainService for ChainReaderServiceInner {
         self.storage
             .get_transaction_info_by_block_and_index(block_id, idx)
     }
+
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        idx: u64,
+    ) -> Result<Option<TransactionInfoWithProof>, Error> {
+        let block_info = match self.storage.get_block_info(block_id)? {
+            Some(block_info) => block_info,
+            None => return Ok(None),
+        };
+        let txn_info_ids = self.storage.get_block_txn_info_ids(block_id)?;
+        let txn_info_id = match txn_info_ids.get(idx as usize) {
+            Some(txn_info_id) => *txn_info_id,
+            None => return Ok(None),
+        };
+        let txn_info = self
+            .storage
+            .get_transaction_info(txn_info_id)?
+            .ok_or_else(|| format_err!("can't find txn info {}", txn_info_id))?;
+        // the txn infos of the block are the last leaves of the txn accumulator of the block.
+        let txn_accumulator_info = block_info.get_txn_accumulator_info();
+        let leaf_index = txn_accumulator_info
+            .num_leaves
+            .checked_sub(txn_info_ids.len() as u64)
+            .and_then(|first_index| first_index.checked_add(idx))
+            .ok_or_else(|| format_err!("invalid txn accumulator info of block {}", block_id))?;
+        let accumulator = MerkleAccumulator::new_with_info(
+            txn_accumulator_info.clone(),
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Transaction),
+        );
+        ensure!(
+            accumulator.get_leaf(leaf_index)? == Some(txn_info_id),
+            "txn info {} is not the leaf {} of the txn accumulator of block {}",
+            txn_info_id,
+            leaf_index,
+            block_id
+        );
+        let proof = accumulator
+            .get_proof(leaf_index)?
+            .ok_or_else(|| format_err!("can't get proof of txn accumulator leaf {}", leaf_index))?;
+        let (_, txn_info) = txn_info.into();
+        Ok(Some(TransactionInfoWithProof::new(
+            txn_info, leaf_index, proof,
+        )))
+    }
     fn get_events_by_txn_info_hash(
         &self,
         txn_info_id: HashValue,
@@ -18,6 +18,7 @@ use starcoin_open_block::OpenedBlock;
 use starcoin_state_api::{AccountStateReader, ChainState, ChainStateReader, ChainStateWriter};
 use starcoin_statedb::ChainStateDB;
 use starcoin_types::block::BlockIdAndNumber;
+use starcoin_types::block_bloom::BlockBloom;
 use starcoin_types::contract_event::ContractEventInfo;
 use starcoin_types::filter::Filter;
 use starcoin_types::startup_info::{ChainInfo, ChainStatus};
@@ -349,6 +350,8 @@ impl BlockChain {
     }
 
     //TODO consider move this logic to BlockExecutor
+    /// Execute the block and save it, the state, accumulator and block writes of the block are
+    /// written to the db in one atomic write batch, nothing is written if the block fails.
     fn execute_block_and_save(
         storage: &dyn Store,
         statedb: ChainStateDB,
@@ -357,6 +360,36 @@ impl BlockChain {
         epoch: &Epoch,
         parent_status: Option<ChainStatus>,
         block: Block,
+    ) -> Result<ExecutedBlock> {
+        storage.begin_write_batch();
+        match Self::execute_block_and_save_inner(
+            storage,
+            statedb,
+            txn_accumulator,
+            block_accumulator,
+            epoch,
+            parent_status,
+            block,
+        ) {
+            Ok(executed_block) => {
+                storage.commit_write_batch()?;
+                Ok(executed_block)
+            }
+            Err(e) => {
+                storage.discard_write_batch();
+                Err(e)
+            }
+        }
+    }
+
+    fn execute_block_and_save_inner(
+        storage: &dyn Store,
+        statedb: ChainStateDB,
+        txn_accumulator: MerkleAccumulator,
+        block_accumulator: MerkleAccumulator,
+        epoch: &Epoch,
+        parent_status: Option<ChainStatus>,
+        block: Block,
     ) -> Result<ExecutedBlock> {
         let header = block.header();
         debug_assert!(header.is_genesis() || parent_status.is_some());
@@ -425,6 +458,12 @@ impl BlockChain {
         statedb
             .flush()
             .map_err(BlockExecutorError::BlockChainStateErr)?;
+        storage.save_state_node_changes(
+            header.number(),
+            block_id,
+            header.parent_hash(),
+            statedb.take_node_changes(),
+        )?;
         // If chain state is matched, and accumulator is matched,
         // then, we save flush states, and save block data.
         watch(CHAIN_WATCH_NAME, "n24");
@@ -481,6 +520,16 @@ impl BlockChain {
             "events' length should be equal to txn infos' length"
         );
         let txn_info_ids: Vec<_> = txn_infos.iter().map(|info| info.id()).collect();
+        let bloom = BlockBloom::from_txn_events(transactions.iter().zip(txn_events.iter()).map(
+            |(txn, events)| {
+                let sender = match txn {
+                    Transaction::UserTransaction(txn) => Some(txn.sender()),
+                    _ => None,
+                };
+                (sender, events.as_slice())
+            },
+        ));
+        storage.save_block_bloom(block_id, bloom)?;
         for (info_id, events) in txn_info_ids.iter().zip(txn_events.into_iter()) {
             storage.save_contract_events(*info_id, events)?;
         }
@@ -781,64 +830,83 @@ impl BlockChain {
         };
         let mut event_with_infos = vec![];
         'outer: loop {
-            let block = self.get_block_by_number(cur_block_number)?.ok_or_else(|| {
+            let block_id = self.get_hash_by_number(cur_block_number)?.ok_or_else(|| {
                 anyhow::anyhow!(format!(
                     "cannot find block({}) on main chain(head: {})",
                     cur_block_number,
                     chain_header.id()
                 ))
             })?;
-            let block_id = block.id();
-            let block_number = block.header().number();
-            let mut txn_info_ids = self
-                .storage
-                .get_block_txn_info_ids(block_id)?
-                .into_iter()
-                .enumerate()
-                .collect::<Vec<_>>();
-            if reverse {
-                txn_info_ids.reverse();
-            }
-            for (idx, id) in txn_info_ids.iter() {
-                let events = self.storage.get_contract_events(*id)?.ok_or_else(|| {
+            // skip the block without reading its events if the bloom can not match the filter,
+            // the blocks committed before the bloom is saved are always read.
+            let may_match = match self.storage.get_block_bloom(block_id)? {
+                Some(bloom) => filter.bloom_matching(&bloom),
+                None => true,
+            };
+            if may_match {
+                let block = self.storage.get_block_by_hash(block_id)?.ok_or_else(|| {
                     anyhow::anyhow!(format!(
-                        "cannot find events of txn with txn_info_id {} on main chain(header: {})",
-                        id,
+                        "cannot find block({}) on main chain(head: {})",
+                        cur_block_number,
                         chain_header.id()
                     ))
                 })?;
-                let mut filtered_events = events
+                let block_number = block.header().number();
+                let mut txn_info_ids = self
+                    .storage
+                    .get_block_txn_info_ids(block_id)?
                     .into_iter()
-                    .filter(|evt| filter.matching(block_number, evt))
-                    .peekable();
-                if filtered_events.peek().is_none() {
-                    continue;
-                }
-
-                let txn_info = self.storage.get_transaction_info(*id)?.ok_or_else(|| {
-                    anyhow::anyhow!(format!(
-                        "cannot find txn info with txn_info_id {} on main chain(head: {})",
-                        id,
-                        chain_header.id()
-                    ))
-                })?;
-
-                let filtered_event_with_info = filtered_events.map(|evt| ContractEventInfo {
-                    block_hash: block_id,
-                    block_number: block.header().number(),
-                    transaction_hash: txn_info.transaction_hash(),
-                    transaction_index: *idx as u32,
-                    event: evt,
-                });
+                    .enumerate()
+                    .collect::<Vec<_>>();
                 if reverse {
-                    event_with_infos.extend(filtered_event_with_info.rev())
-                } else {
-                    event_with_infos.extend(filtered_event_with_info);
+                    txn_info_ids.reverse();
                 }
+                for (idx, id) in txn_info_ids.iter() {
+                    let events = self.storage.get_contract_events(*id)?.ok_or_else(|| {
+                        anyhow::anyhow!(format!(
+                            "cannot find events of txn with txn_info_id {} on main chain(header: {})",
+                            id,
+                            chain_header.id()
+                        ))
+                    })?;
+                    // the first txn of the block is the block metadata txn, which has no sender.
+                    let sender = idx
+                        .checked_sub(1)
+                        .and_then(|i| block.transactions().get(i))
+                        .map(|txn| txn.sender());
+                    let mut filtered_events = events
+                        .into_iter()
+                        .filter(|evt| filter.matching(block_number, sender, evt))
+                        .peekable();
+                    if filtered_events.peek().is_none() {
+                        continue;
+                    }
+
+                    let txn_info = self.storage.get_transaction_info(*id)?.ok_or_else(|| {
+                        anyhow::anyhow!(format!(
+                            "cannot find txn info with txn_info_id {} on main chain(head: {})",
+                            id,
+                            chain_header.id()
+                        ))
+                    })?;
+
+                    let filtered_event_with_info = filtered_events.map(|evt| ContractEventInfo {
+                        block_hash: block_id,
+                        block_number: block.header().number(),
+                        transaction_hash: txn_info.transaction_hash(),
+                        transaction_index: *idx as u32,
+                        event: evt,
+                    });
+                    if reverse {
+                        event_with_infos.extend(filtered_event_with_info.rev())
+                    } else {
+                        event_with_infos.extend(filtered_event_with_info);
+                    }
 
-                if let Some(limit) = filter.limit {
-                    if event_with_infos.len() >= limit {
-                        break 'outer;
+                    if let Some(limit) = filter.limit {
+                        if event_with_infos.len() >= limit {
+                            break 'outer;
+                        }
                     }
                 }
             }
@@ -9,15 +9,21 @@ use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
 use starcoin_chain_mock::MockChain;
 use starcoin_config::NodeConfig;
-use starcoin_config::{BuiltinNetworkID, ChainNetwork};
+use starcoin_config::{temp_path, BuiltinNetworkID, ChainNetwork, RocksdbConfig};
 use starcoin_executor::{build_transfer_from_association, DEFAULT_EXPIRATION_TIME};
+use starcoin_genesis::Genesis as StarcoinGenesis;
 use starcoin_types::account_address;
 use starcoin_types::block::{Block, BlockHeader};
-use starcoin_types::filter::Filter;
+use starcoin_types::filter::{EventCriteria, Filter};
+use starcoin_types::startup_info::StartupInfo;
 use starcoin_vm_types::account_config::genesis_address;
 use starcoin_vm_types::event::EventKey;
 use starcoin_vm_types::transaction::authenticator::AuthenticationKey;
 use std::sync::Arc;
+use storage::db_storage::DBStorage;
+use storage::repair::StorageRepairer;
+use storage::storage::{InnerStore, StorageInstance};
+use storage::{BlockInfoStore, BlockStore, Storage, BLOCK_TRANSACTION_INFOS_PREFIX_NAME};
 
 #[stest::test(timeout = 120)]
 fn test_chain_filter_events() {
@@ -30,6 +36,10 @@ fn test_chain_filter_events() {
             from_block: 1,
             to_block: 5,
             event_keys: vec![evt_key],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: None,
             reverse: false,
         };
@@ -46,6 +56,10 @@ fn test_chain_filter_events() {
             from_block: 1,
             to_block: 10,
             event_keys: vec![EventKey::new_from_address(&genesis_address(), 4)],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: Some(5),
             reverse: false,
         };
@@ -60,6 +74,10 @@ fn test_chain_filter_events() {
             from_block: 1,
             to_block: 10,
             event_keys: vec![EventKey::new_from_address(&genesis_address(), 4)],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: Some(5),
             reverse: true,
         };
@@ -76,6 +94,10 @@ fn test_chain_filter_events() {
             from_block: 0,
             to_block: 10,
             event_keys: vec![EventKey::new_from_address(&genesis_address(), 4)],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: Some(20),
             reverse: true,
         };
@@ -92,6 +114,10 @@ fn test_chain_filter_events() {
             from_block: 0,
             to_block: 20,
             event_keys: vec![EventKey::new_from_address(&genesis_address(), 4)],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: Some(20),
             reverse: true,
         };
@@ -101,6 +127,76 @@ fn test_chain_filter_events() {
         assert_eq!(evt.block_number, 10);
         assert_eq!(evt.transaction_index, 0);
     }
+
+    // test on composite criteria
+    {
+        let evt_key = EventKey::new_from_address(&genesis_address(), 4);
+        let event_filter = Filter {
+            from_block: 1,
+            to_block: 10,
+            any_of: vec![
+                EventCriteria {
+                    event_keys: vec![EventKey::new_from_address(&genesis_address(), 1000)],
+                    ..Default::default()
+                },
+                EventCriteria {
+                    event_keys: vec![evt_key],
+                    ..Default::default()
+                },
+            ],
+            reverse: false,
+            ..Default::default()
+        };
+        let evts = mock_chain.head().filter_events(event_filter).unwrap();
+        assert_eq!(evts.len(), 10);
+        assert!(evts.iter().all(|evt| evt.event.key() == &evt_key));
+
+        // the events of the block metadata txns have no sender.
+        let event_filter = Filter {
+            from_block: 1,
+            to_block: 10,
+            any_of: vec![EventCriteria {
+                event_keys: vec![evt_key],
+                senders: vec![genesis_address()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(mock_chain
+            .head()
+            .filter_events(event_filter)
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[stest::test(timeout = 120)]
+fn test_block_bloom() -> Result<()> {
+    let mut mock_chain = MockChain::new(ChainNetwork::new_test())?;
+    mock_chain.produce_and_apply_times(3)?;
+    let chain = mock_chain.head();
+    let evt_key = EventKey::new_from_address(&genesis_address(), 4);
+    let block_id = chain.current_header().id();
+    let bloom = chain
+        .get_storage()
+        .get_block_bloom(block_id)?
+        .expect("block bloom must exist");
+    assert!(bloom.contains_event_key(&evt_key));
+    assert!(bloom.contains_address(&genesis_address()));
+    let random_key = EventKey::new_from_address(&account_address::AccountAddress::random(), 0);
+    assert!(!bloom.contains_event_key(&random_key));
+    // the block metadata txn has no sender.
+    assert!(!bloom.contains_sender(&genesis_address()));
+
+    let event_filter = Filter {
+        from_block: 0,
+        to_block: 3,
+        event_keys: vec![random_key],
+        ..Default::default()
+    };
+    assert!(!event_filter.bloom_matching(&bloom));
+    assert!(chain.filter_events(event_filter)?.is_empty());
+    Ok(())
 }
 
 #[stest::test]
@@ -449,3 +545,45 @@ fn test_get_blocks_by_number() -> Result<()> {
     assert_eq!(blocks.len(), 11);
     Ok(())
 }
+
+#[stest::test(timeout = 120)]
+fn test_apply_block_after_repair() -> Result<()> {
+    let net = ChainNetwork::new_test();
+    let tmpdir = temp_path();
+    let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default())?,
+    ))?);
+    let (chain_info, _) =
+        StarcoinGenesis::init_and_check_storage(&net, storage.clone(), tmpdir.path())?;
+    let mut mock_chain = MockChain::new_with_storage(
+        net.clone(),
+        storage.clone(),
+        chain_info.head().id(),
+        AccountInfo::random(),
+    )?;
+    let mut blocks = vec![];
+    for _i in 0..3 {
+        let block = mock_chain.produce()?;
+        mock_chain.apply(block.clone())?;
+        blocks.push(block);
+    }
+    storage.save_startup_info(StartupInfo::new(blocks[2].id()))?;
+
+    // The primary data of block 2 is damaged, the chain is truncated to block 1.
+    storage
+        .db()
+        .unwrap()
+        .remove(BLOCK_TRANSACTION_INFOS_PREFIX_NAME, blocks[1].id().to_vec())?;
+    let report = StorageRepairer::new(storage.clone()).repair(None, &|_| {})?;
+    assert_eq!(report.head_hash, blocks[0].id());
+    assert!(storage.get_block_info(blocks[1].id())?.is_none());
+
+    let mut chain = BlockChain::new(net.time_service(), blocks[0].id(), storage.clone())?;
+    for block in &blocks[1..] {
+        chain.apply(block.clone())?;
+    }
+    assert_eq!(chain.current_header().id(), blocks[2].id());
+    assert!(storage.get_block_info(blocks[2].id())?.is_some());
+    assert_eq!(storage.get_block_txn_info_ids(blocks[1].id())?.len(), 1);
+    Ok(())
+}
@@ -155,7 +155,7 @@ async fn main() -> Result<()> {
 
         let signed_txn_hex = hex::encode(signed_txn.encode()?);
         let txn_hash: HashValue = txpool_client
-            .submit_hex_transaction(signed_txn_hex)
+            .submit_hex_transaction(signed_txn_hex, None)
             .await
             .map_err(map_rpc_error)?;
         let txn_info: TransactionInfoView = loop {
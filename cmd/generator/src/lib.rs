@@ -29,7 +29,11 @@ pub fn init_or_load_data_dir(
     }
     let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
         CacheStorage::new_with_capacity(config.storage.cache_size()),
-        DBStorage::new(config.storage.dir(), config.storage.rocksdb_config())?,
+        DBStorage::new_with_encryption(
+            config.storage.dir(),
+            config.storage.rocksdb_config(),
+            config.storage.db_encryption_key()?,
+        )?,
     ))?);
     let (chain_info, _genesis) =
         Genesis::init_and_check_storage(config.net(), storage.clone(), config.data_dir())?;
@@ -18,7 +18,7 @@ impl BlockClient {
         }
     }
     pub async fn get_block_whole_by_height(&self, height: u64) -> Result<BlockData, RpcError> {
-        let block: Option<BlockView> = self.node_client.get_block_by_number(height).await?;
+        let block: Option<BlockView> = self.node_client.get_block_by_number(height, None).await?;
         let block = block
             .ok_or_else(|| RpcError::Client(format!("cannot find block of height {}", height)))?;
         let mut txn_infos: Vec<TransactionInfoView> = self
@@ -31,7 +31,7 @@ impl BlockClient {
             let txn_info = txn_infos.remove(0);
             let txn: Option<TransactionView> = self
                 .node_client
-                .get_transaction(txn_info.transaction_hash)
+                .get_transaction(txn_info.transaction_hash, None)
                 .await?;
             let txn = txn.ok_or_else(|| {
                 RpcError::Client(format!(
@@ -26,6 +26,7 @@ fn main() {
                         remote,
                         protocol: _,
                         info,
+                        version: _,
                         notif_protocols,
                         rpc_protocols,
                     } => Some(PeerInfo::new(
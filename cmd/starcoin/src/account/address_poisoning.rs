@@ -0,0 +1,195 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protection against the address poisoning scam: the attacker sends dust from an address which
+//! shares the leading and trailing characters with a counterparty of the victim, hoping the victim
+//! copies the fake address from the history next time. Since the addresses are usually displayed
+//! and compared by the head and tail, an address that only differs in the middle is flagged.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starcoin_vm_types::account_address::AccountAddress;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The count of hex chars at the head and at the tail compared to detect look-alike addresses.
+pub const LOOKALIKE_MATCH_CHARS: usize = 4;
+/// The max count of the recent counterparties kept in the cli data dir.
+pub const MAX_RECENT_COUNTERPARTIES: usize = 100;
+
+/// Returns true if `a` and `b` are different addresses with the same head and tail hex chars.
+pub fn is_lookalike(a: &AccountAddress, b: &AccountAddress) -> bool {
+    if a == b {
+        return false;
+    }
+    let a = hex::encode(a.to_vec());
+    let b = hex::encode(b.to_vec());
+    let n = LOOKALIKE_MATCH_CHARS;
+    a[..n] == b[..n] && a[a.len() - n..] == b[b.len() - n..]
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KnownAddressSource {
+    /// An account of the local wallet.
+    WalletAccount,
+    /// A recent counterparty of the transfers sent by the cli.
+    RecentCounterparty,
+}
+
+impl fmt::Display for KnownAddressSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KnownAddressSource::WalletAccount => write!(f, "wallet account"),
+            KnownAddressSource::RecentCounterparty => write!(f, "recent counterparty"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LookalikeWarning {
+    pub address: AccountAddress,
+    pub similar_to: AccountAddress,
+    pub source: KnownAddressSource,
+}
+
+impl fmt::Display for LookalikeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "address {} looks like the {} {}, they only differ in the middle characters, it may be an address poisoning scam",
+            self.address, self.source, self.similar_to
+        )
+    }
+}
+
+/// The recent counterparties of the transfers, the most recent first.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RecentCounterparties {
+    addresses: Vec<AccountAddress>,
+}
+
+impl RecentCounterparties {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_slice(std::fs::read(path)?.as_slice())?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Move `address` to the head, and drop the oldest ones beyond `MAX_RECENT_COUNTERPARTIES`.
+    pub fn record(&mut self, address: AccountAddress) {
+        self.addresses.retain(|addr| addr != &address);
+        self.addresses.insert(0, address);
+        self.addresses.truncate(MAX_RECENT_COUNTERPARTIES);
+    }
+
+    pub fn addresses(&self) -> &[AccountAddress] {
+        self.addresses.as_slice()
+    }
+}
+
+/// The addresses known by the user, to check the look-alike addresses against.
+#[derive(Clone, Debug, Default)]
+pub struct KnownAddresses {
+    addresses: Vec<(AccountAddress, KnownAddressSource)>,
+}
+
+impl KnownAddresses {
+    pub fn new(wallet_accounts: &[AccountAddress], recent: &RecentCounterparties) -> Self {
+        let addresses = wallet_accounts
+            .iter()
+            .map(|addr| (*addr, KnownAddressSource::WalletAccount))
+            .chain(
+                recent
+                    .addresses()
+                    .iter()
+                    .map(|addr| (*addr, KnownAddressSource::RecentCounterparty)),
+            )
+            .collect();
+        Self { addresses }
+    }
+
+    /// Check `address` against the known addresses, return None if `address` itself is known.
+    pub fn check(&self, address: &AccountAddress) -> Option<LookalikeWarning> {
+        if self.addresses.iter().any(|(addr, _)| addr == address) {
+            return None;
+        }
+        self.addresses
+            .iter()
+            .find(|(addr, _)| is_lookalike(addr, address))
+            .map(|(addr, source)| LookalikeWarning {
+                address: *address,
+                similar_to: *addr,
+                source: *source,
+            })
+    }
+}
+
+/// The file to keep the recent counterparties in the cli data dir.
+pub fn recent_counterparties_file(cli_data_dir: &Path) -> PathBuf {
+    cli_data_dir.join("recent_counterparties.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(hex: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(hex).unwrap()
+    }
+
+    #[test]
+    fn test_is_lookalike() {
+        let known = address("0x1234aaaaaaaaaaaaaaaaaaaaaaaa5678");
+        assert!(is_lookalike(
+            &known,
+            &address("0x1234bbbbbbbbbbbbbbbbbbbbbbbb5678")
+        ));
+        assert!(!is_lookalike(&known, &known));
+        assert!(!is_lookalike(
+            &known,
+            &address("0x1235aaaaaaaaaaaaaaaaaaaaaaaa5678")
+        ));
+        assert!(!is_lookalike(
+            &known,
+            &address("0x1234aaaaaaaaaaaaaaaaaaaaaaaa5679")
+        ));
+    }
+
+    #[test]
+    fn test_known_addresses() {
+        let wallet = address("0x1234aaaaaaaaaaaaaaaaaaaaaaaa5678");
+        let counterparty = address("0xabcdaaaaaaaaaaaaaaaaaaaaaaaaef01");
+        let mut recent = RecentCounterparties::default();
+        recent.record(counterparty);
+        recent.record(counterparty);
+        assert_eq!(recent.addresses(), &[counterparty]);
+
+        let known = KnownAddresses::new(&[wallet], &recent);
+        assert_eq!(known.check(&wallet), None);
+        assert_eq!(known.check(&counterparty), None);
+        assert_eq!(
+            known.check(&address("0x2234aaaaaaaaaaaaaaaaaaaaaaaa5678")),
+            None
+        );
+        let fake = address("0xabcd000000000000000000000000ef01");
+        let warning = known.check(&fake).unwrap();
+        assert_eq!(warning.similar_to, counterparty);
+        assert_eq!(warning.source, KnownAddressSource::RecentCounterparty);
+    }
+
+    #[test]
+    fn test_recent_counterparties_limit() {
+        let mut recent = RecentCounterparties::default();
+        for _ in 0..MAX_RECENT_COUNTERPARTIES + 10 {
+            recent.record(AccountAddress::random());
+        }
+        assert_eq!(recent.addresses().len(), MAX_RECENT_COUNTERPARTIES);
+    }
+}
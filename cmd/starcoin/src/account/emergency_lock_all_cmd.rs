@@ -0,0 +1,301 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::account_config::core_code_address;
+use starcoin_vm_types::transaction::{SignedUserTransaction, TransactionPayload};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// The incident response command for operators: lock all the accounts of the wallet,
+/// which revokes every unlocked session, rotate the rpc api keys of the node, then optionally
+/// submit the pre-signed key rotation txns.
+/// It goes on when a step fails, and reports the failures at the end.
+/// The rotated api keys are only kept in memory by the node, update the config file with them.
+///  Some examples:
+///  ``` shell
+///  account emergency lock-all
+///  account emergency lock-all --rotation-txn-dir ~/.starcoin/rotation_txns
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "lock-all")]
+pub struct EmergencyLockAllOpt {
+    #[structopt(long = "rotation-txn-dir", parse(from_os_str))]
+    /// the dir of the pre-signed key rotation txns, such as the files generated by
+    /// `account execute-function --function 0x1::Account::rotate_authentication_key --offline`.
+    /// other txns in the dir are rejected.
+    rotation_txn_dir: Option<PathBuf>,
+
+    #[structopt(long = "skip-api-keys")]
+    /// do not rotate the rpc api keys of the node.
+    skip_api_keys: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockFailure {
+    pub address: AccountAddress,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedRotation {
+    pub file: PathBuf,
+    pub sender: AccountAddress,
+    pub txn_hash: HashValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitFailure {
+    pub file: PathBuf,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyLockAllView {
+    pub locked: Vec<AccountAddress>,
+    pub lock_failed: Vec<LockFailure>,
+    /// The new secrets of the rpc api keys by the key name.
+    pub rotated_api_keys: HashMap<String, String>,
+    pub rotate_api_keys_error: Option<String>,
+    pub submitted: Vec<SubmittedRotation>,
+    pub submit_failed: Vec<SubmitFailure>,
+}
+
+/// Load the signed txn in `file`, and check it is a key rotation txn.
+pub(crate) fn load_rotation_txn(file: &Path) -> Result<SignedUserTransaction> {
+    let txn: SignedUserTransaction = bcs_ext::from_bytes(std::fs::read(file)?.as_slice())?;
+    match txn.payload() {
+        TransactionPayload::ScriptFunction(function)
+            if function.module().address() == &core_code_address()
+                && function.module().name().as_str() == "Account"
+                && function.function().as_str() == "rotate_authentication_key" =>
+        {
+            Ok(txn)
+        }
+        _ => bail!("txn {:#x} is not a key rotation txn", txn.id()),
+    }
+}
+
+/// Lock every account by `lock`, the failed accounts are reported and do not stop the others.
+pub(crate) fn lock_accounts<F>(
+    addresses: Vec<AccountAddress>,
+    lock: F,
+) -> (Vec<AccountAddress>, Vec<LockFailure>)
+where
+    F: Fn(AccountAddress) -> Result<()>,
+{
+    let mut locked = vec![];
+    let mut lock_failed = vec![];
+    for address in addresses {
+        match lock(address) {
+            Ok(_) => locked.push(address),
+            Err(e) => lock_failed.push(LockFailure {
+                address,
+                error: e.to_string(),
+            }),
+        }
+    }
+    (locked, lock_failed)
+}
+
+/// Submit the key rotation txns in the files of `dir` in the file name order by `submit`,
+/// the invalid or failed txns are reported and do not stop the others.
+pub(crate) fn submit_rotation_txns<F>(
+    dir: &Path,
+    submit: F,
+) -> Result<(Vec<SubmittedRotation>, Vec<SubmitFailure>)>
+where
+    F: Fn(SignedUserTransaction) -> Result<HashValue>,
+{
+    let mut files = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    files.retain(|file| file.is_file());
+    files.sort();
+    let mut submitted = vec![];
+    let mut submit_failed = vec![];
+    for file in files {
+        match load_rotation_txn(file.as_path()).and_then(|txn| {
+            let (sender, txn_hash) = (txn.sender(), txn.id());
+            submit(txn)?;
+            Ok((sender, txn_hash))
+        }) {
+            Ok((sender, txn_hash)) => submitted.push(SubmittedRotation {
+                file,
+                sender,
+                txn_hash,
+            }),
+            Err(e) => submit_failed.push(SubmitFailure {
+                file,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok((submitted, submit_failed))
+}
+
+pub struct EmergencyLockAllCommand;
+
+impl CommandAction for EmergencyLockAllCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = EmergencyLockAllOpt;
+    type ReturnItem = EmergencyLockAllView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+
+        let addresses = client
+            .account_list()?
+            .into_iter()
+            .map(|account| account.address)
+            .collect();
+        let (locked, lock_failed) =
+            lock_accounts(addresses, |address| client.account_lock(address));
+
+        let (rotated_api_keys, rotate_api_keys_error) = if opt.skip_api_keys {
+            (HashMap::new(), None)
+        } else {
+            match client.node_admin_rotate_api_keys() {
+                Ok(keys) => (keys, None),
+                Err(e) => (HashMap::new(), Some(e.to_string())),
+            }
+        };
+
+        let (submitted, submit_failed) = match opt.rotation_txn_dir.as_ref() {
+            Some(dir) => submit_rotation_txns(dir.as_path(), |txn| client.submit_transaction(txn))?,
+            None => (vec![], vec![]),
+        };
+        Ok(EmergencyLockAllView {
+            locked,
+            lock_failed,
+            rotated_api_keys,
+            rotate_api_keys_error,
+            submitted,
+            submit_failed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::format_err;
+    use starcoin_crypto::ed25519::genesis_key_pair;
+    use starcoin_vm_types::genesis_config::ChainId;
+    use starcoin_vm_types::identifier::Identifier;
+    use starcoin_vm_types::language_storage::ModuleId;
+    use starcoin_vm_types::transaction::{RawUserTransaction, Script, ScriptFunction};
+
+    fn signed_txn(sender: AccountAddress, payload: TransactionPayload) -> SignedUserTransaction {
+        let (private_key, public_key) = genesis_key_pair();
+        RawUserTransaction::new_with_default_gas_token(
+            sender,
+            0,
+            payload,
+            10000000,
+            1,
+            3600,
+            ChainId::test(),
+        )
+        .sign(&private_key, public_key)
+        .unwrap()
+        .into_inner()
+    }
+
+    fn rotation_txn(sender: AccountAddress) -> SignedUserTransaction {
+        signed_txn(
+            sender,
+            TransactionPayload::ScriptFunction(ScriptFunction::new(
+                ModuleId::new(core_code_address(), Identifier::new("Account").unwrap()),
+                Identifier::new("rotate_authentication_key").unwrap(),
+                vec![],
+                vec![bcs_ext::to_bytes(&vec![0u8; 32]).unwrap()],
+            )),
+        )
+    }
+
+    #[test]
+    fn test_lock_accounts() {
+        let failed_address = AccountAddress::random();
+        let addresses = vec![
+            AccountAddress::random(),
+            failed_address,
+            AccountAddress::random(),
+        ];
+        let (locked, lock_failed) = lock_accounts(addresses.clone(), |address| {
+            if address == failed_address {
+                Err(format_err!("lock failed"))
+            } else {
+                Ok(())
+            }
+        });
+        // the failed account does not stop locking the others.
+        assert_eq!(locked, vec![addresses[0], addresses[2]]);
+        assert_eq!(lock_failed.len(), 1);
+        assert_eq!(lock_failed[0].address, failed_address);
+        assert!(lock_failed[0].error.contains("lock failed"));
+    }
+
+    #[test]
+    fn test_submit_rotation_txns() {
+        let temp_path = starcoin_config::temp_path();
+        let dir = temp_path.path();
+        let (sender1, sender2) = (AccountAddress::random(), AccountAddress::random());
+        let txn1 = rotation_txn(sender1);
+        let txn2 = rotation_txn(sender2);
+        std::fs::write(dir.join("1.txn"), bcs_ext::to_bytes(&txn1).unwrap()).unwrap();
+        std::fs::write(dir.join("2.txn"), bcs_ext::to_bytes(&txn2).unwrap()).unwrap();
+        // a txn which is not a key rotation txn.
+        let script_txn = signed_txn(
+            sender1,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        );
+        std::fs::write(dir.join("3.txn"), bcs_ext::to_bytes(&script_txn).unwrap()).unwrap();
+        // a file which is not a txn.
+        std::fs::write(dir.join("4.txn"), b"invalid").unwrap();
+
+        let txn2_hash = txn2.id();
+        let (submitted, submit_failed) = submit_rotation_txns(dir, |txn| {
+            if txn.id() == txn2_hash {
+                Err(format_err!("submit failed"))
+            } else {
+                Ok(txn.id())
+            }
+        })
+        .unwrap();
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].sender, sender1);
+        assert_eq!(submitted[0].txn_hash, txn1.id());
+        let failed_files: Vec<_> = submit_failed
+            .iter()
+            .map(|failure| {
+                failure
+                    .file
+                    .file_name()
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(failed_files, vec!["2.txn", "3.txn", "4.txn"]);
+        assert!(submit_failed[0].error.contains("submit failed"));
+        assert!(submit_failed[1].error.contains("is not a key rotation txn"));
+
+        assert!(
+            submit_rotation_txns(dir.join("not_exists").as_path(), |txn| Ok(txn.id())).is_err()
+        );
+    }
+}
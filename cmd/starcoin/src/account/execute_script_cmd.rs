@@ -8,7 +8,7 @@ use anyhow::{bail, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_dev::playground;
 use starcoin_move_compiler::load_bytecode_file;
-use starcoin_rpc_api::types::{TransactionOutputView, TransactionVMStatus};
+use starcoin_rpc_api::types::{DryRunOutputView, TransactionVMStatus};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::transaction::{
@@ -147,16 +147,18 @@ impl CommandAction for ExecuteScriptCommand {
 
         let signed_txn = client.account_sign_txn(raw_txn)?;
         let txn_hash = signed_txn.id();
-        let output: TransactionOutputView = {
+        let output: DryRunOutputView = {
             let state_view = RemoteStateReader::new(client)?;
-            playground::dry_run(
+            playground::dry_run_annotated(
                 &state_view,
                 DryRunTransaction {
                     public_key: signed_txn.authenticator().public_key(),
                     raw_txn: signed_txn.raw_txn().clone(),
                 },
             )
-            .map(|(_, b)| b.into())?
+            .map(|(_, output, annotation)| {
+                DryRunOutputView::new(output, annotation.write_set, annotation.events)
+            })?
         };
         match output.status {
             TransactionVMStatus::Discard { status_code } => {
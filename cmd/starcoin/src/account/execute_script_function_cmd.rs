@@ -1,26 +1,50 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account::gas_bump::{watch_txn_with_gas_bump, GasBumpOpt};
+use crate::account::gas_estimate::{estimate_gas, with_max_gas_amount, GasEstimateOpt};
 use crate::cli_state::CliState;
+use crate::dev::script_function_abi::{encode_script_function_txn_args, load_script_function_abi};
 use crate::view::{ExecuteResultView, ExecutionOutputView};
 use crate::StarcoinOpt;
 use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
+use short_hex_str::AsShortHexStr;
+use starcoin_account::account_storage::AccountStorage;
+use starcoin_account::AccountManager;
+use starcoin_config::RocksdbConfig;
+use starcoin_crypto::hash::PlainCryptoHash;
 use starcoin_dev::playground;
-use starcoin_rpc_api::types::{FunctionIdView, TransactionOutputView, TransactionVMStatus};
+use starcoin_executor::DEFAULT_MAX_GAS_AMOUNT;
+use starcoin_rpc_api::types::{DryRunOutputView, FunctionIdView, TransactionVMStatus};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::transaction::{
-    parse_transaction_argument, DryRunTransaction, RawUserTransaction, TransactionArgument,
+    parse_transaction_argument, DryRunTransaction, RawUserTransaction, SignedUserTransaction,
+    TransactionArgument,
 };
 use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
 use starcoin_vm_types::transaction::ScriptFunction;
 use starcoin_vm_types::transaction_argument::convert_txn_args;
 use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::env::current_dir;
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use structopt::StructOpt;
 
+/// The max gas amount of the offline txn, which can not be estimated without the chain state.
+const DEFAULT_OFFLINE_MAX_GAS_AMOUNT: u64 = 10000000;
+/// How long the sender is unlocked in the local account vault to sign the offline txn.
+const OFFLINE_UNLOCK_DURATION: Duration = Duration::from_secs(30);
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "execute-function")]
+/// Execute a script function.
+/// With `--offline`, the txn is built by the local ABI file, signed by the local account vault,
+/// and saved to file without connecting to (or starting) a node,
+/// then submit it by `account submit-txn` on an online node.
 pub struct ExecuteScriptFunctionOpt {
     #[structopt(short = "s")]
     /// if `sender` is absent, use default account.
@@ -47,13 +71,10 @@ pub struct ExecuteScriptFunctionOpt {
     )]
     expiration_time: u64,
 
-    #[structopt(
-        short = "g",
-        name = "max-gas-amount",
-        default_value = "10000000",
-        help = "max gas used to deploy the module"
-    )]
-    max_gas_amount: u64,
+    #[structopt(short = "g", name = "max-gas-amount")]
+    /// max gas used to execute the script function, estimated by dry run if absent.
+    /// Default to 10000000 in offline mode.
+    max_gas_amount: Option<u64>,
     #[structopt(
         short = "p",
         long = "gas-price",
@@ -75,9 +96,139 @@ pub struct ExecuteScriptFunctionOpt {
     /// dry-run script, only get transaction output, no state change to chain
     dry_run: bool,
 
+    #[structopt(long = "abi-file", name = "abi-file", parse(from_os_str))]
+    /// the local ABI file of the script function, the args are validated and encoded by the ABI.
+    abi_file: Option<PathBuf>,
+
+    #[structopt(
+        long = "offline",
+        requires_all = &["abi-file", "sequence-number", "account-dir", "chain-id"],
+        conflicts_with_all = &["dry-run", "blocking-mode"]
+    )]
+    /// build and sign the txn without any rpc call, and save the signed txn to file.
+    offline: bool,
+
+    #[structopt(long = "account-dir", name = "account-dir", parse(from_os_str))]
+    /// the local account vault dir used to sign the txn, required in offline mode.
+    account_dir: Option<PathBuf>,
+
+    #[structopt(long = "password", name = "password", default_value = "")]
+    /// the password of the sender in the local account vault, only used in offline mode.
+    password: String,
+
+    #[structopt(long = "chain-id", name = "chain-id")]
+    /// the chain id of the txn, required in offline mode, read from the node by default.
+    chain_id: Option<ChainId>,
+
+    #[structopt(long = "sequence-number", name = "sequence-number")]
+    /// the sequence number of the sender, required in offline mode, read from chain by default.
+    sequence_number: Option<u64>,
+
+    #[structopt(long = "output-dir", name = "output-dir", parse(from_os_str))]
+    /// dir used to save the offline signed txn file. Default to current dir.
+    output_dir: Option<PathBuf>,
+
     #[structopt(long = "function", name = "script-function")]
     /// script function to execute, example: 0x1::TransferScripts::peer_to_peer
     script_function: FunctionIdView,
+
+    #[structopt(flatten)]
+    gas_bump: GasBumpOpt,
+
+    #[structopt(flatten)]
+    gas_estimate: GasEstimateOpt,
+}
+
+impl ExecuteScriptFunctionOpt {
+    fn build_raw_txn(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        now_seconds: u64,
+        max_gas_amount: u64,
+        chain_id: ChainId,
+    ) -> Result<RawUserTransaction> {
+        let type_tags = self.type_tags.clone().unwrap_or_default();
+        let args = self.args.clone().unwrap_or_default();
+        let script_function = self.script_function.clone().0;
+        let encoded_args = match &self.abi_file {
+            Some(abi_file) => {
+                let abi = load_script_function_abi(abi_file.as_path(), &script_function)?;
+                encode_script_function_txn_args(&abi, type_tags.as_slice(), args.as_slice())?
+            }
+            None => convert_txn_args(&args),
+        };
+        Ok(RawUserTransaction::new_script_function(
+            sender,
+            sequence_number,
+            ScriptFunction::new(
+                script_function.module,
+                script_function.function,
+                type_tags,
+                encoded_args,
+            ),
+            max_gas_amount,
+            self.gas_price,
+            self.expiration_time + now_seconds,
+            chain_id,
+        ))
+    }
+
+    fn save_signed_txn(&self, signed_txn: &SignedUserTransaction) -> Result<PathBuf> {
+        let mut output_file = self.output_dir.clone().unwrap_or(current_dir()?);
+        // use hash's short str as output file name
+        let file_name = signed_txn.raw_txn().crypto_hash().short_str();
+        output_file.push(file_name.as_str());
+        output_file.set_extension("signed-txn");
+        let mut file = File::create(output_file.clone())?;
+        bcs_ext::serialize_into(&mut file, signed_txn)?;
+        Ok(output_file)
+    }
+}
+
+/// Run `account execute-function --offline`: build the txn by the local ABI file,
+/// sign it by the local account vault, and save it to file.
+/// No rpc call is made, so it is executed without connecting to (or starting) a node.
+fn run_offline(opt: &ExecuteScriptFunctionOpt) -> Result<ExecuteResultView> {
+    let account_dir = opt
+        .account_dir
+        .as_ref()
+        .ok_or_else(|| format_err!("account dir is required in offline mode"))?;
+    let chain_id = opt
+        .chain_id
+        .ok_or_else(|| format_err!("chain id is required in offline mode"))?;
+    let sequence_number = opt
+        .sequence_number
+        .ok_or_else(|| format_err!("sequence number is required in offline mode"))?;
+
+    let storage = AccountStorage::create_from_path(account_dir, RocksdbConfig::default())?;
+    let manager = AccountManager::new(storage)?;
+    let sender = match opt.sender {
+        Some(sender) => sender,
+        None => {
+            manager
+                .default_account_info()?
+                .ok_or_else(|| format_err!("no default account in {}", account_dir.display()))?
+                .address
+        }
+    };
+    let now_seconds = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_txn = opt.build_raw_txn(
+        sender,
+        sequence_number,
+        now_seconds,
+        opt.max_gas_amount.unwrap_or(DEFAULT_OFFLINE_MAX_GAS_AMOUNT),
+        chain_id,
+    )?;
+    manager.unlock_account(sender, opt.password.as_str(), OFFLINE_UNLOCK_DURATION)?;
+    let signed_txn = manager.sign_txn(sender, raw_txn);
+    manager.lock_account(sender)?;
+    let signed_txn = signed_txn?;
+    let output_file = opt.save_signed_txn(&signed_txn)?;
+    Ok(ExecuteResultView::Offline {
+        txn_hash: signed_txn.id(),
+        output_file,
+    })
 }
 
 pub struct ExecuteScriptFunctionCmd;
@@ -94,47 +245,64 @@ impl CommandAction for ExecuteScriptFunctionCmd {
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
         let client = ctx.state().client();
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+
         let node_info = client.node_info()?;
+        let sequence_number = match opt.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => {
+                let chain_state_reader = RemoteStateReader::new(client)?;
+                let account_state_reader = AccountStateReader::new(&chain_state_reader);
+                let account_resource =
+                    account_state_reader.get_account_resource(&sender.address)?;
+                account_resource
+                    .ok_or_else(|| {
+                        format_err!("account of address {} not exists on chain", sender.address)
+                    })?
+                    .sequence_number()
+            }
+        };
+        let chain_id = ctx.state().net().chain_id();
+        if let Some(opt_chain_id) = opt.chain_id {
+            if opt_chain_id != chain_id {
+                bail!(
+                    "chain id {} mismatch with the connected node's chain id {}",
+                    opt_chain_id,
+                    chain_id
+                );
+            }
+        }
 
-        let sender = ctx.state().get_account_or_default(opt.sender)?;
-        let chain_state_reader = RemoteStateReader::new(client)?;
-        let account_state_reader = AccountStateReader::new(&chain_state_reader);
-        let account_resource = account_state_reader.get_account_resource(&sender.address)?;
-        let account_resource = account_resource.ok_or_else(|| {
-            format_err!("account of address {} not exists on chain", sender.address)
-        })?;
-        let expiration_time = opt.expiration_time + node_info.now_seconds;
-
-        let type_tags = opt.type_tags.clone().unwrap_or_default();
-        let args = opt.args.clone().unwrap_or_default();
-        let script_function = opt.script_function.clone().0;
-        let script_txn = RawUserTransaction::new_script_function(
+        let script_txn = opt.build_raw_txn(
             sender.address,
-            account_resource.sequence_number(),
-            ScriptFunction::new(
-                script_function.module,
-                script_function.function,
-                type_tags,
-                convert_txn_args(&args),
-            ),
-            opt.max_gas_amount,
-            opt.gas_price,
-            expiration_time,
-            ctx.state().net().chain_id(),
-        );
+            sequence_number,
+            node_info.now_seconds,
+            opt.max_gas_amount.unwrap_or(DEFAULT_MAX_GAS_AMOUNT),
+            chain_id,
+        )?;
+        let script_txn = match opt.max_gas_amount {
+            None => {
+                let estimate =
+                    estimate_gas(client, &sender, &script_txn, opt.gas_estimate.gas_margin)?;
+                with_max_gas_amount(&script_txn, estimate.max_gas_amount)
+            }
+            Some(_) => script_txn,
+        };
 
         let signed_txn = client.account_sign_txn(script_txn)?;
         let txn_hash = signed_txn.id();
-        let output: TransactionOutputView = {
+        let output: DryRunOutputView = {
             let state_view = RemoteStateReader::new(client)?;
-            playground::dry_run(
+            playground::dry_run_annotated(
                 &state_view,
                 DryRunTransaction {
                     public_key: signed_txn.authenticator().public_key(),
                     raw_txn: signed_txn.raw_txn().clone(),
                 },
             )
-            .map(|(_, b)| b.into())?
+            .map(|(_, output, annotation)| {
+                DryRunOutputView::new(output, annotation.write_set, annotation.events)
+            })?
         };
         match output.status {
             TransactionVMStatus::Discard { status_code } => {
@@ -146,20 +314,26 @@ impl CommandAction for ExecuteScriptFunctionCmd {
             }
         }
         if !opt.dry_run {
-            client.submit_transaction(signed_txn)?;
+            client.submit_transaction(signed_txn.clone())?;
 
             println!("txn {:#x} submitted.", txn_hash);
 
-            let mut output_view = ExecutionOutputView::new(txn_hash);
-
-            if opt.blocking {
-                let block = ctx.state().watch_txn(txn_hash)?.0;
-                output_view.block_number = Some(block.header.number.0);
-                output_view.block_id = Some(block.header.block_hash);
-            }
+            let output_view = if opt.blocking {
+                watch_txn_with_gas_bump(ctx.state(), &signed_txn, &opt.gas_bump)?
+            } else {
+                ExecutionOutputView::new(txn_hash)
+            };
             Ok(ExecuteResultView::Run(output_view))
         } else {
             Ok(ExecuteResultView::DryRun(output.into()))
         }
     }
+
+    fn run_stateless(&self, opt: &Self::Opt) -> Option<Result<Self::ReturnItem>> {
+        if opt.offline {
+            Some(run_offline(opt))
+        } else {
+            None
+        }
+    }
 }
@@ -5,7 +5,8 @@ use crate::cli_state::CliState;
 use crate::StarcoinOpt;
 use anyhow::{bail, Result};
 use scmd::{CommandAction, ExecContext};
-use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_crypto::{ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use starcoin_decrypt::scrypt_encrypt;
 use starcoin_types::transaction::authenticator::AccountPrivateKey;
 use starcoin_vm_types::account_address::AccountAddress;
 use std::convert::TryFrom;
@@ -21,8 +22,17 @@ pub struct ExportOpt {
     password: String,
     #[structopt(short = "o", parse(from_os_str))]
     output_file: Option<PathBuf>,
+    #[structopt(long = "encrypted")]
+    /// export the private key encrypted by a scrypt derived key, instead of plaintext hex.
+    encrypted: bool,
+    #[structopt(long = "encrypted-password")]
+    /// password to encrypt the exported private key, default to the account password.
+    encrypted_password: Option<String>,
 }
 
+/// The prefix of encrypted private key, followed by the hex of the encrypted data.
+pub const ENCRYPTED_PRIVATE_KEY_PREFIX: &str = "encrypted:";
+
 pub struct ExportCommand;
 
 impl CommandAction for ExportCommand {
@@ -36,7 +46,13 @@ impl CommandAction for ExportCommand {
         let opt: &ExportOpt = ctx.opt();
         let data = client.account_export(opt.account_address, opt.password.clone())?;
         let private_key = AccountPrivateKey::try_from(data.as_slice())?;
-        let encoded = private_key.to_encoded_string()?;
+        let encoded = if opt.encrypted {
+            let password = opt.encrypted_password.as_ref().unwrap_or(&opt.password);
+            let encrypted = scrypt_encrypt(password.as_bytes(), &private_key.to_bytes());
+            format!("{}{}", ENCRYPTED_PRIVATE_KEY_PREFIX, hex::encode(encrypted))
+        } else {
+            private_key.to_encoded_string()?
+        };
         if let Some(output_file) = &opt.output_file {
             if output_file.exists() {
                 bail!("the output_file {} is already exists, please change a name");
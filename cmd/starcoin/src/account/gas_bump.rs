@@ -0,0 +1,157 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Speed up a stuck txn of a blocking command: if the txn is not mined in time, rebuild it with a
+//! bumped gas price at the same sequence number, the txpool replaces the pending txn by the new one.
+
+use crate::cli_state::CliState;
+use crate::view::ExecutionOutputView;
+use anyhow::{bail, Result};
+use starcoin_vm_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+/// The default cap of the bumped gas price is the multiple of the original gas price.
+const DEFAULT_MAX_GAS_PRICE_MULTIPLE: u64 = 10;
+
+#[derive(Debug, Clone, StructOpt)]
+pub struct GasBumpOpt {
+    #[structopt(long = "gas-bump")]
+    /// in blocking mode, rebuild the txn with a bumped gas price at the same sequence number
+    /// if it is not mined in `--bump-after` seconds.
+    pub gas_bump: bool,
+
+    #[structopt(long = "bump-after", default_value = "60")]
+    /// seconds to wait before every bump.
+    pub bump_after: u64,
+
+    #[structopt(long = "bump-percent", default_value = "20")]
+    /// the percent to increase the gas price by every bump.
+    pub bump_percent: u64,
+
+    #[structopt(long = "max-gas-price")]
+    /// the cap of the bumped gas price, default is 10 times of the original gas price.
+    pub max_gas_price: Option<u64>,
+}
+
+/// Returns the bumped gas price, or None if it exceeds `cap`.
+pub fn bump_gas_price(gas_price: u64, bump_percent: u64, cap: u64) -> Option<u64> {
+    let bumped = gas_price
+        .saturating_mul(100 + bump_percent)
+        .checked_div(100)
+        .unwrap_or(gas_price)
+        .max(gas_price.saturating_add(1));
+    if bumped > cap {
+        None
+    } else {
+        Some(bumped)
+    }
+}
+
+fn with_gas_price(raw_txn: &RawUserTransaction, gas_price: u64) -> RawUserTransaction {
+    RawUserTransaction::new(
+        raw_txn.sender(),
+        raw_txn.sequence_number(),
+        raw_txn.clone().into_payload(),
+        raw_txn.max_gas_amount(),
+        gas_price,
+        raw_txn.expiration_timestamp_secs(),
+        raw_txn.chain_id(),
+        raw_txn.gas_token_code(),
+    )
+}
+
+/// Watch the submitted `signed_txn` until it or one of its bumped txns is mined,
+/// every bump is reported to stdout, and the mined txn is returned.
+pub fn watch_txn_with_gas_bump(
+    state: &CliState,
+    signed_txn: &SignedUserTransaction,
+    opt: &GasBumpOpt,
+) -> Result<ExecutionOutputView> {
+    let client = state.client();
+    if !opt.gas_bump {
+        let block = state.watch_txn(signed_txn.id())?.0;
+        return Ok(ExecutionOutputView {
+            txn_hash: signed_txn.id(),
+            block_number: Some(block.header.number.0),
+            block_id: Some(block.header.block_hash),
+        });
+    }
+    let mut raw_txn = signed_txn.raw_txn().clone();
+    let cap = opt.max_gas_price.unwrap_or_else(|| {
+        raw_txn
+            .gas_unit_price()
+            .saturating_mul(DEFAULT_MAX_GAS_PRICE_MULTIPLE)
+    });
+    let mut txn_hashes = vec![signed_txn.id()];
+    let start = Instant::now();
+    loop {
+        let latest = *txn_hashes.last().expect("txn hashes should not be empty");
+        if let Ok(block) = client.watch_txn(latest, Some(Duration::from_secs(opt.bump_after))) {
+            println!(
+                "txn {:#x} mined in block height: {}, hash: {:#x}",
+                latest, block.header.number, block.header.block_hash
+            );
+            return Ok(ExecutionOutputView {
+                txn_hash: latest,
+                block_number: Some(block.header.number.0),
+                block_id: Some(block.header.block_hash),
+            });
+        }
+        // the replaced txn may be mined before the bumped one.
+        for txn_hash in &txn_hashes {
+            if let Some(txn_info) = client.chain_get_transaction_info(*txn_hash)? {
+                return Ok(ExecutionOutputView {
+                    txn_hash: *txn_hash,
+                    block_number: Some(txn_info.block_number.0),
+                    block_id: Some(txn_info.block_hash),
+                });
+            }
+        }
+        if start.elapsed() > state.watch_timeout() {
+            bail!(
+                "txn {:#x} is not mined in {:?}",
+                latest,
+                state.watch_timeout()
+            );
+        }
+        match bump_gas_price(raw_txn.gas_unit_price(), opt.bump_percent, cap) {
+            Some(gas_price) => {
+                let old_gas_price = raw_txn.gas_unit_price();
+                raw_txn = with_gas_price(&raw_txn, gas_price);
+                let bumped_txn = client.account_sign_txn(raw_txn.clone())?;
+                let txn_hash = bumped_txn.id();
+                client.submit_transaction(bumped_txn)?;
+                println!(
+                    "txn {:#x} is not mined in {}s, bump gas price from {} to {}, new txn {:#x} submitted.",
+                    latest, opt.bump_after, old_gas_price, gas_price, txn_hash
+                );
+                txn_hashes.push(txn_hash);
+            }
+            None => {
+                println!(
+                    "txn {:#x} is not mined in {}s, the gas price {} reaches the cap {}, keep waiting.",
+                    latest,
+                    opt.bump_after,
+                    raw_txn.gas_unit_price(),
+                    cap
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_gas_price() {
+        assert_eq!(bump_gas_price(100, 20, 1000), Some(120));
+        // at least increase by 1.
+        assert_eq!(bump_gas_price(1, 20, 1000), Some(2));
+        assert_eq!(bump_gas_price(1000, 20, 1000), None);
+        assert_eq!(bump_gas_price(900, 20, 1000), None);
+        assert_eq!(bump_gas_price(u64::MAX, 20, u64::MAX), None);
+    }
+}
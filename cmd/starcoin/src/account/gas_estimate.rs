@@ -0,0 +1,98 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimate the max gas amount of a txn by dry running it on the latest chain state,
+//! the gas used by the dry run is increased by a safety margin, as the state may change before the txn is mined.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_account_api::AccountInfo;
+use starcoin_dev::playground;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_vm_types::transaction::{DryRunTransaction, RawUserTransaction, TransactionStatus};
+use starcoin_vm_types::vm_status::KeptVMStatus;
+use structopt::StructOpt;
+
+#[derive(Debug, Clone, StructOpt)]
+pub struct GasEstimateOpt {
+    #[structopt(long = "gas-margin", default_value = "20")]
+    /// the percent added to the gas used by dry run when the max gas amount is estimated.
+    pub gas_margin: u64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimateView {
+    /// The gas used by the dry run.
+    pub gas_used: u64,
+    /// The recommended max gas amount, the gas used with the safety margin.
+    pub max_gas_amount: u64,
+    pub gas_margin: u64,
+    /// The gas price suggested by the txpool.
+    pub gas_unit_price: u64,
+}
+
+/// Returns the gas used increased by `margin_percent`, rounded up and capped by `cap`.
+pub fn apply_gas_margin(gas_used: u64, margin_percent: u64, cap: u64) -> u64 {
+    let margin = (gas_used as u128 * margin_percent as u128 + 99) / 100;
+    let max_gas_amount = (gas_used as u128 + margin).min(u64::MAX as u128) as u64;
+    max_gas_amount.min(cap)
+}
+
+pub fn with_max_gas_amount(
+    raw_txn: &RawUserTransaction,
+    max_gas_amount: u64,
+) -> RawUserTransaction {
+    RawUserTransaction::new(
+        raw_txn.sender(),
+        raw_txn.sequence_number(),
+        raw_txn.clone().into_payload(),
+        max_gas_amount,
+        raw_txn.gas_unit_price(),
+        raw_txn.expiration_timestamp_secs(),
+        raw_txn.chain_id(),
+        raw_txn.gas_token_code(),
+    )
+}
+
+/// Dry run `raw_txn` as `sender` to estimate the max gas amount, the max gas amount of `raw_txn`
+/// is the cap of the estimate, fails if the txn is not executed successfully.
+pub fn estimate_gas(
+    client: &RpcClient,
+    sender: &AccountInfo,
+    raw_txn: &RawUserTransaction,
+    gas_margin: u64,
+) -> Result<GasEstimateView> {
+    let state_view = RemoteStateReader::new(client)?;
+    let (_, output) = playground::dry_run(
+        &state_view,
+        DryRunTransaction {
+            raw_txn: raw_txn.clone(),
+            public_key: sender.public_key.clone(),
+        },
+    )?;
+    match output.status() {
+        TransactionStatus::Keep(KeptVMStatus::Executed) => {}
+        status => bail!("estimate gas failed, the dry run status: {:?}", status),
+    }
+    Ok(GasEstimateView {
+        gas_used: output.gas_used(),
+        max_gas_amount: apply_gas_margin(output.gas_used(), gas_margin, raw_txn.max_gas_amount()),
+        gas_margin,
+        gas_unit_price: client.txpool_gas_price()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_gas_margin() {
+        assert_eq!(apply_gas_margin(1000, 20, 10000), 1200);
+        // round up the margin.
+        assert_eq!(apply_gas_margin(101, 10, 10000), 112);
+        assert_eq!(apply_gas_margin(1000, 0, 10000), 1000);
+        assert_eq!(apply_gas_margin(9000, 20, 10000), 10000);
+        assert_eq!(apply_gas_margin(u64::MAX, 20, u64::MAX), u64::MAX);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_rpc_api::types::{BlockTransactionsView, TransactionVMStatus};
+use starcoin_types::block::BlockNumber;
+use starcoin_types::transaction::TransactionPayload;
+use starcoin_vm_types::account_address::AccountAddress;
+use std::collections::BTreeMap;
+use structopt::StructOpt;
+
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "gas-report")]
+/// Summarize the gas spent by the txns sent by an account in the recent days, grouped by the called function and by day.
+/// The blocks of the period are scanned from the head block of the connected node, so it is slow for a long period.
+pub struct GasReportOpt {
+    #[structopt(name = "account_address")]
+    /// the sender address, if absent, use the default account.
+    account_address: Option<AccountAddress>,
+
+    #[structopt(long = "days", default_value = "30")]
+    /// the days before the head block to report.
+    days: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionGasView {
+    /// `address::Module::function` of the script function, or `script` and `package`.
+    pub function: String,
+    pub txns: u64,
+    pub failed_txns: u64,
+    pub gas_used: u64,
+    pub avg_gas_used: u64,
+    pub max_gas_used: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyGasView {
+    /// The days before the head block, 0 is the last 24 hours.
+    pub days_ago: u64,
+    pub txns: u64,
+    pub gas_used: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasReportView {
+    pub address: AccountAddress,
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    pub txns: u64,
+    pub gas_used: u64,
+    /// sorted by gas used desc.
+    pub functions: Vec<FunctionGasView>,
+    /// sorted by days ago asc, the days without txn are omitted.
+    pub daily: Vec<DailyGasView>,
+}
+
+pub(crate) fn payload_function(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::ScriptFunction(script_function) => format!(
+            "{}::{}",
+            script_function.module(),
+            script_function.function()
+        ),
+        TransactionPayload::Script(_) => "script".to_string(),
+        TransactionPayload::Package(_) => "package".to_string(),
+    }
+}
+
+/// Accumulate the gas of txns into the report.
+pub(crate) struct GasReport {
+    head_timestamp: u64,
+    functions: BTreeMap<String, FunctionGasView>,
+    daily: BTreeMap<u64, DailyGasView>,
+}
+
+impl GasReport {
+    pub(crate) fn new(head_timestamp: u64) -> Self {
+        Self {
+            head_timestamp,
+            functions: BTreeMap::new(),
+            daily: BTreeMap::new(),
+        }
+    }
+
+    /// Record a txn executed in the block at `timestamp` milliseconds.
+    pub(crate) fn record(&mut self, function: String, timestamp: u64, gas_used: u64, failed: bool) {
+        let function_gas =
+            self.functions
+                .entry(function.clone())
+                .or_insert_with(|| FunctionGasView {
+                    function,
+                    ..Default::default()
+                });
+        function_gas.txns += 1;
+        if failed {
+            function_gas.failed_txns += 1;
+        }
+        function_gas.gas_used += gas_used;
+        function_gas.max_gas_used = function_gas.max_gas_used.max(gas_used);
+        function_gas.avg_gas_used = function_gas.gas_used / function_gas.txns;
+
+        let days_ago = self.head_timestamp.saturating_sub(timestamp) / DAY_MILLIS;
+        let daily = self.daily.entry(days_ago).or_insert_with(|| DailyGasView {
+            days_ago,
+            ..Default::default()
+        });
+        daily.txns += 1;
+        daily.gas_used += gas_used;
+    }
+
+    pub(crate) fn into_view(
+        self,
+        address: AccountAddress,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> GasReportView {
+        let mut functions = self
+            .functions
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.gas_used.cmp(&a.gas_used));
+        let daily = self.daily.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        GasReportView {
+            address,
+            from_block,
+            to_block,
+            txns: daily.iter().map(|day| day.txns).sum(),
+            gas_used: daily.iter().map(|day| day.gas_used).sum(),
+            functions,
+            daily,
+        }
+    }
+}
+
+pub struct GasReportCommand;
+
+impl CommandAction for GasReportCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = GasReportOpt;
+    type ReturnItem = GasReportView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let address = match opt.account_address {
+            Some(address) => address,
+            None => ctx.state().default_account()?.address,
+        };
+        let head = client.chain_info()?.head;
+        let to_block = head.number.0;
+        let since = head
+            .timestamp
+            .0
+            .saturating_sub(opt.days.saturating_mul(DAY_MILLIS));
+        let mut report = GasReport::new(head.timestamp.0);
+        let mut from_block = to_block;
+        for number in (0..=to_block).rev() {
+            let block = client
+                .chain_get_block_by_number(number)?
+                .ok_or_else(|| format_err!("Can not find block by number: {}", number))?;
+            if block.header.timestamp.0 < since {
+                break;
+            }
+            from_block = number;
+            let txns = match block.body {
+                BlockTransactionsView::Full(txns) => txns,
+                BlockTransactionsView::Hashes(_) => bail!(
+                    "The node returns the txn hashes of block {} instead of the full txns.",
+                    number
+                ),
+            };
+            if !txns.iter().any(|txn| txn.raw_txn.sender == address) {
+                continue;
+            }
+            let txn_infos = client.chain_get_block_txn_infos(block.header.block_hash)?;
+            for txn in txns.iter().filter(|txn| txn.raw_txn.sender == address) {
+                let txn_info = txn_infos
+                    .iter()
+                    .find(|info| info.transaction_hash == txn.transaction_hash)
+                    .ok_or_else(|| {
+                        format_err!("Can not find txn info of txn {}", txn.transaction_hash)
+                    })?;
+                let payload =
+                    bcs_ext::from_bytes::<TransactionPayload>(txn.raw_txn.payload.0.as_slice())?;
+                report.record(
+                    payload_function(&payload),
+                    block.header.timestamp.0,
+                    txn_info.gas_used.0,
+                    txn_info.status != TransactionVMStatus::Executed,
+                );
+            }
+        }
+        Ok(report.into_view(address, from_block, to_block))
+    }
+}
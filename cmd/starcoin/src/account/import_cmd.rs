@@ -1,13 +1,16 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account::ENCRYPTED_PRIVATE_KEY_PREFIX;
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
 use anyhow::{bail, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_account_api::{AccountInfo, AccountPrivateKey};
 use starcoin_crypto::{ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use starcoin_decrypt::scrypt_decrypt;
 use starcoin_vm_types::account_address::AccountAddress;
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -31,6 +34,11 @@ pub struct ImportOpt {
     /// if account_address is absent, generate address by public_key.
     #[structopt(name = "account_address")]
     account_address: Option<AccountAddress>,
+
+    #[structopt(long = "encrypted-password")]
+    /// password to decrypt the private key exported by `account export --encrypted`,
+    /// default to the account password.
+    encrypted_password: Option<String>,
 }
 
 pub struct ImportCommand;
@@ -48,16 +56,22 @@ impl CommandAction for ImportCommand {
         let client = ctx.state().client();
         let opt: &ImportOpt = ctx.opt();
 
-        let private_key = match (opt.from_input.as_ref(), opt.from_file.as_ref()) {
-            (Some(p), _) => AccountPrivateKey::from_encoded_string(p)?,
-            (None, Some(p)) => {
-                let data = std::fs::read_to_string(p)?;
-                AccountPrivateKey::from_encoded_string(data.as_str())?
-            }
+        let encoded = match (opt.from_input.as_ref(), opt.from_file.as_ref()) {
+            (Some(p), _) => p.clone(),
+            (None, Some(p)) => std::fs::read_to_string(p)?,
             (None, None) => {
                 bail!("private key should be specified, use one of <input>, <from-file>")
             }
         };
+        let encoded = encoded.trim();
+        let private_key = match encoded.strip_prefix(ENCRYPTED_PRIVATE_KEY_PREFIX) {
+            Some(encrypted) => {
+                let password = opt.encrypted_password.as_ref().unwrap_or(&opt.password);
+                let data = scrypt_decrypt(password.as_bytes(), &hex::decode(encrypted)?)?;
+                AccountPrivateKey::try_from(data.as_slice())?
+            }
+            None => AccountPrivateKey::from_encoded_string(encoded)?,
+        };
 
         let address = opt
             .account_address
@@ -5,32 +5,45 @@ pub use accept_token_cmd::*;
 pub use change_password_cmd::*;
 pub use create_cmd::*;
 pub use default_cmd::*;
+pub use emergency_lock_all_cmd::*;
 pub use execute_script_cmd::*;
 pub use execute_script_function_cmd::*;
 pub use export_cmd::*;
+pub use gas_report_cmd::*;
 pub use import_cmd::*;
 pub use list_cmd::*;
 pub use lock_cmd::*;
+pub use multisig_rotate_cmd::*;
 pub use partial_sign_txn_cmd::*;
 pub use show_cmd::*;
 pub use sign_cmd::*;
+pub use submit_txn_cmd::*;
 pub use transfer_cmd::*;
 pub use unlock_cmd::*;
 pub use verify_sign_cmd::*;
+pub use watch_cmd::*;
 
 mod accept_token_cmd;
+pub mod address_poisoning;
 mod change_password_cmd;
 mod create_cmd;
 mod default_cmd;
+mod emergency_lock_all_cmd;
 mod execute_script_cmd;
 mod execute_script_function_cmd;
 mod export_cmd;
+pub mod gas_bump;
+pub mod gas_estimate;
+mod gas_report_cmd;
 mod import_cmd;
 mod list_cmd;
 mod lock_cmd;
+mod multisig_rotate_cmd;
 mod partial_sign_txn_cmd;
 mod show_cmd;
 mod sign_cmd;
+mod submit_txn_cmd;
 mod transfer_cmd;
 mod unlock_cmd;
 mod verify_sign_cmd;
+mod watch_cmd;
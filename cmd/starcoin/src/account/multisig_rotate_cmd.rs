@@ -0,0 +1,206 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::mutlisig_transaction::MultisigTransaction;
+use crate::StarcoinOpt;
+use anyhow::{ensure, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use starcoin_account_api::AccountInfo;
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+use starcoin_crypto::{ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_transaction_builder::encode_rotate_authentication_key_script_function;
+use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_types::transaction::RawUserTransaction;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::token::stc::STC_TOKEN_CODE_STR;
+use starcoin_vm_types::transaction::TransactionPayload;
+use std::convert::TryFrom;
+use std::env::current_dir;
+use std::fs::File;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "rotate")]
+/// Rotate the signers or threshold of a multisig account in the wallet.
+/// Without `--commit`, generate the auth key rotation txn which should be signed by the current signers,
+/// and output the txn to file, waiting for signers to sign it by `account partial-sign-txn`,
+/// then submit it by `dev submit-multisig-txn`.
+/// With `--commit`, check the rotation is confirmed on chain, and update the local key shard to the new signers.
+pub struct MultisigRotateOpt {
+    #[structopt(short = "s")]
+    /// the multisig account address in wallet, if empty, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(short = "p", required = true, min_values = 1, max_values = 32, parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    /// public keys of the new signers.
+    public_key: Vec<Ed25519PublicKey>,
+
+    #[structopt(long)]
+    /// the new threshold of the multisig account, default to the number of new signers.
+    threshold: Option<u8>,
+
+    #[structopt(long)]
+    /// update the local key shard after the rotation txn is confirmed on chain.
+    commit: bool,
+
+    #[structopt(long = "password", default_value = "")]
+    /// password of the multisig account in wallet, only used with `--commit`.
+    password: String,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "g",
+        long = "max-gas",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+
+    #[structopt(name = "output-dir", long = "output-dir")]
+    /// dir used to save raw txn data file. Default to current dir.
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultisigRotateView {
+    pub address: AccountAddress,
+    pub new_auth_key: AuthenticationKey,
+    /// the rotation txn file, waiting for signers to sign.
+    pub txn_file: Option<PathBuf>,
+    /// the local account info after the key shard updated.
+    pub account: Option<AccountInfo>,
+}
+
+pub struct MultisigRotateCommand;
+
+impl CommandAction for MultisigRotateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = MultisigRotateOpt;
+    type ReturnItem = MultisigRotateView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let account = ctx.state().get_account_or_default(opt.sender)?;
+        let old_public_key = account
+            .public_key
+            .as_multi()
+            .ok_or_else(|| format_err!("account {} is not a multisig account", account.address))?;
+
+        let new_public_key = {
+            // sort the public key to keep the same order as gen-multisig-txn.
+            let mut pubkeys = opt.public_key.clone();
+            pubkeys.sort_by_key(|k| k.to_bytes());
+            pubkeys.dedup();
+            ensure!(
+                pubkeys.len() == opt.public_key.len(),
+                "the public keys of the new signers should not be duplicated"
+            );
+            let threshold = match opt.threshold {
+                Some(threshold) => threshold,
+                None => u8::try_from(pubkeys.len())?,
+            };
+            MultiEd25519PublicKey::new(pubkeys, threshold)?
+        };
+        ensure!(
+            new_public_key.to_bytes() != old_public_key.to_bytes(),
+            "the new signers and threshold are same as current"
+        );
+        let new_auth_key = AuthenticationKey::multi_ed25519(&new_public_key);
+
+        let client = ctx.state().client();
+        let chain_state_reader = RemoteStateReader::new(client)?;
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let account_resource = account_state_reader
+            .get_account_resource(&account.address)?
+            .ok_or_else(|| format_err!("address {} not exists on chain", &account.address))?;
+        let on_chain_auth_key = account_resource.authentication_key();
+
+        if opt.commit {
+            ensure!(
+                on_chain_auth_key == new_auth_key.as_ref(),
+                "the auth key of {} on chain is not rotated to {} yet",
+                account.address,
+                new_auth_key
+            );
+            let account_info = client.account_rotate_multisig_key(
+                account.address,
+                new_public_key,
+                opt.password.clone(),
+            )?;
+            return Ok(MultisigRotateView {
+                address: account.address,
+                new_auth_key,
+                txn_file: None,
+                account: Some(account_info),
+            });
+        }
+
+        ensure!(
+            on_chain_auth_key == AuthenticationKey::multi_ed25519(&old_public_key).as_ref(),
+            "the auth key of {} on chain does not match the local multisig key",
+            account.address
+        );
+        let node_info = client.node_info()?;
+        let raw_txn = RawUserTransaction::new(
+            account.address,
+            account_resource.sequence_number(),
+            TransactionPayload::ScriptFunction(encode_rotate_authentication_key_script_function(
+                new_auth_key,
+            )),
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time + node_info.now_seconds,
+            ctx.state().net().chain_id(),
+            STC_TOKEN_CODE_STR.to_string(),
+        );
+        // the rotation txn must be signed under the current key.
+        let txn = MultisigTransaction::new(
+            raw_txn.clone(),
+            old_public_key.public_keys().clone(),
+            *old_public_key.threshold(),
+        );
+        let output_file = {
+            let mut output_dir = opt.output_dir.clone().unwrap_or(current_dir()?);
+            let file_name = raw_txn.crypto_hash().short_str();
+            output_dir.push(file_name.as_str());
+            output_dir.set_extension("multisig-txn");
+            output_dir
+        };
+        let mut file = File::create(output_file.clone())?;
+        bcs_ext::serialize_into(&mut file, &txn)?;
+        Ok(MultisigRotateView {
+            address: account.address,
+            new_auth_key,
+            txn_file: Some(output_file),
+            account: None,
+        })
+    }
+}
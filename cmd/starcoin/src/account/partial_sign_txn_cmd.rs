@@ -83,7 +83,8 @@ impl CommandAction for PartialSignTxnCommand {
                 public_key,
                 signature,
             } => (public_key, signature),
-            transaction::authenticator::TransactionAuthenticator::MultiEd25519 { .. } => {
+            transaction::authenticator::TransactionAuthenticator::MultiEd25519 { .. }
+            | transaction::authenticator::TransactionAuthenticator::Bls12381 { .. } => {
                 unreachable!()
             }
         };
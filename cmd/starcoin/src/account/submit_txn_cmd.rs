@@ -0,0 +1,64 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::view::{ExecuteResultView, ExecutionOutputView};
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_vm_types::transaction::SignedUserTransaction;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "submit-txn")]
+/// Submit a signed txn file, such as the file generated by `account execute-function --offline`.
+pub struct SubmitTxnOpt {
+    #[structopt(name = "signed-txn-file", parse(from_os_str))]
+    /// the signed txn file
+    signed_txn_file: PathBuf,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+}
+
+pub struct SubmitTxnCommand;
+
+impl CommandAction for SubmitTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SubmitTxnOpt;
+    type ReturnItem = ExecuteResultView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let signed_txn: SignedUserTransaction = {
+            let mut f = File::open(&opt.signed_txn_file)?;
+            let mut data = vec![];
+            f.read_to_end(&mut data)?;
+            bcs_ext::from_bytes(data.as_slice())?
+        };
+        let txn_hash = signed_txn.id();
+        ctx.state().client().submit_transaction(signed_txn)?;
+
+        println!("txn {:#x} submitted.", txn_hash);
+
+        let mut output_view = ExecutionOutputView::new(txn_hash);
+        if opt.blocking {
+            let block = ctx.state().watch_txn(txn_hash)?.0;
+            output_view.block_number = Some(block.header.number.0);
+            output_view.block_id = Some(block.header.block_hash);
+        }
+        Ok(ExecuteResultView::Run(output_view))
+    }
+}
@@ -1,13 +1,16 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account::gas_bump::{watch_txn_with_gas_bump, GasBumpOpt};
+use crate::account::gas_estimate::{estimate_gas, with_max_gas_amount, GasEstimateOpt};
 use crate::cli_state::CliState;
 use crate::view::{ExecuteResultView, ExecutionOutputView};
 use crate::StarcoinOpt;
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
-use starcoin_executor::DEFAULT_EXPIRATION_TIME;
+use starcoin_executor::{DEFAULT_EXPIRATION_TIME, DEFAULT_MAX_GAS_AMOUNT};
+use starcoin_logger::prelude::*;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::receipt_identifier::ReceiptIdentifier;
@@ -37,14 +40,9 @@ pub struct TransferOpt {
 
     #[structopt(short = "v")]
     amount: u128,
-    #[structopt(
-        short = "g",
-        long = "max-gas",
-        name = "max-gas-amount",
-        default_value = "10000000",
-        help = "max gas to use"
-    )]
-    max_gas_amount: u64,
+    #[structopt(short = "g", long = "max-gas", name = "max-gas-amount")]
+    /// max gas to use, estimated by dry run if absent.
+    max_gas_amount: Option<u64>,
     #[structopt(
         short = "p",
         long = "gas-price",
@@ -69,6 +67,17 @@ pub struct TransferOpt {
         help = "blocking wait txn mined"
     )]
     blocking: bool,
+
+    #[structopt(long = "allow-lookalike")]
+    /// transfer even if the receiver looks like a wallet account or a recent counterparty,
+    /// which only differs in the middle characters, see the address poisoning scam.
+    allow_lookalike: bool,
+
+    #[structopt(flatten)]
+    gas_bump: GasBumpOpt,
+
+    #[structopt(flatten)]
+    gas_estimate: GasEstimateOpt,
 }
 
 pub struct TransferCommand;
@@ -131,6 +140,16 @@ impl CommandAction for TransferCommand {
             },
         };
 
+        if let Some(warning) = ctx.state().check_lookalike_address(&receiver_address)? {
+            if !opt.allow_lookalike {
+                bail!(
+                    "{}, please double check the receiver, or use --allow-lookalike to transfer anyway",
+                    warning
+                );
+            }
+            eprintln!("Warning: {}", warning);
+        }
+
         let account_resource = account_state_reader
             .get_account_resource(sender.address())?
             .ok_or_else(|| {
@@ -150,22 +169,32 @@ impl CommandAction for TransferCommand {
             account_resource.sequence_number(),
             opt.amount,
             opt.gas_price,
-            opt.max_gas_amount,
+            opt.max_gas_amount.unwrap_or(DEFAULT_MAX_GAS_AMOUNT),
             token_code,
             node_info.now_seconds + DEFAULT_EXPIRATION_TIME,
             ctx.state().net().chain_id(),
         );
+        let raw_txn = match opt.max_gas_amount {
+            Some(_) => raw_txn,
+            None => {
+                let estimate =
+                    estimate_gas(client, &sender, &raw_txn, opt.gas_estimate.gas_margin)?;
+                with_max_gas_amount(&raw_txn, estimate.max_gas_amount)
+            }
+        };
         let txn = client.account_sign_txn(raw_txn)?;
         let txn_hash = txn.id();
-        client.submit_transaction(txn)?;
-
-        let mut output_view = ExecutionOutputView::new(txn_hash);
-
-        if opt.blocking {
-            let block = ctx.state().watch_txn(txn_hash)?.0;
-            output_view.block_number = Some(block.header.number.0);
-            output_view.block_id = Some(block.header.block_hash);
+        client.submit_transaction(txn.clone())?;
+        // The txn is already submitted, do not fail the transfer if the address book can not be saved.
+        if let Err(e) = ctx.state().record_counterparty(receiver_address) {
+            warn!("Record counterparty {} failed: {:?}", receiver_address, e);
         }
+
+        let output_view = if opt.blocking {
+            watch_txn_with_gas_bump(ctx.state(), &txn, &opt.gas_bump)?
+        } else {
+            ExecutionOutputView::new(txn_hash)
+        };
         Ok(ExecuteResultView::Run(output_view))
     }
 }
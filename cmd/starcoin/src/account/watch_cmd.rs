@@ -0,0 +1,108 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::blocking_display_notification;
+use crate::view::{EventDataView, EventView};
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_rpc_api::types::TransactionEventView;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_vm_types::account_address::AccountAddress;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "watch")]
+/// Watch the deposit, withdraw and accept token events of an account in real time.
+pub struct WatchOpt {
+    #[structopt(name = "account_address")]
+    /// the account address to watch, if absent, use the default account.
+    account_address: Option<AccountAddress>,
+
+    #[structopt(long)]
+    /// print every event as a json line, for piping into other tools.
+    json: bool,
+}
+
+pub struct WatchCommand;
+
+impl CommandAction for WatchCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = WatchOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let address = match opt.account_address {
+            Some(address) => address,
+            None => ctx.state().default_account()?.address,
+        };
+        let chain_state_reader = RemoteStateReader::new(client)?;
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let account_resource = account_state_reader
+            .get_account_resource(&address)?
+            .ok_or_else(|| format_err!("address {} not exists on chain", address))?;
+
+        let filter = EventFilter {
+            from_block: None,
+            to_block: None,
+            event_keys: vec![
+                *account_resource.deposit_events().key(),
+                *account_resource.withdraw_events().key(),
+                *account_resource.accept_token_events().key(),
+            ],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
+            limit: None,
+        };
+        let event_stream = client.subscribe_events(filter)?;
+        if !opt.json {
+            println!(
+                "Watching events of {}, Press `q` and Enter to quit",
+                address
+            );
+        }
+        let json = opt.json;
+        blocking_display_notification(event_stream, |evt: &TransactionEventView| {
+            if json {
+                serde_json::to_string(evt).expect("should never fail")
+            } else {
+                display_event(evt)
+            }
+        });
+        Ok(())
+    }
+}
+
+fn display_event(evt: &TransactionEventView) -> String {
+    let block_number = evt
+        .block_number
+        .as_ref()
+        .map(|n| n.0.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let txn_hash = evt
+        .transaction_hash
+        .map(|h| format!("{:#x}", h))
+        .unwrap_or_else(|| "-".to_string());
+    let event = match EventView::from(evt.clone()).data {
+        EventDataView::ReceivedPayment {
+            amount, token_code, ..
+        } => format!("deposit {} {}", amount, token_code),
+        EventDataView::SentPayment {
+            amount, token_code, ..
+        } => format!("withdraw {} {}", amount, token_code),
+        EventDataView::AcceptToken { token_code } => format!("accept token {}", token_code),
+        other => format!("{:?}", other),
+    };
+    format!("block {} txn {}: {}", block_number, txn_hash, event)
+}
@@ -5,11 +5,12 @@ use crate::cli_state::CliState;
 use crate::StarcoinOpt;
 use anyhow::Result;
 use scmd::{CommandAction, ExecContext};
-use starcoin_rpc_api::types::BlockHeaderView;
+use starcoin_rpc_api::types::{BlockHeaderView, GetBlocksOption};
 use starcoin_types::block::BlockNumber;
 use structopt::StructOpt;
 
 /// List latest `count` blocks before `number`. if `number` is absent, use head block number.
+/// With `--ascending`, list `count` blocks from `number`, and `number` defaults to 0.
 #[derive(Debug, StructOpt)]
 #[structopt(name = "list_block")]
 pub struct GetOpt {
@@ -17,6 +18,9 @@ pub struct GetOpt {
     number: Option<BlockNumber>,
     #[structopt(name = "count", long, short = "c", default_value = "10")]
     count: u64,
+    #[structopt(long = "ascending", short = "a")]
+    /// list blocks in ascending order from `number`.
+    ascending: bool,
 }
 
 pub struct ListBlockCommand;
@@ -33,7 +37,15 @@ impl CommandAction for ListBlockCommand {
     ) -> Result<Self::ReturnItem> {
         let client = ctx.state().client();
         let opt = ctx.opt();
-        let blocks = client.chain_get_blocks_by_number(opt.number, opt.count)?;
+        let blocks = client.chain_get_blocks_by_number_with_option(
+            opt.number,
+            opt.count,
+            GetBlocksOption {
+                reverse: !opt.ascending,
+                full_txns: false,
+                ..Default::default()
+            },
+        )?;
         let block_view = blocks.into_iter().map(|block| block.header).collect();
         Ok(block_view)
     }
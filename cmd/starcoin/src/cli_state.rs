@@ -1,5 +1,8 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
+use crate::account::address_poisoning::{
+    recent_counterparties_file, KnownAddresses, LookalikeWarning, RecentCounterparties,
+};
 use anyhow::{format_err, Result};
 use starcoin_account_api::AccountInfo;
 use starcoin_config::{ChainNetworkID, DataDirPath};
@@ -15,6 +18,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 static HISTORY_FILE_NAME: &str = "history";
+static USAGE_METRICS_FILE_NAME: &str = "usage_metrics.json";
+/// Set this env to `true` to opt in the cli usage metrics.
+pub static USAGE_METRICS_ENV: &str = "STARCOIN_CLI_USAGE_METRICS";
 
 pub struct CliState {
     net: ChainNetworkID,
@@ -67,6 +73,10 @@ impl CliState {
         &self.client
     }
 
+    pub fn watch_timeout(&self) -> Duration {
+        self.watch_timeout
+    }
+
     pub fn temp_dir(&self) -> &Path {
         self.temp_dir.path()
     }
@@ -80,6 +90,20 @@ impl CliState {
         self.data_dir().join(HISTORY_FILE_NAME)
     }
 
+    /// Cli usage metrics file, shared by all networks, ~/.starcoin/cli/usage_metrics.json
+    pub fn usage_metrics_file() -> PathBuf {
+        starcoin_config::DEFAULT_BASE_DATA_DIR
+            .join("cli")
+            .join(USAGE_METRICS_FILE_NAME)
+    }
+
+    /// The cli usage metrics is opt-in, enabled by env `STARCOIN_CLI_USAGE_METRICS=true`.
+    pub fn usage_metrics_enabled() -> bool {
+        std::env::var(USAGE_METRICS_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+    }
+
     pub fn node_handle(&self) -> Option<&NodeHandle> {
         self.node_handle.as_ref()
     }
@@ -103,6 +127,30 @@ impl CliState {
         }
     }
 
+    /// Check whether `address` looks like a wallet account or a recent counterparty, see `address_poisoning`.
+    pub fn check_lookalike_address(
+        &self,
+        address: &AccountAddress,
+    ) -> Result<Option<LookalikeWarning>> {
+        let wallet_accounts = self
+            .client
+            .account_list()?
+            .into_iter()
+            .map(|account| account.address)
+            .collect::<Vec<_>>();
+        let recent =
+            RecentCounterparties::load(recent_counterparties_file(self.data_dir()).as_path())?;
+        Ok(KnownAddresses::new(wallet_accounts.as_slice(), &recent).check(address))
+    }
+
+    /// Record `address` as a recent counterparty.
+    pub fn record_counterparty(&self, address: AccountAddress) -> Result<()> {
+        let file = recent_counterparties_file(self.data_dir());
+        let mut recent = RecentCounterparties::load(file.as_path())?;
+        recent.record(address);
+        recent.save(file.as_path())
+    }
+
     pub fn association_account(&self) -> Result<Option<AccountInfo>> {
         self.client.account_get(association_address())
     }
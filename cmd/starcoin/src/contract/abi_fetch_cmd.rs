@@ -0,0 +1,95 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Serialize, Serializer};
+use starcoin_rpc_api::types::StrView;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::{FunctionId, ModuleId};
+use starcoin_vm_types::module_abi::{EventABI, ModuleABI};
+use starcoin_vm_types::transaction::ScriptFunctionABI;
+use structopt::StructOpt;
+
+/// Fetch the ABI of a module from the `ABIRegistry` at the module address.
+///  Some examples:
+///  ``` shell
+///  contract abi fetch 0x123::MyToken
+///  contract abi fetch 0x123::MyToken --function mint
+///  contract abi fetch 0x123::MyToken --event MintEvent
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "fetch")]
+pub struct AbiFetchOpt {
+    #[structopt(help = "module id like: 0x1::Account")]
+    module_id: StrView<ModuleId>,
+
+    #[structopt(long = "function", conflicts_with = "event")]
+    /// only show the ABI of the script function, resolved from the module code if the ABI is not published.
+    function: Option<Identifier>,
+
+    #[structopt(long = "event")]
+    /// only show the schema of the event.
+    event: Option<Identifier>,
+}
+
+pub enum AbiFetchResult {
+    Module(Option<ModuleABI>),
+    Function(ScriptFunctionABI),
+    Event(EventABI),
+}
+
+impl Serialize for AbiFetchResult {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Module(m) => m.serialize(serializer),
+            Self::Function(f) => f.serialize(serializer),
+            Self::Event(e) => e.serialize(serializer),
+        }
+    }
+}
+
+pub struct AbiFetchCommand;
+
+impl CommandAction for AbiFetchCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = AbiFetchOpt;
+    type ReturnItem = AbiFetchResult;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let module_id = opt.module_id.0.clone();
+        if let Some(function) = &opt.function {
+            return Ok(AbiFetchResult::Function(client.contract_resolve_function(
+                FunctionId {
+                    module: module_id,
+                    function: function.clone(),
+                },
+            )?));
+        }
+        let module_abi = client.contract_get_abi(module_id.clone())?;
+        match &opt.event {
+            Some(event) => {
+                let event_abi = module_abi
+                    .as_ref()
+                    .and_then(|abi| abi.event(event.as_ident_str()))
+                    .cloned()
+                    .ok_or_else(|| {
+                        format_err!("event {} of module {} not published", event, module_id)
+                    })?;
+                Ok(AbiFetchResult::Event(event_abi))
+            }
+            None => Ok(AbiFetchResult::Module(module_abi)),
+        }
+    }
+}
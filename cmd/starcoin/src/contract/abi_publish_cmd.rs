@@ -0,0 +1,320 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::publish_package_cmd::compile_package;
+use crate::view::{ExecuteResultView, ExecutionOutputView};
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_config::temp_path;
+use starcoin_dev::playground;
+use starcoin_rpc_api::types::{DryRunOutputView, TransactionVMStatus};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{
+    DryRunTransaction, Module, Package, RawUserTransaction, ScriptFunction, TransactionPayload,
+};
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::module_abi::{
+    event_abi_from_module, script_function_abis_from_module, ABIRegistry, ModuleABI,
+};
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::transaction::{ScriptABI, ScriptFunctionABI};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// The move source of the `ABIRegistry` module, which is published at the sender address with the first ABI.
+pub(crate) const ABI_REGISTRY_MODULE_SOURCE: &str = r#"
+module ABIRegistry {
+    use 0x1::Signer;
+    use 0x1::Vector;
+
+    struct ModuleABI has store {
+        module_name: vector<u8>,
+        abi: vector<u8>,
+    }
+
+    struct ABIRegistry has key {
+        abis: vector<ModuleABI>,
+    }
+
+    /// Attach the bcs encoded `abi` to the module `module_name` of the signer, replace the old one if exists.
+    public(script) fun publish(account: signer, module_name: vector<u8>, abi: vector<u8>) acquires ABIRegistry {
+        let addr = Signer::address_of(&account);
+        if (!exists<ABIRegistry>(addr)) {
+            move_to(&account, ABIRegistry { abis: Vector::empty<ModuleABI>() });
+        };
+        let registry = borrow_global_mut<ABIRegistry>(addr);
+        let len = Vector::length(&registry.abis);
+        let i = 0;
+        while (i < len) {
+            let entry = Vector::borrow_mut(&mut registry.abis, i);
+            if (&entry.module_name == &module_name) {
+                entry.abi = abi;
+                return
+            };
+            i = i + 1;
+        };
+        Vector::push_back(&mut registry.abis, ModuleABI { module_name, abi });
+    }
+}
+"#;
+
+/// Attach the ABI of a module published by the sender to the `ABIRegistry` at the sender address,
+/// so the RPC and the tools can resolve the named args and the event schemas of the module from chain.
+/// The `ABIRegistry` module is published together with the first ABI if it not exists.
+///  Some examples:
+///  ``` shell
+///  contract abi publish -s 0x123 --module MyToken --abi-dir ./build/abi --event MintEvent
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "publish")]
+pub struct AbiPublishOpt {
+    #[structopt(short = "s", long = "sender")]
+    /// the address of the module, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(short = "m", long = "module")]
+    /// the module name.
+    module: Identifier,
+
+    #[structopt(long = "abi-dir", parse(from_os_str))]
+    /// the dir of the `.abi` files generated by abigen, the ABIs of other modules are ignored.
+    /// if absent, the script functions are resolved from the module code and the args are named by position.
+    abi_dir: Option<PathBuf>,
+
+    #[structopt(long = "event")]
+    /// the event struct of the module, the schema is resolved from the module code, support multi events.
+    events: Option<Vec<Identifier>>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to publish the abi"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to publish the abi"
+    )]
+    gas_price: u64,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+
+    #[structopt(long = "dry-run")]
+    /// dry-run mode, only get transaction output, no state change to chain
+    dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AbiPublishView {
+    pub module_id: String,
+    pub functions: Vec<String>,
+    pub events: Vec<String>,
+    /// Whether the `ABIRegistry` module is published by this txn.
+    pub registry_published: bool,
+    pub result: ExecuteResultView,
+}
+
+/// Load the ABIs of the script functions of `module_id` from the `.abi` files in `dir` recursively.
+pub(crate) fn load_module_function_abis(
+    dir: &Path,
+    module_id: &ModuleId,
+) -> Result<Vec<ScriptFunctionABI>> {
+    let mut abis = vec![];
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            abis.extend(load_module_function_abis(path.as_path(), module_id)?);
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("abi") {
+            let abi: ScriptABI = bcs_ext::from_bytes(std::fs::read(path.as_path())?.as_slice())
+                .map_err(|e| format_err!("invalid abi file {}: {}", path.display(), e))?;
+            if let ScriptABI::ScriptFunction(abi) = abi {
+                if abi.module_name() == module_id {
+                    abis.push(abi);
+                }
+            }
+        }
+    }
+    Ok(abis)
+}
+
+/// Build the ABI of the module deployed on chain, the ABIs in `abi_dir` are preferred for the script functions.
+pub(crate) fn build_module_abi(
+    state_view: &dyn StateView,
+    module_id: &ModuleId,
+    abi_dir: Option<&Path>,
+    events: &[Identifier],
+) -> Result<ModuleABI> {
+    let code = state_view
+        .get(&AccessPath::from(module_id))?
+        .ok_or_else(|| format_err!("module {} not exists on chain", module_id))?;
+    let module = CompiledModule::deserialize(code.as_slice())
+        .map_err(|e| format_err!("deserialize module {} error: {:?}", module_id, e))?;
+    let functions = match abi_dir {
+        Some(abi_dir) => load_module_function_abis(abi_dir, module_id)?,
+        None => script_function_abis_from_module(&module)?,
+    };
+    let events = events
+        .iter()
+        .map(|event| event_abi_from_module(&module, event.as_ident_str()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ModuleABI {
+        module_id: module_id.clone(),
+        functions,
+        events,
+    })
+}
+
+pub struct AbiPublishCommand;
+
+impl CommandAction for AbiPublishCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = AbiPublishOpt;
+    type ReturnItem = AbiPublishView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let module_id = ModuleId::new(sender.address, opt.module.clone());
+        let chain_state_reader = RemoteStateReader::new(client)?;
+
+        let module_abi = build_module_abi(
+            &chain_state_reader,
+            &module_id,
+            opt.abi_dir.as_deref(),
+            opt.events.clone().unwrap_or_default().as_slice(),
+        )?;
+        let publish_function = ScriptFunction::new(
+            ABIRegistry::module_id(sender.address),
+            Identifier::new("publish")?,
+            vec![],
+            vec![
+                bcs_ext::to_bytes(&module_id.name().as_str().as_bytes().to_vec())?,
+                bcs_ext::to_bytes(&bcs_ext::to_bytes(&module_abi)?)?,
+            ],
+        );
+        let registry_published = chain_state_reader
+            .get(&AccessPath::from(&ABIRegistry::module_id(sender.address)))?
+            .is_none();
+        let payload = if registry_published {
+            let temp_path = temp_path();
+            std::fs::write(
+                temp_path.path().join("ABIRegistry.move"),
+                ABI_REGISTRY_MODULE_SOURCE,
+            )?;
+            let modules = compile_package(temp_path.path(), sender.address, &[], &[])?
+                .into_iter()
+                .map(|module| {
+                    let mut blob = vec![];
+                    module
+                        .serialize(&mut blob)
+                        .map_err(|e| format_err!("serialize module error: {:?}", e))?;
+                    Ok(Module::new(blob))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            TransactionPayload::Package(Package::new(modules, Some(publish_function))?)
+        } else {
+            TransactionPayload::ScriptFunction(publish_function)
+        };
+
+        let node_info = client.node_info()?;
+        let account_resource = AccountStateReader::new(&chain_state_reader)
+            .get_account_resource(&sender.address)?
+            .ok_or_else(|| format_err!("account {} not exists on chain", sender.address))?;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender.address,
+            account_resource.sequence_number(),
+            payload,
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time + node_info.now_seconds,
+            ctx.state().net().chain_id(),
+        );
+        let signed_txn = client.account_sign_txn(raw_txn)?;
+        let txn_hash = signed_txn.id();
+
+        let output: DryRunOutputView = playground::dry_run_annotated(
+            &chain_state_reader,
+            DryRunTransaction {
+                public_key: signed_txn.authenticator().public_key(),
+                raw_txn: signed_txn.raw_txn().clone(),
+            },
+        )
+        .map(|(_, output, annotation)| {
+            DryRunOutputView::new(output, annotation.write_set, annotation.events)
+        })?;
+        match output.status {
+            TransactionVMStatus::Discard { status_code } => {
+                bail!("TransactionStatus is discard: {:?}", status_code)
+            }
+            TransactionVMStatus::Executed => {}
+            s => {
+                bail!("pre-run failed, status: {:?}", s);
+            }
+        }
+
+        let result = if !opt.dry_run {
+            client.submit_transaction(signed_txn)?;
+            println!("txn {:#x} submitted.", txn_hash);
+
+            let mut output_view = ExecutionOutputView::new(txn_hash);
+            if opt.blocking {
+                let block = ctx.state().watch_txn(txn_hash)?.0;
+                output_view.block_number = Some(block.header.number.0);
+                output_view.block_id = Some(block.header.block_hash);
+            }
+            ExecuteResultView::Run(output_view)
+        } else {
+            ExecuteResultView::DryRun(output.into())
+        };
+        Ok(AbiPublishView {
+            module_id: module_id.to_string(),
+            functions: module_abi
+                .functions
+                .iter()
+                .map(|function| function.name().to_string())
+                .collect(),
+            events: module_abi
+                .events
+                .iter()
+                .map(|event| event.name.clone())
+                .collect(),
+            registry_published,
+            result,
+        })
+    }
+}
@@ -1,6 +1,14 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod abi_fetch_cmd;
+mod abi_publish_cmd;
+#[cfg(test)]
+mod tests;
+
+pub use abi_fetch_cmd::*;
+pub use abi_publish_cmd::*;
+
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
 use anyhow::Result;
@@ -15,6 +23,7 @@ use structopt::StructOpt;
 ///  Some examples:
 ///  ``` shell
 ///  contract get code 0x1::Account
+///  contract get code 0x1::Account --disassemble
 ///  contract get resource 0x1 0x1::Account::Account
 ///  ```
 #[derive(Debug, StructOpt)]
@@ -23,6 +32,11 @@ pub enum GetContractDataOpt {
     Code {
         #[structopt(help = "module id like: 0x1::Account")]
         module_id: StrView<ModuleId>,
+        #[structopt(
+            long = "disassemble",
+            help = "show the disassembled code instead of the bytes"
+        )]
+        disassemble: bool,
     },
     Resource {
         #[structopt(help = "account address")]
@@ -65,8 +79,18 @@ impl CommandAction for GetContractDataCommand {
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
         let result = match opt {
-            GetContractDataOpt::Code { module_id } => {
-                GetContractDataResult::Code(ctx.state().client().get_code(module_id.0.clone())?)
+            GetContractDataOpt::Code {
+                module_id,
+                disassemble,
+            } => {
+                let code = if *disassemble {
+                    ctx.state()
+                        .client()
+                        .get_code_disassembled(module_id.0.clone())?
+                } else {
+                    ctx.state().client().get_code(module_id.0.clone())?
+                };
+                GetContractDataResult::Code(code)
             }
             GetContractDataOpt::Resource {
                 address,
@@ -0,0 +1,130 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::contract::abi_publish_cmd::{load_module_function_abis, ABI_REGISTRY_MODULE_SOURCE};
+use crate::dev::publish_package_cmd::compile_package;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_vm_types::access::ModuleAccess;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::{ModuleId, TypeTag};
+use starcoin_vm_types::module_abi::{
+    compiled_module_abi, event_abi_from_module, script_function_abis_from_module, ABIRegistry,
+    ModuleABI, ModuleABIEntry, TypeABI,
+};
+use starcoin_vm_types::transaction::{ArgumentABI, ScriptABI, ScriptFunctionABI};
+
+#[stest::test]
+fn test_abi_registry_module() {
+    let temp_path = starcoin_config::temp_path();
+    std::fs::write(
+        temp_path.path().join("ABIRegistry.move"),
+        ABI_REGISTRY_MODULE_SOURCE,
+    )
+    .unwrap();
+    let sender = AccountAddress::random();
+    let modules = compile_package(temp_path.path(), sender, &[], &[]).unwrap();
+    assert_eq!(modules.len(), 1);
+    let module = &modules[0];
+    assert_eq!(module.self_id(), ABIRegistry::module_id(sender));
+
+    let functions = script_function_abis_from_module(module).unwrap();
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].name(), "publish");
+    let bytes = TypeTag::Vector(Box::new(TypeTag::U8));
+    assert_eq!(
+        functions[0].args(),
+        &[
+            ArgumentABI::new("arg0".to_string(), bytes.clone()),
+            ArgumentABI::new("arg1".to_string(), bytes.clone()),
+        ]
+    );
+
+    // the registry entry struct has the same layout as an event.
+    let entry = event_abi_from_module(module, Identifier::new("ModuleABI").unwrap().as_ident_str())
+        .unwrap();
+    assert_eq!(
+        entry.fields,
+        vec![
+            ArgumentABI::new("module_name".to_string(), bytes.clone()),
+            ArgumentABI::new("abi".to_string(), bytes),
+        ]
+    );
+
+    let compiled_abi = compiled_module_abi(module);
+    assert_eq!(compiled_abi.module_id, ABIRegistry::module_id(sender));
+    assert_eq!(compiled_abi.functions.len(), 1);
+    assert_eq!(
+        compiled_abi.functions[0].params,
+        vec![
+            TypeABI::Signer,
+            TypeABI::Vector(Box::new(TypeABI::U8)),
+            TypeABI::Vector(Box::new(TypeABI::U8)),
+        ]
+    );
+    let registry = compiled_abi
+        .structs
+        .iter()
+        .find(|s| s.name == "ABIRegistry")
+        .unwrap();
+    assert_eq!(registry.abilities, vec!["key".to_string()]);
+    assert_eq!(
+        registry.fields[0].type_abi,
+        TypeABI::Vector(Box::new(TypeABI::Struct {
+            address: sender,
+            module: Identifier::new("ABIRegistry").unwrap(),
+            name: Identifier::new("ModuleABI").unwrap(),
+            type_params: vec![],
+        }))
+    );
+}
+
+#[stest::test]
+fn test_abi_registry_get_abi() {
+    let address = AccountAddress::random();
+    let module_id = ModuleId::new(address, Identifier::new("MyToken").unwrap());
+    let module_abi = ModuleABI {
+        module_id: module_id.clone(),
+        functions: vec![ScriptFunctionABI::new(
+            "mint".to_string(),
+            module_id.clone(),
+            String::new(),
+            vec![],
+            vec![ArgumentABI::new("amount".to_string(), TypeTag::U128)],
+        )],
+        events: vec![],
+    };
+    let mut registry = ABIRegistry::default();
+    registry.abis.push(ModuleABIEntry {
+        module_name: b"MyToken".to_vec(),
+        abi: bcs_ext::to_bytes(&module_abi).unwrap(),
+    });
+    let registry: ABIRegistry =
+        bcs_ext::from_bytes(bcs_ext::to_bytes(&registry).unwrap().as_slice()).unwrap();
+    assert_eq!(
+        registry.get_abi(&module_id).unwrap(),
+        Some(module_abi.clone())
+    );
+    assert!(module_abi
+        .function(Identifier::new("mint").unwrap().as_ident_str())
+        .is_some());
+
+    let other = ModuleId::new(address, Identifier::new("Other").unwrap());
+    assert_eq!(registry.get_abi(&other).unwrap(), None);
+
+    // the abi registered under another module name is rejected.
+    registry.abis[0].module_name = b"Other".to_vec();
+    assert!(registry.get_abi(&other).is_err());
+
+    let temp_path = starcoin_config::temp_path();
+    std::fs::create_dir_all(temp_path.path().join("sub")).unwrap();
+    std::fs::write(
+        temp_path.path().join("sub").join("mint.abi"),
+        bcs_ext::to_bytes(&ScriptABI::ScriptFunction(module_abi.functions[0].clone())).unwrap(),
+    )
+    .unwrap();
+    let loaded = load_module_function_abis(temp_path.path(), &module_id).unwrap();
+    assert_eq!(loaded, module_abi.functions);
+    assert!(load_module_function_abis(temp_path.path(), &other)
+        .unwrap()
+        .is_empty());
+}
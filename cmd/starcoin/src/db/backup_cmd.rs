@@ -0,0 +1,48 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::types::DBBackupView;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Take an incremental, checksummed backup of the connected node's db, the sst files already in the
+/// target dir are shared with the previous backups. The target dir is on the node's filesystem,
+/// see `db restore` to restore a backup.
+///  Some examples:
+///  ``` shell
+///  db backup --target /data/starcoin-backup
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "backup")]
+pub struct BackupOpt {
+    #[structopt(long = "target", parse(from_os_str))]
+    /// the backup dir, a relative path is resolved by the current dir.
+    target: PathBuf,
+}
+
+pub struct BackupCommand;
+
+impl CommandAction for BackupCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = BackupOpt;
+    type ReturnItem = DBBackupView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let target = if ctx.opt().target.is_absolute() {
+            ctx.opt().target.clone()
+        } else {
+            std::env::current_dir()?.join(ctx.opt().target.as_path())
+        };
+        ctx.state()
+            .client()
+            .node_admin_backup_db(target.to_string_lossy().to_string())
+    }
+}
@@ -0,0 +1,465 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The interchange formats of the chain export, both are hash-linked and can be verified
+//! without a starcoin node.
+//!
+//! `jsonl`: the first line is the `ExportHeader`, and every following line is an `ExportedBlock`,
+//! in block number order. The `block` field is the hex encoded bcs bytes of the block, the block id
+//! is the crypto hash of the block header, and every header links to the previous block by the
+//! parent hash. The txn infos are the same as the `chain.get_block_txn_infos` rpc, the first is the
+//! block metadata txn, and the following are the user txns of the block body.
+//!
+//! `car`: a CARv1 (https://ipld.io/specs/transport/car/carv1/) file, every section is the bcs bytes
+//! of a block in block number order, addressed by a CIDv1 with the `raw` codec and a `sha3-256`
+//! multihash of the bytes. The root of the CAR header is the CID of the last block.
+
+use anyhow::{bail, ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_rpc_api::types::{StrView, TransactionInfoView};
+use starcoin_types::block::{Block, BlockNumber};
+use std::fmt;
+use std::io::{BufRead, Read, Write};
+use std::str::FromStr;
+
+pub const EXPORT_FORMAT_VERSION: u8 = 1;
+
+/// CIDv1 prefix of the `raw` codec and the 32 bytes `sha3-256` multihash.
+const CID_PREFIX: [u8; 4] = [0x01, 0x55, 0x16, 0x20];
+const CID_LEN: usize = CID_PREFIX.len() + HashValue::LENGTH;
+/// Max CAR section length, a block should never be so large.
+const MAX_CAR_SECTION_LEN: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Car,
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Guess the format by the file extension, `.car` is car, others are jsonl.
+    pub fn from_file_name(file_name: &str) -> Self {
+        if file_name.ends_with(".car") {
+            ExportFormat::Car
+        } else {
+            ExportFormat::Jsonl
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Car => write!(f, "car"),
+            ExportFormat::Jsonl => write!(f, "jsonl"),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "car" => Ok(ExportFormat::Car),
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            _ => bail!("Unknown export format: {}, expect car or jsonl", s),
+        }
+    }
+}
+
+/// A block number range `start..end`, both inclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockRange {
+    pub start: BlockNumber,
+    pub end: BlockNumber,
+}
+
+impl FromStr for BlockRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, "..");
+        let start = parts.next().unwrap_or_default().trim();
+        let end = parts
+            .next()
+            .ok_or_else(|| format_err!("Invalid block range: {}, expect start..end", s))?
+            .trim();
+        let range = Self {
+            start: start.parse()?,
+            end: end.parse()?,
+        };
+        ensure!(
+            range.start <= range.end,
+            "Invalid block range: {}, start is greater than end",
+            s
+        );
+        Ok(range)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format_version: u8,
+    pub chain_id: u8,
+    pub start: BlockNumber,
+    pub end: BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExportedBlock {
+    pub number: BlockNumber,
+    pub block_hash: HashValue,
+    pub parent_hash: HashValue,
+    /// The bcs bytes of the block.
+    pub block: StrView<Vec<u8>>,
+    pub txn_infos: Vec<TransactionInfoView>,
+}
+
+pub trait ChainExportWriter {
+    fn write_block(&mut self, block: &Block, txn_infos: Vec<TransactionInfoView>) -> Result<()>;
+    fn finish(&mut self) -> Result<()>;
+}
+
+pub struct JsonlExportWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonlExportWriter<W> {
+    pub fn new(mut writer: W, header: &ExportHeader) -> Result<Self> {
+        serde_json::to_writer(&mut writer, header)?;
+        writeln!(writer)?;
+        Ok(Self { writer })
+    }
+}
+
+impl<W: Write> ChainExportWriter for JsonlExportWriter<W> {
+    fn write_block(&mut self, block: &Block, txn_infos: Vec<TransactionInfoView>) -> Result<()> {
+        let exported = ExportedBlock {
+            number: block.header.number(),
+            block_hash: block.id(),
+            parent_hash: block.header.parent_hash(),
+            block: StrView(bcs_ext::to_bytes(block)?),
+            txn_infos,
+        };
+        serde_json::to_writer(&mut self.writer, &exported)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub struct CarExportWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CarExportWriter<W> {
+    /// The CAR header is written first, so the last block, the root of the export, is required.
+    pub fn new(mut writer: W, last_block: &Block) -> Result<Self> {
+        let header = car_header(&block_cid(&bcs_ext::to_bytes(last_block)?));
+        write_varint(&mut writer, header.len() as u64)?;
+        writer.write_all(&header)?;
+        Ok(Self { writer })
+    }
+}
+
+impl<W: Write> ChainExportWriter for CarExportWriter<W> {
+    fn write_block(&mut self, block: &Block, _txn_infos: Vec<TransactionInfoView>) -> Result<()> {
+        let data = bcs_ext::to_bytes(block)?;
+        let cid = block_cid(&data);
+        write_varint(&mut self.writer, (cid.len() + data.len()) as u64)?;
+        self.writer.write_all(&cid)?;
+        self.writer.write_all(&data)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub start: BlockNumber,
+    pub end: BlockNumber,
+    pub blocks: u64,
+    pub txns: u64,
+    pub last_block_hash: HashValue,
+}
+
+/// Check the blocks are continuous and linked by the parent hash.
+#[derive(Default)]
+struct ChainLinkVerifier {
+    first: Option<BlockNumber>,
+    last: Option<(BlockNumber, HashValue)>,
+    blocks: u64,
+    txns: u64,
+}
+
+impl ChainLinkVerifier {
+    fn verify_block(&mut self, block: &Block) -> Result<()> {
+        let number = block.header.number();
+        ensure!(
+            block.header.body_hash() == block.body.hash(),
+            "The body hash of block {} mismatch",
+            number
+        );
+        if let Some((last_number, last_hash)) = self.last {
+            ensure!(
+                number == last_number + 1,
+                "Block {} follows block {}, the blocks are not continuous",
+                number,
+                last_number
+            );
+            ensure!(
+                block.header.parent_hash() == last_hash,
+                "The parent hash {} of block {} mismatch with the previous block hash {}",
+                block.header.parent_hash(),
+                number,
+                last_hash
+            );
+        } else {
+            self.first = Some(number);
+        }
+        self.last = Some((number, block.id()));
+        self.blocks += 1;
+        self.txns += block.transactions().len() as u64;
+        Ok(())
+    }
+
+    fn into_summary(self) -> Result<ExportSummary> {
+        match (self.first, self.last) {
+            (Some(start), Some((end, last_block_hash))) => Ok(ExportSummary {
+                start,
+                end,
+                blocks: self.blocks,
+                txns: self.txns,
+                last_block_hash,
+            }),
+            _ => bail!("The export does not contain any block"),
+        }
+    }
+}
+
+pub fn verify_jsonl_export<R: BufRead>(reader: R) -> Result<ExportSummary> {
+    let mut lines = reader.lines();
+    let header: ExportHeader = serde_json::from_str(
+        lines
+            .next()
+            .ok_or_else(|| format_err!("The export is empty"))??
+            .as_str(),
+    )?;
+    ensure!(
+        header.format_version == EXPORT_FORMAT_VERSION,
+        "Unsupported export format version: {}",
+        header.format_version
+    );
+    let mut verifier = ChainLinkVerifier::default();
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exported: ExportedBlock = serde_json::from_str(line.as_str())?;
+        let block: Block = bcs_ext::from_bytes(exported.block.0.as_slice())?;
+        ensure!(
+            block.id() == exported.block_hash
+                && block.header.number() == exported.number
+                && block.header.parent_hash() == exported.parent_hash,
+            "The block {} mismatch with its bcs bytes",
+            exported.number
+        );
+        ensure!(
+            block.header.chain_id().id() == header.chain_id,
+            "The chain id of block {} mismatch with the export header",
+            exported.number
+        );
+        let mut txn_infos = exported.txn_infos;
+        txn_infos.sort_by_key(|txn_info| txn_info.transaction_index);
+        let user_txn_hashes = txn_infos
+            .iter()
+            .skip(1)
+            .map(|txn_info| txn_info.transaction_hash)
+            .collect::<Vec<_>>();
+        ensure!(
+            txn_infos
+                .iter()
+                .all(|txn_info| txn_info.block_hash == exported.block_hash)
+                && user_txn_hashes
+                    == block
+                        .transactions()
+                        .iter()
+                        .map(|txn| txn.id())
+                        .collect::<Vec<_>>(),
+            "The txn infos of block {} mismatch with the block txns",
+            exported.number
+        );
+        verifier.verify_block(&block)?;
+    }
+    let summary = verifier.into_summary()?;
+    ensure!(
+        summary.start == header.start && summary.end == header.end,
+        "The export contains blocks {}..{}, but the header declares {}..{}",
+        summary.start,
+        summary.end,
+        header.start,
+        header.end
+    );
+    Ok(summary)
+}
+
+pub fn verify_car_export<R: Read>(mut reader: R) -> Result<ExportSummary> {
+    let header =
+        read_car_section(&mut reader)?.ok_or_else(|| format_err!("The export is empty"))?;
+    let root = parse_car_header(&header)?;
+    let mut verifier = ChainLinkVerifier::default();
+    let mut last_cid = None;
+    while let Some(section) = read_car_section(&mut reader)? {
+        ensure!(section.len() > CID_LEN, "Invalid CAR section");
+        let (cid, data) = section.split_at(CID_LEN);
+        ensure!(
+            cid == block_cid(data).as_slice(),
+            "The CID {} mismatch with the section data",
+            hex::encode(cid)
+        );
+        let block: Block = bcs_ext::from_bytes(data)?;
+        verifier.verify_block(&block)?;
+        last_cid = Some(cid.to_vec());
+    }
+    ensure!(
+        last_cid.as_ref() == Some(&root),
+        "The root of the CAR header is not the last block"
+    );
+    verifier.into_summary()
+}
+
+/// Read the blocks of an export in order until the `visitor` returns false. Every block must match
+/// its hash or CID, but the chain is not verified, see `verify_jsonl_export` and `verify_car_export`.
+pub fn read_export_blocks<R: BufRead>(
+    mut reader: R,
+    format: ExportFormat,
+    mut visitor: impl FnMut(Block) -> Result<bool>,
+) -> Result<()> {
+    match format {
+        ExportFormat::Jsonl => {
+            let mut lines = reader.lines();
+            lines
+                .next()
+                .ok_or_else(|| format_err!("The export is empty"))??;
+            for line in lines {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let exported: ExportedBlock = serde_json::from_str(line.as_str())?;
+                let block: Block = bcs_ext::from_bytes(exported.block.0.as_slice())?;
+                ensure!(
+                    block.id() == exported.block_hash,
+                    "The block {} mismatch with its bcs bytes",
+                    exported.number
+                );
+                if !visitor(block)? {
+                    break;
+                }
+            }
+        }
+        ExportFormat::Car => {
+            read_car_section(&mut reader)?.ok_or_else(|| format_err!("The export is empty"))?;
+            while let Some(section) = read_car_section(&mut reader)? {
+                ensure!(section.len() > CID_LEN, "Invalid CAR section");
+                let (cid, data) = section.split_at(CID_LEN);
+                ensure!(
+                    cid == block_cid(data).as_slice(),
+                    "The CID {} mismatch with the section data",
+                    hex::encode(cid)
+                );
+                if !visitor(bcs_ext::from_bytes(data)?)? {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The CIDv1 of the `data` with the `raw` codec and `sha3-256` multihash.
+pub(crate) fn block_cid(data: &[u8]) -> Vec<u8> {
+    let mut cid = CID_PREFIX.to_vec();
+    cid.extend_from_slice(HashValue::sha3_256_of(data).to_vec().as_slice());
+    cid
+}
+
+/// The dag-cbor encoded CARv1 header `{"roots": [root], "version": 1}`.
+fn car_header(root: &[u8]) -> Vec<u8> {
+    let mut header = vec![0xa2, 0x65];
+    header.extend_from_slice(b"roots");
+    // an array of one CID, which is the tag 42 of the bytes with a leading zero.
+    header.extend_from_slice(&[0x81, 0xd8, 0x2a, 0x58, (root.len() + 1) as u8, 0x00]);
+    header.extend_from_slice(root);
+    header.push(0x67);
+    header.extend_from_slice(b"version");
+    header.push(0x01);
+    header
+}
+
+fn parse_car_header(header: &[u8]) -> Result<Vec<u8>> {
+    let prefix_len = 13;
+    ensure!(
+        header.len() == car_header(&[0u8; CID_LEN]).len(),
+        "Unsupported CAR header"
+    );
+    let root = header[prefix_len..prefix_len + CID_LEN].to_vec();
+    ensure!(car_header(&root) == header, "Unsupported CAR header");
+    Ok(root)
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.write_all(&[byte])?;
+            return Ok(());
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Read a varint, return None at the end of the reader.
+fn read_varint<R: Read>(reader: &mut R) -> Result<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            ensure!(i == 0, "Unexpected end of varint");
+            return Ok(None);
+        }
+        value |= ((byte[0] & 0x7f) as u64) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    bail!("Invalid varint")
+}
+
+fn read_car_section<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    let len = match read_varint(reader)? {
+        Some(len) => len,
+        None => return Ok(None),
+    };
+    ensure!(
+        len <= MAX_CAR_SECTION_LEN,
+        "CAR section is too large: {}",
+        len
+    );
+    let mut section = vec![0u8; len as usize];
+    reader.read_exact(&mut section)?;
+    Ok(Some(section))
+}
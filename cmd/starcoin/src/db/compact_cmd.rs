@@ -0,0 +1,39 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use structopt::StructOpt;
+
+/// Compact the db of the connected node, the command returns after the compaction finished.
+///  Some examples:
+///  ``` shell
+///  db compact
+///  db compact --cf state_node
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "compact")]
+pub struct CompactOpt {
+    #[structopt(long = "cf")]
+    /// the column family to compact, compact all the column families if not set.
+    cf: Option<String>,
+}
+
+pub struct CompactCommand;
+
+impl CommandAction for CompactCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = CompactOpt;
+    type ReturnItem = Vec<String>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        client.node_admin_compact_db(ctx.opt().cf.clone())
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::db::chain_export::{
+    BlockRange, CarExportWriter, ChainExportWriter, ExportFormat, ExportHeader, JsonlExportWriter,
+    EXPORT_FORMAT_VERSION,
+};
+use crate::StarcoinOpt;
+use anyhow::{ensure, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_rpc_client::RpcClient;
+use starcoin_types::block::{Block, BlockNumber};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Export the blocks and txns of a block range from the connected node in a hash-linked interchange format,
+/// see `verify-export` to verify an export without a node, and `import-chain` to import an export to a node.
+///  Some examples:
+///  ``` shell
+///  db export-chain --format jsonl --range 0..1000 -o ./chain.jsonl
+///  db export-chain --format car --range 0..1000 -o ./chain.car
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "export-chain")]
+pub struct ExportChainOpt {
+    #[structopt(long = "format", default_value = "jsonl")]
+    /// the export format, jsonl or car.
+    format: ExportFormat,
+
+    #[structopt(long = "range")]
+    /// the block number range to export, such as 0..1000, both inclusive.
+    range: BlockRange,
+
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    /// the output file.
+    output: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportChainView {
+    pub format: ExportFormat,
+    pub start: BlockNumber,
+    pub end: BlockNumber,
+    pub blocks: u64,
+    pub txns: u64,
+    pub last_block_hash: HashValue,
+    pub output: PathBuf,
+}
+
+fn get_block(client: &RpcClient, number: BlockNumber) -> Result<Block> {
+    let block_view = client
+        .chain_get_block_by_number(number)?
+        .ok_or_else(|| format_err!("Can not find block by number: {}", number))?;
+    Block::try_from(block_view)
+}
+
+pub struct ExportChainCommand;
+
+impl CommandAction for ExportChainCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ExportChainOpt;
+    type ReturnItem = ExportChainView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let head_number = client.chain_info()?.head.number.0;
+        ensure!(
+            opt.range.end <= head_number,
+            "The end of range {} is greater than the head block number {}",
+            opt.range.end,
+            head_number
+        );
+        let last_block = get_block(client, opt.range.end)?;
+        let file = BufWriter::new(File::create(opt.output.as_path())?);
+        let mut writer: Box<dyn ChainExportWriter> = match opt.format {
+            ExportFormat::Car => Box::new(CarExportWriter::new(file, &last_block)?),
+            ExportFormat::Jsonl => Box::new(JsonlExportWriter::new(
+                file,
+                &ExportHeader {
+                    format_version: EXPORT_FORMAT_VERSION,
+                    chain_id: last_block.header.chain_id().id(),
+                    start: opt.range.start,
+                    end: opt.range.end,
+                },
+            )?),
+        };
+        let mut txns = 0u64;
+        for number in opt.range.start..=opt.range.end {
+            let block = if number == opt.range.end {
+                last_block.clone()
+            } else {
+                get_block(client, number)?
+            };
+            let txn_infos = client.chain_get_block_txn_infos(block.id())?;
+            txns += block.transactions().len() as u64;
+            writer.write_block(&block, txn_infos)?;
+        }
+        writer.finish()?;
+        Ok(ExportChainView {
+            format: opt.format,
+            start: opt.range.start,
+            end: opt.range.end,
+            blocks: opt.range.end - opt.range.start + 1,
+            txns,
+            last_block_hash: last_block.id(),
+            output: opt.output.clone(),
+        })
+    }
+}
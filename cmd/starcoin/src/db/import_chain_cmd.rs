@@ -0,0 +1,254 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::chain_export::{
+    read_export_blocks, verify_car_export, verify_jsonl_export, ExportFormat, ExportSummary,
+};
+use crate::db::{check_cold_db, with_cold_db};
+use anyhow::{bail, ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_chain::{BlockChain, ChainReader, ChainWriter};
+use starcoin_config::{BuiltinNetworkID, ChainNetwork, EncryptionKeySource, RocksdbConfig};
+use starcoin_consensus::Consensus;
+use starcoin_crypto::HashValue;
+use starcoin_genesis::Genesis;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage, VEC_PREFIX_NAME};
+use starcoin_types::block::{Block, BlockNumber};
+use starcoin_types::startup_info::StartupInfo;
+use starcoin_vm_types::on_chain_config::GlobalTimeOnChain;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Import a chain export of `db export-chain` to the data dir of a stopped node, such as to bootstrap
+/// a node offline. The whole export is verified first, then the blocks after the local head are
+/// re-executed and fully verified one by one, the blocks already in the local main chain must match.
+/// With `--verify-only`, the blocks after the local head are saved without re-executing, only the
+/// proof of work of their headers is verified, and the head is not moved. The node executes them
+/// from the local store instead of downloading them when it syncs.
+/// With `--dry-run`, the export is verified and checked against the local chain, nothing is written.
+/// If the data dir does not contain a db, the db is initialized with the genesis of the `--net`.
+///  Some examples:
+///  ``` shell
+///  starcoin db import-chain --net main --data-dir ~/.starcoin/main -i ./chain.car
+///  starcoin db import-chain --net main --data-dir ~/.starcoin/main -i ./chain.car --verify-only
+///  starcoin db import-chain --net main --data-dir ~/.starcoin/main -i ./chain.car --dry-run
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "import-chain")]
+pub struct ImportChainOpt {
+    #[structopt(long = "net")]
+    /// the builtin network of the export, such as main or barnard.
+    pub net: BuiltinNetworkID,
+
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/main.
+    pub data_dir: PathBuf,
+
+    #[structopt(short = "i", long = "input", parse(from_os_str))]
+    /// the export file.
+    pub input: PathBuf,
+
+    #[structopt(long = "format")]
+    /// the export format, jsonl or car, if absent, guess by the file extension.
+    pub format: Option<ExportFormat>,
+
+    #[structopt(long = "verify-only")]
+    /// import the blocks without re-executing them, the head is not moved.
+    pub verify_only: bool,
+
+    #[structopt(long = "dry-run", conflicts_with = "verify-only")]
+    /// only verify the export and check it links to the local chain, nothing is written.
+    pub dry_run: bool,
+
+    #[structopt(long = "db-encryption-key")]
+    /// the db encryption key of the node, file:<path>, env:<name> or cmd:<command>, if the db is encrypted.
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
+    #[structopt(long = "cold-db-dir", parse(from_os_str))]
+    /// the cold db dir of the node, required if the old blocks are moved to the cold db, a relative dir is relative to the data dir.
+    pub cold_db_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportChainReport {
+    pub export: ExportSummary,
+    /// The blocks of the export which are already in the local main chain.
+    pub skipped_blocks: u64,
+    /// The blocks imported, re-executed unless `--verify-only`, always 0 with `--dry-run`.
+    pub imported_blocks: u64,
+    pub head_number: Option<BlockNumber>,
+    pub head_hash: Option<HashValue>,
+}
+
+fn verify_export(input: &Path, format: ExportFormat) -> Result<ExportSummary> {
+    let reader = BufReader::new(File::open(input)?);
+    match format {
+        ExportFormat::Car => verify_car_export(reader),
+        ExportFormat::Jsonl => verify_jsonl_export(reader),
+    }
+}
+
+/// Check the block matches the local main chain block at the same number, return false if the
+/// block is after the local head.
+fn check_local_block(chain: &BlockChain, block: &Block) -> Result<bool> {
+    let head = chain.current_header();
+    let number = block.header().number();
+    if number > head.number() {
+        ensure!(
+            number == head.number() + 1 && block.header().parent_hash() == head.id(),
+            "The block {} of the export does not link to the local head {}({})",
+            number,
+            head.number(),
+            head.id()
+        );
+        return Ok(false);
+    }
+    let local_id = chain.get_hash_by_number_ensure(number)?;
+    ensure!(
+        local_id == block.id(),
+        "The block {}({}) of the export forks from the local main chain block {}",
+        number,
+        block.id(),
+        local_id
+    );
+    Ok(true)
+}
+
+/// Verify the proof of work of the block without executing it, the body hashes and links of the
+/// blocks are verified with the export.
+fn verify_block_pow(chain: &BlockChain, block: &Block) -> Result<()> {
+    let header = block.header();
+    chain
+        .consensus()
+        .verify_header_difficulty(header.difficulty(), header)
+        .map_err(|e| {
+            format_err!(
+                "Verify the pow of block {}({}) failed: {:?}",
+                header.number(),
+                block.id(),
+                e
+            )
+        })
+}
+
+/// The db is opened directly, so the command is executed without connecting to (or starting) a node.
+/// The db is opened read only with `--dry-run`.
+pub fn run_import_chain(opt: ImportChainOpt) -> Result<ImportChainReport> {
+    let format = opt
+        .format
+        .unwrap_or_else(|| ExportFormat::from_file_name(&opt.input.to_string_lossy()));
+    let net = ChainNetwork::new_builtin(opt.net);
+    // Verify the checksums and links of the whole export before anything is written.
+    let export = verify_export(opt.input.as_path(), format)?;
+    eprintln!(
+        "verified the export of blocks [{}, {}], {} txns",
+        export.start, export.end, export.txns
+    );
+
+    let encryption_key = opt
+        .db_encryption_key
+        .as_ref()
+        .map(|source| source.load(opt.data_dir.as_path()))
+        .transpose()?;
+    let db_dir = opt.data_dir.join("starcoindb").join("db");
+    let storage = if opt.dry_run {
+        if !db_dir.exists() {
+            return Ok(ImportChainReport {
+                export,
+                skipped_blocks: 0,
+                imported_blocks: 0,
+                head_number: None,
+                head_hash: None,
+            });
+        }
+        let db = DBStorage::open_with_encryption(
+            db_dir.join("starcoindb"),
+            VEC_PREFIX_NAME.to_vec(),
+            true,
+            RocksdbConfig::default(),
+            encryption_key,
+        )?;
+        let db = with_cold_db(
+            db,
+            opt.data_dir.as_path(),
+            opt.cold_db_dir.as_deref(),
+            true,
+            encryption_key,
+        )?;
+        Arc::new(Storage::new(StorageInstance::new_db_instance(db))?)
+    } else {
+        let db = with_cold_db(
+            DBStorage::new_with_encryption(db_dir, RocksdbConfig::default(), encryption_key)?,
+            opt.data_dir.as_path(),
+            opt.cold_db_dir.as_deref(),
+            false,
+            encryption_key,
+        )?;
+        let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+            CacheStorage::new(),
+            db,
+        ))?);
+        Genesis::init_and_check_storage(&net, storage.clone(), opt.data_dir.as_path())?;
+        storage
+    };
+    check_cold_db(storage.clone())?;
+    let head_id = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("The startup info of the db is none."))?
+        .main;
+    let mut chain = BlockChain::new(net.time_service(), head_id, storage.clone())?;
+
+    let mut skipped_blocks = 0;
+    let mut imported_blocks = 0;
+    read_export_blocks(
+        BufReader::new(File::open(opt.input.as_path())?),
+        format,
+        |block| {
+            // The rest of the export is linked to the first imported block, which is verified above.
+            if imported_blocks == 0 && check_local_block(&chain, &block)? {
+                skipped_blocks += 1;
+                return Ok(true);
+            }
+            if opt.dry_run {
+                return Ok(false);
+            }
+            let number = block.header().number();
+            let block_id = block.id();
+            let timestamp = block.header().timestamp();
+            if opt.verify_only {
+                verify_block_pow(&chain, &block)?;
+                storage.commit_block(block)?;
+                // Let the sync execute the saved blocks from the local store.
+                storage.save_imported_block_number(number)?;
+            } else {
+                if let Err(e) = chain.apply(block) {
+                    bail!("Import block {}({}) failed: {:?}", number, block_id, e);
+                }
+                // The mock time service of the dev networks follows the blocks, same as the sync.
+                chain
+                    .time_service()
+                    .adjust(GlobalTimeOnChain::new(timestamp));
+                storage.save_startup_info(StartupInfo::new(block_id))?;
+            }
+            imported_blocks += 1;
+            if imported_blocks % 1000 == 0 {
+                eprintln!("imported {} blocks, head is {}", imported_blocks, number);
+            }
+            Ok(true)
+        },
+    )?;
+    let head = chain.current_header();
+    Ok(ImportChainReport {
+        export,
+        skipped_blocks,
+        imported_blocks,
+        head_number: Some(head.number()),
+        head_hash: Some(head.id()),
+    })
+}
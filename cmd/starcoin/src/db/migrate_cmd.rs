@@ -0,0 +1,69 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::with_cold_db;
+use anyhow::{ensure, Result};
+use starcoin_config::{EncryptionKeySource, RocksdbConfig};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::migration::{migrate, MigrationProgress, MigrationReport};
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Migrate the storage of a node to the schema version of the current binary,
+/// the node must be stopped before migrating, and it is recommended to backup the data dir first.
+/// The node also migrates its storage when it starts.
+///  Some examples:
+///  ``` shell
+///  starcoin db migrate --data-dir ~/.starcoin/main --dry-run
+///  starcoin db migrate --data-dir ~/.starcoin/main
+///  ```
+#[derive(Clone, Debug, StructOpt)]
+#[structopt(name = "migrate")]
+pub struct MigrateOpt {
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/main.
+    pub data_dir: PathBuf,
+
+    #[structopt(long = "dry-run")]
+    /// only scan the db and report the changes, nothing is written.
+    pub dry_run: bool,
+
+    #[structopt(long = "db-encryption-key")]
+    /// the db encryption key of the node, file:<path>, env:<name> or cmd:<command>, if the db is encrypted.
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
+    #[structopt(long = "cold-db-dir", parse(from_os_str))]
+    /// the cold db dir of the node, required if the old blocks are moved to the cold db, a relative dir is relative to the data dir.
+    pub cold_db_dir: Option<PathBuf>,
+}
+
+fn print_progress(progress: &MigrationProgress) {
+    eprintln!(
+        "[{}] {}: scanned {}, updated {}, deleted {}",
+        progress.version,
+        progress.cf,
+        progress.stats.scanned,
+        progress.stats.updated,
+        progress.stats.deleted
+    );
+}
+
+/// The db is opened directly, so the command is executed without connecting to (or starting) a node.
+pub fn run_migrate(opt: MigrateOpt) -> Result<MigrationReport> {
+    let db_dir = opt.data_dir.join("starcoindb").join("db");
+    ensure!(db_dir.exists(), "The db dir {:?} not exists", db_dir);
+    let encryption_key = opt
+        .db_encryption_key
+        .as_ref()
+        .map(|source| source.load(opt.data_dir.as_path()))
+        .transpose()?;
+    let db = Arc::new(with_cold_db(
+        DBStorage::new_with_encryption(db_dir, RocksdbConfig::default(), encryption_key)?,
+        opt.data_dir.as_path(),
+        opt.cold_db_dir.as_deref(),
+        false,
+        encryption_key,
+    )?);
+    migrate(db, opt.dry_run, &print_progress)
+}
@@ -0,0 +1,70 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod backup_cmd;
+pub mod chain_export;
+mod compact_cmd;
+mod export_chain_cmd;
+pub mod import_chain_cmd;
+pub mod migrate_cmd;
+pub mod repair_cmd;
+pub mod restore_cmd;
+#[cfg(test)]
+mod tests;
+pub mod verify_cmd;
+mod verify_export_cmd;
+
+use anyhow::{ensure, Result};
+use starcoin_config::RocksdbConfig;
+use starcoin_storage::cold::ColdStorageMigrator;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::{Storage, VEC_PREFIX_NAME};
+use std::path::Path;
+use std::sync::Arc;
+
+pub use backup_cmd::*;
+pub use compact_cmd::*;
+pub use export_chain_cmd::*;
+pub use verify_export_cmd::*;
+
+/// Attach the cold db of `--cold-db-dir` to the db, a relative dir is relative to the data dir,
+/// same as the `cold-db-dir` of the node config.
+pub(crate) fn with_cold_db(
+    db: DBStorage,
+    data_dir: &Path,
+    cold_db_dir: Option<&Path>,
+    readonly: bool,
+    encryption_key: Option<[u8; 32]>,
+) -> Result<DBStorage> {
+    let cold_db_dir = match cold_db_dir {
+        Some(dir) if dir.is_absolute() => dir.to_path_buf(),
+        Some(dir) => data_dir.join(dir),
+        None => return Ok(db),
+    };
+    ensure!(
+        !readonly || cold_db_dir.exists(),
+        "The cold db dir {:?} not exists",
+        cold_db_dir
+    );
+    let cold = DBStorage::open_with_encryption(
+        cold_db_dir.join("starcoindb"),
+        VEC_PREFIX_NAME.to_vec(),
+        readonly,
+        RocksdbConfig::default(),
+        encryption_key,
+    )?;
+    Ok(db.with_cold_db(cold))
+}
+
+/// The old blocks can not be read without the cold db after they are moved to it.
+pub(crate) fn check_cold_db(storage: Arc<Storage>) -> Result<()> {
+    let has_cold_db = storage.db().map(|db| db.has_cold_db()).unwrap_or(false);
+    ensure!(
+        has_cold_db
+            || ColdStorageMigrator::new(storage)
+                .cold_block_number()?
+                .is_none(),
+        "The old blocks have been moved to the cold db, the --cold-db-dir must be set."
+    );
+    Ok(())
+}
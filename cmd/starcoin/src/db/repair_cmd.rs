@@ -0,0 +1,79 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::{check_cold_db, with_cold_db};
+use anyhow::{ensure, Result};
+use starcoin_config::{EncryptionKeySource, RocksdbConfig};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::repair::{RepairReport, StorageRepairer};
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{Storage, VEC_PREFIX_NAME};
+use starcoin_types::block::BlockNumber;
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Repair the db of a stopped node, such as after `db verify` reports issues.
+/// The derived entries of the main chain blocks (block headers and bodies, txn indices, event blooms,
+/// accumulator nodes and block infos) are rebuilt from the blocks, transaction infos and events.
+/// If these primary data of a block are damaged, the head is truncated back to the block before it,
+/// so the node syncs the blocks from there again.
+///  Some examples:
+///  ``` shell
+///  starcoin db repair --data-dir ~/.starcoin/main
+///  starcoin db repair --data-dir ~/.starcoin/main --from-block 100000
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "repair")]
+pub struct RepairOpt {
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/main.
+    pub data_dir: PathBuf,
+
+    #[structopt(long = "from-block")]
+    /// repair the main chain blocks from this block to the head, default is the first block which is not pruned.
+    pub from_block: Option<BlockNumber>,
+
+    #[structopt(long = "db-encryption-key")]
+    /// the db encryption key of the node, file:<path>, env:<name> or cmd:<command>, if the db is encrypted.
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
+    #[structopt(long = "cold-db-dir", parse(from_os_str))]
+    /// the cold db dir of the node, required if the old blocks are moved to the cold db, a relative dir is relative to the data dir.
+    pub cold_db_dir: Option<PathBuf>,
+}
+
+fn print_progress(report: &RepairReport) {
+    eprintln!(
+        "repaired {} blocks, {} txns",
+        report.repaired_blocks, report.repaired_txns
+    );
+}
+
+/// The db is opened directly, so the command is executed without connecting to (or starting) a node.
+pub fn run_repair(opt: RepairOpt) -> Result<RepairReport> {
+    let db_dir = opt.data_dir.join("starcoindb").join("db");
+    ensure!(db_dir.exists(), "The db dir {:?} not exists", db_dir);
+    let encryption_key = opt
+        .db_encryption_key
+        .as_ref()
+        .map(|source| source.load(opt.data_dir.as_path()))
+        .transpose()?;
+    let db = DBStorage::open_with_encryption(
+        db_dir.join("starcoindb"),
+        VEC_PREFIX_NAME.to_vec(),
+        false,
+        RocksdbConfig::default(),
+        encryption_key,
+    )?;
+    let db = with_cold_db(
+        db,
+        opt.data_dir.as_path(),
+        opt.cold_db_dir.as_deref(),
+        false,
+        encryption_key,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(db))?);
+    check_cold_db(storage.clone())?;
+    StorageRepairer::new(storage).repair(opt.from_block, &print_progress)
+}
@@ -0,0 +1,170 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::chain_export::{read_export_blocks, ExportFormat};
+use crate::db::{check_cold_db, with_cold_db};
+use anyhow::{ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_chain::{BlockChain, ChainReader, ChainWriter};
+use starcoin_config::{EncryptionKeySource, RocksdbConfig};
+use starcoin_crypto::HashValue;
+use starcoin_storage::backup::{list_backups, restore};
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_types::block::BlockNumber;
+use starcoin_types::startup_info::StartupInfo;
+use starcoin_vm_types::time::{RealTimeService, TimeService};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Restore a backup of `db backup` to the data dir of a stopped node, and optionally move the head
+/// to a chosen block. If the block is after the backup, the blocks are applied from a chain export
+/// of `db export-chain`. The data dir must not contain a db.
+///  Some examples:
+///  ``` shell
+///  starcoin db restore --backup-dir /data/starcoin-backup --data-dir ~/.starcoin/main
+///  starcoin db restore --backup-dir /data/starcoin-backup --data-dir ~/.starcoin/main --to-block 100000 --blocks ./chain.car
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "restore")]
+pub struct RestoreOpt {
+    #[structopt(long = "backup-dir", parse(from_os_str))]
+    /// the backup dir of `db backup`.
+    pub backup_dir: PathBuf,
+
+    #[structopt(long = "backup-id")]
+    /// the backup to restore, default is the latest backup before the `to-block`, or the latest backup.
+    pub backup_id: Option<u64>,
+
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/main.
+    pub data_dir: PathBuf,
+
+    #[structopt(long = "to-block")]
+    /// the block number of the restored head, default is the head of the backup.
+    pub to_block: Option<BlockNumber>,
+
+    #[structopt(long = "blocks", parse(from_os_str))]
+    /// the chain export to roll forward from the backup to the `to-block`.
+    pub blocks: Option<PathBuf>,
+
+    #[structopt(long = "format")]
+    /// the format of the chain export, jsonl or car, if absent, guess by the file extension.
+    pub format: Option<ExportFormat>,
+
+    #[structopt(long = "db-encryption-key")]
+    /// the db encryption key of the backup, file:<path>, env:<name> or cmd:<command>, if the db is encrypted.
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
+    #[structopt(long = "cold-db-dir", parse(from_os_str))]
+    /// the cold db dir of the node, required if the old blocks are moved to the cold db, a relative dir is relative to the data dir.
+    pub cold_db_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub backup_id: u64,
+    pub backup_block_number: BlockNumber,
+    pub head_number: BlockNumber,
+    pub head_hash: HashValue,
+    /// The blocks applied from the chain export.
+    pub applied_blocks: u64,
+}
+
+/// The db is opened directly, so the command is executed without connecting to (or starting) a node.
+pub fn run_restore(opt: RestoreOpt) -> Result<RestoreReport> {
+    let backups = list_backups(opt.backup_dir.as_path())?;
+    let backup = match (opt.backup_id, opt.to_block) {
+        (Some(id), _) => backups.iter().find(|backup| backup.id == id),
+        (None, Some(to_block)) => backups
+            .iter()
+            .rev()
+            .find(|backup| backup.block_number <= to_block),
+        (None, None) => backups.last(),
+    }
+    .ok_or_else(|| format_err!("Can not find a backup to restore in {:?}", opt.backup_dir))?;
+    let encryption_key = opt
+        .db_encryption_key
+        .as_ref()
+        .map(|source| source.load(opt.data_dir.as_path()))
+        .transpose()?;
+    let db_dir = opt.data_dir.join("starcoindb").join("db");
+    restore(
+        opt.backup_dir.as_path(),
+        backup.id,
+        db_dir.join("starcoindb").as_path(),
+    )?;
+
+    let db = with_cold_db(
+        DBStorage::new_with_encryption(db_dir, RocksdbConfig::default(), encryption_key)?,
+        opt.data_dir.as_path(),
+        opt.cold_db_dir.as_deref(),
+        false,
+        encryption_key,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(),
+        db,
+    ))?);
+    check_cold_db(storage.clone())?;
+    let head_id = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("The startup info of the backup is none."))?
+        .main;
+    let time_service: Arc<dyn TimeService> = Arc::new(RealTimeService::new());
+    let mut chain = BlockChain::new(time_service.clone(), head_id, storage.clone())?;
+    let mut applied_blocks = 0;
+    if let Some(to_block) = opt.to_block {
+        let head_number = chain.current_header().number();
+        if to_block < head_number {
+            // The backup may contain a few blocks after the block it's taken at.
+            let header = chain
+                .get_header_by_number(to_block)?
+                .ok_or_else(|| format_err!("Can not find block {} in the backup", to_block))?;
+            chain = BlockChain::new(time_service, header.id(), storage.clone())?;
+        } else if to_block > head_number {
+            let blocks = opt.blocks.as_ref().ok_or_else(|| {
+                format_err!(
+                    "The head of the backup is block {}, --blocks is required to roll forward to block {}",
+                    head_number,
+                    to_block
+                )
+            })?;
+            let format = opt
+                .format
+                .unwrap_or_else(|| ExportFormat::from_file_name(&blocks.to_string_lossy()));
+            read_export_blocks(
+                BufReader::new(File::open(blocks.as_path())?),
+                format,
+                |block| {
+                    let number = block.header().number();
+                    if number > chain.current_header().number() {
+                        chain.apply(block)?;
+                        applied_blocks += 1;
+                    }
+                    Ok(number < to_block)
+                },
+            )?;
+            ensure!(
+                chain.current_header().number() == to_block,
+                "The chain export ends at block {}, before block {}",
+                chain.current_header().number(),
+                to_block
+            );
+        }
+        storage.save_startup_info(StartupInfo::new(chain.current_header().id()))?;
+    }
+    let head = chain.current_header();
+    Ok(RestoreReport {
+        backup_id: backup.id,
+        backup_block_number: backup.block_number,
+        head_number: head.number(),
+        head_hash: head.id(),
+        applied_blocks,
+    })
+}
@@ -0,0 +1,200 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::chain_export::{
+    verify_car_export, verify_jsonl_export, BlockRange, CarExportWriter, ChainExportWriter,
+    ExportHeader, JsonlExportWriter, EXPORT_FORMAT_VERSION,
+};
+use crate::db::import_chain_cmd::{run_import_chain, ImportChainOpt};
+use starcoin_chain::ChainReader;
+use starcoin_chain_mock::MockChain;
+use starcoin_config::{temp_path, BuiltinNetworkID, ChainNetwork, RocksdbConfig};
+use starcoin_crypto::HashValue;
+use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockHeaderExtra};
+use starcoin_types::U256;
+use starcoin_vm_types::genesis_config::ChainId;
+use std::fs::File;
+use std::path::Path;
+
+fn mock_chain(len: u64) -> Vec<Block> {
+    let mut parent_hash = HashValue::random();
+    (0..len)
+        .map(|number| {
+            let body = BlockBody::new_empty();
+            let header = BlockHeader::new(
+                parent_hash,
+                number * 1000,
+                number,
+                AccountAddress::random(),
+                None,
+                HashValue::random(),
+                HashValue::random(),
+                HashValue::random(),
+                0,
+                U256::zero(),
+                body.hash(),
+                ChainId::test(),
+                0,
+                BlockHeaderExtra::new([0u8; 4]),
+            );
+            parent_hash = header.id();
+            Block::new(header, body)
+        })
+        .collect()
+}
+
+fn export(blocks: &[Block], writer: &mut dyn ChainExportWriter) {
+    for block in blocks {
+        writer.write_block(block, vec![]).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[stest::test]
+fn test_block_range() {
+    let range: BlockRange = "10..20".parse().unwrap();
+    assert_eq!(range, BlockRange { start: 10, end: 20 });
+    assert!("20..10".parse::<BlockRange>().is_err());
+    assert!("10".parse::<BlockRange>().is_err());
+}
+
+#[stest::test]
+fn test_car_export() {
+    let blocks = mock_chain(3);
+    let mut output = vec![];
+    export(
+        &blocks,
+        &mut CarExportWriter::new(&mut output, blocks.last().unwrap()).unwrap(),
+    );
+    let summary = verify_car_export(output.as_slice()).unwrap();
+    assert_eq!(summary.start, 0);
+    assert_eq!(summary.end, 2);
+    assert_eq!(summary.blocks, 3);
+    assert_eq!(summary.last_block_hash, blocks[2].id());
+
+    // tamper the last byte of the last block.
+    let last = output.len() - 1;
+    output[last] ^= 0xff;
+    assert!(verify_car_export(output.as_slice()).is_err());
+
+    // a block is missing.
+    let mut output = vec![];
+    export(
+        &[blocks[0].clone(), blocks[2].clone()],
+        &mut CarExportWriter::new(&mut output, &blocks[2]).unwrap(),
+    );
+    assert!(verify_car_export(output.as_slice()).is_err());
+}
+
+#[stest::test]
+fn test_jsonl_export() {
+    let blocks = mock_chain(3);
+    let header = ExportHeader {
+        format_version: EXPORT_FORMAT_VERSION,
+        chain_id: ChainId::test().id(),
+        start: 0,
+        end: 2,
+    };
+    let mut output = vec![];
+    export(
+        &blocks,
+        &mut JsonlExportWriter::new(&mut output, &header).unwrap(),
+    );
+    let summary = verify_jsonl_export(output.as_slice()).unwrap();
+    assert_eq!(summary.blocks, 3);
+    assert_eq!(summary.last_block_hash, blocks[2].id());
+
+    // the blocks are not linked by parent hash.
+    let mut output = vec![];
+    let mut other = mock_chain(3);
+    other[0] = blocks[0].clone();
+    export(
+        &other,
+        &mut JsonlExportWriter::new(&mut output, &header).unwrap(),
+    );
+    assert!(verify_jsonl_export(output.as_slice()).is_err());
+}
+
+fn import_opt(data_dir: &Path, input: &Path) -> ImportChainOpt {
+    ImportChainOpt {
+        net: BuiltinNetworkID::Test,
+        data_dir: data_dir.to_path_buf(),
+        input: input.to_path_buf(),
+        format: None,
+        verify_only: false,
+        dry_run: false,
+        db_encryption_key: None,
+        cold_db_dir: None,
+    }
+}
+
+#[stest::test(timeout = 120)]
+fn test_export_and_import_chain() {
+    let mut chain = MockChain::new(ChainNetwork::new_builtin(BuiltinNetworkID::Test)).unwrap();
+    chain.produce_and_apply_times(5).unwrap();
+    let head = chain.head().current_header();
+    let blocks: Vec<Block> = (0..=head.number())
+        .map(|number| chain.head().get_block_by_number(number).unwrap().unwrap())
+        .collect();
+    let export_dir = temp_path();
+    let input = export_dir.path().join("chain.jsonl");
+    let header = ExportHeader {
+        format_version: EXPORT_FORMAT_VERSION,
+        chain_id: ChainId::test().id(),
+        start: 0,
+        end: head.number(),
+    };
+    export(
+        &blocks,
+        &mut JsonlExportWriter::new(File::create(input.as_path()).unwrap(), &header).unwrap(),
+    );
+
+    // nothing is written by the dry run.
+    let data_dir = temp_path();
+    let report = run_import_chain(ImportChainOpt {
+        dry_run: true,
+        ..import_opt(data_dir.path(), input.as_path())
+    })
+    .unwrap();
+    assert_eq!(report.export.blocks, head.number() + 1);
+    assert_eq!(report.head_hash, None);
+
+    let report = run_import_chain(import_opt(data_dir.path(), input.as_path())).unwrap();
+    assert_eq!(report.skipped_blocks, 1);
+    assert_eq!(report.imported_blocks, head.number());
+    assert_eq!(report.head_hash, Some(head.id()));
+    // the blocks are in the local main chain now.
+    let report = run_import_chain(import_opt(data_dir.path(), input.as_path())).unwrap();
+    assert_eq!(report.skipped_blocks, head.number() + 1);
+    assert_eq!(report.imported_blocks, 0);
+    assert_eq!(report.head_hash, Some(head.id()));
+
+    // the blocks are saved without executing, the head is not moved.
+    let data_dir = temp_path();
+    let report = run_import_chain(ImportChainOpt {
+        verify_only: true,
+        ..import_opt(data_dir.path(), input.as_path())
+    })
+    .unwrap();
+    assert_eq!(report.imported_blocks, head.number());
+    assert_eq!(report.head_hash, Some(blocks[0].id()));
+    let storage = Storage::new(StorageInstance::new_db_instance(
+        DBStorage::new(
+            data_dir.path().join("starcoindb").join("db"),
+            RocksdbConfig::default(),
+        )
+        .unwrap(),
+    ))
+    .unwrap();
+    assert!(storage.get_block_by_hash(head.id()).unwrap().is_some());
+    assert!(storage.get_block_info(head.id()).unwrap().is_none());
+    assert_eq!(
+        storage.get_imported_block_number().unwrap(),
+        Some(head.number())
+    );
+}
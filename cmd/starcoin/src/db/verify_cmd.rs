@@ -0,0 +1,134 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db::{check_cold_db, with_cold_db};
+use anyhow::{bail, ensure, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_config::{EncryptionKeySource, RocksdbConfig};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::verifier::{QuarantineReport, StorageVerifier, VerifyReport};
+use starcoin_storage::{Storage, VEC_PREFIX_NAME};
+use starcoin_types::block::BlockNumber;
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Verify the db of a stopped node, such as after an unclean shutdown or disk errors.
+/// The blocks, accumulators and state trees of the main chain are checked, and the missing or
+/// corrupt entries are reported. With `--quarantine`, the entries of the bad blocks are moved out
+/// of the db, and the head is reset to the block before the first bad block.
+///  Some examples:
+///  ``` shell
+///  starcoin db verify --data-dir ~/.starcoin/main
+///  starcoin db verify --data-dir ~/.starcoin/main --from-block 100000 --skip-state
+///  starcoin db verify --data-dir ~/.starcoin/main --quarantine
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "verify")]
+pub struct VerifyOpt {
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/main.
+    pub data_dir: PathBuf,
+
+    #[structopt(long = "from-block", default_value = "0")]
+    /// verify the main chain blocks from this block to the head.
+    pub from_block: BlockNumber,
+
+    #[structopt(long = "skip-state")]
+    /// only check the state roots exist, instead of walking the whole state trees.
+    pub skip_state: bool,
+
+    #[structopt(long = "quarantine")]
+    /// move the entries of the bad blocks to the quarantine dir, and reset the head to the block before the first bad block.
+    pub quarantine: bool,
+
+    #[structopt(long = "quarantine-dir", parse(from_os_str))]
+    /// the dir of the quarantined blocks, default is `<data-dir>/quarantine`.
+    pub quarantine_dir: Option<PathBuf>,
+
+    #[structopt(long = "db-encryption-key")]
+    /// the db encryption key of the node, file:<path>, env:<name> or cmd:<command>, if the db is encrypted.
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
+    #[structopt(long = "cold-db-dir", parse(from_os_str))]
+    /// the cold db dir of the node, required if the old blocks are moved to the cold db, a relative dir is relative to the data dir.
+    pub cold_db_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyDBReport {
+    #[serde(flatten)]
+    pub report: VerifyReport,
+    pub quarantined: Option<QuarantineReport>,
+}
+
+fn print_progress(report: &VerifyReport) {
+    eprintln!(
+        "verified {} blocks, {} txns, {} state nodes, {} issues",
+        report.verified_blocks,
+        report.verified_txns,
+        report.verified_state_nodes,
+        report.issues.len()
+    );
+}
+
+/// The db is opened directly, so the command is executed without connecting to (or starting) a node.
+/// The db is opened read only unless quarantining.
+pub fn run_verify(opt: VerifyOpt) -> Result<VerifyDBReport> {
+    let db_dir = opt.data_dir.join("starcoindb").join("db");
+    ensure!(db_dir.exists(), "The db dir {:?} not exists", db_dir);
+    let encryption_key = opt
+        .db_encryption_key
+        .as_ref()
+        .map(|source| source.load(opt.data_dir.as_path()))
+        .transpose()?;
+    let db = DBStorage::open_with_encryption(
+        db_dir.join("starcoindb"),
+        VEC_PREFIX_NAME.to_vec(),
+        !opt.quarantine,
+        RocksdbConfig::default(),
+        encryption_key,
+    )?;
+    let db = with_cold_db(
+        db,
+        opt.data_dir.as_path(),
+        opt.cold_db_dir.as_deref(),
+        !opt.quarantine,
+        encryption_key,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(db))?);
+    check_cold_db(storage.clone())?;
+    let verifier = StorageVerifier::new(storage, !opt.skip_state);
+    let report = verifier.verify(opt.from_block, &print_progress)?;
+    let quarantined = if opt.quarantine {
+        let dir = opt
+            .quarantine_dir
+            .unwrap_or_else(|| opt.data_dir.join("quarantine"));
+        verifier.quarantine(&report, dir.as_path())?
+    } else {
+        None
+    };
+    Ok(VerifyDBReport {
+        report,
+        quarantined,
+    })
+}
+
+/// Fail the command if there are issues which are not quarantined, so scripts can check the exit code.
+/// The issues are printed to stderr before failing, as the report is not returned.
+pub fn check_verify_report(report: VerifyDBReport) -> Result<VerifyDBReport> {
+    if !report.report.issues.is_empty() && report.quarantined.is_none() {
+        for issue in &report.report.issues {
+            eprintln!(
+                "block {} ({:?}): {:?} {}",
+                issue.block_number, issue.block_id, issue.kind, issue.detail
+            );
+        }
+        bail!(
+            "Found {} issues in the db, run with --quarantine to move the bad blocks out of the db.",
+            report.report.issues.len()
+        );
+    }
+    Ok(report)
+}
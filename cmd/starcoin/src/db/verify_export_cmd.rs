@@ -0,0 +1,52 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::db::chain_export::{
+    verify_car_export, verify_jsonl_export, ExportFormat, ExportSummary,
+};
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Verify a chain export of `db export-chain`, the blocks must be continuous and linked by the parent hash,
+/// and every block must match its hash. The verification does not require a node.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "verify-export")]
+pub struct VerifyExportOpt {
+    #[structopt(long = "format")]
+    /// the export format, jsonl or car, if absent, guess by the file extension.
+    format: Option<ExportFormat>,
+
+    #[structopt(short = "i", long = "input", parse(from_os_str))]
+    /// the export file.
+    input: PathBuf,
+}
+
+pub struct VerifyExportCommand;
+
+impl CommandAction for VerifyExportCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = VerifyExportOpt;
+    type ReturnItem = ExportSummary;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let format = opt
+            .format
+            .unwrap_or_else(|| ExportFormat::from_file_name(&opt.input.to_string_lossy()));
+        let reader = BufReader::new(File::open(opt.input.as_path())?);
+        match format {
+            ExportFormat::Car => verify_car_export(reader),
+            ExportFormat::Jsonl => verify_jsonl_export(reader),
+        }
+    }
+}
@@ -9,6 +9,7 @@ mod panic_cmd;
 mod sleep_cmd;
 mod txfactory_cmd;
 mod txpool_status;
+mod usage_metrics_cmd;
 
 pub use gen_block_cmd::*;
 pub use get_block_by_uncle::*;
@@ -18,3 +19,4 @@ pub use panic_cmd::*;
 pub use sleep_cmd::*;
 pub use txfactory_cmd::*;
 pub use txpool_status::*;
+pub use usage_metrics_cmd::*;
@@ -0,0 +1,40 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext, UsageMetrics};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "usage-metrics")]
+/// Show the local aggregated usage metrics of cli commands, export it by `-o json`.
+/// The usage metrics is opt-in, set env `STARCOIN_CLI_USAGE_METRICS=true` to enable it,
+/// only the command name, duration and success or failure are recorded.
+pub struct UsageMetricsOpt {
+    #[structopt(long)]
+    /// clear the recorded usage metrics.
+    clear: bool,
+}
+
+pub struct UsageMetricsCommand;
+
+impl CommandAction for UsageMetricsCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = UsageMetricsOpt;
+    type ReturnItem = UsageMetrics;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let file = CliState::usage_metrics_file();
+        let metrics = UsageMetrics::load(file.as_path())?;
+        if ctx.opt().clear && file.exists() {
+            std::fs::remove_file(file.as_path())?;
+        }
+        Ok(metrics)
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, ensure, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::HashValue;
+use starcoin_executor::{DEFAULT_EXPIRATION_TIME, DEFAULT_MAX_GAS_AMOUNT};
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+/// The count of worker accounts funded by one batch transfer txn.
+const FUND_BATCH_SIZE: usize = 50;
+/// The interval to check the new blocks for the mined txns.
+const POLL_BLOCK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Generate transfer traffic against the connected node and report the achieved TPS,
+/// the latency percentiles (from submit to mined) and the failure reasons, for capacity testing.
+/// The worker accounts are generated in memory and funded by the sender before the bench,
+/// the funds sent to them are not recovered, so only use it on dev or test networks.
+///  Some examples:
+///  ``` shell
+///  dev bench --tps 500 --accounts 100 --duration 60s
+///  dev bench --tps 50 --accounts 10 --duration 5m --txn-type batch-transfer
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "bench")]
+pub struct BenchOpt {
+    #[structopt(short = "s", long = "sender")]
+    /// the account to fund the worker accounts, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "tps", default_value = "100")]
+    /// the target txns submitted per second.
+    tps: u64,
+
+    #[structopt(long = "accounts", default_value = "10")]
+    /// the count of worker accounts to send txns.
+    accounts: usize,
+
+    #[structopt(long = "duration", default_value = "60s", parse(try_from_str = parse_duration))]
+    /// how long to generate the traffic, such as 60s, 5m, 1h.
+    duration: Duration,
+
+    #[structopt(long = "txn-type", default_value = "transfer")]
+    /// the txn to generate, `transfer` transfers to the next worker,
+    /// `batch-transfer` transfers to all the other workers by one script function call.
+    txn_type: BenchTxnType,
+
+    #[structopt(long = "fund-amount", default_value = "1000000000")]
+    /// the amount of STC(in nanoSTC) to fund every worker account.
+    fund_amount: u128,
+
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used by the bench txns"
+    )]
+    gas_price: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BenchTxnType {
+    Transfer,
+    BatchTransfer,
+}
+
+impl FromStr for BenchTxnType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "transfer" => Ok(Self::Transfer),
+            "batch-transfer" => Ok(Self::BatchTransfer),
+            _ => bail!("unknown txn type {}, expect transfer or batch-transfer", s),
+        }
+    }
+}
+
+impl fmt::Display for BenchTxnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transfer => write!(f, "transfer"),
+            Self::BatchTransfer => write!(f, "batch-transfer"),
+        }
+    }
+}
+
+/// Parse a duration such as `500ms`, `60s`, `5m`, `1h`, the unit is second if absent.
+pub(crate) fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => s.split_at(index),
+        None => (s, "s"),
+    };
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format_err!("invalid duration {}", s))?;
+    Ok(match unit {
+        "ms" => Duration::from_millis(value),
+        "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value * 60),
+        "h" => Duration::from_secs(value * 3600),
+        _ => bail!("invalid duration unit {}, expect ms, s, m or h", unit),
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyView {
+    /// the latency in milliseconds from submitted to mined.
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl LatencyView {
+    pub(crate) fn from_millis(mut latencies: Vec<u64>) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort_unstable();
+        let percentile = |q: u64| {
+            let index = (latencies.len() as u64 * q + 99) / 100;
+            latencies[(index.max(1) as usize - 1).min(latencies.len() - 1)]
+        };
+        Self {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: latencies[latencies.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchView {
+    pub txn_type: BenchTxnType,
+    pub accounts: usize,
+    pub target_tps: u64,
+    pub duration_secs: u64,
+    pub submitted: u64,
+    pub mined: u64,
+    pub failed: u64,
+    /// the submitted txns per second.
+    pub submit_tps: f64,
+    /// the mined txns per second, from the bench start to the last txn mined.
+    pub mined_tps: f64,
+    pub latency: LatencyView,
+    /// the failure reason to the count of the failed txns.
+    pub failures: BTreeMap<String, u64>,
+}
+
+struct Worker {
+    address: AccountAddress,
+    auth_key: AuthenticationKey,
+    private_key: Ed25519PrivateKey,
+    public_key: Ed25519PublicKey,
+    sequence_number: u64,
+}
+
+impl Worker {
+    fn sign(&self, raw_txn: RawUserTransaction) -> Result<SignedUserTransaction> {
+        Ok(raw_txn
+            .sign(&self.private_key, self.public_key.clone())?
+            .into_inner())
+    }
+}
+
+fn generate_workers(count: usize) -> Vec<Worker> {
+    let mut keygen = KeyGen::from_os_rng();
+    (0..count)
+        .map(|_| {
+            let (private_key, public_key) = keygen.generate_keypair();
+            let auth_key = AuthenticationKey::ed25519(&public_key);
+            Worker {
+                address: auth_key.derived_address(),
+                auth_key,
+                private_key,
+                public_key,
+                sequence_number: 0,
+            }
+        })
+        .collect()
+}
+
+/// Classify the submit error, so the same failures are counted together.
+fn failure_reason(e: &anyhow::Error) -> String {
+    let reason = e.to_string();
+    match reason.find(|c: char| c == '\n' || c == ',') {
+        Some(index) => reason[..index].to_string(),
+        None => reason,
+    }
+}
+
+fn head_number(client: &RpcClient) -> Result<u64> {
+    Ok(client.chain_info()?.head.number.0)
+}
+
+/// Check the blocks after `from_number` for the pending txns, returns the checked head number.
+fn check_mined(
+    client: &RpcClient,
+    from_number: u64,
+    pending: &mut HashMap<HashValue, Instant>,
+    latencies: &mut Vec<u64>,
+    last_mined_at: &mut Option<Instant>,
+) -> Result<u64> {
+    let head = head_number(client)?;
+    for number in from_number + 1..=head {
+        let block = client
+            .chain_get_block_by_number(number)?
+            .ok_or_else(|| format_err!("block {} not exists", number))?;
+        let now = Instant::now();
+        for txn_hash in block.body.txn_hashes() {
+            if let Some(submitted_at) = pending.remove(&txn_hash) {
+                latencies.push(now.duration_since(submitted_at).as_millis() as u64);
+                *last_mined_at = Some(now);
+            }
+        }
+    }
+    Ok(head)
+}
+
+pub struct BenchCommand;
+
+impl CommandAction for BenchCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = BenchOpt;
+    type ReturnItem = BenchView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        ensure!(opt.tps > 0, "tps should be greater than 0");
+        ensure!(opt.accounts > 1, "at least 2 worker accounts are required");
+        let client = ctx.state().client();
+        let chain_id = ctx.state().net().chain_id();
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let mut workers = generate_workers(opt.accounts);
+
+        // fund the worker accounts.
+        let account_resource = AccountStateReader::new(&RemoteStateReader::new(client)?)
+            .get_account_resource(&sender.address)?
+            .ok_or_else(|| format_err!("account {} not exists on chain", sender.address))?;
+        let mut sequence_number = account_resource.sequence_number();
+        let expiration = client.node_info()?.now_seconds + DEFAULT_EXPIRATION_TIME;
+        let mut fund_txns = vec![];
+        for batch in workers.chunks(FUND_BATCH_SIZE) {
+            let raw_txn = starcoin_executor::build_batch_transfer_txn(
+                sender.address,
+                batch.iter().map(|worker| worker.address).collect(),
+                batch.iter().map(|worker| worker.auth_key).collect(),
+                sequence_number,
+                opt.fund_amount,
+                opt.gas_price,
+                DEFAULT_MAX_GAS_AMOUNT,
+                expiration,
+                chain_id,
+            );
+            let txn = client.account_sign_txn(raw_txn)?;
+            fund_txns.push(client.submit_transaction(txn)?);
+            sequence_number += 1;
+        }
+        for txn_hash in fund_txns {
+            ctx.state().watch_txn(txn_hash)?;
+        }
+        println!(
+            "{} worker accounts funded, start the bench for {:?}.",
+            workers.len(),
+            opt.duration
+        );
+
+        let expiration = client.node_info()?.now_seconds
+            + opt.duration.as_secs()
+            + ctx.state().watch_timeout().as_secs()
+            + DEFAULT_EXPIRATION_TIME;
+        let mut pending: HashMap<HashValue, Instant> = HashMap::new();
+        let mut latencies = vec![];
+        let mut failures: BTreeMap<String, u64> = BTreeMap::new();
+        let mut last_mined_at = None;
+        let mut checked_number = head_number(client)?;
+        let mut last_checked_at = Instant::now();
+        let mut submitted = 0u64;
+        let mut next_worker = 0usize;
+
+        let start = Instant::now();
+        while start.elapsed() < opt.duration {
+            let expected = start.elapsed().as_millis() as u64 * opt.tps / 1000;
+            while submitted < expected {
+                let index = next_worker % workers.len();
+                next_worker += 1;
+                let worker = &workers[index];
+                let raw_txn = match opt.txn_type {
+                    BenchTxnType::Transfer => {
+                        let receiver = &workers[(index + 1) % workers.len()];
+                        starcoin_executor::build_transfer_txn(
+                            worker.address,
+                            receiver.address,
+                            Some(receiver.auth_key),
+                            worker.sequence_number,
+                            1,
+                            opt.gas_price,
+                            DEFAULT_MAX_GAS_AMOUNT,
+                            expiration,
+                            chain_id,
+                        )
+                    }
+                    BenchTxnType::BatchTransfer => {
+                        let receivers = workers.iter().filter(|w| w.address != worker.address);
+                        starcoin_executor::build_batch_transfer_txn(
+                            worker.address,
+                            receivers.clone().map(|w| w.address).collect(),
+                            receivers.map(|w| w.auth_key).collect(),
+                            worker.sequence_number,
+                            1,
+                            opt.gas_price,
+                            DEFAULT_MAX_GAS_AMOUNT,
+                            expiration,
+                            chain_id,
+                        )
+                    }
+                };
+                let txn = worker.sign(raw_txn)?;
+                let txn_hash = txn.id();
+                submitted += 1;
+                match client.submit_transaction(txn) {
+                    Ok(_) => {
+                        pending.insert(txn_hash, Instant::now());
+                        workers[index].sequence_number += 1;
+                    }
+                    Err(e) => {
+                        *failures.entry(failure_reason(&e)).or_default() += 1;
+                        // the sequence number may be out of sync, resync from the txpool.
+                        if let Ok(Some(sequence_number)) =
+                            client.next_sequence_number_in_txpool(workers[index].address)
+                        {
+                            workers[index].sequence_number = sequence_number;
+                        }
+                    }
+                }
+            }
+            if last_checked_at.elapsed() >= POLL_BLOCK_INTERVAL {
+                checked_number = check_mined(
+                    client,
+                    checked_number,
+                    &mut pending,
+                    &mut latencies,
+                    &mut last_mined_at,
+                )?;
+                last_checked_at = Instant::now();
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let elapsed = start.elapsed();
+
+        // wait the pending txns mined.
+        let drain_start = Instant::now();
+        while !pending.is_empty() && drain_start.elapsed() < ctx.state().watch_timeout() {
+            std::thread::sleep(POLL_BLOCK_INTERVAL);
+            checked_number = check_mined(
+                client,
+                checked_number,
+                &mut pending,
+                &mut latencies,
+                &mut last_mined_at,
+            )?;
+        }
+        if !pending.is_empty() {
+            failures.insert(
+                "not mined in watch timeout".to_string(),
+                pending.len() as u64,
+            );
+        }
+
+        let mined = latencies.len() as u64;
+        let mined_elapsed = last_mined_at
+            .map(|at| at.duration_since(start))
+            .unwrap_or(elapsed);
+        Ok(BenchView {
+            txn_type: opt.txn_type,
+            accounts: workers.len(),
+            target_tps: opt.tps,
+            duration_secs: elapsed.as_secs(),
+            submitted,
+            mined,
+            failed: submitted - mined,
+            submit_tps: submitted as f64 / elapsed.as_secs_f64(),
+            mined_tps: mined as f64 / mined_elapsed.as_secs_f64().max(0.001),
+            latency: LatencyView::from_millis(latencies),
+            failures,
+        })
+    }
+}
@@ -2,19 +2,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::cli_state::CliState;
-use crate::view::StringView;
+use crate::dev::publish_package_cmd::{collect_move_files, parse_named_address};
 use crate::StarcoinOpt;
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
 use starcoin_config::temp_path;
-use starcoin_move_compiler::{compile_source_string_no_report, errors};
+use starcoin_move_compiler::compiled_unit::CompiledUnit;
+use starcoin_move_compiler::shared::Address;
+use starcoin_move_compiler::{errors, move_compile, process_source_tpl};
+use starcoin_vm_types::access::ModuleAccess;
 use starcoin_vm_types::account_address::AccountAddress;
-use std::fs::File;
-use std::io::Write;
+use starcoin_vm_types::module_abi::script_function_abis_from_module;
+use starcoin_vm_types::transaction::ScriptABI;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use stdlib::restore_stdlib_in_dir;
 use structopt::StructOpt;
 
+/// The address labels file in the cli data dir, a json map from the label to the address,
+/// such as `{"alice": "0x1234..."}`, used as named addresses by `dev compile --wallet-labels`.
+pub const ADDRESS_LABELS_FILE_NAME: &str = "address_labels.json";
+
+/// Compile the move source file, or all the move source files in a dir, by the embedded compiler.
+/// The `{{sender}}` placeholder in the sources is replaced with the sender address,
+/// and other `{{name}}` placeholders are replaced with the `--addresses`.
+/// The compiled units are written to the out dir, named by the source file, or by the module name if
+/// a file contains multi modules. The ABIs of the script functions are written to `<out_dir>/<module>/<function>.abi`,
+/// the args in the ABIs are named by position.
+///  Some examples:
+///  ``` shell
+///  dev compile ./module/MyToken.move -o ./build
+///  dev compile ./modules --addresses alice=0x1234,std=0x1 -o ./build
+///  ```
 #[derive(Debug, StructOpt)]
 #[structopt(name = "compile")]
 pub struct CompileOpt {
@@ -34,59 +54,111 @@ pub struct CompileOpt {
     )]
     deps: Option<Vec<String>>,
 
+    #[structopt(long = "addresses", use_delimiter = true, parse(try_from_str = parse_named_address))]
+    /// named addresses used in the sources, like `alice=0x1234,std=0x1`.
+    addresses: Option<Vec<(String, AccountAddress)>>,
+
+    #[structopt(long = "wallet-labels")]
+    /// use the address labels in the cli data dir as named addresses, the `--addresses` take precedence.
+    wallet_labels: bool,
+
     #[structopt(short = "o", name = "out_dir", help = "out dir", parse(from_os_str))]
     out_dir: Option<PathBuf>,
 
-    #[structopt(name = "source", help = "source file path")]
-    source_file: String,
+    #[structopt(name = "source", help = "source file or dir path", parse(from_os_str))]
+    source: PathBuf,
 
     /// Do not automatically run the bytecode verifier
     #[structopt(long = "no-verify")]
     pub no_verify: bool,
 }
 
-pub struct CompileCommand;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileView {
+    /// the last compiled file, the only one when compile a single file with a single unit.
+    pub result: PathBuf,
+    pub compiled_files: Vec<PathBuf>,
+    pub abi_files: Vec<PathBuf>,
+}
 
-impl CommandAction for CompileCommand {
-    type State = CliState;
-    type GlobalOpt = StarcoinOpt;
-    type Opt = CompileOpt;
-    type ReturnItem = StringView;
+/// Load the address labels from the cli data dir, return empty map if the file not exists.
+pub(crate) fn load_address_labels(data_dir: &Path) -> Result<BTreeMap<String, AccountAddress>> {
+    let file = data_dir.join(ADDRESS_LABELS_FILE_NAME);
+    if !file.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_slice(std::fs::read(file)?.as_slice())?)
+}
 
-    fn run(
-        &self,
-        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
-    ) -> Result<Self::ReturnItem> {
-        let sender = if let Some(sender) = ctx.opt().sender {
-            sender
-        } else {
-            ctx.state().default_account()?.address
-        };
-        let source_file = ctx.opt().source_file.as_str();
-        let source_file_path = Path::new(source_file);
-        let ext = source_file_path
-            .extension()
-            .map(|os_str| os_str.to_str().expect("file extension should is utf str"))
-            .unwrap_or_else(|| "");
-        //TODO support compile dir.
-        if ext != starcoin_move_compiler::MOVE_EXTENSION {
-            bail!("Only support compile *.move file.")
-        }
+/// Compile the move files in `source`, every file is compiled as a target, with the other files as deps.
+pub(crate) fn compile_files(
+    source: &Path,
+    sender: AccountAddress,
+    named_addresses: &BTreeMap<String, AccountAddress>,
+    extra_deps: &[String],
+    verify: bool,
+) -> Result<Vec<(PathBuf, Vec<CompiledUnit>)>> {
+    let mut source_files = vec![];
+    collect_move_files(source, &mut source_files)?;
+    ensure!(
+        !source_files.is_empty(),
+        "no move source file found in {:?}",
+        source
+    );
 
-        let temp_path = temp_path();
-        let mut deps = restore_stdlib_in_dir(temp_path.path())?;
+    let temp_path = temp_path();
+    let source_dir = temp_path.path().join("sources");
+    let dep_dir = temp_path.path().join("deps");
+    std::fs::create_dir_all(source_dir.as_path())?;
+    std::fs::create_dir_all(dep_dir.as_path())?;
 
-        // add extra deps
-        deps.append(&mut ctx.opt().deps.clone().unwrap_or_default());
-        let (sources, compile_result) = compile_source_string_no_report(
-            std::fs::read_to_string(source_file_path)?.as_str(),
-            &deps,
-            sender,
-        )?;
+    let sender_address = Address::new(sender.into());
+    let vars = named_addresses
+        .iter()
+        .map(|(name, address)| {
+            (
+                name.as_str(),
+                format!("{}", Address::new((*address).into())),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let mut processed_files = vec![];
+    for (i, source_file) in source_files.iter().enumerate() {
+        let processed = process_source_tpl(
+            std::fs::read_to_string(source_file)?.as_str(),
+            sender_address,
+            vars.clone(),
+        );
+        // prefix with the index to avoid name conflict of files in different sub dirs.
+        let file_name = format!(
+            "{}_{}",
+            i,
+            source_file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .expect("move file name should be utf str")
+        );
+        let target = source_dir.join(file_name);
+        std::fs::write(target.as_path(), processed)?;
+        processed_files.push(target.display().to_string());
+    }
 
-        let compile_result = if ctx.opt().no_verify {
-            compile_result
-        } else {
+    let mut deps = restore_stdlib_in_dir(dep_dir.as_path())?;
+    deps.extend_from_slice(extra_deps);
+    let mut results = vec![];
+    for (i, source_file) in source_files.into_iter().enumerate() {
+        let targets = vec![processed_files[i].clone()];
+        let mut file_deps = deps.clone();
+        file_deps.extend(
+            processed_files
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, file)| file.clone()),
+        );
+        let (sources, compile_result) =
+            move_compile(&targets, &file_deps, Some(sender_address), None, true)?;
+        let compile_result = if verify {
             compile_result.and_then(|units| {
                 let (units, errors) = units.into_iter().map(|unit| unit.verify()).fold(
                     (vec![], vec![]),
@@ -102,12 +174,11 @@ impl CommandAction for CompileCommand {
                     Ok(units)
                 }
             })
+        } else {
+            compile_result
         };
-
-        let compile_unit = match compile_result {
-            Ok(mut c) => c
-                .pop()
-                .ok_or_else(|| anyhow::anyhow!("file should at least contain one compile unit"))?,
+        match compile_result {
+            Ok(units) => results.push((source_file, units)),
             Err(e) => {
                 eprintln!(
                     "{}",
@@ -115,23 +186,104 @@ impl CommandAction for CompileCommand {
                         errors::report_errors_to_color_buffer(sources, e).as_slice()
                     )
                 );
-                bail!("compile error")
+                bail!("compile {:?} error", source_file)
             }
+        }
+    }
+    Ok(results)
+}
+
+/// Write the compiled units and the ABIs of the script functions to `out_dir`.
+pub(crate) fn write_compiled_units(
+    out_dir: &Path,
+    units: Vec<(PathBuf, Vec<CompiledUnit>)>,
+) -> Result<CompileView> {
+    std::fs::create_dir_all(out_dir)?;
+    let mut compiled_files = vec![];
+    let mut abi_files = vec![];
+    for (source_file, units) in units {
+        let stem = source_file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("move file name should be utf str")
+            .to_string();
+        let single = units.len() == 1;
+        for (i, unit) in units.into_iter().enumerate() {
+            let name = match &unit {
+                CompiledUnit::Module { module, .. } => {
+                    let module_name = module.self_id().name().to_string();
+                    for abi in script_function_abis_from_module(module)? {
+                        let abi_dir = out_dir.join(module_name.as_str());
+                        std::fs::create_dir_all(abi_dir.as_path())?;
+                        let abi_file = abi_dir.join(format!("{}.abi", abi.name()));
+                        std::fs::write(
+                            abi_file.as_path(),
+                            bcs_ext::to_bytes(&ScriptABI::ScriptFunction(abi))?,
+                        )?;
+                        abi_files.push(abi_file);
+                    }
+                    if single {
+                        stem.clone()
+                    } else {
+                        module_name
+                    }
+                }
+                CompiledUnit::Script { .. } if single => stem.clone(),
+                CompiledUnit::Script { .. } => format!("{}_{}", stem, i),
+            };
+            let mut file = out_dir.join(name);
+            file.set_extension(stdlib::COMPILED_EXTENSION);
+            std::fs::write(file.as_path(), unit.serialize())?;
+            compiled_files.push(file);
+        }
+    }
+    let result = compiled_files
+        .last()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("source should at least contain one compile unit"))?;
+    Ok(CompileView {
+        result,
+        compiled_files,
+        abi_files,
+    })
+}
+
+pub struct CompileCommand;
+
+impl CommandAction for CompileCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = CompileOpt;
+    type ReturnItem = CompileView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let sender = if let Some(sender) = opt.sender {
+            sender
+        } else {
+            ctx.state().default_account()?.address
+        };
+        let mut named_addresses = if opt.wallet_labels {
+            load_address_labels(ctx.state().data_dir())?
+        } else {
+            BTreeMap::new()
         };
+        named_addresses.extend(opt.addresses.clone().unwrap_or_default());
 
-        let mut txn_path = ctx
-            .opt()
+        let units = compile_files(
+            opt.source.as_path(),
+            sender,
+            &named_addresses,
+            opt.deps.clone().unwrap_or_default().as_slice(),
+            !opt.no_verify,
+        )?;
+        let out_dir = opt
             .out_dir
             .clone()
             .unwrap_or_else(|| ctx.state().temp_dir().to_path_buf());
-
-        txn_path.push(source_file_path.file_name().unwrap());
-        txn_path.set_extension(stdlib::COMPILED_EXTENSION);
-        let mut file = File::create(txn_path.clone()).expect("unable create out file");
-        file.write_all(&compile_unit.serialize())
-            .expect("write out file error");
-        Ok(StringView {
-            result: txn_path.to_str().unwrap().to_string(),
-        })
+        write_compiled_units(out_dir.as_path(), units)
     }
 }
@@ -0,0 +1,293 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::script_function_abi::resolve_script_function_abi;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_resource_viewer::MoveValueAnnotator;
+use starcoin_rpc_api::types::{
+    AnnotatedMoveValueView, FunctionIdView, ModuleIdView, StrView, TypeTagView,
+};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::contract_event::ContractEvent;
+use starcoin_vm_types::event::EventKey;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::language_storage::FunctionId;
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::transaction::authenticator::TransactionAuthenticator;
+use starcoin_vm_types::transaction::{
+    RawUserTransaction, ScriptFunction, SignedUserTransaction, TransactionPayload,
+};
+use std::fmt;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlobType {
+    /// A SignedUserTransaction, or a RawUserTransaction if it is not signed.
+    Txn,
+    Payload,
+    Event,
+}
+
+impl fmt::Display for BlobType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlobType::Txn => write!(f, "txn"),
+            BlobType::Payload => write!(f, "payload"),
+            BlobType::Event => write!(f, "event"),
+        }
+    }
+}
+
+impl FromStr for BlobType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "txn" => Ok(BlobType::Txn),
+            "payload" => Ok(BlobType::Payload),
+            "event" => Ok(BlobType::Event),
+            _ => bail!("Unknown blob type: {}, expect txn, payload or event", s),
+        }
+    }
+}
+
+/// Decode a bcs encoded txn, txn payload or contract event, the script function args are decoded by the on chain ABI.
+/// Use it to audit a multisig txn before signing it.
+///  Some examples:
+///  ``` shell
+///  dev decode --type txn 0x...
+///  dev decode --type payload 0x...
+///  dev decode --type event 0x...
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "decode")]
+pub struct DecodeOpt {
+    #[structopt(long = "type", default_value = "txn")]
+    /// the type of the blob, txn, payload or event.
+    blob_type: BlobType,
+
+    #[structopt(name = "hex")]
+    /// the hex of the bcs encoded blob, the 0x prefix is optional.
+    data: StrView<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedArgView {
+    pub name: String,
+    pub type_tag: TypeTagView,
+    /// None if the arg can not be decoded by the type in ABI.
+    pub value: Option<AnnotatedMoveValueView>,
+    pub raw: StrView<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedScriptFunctionView {
+    pub function: FunctionIdView,
+    pub ty_args: Vec<TypeTagView>,
+    /// Empty and the raw args are kept if the ABI of the function can not be resolved.
+    pub args: Vec<DecodedArgView>,
+    pub raw_args: Vec<StrView<Vec<u8>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DecodedPayloadView {
+    Script {
+        code: StrView<Vec<u8>>,
+        ty_args: Vec<TypeTagView>,
+        args: Vec<StrView<Vec<u8>>>,
+    },
+    Package {
+        package_address: AccountAddress,
+        modules: Vec<ModuleIdView>,
+        init_script: Option<DecodedScriptFunctionView>,
+    },
+    ScriptFunction(DecodedScriptFunctionView),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedTxnView {
+    /// None if the txn is not signed.
+    pub transaction_hash: Option<HashValue>,
+    pub authenticator: Option<TransactionAuthenticator>,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub gas_token_code: String,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+    pub payload: DecodedPayloadView,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedEventView {
+    pub key: EventKey,
+    pub sequence_number: u64,
+    pub type_tag: TypeTagView,
+    /// None if the event data can not be decoded by the on chain module.
+    pub data: Option<AnnotatedMoveValueView>,
+    pub raw: StrView<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DecodedView {
+    Txn(Box<DecodedTxnView>),
+    Payload(DecodedPayloadView),
+    Event(DecodedEventView),
+}
+
+pub struct DecodeCommand;
+
+impl CommandAction for DecodeCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = DecodeOpt;
+    type ReturnItem = DecodedView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let state_reader = RemoteStateReader::new(ctx.state().client())?;
+        decode_blob(&state_reader, opt.blob_type, opt.data.0.as_slice())
+    }
+}
+
+pub(crate) fn decode_blob(
+    state_view: &dyn StateView,
+    blob_type: BlobType,
+    blob: &[u8],
+) -> Result<DecodedView> {
+    Ok(match blob_type {
+        BlobType::Txn => DecodedView::Txn(Box::new(decode_txn(state_view, blob)?)),
+        BlobType::Payload => {
+            let payload: TransactionPayload =
+                bcs_ext::from_bytes(blob).map_err(|e| format_err!("invalid txn payload: {}", e))?;
+            DecodedView::Payload(decode_payload(state_view, payload)?)
+        }
+        BlobType::Event => {
+            let event: ContractEvent = bcs_ext::from_bytes(blob)
+                .map_err(|e| format_err!("invalid contract event: {}", e))?;
+            DecodedView::Event(decode_event(state_view, &event))
+        }
+    })
+}
+
+fn decode_txn(state_view: &dyn StateView, blob: &[u8]) -> Result<DecodedTxnView> {
+    let (raw_txn, transaction_hash, authenticator) =
+        match bcs_ext::from_bytes::<SignedUserTransaction>(blob) {
+            Ok(txn) => {
+                let txn_hash = txn.id();
+                let authenticator = txn.authenticator();
+                (
+                    txn.into_raw_transaction(),
+                    Some(txn_hash),
+                    Some(authenticator),
+                )
+            }
+            Err(_) => (
+                bcs_ext::from_bytes::<RawUserTransaction>(blob)
+                    .map_err(|e| format_err!("invalid txn, neither signed nor raw txn: {}", e))?,
+                None,
+                None,
+            ),
+        };
+    Ok(DecodedTxnView {
+        transaction_hash,
+        authenticator,
+        sender: raw_txn.sender(),
+        sequence_number: raw_txn.sequence_number(),
+        max_gas_amount: raw_txn.max_gas_amount(),
+        gas_unit_price: raw_txn.gas_unit_price(),
+        gas_token_code: raw_txn.gas_token_code(),
+        expiration_timestamp_secs: raw_txn.expiration_timestamp_secs(),
+        chain_id: raw_txn.chain_id().id(),
+        payload: decode_payload(state_view, raw_txn.into_payload())?,
+    })
+}
+
+pub(crate) fn decode_payload(
+    state_view: &dyn StateView,
+    payload: TransactionPayload,
+) -> Result<DecodedPayloadView> {
+    Ok(match payload {
+        TransactionPayload::Script(script) => {
+            let (code, ty_args, args) = script.into_inner();
+            DecodedPayloadView::Script {
+                code: StrView(code),
+                ty_args: ty_args.into_iter().map(StrView).collect(),
+                args: args.into_iter().map(StrView).collect(),
+            }
+        }
+        TransactionPayload::Package(package) => {
+            let (package_address, modules, init_script) = package.into_inner();
+            let modules = modules
+                .iter()
+                .map(|module| {
+                    CompiledModule::deserialize(module.code())
+                        .map(|module| StrView(module.self_id()))
+                        .map_err(|e| format_err!("invalid module in package: {:?}", e))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            DecodedPayloadView::Package {
+                package_address,
+                modules,
+                init_script: init_script.map(|script| decode_script_function(state_view, script)),
+            }
+        }
+        TransactionPayload::ScriptFunction(function) => {
+            DecodedPayloadView::ScriptFunction(decode_script_function(state_view, function))
+        }
+    })
+}
+
+fn decode_script_function(
+    state_view: &dyn StateView,
+    function: ScriptFunction,
+) -> DecodedScriptFunctionView {
+    let (module, function, ty_args, args) = function.into_inner();
+    let function_id = FunctionId { module, function };
+    let annotator = MoveValueAnnotator::new(state_view);
+    let decoded_args = match resolve_script_function_abi(state_view, &function_id) {
+        Ok(abi) if abi.args().len() == args.len() => abi
+            .args()
+            .iter()
+            .zip(args.iter())
+            .map(|(arg_abi, arg)| DecodedArgView {
+                name: arg_abi.name().to_string(),
+                type_tag: StrView(arg_abi.type_tag().clone()),
+                value: annotator
+                    .view_value(arg_abi.type_tag(), arg.as_slice())
+                    .ok()
+                    .map(Into::into),
+                raw: StrView(arg.clone()),
+            })
+            .collect(),
+        _ => vec![],
+    };
+    DecodedScriptFunctionView {
+        function: StrView(function_id),
+        ty_args: ty_args.into_iter().map(StrView).collect(),
+        args: decoded_args,
+        raw_args: args.into_iter().map(StrView).collect(),
+    }
+}
+
+fn decode_event(state_view: &dyn StateView, event: &ContractEvent) -> DecodedEventView {
+    let annotator = MoveValueAnnotator::new(state_view);
+    DecodedEventView {
+        key: *event.key(),
+        sequence_number: event.sequence_number(),
+        type_tag: StrView(event.type_tag().clone()),
+        data: annotator.view_contract_event(event).ok().map(Into::into),
+        raw: StrView(event.event_data().to_vec()),
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::view::TranscationOutputView;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_dev::playground;
+use starcoin_rpc_api::types::DryRunOutputView;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{DryRunTransaction, RawUserTransaction, TransactionPayload};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Dry-run a bcs encoded txn payload on the latest state, the output contains the full write set
+/// with the decoded resources, and the events with the decoded data.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "dry-run")]
+pub struct DryRunOpt {
+    #[structopt(long = "payload", parse(from_os_str))]
+    /// the file of the bcs encoded `TransactionPayload`, which can be a script, a script function, or a package.
+    payload: PathBuf,
+
+    #[structopt(short = "s")]
+    /// the sender of the txn, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "public-key", parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    /// the hex encoded public key of the sender, if absent, use the public key of the sender account in local wallet.
+    public_key: Option<Ed25519PublicKey>,
+
+    #[structopt(long = "sequence-number")]
+    /// the sequence number of the txn, if absent, use the sequence number of the sender on chain.
+    sequence_number: Option<u64>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to dry-run the txn"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "how many STC(in nanoSTC) per gas"
+    )]
+    gas_price: u64,
+}
+
+pub struct DryRunCommand;
+
+impl CommandAction for DryRunCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = DryRunOpt;
+    type ReturnItem = TranscationOutputView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let payload: TransactionPayload =
+            bcs_ext::from_bytes(std::fs::read(opt.payload.as_path())?.as_slice())?;
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let public_key = match &opt.public_key {
+            Some(public_key) => AccountPublicKey::Single(public_key.clone()),
+            None => sender.public_key,
+        };
+
+        let state_reader = RemoteStateReader::new(client)?;
+        let sequence_number = match opt.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => AccountStateReader::new(&state_reader)
+                .get_account_resource(&sender.address)?
+                .ok_or_else(|| {
+                    format_err!("account of address {} not exists on chain", sender.address)
+                })?
+                .sequence_number(),
+        };
+        let node_info = client.node_info()?;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender.address,
+            sequence_number,
+            payload,
+            opt.max_gas_amount,
+            opt.gas_price,
+            node_info.now_seconds + 3600,
+            ctx.state().net().chain_id(),
+        );
+        let (_, output, annotation) = playground::dry_run_annotated(
+            &state_reader,
+            DryRunTransaction {
+                raw_txn,
+                public_key,
+            },
+        )?;
+        Ok(DryRunOutputView::new(output, annotation.write_set, annotation.events).into())
+    }
+}
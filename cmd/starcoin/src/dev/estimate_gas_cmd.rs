@@ -0,0 +1,115 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account::gas_estimate::{estimate_gas, GasEstimateOpt, GasEstimateView};
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_executor::{DEFAULT_EXPIRATION_TIME, DEFAULT_MAX_GAS_AMOUNT};
+use starcoin_rpc_api::types::{FunctionIdView, StrView};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{parse_transaction_argument, TransactionArgument};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::{RawUserTransaction, ScriptFunction, TransactionPayload};
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+/// Estimate the max gas amount of a script function or a bcs encoded payload by dry run,
+/// and show the gas price suggested by the txpool.
+///  Some examples:
+///  ``` shell
+///  dev estimate-gas --function 0x1::TransferScripts::peer_to_peer -t 0x1::STC::STC --arg 0xc --arg x"" --arg 1u128
+///  dev estimate-gas --payload 0x... --gas-margin 50
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "estimate-gas")]
+pub struct EstimateGasOpt {
+    #[structopt(short = "s")]
+    /// if `sender` is absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(
+        long = "function",
+        name = "script-function",
+        required_unless = "payload"
+    )]
+    /// script function to estimate, example: 0x1::TransferScripts::peer_to_peer
+    script_function: Option<FunctionIdView>,
+
+    #[structopt(
+        short = "t",
+        long = "type_tag",
+        name = "type-tag",
+        parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the script function.
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument))]
+    /// args for the script function.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[structopt(long = "payload", name = "payload", conflicts_with = "script-function")]
+    /// the hex of the bcs encoded txn payload.
+    payload: Option<StrView<Vec<u8>>>,
+
+    #[structopt(short = "p", long = "gas-price")]
+    /// the gas price of the dry run, default is the gas price suggested by the txpool.
+    gas_price: Option<u64>,
+
+    #[structopt(flatten)]
+    gas_estimate: GasEstimateOpt,
+}
+
+pub struct EstimateGasCommand;
+
+impl CommandAction for EstimateGasCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = EstimateGasOpt;
+    type ReturnItem = GasEstimateView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let payload = match (&opt.payload, &opt.script_function) {
+            (Some(payload), _) => bcs_ext::from_bytes::<TransactionPayload>(payload.0.as_slice())
+                .map_err(|e| format_err!("invalid txn payload: {}", e))?,
+            (None, Some(function)) => TransactionPayload::ScriptFunction(ScriptFunction::new(
+                function.0.module.clone(),
+                function.0.function.clone(),
+                opt.type_tags.clone().unwrap_or_default(),
+                convert_txn_args(&opt.args.clone().unwrap_or_default()),
+            )),
+            (None, None) => unreachable!("structopt should ensure payload or function present"),
+        };
+        let chain_state_reader = RemoteStateReader::new(client)?;
+        let sequence_number = AccountStateReader::new(&chain_state_reader)
+            .get_account_resource(&sender.address)?
+            .ok_or_else(|| {
+                format_err!("account of address {} not exists on chain", sender.address)
+            })?
+            .sequence_number();
+        let gas_price = match opt.gas_price {
+            Some(gas_price) => gas_price,
+            None => client.txpool_gas_price()?,
+        };
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender.address,
+            sequence_number,
+            payload,
+            DEFAULT_MAX_GAS_AMOUNT,
+            gas_price,
+            client.node_info()?.now_seconds + DEFAULT_EXPIRATION_TIME,
+            ctx.state().net().chain_id(),
+        );
+        estimate_gas(client, &sender, &raw_txn, opt.gas_estimate.gas_margin)
+    }
+}
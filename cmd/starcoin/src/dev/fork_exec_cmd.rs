@@ -0,0 +1,223 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{ensure, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_config::RocksdbConfig;
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_crypto::{HashValue, ValidCryptoMaterialStringExt};
+use starcoin_dev::playground;
+use starcoin_rpc_api::types::{DryRunOutputView, FunctionIdView};
+use starcoin_rpc_client::{RemoteStateNodeStore, RpcClient};
+use starcoin_state_api::{AccountStateReader, ChainStateWriter};
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::Storage;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::transaction::{
+    parse_transaction_argument, DryRunTransaction, RawUserTransaction, ScriptFunction,
+    TransactionArgument, TransactionPayload, TransactionStatus,
+};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+const FORK_META_FILE: &str = "fork.json";
+
+/// Execute a script function txn locally on the state of a remote chain at a block.
+/// The state nodes are loaded lazily from the remote node and cached in the fork dir,
+/// the txn output is applied to the forked state, so the following txns of the same fork dir
+/// are executed on the state after the previous txns. It does not run a forked chain, no block
+/// is produced and no node is started, every txn is executed at the forked block's timestamp.
+///  Some examples:
+///  ``` shell
+///  dev fork-exec --rpc ws://main.seed.starcoin.org:9870 --block 100000 --dir ./fork -s 0x1 --function 0x1::TransferScripts::peer_to_peer_v2 -t 0x1::STC::STC --arg 0xb6d69dd1c6cfd6de9ae8cd27bd07b16c --arg 1000u128
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "fork-exec")]
+pub struct ForkExecOpt {
+    #[structopt(long = "rpc")]
+    /// the websocket rpc address of the remote node to fork, example: ws://127.0.0.1:9870
+    rpc: String,
+
+    #[structopt(long = "block")]
+    /// the block number to fork at.
+    block: BlockNumber,
+
+    #[structopt(long = "dir", parse(from_os_str))]
+    /// the dir to keep the forked state, reuse the dir to continue executing txns on the fork.
+    dir: PathBuf,
+
+    #[structopt(long = "function")]
+    /// script function to execute, example: 0x1::TransferScripts::peer_to_peer_v2
+    function: FunctionIdView,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the script function
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument))]
+    /// args for the script function.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[structopt(short = "s")]
+    /// the sender of the txn, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "public-key", parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    /// the hex encoded public key of the sender, if absent, use the public key of the sender account in local wallet.
+    public_key: Option<Ed25519PublicKey>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the txn"
+    )]
+    max_gas_amount: u64,
+}
+
+/// The meta of a fork dir.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ForkMeta {
+    pub rpc: String,
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    pub chain_id: u8,
+    /// The block timestamp in milliseconds.
+    pub timestamp: u64,
+    /// The state root after the txns executed on the fork.
+    pub state_root: HashValue,
+    pub executed_txns: u64,
+}
+
+impl ForkMeta {
+    pub(crate) fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(FORK_META_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(
+            std::fs::read(path)?.as_slice(),
+        )?))
+    }
+
+    pub(crate) fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::write(dir.join(FORK_META_FILE), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForkExecView {
+    pub meta: ForkMeta,
+    pub output: DryRunOutputView,
+}
+
+pub struct ForkExecCommand;
+
+impl CommandAction for ForkExecCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ForkExecOpt;
+    type ReturnItem = ForkExecView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let remote = Arc::new(RpcClient::connect_websocket(opt.rpc.as_str())?);
+        std::fs::create_dir_all(opt.dir.as_path())?;
+        let mut meta = match ForkMeta::load(opt.dir.as_path())? {
+            Some(meta) => {
+                ensure!(
+                    meta.block_number == opt.block,
+                    "The fork dir {:?} is forked at block {}, not {}.",
+                    opt.dir,
+                    meta.block_number,
+                    opt.block
+                );
+                meta
+            }
+            None => {
+                let block = remote
+                    .chain_get_block_by_number(opt.block)?
+                    .ok_or_else(|| format_err!("Can not find block by number: {}", opt.block))?;
+                ForkMeta {
+                    rpc: opt.rpc.clone(),
+                    block_number: opt.block,
+                    block_hash: block.header.block_hash,
+                    chain_id: block.header.chain_id,
+                    timestamp: block.header.timestamp.0,
+                    state_root: block.header.state_root,
+                    executed_txns: 0,
+                }
+            }
+        };
+
+        let local = Arc::new(Storage::new(StorageInstance::new_db_instance(
+            DBStorage::new(opt.dir.join("db"), RocksdbConfig::default())?,
+        ))?);
+        let store = Arc::new(RemoteStateNodeStore::new(local, remote));
+        let statedb = ChainStateDB::new(store, Some(meta.state_root));
+
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let public_key = match &opt.public_key {
+            Some(public_key) => AccountPublicKey::Single(public_key.clone()),
+            None => sender.public_key,
+        };
+        let sequence_number = AccountStateReader::new(&statedb)
+            .get_account_resource(&sender.address)?
+            .ok_or_else(|| format_err!("account of address {} not exists on fork", sender.address))?
+            .sequence_number();
+        let function = opt.function.clone().0;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender.address,
+            sequence_number,
+            TransactionPayload::ScriptFunction(ScriptFunction::new(
+                function.module,
+                function.function,
+                opt.type_tags.clone().unwrap_or_default(),
+                convert_txn_args(&opt.args.clone().unwrap_or_default()),
+            )),
+            opt.max_gas_amount,
+            1,
+            meta.timestamp / 1000 + 3600,
+            ChainId::new(meta.chain_id),
+        );
+        let (_, output, annotation) = playground::dry_run_annotated(
+            &statedb,
+            DryRunTransaction {
+                raw_txn,
+                public_key,
+            },
+        )?;
+        // the discarded txn does not change the fork.
+        if let TransactionStatus::Keep(_) = output.status() {
+            statedb.apply_write_set(output.write_set().clone())?;
+            meta.state_root = statedb.commit()?;
+            statedb.flush()?;
+            meta.executed_txns += 1;
+            meta.save(opt.dir.as_path())?;
+        }
+        Ok(ForkExecView {
+            meta,
+            output: DryRunOutputView::new(output, annotation.write_set, annotation.events),
+        })
+    }
+}
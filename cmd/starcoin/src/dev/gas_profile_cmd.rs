@@ -0,0 +1,524 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_dev::playground;
+use starcoin_move_compiler::load_bytecode_file;
+use starcoin_rpc_api::types::{
+    FunctionIdView, SignedUserTransactionView, TransactionOutputView, TransactionVMStatus,
+};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{
+    parse_transaction_argument, DryRunTransaction, RawUserTransaction, Script, ScriptFunction,
+    TransactionArgument, TransactionPayload,
+};
+use starcoin_vm_types::access::{ModuleAccess, ScriptAccess};
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::file_format::{Bytecode, CompiledModule, CompiledScript};
+use starcoin_vm_types::file_format_common::instruction_key;
+use starcoin_vm_types::gas_schedule::{CostTable, GasAlgebra};
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::on_chain_config::VMConfig;
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use structopt::StructOpt;
+
+/// The env var used by Move VM to enable the execution trace, and the value is the trace file path.
+const MOVE_VM_TRACE_ENV: &str = "MOVE_VM_TRACE";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "gas-profile")]
+/// Dry-run a txn payload locally with Move VM execution trace enabled, and report the gas spent per bytecode instruction,
+/// per native call and per module.
+/// The total `gas_used` is measured by the dry-run, the per instruction gas is estimated by the on chain
+/// gas schedule with the minimal memory size, and the rest of the measured gas, such as the native calls,
+/// the argument size dependent cost and the intrinsic gas, is reported as `unattributed_internal_gas`.
+/// The execution trace is only written by the Move VM built with debug assertions, so the command fails
+/// in release builds, and the trace file path is fixed at the first execution of the process,
+/// so run it out of console mode.
+pub struct GasProfileOpt {
+    #[structopt(name = "txn-or-script")]
+    /// the hash of an on chain or pending txn, whose payload is re-executed on the latest state,
+    /// or the path of a compiled script file.
+    txn_or_script: Option<String>,
+
+    #[structopt(
+        long = "function",
+        name = "script-function",
+        conflicts_with = "txn-or-script"
+    )]
+    /// script function to profile, example: 0x1::TransferScripts::peer_to_peer
+    script_function: Option<FunctionIdView>,
+
+    #[structopt(short = "s")]
+    /// the sender of the script or script function, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the script or script function
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument))]
+    /// args for the script or script function.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to dry-run the txn"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(long = "trace-file", name = "trace-file", parse(from_os_str))]
+    /// the file to save the Move VM execution trace, default to a file in the temp dir.
+    /// Ignored if the `MOVE_VM_TRACE` env is set.
+    trace_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionGasView {
+    pub instruction: String,
+    pub count: u64,
+    /// estimated gas in internal gas units.
+    pub internal_gas: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NativeCallView {
+    pub function: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleGasView {
+    pub module: String,
+    pub instructions: u64,
+    /// estimated gas in internal gas units.
+    pub internal_gas: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasProfileView {
+    pub status: TransactionVMStatus,
+    pub gas_used: u64,
+    /// internal gas units divided by the scaling factor is the gas units.
+    pub gas_unit_scaling_factor: u64,
+    pub trace_file: PathBuf,
+    /// the measured internal gas which is not attributed to the instructions,
+    /// `gas_used * gas_unit_scaling_factor - sum(instructions.internal_gas)`.
+    pub unattributed_internal_gas: u64,
+    /// sorted by gas desc.
+    pub instructions: Vec<InstructionGasView>,
+    /// sorted by count desc.
+    pub natives: Vec<NativeCallView>,
+    /// sorted by gas desc.
+    pub modules: Vec<ModuleGasView>,
+}
+
+pub struct GasProfileCommand;
+
+impl CommandAction for GasProfileCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = GasProfileOpt;
+    type ReturnItem = GasProfileView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        // The Move VM only writes the execution trace when it is built with debug assertions,
+        // and all crates share the build profile, so check it before the dry-run.
+        if !cfg!(debug_assertions) {
+            bail!(
+                "gas-profile requires a starcoin build with debug assertions (a non-release build), \
+                 the Move VM does not write the execution trace in release builds"
+            );
+        }
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let type_tags = opt.type_tags.clone().unwrap_or_default();
+        let args = opt.args.clone().unwrap_or_default();
+
+        let mut script = None;
+        let (sender, public_key, payload) = match (&opt.txn_or_script, &opt.script_function) {
+            (Some(txn_or_script), None) => match HashValue::from_str(txn_or_script) {
+                Ok(txn_hash) => {
+                    let txn: SignedUserTransactionView = match client
+                        .chain_get_transaction(txn_hash)?
+                        .and_then(|txn| txn.user_transaction)
+                    {
+                        Some(txn) => txn,
+                        None => client.get_pending_txn_by_hash(txn_hash)?.ok_or_else(|| {
+                            format_err!("cannot find user txn {} on chain or in txpool", txn_hash)
+                        })?,
+                    };
+                    let payload = bcs_ext::from_bytes::<TransactionPayload>(
+                        txn.raw_txn.payload.0.as_slice(),
+                    )?;
+                    if let TransactionPayload::Script(s) = &payload {
+                        script = Some(CompiledScript::deserialize(s.code()).map_err(|e| {
+                            format_err!("deserialize script of txn error: {:?}", e)
+                        })?);
+                    }
+                    (txn.raw_txn.sender, txn.authenticator.public_key(), payload)
+                }
+                Err(_) => {
+                    let (bytecode, is_script) = load_bytecode_file(Path::new(txn_or_script))?;
+                    if !is_script {
+                        bail!("{} is not a script", txn_or_script);
+                    }
+                    script = Some(
+                        CompiledScript::deserialize(bytecode.as_slice())
+                            .map_err(|e| format_err!("deserialize script error: {:?}", e))?,
+                    );
+                    let sender = ctx.state().get_account_or_default(opt.sender)?;
+                    (
+                        sender.address,
+                        sender.public_key,
+                        TransactionPayload::Script(Script::new(
+                            bytecode,
+                            type_tags,
+                            convert_txn_args(&args),
+                        )),
+                    )
+                }
+            },
+            (None, Some(script_function)) => {
+                let script_function = script_function.clone().0;
+                let sender = ctx.state().get_account_or_default(opt.sender)?;
+                (
+                    sender.address,
+                    sender.public_key,
+                    TransactionPayload::ScriptFunction(ScriptFunction::new(
+                        script_function.module,
+                        script_function.function,
+                        type_tags,
+                        convert_txn_args(&args),
+                    )),
+                )
+            }
+            _ => bail!("one of txn hash, script file or `--function` is required"),
+        };
+
+        let state_reader = RemoteStateReader::new(client)?;
+        let account_state_reader = AccountStateReader::new(&state_reader);
+        let sequence_number = account_state_reader
+            .get_account_resource(&sender)?
+            .ok_or_else(|| format_err!("account of address {} not exists on chain", sender))?
+            .sequence_number();
+        let cost_table = account_state_reader
+            .get_on_chain_config::<VMConfig>()?
+            .ok_or_else(|| format_err!("cannot find VMConfig on chain"))?
+            .gas_schedule;
+        let node_info = client.node_info()?;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender,
+            sequence_number,
+            payload,
+            opt.max_gas_amount,
+            1,
+            node_info.now_seconds + 3600,
+            ctx.state().net().chain_id(),
+        );
+
+        let trace_file = match std::env::var(MOVE_VM_TRACE_ENV) {
+            Ok(trace_file) => PathBuf::from(trace_file),
+            Err(_) => {
+                let trace_file = opt.trace_file.clone().unwrap_or_else(|| {
+                    std::env::temp_dir()
+                        .join(format!("starcoin-gas-profile-{}.trace", std::process::id()))
+                });
+                std::env::set_var(MOVE_VM_TRACE_ENV, trace_file.as_os_str());
+                trace_file
+            }
+        };
+        // the VM appends to the trace file, so only read the trace written by this execution.
+        let trace_offset = std::fs::metadata(trace_file.as_path())
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let output: TransactionOutputView = playground::dry_run(
+            &state_reader,
+            DryRunTransaction {
+                raw_txn,
+                public_key,
+            },
+        )
+        .map(|(_, output)| output.into())?;
+        let trace = read_trace(trace_file.as_path(), trace_offset)?;
+        if trace.is_empty() {
+            bail!(
+                "no Move VM execution trace in {:?}, the `{}` env may be set by a previous execution",
+                trace_file,
+                MOVE_VM_TRACE_ENV
+            );
+        }
+
+        let mut profiler = GasProfiler::new(&state_reader, &cost_table, script);
+        for line in trace.lines() {
+            if let Some((function, pc)) = parse_trace_line(line) {
+                profiler.record(function, pc)?;
+            }
+        }
+        Ok(profiler.into_view(
+            output.status,
+            output.gas_used.0,
+            cost_table.gas_constants.gas_unit_scaling_factor,
+            trace_file,
+        ))
+    }
+}
+
+fn read_trace(trace_file: &Path, offset: u64) -> Result<String> {
+    let mut trace = String::new();
+    if !trace_file.exists() {
+        return Ok(trace);
+    }
+    let mut file = File::open(trace_file)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_to_string(&mut trace)?;
+    Ok(trace)
+}
+
+/// Parse a line of the Move VM execution trace, the format is `[exec_id,]function,pc`,
+/// and the function is `address::Module::function` or `Script::main`.
+pub(crate) fn parse_trace_line(line: &str) -> Option<(&str, u16)> {
+    let mut splits = line.rsplit(',');
+    let pc = splits.next()?.trim().parse::<u16>().ok()?;
+    let function = splits.next()?.trim();
+    Some((function, pc))
+}
+
+/// The instruction name without operands, such as `CopyLoc` for `CopyLoc(0)`.
+pub(crate) fn instruction_name(instr: &Bytecode) -> String {
+    let name = format!("{:?}", instr);
+    match name.find('(') {
+        Some(idx) => name[..idx].to_string(),
+        None => name,
+    }
+}
+
+fn instruction_gas(cost_table: &CostTable, instr: &Bytecode) -> u64 {
+    let cost = &cost_table.instruction_table[(instruction_key(instr) - 1) as usize];
+    cost.instruction_gas.get() + cost.memory_gas.get()
+}
+
+struct GasProfiler<'a> {
+    state_view: &'a dyn StateView,
+    cost_table: &'a CostTable,
+    script: Option<CompiledScript>,
+    modules: BTreeMap<ModuleId, CompiledModule>,
+    instructions: BTreeMap<String, (u64, u64)>,
+    natives: BTreeMap<String, u64>,
+    module_gas: BTreeMap<String, (u64, u64)>,
+}
+
+impl<'a> GasProfiler<'a> {
+    fn new(
+        state_view: &'a dyn StateView,
+        cost_table: &'a CostTable,
+        script: Option<CompiledScript>,
+    ) -> Self {
+        Self {
+            state_view,
+            cost_table,
+            script,
+            modules: BTreeMap::new(),
+            instructions: BTreeMap::new(),
+            natives: BTreeMap::new(),
+            module_gas: BTreeMap::new(),
+        }
+    }
+
+    fn load_module(&mut self, module_id: &ModuleId) -> Result<&CompiledModule> {
+        if !self.modules.contains_key(module_id) {
+            let bytes = self
+                .state_view
+                .get(&AccessPath::from(module_id))?
+                .ok_or_else(|| format_err!("cannot find module {} on chain", module_id))?;
+            let module = CompiledModule::deserialize(bytes.as_slice())
+                .map_err(|e| format_err!("deserialize module {} error: {:?}", module_id, e))?;
+            self.modules.insert(module_id.clone(), module);
+        }
+        Ok(&self.modules[module_id])
+    }
+
+    /// Record an executed instruction at `pc` of the `function`.
+    fn record(&mut self, function: &str, pc: u16) -> Result<()> {
+        let segs = function.split("::").collect::<Vec<_>>();
+        let (module_name, instr, call_target) = match segs.as_slice() {
+            [address, module_name, function_name] => {
+                let module_id = ModuleId::new(
+                    AccountAddress::from_hex_literal(address)?,
+                    Identifier::new(*module_name)?,
+                );
+                let module = self.load_module(&module_id)?;
+                let code = module
+                    .function_defs()
+                    .iter()
+                    .find(|def| {
+                        module
+                            .identifier_at(module.function_handle_at(def.function).name)
+                            .as_str()
+                            == *function_name
+                    })
+                    .and_then(|def| def.code.as_ref())
+                    .ok_or_else(|| format_err!("cannot find code of function {}", function))?;
+                let instr = code
+                    .code
+                    .get(pc as usize)
+                    .ok_or_else(|| format_err!("invalid pc {} of function {}", pc, function))?
+                    .clone();
+                let call_target = module_call_target(module, &instr);
+                (module_id.to_string(), instr, call_target)
+            }
+            ["Script", "main"] => {
+                let script = self
+                    .script
+                    .as_ref()
+                    .ok_or_else(|| format_err!("script is not available for trace {}", function))?;
+                let instr = script
+                    .code()
+                    .code
+                    .get(pc as usize)
+                    .ok_or_else(|| format_err!("invalid pc {} of script", pc))?
+                    .clone();
+                let call_target = script_call_target(script, &instr);
+                ("Script".to_string(), instr, call_target)
+            }
+            _ => bail!("unknown function {} in trace", function),
+        };
+
+        let gas = instruction_gas(self.cost_table, &instr);
+        let instruction = self
+            .instructions
+            .entry(instruction_name(&instr))
+            .or_default();
+        instruction.0 += 1;
+        instruction.1 += gas;
+        let module = self.module_gas.entry(module_name).or_default();
+        module.0 += 1;
+        module.1 += gas;
+
+        if let Some((module_id, function_name)) = call_target {
+            let module = self.load_module(&module_id)?;
+            let is_native = module.function_defs().iter().any(|def| {
+                def.is_native()
+                    && module.identifier_at(module.function_handle_at(def.function).name)
+                        == function_name.as_ident_str()
+            });
+            if is_native {
+                *self
+                    .natives
+                    .entry(format!("{}::{}", module_id, function_name))
+                    .or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn into_view(
+        self,
+        status: TransactionVMStatus,
+        gas_used: u64,
+        gas_unit_scaling_factor: u64,
+        trace_file: PathBuf,
+    ) -> GasProfileView {
+        let mut instructions = self
+            .instructions
+            .into_iter()
+            .map(|(instruction, (count, internal_gas))| InstructionGasView {
+                instruction,
+                count,
+                internal_gas,
+            })
+            .collect::<Vec<_>>();
+        instructions.sort_by(|a, b| b.internal_gas.cmp(&a.internal_gas));
+        let mut natives = self
+            .natives
+            .into_iter()
+            .map(|(function, count)| NativeCallView { function, count })
+            .collect::<Vec<_>>();
+        natives.sort_by(|a, b| b.count.cmp(&a.count));
+        let estimated_internal_gas: u64 = instructions.iter().map(|i| i.internal_gas).sum();
+        let unattributed_internal_gas = gas_used
+            .saturating_mul(gas_unit_scaling_factor)
+            .saturating_sub(estimated_internal_gas);
+        let mut modules = self
+            .module_gas
+            .into_iter()
+            .map(|(module, (instructions, internal_gas))| ModuleGasView {
+                module,
+                instructions,
+                internal_gas,
+            })
+            .collect::<Vec<_>>();
+        modules.sort_by(|a, b| b.internal_gas.cmp(&a.internal_gas));
+        GasProfileView {
+            status,
+            gas_used,
+            gas_unit_scaling_factor,
+            trace_file,
+            unattributed_internal_gas,
+            instructions,
+            natives,
+            modules,
+        }
+    }
+}
+
+fn module_call_target(module: &CompiledModule, instr: &Bytecode) -> Option<(ModuleId, Identifier)> {
+    let handle = match instr {
+        Bytecode::Call(idx) => module.function_handle_at(*idx),
+        Bytecode::CallGeneric(idx) => {
+            module.function_handle_at(module.function_instantiation_at(*idx).handle)
+        }
+        _ => return None,
+    };
+    let module_handle = module.module_handle_at(handle.module);
+    Some((
+        ModuleId::new(
+            *module.address_identifier_at(module_handle.address),
+            module.identifier_at(module_handle.name).to_owned(),
+        ),
+        module.identifier_at(handle.name).to_owned(),
+    ))
+}
+
+fn script_call_target(script: &CompiledScript, instr: &Bytecode) -> Option<(ModuleId, Identifier)> {
+    let handle = match instr {
+        Bytecode::Call(idx) => script.function_handle_at(*idx),
+        Bytecode::CallGeneric(idx) => {
+            script.function_handle_at(script.function_instantiation_at(*idx).handle)
+        }
+        _ => return None,
+    };
+    let module_handle = script.module_handle_at(handle.module);
+    Some((
+        ModuleId::new(
+            *script.address_identifier_at(module_handle.address),
+            script.identifier_at(module_handle.name).to_owned(),
+        ),
+        script.identifier_at(handle.name).to_owned(),
+    ))
+}
@@ -0,0 +1,171 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::genesis::CustomNetworkSpec;
+use crate::StarcoinOpt;
+use anyhow::{ensure, Result};
+use network_p2p_types::{Multiaddr, MultiaddrWithPeerId};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_config::GENESIS_CONFIG_FILE_NAME;
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+use starcoin_crypto::{HashValue, ValidCryptoMaterialStringExt};
+use starcoin_genesis::Genesis;
+use starcoin_types::peer_info::PeerId;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+
+pub const ASSOCIATION_KEY_FILE_NAME: &str = "association_key";
+
+/// Generate the genesis of a custom network by a toml spec, the output dir contains:
+///  `genesis`: the bcs encoded genesis block.
+///  `genesis_config.json`: the genesis config, start the node with `--net <chain_name>:<chain_id> --genesis-config <path>`.
+///  `association_key`: the generated private key of the association account, if the spec not set the association public key.
+///  `boot_node_<index>/network_key`: the network key of every boot node, copy it to the data dir of the boot node.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "generate")]
+pub struct GenerateGenesisOpt {
+    #[structopt(long = "config", parse(from_os_str))]
+    /// the toml spec of the custom network.
+    config: PathBuf,
+
+    #[structopt(short = "o", long = "output-dir", parse(from_os_str))]
+    /// the dir to save the generated files, default is `./<chain_name>`.
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootNodeView {
+    pub peer_id: PeerId,
+    pub network_key_file: PathBuf,
+    /// The seed address to config in other nodes, `<listen_address>/p2p/<peer_id>`.
+    pub seed: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateGenesisView {
+    pub net: String,
+    pub genesis_block_hash: HashValue,
+    pub genesis_file: PathBuf,
+    pub genesis_config_file: PathBuf,
+    pub association_public_key: String,
+    pub association_key_file: Option<PathBuf>,
+    pub boot_nodes: Vec<BootNodeView>,
+}
+
+/// Write the private key to a new file which is only readable by the owner.
+fn write_key_file(path: &Path, contents: &str) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Generate the genesis files of `spec` to `output_dir`.
+pub(crate) fn generate_genesis(
+    spec: &CustomNetworkSpec,
+    output_dir: &Path,
+) -> Result<GenerateGenesisView> {
+    ensure!(
+        !output_dir.join(Genesis::GENESIS_FILE_NAME).exists(),
+        "The genesis file already exists in {:?}",
+        output_dir
+    );
+    let boot_node_addresses = spec
+        .boot_nodes
+        .iter()
+        .map(|address| address.parse::<Multiaddr>())
+        .collect::<Result<Vec<_>, _>>()?;
+    std::fs::create_dir_all(output_dir)?;
+
+    let mut keygen = KeyGen::from_os_rng();
+    let (association_public_key, association_key_file) = match spec.association_public_key()? {
+        Some(public_key) => (public_key, None),
+        None => {
+            let (private_key, public_key) = keygen.generate_keypair();
+            let key_file = output_dir.join(ASSOCIATION_KEY_FILE_NAME);
+            write_key_file(
+                key_file.as_path(),
+                private_key.to_encoded_string()?.as_str(),
+            )?;
+            (
+                MultiEd25519PublicKey::new(vec![public_key], 1)?,
+                Some(key_file),
+            )
+        }
+    };
+    let timestamp = match spec.timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+    };
+    let net = spec.build_network(association_public_key.clone(), timestamp)?;
+    let genesis = Genesis::build(&net)?;
+    genesis.save(output_dir)?;
+    let genesis_config_file = output_dir.join(GENESIS_CONFIG_FILE_NAME);
+    net.genesis_config().save(genesis_config_file.as_path())?;
+
+    let boot_nodes = boot_node_addresses
+        .into_iter()
+        .enumerate()
+        .map(|(index, address)| {
+            let (private_key, public_key) = keygen.generate_keypair();
+            let node_dir = output_dir.join(format!("boot_node_{}", index));
+            std::fs::create_dir_all(node_dir.as_path())?;
+            let network_key_file = node_dir.join("network_key");
+            // same format as the node key file of NetworkConfig.
+            write_key_file(
+                network_key_file.as_path(),
+                hex::encode(private_key.to_bytes()).as_str(),
+            )?;
+            let peer_id = PeerId::from_ed25519_public_key(public_key);
+            let seed = MultiaddrWithPeerId::new(address, peer_id.clone().into());
+            Ok(BootNodeView {
+                peer_id,
+                network_key_file,
+                seed: seed.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(GenerateGenesisView {
+        net: net.id().to_string(),
+        genesis_block_hash: genesis.block().id(),
+        genesis_file: output_dir.join(Genesis::GENESIS_FILE_NAME),
+        genesis_config_file,
+        association_public_key: association_public_key.to_encoded_string()?,
+        association_key_file,
+        boot_nodes,
+    })
+}
+
+pub struct GenerateGenesisCommand;
+
+impl CommandAction for GenerateGenesisCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = GenerateGenesisOpt;
+    type ReturnItem = GenerateGenesisView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let spec =
+            CustomNetworkSpec::load(std::fs::read_to_string(opt.config.as_path())?.as_str())?;
+        let output_dir = opt
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(spec.chain_name.as_str()));
+        generate_genesis(&spec, output_dir.as_path())
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands to `generate` the genesis of a custom network from a toml spec,
+//! and `validate` that a node data dir is initialized by a generated genesis.
+
+mod generate_cmd;
+mod validate_cmd;
+
+pub use generate_cmd::*;
+pub use validate_cmd::*;
+
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_config::{
+    BuiltinNetworkID, ChainNetwork, GenesisBlockParameter, GenesisBlockParameterConfig,
+};
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+use starcoin_crypto::{HashValue, ValidCryptoMaterialStringExt};
+use starcoin_types::U256;
+use starcoin_vm_types::genesis_config::{ChainId, ConsensusStrategy};
+use std::str::FromStr;
+
+/// The spec of a custom network, the fields absent are inherited from the genesis config of the `base` network.
+///  An example:
+///  ``` toml
+///  chain_name = "my_net"
+///  chain_id = 123
+///  base = "halley"
+///  pre_mine_amount = 1000000000000000
+///  boot_nodes = ["/ip4/192.168.1.10/tcp/9840"]
+///
+///  [consensus]
+///  strategy = "argon"
+///  base_block_time_target = 5000
+///  ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomNetworkSpec {
+    pub chain_name: String,
+    pub chain_id: u8,
+    /// The builtin network whose genesis config is used as the base, default is halley.
+    pub base: Option<String>,
+    /// The genesis block timestamp in milliseconds, default is now.
+    pub timestamp: Option<u64>,
+    pub difficulty: Option<u64>,
+    pub reward_delay: Option<u64>,
+    /// The STC amount(in nanoSTC) pre mined to the association account.
+    pub pre_mine_amount: Option<u128>,
+    /// The STC amount(in nanoSTC) linear time minted to the association account.
+    pub time_mint_amount: Option<u128>,
+    pub time_mint_period: Option<u64>,
+    /// The hex encoded multi-ed25519 public key which controls the association account and the pre mined STC,
+    /// if absent, a new key is generated.
+    pub association_public_key: Option<String>,
+    /// The listen addresses of the boot nodes, a network key is generated for every boot node.
+    #[serde(default)]
+    pub boot_nodes: Vec<String>,
+    pub consensus: Option<ConsensusSpec>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsensusSpec {
+    /// dummy, argon, keccak or cryptonight.
+    pub strategy: Option<String>,
+    pub uncle_rate_target: Option<u64>,
+    pub base_block_time_target: Option<u64>,
+    pub base_reward_per_block: Option<u128>,
+    pub base_reward_per_uncle_percent: Option<u64>,
+    pub epoch_block_count: Option<u64>,
+    pub base_block_difficulty_window: Option<u64>,
+    pub min_block_time_target: Option<u64>,
+    pub max_block_time_target: Option<u64>,
+    pub base_max_uncles_per_block: Option<u64>,
+    pub base_block_gas_limit: Option<u64>,
+}
+
+impl CustomNetworkSpec {
+    pub fn load(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn base(&self) -> Result<BuiltinNetworkID> {
+        match &self.base {
+            Some(base) => BuiltinNetworkID::from_str(base.as_str()),
+            None => Ok(BuiltinNetworkID::Halley),
+        }
+    }
+
+    /// Build the custom network by the spec, the association public key and the genesis timestamp
+    /// must be resolved by caller if they are absent in spec.
+    pub fn build_network(
+        &self,
+        association_public_key: MultiEd25519PublicKey,
+        timestamp: u64,
+    ) -> Result<ChainNetwork> {
+        let base = self.base()?;
+        let mut genesis_config = base.genesis_config().clone();
+        let difficulty = match (self.difficulty, genesis_config.genesis_block_parameter()) {
+            (Some(difficulty), _) => U256::from(difficulty),
+            (None, Some(parameter)) => parameter.difficulty,
+            (None, None) => U256::from(1),
+        };
+        genesis_config.genesis_block_parameter =
+            GenesisBlockParameterConfig::Static(GenesisBlockParameter {
+                parent_hash: HashValue::sha3_256_of(
+                    format!("starcoin_{}", self.chain_name).as_bytes(),
+                ),
+                timestamp,
+                difficulty,
+            });
+        if let Some(reward_delay) = self.reward_delay {
+            genesis_config.reward_delay = reward_delay;
+        }
+        if let Some(pre_mine_amount) = self.pre_mine_amount {
+            genesis_config.pre_mine_amount = pre_mine_amount;
+        }
+        if let Some(time_mint_amount) = self.time_mint_amount {
+            genesis_config.time_mint_amount = time_mint_amount;
+        }
+        if let Some(time_mint_period) = self.time_mint_period {
+            genesis_config.time_mint_period = time_mint_period;
+        }
+        genesis_config.association_key_pair = (None, association_public_key);
+        // the genesis private key is only for test and dev network.
+        genesis_config.genesis_key_pair = None;
+
+        if let Some(consensus) = &self.consensus {
+            let config = &mut genesis_config.consensus_config;
+            if let Some(strategy) = &consensus.strategy {
+                config.strategy = ConsensusStrategy::from_str(strategy.as_str())?.value();
+            }
+            macro_rules! override_fields {
+                ($($field:ident),*) => {
+                    $(if let Some(value) = consensus.$field {
+                        config.$field = value;
+                    })*
+                };
+            }
+            override_fields!(
+                uncle_rate_target,
+                base_block_time_target,
+                base_reward_per_block,
+                base_reward_per_uncle_percent,
+                epoch_block_count,
+                base_block_difficulty_window,
+                min_block_time_target,
+                max_block_time_target,
+                base_max_uncles_per_block,
+                base_block_gas_limit
+            );
+        }
+        ChainNetwork::new_custom(
+            self.chain_name.clone(),
+            ChainId::new(self.chain_id),
+            genesis_config,
+        )
+    }
+
+    pub fn association_public_key(&self) -> Result<Option<MultiEd25519PublicKey>> {
+        self.association_public_key
+            .as_ref()
+            .map(|key| {
+                MultiEd25519PublicKey::from_encoded_string(key.as_str())
+                    .map_err(|e| format_err!("Invalid association public key {}: {:?}", key, e))
+            })
+            .transpose()
+    }
+}
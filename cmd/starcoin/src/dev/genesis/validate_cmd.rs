@@ -0,0 +1,201 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_config::{GenesisConfig, RocksdbConfig, GENESIS_CONFIG_FILE_NAME};
+use starcoin_crypto::HashValue;
+use starcoin_genesis::Genesis;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Validate that the data dir of a node matches a genesis generated by `dev genesis generate`,
+/// the genesis file, the genesis config and the genesis block in db (if the db exists) are checked.
+/// The node must be stopped before validating the db.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "validate")]
+pub struct ValidateGenesisOpt {
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    /// the data dir of the node, such as ~/.starcoin/my_net.
+    data_dir: PathBuf,
+
+    #[structopt(long = "genesis", parse(from_os_str))]
+    /// the genesis file, or the output dir of `dev genesis generate`.
+    genesis: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisCheckView {
+    pub item: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateGenesisView {
+    pub genesis_block_hash: HashValue,
+    pub valid: bool,
+    pub checks: Vec<GenesisCheckView>,
+}
+
+impl GenesisCheckView {
+    fn new(item: &str, passed: bool, message: String) -> Self {
+        Self {
+            item: item.to_string(),
+            passed,
+            message,
+        }
+    }
+}
+
+fn load_genesis(path: &Path) -> Result<Genesis> {
+    if path.is_dir() {
+        Genesis::load_from_dir(path)?
+            .ok_or_else(|| format_err!("Can not find genesis file in {:?}", path))
+    } else {
+        bcs_ext::from_bytes(std::fs::read(path)?.as_slice())
+    }
+}
+
+fn check_genesis_file(expect: &Genesis, data_dir: &Path) -> GenesisCheckView {
+    let item = "genesis_file";
+    match Genesis::load_from_dir(data_dir) {
+        Ok(Some(genesis)) if genesis.block().id() == expect.block().id() => {
+            GenesisCheckView::new(item, true, "genesis block matched".to_string())
+        }
+        Ok(Some(genesis)) => GenesisCheckView::new(
+            item,
+            false,
+            format!("genesis block mismatch, real: {}", genesis.block().id()),
+        ),
+        Ok(None) => GenesisCheckView::new(item, false, "genesis file not exist".to_string()),
+        Err(e) => GenesisCheckView::new(item, false, format!("load genesis failed: {}", e)),
+    }
+}
+
+fn check_genesis_config(expect: Option<&GenesisConfig>, data_dir: &Path) -> GenesisCheckView {
+    let item = "genesis_config";
+    let config_path = data_dir.join(GENESIS_CONFIG_FILE_NAME);
+    if !config_path.exists() {
+        return GenesisCheckView::new(item, false, "genesis config not exist".to_string());
+    }
+    match (GenesisConfig::load(config_path), expect) {
+        (Ok(config), Some(expect)) if &config == expect => {
+            GenesisCheckView::new(item, true, "genesis config matched".to_string())
+        }
+        (Ok(_), Some(_)) => {
+            GenesisCheckView::new(item, false, "genesis config mismatch".to_string())
+        }
+        (Ok(config), None) => match config.genesis_block_parameter() {
+            Some(_) => GenesisCheckView::new(
+                item,
+                true,
+                "genesis config exists, no expected genesis config to compare".to_string(),
+            ),
+            None => GenesisCheckView::new(
+                item,
+                false,
+                "genesis block parameter is not resolved".to_string(),
+            ),
+        },
+        (Err(e), _) => {
+            GenesisCheckView::new(item, false, format!("load genesis config failed: {}", e))
+        }
+    }
+}
+
+fn check_genesis_db(expect: &Genesis, data_dir: &Path) -> Option<GenesisCheckView> {
+    let item = "genesis_db";
+    let db_dir = data_dir.join("starcoindb").join("db");
+    if !db_dir.exists() {
+        return None;
+    }
+    let result = (|| -> Result<GenesisCheckView> {
+        let storage = Storage::new(StorageInstance::new_db_instance(DBStorage::new(
+            db_dir,
+            RocksdbConfig::default(),
+        )?))?;
+        let expect_id = expect.block().id();
+        Ok(match storage.get_genesis()? {
+            Some(genesis_hash) if genesis_hash == expect_id => {
+                match storage.get_block(genesis_hash)? {
+                    Some(block) if &block == expect.block() => {
+                        GenesisCheckView::new(item, true, "genesis block matched".to_string())
+                    }
+                    Some(_) => GenesisCheckView::new(
+                        item,
+                        false,
+                        "genesis block in db is not same as the genesis file".to_string(),
+                    ),
+                    None => GenesisCheckView::new(
+                        item,
+                        false,
+                        "genesis block not exist in db".to_string(),
+                    ),
+                }
+            }
+            Some(genesis_hash) => GenesisCheckView::new(
+                item,
+                false,
+                format!("genesis block mismatch, real: {}", genesis_hash),
+            ),
+            None => GenesisCheckView::new(item, false, "db is not initialized".to_string()),
+        })
+    })();
+    Some(result.unwrap_or_else(|e| {
+        GenesisCheckView::new(
+            item,
+            false,
+            format!("open db failed, please stop the node first: {}", e),
+        )
+    }))
+}
+
+/// Check the data dir against the genesis file at `genesis_path`,
+/// if `genesis_path` is a dir, also check the genesis config in it.
+pub(crate) fn validate_genesis(
+    data_dir: &Path,
+    genesis_path: &Path,
+) -> Result<ValidateGenesisView> {
+    let genesis = load_genesis(genesis_path)?;
+    let genesis_config_path = genesis_path.join(GENESIS_CONFIG_FILE_NAME);
+    let expect_config = if genesis_path.is_dir() && genesis_config_path.exists() {
+        Some(GenesisConfig::load(genesis_config_path)?)
+    } else {
+        None
+    };
+
+    let mut checks = vec![
+        check_genesis_file(&genesis, data_dir),
+        check_genesis_config(expect_config.as_ref(), data_dir),
+    ];
+    checks.extend(check_genesis_db(&genesis, data_dir));
+    Ok(ValidateGenesisView {
+        genesis_block_hash: genesis.block().id(),
+        valid: checks.iter().all(|check| check.passed),
+        checks,
+    })
+}
+
+pub struct ValidateGenesisCommand;
+
+impl CommandAction for ValidateGenesisCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ValidateGenesisOpt;
+    type ReturnItem = ValidateGenesisView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        validate_genesis(opt.data_dir.as_path(), opt.genesis.as_path())
+    }
+}
@@ -3,25 +3,36 @@
 
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
-use anyhow::{bail, format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_crypto::ValidCryptoMaterial;
 use starcoin_executor::{DEFAULT_EXPIRATION_TIME, DEFAULT_MAX_GAS_AMOUNT};
 use starcoin_rpc_api::types::SignedUserTransactionView;
-use starcoin_rpc_client::RemoteStateReader;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_config;
+use starcoin_vm_types::account_address::AccountAddress;
 use std::convert::TryInto;
+use std::time::Instant;
 use structopt::StructOpt;
 use tokio::time::Duration;
 
+const FAUCET_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Get coin to default account.
-/// This command only available in dev network.
+/// With `--faucet-url`, request the coin from a remote faucet service (see `cmd/faucet`),
+/// otherwise mint by the association account, which is only available in test or dev network.
+/// The command waits until the coin lands unless `--no-blocking`.
 #[derive(Debug, StructOpt, Default)]
 #[structopt(name = "get_coin")]
 pub struct GetCoinOpt {
-    #[structopt(short = "v")]
-    /// if amount absent, transfer 20% of association_address's balance.
+    #[structopt(short = "v", long = "amount")]
+    /// if amount absent, transfer 20% of association_address's balance, required by the faucet.
     amount: Option<u128>,
+    #[structopt(long = "faucet-url")]
+    /// the url of the faucet service, the coin is requested by `<faucet-url>/api/fund`.
+    faucet_url: Option<String>,
     #[structopt(
         name = "no-blocking-mode",
         long = "no-blocking",
@@ -30,13 +41,84 @@ pub struct GetCoinOpt {
     no_blocking: bool,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GetCoinView {
+    pub receiver: AccountAddress,
+    pub amount: u128,
+    /// the txn sent by the association account, None if the coin is from the faucet.
+    pub txn: Option<SignedUserTransactionView>,
+    /// the balance after the coin landed, None in no blocking mode.
+    pub balance: Option<u128>,
+}
+
+/// Request `amount` coin for `receiver` from the faucet, the public key is used to create the account if not exists.
+fn request_faucet(
+    faucet_url: &str,
+    receiver: AccountAddress,
+    public_key: &[u8],
+    amount: u128,
+) -> Result<()> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(FAUCET_REQUEST_TIMEOUT)
+        .build()?;
+    let response = client
+        .get(format!("{}/api/fund", faucet_url.trim_end_matches('/')).as_str())
+        .query(&[
+            ("address", receiver.to_string()),
+            ("amount", amount.to_string()),
+            ("public_key", hex::encode(public_key)),
+        ])
+        .send()?;
+    let status = response.status();
+    let body = response.text()?;
+    ensure!(
+        status.is_success(),
+        "faucet request failed, status: {}, response: {}",
+        status,
+        body
+    );
+    Ok(())
+}
+
+fn get_balance(client: &RpcClient, address: AccountAddress) -> Result<u128> {
+    let chain_state_reader = RemoteStateReader::new(client)?;
+    Ok(AccountStateReader::new(&chain_state_reader)
+        .get_balance(&address)?
+        .unwrap_or_default())
+}
+
+/// Wait until the balance of `address` reach `expect`.
+fn wait_balance(
+    client: &RpcClient,
+    address: AccountAddress,
+    expect: u128,
+    timeout: Duration,
+) -> Result<u128> {
+    let start = Instant::now();
+    loop {
+        let balance = get_balance(client, address)?;
+        if balance >= expect {
+            return Ok(balance);
+        }
+        if start.elapsed() > timeout {
+            bail!(
+                "wait coin timeout, the balance of {} is {}, expect {}",
+                address,
+                balance,
+                expect
+            );
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
 pub struct GetCoinCommand;
 
 impl CommandAction for GetCoinCommand {
     type State = CliState;
     type GlobalOpt = StarcoinOpt;
     type Opt = GetCoinOpt;
-    type ReturnItem = SignedUserTransactionView;
+    type ReturnItem = GetCoinView;
 
     fn run(
         &self,
@@ -44,18 +126,50 @@ impl CommandAction for GetCoinCommand {
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
         let net = ctx.state().net();
+        let client = ctx.state().client();
+        let to = client.account_default()?.ok_or_else(|| {
+            format_err!("Can not find default account, Please create account first.")
+        })?;
+
+        if let Some(faucet_url) = opt.faucet_url.as_ref() {
+            let amount = opt
+                .amount
+                .ok_or_else(|| format_err!("amount is required by the faucet"))?;
+            let public_key = to.public_key.as_single().ok_or_else(|| {
+                format_err!("the faucet only supports the account with a single ed25519 key")
+            })?;
+            let balance_before = get_balance(client, to.address)?;
+            request_faucet(
+                faucet_url,
+                to.address,
+                public_key.to_bytes().as_slice(),
+                amount,
+            )?;
+            let balance = if !opt.no_blocking {
+                Some(wait_balance(
+                    client,
+                    to.address,
+                    balance_before + amount,
+                    ctx.state().watch_timeout(),
+                )?)
+            } else {
+                None
+            };
+            return Ok(GetCoinView {
+                receiver: to.address,
+                amount,
+                txn: None,
+                balance,
+            });
+        }
+
         if !net.is_test_or_dev() {
             bail!(
-                "This command only available in test or dev network, current network is: {}",
+                "This command only available in test or dev network without --faucet-url, current network is: {}",
                 net
             );
         }
-        let client = ctx.state().client();
         let node_info = client.node_info()?;
-        let to = client.account_default()?.ok_or_else(|| {
-            format_err!("Can not find default account, Please create account first.")
-        })?;
-
         let association_address = account_config::association_address();
         let chain_state_reader = RemoteStateReader::new(client)?;
         let account_state_reader = AccountStateReader::new(&chain_state_reader);
@@ -95,9 +209,17 @@ impl CommandAction for GetCoinCommand {
         let txn = client.account_sign_txn(raw_txn)?;
         let id = txn.id();
         client.submit_transaction(txn.clone())?;
-        if !opt.no_blocking {
+        let balance = if !opt.no_blocking {
             ctx.state().watch_txn(id)?;
-        }
-        txn.try_into()
+            Some(get_balance(client, to.address)?)
+        } else {
+            None
+        };
+        Ok(GetCoinView {
+            receiver: to.address,
+            amount,
+            txn: Some(txn.try_into()?),
+            balance,
+        })
     }
 }
@@ -0,0 +1,82 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use starcoin_config::BuiltinNetworkID;
+use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_logger::prelude::*;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+use structopt::StructOpt;
+use test_helper::LocalCluster;
+
+/// Spin up a network of local nodes with separate data dirs, ports and keys, fund test accounts,
+/// and stop all nodes when receiving Ctrl-C.
+///  Some examples:
+///  ``` shell
+///  starcoin dev localnet -n dev --nodes 3 --fund-count 2
+///  ```
+#[derive(Clone, Debug, StructOpt)]
+#[structopt(name = "localnet")]
+pub struct LocalnetOpt {
+    #[structopt(short = "n", long, default_value = "dev")]
+    /// Chain network, only test and dev are supported.
+    pub net: BuiltinNetworkID,
+    #[structopt(long, default_value = "3")]
+    /// The number of nodes in the network.
+    pub nodes: usize,
+    #[structopt(long, default_value = "0")]
+    /// The number of new accounts to fund after the network started.
+    pub fund_count: usize,
+    #[structopt(long, default_value = "1000000000000")]
+    /// The STC amount (in nanoSTC) funded to each new account.
+    pub fund_amount: u128,
+    #[structopt(long)]
+    /// Do not mine blocks automatically, the funding txns are packaged by a generated block.
+    pub disable_auto_mine: bool,
+    #[structopt(long, default_value = "60")]
+    /// Timeout in seconds for waiting the nodes to connect and sync.
+    pub timeout: u64,
+}
+
+/// The nodes run in the current process, so the command is executed without connecting to
+/// (or starting) a node, and it returns after all nodes are stopped.
+pub fn run_localnet(opt: LocalnetOpt) -> Result<()> {
+    let timeout = Duration::from_secs(opt.timeout);
+    let cluster = LocalCluster::start(opt.net, opt.nodes, !opt.disable_auto_mine, timeout)?;
+    for (i, node) in cluster.nodes().iter().enumerate() {
+        let config = node.config();
+        println!("Node {}:", i);
+        println!("  peer id: {}", config.network.self_peer_id());
+        println!("  network address: {}", config.network.self_address());
+        println!("  data dir: {}", config.data_dir().display());
+        println!("  ipc file: {}", config.rpc.get_ipc_file().display());
+        if let Some(address) = config.rpc.get_http_address() {
+            println!("  http rpc: {}", address);
+        }
+        if let Some(address) = config.rpc.get_ws_address() {
+            println!("  websocket rpc: {}", address);
+        }
+    }
+    if opt.fund_count > 0 {
+        let accounts = cluster.fund_new_accounts(opt.fund_count, opt.fund_amount)?;
+        cluster.wait_synced(timeout)?;
+        println!("Funded accounts:");
+        for account in accounts {
+            println!(
+                "  address: {}, private key: {}",
+                account.address(),
+                account.private_key().to_encoded_string()?
+            );
+        }
+    }
+
+    let (sender, receiver) = channel();
+    ctrlc::set_handler(move || {
+        let _ = sender.send(());
+    })?;
+    println!("Local network started, press Ctrl-C to stop.");
+    receiver.recv()?;
+    info!("Stopping local network.");
+    cluster.stop()
+}
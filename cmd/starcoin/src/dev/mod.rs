@@ -1,18 +1,40 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod bench_cmd;
 mod call_contract_cmd;
+mod cast_vote_cmd;
 mod compile_cmd;
+mod decode_cmd;
 mod deploy_cmd;
 mod derive_account_address_cmd;
+mod dry_run_cmd;
+mod estimate_gas_cmd;
+mod fork_exec_cmd;
+mod gas_profile_cmd;
 mod generate_multisig_txn_cmd;
+pub mod genesis;
 mod get_coin_cmd;
+mod localnet_cmd;
+mod module_diff_cmd;
 mod package_cmd;
+pub mod proposal;
+mod propose_cmd;
+mod publish_package_cmd;
+mod replay_txn_cmd;
+pub(crate) mod script_function_abi;
+mod seed_cmd;
+mod show_config_cmd;
+mod sign_peer_identity_cmd;
 pub(crate) mod sign_txn_helper;
+pub mod state;
 mod submit_multisig_txn_cmd;
 mod subscribe_cmd;
 #[cfg(test)]
 mod tests;
+mod treasury_withdraw_exe_cmd;
+mod treasury_withdraw_proposal_cmd;
+mod unstake_votes_cmd;
 mod upgrade_module_exe_cmd;
 mod upgrade_module_plan_cmd;
 mod upgrade_module_proposal_cmd;
@@ -20,17 +42,36 @@ mod upgrade_module_proposal_v2_cmd;
 mod upgrade_module_queue_cmd;
 mod upgrade_module_queue_v2_cmd;
 mod upgrade_vm_config_proposal_cmd;
+mod verify_package_cmd;
 
+pub use bench_cmd::*;
 pub use call_contract_cmd::*;
+pub use cast_vote_cmd::*;
 pub use compile_cmd::*;
+pub use decode_cmd::*;
 pub use deploy_cmd::*;
 pub use derive_account_address_cmd::*;
+pub use dry_run_cmd::*;
+pub use estimate_gas_cmd::*;
+pub use fork_exec_cmd::*;
+pub use gas_profile_cmd::*;
 pub use generate_multisig_txn_cmd::*;
 pub use get_coin_cmd::*;
+pub use localnet_cmd::*;
+pub use module_diff_cmd::*;
 pub use package_cmd::*;
+pub use propose_cmd::*;
+pub use publish_package_cmd::*;
+pub use replay_txn_cmd::*;
+pub use seed_cmd::*;
+pub use show_config_cmd::*;
+pub use sign_peer_identity_cmd::*;
 pub use sign_txn_helper::sign_txn_with_account_by_rpc_client;
 pub use submit_multisig_txn_cmd::*;
 pub use subscribe_cmd::*;
+pub use treasury_withdraw_exe_cmd::*;
+pub use treasury_withdraw_proposal_cmd::*;
+pub use unstake_votes_cmd::*;
 pub use upgrade_module_exe_cmd::*;
 pub use upgrade_module_plan_cmd::*;
 pub use upgrade_module_proposal_cmd::*;
@@ -38,3 +79,4 @@ pub use upgrade_module_proposal_v2_cmd::*;
 pub use upgrade_module_queue_cmd::*;
 pub use upgrade_module_queue_v2_cmd::*;
 pub use upgrade_vm_config_proposal_cmd::*;
+pub use verify_package_cmd::*;
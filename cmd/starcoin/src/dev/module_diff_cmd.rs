@@ -0,0 +1,209 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_types::transaction::Package;
+use starcoin_vm_types::access::ModuleAccess;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::compatibility::Compatibility;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::normalized::Module;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "module-diff")]
+/// Compare the modules of a package with the modules published at the package address,
+/// and report the added, removed and changed functions and structs, and the compatibility of every module,
+/// before filing an upgrade proposal.
+pub struct ModuleDiffOpt {
+    #[structopt(long = "package", name = "package", parse(from_os_str))]
+    /// path of the package file, such as the blob file generated by `dev package`.
+    package: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ModuleChange {
+    /// the module is not published on chain.
+    Added,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CompatibilityView {
+    /// the public functions and structs used by other modules are still linkable.
+    pub struct_and_function_linking: bool,
+    /// the layout of the structs are not changed, so the published resources can be read.
+    pub struct_layout: bool,
+    pub fully_compatible: bool,
+}
+
+impl From<Compatibility> for CompatibilityView {
+    fn from(compatibility: Compatibility) -> Self {
+        Self {
+            struct_and_function_linking: compatibility.struct_and_function_linking,
+            struct_layout: compatibility.struct_layout,
+            fully_compatible: compatibility.is_fully_compatible(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDiffView {
+    pub name: Identifier,
+    pub change: ModuleChange,
+    pub added_functions: Vec<Identifier>,
+    pub removed_functions: Vec<Identifier>,
+    /// public functions whose signature is changed.
+    pub changed_functions: Vec<Identifier>,
+    pub added_structs: Vec<Identifier>,
+    pub removed_structs: Vec<Identifier>,
+    /// structs whose abilities, type parameters or fields are changed.
+    pub changed_structs: Vec<Identifier>,
+    /// None if the module is not published on chain.
+    pub compatibility: Option<CompatibilityView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageDiffView {
+    pub package_address: AccountAddress,
+    pub modules: Vec<ModuleDiffView>,
+    /// modules published on chain but not in the package, they are kept as is after upgrade.
+    pub not_in_package: Vec<Identifier>,
+    /// all modules in the package are fully compatible with the published ones,
+    /// an incompatible upgrade requires an enforced upgrade proposal.
+    pub fully_compatible: bool,
+}
+
+/// Diff the exported functions and structs of the `new` module with the published `old` module.
+pub(crate) fn diff_module(old: Option<&CompiledModule>, new: &CompiledModule) -> ModuleDiffView {
+    let name = new.self_id().name().to_owned();
+    let new = Module::new(new);
+    let old = match old {
+        Some(old) => Module::new(old),
+        None => {
+            return ModuleDiffView {
+                name,
+                change: ModuleChange::Added,
+                added_functions: new.exported_functions.keys().cloned().collect(),
+                removed_functions: vec![],
+                changed_functions: vec![],
+                added_structs: new.structs.keys().cloned().collect(),
+                removed_structs: vec![],
+                changed_structs: vec![],
+                compatibility: None,
+            };
+        }
+    };
+    let (added_functions, removed_functions, changed_functions) =
+        diff_map(&old.exported_functions, &new.exported_functions);
+    let (added_structs, removed_structs, changed_structs) = diff_map(&old.structs, &new.structs);
+    let change = if old == new {
+        ModuleChange::Unchanged
+    } else {
+        ModuleChange::Changed
+    };
+    ModuleDiffView {
+        name,
+        change,
+        added_functions,
+        removed_functions,
+        changed_functions,
+        added_structs,
+        removed_structs,
+        changed_structs,
+        compatibility: Some(Compatibility::check(&old, &new).into()),
+    }
+}
+
+/// Return the added, removed and changed keys of `new` compared with `old`.
+fn diff_map<V: PartialEq>(
+    old: &BTreeMap<Identifier, V>,
+    new: &BTreeMap<Identifier, V>,
+) -> (Vec<Identifier>, Vec<Identifier>, Vec<Identifier>) {
+    let added = new
+        .keys()
+        .filter(|k| !old.contains_key(*k))
+        .cloned()
+        .collect();
+    let removed = old
+        .keys()
+        .filter(|k| !new.contains_key(*k))
+        .cloned()
+        .collect();
+    let changed = new
+        .iter()
+        .filter(|(k, v)| old.get(*k).map(|old_v| old_v != *v).unwrap_or(false))
+        .map(|(k, _)| k.clone())
+        .collect();
+    (added, removed, changed)
+}
+
+pub struct ModuleDiffCommand;
+
+impl CommandAction for ModuleDiffCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ModuleDiffOpt;
+    type ReturnItem = PackageDiffView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let mut bytes = vec![];
+        File::open(opt.package.as_path())?.read_to_end(&mut bytes)?;
+        let package: Package = bcs_ext::from_bytes(&bytes)?;
+        let package_address = package.package_address();
+
+        let published = client
+            .get_account_state_set(package_address)?
+            .map(|state_set| state_set.codes)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, code)| {
+                let module = CompiledModule::deserialize(code.0.as_slice()).map_err(|e| {
+                    format_err!("deserialize module {} on chain error: {:?}", name, e)
+                })?;
+                Ok((name, module))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        let mut modules = vec![];
+        let mut package_modules = BTreeSet::new();
+        for module in package.modules() {
+            let new = CompiledModule::deserialize(module.code())
+                .map_err(|e| format_err!("deserialize module in package error: {:?}", e))?;
+            let name = new.self_id().name().to_owned();
+            modules.push(diff_module(published.get(&name), &new));
+            package_modules.insert(name);
+        }
+        let fully_compatible = modules.iter().all(|m| {
+            m.compatibility
+                .as_ref()
+                .map(|c| c.fully_compatible)
+                .unwrap_or(true)
+        });
+        Ok(PackageDiffView {
+            package_address,
+            modules,
+            not_in_package: published
+                .keys()
+                .filter(|name| !package_modules.contains(*name))
+                .cloned()
+                .collect(),
+            fully_compatible,
+        })
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::proposal::{list_proposals, ProposalView};
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "list")]
+/// List the proposals of any action type created by the proposers.
+pub struct ListProposalOpt {
+    #[structopt(short = "a", long = "proposer", name = "proposer")]
+    /// the proposers, default to the default account.
+    proposers: Option<Vec<AccountAddress>>,
+
+    #[structopt(short = "t", long = "token", parse(try_from_str = parse_type_tag))]
+    /// only list the proposals of the DAO of the token, such as 0x1::STC::STC
+    token: Option<TypeTag>,
+}
+
+pub struct ListProposalCommand;
+
+impl CommandAction for ListProposalCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ListProposalOpt;
+    type ReturnItem = Vec<ProposalView>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let proposers = match &opt.proposers {
+            Some(proposers) => proposers.clone(),
+            None => vec![ctx.state().default_account()?.address],
+        };
+        let mut proposals = vec![];
+        for proposer in proposers {
+            proposals.extend(list_proposals(
+                ctx.state().client(),
+                proposer,
+                opt.token.as_ref(),
+            )?);
+        }
+        Ok(proposals)
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands to go through the lifecycle of DAO proposals of any action type:
+//! `list`/`show` the proposals, `vote` on an active proposal, `queue` the agreed proposal,
+//! `execute` the executable proposal, and `revoke` the vote, or `wait` until the proposal is executable.
+
+mod execute_cmd;
+mod list_cmd;
+mod queue_cmd;
+mod revoke_cmd;
+mod show_cmd;
+mod vote_cmd;
+mod wait_cmd;
+
+pub use execute_cmd::*;
+pub use list_cmd::*;
+pub use queue_cmd::*;
+pub use revoke_cmd::*;
+pub use show_cmd::*;
+pub use vote_cmd::*;
+pub use wait_cmd::*;
+
+use anyhow::{bail, ensure, format_err, Result};
+use serde::Serialize;
+use starcoin_rpc_api::types::{
+    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, FunctionIdView, StrView,
+    TypeTagView,
+};
+use starcoin_rpc_client::RpcClient;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::account_config::core_code_address;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::{FunctionId, ModuleId, StructTag, TypeTag};
+use starcoin_vm_types::transaction::ScriptFunction;
+use starcoin_vm_types::transaction_argument::TransactionArgument;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The state of proposal, same as the state constants in `0x1::Dao`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum ProposalState {
+    Pending,
+    Active,
+    Defeated,
+    Agreed,
+    Queued,
+    Executable,
+    Extracted,
+}
+
+impl TryFrom<u8> for ProposalState {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Ok(match value {
+            1 => ProposalState::Pending,
+            2 => ProposalState::Active,
+            3 => ProposalState::Defeated,
+            4 => ProposalState::Agreed,
+            5 => ProposalState::Queued,
+            6 => ProposalState::Executable,
+            7 => ProposalState::Extracted,
+            _ => bail!("unknown proposal state {}", value),
+        })
+    }
+}
+
+impl fmt::Display for ProposalState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProposalView {
+    pub proposer: AccountAddress,
+    pub id: u64,
+    /// the token type of the DAO.
+    pub token: TypeTagView,
+    /// the action type of the proposal.
+    pub action: TypeTagView,
+    pub state: ProposalState,
+    /// voting start time, in milliseconds.
+    pub start_time: u64,
+    /// voting end time, in milliseconds.
+    pub end_time: u64,
+    pub for_votes: u128,
+    pub against_votes: u128,
+    pub quorum_votes: u128,
+    /// the delay (in milliseconds) between queued and executable.
+    pub action_delay: u64,
+    /// executable after this time, in milliseconds, 0 if the proposal is not queued.
+    pub eta: u64,
+}
+
+impl ProposalView {
+    /// Ensure the proposal is in the `expected` state, to fail fast before submitting txn.
+    pub fn ensure_state(&self, expected: ProposalState, operation: &str) -> Result<()> {
+        ensure!(
+            self.state == expected,
+            "can not {} proposal {} of {}, expect state {}, but current state is {}",
+            operation,
+            self.id,
+            self.proposer,
+            expected,
+            self.state
+        );
+        Ok(())
+    }
+}
+
+fn dao_module() -> ModuleId {
+    ModuleId::new(core_code_address(), Identifier::new("Dao").unwrap())
+}
+
+fn is_proposal_resource(tag: &StructTag) -> bool {
+    tag.address == core_code_address()
+        && tag.module.as_str() == "Dao"
+        && tag.name.as_str() == "Proposal"
+        && tag.type_params.len() == 2
+}
+
+fn get_field<'a>(
+    resource: &'a AnnotatedMoveStructView,
+    name: &str,
+) -> Result<&'a AnnotatedMoveValueView> {
+    resource
+        .value
+        .iter()
+        .find(|(field, _)| field.as_str() == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| format_err!("field {} not exists in proposal", name))
+}
+
+fn get_u64_field(resource: &AnnotatedMoveStructView, name: &str) -> Result<u64> {
+    match get_field(resource, name)? {
+        AnnotatedMoveValueView::U64(v) => Ok(v.0),
+        v => bail!("unexpected value of field {}: {:?}", name, v),
+    }
+}
+
+fn get_u128_field(resource: &AnnotatedMoveStructView, name: &str) -> Result<u128> {
+    match get_field(resource, name)? {
+        AnnotatedMoveValueView::U128(v) => Ok(v.0),
+        v => bail!("unexpected value of field {}: {:?}", name, v),
+    }
+}
+
+/// Get the current state of proposal by `0x1::Dao::proposal_state`, which depends on the on chain time.
+fn get_proposal_state(
+    client: &RpcClient,
+    proposer: AccountAddress,
+    proposal_id: u64,
+    token: TypeTag,
+    action: TypeTag,
+) -> Result<ProposalState> {
+    let call = ContractCall {
+        function_id: FunctionIdView(FunctionId {
+            module: dao_module(),
+            function: Identifier::new("proposal_state").unwrap(),
+        }),
+        type_args: vec![StrView(token), StrView(action)],
+        args: vec![
+            StrView(TransactionArgument::Address(proposer)),
+            StrView(TransactionArgument::U64(proposal_id)),
+        ],
+    };
+    match client.contract_call(call)?.get(0) {
+        Some(AnnotatedMoveValueView::U8(state)) => ProposalState::try_from(*state),
+        v => bail!("unexpected proposal state: {:?}", v),
+    }
+}
+
+/// List the proposals created by `proposer`, filtered by `token` if present.
+/// A proposer can only have one proposal for each pair of token and action type at the same time.
+pub(crate) fn list_proposals(
+    client: &RpcClient,
+    proposer: AccountAddress,
+    token: Option<&TypeTag>,
+) -> Result<Vec<ProposalView>> {
+    let state_set = match client.get_account_state_set(proposer)? {
+        Some(state_set) => state_set,
+        None => return Ok(vec![]),
+    };
+    state_set
+        .resources
+        .iter()
+        .filter(|(tag, _)| is_proposal_resource(&tag.0))
+        .filter(|(tag, _)| token.map_or(true, |token| &tag.0.type_params[0] == token))
+        .map(|(tag, resource)| {
+            let token = tag.0.type_params[0].clone();
+            let action = tag.0.type_params[1].clone();
+            let id = get_u64_field(resource, "id")?;
+            let state = get_proposal_state(client, proposer, id, token.clone(), action.clone())?;
+            Ok(ProposalView {
+                proposer,
+                id,
+                token: StrView(token),
+                action: StrView(action),
+                state,
+                start_time: get_u64_field(resource, "start_time")?,
+                end_time: get_u64_field(resource, "end_time")?,
+                for_votes: get_u128_field(resource, "for_votes")?,
+                against_votes: get_u128_field(resource, "against_votes")?,
+                quorum_votes: get_u128_field(resource, "quorum_votes")?,
+                action_delay: get_u64_field(resource, "action_delay")?,
+                eta: get_u64_field(resource, "eta")?,
+            })
+        })
+        .collect()
+}
+
+/// Get the proposal with `proposal_id` created by `proposer`.
+/// The proposal id is allocated by the DAO of each token,
+/// so the `token` is required if the proposer has proposals with same id in different DAO.
+pub(crate) fn get_proposal(
+    client: &RpcClient,
+    proposer: AccountAddress,
+    proposal_id: u64,
+    token: Option<&TypeTag>,
+) -> Result<ProposalView> {
+    let mut proposals = list_proposals(client, proposer, token)?
+        .into_iter()
+        .filter(|proposal| proposal.id == proposal_id)
+        .collect::<Vec<_>>();
+    ensure!(
+        proposals.len() <= 1,
+        "there are {} proposals with id {} of {}, please specify the token",
+        proposals.len(),
+        proposal_id,
+        proposer
+    );
+    proposals
+        .pop()
+        .ok_or_else(|| format_err!("proposal {} of {} not exists", proposal_id, proposer))
+}
+
+/// Build the script function to execute the proposal by its action type.
+/// The stdlib action types are supported, and a custom `function` is required for other action types,
+/// which is called with the token type arg and the args `(proposer_address, proposal_id)`.
+pub(crate) fn build_proposal_execute(
+    proposal: &ProposalView,
+    sender: AccountAddress,
+    function: Option<FunctionId>,
+) -> Result<ScriptFunction> {
+    let token = proposal.token.0.clone();
+    let proposer_args = vec![
+        bcs_ext::to_bytes(&proposal.proposer)?,
+        bcs_ext::to_bytes(&proposal.id)?,
+    ];
+    if let Some(function) = function {
+        return Ok(ScriptFunction::new(
+            function.module,
+            function.function,
+            vec![token],
+            proposer_args,
+        ));
+    }
+    let action = match &proposal.action.0 {
+        TypeTag::Struct(action) if action.address == core_code_address() => action,
+        action => bail!(
+            "unknown proposal action {}, please specify the execute function",
+            action
+        ),
+    };
+    let script_function = |module: &str, function: &str, ty_args, args| {
+        ScriptFunction::new(
+            ModuleId::new(core_code_address(), Identifier::new(module).unwrap()),
+            Identifier::new(function).unwrap(),
+            ty_args,
+            args,
+        )
+    };
+    Ok(match (action.module.as_str(), action.name.as_str()) {
+        ("ModifyDaoConfigProposal", "DaoConfigUpdate") => script_function(
+            "ModifyDaoConfigProposal",
+            "execute",
+            vec![token],
+            proposer_args,
+        ),
+        ("UpgradeModuleDaoProposal", "UpgradeModuleV2") => script_function(
+            "ModuleUpgradeScripts",
+            "submit_module_upgrade_plan",
+            vec![token],
+            proposer_args,
+        ),
+        ("TreasuryWithdrawDaoProposal", "WithdrawToken") => script_function(
+            "TreasuryScripts",
+            "execute_withdraw_proposal",
+            vec![token],
+            proposer_args,
+        ),
+        ("OnChainConfigDao", "OnChainConfigUpdate") => {
+            // the on chain config proposal can only be executed by the proposer.
+            ensure!(
+                sender == proposal.proposer,
+                "on chain config proposal can only be executed by the proposer {}",
+                proposal.proposer
+            );
+            script_function(
+                "OnChainConfigScripts",
+                "execute_on_chain_config_proposal",
+                action.type_params.clone(),
+                vec![bcs_ext::to_bytes(&proposal.id)?],
+            )
+        }
+        _ => bail!(
+            "unknown proposal action {}, please specify the execute function",
+            proposal.action.0
+        ),
+    })
+}
@@ -0,0 +1,130 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::proposal::{get_proposal, ProposalState};
+use crate::dev::sign_txn_helper::sign_txn_with_account_by_rpc_client;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::HashValue;
+use starcoin_transaction_builder::{build_dao_revoke_vote, build_dao_unstake_vote};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::TransactionPayload;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "revoke")]
+/// Revoke the vote of sender on the proposal, and get back the staked tokens.
+/// The vote is revoked while the proposal is active, and unstaked after the voting is end.
+pub struct RevokeProposalOpt {
+    #[structopt(short = "s", long)]
+    /// hex encoded string, like 0x1, 0x12
+    sender: Option<AccountAddress>,
+
+    #[structopt(short = "a", long = "proposer")]
+    /// the proposer of the proposal, default to the sender.
+    proposer: Option<AccountAddress>,
+
+    #[structopt(short = "i", long = "id")]
+    /// the proposal id.
+    proposal_id: u64,
+
+    #[structopt(short = "t", long = "token", parse(try_from_str = parse_type_tag))]
+    /// the token of the DAO, such as 0x1::STC::STC, required if the proposer has proposals with same id in different DAO.
+    token: Option<TypeTag>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+}
+
+pub struct RevokeProposalCommand;
+
+impl CommandAction for RevokeProposalCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = RevokeProposalOpt;
+    type ReturnItem = HashValue;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let cli_state = ctx.state();
+        let sender = cli_state.get_account_or_default(opt.sender)?.address;
+        let proposer = opt.proposer.unwrap_or(sender);
+        let proposal = get_proposal(
+            cli_state.client(),
+            proposer,
+            opt.proposal_id,
+            opt.token.as_ref(),
+        )?;
+        let script_function = match proposal.state {
+            ProposalState::Pending => bail!(
+                "can not revoke vote of proposal {} of {}, the voting is not started",
+                proposal.id,
+                proposal.proposer
+            ),
+            ProposalState::Active => build_dao_revoke_vote(
+                proposal.token.0,
+                proposal.action.0,
+                proposal.proposer,
+                proposal.id,
+            ),
+            _ => build_dao_unstake_vote(
+                proposal.token.0,
+                proposal.action.0,
+                proposal.proposer,
+                proposal.id,
+            ),
+        };
+        let signed_txn = sign_txn_with_account_by_rpc_client(
+            cli_state,
+            sender,
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time,
+            TransactionPayload::ScriptFunction(script_function),
+        )?;
+        let txn_hash = signed_txn.id();
+        cli_state.client().submit_transaction(signed_txn)?;
+
+        println!("txn {:#x} submitted.", txn_hash);
+
+        if opt.blocking {
+            cli_state.watch_txn(txn_hash)?;
+        }
+        Ok(txn_hash)
+    }
+}
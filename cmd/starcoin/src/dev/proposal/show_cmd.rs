@@ -0,0 +1,54 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::proposal::{get_proposal, ProposalView};
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "show")]
+/// Show the proposal, including the votes and the current state.
+pub struct ShowProposalOpt {
+    #[structopt(short = "a", long = "proposer")]
+    /// the proposer of the proposal, default to the default account.
+    proposer: Option<AccountAddress>,
+
+    #[structopt(short = "i", long = "id")]
+    /// the proposal id.
+    proposal_id: u64,
+
+    #[structopt(short = "t", long = "token", parse(try_from_str = parse_type_tag))]
+    /// the token of the DAO, such as 0x1::STC::STC, required if the proposer has proposals with same id in different DAO.
+    token: Option<TypeTag>,
+}
+
+pub struct ShowProposalCommand;
+
+impl CommandAction for ShowProposalCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ShowProposalOpt;
+    type ReturnItem = ProposalView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let proposer = match opt.proposer {
+            Some(proposer) => proposer,
+            None => ctx.state().default_account()?.address,
+        };
+        get_proposal(
+            ctx.state().client(),
+            proposer,
+            opt.proposal_id,
+            opt.token.as_ref(),
+        )
+    }
+}
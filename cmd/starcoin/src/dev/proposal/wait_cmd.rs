@@ -0,0 +1,124 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::proposal::{get_proposal, ProposalState, ProposalView};
+use crate::StarcoinOpt;
+use anyhow::{bail, ensure, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "wait")]
+/// Wait until the proposal becomes executable, and report the state changes while waiting.
+/// Return error if the proposal is defeated, or the wait is timeout.
+pub struct WaitProposalOpt {
+    #[structopt(short = "a", long = "proposer")]
+    /// the proposer of the proposal, default to the default account.
+    proposer: Option<AccountAddress>,
+
+    #[structopt(short = "i", long = "id")]
+    /// the proposal id.
+    proposal_id: u64,
+
+    #[structopt(short = "t", long = "token", parse(try_from_str = parse_type_tag))]
+    /// the token of the DAO, such as 0x1::STC::STC, required if the proposer has proposals with same id in different DAO.
+    token: Option<TypeTag>,
+
+    #[structopt(long = "interval", default_value = "10")]
+    /// the interval (in seconds) of polling the proposal state.
+    interval: u64,
+
+    #[structopt(long = "timeout")]
+    /// max seconds to wait, wait forever if absent.
+    timeout: Option<u64>,
+}
+
+/// Check whether the wait is finished by the proposal `state`,
+/// the proposal is finished when it becomes executable or has been executed (extracted).
+pub(crate) fn is_wait_finished(proposal: &ProposalView) -> Result<bool> {
+    match proposal.state {
+        ProposalState::Executable | ProposalState::Extracted => Ok(true),
+        ProposalState::Defeated => bail!(
+            "proposal {} of {} is defeated, for votes: {}, against votes: {}, quorum votes: {}",
+            proposal.id,
+            proposal.proposer,
+            proposal.for_votes,
+            proposal.against_votes,
+            proposal.quorum_votes
+        ),
+        ProposalState::Pending
+        | ProposalState::Active
+        | ProposalState::Agreed
+        | ProposalState::Queued => Ok(false),
+    }
+}
+
+/// A hint of what the proposal is waiting for in current state.
+fn state_hint(proposal: &ProposalView) -> String {
+    match proposal.state {
+        ProposalState::Pending => format!("voting starts at {}", proposal.start_time),
+        ProposalState::Active => format!(
+            "voting ends at {}, for votes: {}, against votes: {}, quorum votes: {}",
+            proposal.end_time, proposal.for_votes, proposal.against_votes, proposal.quorum_votes
+        ),
+        ProposalState::Agreed => "waiting to be queued".to_string(),
+        ProposalState::Queued => format!("executable at {}", proposal.eta),
+        ProposalState::Executable => "ready to execute".to_string(),
+        ProposalState::Extracted => "already executed".to_string(),
+        ProposalState::Defeated => "defeated".to_string(),
+    }
+}
+
+pub struct WaitProposalCommand;
+
+impl CommandAction for WaitProposalCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = WaitProposalOpt;
+    type ReturnItem = ProposalView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let proposer = match opt.proposer {
+            Some(proposer) => proposer,
+            None => ctx.state().default_account()?.address,
+        };
+        let start = Instant::now();
+        let mut last_state = None;
+        loop {
+            let proposal = get_proposal(client, proposer, opt.proposal_id, opt.token.as_ref())?;
+            if last_state != Some(proposal.state) {
+                println!(
+                    "proposal {} of {} is {}, {}.",
+                    proposal.id,
+                    proposal.proposer,
+                    proposal.state,
+                    state_hint(&proposal)
+                );
+                last_state = Some(proposal.state);
+            }
+            if is_wait_finished(&proposal)? {
+                return Ok(proposal);
+            }
+            if let Some(timeout) = opt.timeout {
+                ensure!(
+                    start.elapsed() < Duration::from_secs(timeout),
+                    "wait proposal {} of {} timeout, current state is {}",
+                    proposal.id,
+                    proposal.proposer,
+                    proposal.state
+                );
+            }
+            sleep(Duration::from_secs(opt.interval.max(1)));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::script_function_abi::{encode_script_function_args, resolve_script_function_abi};
+use crate::dev::sign_txn_helper::{get_dao_config, sign_txn_with_account_by_rpc_client};
+use crate::StarcoinOpt;
+use anyhow::{ensure, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::HashValue;
+use starcoin_rpc_api::types::FunctionIdView;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::{ScriptFunction, TransactionPayload};
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+/// The placeholder in `--args` which is replaced by the exec delay of the proposal.
+pub const EXEC_DELAY_PLACEHOLDER: &str = "exec_delay";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "propose")]
+/// Submit a DAO proposal by any propose script function, such as `0x1::ModifyDaoConfigProposal::propose`.
+/// The args are validated and encoded by the ABI of the function on chain,
+/// use `exec_delay` in args as the exec delay of the proposal, which is the min action delay of the DAO by default.
+pub struct ProposeOpt {
+    #[structopt(short = "s", long)]
+    /// hex encoded string, like 0x1, 0x12
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "function", name = "script-function")]
+    /// the propose script function, example: 0x1::ModifyDaoConfigProposal::propose
+    script_function: FunctionIdView,
+
+    #[structopt(
+        short = "t",
+        long = "type_tag",
+        name = "type-tag",
+        parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the function, such as the token type of the DAO.
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "args", name = "args")]
+    /// args for the function, without the signer.
+    args: Option<Vec<String>>,
+
+    #[structopt(long = "exec-delay")]
+    /// the exec delay (in milliseconds) of the proposal, default to the min action delay of the DAO.
+    exec_delay: Option<u64>,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+}
+
+pub struct ProposeCommand;
+
+impl CommandAction for ProposeCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ProposeOpt;
+    type ReturnItem = HashValue;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let cli_state = ctx.state();
+        let sender = cli_state.get_account_or_default(opt.sender)?.address;
+        let function_id = opt.script_function.clone().0;
+
+        let min_action_delay = get_dao_config(cli_state)?.min_action_delay;
+        let exec_delay = opt.exec_delay.unwrap_or(min_action_delay);
+        ensure!(
+            exec_delay >= min_action_delay,
+            "exec delay {} is less than the min action delay {} of DAO",
+            exec_delay,
+            min_action_delay
+        );
+        let args = opt
+            .args
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|arg| {
+                if arg == EXEC_DELAY_PLACEHOLDER {
+                    exec_delay.to_string()
+                } else {
+                    arg
+                }
+            })
+            .collect::<Vec<_>>();
+        let type_tags = opt.type_tags.clone().unwrap_or_default();
+
+        let state_reader = RemoteStateReader::new(cli_state.client())?;
+        let abi = resolve_script_function_abi(&state_reader, &function_id)?;
+        let encoded_args =
+            encode_script_function_args(&abi, type_tags.as_slice(), args.as_slice())?;
+
+        let signed_txn = sign_txn_with_account_by_rpc_client(
+            cli_state,
+            sender,
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time,
+            TransactionPayload::ScriptFunction(ScriptFunction::new(
+                function_id.module,
+                function_id.function,
+                type_tags,
+                encoded_args,
+            )),
+        )?;
+        let txn_hash = signed_txn.id();
+        cli_state.client().submit_transaction(signed_txn)?;
+
+        println!("txn {:#x} submitted.", txn_hash);
+
+        if opt.blocking {
+            cli_state.watch_txn(txn_hash)?;
+        }
+        Ok(txn_hash)
+    }
+}
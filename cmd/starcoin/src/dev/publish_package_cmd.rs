@@ -0,0 +1,413 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::module_diff_cmd::{diff_module, ModuleDiffView};
+use crate::view::{ExecuteResultView, ExecutionOutputView};
+use crate::StarcoinOpt;
+use anyhow::{bail, ensure, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_config::temp_path;
+use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
+use starcoin_dev::playground;
+use starcoin_move_compiler::compiled_unit::CompiledUnit;
+use starcoin_move_compiler::shared::Address;
+use starcoin_move_compiler::{errors, move_compile, process_source_tpl, MOVE_EXTENSION};
+use starcoin_rpc_api::types::{DryRunOutputView, FunctionIdView, TransactionVMStatus};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{
+    parse_transaction_argument, DryRunTransaction, Module, Package, RawUserTransaction,
+    TransactionArgument, TransactionPayload,
+};
+use starcoin_vm_types::access::ModuleAccess;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::transaction::ScriptFunction;
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use stdlib::restore_stdlib_in_dir;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "publish-package")]
+/// Compile all the move modules in the package path, verify them and check the compatibility
+/// with the modules published on chain, then publish them as a package by one txn.
+/// The `{{sender}}` placeholder in the sources is replaced with the sender address,
+/// and other `{{name}}` placeholders are replaced with the `--named-address`.
+pub struct PublishPackageOpt {
+    #[structopt(short = "s", long = "sender")]
+    /// the package address, if absent, use default account.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "named-address", name = "named-address", parse(try_from_str = parse_named_address))]
+    /// named address used in the sources, like `name=0x1`, support multi named addresses.
+    named_addresses: Option<Vec<(String, AccountAddress)>>,
+
+    #[structopt(
+        short = "d",
+        name = "dependency_path",
+        long = "dep",
+        help = "path of dependency used to build, support multi deps"
+    )]
+    deps: Option<Vec<String>>,
+
+    #[structopt(long = "function", name = "script-function")]
+    /// init script function to execute after the package published, example: 0x1::TransferScripts::peer_to_peer
+    init_script: Option<FunctionIdView>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the init script
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument))]
+    /// args for the init script.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[structopt(long = "ignore-incompatible")]
+    /// publish the package even if some modules are not compatible with the published ones,
+    /// only works when the upgrade strategy of the package address allows arbitrary upgrade.
+    ignore_incompatible: bool,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to publish the package"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to publish the package"
+    )]
+    gas_price: u64,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+
+    #[structopt(long = "dry-run")]
+    /// dry-run mode, only get transaction output, no state change to chain
+    dry_run: bool,
+
+    #[structopt(name = "path", parse(from_os_str))]
+    /// the package path, a dir contains move source files, or a single move source file.
+    path: PathBuf,
+}
+
+pub(crate) fn parse_named_address(s: &str) -> Result<(String, AccountAddress)> {
+    let mut parts = s.splitn(2, '=');
+    let name = parts.next().unwrap_or_default().trim();
+    let address = parts
+        .next()
+        .ok_or_else(|| format_err!("invalid named address {}, should be name=address", s))?
+        .trim();
+    ensure!(
+        !name.is_empty()
+            && name.chars().next().unwrap().is_ascii_alphabetic()
+            && name.chars().all(|c| c.is_ascii_alphanumeric()),
+        "invalid address name {}, should be alphanumeric and start with a letter",
+        name
+    );
+    Ok((name.to_string(), AccountAddress::from_hex_literal(address)?))
+}
+
+/// Collect the move source files in `path` recursively, sorted by path.
+pub(crate) fn collect_move_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        if path.extension().and_then(|ext| ext.to_str()) == Some(MOVE_EXTENSION) {
+            files.push(path.to_path_buf());
+        }
+    } else if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            collect_move_files(entry.as_path(), files)?;
+        }
+    } else {
+        bail!("{:?} is not a file or dir", path);
+    }
+    Ok(())
+}
+
+/// Compile and verify the modules in the package `path`, the modules are sorted by dependency order.
+pub(crate) fn compile_package(
+    path: &Path,
+    sender: AccountAddress,
+    named_addresses: &[(String, AccountAddress)],
+    extra_deps: &[String],
+) -> Result<Vec<CompiledModule>> {
+    let mut source_files = vec![];
+    collect_move_files(path, &mut source_files)?;
+    ensure!(
+        !source_files.is_empty(),
+        "no move source file found in {:?}",
+        path
+    );
+
+    let temp_path = temp_path();
+    let source_dir = temp_path.path().join("sources");
+    let dep_dir = temp_path.path().join("deps");
+    std::fs::create_dir_all(source_dir.as_path())?;
+    std::fs::create_dir_all(dep_dir.as_path())?;
+
+    let sender_address = Address::new(sender.into());
+    let mut targets = vec![];
+    for (i, source_file) in source_files.iter().enumerate() {
+        let vars = named_addresses
+            .iter()
+            .map(|(name, address)| {
+                (
+                    name.as_str(),
+                    format!("{}", Address::new((*address).into())),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let source = process_source_tpl(
+            std::fs::read_to_string(source_file)?.as_str(),
+            sender_address,
+            vars,
+        );
+        // prefix with the index to avoid name conflict of files in different sub dirs.
+        let file_name = format!(
+            "{}_{}",
+            i,
+            source_file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .expect("move file name should be utf str")
+        );
+        let target = source_dir.join(file_name);
+        std::fs::write(target.as_path(), source)?;
+        targets.push(target.display().to_string());
+    }
+
+    let mut deps = restore_stdlib_in_dir(dep_dir.as_path())?;
+    deps.extend_from_slice(extra_deps);
+    let (sources, compile_result) =
+        move_compile(&targets, &deps, Some(sender_address), None, true)?;
+    let compile_result = compile_result.and_then(|units| {
+        let (units, errors) = units.into_iter().map(|unit| unit.verify()).fold(
+            (vec![], vec![]),
+            |(mut units, mut errors), (unit, error)| {
+                units.push(unit);
+                errors.extend(error);
+                (units, errors)
+            },
+        );
+        if !errors.is_empty() {
+            Err(errors)
+        } else {
+            Ok(units)
+        }
+    });
+    let units = match compile_result {
+        Ok(units) => units,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                String::from_utf8_lossy(
+                    errors::report_errors_to_color_buffer(sources, e).as_slice()
+                )
+            );
+            bail!("compile error")
+        }
+    };
+
+    let mut modules = vec![];
+    for unit in units {
+        match unit {
+            CompiledUnit::Module { module, .. } => {
+                ensure!(
+                    *module.address() == sender,
+                    "the address of module {} should be the package address {}",
+                    module.self_id(),
+                    sender
+                );
+                modules.push(module);
+            }
+            CompiledUnit::Script { .. } => {
+                bail!("package should only contains modules, but found a script.")
+            }
+        }
+    }
+    Ok(modules)
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishPackageView {
+    pub package_address: AccountAddress,
+    pub package_hash: HashValue,
+    pub modules: Vec<ModuleDiffView>,
+    pub result: ExecuteResultView,
+}
+
+pub struct PublishPackageCommand;
+
+impl CommandAction for PublishPackageCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = PublishPackageOpt;
+    type ReturnItem = PublishPackageView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let sender = ctx.state().get_account_or_default(opt.sender)?;
+        let package_address = sender.address;
+
+        let compiled_modules = compile_package(
+            opt.path.as_path(),
+            package_address,
+            opt.named_addresses.clone().unwrap_or_default().as_slice(),
+            opt.deps.clone().unwrap_or_default().as_slice(),
+        )?;
+
+        let published = client
+            .get_account_state_set(package_address)?
+            .map(|state_set| state_set.codes)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, code)| {
+                let module = CompiledModule::deserialize(code.0.as_slice()).map_err(|e| {
+                    format_err!("deserialize module {} on chain error: {:?}", name, e)
+                })?;
+                Ok((name, module))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let module_diffs = compiled_modules
+            .iter()
+            .map(|module| diff_module(published.get(module.self_id().name()), module))
+            .collect::<Vec<_>>();
+        let incompatible_modules = module_diffs
+            .iter()
+            .filter(|diff| {
+                diff.compatibility
+                    .as_ref()
+                    .map(|c| !c.fully_compatible)
+                    .unwrap_or(false)
+            })
+            .map(|diff| diff.name.to_string())
+            .collect::<Vec<_>>();
+        if !incompatible_modules.is_empty() && !opt.ignore_incompatible {
+            bail!(
+                "modules {:?} are not compatible with the published ones, use `dev module-diff` to see the detail.",
+                incompatible_modules
+            );
+        }
+
+        let init_script = opt.init_script.as_ref().map(|script| {
+            let script_function = script.clone().0;
+            ScriptFunction::new(
+                script_function.module,
+                script_function.function,
+                opt.type_tags.clone().unwrap_or_default(),
+                convert_txn_args(&opt.args.clone().unwrap_or_default()),
+            )
+        });
+        let modules = compiled_modules
+            .iter()
+            .map(|module| {
+                let mut blob = vec![];
+                module
+                    .serialize(&mut blob)
+                    .map_err(|e| format_err!("serialize module error: {:?}", e))?;
+                Ok(Module::new(blob))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let package = Package::new(modules, init_script)?;
+        let package_hash = package.crypto_hash();
+
+        let node_info = client.node_info()?;
+        let chain_state_reader = RemoteStateReader::new(client)?;
+        let account_resource = AccountStateReader::new(&chain_state_reader)
+            .get_account_resource(&package_address)?
+            .ok_or_else(|| {
+                format_err!(
+                    "account of package address {} not exists on chain",
+                    package_address
+                )
+            })?;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            package_address,
+            account_resource.sequence_number(),
+            TransactionPayload::Package(package),
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time + node_info.now_seconds,
+            ctx.state().net().chain_id(),
+        );
+        let signed_txn = client.account_sign_txn(raw_txn)?;
+        let txn_hash = signed_txn.id();
+
+        let output: DryRunOutputView = playground::dry_run_annotated(
+            &chain_state_reader,
+            DryRunTransaction {
+                public_key: signed_txn.authenticator().public_key(),
+                raw_txn: signed_txn.raw_txn().clone(),
+            },
+        )
+        .map(|(_, output, annotation)| {
+            DryRunOutputView::new(output, annotation.write_set, annotation.events)
+        })?;
+        match output.status {
+            TransactionVMStatus::Discard { status_code } => {
+                bail!("TransactionStatus is discard: {:?}", status_code)
+            }
+            TransactionVMStatus::Executed => {}
+            s => {
+                bail!("pre-run failed, status: {:?}", s);
+            }
+        }
+
+        let result = if !opt.dry_run {
+            client.submit_transaction(signed_txn)?;
+            println!("txn {:#x} submitted.", txn_hash);
+
+            let mut output_view = ExecutionOutputView::new(txn_hash);
+            if opt.blocking {
+                let block = ctx.state().watch_txn(txn_hash)?.0;
+                output_view.block_number = Some(block.header.number.0);
+                output_view.block_id = Some(block.header.block_hash);
+            }
+            ExecuteResultView::Run(output_view)
+        } else {
+            ExecuteResultView::DryRun(output.into())
+        };
+        Ok(PublishPackageView {
+            package_address,
+            package_hash,
+            modules: module_diffs,
+            result,
+        })
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_crypto::HashValue;
+use starcoin_rpc_api::types::{StrView, TransactionEventView, TransactionVMStatus};
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::transaction::Transaction;
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::on_chain_config::Version;
+use starcoin_vm_types::write_set::WriteOp;
+use std::convert::TryInto;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "replay-txn")]
+/// Re-execute an on chain txn locally on the state before it, with the on chain VM config and gas schedule of that state,
+/// and diff the execution result (status, gas used, events and write set) with the on chain result.
+/// The write set is checked by the state after the txn, so the node must keep the states of the txn block.
+pub struct ReplayTxnOpt {
+    #[structopt(name = "txn-hash")]
+    /// the hash of the on chain txn.
+    txn_hash: HashValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueDiff<T> {
+    pub local: T,
+    pub on_chain: T,
+}
+
+impl<T: PartialEq> ValueDiff<T> {
+    pub fn new(local: T, on_chain: T) -> Option<Self> {
+        if local == on_chain {
+            None
+        } else {
+            Some(Self { local, on_chain })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteSetDiff {
+    pub access_path: AccessPath,
+    #[serde(flatten)]
+    pub value: ValueDiff<Option<StrView<Vec<u8>>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayTxnView {
+    pub transaction_hash: HashValue,
+    pub block_hash: HashValue,
+    pub block_number: BlockNumber,
+    pub transaction_index: u32,
+    /// The state root before the txn, which the txn is replayed on.
+    pub pre_state_root: HashValue,
+    /// The stdlib version of the state before the txn.
+    pub stdlib_version: Option<u64>,
+    pub status: TransactionVMStatus,
+    pub gas_used: u64,
+    pub events: usize,
+    pub write_set: usize,
+    pub status_diff: Option<ValueDiff<TransactionVMStatus>>,
+    pub gas_used_diff: Option<ValueDiff<u64>>,
+    /// The event diffs by index, the missing event on one side is None.
+    pub event_diffs: Vec<ValueDiff<Option<TransactionEventView>>>,
+    pub write_set_diffs: Vec<WriteSetDiff>,
+    /// Whether the local execution result is same as the on chain result.
+    pub matched: bool,
+}
+
+/// Diff the events by index, only the event content is compared.
+pub(crate) fn diff_events(
+    local: &[TransactionEventView],
+    on_chain: &[TransactionEventView],
+) -> Vec<ValueDiff<Option<TransactionEventView>>> {
+    let content = |event: &TransactionEventView| {
+        (
+            event.event_key,
+            event.event_seq_number.0,
+            event.type_tag.clone(),
+            event.data.0.clone(),
+        )
+    };
+    (0..local.len().max(on_chain.len()))
+        .filter_map(|i| {
+            let (local, on_chain) = (local.get(i), on_chain.get(i));
+            if local.map(content) == on_chain.map(content) {
+                None
+            } else {
+                Some(ValueDiff {
+                    local: local.cloned(),
+                    on_chain: on_chain.cloned(),
+                })
+            }
+        })
+        .collect()
+}
+
+/// The state root before the txn at `index` of the block, it is the state root after the previous txn,
+/// or the state root of the parent block for the first txn.
+fn pre_state_root(client: &RpcClient, block_hash: HashValue, index: u32) -> Result<HashValue> {
+    if index > 0 {
+        return client
+            .chain_get_txn_info_by_block_and_index(block_hash, (index - 1) as u64)?
+            .map(|info| info.state_root_hash)
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find txn info {} of block {}",
+                    index - 1,
+                    block_hash
+                )
+            });
+    }
+    let block = client
+        .chain_get_block_by_hash(block_hash)?
+        .ok_or_else(|| format_err!("Can not find block by hash: {}", block_hash))?;
+    let parent_hash = block.header.parent_hash;
+    client
+        .chain_get_block_by_hash(parent_hash)?
+        .map(|parent| parent.header.state_root)
+        .ok_or_else(|| format_err!("Can not find parent block by hash: {}", parent_hash))
+}
+
+pub struct ReplayTxnCommand;
+
+impl CommandAction for ReplayTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ReplayTxnOpt;
+    type ReturnItem = ReplayTxnView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let txn_view = client
+            .chain_get_transaction(opt.txn_hash)?
+            .ok_or_else(|| format_err!("Can not find txn by hash: {}", opt.txn_hash))?;
+        let txn_info = client
+            .chain_get_transaction_info(opt.txn_hash)?
+            .ok_or_else(|| format_err!("Can not find txn info by hash: {}", opt.txn_hash))?;
+        let txn = match (txn_view.user_transaction, txn_view.block_metadata) {
+            (Some(user_txn), _) => Transaction::UserTransaction(user_txn.try_into()?),
+            (None, Some(block_metadata)) => Transaction::BlockMetadata(block_metadata.into()),
+            (None, None) => bail!(
+                "Txn {} is neither a user txn nor a block metadata txn, can not replay it.",
+                opt.txn_hash
+            ),
+        };
+        let pre_state_root =
+            pre_state_root(client, txn_view.block_hash, txn_view.transaction_index)?;
+        let state = RemoteStateReader::new_with_root(client, pre_state_root);
+        let stdlib_version = AccountStateReader::new(&state)
+            .get_on_chain_config::<Version>()?
+            .map(|version| version.major);
+
+        let output = starcoin_executor::execute_transactions(&state, vec![txn])?
+            .pop()
+            .ok_or_else(|| format_err!("No output of txn {}", opt.txn_hash))?;
+        let (write_set, events, gas_used, status) = output.into_inner();
+        let status: TransactionVMStatus = status.into();
+        let events: Vec<TransactionEventView> = events.into_iter().map(Into::into).collect();
+        let on_chain_events = client.chain_get_events_by_txn_hash(opt.txn_hash)?;
+
+        let mut write_set_diffs = vec![];
+        for (access_path, write_op) in &write_set {
+            let local = match write_op {
+                WriteOp::Value(value) => Some(StrView(value.clone())),
+                WriteOp::Deletion => None,
+            };
+            let on_chain = client
+                .state_get_with_proof_by_root(access_path.clone(), txn_info.state_root_hash)?
+                .state;
+            if let Some(value) = ValueDiff::new(local, on_chain) {
+                write_set_diffs.push(WriteSetDiff {
+                    access_path: access_path.clone(),
+                    value,
+                });
+            }
+        }
+
+        let status_diff = ValueDiff::new(status.clone(), txn_info.status);
+        let gas_used_diff = ValueDiff::new(gas_used, txn_info.gas_used.0);
+        let event_diffs = diff_events(events.as_slice(), on_chain_events.as_slice());
+        let matched = status_diff.is_none()
+            && gas_used_diff.is_none()
+            && event_diffs.is_empty()
+            && write_set_diffs.is_empty();
+        Ok(ReplayTxnView {
+            transaction_hash: opt.txn_hash,
+            block_hash: txn_view.block_hash,
+            block_number: txn_view.block_number.0,
+            transaction_index: txn_view.transaction_index,
+            pre_state_root,
+            stdlib_version,
+            status,
+            gas_used,
+            events: events.len(),
+            write_set: write_set.iter().count(),
+            status_diff,
+            gas_used_diff,
+            event_diffs,
+            write_set_diffs,
+            matched,
+        })
+    }
+}
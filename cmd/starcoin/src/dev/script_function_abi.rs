@@ -0,0 +1,116 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err, Result};
+use starcoin_vm_types::language_storage::{FunctionId, TypeTag};
+pub use starcoin_vm_types::module_abi::resolve_script_function_abi;
+use starcoin_vm_types::parser::parse_transaction_argument;
+use starcoin_vm_types::transaction::{ScriptABI, ScriptFunctionABI};
+use starcoin_vm_types::transaction_argument::TransactionArgument;
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// Load the ABI of a script function from a local ABI file, such as the files generated by stdlib,
+/// so the payload can be built without a reachable node.
+pub fn load_script_function_abi(
+    abi_file: &Path,
+    function_id: &FunctionId,
+) -> Result<ScriptFunctionABI> {
+    let bytes = std::fs::read(abi_file)
+        .map_err(|e| format_err!("read abi file {} error: {}", abi_file.display(), e))?;
+    let abi: ScriptABI = bcs_ext::from_bytes(bytes.as_slice())
+        .map_err(|e| format_err!("invalid abi file {}: {}", abi_file.display(), e))?;
+    match abi {
+        ScriptABI::ScriptFunction(abi) => {
+            ensure!(
+                abi.module_name() == &function_id.module
+                    && abi.name() == function_id.function.as_str(),
+                "abi file {} is the abi of {}::{}, mismatch with function {}",
+                abi_file.display(),
+                abi.module_name(),
+                abi.name(),
+                function_id
+            );
+            Ok(abi)
+        }
+        ScriptABI::TransactionScript(_) => bail!(
+            "abi file {} is not the abi of a script function",
+            abi_file.display()
+        ),
+    }
+}
+
+/// Validate the type args and args with the ABI, and encode the args to bcs bytes.
+/// Integer args without suffix are converted to the integer type in ABI if the value fits.
+pub fn encode_script_function_args(
+    abi: &ScriptFunctionABI,
+    type_args: &[TypeTag],
+    args: &[String],
+) -> Result<Vec<Vec<u8>>> {
+    let args = args
+        .iter()
+        .map(|arg| parse_transaction_argument(arg.as_str()))
+        .collect::<Result<Vec<_>>>()?;
+    encode_script_function_txn_args(abi, type_args, args.as_slice())
+}
+
+/// Same as `encode_script_function_args`, but the args are already parsed.
+pub fn encode_script_function_txn_args(
+    abi: &ScriptFunctionABI,
+    type_args: &[TypeTag],
+    args: &[TransactionArgument],
+) -> Result<Vec<Vec<u8>>> {
+    ensure!(
+        abi.ty_args().len() == type_args.len(),
+        "function {} expect {} type args, but got {}",
+        abi.name(),
+        abi.ty_args().len(),
+        type_args.len()
+    );
+    ensure!(
+        abi.args().len() == args.len(),
+        "function {} expect {} args, but got {}",
+        abi.name(),
+        abi.args().len(),
+        args.len()
+    );
+    abi.args()
+        .iter()
+        .zip(args)
+        .map(|(arg_abi, arg)| {
+            encode_arg(arg_abi.type_tag(), arg)
+                .map_err(|e| format_err!("invalid arg {}: {}", arg_abi.name(), e))
+        })
+        .collect()
+}
+
+fn encode_arg(type_tag: &TypeTag, arg: &TransactionArgument) -> Result<Vec<u8>> {
+    let mismatch = || format_err!("`{:?}` mismatch with type {}", arg, type_tag);
+    let bytes = match (type_tag, arg.clone()) {
+        (TypeTag::Bool, TransactionArgument::Bool(v)) => bcs_ext::to_bytes(&v)?,
+        (TypeTag::Address, TransactionArgument::Address(v)) => bcs_ext::to_bytes(&v)?,
+        (TypeTag::Vector(inner), TransactionArgument::U8Vector(v)) if **inner == TypeTag::U8 => {
+            bcs_ext::to_bytes(&v)?
+        }
+        (TypeTag::U8, v) => {
+            let v = to_integer(&v).ok_or_else(mismatch)?;
+            bcs_ext::to_bytes(&u8::try_from(v).map_err(|_| mismatch())?)?
+        }
+        (TypeTag::U64, v) => {
+            let v = to_integer(&v).ok_or_else(mismatch)?;
+            bcs_ext::to_bytes(&u64::try_from(v).map_err(|_| mismatch())?)?
+        }
+        (TypeTag::U128, v) => bcs_ext::to_bytes(&to_integer(&v).ok_or_else(mismatch)?)?,
+        _ => return Err(mismatch()),
+    };
+    Ok(bytes)
+}
+
+fn to_integer(arg: &TransactionArgument) -> Option<u128> {
+    match arg {
+        TransactionArgument::U8(v) => Some(*v as u128),
+        TransactionArgument::U64(v) => Some(*v as u128),
+        TransactionArgument::U128(v) => Some(*v),
+        _ => None,
+    }
+}
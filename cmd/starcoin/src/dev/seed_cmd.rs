@@ -0,0 +1,475 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::publish_package_cmd::compile_package;
+use crate::StarcoinOpt;
+use anyhow::{bail, ensure, format_err, Result};
+use rand::prelude::*;
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_config::temp_path;
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::{HashValue, ValidCryptoMaterialStringExt};
+use starcoin_executor::DEFAULT_MAX_GAS_AMOUNT;
+use starcoin_rpc_api::types::TransactionVMStatus;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::AccountStateReader;
+use starcoin_transaction_builder::{
+    build_accept_token_txn, build_transfer_txn, build_transfer_txn_by_token_type,
+};
+use starcoin_types::account_config;
+use starcoin_types::transaction::{
+    Module, Package, RawUserTransaction, ScriptFunction, SignedUserTransaction, TransactionPayload,
+};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::token::stc::STC_TOKEN_CODE;
+use starcoin_vm_types::token::token_code::TokenCode;
+use starcoin_vm_types::transaction::authenticator::AuthenticationKey;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+const SEED_MODULE: &str = "SeedData";
+const NANO_STC_PER_STC: u128 = 1_000_000_000;
+/// The precision of the seeded tokens, same as STC.
+const SEED_TOKEN_PRECISION: u8 = 9;
+const SEED_TOKEN_SUPPLY: u128 = 1_000_000_000 * NANO_STC_PER_STC;
+const TXN_EXPIRATION_SECS: u64 = 3600;
+const WAIT_TXNS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Seed a dev chain with deterministic test data: accounts funded with pseudo-random amounts of STC,
+/// tokens registered by the first account and distributed to all accounts, NFTs minted to random accounts,
+/// and a random transfer history. The same seed always generates the same accounts and txns on a fresh dev chain.
+/// The accounts are imported into the wallet with an empty password.
+/// This command only available in test or dev network.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "seed")]
+pub struct SeedOpt {
+    #[structopt(long = "accounts", default_value = "10")]
+    /// how many accounts to create.
+    accounts: usize,
+
+    #[structopt(long = "tokens", default_value = "0")]
+    /// how many tokens to register, the tokens are named SeedToken0, SeedToken1 ... under the first account.
+    tokens: usize,
+
+    #[structopt(long = "nfts", default_value = "0")]
+    /// how many NFTs to mint.
+    nfts: usize,
+
+    #[structopt(long = "transfers")]
+    /// how many random transfers to generate, default to twice the accounts.
+    transfers: Option<usize>,
+
+    #[structopt(long = "seed", default_value = "0")]
+    /// the seed of the pseudo-random generator.
+    seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedAccountView {
+    pub address: AccountAddress,
+    pub public_key: String,
+    pub private_key: String,
+    /// The STC (in nanoSTC) funded to the account.
+    pub funded: u128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedView {
+    pub seed: u64,
+    pub accounts: Vec<SeedAccountView>,
+    pub tokens: Vec<TokenCode>,
+    pub nfts: usize,
+    pub transfers: usize,
+    pub txns: usize,
+}
+
+/// The move source of the seed module, with `tokens` token types and a simple NFT gallery.
+pub(crate) fn seed_module_source(tokens: usize) -> String {
+    let mut source = format!("module {} {{\n", SEED_MODULE);
+    if tokens > 0 {
+        source.push_str("    use 0x1::Account;\n    use 0x1::Token;\n");
+    }
+    source.push_str("    use 0x1::Signer;\n    use 0x1::Vector;\n\n");
+    for i in 0..tokens {
+        source.push_str(&format!(
+            "    struct SeedToken{} has copy, drop, store {{ }}\n",
+            i
+        ));
+    }
+    source.push_str(
+        r#"
+    struct SeedNFT has store {
+        id: u64,
+        data: vector<u8>,
+    }
+
+    struct SeedNFTGallery has key {
+        items: vector<SeedNFT>,
+    }
+
+    public(script) fun mint_nft(account: signer, id: u64, data: vector<u8>) acquires SeedNFTGallery {
+        let addr = Signer::address_of(&account);
+        if (!exists<SeedNFTGallery>(addr)) {
+            move_to(&account, SeedNFTGallery { items: Vector::empty<SeedNFT>() });
+        };
+        let gallery = borrow_global_mut<SeedNFTGallery>(addr);
+        Vector::push_back(&mut gallery.items, SeedNFT { id, data });
+    }
+"#,
+    );
+    if tokens > 0 {
+        source.push_str("\n    public(script) fun init(account: signer, amount: u128) {\n");
+        for i in 0..tokens {
+            source.push_str(&format!(
+                "        Token::register_token<SeedToken{i}>(&account, {precision});\n        \
+                 Account::do_accept_token<SeedToken{i}>(&account);\n        \
+                 Account::deposit_to_self(&account, Token::mint<SeedToken{i}>(&account, amount));\n",
+                i = i,
+                precision = SEED_TOKEN_PRECISION
+            ));
+        }
+        source.push_str("    }\n");
+    }
+    source.push_str("}\n");
+    source
+}
+
+struct SeedAccount {
+    address: AccountAddress,
+    private_key: Ed25519PrivateKey,
+    public_key: Ed25519PublicKey,
+}
+
+impl SeedAccount {
+    fn auth_key(&self) -> AuthenticationKey {
+        AuthenticationKey::ed25519(&self.public_key)
+    }
+}
+
+/// Build and submit the seed txns, the sequence numbers are tracked locally,
+/// so the txns of an account can be submitted without waiting the previous txn mined.
+struct TxnSubmitter<'a> {
+    client: &'a RpcClient,
+    chain_id: ChainId,
+    expiration_timestamp_secs: u64,
+    sequence_numbers: HashMap<AccountAddress, u64>,
+    pending: Vec<HashValue>,
+    submitted: usize,
+}
+
+impl<'a> TxnSubmitter<'a> {
+    fn new(client: &'a RpcClient, chain_id: ChainId) -> Result<Self> {
+        Ok(Self {
+            client,
+            chain_id,
+            expiration_timestamp_secs: client.node_info()?.now_seconds + TXN_EXPIRATION_SECS,
+            sequence_numbers: HashMap::new(),
+            pending: vec![],
+            submitted: 0,
+        })
+    }
+
+    fn next_sequence_number(&mut self, address: AccountAddress) -> Result<u64> {
+        let sequence_number = match self.sequence_numbers.get(&address) {
+            Some(sequence_number) => *sequence_number,
+            None => {
+                let state_reader = RemoteStateReader::new(self.client)?;
+                AccountStateReader::new(&state_reader)
+                    .get_account_resource(&address)?
+                    .map(|resource| resource.sequence_number())
+                    .unwrap_or(0)
+            }
+        };
+        self.sequence_numbers.insert(address, sequence_number + 1);
+        Ok(sequence_number)
+    }
+
+    fn payload_txn(
+        &mut self,
+        sender: AccountAddress,
+        payload: TransactionPayload,
+    ) -> Result<RawUserTransaction> {
+        Ok(RawUserTransaction::new_with_default_gas_token(
+            sender,
+            self.next_sequence_number(sender)?,
+            payload,
+            DEFAULT_MAX_GAS_AMOUNT,
+            1,
+            self.expiration_timestamp_secs,
+            self.chain_id,
+        ))
+    }
+
+    fn submit(&mut self, txn: SignedUserTransaction) -> Result<()> {
+        let txn_hash = txn.id();
+        self.client.submit_transaction(txn)?;
+        self.pending.push(txn_hash);
+        self.submitted += 1;
+        Ok(())
+    }
+
+    fn submit_by(&mut self, account: &SeedAccount, raw_txn: RawUserTransaction) -> Result<()> {
+        let txn = raw_txn
+            .sign(&account.private_key, account.public_key.clone())?
+            .into_inner();
+        self.submit(txn)
+    }
+
+    /// Wait all the submitted txns mined and executed.
+    fn wait(&mut self) -> Result<()> {
+        let start = Instant::now();
+        for txn_hash in std::mem::take(&mut self.pending) {
+            loop {
+                if let Some(txn_info) = self.client.chain_get_transaction_info(txn_hash)? {
+                    ensure!(
+                        txn_info.status == TransactionVMStatus::Executed,
+                        "seed txn {} failed: {:?}",
+                        txn_hash,
+                        txn_info.status
+                    );
+                    break;
+                }
+                if start.elapsed() > WAIT_TXNS_TIMEOUT {
+                    bail!("wait seed txn {} mined timeout", txn_hash);
+                }
+                std::thread::sleep(Duration::from_millis(500));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn seed_script_function(
+    address: AccountAddress,
+    function: &str,
+    args: Vec<Vec<u8>>,
+) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(address, Identifier::new(SEED_MODULE).unwrap()),
+        Identifier::new(function).unwrap(),
+        vec![],
+        args,
+    ))
+}
+
+fn seed_token_code(address: AccountAddress, index: usize) -> TokenCode {
+    TokenCode::new(
+        address,
+        SEED_MODULE.to_string(),
+        format!("SeedToken{}", index),
+    )
+}
+
+pub struct SeedCommand;
+
+impl CommandAction for SeedCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SeedOpt;
+    type ReturnItem = SeedView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let net = ctx.state().net();
+        if !net.is_test_or_dev() {
+            bail!(
+                "This command only available in test or dev network, current network is: {}",
+                net
+            );
+        }
+        ensure!(opt.accounts > 0, "at least one account is required");
+        let client = ctx.state().client();
+        let mut rng = StdRng::seed_from_u64(opt.seed);
+        let mut keygen = KeyGen::from_seed(rng.gen());
+        let accounts = (0..opt.accounts)
+            .map(|_| {
+                let (private_key, public_key) = keygen.generate_keypair();
+                SeedAccount {
+                    address: AuthenticationKey::ed25519(&public_key).derived_address(),
+                    private_key,
+                    public_key,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut submitter = TxnSubmitter::new(client, net.chain_id())?;
+
+        // fund the accounts by the association account.
+        let association_address = account_config::association_address();
+        client.account_unlock(
+            association_address,
+            "".to_string(),
+            Duration::from_secs(300),
+        )?;
+        let mut account_views = vec![];
+        for account in &accounts {
+            let funded = rng.gen_range(1_000..=10_000) * NANO_STC_PER_STC;
+            let raw_txn = build_transfer_txn(
+                association_address,
+                account.address,
+                Some(account.auth_key()),
+                submitter.next_sequence_number(association_address)?,
+                funded,
+                1,
+                DEFAULT_MAX_GAS_AMOUNT,
+                submitter.expiration_timestamp_secs,
+                submitter.chain_id,
+            );
+            submitter.submit(client.account_sign_txn(raw_txn)?)?;
+            if client.account_get(account.address)?.is_none() {
+                client.account_import(
+                    account.address,
+                    account.private_key.to_bytes().to_vec(),
+                    "".to_string(),
+                )?;
+            }
+            account_views.push(SeedAccountView {
+                address: account.address,
+                public_key: account.public_key.to_encoded_string()?,
+                private_key: account.private_key.to_encoded_string()?,
+                funded,
+            });
+        }
+        submitter.wait()?;
+
+        // publish the seed module, register and distribute the tokens.
+        let owner = &accounts[0];
+        let token_codes = (0..opt.tokens)
+            .map(|i| seed_token_code(owner.address, i))
+            .collect::<Vec<_>>();
+        if opt.tokens > 0 || opt.nfts > 0 {
+            let temp_path = temp_path();
+            std::fs::write(
+                temp_path.path().join(format!("{}.move", SEED_MODULE)),
+                seed_module_source(opt.tokens),
+            )?;
+            let modules = compile_package(temp_path.path(), owner.address, &[], &[])?
+                .iter()
+                .map(|module| {
+                    let mut blob = vec![];
+                    module
+                        .serialize(&mut blob)
+                        .map_err(|e| format_err!("serialize module error: {:?}", e))?;
+                    Ok(Module::new(blob))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let init_script = if opt.tokens > 0 {
+                Some(ScriptFunction::new(
+                    ModuleId::new(owner.address, Identifier::new(SEED_MODULE)?),
+                    Identifier::new("init")?,
+                    vec![],
+                    vec![bcs_ext::to_bytes(&SEED_TOKEN_SUPPLY)?],
+                ))
+            } else {
+                None
+            };
+            let raw_txn = submitter.payload_txn(
+                owner.address,
+                TransactionPayload::Package(Package::new(modules, init_script)?),
+            )?;
+            submitter.submit_by(owner, raw_txn)?;
+            submitter.wait()?;
+        }
+        for token_code in &token_codes {
+            for account in &accounts[1..] {
+                let raw_txn = build_accept_token_txn(
+                    account.address,
+                    submitter.next_sequence_number(account.address)?,
+                    1,
+                    DEFAULT_MAX_GAS_AMOUNT,
+                    token_code.clone(),
+                    submitter.expiration_timestamp_secs,
+                    submitter.chain_id,
+                );
+                submitter.submit_by(account, raw_txn)?;
+            }
+        }
+        submitter.wait()?;
+        for token_code in &token_codes {
+            for account in &accounts[1..] {
+                let amount = rng.gen_range(1_000..=100_000) * NANO_STC_PER_STC;
+                let raw_txn = build_transfer_txn_by_token_type(
+                    owner.address,
+                    account.address,
+                    Some(account.auth_key()),
+                    submitter.next_sequence_number(owner.address)?,
+                    amount,
+                    1,
+                    DEFAULT_MAX_GAS_AMOUNT,
+                    token_code.clone(),
+                    submitter.expiration_timestamp_secs,
+                    submitter.chain_id,
+                );
+                submitter.submit_by(owner, raw_txn)?;
+            }
+        }
+        submitter.wait()?;
+
+        // mint the NFTs to random accounts.
+        for id in 0..opt.nfts {
+            let account = &accounts[rng.gen_range(0..accounts.len())];
+            let data = rng.gen::<[u8; 16]>().to_vec();
+            let raw_txn = submitter.payload_txn(
+                account.address,
+                seed_script_function(
+                    owner.address,
+                    "mint_nft",
+                    vec![bcs_ext::to_bytes(&(id as u64))?, bcs_ext::to_bytes(&data)?],
+                ),
+            )?;
+            submitter.submit_by(account, raw_txn)?;
+        }
+        submitter.wait()?;
+
+        // generate the transfer history of STC and the seeded tokens.
+        let transfers = if accounts.len() > 1 {
+            opt.transfers.unwrap_or(opt.accounts * 2)
+        } else {
+            0
+        };
+        for _ in 0..transfers {
+            let from = rng.gen_range(0..accounts.len());
+            let to = (from + rng.gen_range(1..accounts.len())) % accounts.len();
+            let (from, to) = (&accounts[from], &accounts[to]);
+            let token_index = rng.gen_range(0..=token_codes.len());
+            let token_code = if token_index == token_codes.len() {
+                STC_TOKEN_CODE.clone()
+            } else {
+                token_codes[token_index].clone()
+            };
+            let amount = rng.gen_range(1..=100) * NANO_STC_PER_STC;
+            let raw_txn = build_transfer_txn_by_token_type(
+                from.address,
+                to.address,
+                Some(to.auth_key()),
+                submitter.next_sequence_number(from.address)?,
+                amount,
+                1,
+                DEFAULT_MAX_GAS_AMOUNT,
+                token_code,
+                submitter.expiration_timestamp_secs,
+                submitter.chain_id,
+            );
+            submitter.submit_by(from, raw_txn)?;
+        }
+        submitter.wait()?;
+
+        Ok(SeedView {
+            seed: opt.seed,
+            accounts: account_views,
+            tokens: token_codes,
+            nfts: opt.nfts,
+            transfers,
+            txns: submitter.submitted,
+        })
+    }
+}
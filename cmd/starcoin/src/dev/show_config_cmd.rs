@@ -0,0 +1,243 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use starcoin_config::BuiltinNetworkID;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_vm_types::on_chain_config::{
+    ConsensusConfig, DaoConfig, OnChainConfig, TransactionPublishOption, VMConfig, Version,
+};
+use std::fmt;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnChainConfigType {
+    /// The txn publish option and the gas constants.
+    Vm,
+    Consensus,
+    Dao,
+    Version,
+    /// The full cost table of instructions and natives.
+    GasSchedule,
+}
+
+impl fmt::Display for OnChainConfigType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OnChainConfigType::Vm => write!(f, "vm"),
+            OnChainConfigType::Consensus => write!(f, "consensus"),
+            OnChainConfigType::Dao => write!(f, "dao"),
+            OnChainConfigType::Version => write!(f, "version"),
+            OnChainConfigType::GasSchedule => write!(f, "gas-schedule"),
+        }
+    }
+}
+
+impl FromStr for OnChainConfigType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "vm" => Ok(OnChainConfigType::Vm),
+            "consensus" => Ok(OnChainConfigType::Consensus),
+            "dao" => Ok(OnChainConfigType::Dao),
+            "version" => Ok(OnChainConfigType::Version),
+            "gas-schedule" | "gas_schedule" => Ok(OnChainConfigType::GasSchedule),
+            _ => bail!(
+                "Unknown on chain config: {}, expect vm, consensus, dao, version or gas-schedule",
+                s
+            ),
+        }
+    }
+}
+
+/// Show the current on chain config as json, optionally diff it with the genesis config of a builtin network.
+///  Some examples:
+///  ``` shell
+///  dev show-config dao
+///  dev show-config gas-schedule --diff main
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "show-config")]
+pub struct ShowConfigOpt {
+    #[structopt(name = "config")]
+    /// the on chain config to show, vm, consensus, dao, version or gas-schedule.
+    config: OnChainConfigType,
+
+    #[structopt(long = "diff")]
+    /// the builtin network (test, dev, halley, proxima, barnard, main) whose genesis config to diff with.
+    diff: Option<BuiltinNetworkID>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConfigDiffView {
+    /// The json path of the changed field, such as `gas_constants.max_transaction_size_in_bytes`.
+    pub path: String,
+    pub on_chain: Value,
+    pub genesis: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShowConfigView {
+    pub config: OnChainConfigType,
+    pub value: Value,
+    /// The builtin network diffed with, and the fields changed from its genesis config.
+    pub diff_with: Option<BuiltinNetworkID>,
+    pub diffs: Option<Vec<ConfigDiffView>>,
+}
+
+fn vm_config_json(
+    vm_config: &VMConfig,
+    publish_option: &TransactionPublishOption,
+) -> Result<Value> {
+    Ok(json!({
+        "publishing_option": serde_json::to_value(publish_option)?,
+        "gas_constants": serde_json::to_value(&vm_config.gas_schedule.gas_constants)?,
+    }))
+}
+
+fn get_config<C: OnChainConfig>(reader: &AccountStateReader<RemoteStateReader>) -> Result<C> {
+    reader.get_on_chain_config::<C>()?.ok_or_else(|| {
+        format_err!(
+            "On chain config {}::{} not exist.",
+            C::MODULE_IDENTIFIER,
+            C::CONF_IDENTIFIER
+        )
+    })
+}
+
+fn on_chain_config_json(
+    reader: &AccountStateReader<RemoteStateReader>,
+    config: OnChainConfigType,
+) -> Result<Value> {
+    Ok(match config {
+        OnChainConfigType::Vm => vm_config_json(
+            &get_config::<VMConfig>(reader)?,
+            &get_config::<TransactionPublishOption>(reader)?,
+        )?,
+        OnChainConfigType::Consensus => {
+            serde_json::to_value(get_config::<ConsensusConfig>(reader)?)?
+        }
+        OnChainConfigType::Dao => serde_json::to_value(get_config::<DaoConfig>(reader)?)?,
+        OnChainConfigType::Version => serde_json::to_value(get_config::<Version>(reader)?)?,
+        OnChainConfigType::GasSchedule => {
+            serde_json::to_value(get_config::<VMConfig>(reader)?.gas_schedule)?
+        }
+    })
+}
+
+pub(crate) fn genesis_config_json(
+    network: BuiltinNetworkID,
+    config: OnChainConfigType,
+) -> Result<Value> {
+    let genesis_config = network.genesis_config();
+    Ok(match config {
+        OnChainConfigType::Vm => {
+            vm_config_json(&genesis_config.vm_config, &genesis_config.publishing_option)?
+        }
+        OnChainConfigType::Consensus => serde_json::to_value(&genesis_config.consensus_config)?,
+        OnChainConfigType::Dao => serde_json::to_value(&genesis_config.dao_config)?,
+        OnChainConfigType::Version => serde_json::to_value(&genesis_config.version)?,
+        OnChainConfigType::GasSchedule => {
+            serde_json::to_value(&genesis_config.vm_config.gas_schedule)?
+        }
+    })
+}
+
+/// Collect the changed leaf fields between two json values, the array items are compared by index.
+pub(crate) fn diff_json(
+    path: &str,
+    on_chain: &Value,
+    genesis: &Value,
+    diffs: &mut Vec<ConfigDiffView>,
+) {
+    let child_path = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (on_chain, genesis) {
+        (Value::Object(on_chain), Value::Object(genesis)) => {
+            for (key, value) in on_chain {
+                diff_json(
+                    &child_path(key),
+                    value,
+                    genesis.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+            for (key, value) in genesis {
+                if !on_chain.contains_key(key) {
+                    diff_json(&child_path(key), &Value::Null, value, diffs);
+                }
+            }
+        }
+        (Value::Array(on_chain), Value::Array(genesis)) => {
+            for i in 0..on_chain.len().max(genesis.len()) {
+                diff_json(
+                    &format!("{}[{}]", path, i),
+                    on_chain.get(i).unwrap_or(&Value::Null),
+                    genesis.get(i).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        (on_chain, genesis) => {
+            if on_chain != genesis {
+                diffs.push(ConfigDiffView {
+                    path: path.to_string(),
+                    on_chain: on_chain.clone(),
+                    genesis: genesis.clone(),
+                });
+            }
+        }
+    }
+}
+
+pub struct ShowConfigCommand;
+
+impl CommandAction for ShowConfigCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ShowConfigOpt;
+    type ReturnItem = ShowConfigView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let chain_state_reader = RemoteStateReader::new(ctx.state().client())?;
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let value = on_chain_config_json(&account_state_reader, opt.config)?;
+        let diffs = match opt.diff {
+            Some(network) => {
+                let mut diffs = vec![];
+                diff_json(
+                    "",
+                    &value,
+                    &genesis_config_json(network, opt.config)?,
+                    &mut diffs,
+                );
+                Some(diffs)
+            }
+            None => None,
+        };
+        Ok(ShowConfigView {
+            config: opt.config,
+            value,
+            diff_with: opt.diff,
+            diffs,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use network_p2p_types::permission::PeerIdentity;
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::Ed25519PrivateKey;
+use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_types::peer_info::PeerId;
+use starcoin_vm_types::genesis_config::ChainId;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "sign-peer-identity")]
+/// Sign the identity of a peer by the network CA private key, the identity is used as the
+/// `--node-identity` of the peer in permissioned network, and only valid on the network of the chain id.
+pub struct SignPeerIdentityOpt {
+    #[structopt(long = "ca-key", parse(try_from_str = Ed25519PrivateKey::from_encoded_string))]
+    /// the private key of the network CA.
+    ca_key: Ed25519PrivateKey,
+
+    #[structopt(long = "chain-id")]
+    /// the chain id of the permissioned network, default to the chain id of the connected node.
+    chain_id: Option<ChainId>,
+
+    #[structopt(name = "peer_id")]
+    /// the peer id to sign.
+    peer_id: PeerId,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerIdentityView {
+    pub peer_id: PeerId,
+    pub chain_id: ChainId,
+    /// hex encoded identity signature.
+    pub identity: String,
+}
+
+pub struct SignPeerIdentityCommand;
+
+impl CommandAction for SignPeerIdentityCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SignPeerIdentityOpt;
+    type ReturnItem = PeerIdentityView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let chain_id = opt
+            .chain_id
+            .unwrap_or_else(|| ctx.state().net().chain_id());
+        let identity = PeerIdentity::sign(chain_id.id(), opt.peer_id.origin(), &opt.ca_key);
+        Ok(PeerIdentityView {
+            peer_id: opt.peer_id.clone(),
+            chain_id,
+            identity: identity.to_encoded_string()?,
+        })
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::state::StateSnapshotView;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::HashValue;
+use starcoin_state_api::snapshot::StateSnapshot;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "export")]
+/// Export the whole chain state at a block to a bcs snapshot file.
+/// The state is fetched page by page with the admin api `node_admin.dump_state`,
+/// so the cli should connect to the node by ipc, or by a transport with the admin api key.
+pub struct ExportStateOpt {
+    #[structopt(short = "b", long = "block")]
+    /// the block hash, default to the current head block.
+    block: Option<HashValue>,
+
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    /// the output snapshot file.
+    output: PathBuf,
+}
+
+pub struct ExportStateCommand;
+
+impl CommandAction for ExportStateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ExportStateOpt;
+    type ReturnItem = StateSnapshotView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let header = match opt.block {
+            Some(block_hash) => {
+                client
+                    .chain_get_block_by_hash(block_hash)?
+                    .ok_or_else(|| format_err!("Can not find block by hash: {}", block_hash))?
+                    .header
+            }
+            None => client.chain_info()?.head,
+        };
+        let state = client.state_dump_by_root(header.state_root)?;
+        let snapshot = StateSnapshot {
+            block_number: header.number.0,
+            block_hash: header.block_hash,
+            state_root: header.state_root,
+            state,
+        };
+        std::fs::write(opt.output.as_path(), bcs_ext::to_bytes(&snapshot)?)?;
+        Ok(StateSnapshotView {
+            block_number: snapshot.block_number,
+            block_hash: snapshot.block_hash,
+            state_root: snapshot.state_root,
+            accounts: snapshot.state.len(),
+            file: opt.output.clone(),
+        })
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::state::StateSnapshotView;
+use crate::StarcoinOpt;
+use anyhow::{ensure, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_config::RocksdbConfig;
+use starcoin_state_api::snapshot::StateSnapshot;
+use starcoin_state_api::{ChainStateReader, ChainStateWriter};
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::Storage;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "import")]
+/// Import a state snapshot exported by `dev state export` into a fresh data dir,
+/// the state tree is recreated and checked against the state root of the snapshot.
+/// Only the state tree nodes are written, the blocks, chain info and startup info are not,
+/// so the imported db is used to read the state by root, not to start a node from the block.
+pub struct ImportStateOpt {
+    #[structopt(short = "i", long = "input", parse(from_os_str))]
+    /// the snapshot file.
+    input: PathBuf,
+
+    #[structopt(long = "target-dir", parse(from_os_str))]
+    /// the data dir of the node to import to, such as ~/.starcoin/dev, the dir must not contain a db.
+    target_dir: PathBuf,
+}
+
+/// Recreate the state tree of the `snapshot` in the db of `data_dir`, return the imported state root.
+/// Only the state tree nodes are written to the db.
+pub(crate) fn import_snapshot(
+    data_dir: &Path,
+    snapshot: StateSnapshot,
+) -> Result<StateSnapshotView> {
+    let db_dir = data_dir.join("starcoindb");
+    ensure!(
+        !db_dir.exists(),
+        "The db dir {:?} already exists, please import to a fresh data dir.",
+        db_dir
+    );
+    let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(
+        DBStorage::new(db_dir.join("db"), RocksdbConfig::default())?,
+    ))?);
+    let accounts = snapshot.state.len();
+    let statedb = ChainStateDB::new(storage, None);
+    statedb.apply(snapshot.state)?;
+    ensure!(
+        statedb.state_root() == snapshot.state_root,
+        "The imported state root {} mismatch with the snapshot state root {}.",
+        statedb.state_root(),
+        snapshot.state_root
+    );
+    Ok(StateSnapshotView {
+        block_number: snapshot.block_number,
+        block_hash: snapshot.block_hash,
+        state_root: snapshot.state_root,
+        accounts,
+        file: db_dir,
+    })
+}
+
+pub struct ImportStateCommand;
+
+impl CommandAction for ImportStateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ImportStateOpt;
+    type ReturnItem = StateSnapshotView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let snapshot: StateSnapshot =
+            bcs_ext::from_bytes(std::fs::read(opt.input.as_path())?.as_slice())?;
+        import_snapshot(opt.target_dir.as_path(), snapshot)
+    }
+}
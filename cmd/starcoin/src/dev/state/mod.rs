@@ -0,0 +1,27 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands to `export` the chain state at a block to a snapshot file,
+//! and `import` the snapshot into a fresh data dir to seed a test environment with real network state.
+//! The import only writes the state tree nodes, without any block or chain info.
+
+mod export_cmd;
+mod import_cmd;
+
+pub use export_cmd::*;
+pub use import_cmd::*;
+
+use serde::Serialize;
+use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockNumber;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateSnapshotView {
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    pub state_root: HashValue,
+    /// The account count in the snapshot.
+    pub accounts: usize,
+    pub file: PathBuf,
+}
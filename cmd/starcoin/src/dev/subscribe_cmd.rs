@@ -1,10 +1,20 @@
 use crate::cli_state::CliState;
+use crate::dev::decode_cmd::{decode_payload, DecodedPayloadView};
 use crate::StarcoinOpt;
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use futures::{StreamExt, TryStream, TryStreamExt};
 use scmd::{CommandAction, ExecContext};
-use starcoin_rpc_api::types::pubsub::EventFilter;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_rpc_api::types::pubsub::{BlockFilter, EventFilter, PendingTxnFilter};
+use starcoin_rpc_api::types::TypeTagView;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_types::account_address::AccountAddress;
 use starcoin_types::event::EventKey;
+use starcoin_types::transaction::SignedUserTransaction;
+use starcoin_vm_types::state_view::StateView;
+use std::convert::TryInto;
+use std::sync::mpsc;
 use structopt::StructOpt;
 use tokio::io::AsyncBufReadExt;
 
@@ -28,6 +38,15 @@ pub struct SubscribeEventOpt {
         multiple = true
     )]
     event_key: Option<Vec<EventKey>>,
+    #[structopt(short = "a", long = "address", name = "address", multiple = true)]
+    /// only the events emitted to the event handles of the addresses.
+    addresses: Option<Vec<AccountAddress>>,
+    #[structopt(long = "type-tag", name = "type-tag", multiple = true)]
+    /// only the events of the types, example: 0x1::Account::DepositEvent
+    type_tags: Option<Vec<TypeTagView>>,
+    #[structopt(long = "sender", name = "sender", multiple = true)]
+    /// only the events emitted by the txns of the senders.
+    senders: Option<Vec<AccountAddress>>,
     #[structopt(
         short = "l",
         long = "limit",
@@ -51,6 +70,10 @@ impl CommandAction for SubscribeEventCommand {
             from_block: ctx.opt().from_block,
             to_block: ctx.opt().to_block,
             event_keys: ctx.opt().event_key.clone().unwrap_or_default(),
+            addresses: ctx.opt().addresses.clone().unwrap_or_default(),
+            type_tags: ctx.opt().type_tags.clone().unwrap_or_default(),
+            senders: ctx.opt().senders.clone().unwrap_or_default(),
+            any_of: vec![],
             limit: ctx.opt().limit,
         };
 
@@ -66,7 +89,14 @@ impl CommandAction for SubscribeEventCommand {
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "new_block")]
-pub struct SubscribeBlockOpt {}
+pub struct SubscribeBlockOpt {
+    #[structopt(short = "f", long = "from", name = "from_block")]
+    /// only the blocks whose number is not less than it.
+    from_block: Option<u64>,
+    #[structopt(long = "author", name = "author", multiple = true)]
+    /// only the blocks mined by the authors.
+    authors: Option<Vec<AccountAddress>>,
+}
 pub struct SubscribeBlockCommand;
 impl CommandAction for SubscribeBlockCommand {
     type State = CliState;
@@ -77,7 +107,11 @@ impl CommandAction for SubscribeBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let event_stream = ctx.state().client().subscribe_new_blocks()?;
+        let filter = BlockFilter {
+            from_block: ctx.opt().from_block,
+            authors: ctx.opt().authors.clone().unwrap_or_default(),
+        };
+        let event_stream = ctx.state().client().subscribe_new_blocks(filter)?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
@@ -87,7 +121,19 @@ impl CommandAction for SubscribeBlockCommand {
 }
 #[derive(Debug, StructOpt)]
 #[structopt(name = "new_pending_txn")]
-pub struct SubscribeNewTxnOpt {}
+pub struct SubscribeNewTxnOpt {
+    #[structopt(short = "s", long = "sender", name = "sender", multiple = true)]
+    /// only the txns of the senders.
+    senders: Option<Vec<AccountAddress>>,
+
+    #[structopt(short = "r", long = "receiver", name = "receiver", multiple = true)]
+    /// only the txns transferring to the receivers.
+    receivers: Option<Vec<AccountAddress>>,
+
+    #[structopt(long = "decode")]
+    /// notify the decoded txns instead of the txn hashes.
+    decode: bool,
+}
 pub struct SubscribeNewTxnCommand;
 impl CommandAction for SubscribeNewTxnCommand {
     type State = CliState;
@@ -98,20 +144,164 @@ impl CommandAction for SubscribeNewTxnCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let event_stream = ctx.state().client().subscribe_new_transactions()?;
+        let opt = ctx.opt();
+        let filter = PendingTxnFilter {
+            senders: opt.senders.clone().unwrap_or_default(),
+            receivers: opt.receivers.clone().unwrap_or_default(),
+            decode: opt.decode,
+        };
+        let client = ctx.state().client();
         println!("Subscribe successful, Press `q` and Enter to quit");
-        blocking_display_notification(event_stream, |evt| {
-            serde_json::to_string(&evt).expect("should never fail")
+        if filter.decode {
+            let event_stream = client.subscribe_new_decoded_transactions(filter)?;
+            blocking_display_notification(event_stream, |evt| {
+                serde_json::to_string(&evt).expect("should never fail")
+            });
+        } else {
+            let event_stream = client.subscribe_new_transactions(filter)?;
+            blocking_display_notification(event_stream, |evt| {
+                serde_json::to_string(&evt).expect("should never fail")
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Stream the txns newly admitted to the txpool with the decoded payload, the txns which have
+/// left the txpool before they are fetched are skipped, and the txns which can not be fetched or
+/// decoded are reported with the error.
+///  Some examples:
+///  ``` shell
+///  dev subscribe pending-txns
+///  dev subscribe pending-txns --sender 0x1
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "pending-txns")]
+pub struct SubscribePendingTxnOpt {
+    #[structopt(short = "s", long = "sender")]
+    /// only show the txns of the sender.
+    sender: Option<AccountAddress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTxnView {
+    pub transaction_hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    pub max_gas_amount: u64,
+    pub expiration_timestamp_secs: u64,
+    pub payload: DecodedPayloadView,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTxnErrorView {
+    pub transaction_hash: HashValue,
+    pub error: String,
+}
+
+fn pending_txn_view(
+    txn_hash: HashValue,
+    txn: SignedUserTransaction,
+    state_view: &dyn StateView,
+) -> Result<PendingTxnView> {
+    Ok(PendingTxnView {
+        transaction_hash: txn_hash,
+        sender: txn.sender(),
+        sequence_number: txn.sequence_number(),
+        gas_unit_price: txn.gas_unit_price(),
+        max_gas_amount: txn.max_gas_amount(),
+        expiration_timestamp_secs: txn.expiration_timestamp_secs(),
+        payload: decode_payload(state_view, txn.payload().clone())?,
+    })
+}
+
+/// Fetch the pending txn by `fetch` and decode it to a json line, None if the txn has left the
+/// txpool. A txn which can not be fetched or decoded is reported by an error line, so a bad txn
+/// does not stop the stream.
+pub(crate) fn pending_txn_line<F>(
+    txn_hash: HashValue,
+    fetch: F,
+    state_view: &dyn StateView,
+) -> Option<String>
+where
+    F: FnOnce(HashValue) -> Result<Option<SignedUserTransaction>>,
+{
+    let view = fetch(txn_hash).and_then(|txn| {
+        txn.map(|txn| pending_txn_view(txn_hash, txn, state_view))
+            .transpose()
+    });
+    let line = match view {
+        Ok(Some(view)) => serde_json::to_string(&view),
+        Ok(None) => return None,
+        Err(e) => serde_json::to_string(&PendingTxnErrorView {
+            transaction_hash: txn_hash,
+            error: e.to_string(),
+        }),
+    };
+    Some(line.expect("should never fail"))
+}
+
+pub struct SubscribePendingTxnCommand;
+impl CommandAction for SubscribePendingTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SubscribePendingTxnOpt;
+    type ReturnItem = ();
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let filter = PendingTxnFilter {
+            senders: ctx.opt().sender.into_iter().collect(),
+            ..Default::default()
+        };
+        let txn_stream = client.subscribe_new_transactions(filter)?;
+        println!("Subscribe successful, Press `q` and Enter to quit");
+        // The txns are fetched by the blocking rpc client, which can not be called in the
+        // runtime of the notification loop, so the notifications are handled by another thread.
+        let (hash_sender, hash_receiver) = mpsc::channel::<Vec<HashValue>>();
+        let handle = std::thread::spawn(move || {
+            blocking_handle_notification(txn_stream, |txn_hashes| {
+                // the receiver is dropped only if the main thread fails.
+                let _ = hash_sender.send(txn_hashes);
+            })
         });
+        let state_reader = RemoteStateReader::new(client)?;
+        for txn_hash in hash_receiver.into_iter().flatten() {
+            let fetch = |txn_hash| -> Result<Option<SignedUserTransaction>> {
+                Ok(match client.get_pending_txn_by_hash(txn_hash)? {
+                    Some(txn) => Some(txn.try_into()?),
+                    None => None,
+                })
+            };
+            if let Some(line) = pending_txn_line(txn_hash, fetch, &state_reader) {
+                println!("{}", line);
+            }
+        }
+        handle
+            .join()
+            .map_err(|e| format_err!("subscription thread panic: {:?}", e))?;
         Ok(())
     }
 }
 
-fn blocking_display_notification<T, F>(
-    mut event_stream: impl TryStream<Ok = T, Error = anyhow::Error> + Unpin,
+pub(crate) fn blocking_display_notification<T, F>(
+    event_stream: impl TryStream<Ok = T, Error = anyhow::Error> + Unpin,
     display: F,
 ) where
     F: Fn(&T) -> String,
+{
+    blocking_handle_notification(event_stream, |evt| println!("{}", display(&evt)))
+}
+
+/// Handle the notifications until the stream is closed or `q` is entered.
+pub(crate) fn blocking_handle_notification<T, F>(
+    mut event_stream: impl TryStream<Ok = T, Error = anyhow::Error> + Unpin,
+    mut handle: F,
+) where
+    F: FnMut(T),
 {
     let mut rt = tokio::runtime::Builder::new()
         .basic_scheduler()
@@ -133,7 +323,7 @@ fn blocking_display_notification<T, F>(
                    match try_event {
                         Ok(None) => break,
                         Ok(Some(evt)) => {
-                            println!("{}", display(&evt));
+                            handle(evt);
                         }
                         Err(e) => {
                             eprintln!("subscription return err: {}", &e);
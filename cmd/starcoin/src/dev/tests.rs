@@ -1,26 +1,54 @@
+use crate::dev::bench_cmd::{parse_duration, LatencyView};
+use crate::dev::compile_cmd::{compile_files, write_compiled_units};
+use crate::dev::decode_cmd::{decode_blob, BlobType, DecodedPayloadView, DecodedView};
+use crate::dev::fork_exec_cmd::ForkMeta;
+use crate::dev::gas_profile_cmd::{instruction_name, parse_trace_line};
+use crate::dev::genesis::{generate_genesis, validate_genesis, CustomNetworkSpec};
+use crate::dev::module_diff_cmd::{diff_module, ModuleChange};
+use crate::dev::proposal::{build_proposal_execute, is_wait_finished, ProposalState, ProposalView};
+use crate::dev::publish_package_cmd::compile_package;
+use crate::dev::replay_txn_cmd::diff_events;
+use crate::dev::script_function_abi::{encode_script_function_args, load_script_function_abi};
+use crate::dev::seed_cmd::seed_module_source;
+use crate::dev::show_config_cmd::{diff_json, genesis_config_json, OnChainConfigType};
 use crate::dev::sign_txn_helper::{sign_txn_by_rpc_client, sign_txn_with_account_by_rpc_client};
+use crate::dev::state::import_snapshot;
+use crate::dev::subscribe_cmd::{pending_txn_line, PendingTxnErrorView, PendingTxnView};
+use crate::dev::verify_package_cmd::{verify_package, DiagnosticSeverity, VerifyCheck};
 use crate::CliState;
 use anyhow::{format_err, Result};
-use starcoin_config::NodeConfig;
+use starcoin_config::{BuiltinNetworkID, NodeConfig};
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_node::NodeHandle;
 use starcoin_rpc_api::types::{
-    AnnotatedMoveValueView, ContractCall, FunctionIdView, TransactionVMStatus,
+    AnnotatedMoveValueView, ContractCall, FunctionIdView, StrView, TransactionEventView,
+    TransactionVMStatus,
 };
 use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::snapshot::StateSnapshot;
 use starcoin_state_api::AccountStateReader;
 use starcoin_transaction_builder::{
     build_module_upgrade_plan, build_module_upgrade_proposal_v2, build_module_upgrade_queue_v2,
+    build_treasury_withdraw_execute,
 };
 use starcoin_types::transaction::{
     parse_transaction_argument, ScriptFunction, TransactionArgument,
 };
+use starcoin_vm_types::access::ModuleAccess;
+use starcoin_vm_types::access_path::AccessPath;
 use starcoin_vm_types::account_address::AccountAddress;
 use starcoin_vm_types::account_config::core_code_address;
+use starcoin_vm_types::contract_event::ContractEvent;
+use starcoin_vm_types::event::EventKey;
+use starcoin_vm_types::file_format::{Bytecode, CompiledModule};
 use starcoin_vm_types::identifier::Identifier;
-use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::language_storage::{FunctionId, ModuleId, StructTag};
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::token::stc::stc_type_tag;
 use starcoin_vm_types::transaction::{
-    RawUserTransaction, SignedUserTransaction, TransactionPayload,
+    ArgumentABI, RawUserTransaction, ScriptABI, ScriptFunctionABI, SignedUserTransaction,
+    TransactionPayload, TypeArgumentABI,
 };
 use starcoin_vm_types::transaction_argument::convert_txn_args;
 use starcoin_vm_types::{
@@ -28,6 +56,7 @@ use starcoin_vm_types::{
     transaction::Package,
 };
 use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{thread::sleep, time::Duration};
@@ -512,3 +541,750 @@ fn test_only_new_module() {
 
     node_handle.stop().unwrap();
 }
+
+#[stest::test]
+fn test_encode_script_function_args() {
+    let abi = ScriptFunctionABI::new(
+        "propose".to_string(),
+        ModuleId::new(
+            core_code_address(),
+            Identifier::new("ModifyDaoConfigProposal").unwrap(),
+        ),
+        String::new(),
+        vec![TypeArgumentABI::new("T0".to_string())],
+        vec![
+            ArgumentABI::new("arg0".to_string(), TypeTag::U64),
+            ArgumentABI::new("arg1".to_string(), TypeTag::U8),
+            ArgumentABI::new("arg2".to_string(), TypeTag::Vector(Box::new(TypeTag::U8))),
+        ],
+    );
+    let type_args = vec![parse_type_tag("0x1::STC::STC").unwrap()];
+    let args = vec![
+        "3600000".to_string(),
+        "50".to_string(),
+        "x\"0102\"".to_string(),
+    ];
+    let encoded = encode_script_function_args(&abi, type_args.as_slice(), args.as_slice()).unwrap();
+    assert_eq!(encoded[0], bcs_ext::to_bytes(&3600000u64).unwrap());
+    assert_eq!(encoded[1], bcs_ext::to_bytes(&50u8).unwrap());
+    assert_eq!(encoded[2], bcs_ext::to_bytes(&vec![1u8, 2u8]).unwrap());
+
+    // u8 overflow
+    let args = vec!["1".to_string(), "256".to_string(), "x\"\"".to_string()];
+    assert!(encode_script_function_args(&abi, type_args.as_slice(), args.as_slice()).is_err());
+    // type mismatch
+    let args = vec!["true".to_string(), "1".to_string(), "x\"\"".to_string()];
+    assert!(encode_script_function_args(&abi, type_args.as_slice(), args.as_slice()).is_err());
+    // lack of type args
+    let args = vec!["1".to_string(), "1".to_string(), "x\"\"".to_string()];
+    assert!(encode_script_function_args(&abi, &[], args.as_slice()).is_err());
+}
+
+#[stest::test]
+fn test_load_script_function_abi() {
+    let module = ModuleId::new(
+        core_code_address(),
+        Identifier::new("TransferScripts").unwrap(),
+    );
+    let abi = ScriptFunctionABI::new(
+        "peer_to_peer".to_string(),
+        module.clone(),
+        String::new(),
+        vec![TypeArgumentABI::new("token_type".to_string())],
+        vec![
+            ArgumentABI::new("payee".to_string(), TypeTag::Address),
+            ArgumentABI::new(
+                "payee_auth_key".to_string(),
+                TypeTag::Vector(Box::new(TypeTag::U8)),
+            ),
+            ArgumentABI::new("amount".to_string(), TypeTag::U128),
+        ],
+    );
+    let abi_file = std::env::temp_dir().join(format!("peer_to_peer_{}.abi", std::process::id()));
+    std::fs::write(
+        abi_file.as_path(),
+        bcs_ext::to_bytes(&ScriptABI::ScriptFunction(abi.clone())).unwrap(),
+    )
+    .unwrap();
+
+    let function_id = FunctionId {
+        module: module.clone(),
+        function: Identifier::new("peer_to_peer").unwrap(),
+    };
+    let loaded = load_script_function_abi(abi_file.as_path(), &function_id).unwrap();
+    assert_eq!(loaded, abi);
+
+    let other_function_id = FunctionId {
+        module,
+        function: Identifier::new("peer_to_peer_v2").unwrap(),
+    };
+    assert!(load_script_function_abi(abi_file.as_path(), &other_function_id).is_err());
+    std::fs::remove_file(abi_file.as_path()).unwrap();
+}
+
+#[stest::test]
+fn test_build_proposal_execute() {
+    let proposer = AccountAddress::random();
+    let action = |module: &str, name: &str, type_params: Vec<TypeTag>| {
+        TypeTag::Struct(StructTag {
+            address: core_code_address(),
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params,
+        })
+    };
+    let proposal = |action: TypeTag| ProposalView {
+        proposer,
+        id: 1,
+        token: StrView(parse_type_tag("0x1::STC::STC").unwrap()),
+        action: StrView(action),
+        state: ProposalState::Executable,
+        start_time: 0,
+        end_time: 0,
+        for_votes: 0,
+        against_votes: 0,
+        quorum_votes: 0,
+        action_delay: 0,
+        eta: 0,
+    };
+
+    let dao_config_proposal =
+        proposal(action("ModifyDaoConfigProposal", "DaoConfigUpdate", vec![]));
+    let script_function =
+        build_proposal_execute(&dao_config_proposal, AccountAddress::random(), None).unwrap();
+    assert_eq!(script_function.function().as_str(), "execute");
+    assert_eq!(
+        script_function.args()[0],
+        bcs_ext::to_bytes(&proposer).unwrap()
+    );
+
+    let config_proposal = proposal(action(
+        "OnChainConfigDao",
+        "OnChainConfigUpdate",
+        vec![action("VMConfig", "VMConfig", vec![])],
+    ));
+    // on chain config proposal can only be executed by the proposer.
+    assert!(build_proposal_execute(&config_proposal, AccountAddress::random(), None).is_err());
+    let script_function = build_proposal_execute(&config_proposal, proposer, None).unwrap();
+    assert_eq!(
+        script_function.function().as_str(),
+        "execute_on_chain_config_proposal"
+    );
+    assert_eq!(script_function.args().len(), 1);
+
+    let withdraw_proposal = proposal(action(
+        "TreasuryWithdrawDaoProposal",
+        "WithdrawToken",
+        vec![],
+    ));
+    assert_eq!(
+        build_proposal_execute(&withdraw_proposal, proposer, None).unwrap(),
+        build_treasury_withdraw_execute(stc_type_tag(), proposer, 1)
+    );
+
+    // custom action type requires the execute function.
+    let custom_proposal = proposal(TypeTag::Struct(StructTag {
+        address: AccountAddress::random(),
+        module: Identifier::new("MyDao").unwrap(),
+        name: Identifier::new("MyAction").unwrap(),
+        type_params: vec![],
+    }));
+    assert!(build_proposal_execute(&custom_proposal, proposer, None).is_err());
+    let function_id = FunctionIdView::from_str("0x1::MyDao::execute").unwrap().0;
+    assert!(build_proposal_execute(&custom_proposal, proposer, Some(function_id)).is_ok());
+}
+
+#[stest::test]
+fn test_parse_gas_profile_trace() {
+    assert_eq!(
+        parse_trace_line("0x1::Account::withdraw,12"),
+        Some(("0x1::Account::withdraw", 12))
+    );
+    assert_eq!(
+        parse_trace_line("exec_1,0x1::Account::withdraw,3"),
+        Some(("0x1::Account::withdraw", 3))
+    );
+    assert_eq!(
+        parse_trace_line("Script::main,0"),
+        Some(("Script::main", 0))
+    );
+    assert_eq!(parse_trace_line("0x1::Account::withdraw"), None);
+    assert_eq!(parse_trace_line(""), None);
+
+    assert_eq!(instruction_name(&Bytecode::CopyLoc(0)), "CopyLoc");
+    assert_eq!(instruction_name(&Bytecode::Add), "Add");
+}
+
+#[stest::test]
+fn test_module_diff() {
+    let old_source = r#"
+        module M {
+            struct S { a: u64 }
+            struct T { a: u64 }
+            public fun f1(): u64 { 1 }
+            public fun f2(a: u64): u64 { a }
+        }
+        "#;
+    let new_source = r#"
+        module M {
+            struct S { a: u64 }
+            struct T { a: u64, b: bool }
+            struct U { a: u64 }
+            public fun f2(a: u64, b: u64): u64 { a + b }
+            public fun f3(): u64 { 3 }
+        }
+        "#;
+    let compile = |source: &str| {
+        let module = compile_modules_with_address(association_address(), source)
+            .pop()
+            .unwrap();
+        CompiledModule::deserialize(module.code()).unwrap()
+    };
+    let old = compile(old_source);
+    let new = compile(new_source);
+
+    let diff = diff_module(Some(&old), &new);
+    assert_eq!(diff.change, ModuleChange::Changed);
+    assert_eq!(diff.added_functions, vec![Identifier::new("f3").unwrap()]);
+    assert_eq!(diff.removed_functions, vec![Identifier::new("f1").unwrap()]);
+    assert_eq!(diff.changed_functions, vec![Identifier::new("f2").unwrap()]);
+    assert_eq!(diff.added_structs, vec![Identifier::new("U").unwrap()]);
+    assert!(diff.removed_structs.is_empty());
+    assert_eq!(diff.changed_structs, vec![Identifier::new("T").unwrap()]);
+    let compatibility = diff.compatibility.unwrap();
+    assert!(!compatibility.struct_and_function_linking);
+    assert!(!compatibility.struct_layout);
+    assert!(!compatibility.fully_compatible);
+
+    let diff = diff_module(Some(&old), &old);
+    assert_eq!(diff.change, ModuleChange::Unchanged);
+    assert!(diff.compatibility.unwrap().fully_compatible);
+
+    let diff = diff_module(None, &new);
+    assert_eq!(diff.change, ModuleChange::Added);
+    assert_eq!(diff.added_functions.len(), 2);
+    assert!(diff.compatibility.is_none());
+}
+
+#[stest::test]
+fn test_compile_package() {
+    let temp_path = starcoin_config::temp_path();
+    let package_dir = temp_path.path().join("package");
+    std::fs::create_dir_all(package_dir.join("sub")).unwrap();
+    std::fs::write(
+        package_dir.join("A.move"),
+        r#"
+        module A {
+            use {{sender}}::B;
+            public fun a(): address { B::b() }
+        }
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        package_dir.join("sub").join("B.move"),
+        r#"
+        module B {
+            public fun b(): address { {{other}} }
+        }
+        "#,
+    )
+    .unwrap();
+    let sender = association_address();
+    let result = compile_package(package_dir.as_path(), sender, &[], &[]);
+    assert!(result.is_err(), "unresolved named address should fail");
+
+    let modules = compile_package(
+        package_dir.as_path(),
+        sender,
+        &[("other".to_string(), genesis_address())],
+        &[],
+    )
+    .unwrap();
+    let names = modules
+        .iter()
+        .map(|m| m.self_id().name().to_string())
+        .collect::<Vec<_>>();
+    // sorted by dependency order.
+    assert_eq!(names, vec!["B".to_string(), "A".to_string()]);
+}
+
+#[stest::test]
+fn test_proposal_wait_finished() {
+    let mut proposal = ProposalView {
+        proposer: AccountAddress::random(),
+        id: 1,
+        token: StrView(parse_type_tag("0x1::STC::STC").unwrap()),
+        action: StrView(parse_type_tag("0x1::UpgradeModuleDaoProposal::UpgradeModuleV2").unwrap()),
+        state: ProposalState::Pending,
+        start_time: 0,
+        end_time: 0,
+        for_votes: 0,
+        against_votes: 0,
+        quorum_votes: 0,
+        action_delay: 0,
+        eta: 0,
+    };
+    for state in &[
+        ProposalState::Pending,
+        ProposalState::Active,
+        ProposalState::Agreed,
+        ProposalState::Queued,
+    ] {
+        proposal.state = *state;
+        assert!(!is_wait_finished(&proposal).unwrap());
+    }
+    for state in &[ProposalState::Executable, ProposalState::Extracted] {
+        proposal.state = *state;
+        assert!(is_wait_finished(&proposal).unwrap());
+    }
+    proposal.state = ProposalState::Defeated;
+    assert!(is_wait_finished(&proposal).is_err());
+}
+
+#[stest::test]
+fn test_state_export_import() {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let node_handle = run_node_by_config(config).unwrap();
+    let rpc_service = node_handle.rpc_service().unwrap();
+    let rpc_client = RpcClient::connect_local(rpc_service).unwrap();
+    let head = rpc_client.chain_info().unwrap().head;
+    let state = rpc_client.state_dump_by_root(head.state_root).unwrap();
+    node_handle.stop().unwrap();
+
+    let snapshot = StateSnapshot {
+        block_number: head.number.0,
+        block_hash: head.block_hash,
+        state_root: head.state_root,
+        state,
+    };
+    let accounts = snapshot.state.len();
+    let temp_path = starcoin_config::temp_path();
+    let view = import_snapshot(temp_path.path(), snapshot.clone()).unwrap();
+    assert_eq!(view.state_root, head.state_root);
+    assert_eq!(view.accounts, accounts);
+    // import to a data dir with db again should fail.
+    assert!(import_snapshot(temp_path.path(), snapshot).is_err());
+}
+
+#[stest::test]
+fn test_replay_diff_events() {
+    let event = |seq: u64, data: Vec<u8>| {
+        TransactionEventView::from(ContractEvent::new(
+            EventKey::new_from_address(&association_address(), 0),
+            seq,
+            TypeTag::U64,
+            data,
+        ))
+    };
+    let local = vec![event(0, vec![1]), event(1, vec![2])];
+    let mut on_chain = local.clone();
+    // the on chain events are returned with the block info.
+    on_chain[0].block_hash = Some(HashValue::random());
+    assert!(diff_events(local.as_slice(), on_chain.as_slice()).is_empty());
+
+    on_chain[1] = event(1, vec![3]);
+    on_chain.push(event(2, vec![4]));
+    let diffs = diff_events(local.as_slice(), on_chain.as_slice());
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[1].local, None);
+}
+
+#[stest::test]
+fn test_fork_meta() {
+    let temp_path = starcoin_config::temp_path();
+    assert!(ForkMeta::load(temp_path.path()).unwrap().is_none());
+    let meta = ForkMeta {
+        rpc: "ws://127.0.0.1:9870".to_string(),
+        block_number: 10,
+        block_hash: HashValue::random(),
+        chain_id: 254,
+        timestamp: 1_000_000,
+        state_root: HashValue::random(),
+        executed_txns: 1,
+    };
+    meta.save(temp_path.path()).unwrap();
+    assert_eq!(ForkMeta::load(temp_path.path()).unwrap(), Some(meta));
+}
+
+#[stest::test]
+fn test_seed_module_source() {
+    for tokens in &[0usize, 3] {
+        let temp_path = starcoin_config::temp_path();
+        std::fs::write(
+            temp_path.path().join("SeedData.move"),
+            seed_module_source(*tokens),
+        )
+        .unwrap();
+        let modules =
+            compile_package(temp_path.path(), AccountAddress::random(), &[], &[]).unwrap();
+        assert_eq!(modules.len(), 1);
+        let module = &modules[0];
+        assert_eq!(module.self_id().name().as_str(), "SeedData");
+        assert_eq!(module.struct_defs().len(), tokens + 2);
+        let has_init = module.function_defs().iter().any(|def| {
+            module
+                .identifier_at(module.function_handle_at(def.function).name)
+                .as_str()
+                == "init"
+        });
+        assert_eq!(has_init, *tokens > 0);
+    }
+}
+
+#[stest::test]
+fn test_show_config_diff() {
+    for config in &[
+        OnChainConfigType::Vm,
+        OnChainConfigType::Consensus,
+        OnChainConfigType::Dao,
+        OnChainConfigType::Version,
+        OnChainConfigType::GasSchedule,
+    ] {
+        let main = genesis_config_json(BuiltinNetworkID::Main, *config).unwrap();
+        let mut diffs = vec![];
+        diff_json("", &main, &main, &mut diffs);
+        assert!(diffs.is_empty(), "{} should not diff with itself", config);
+    }
+
+    let dev = genesis_config_json(BuiltinNetworkID::Dev, OnChainConfigType::Dao).unwrap();
+    let main = genesis_config_json(BuiltinNetworkID::Main, OnChainConfigType::Dao).unwrap();
+    let mut diffs = vec![];
+    diff_json("", &dev, &main, &mut diffs);
+    let voting_delay = diffs
+        .iter()
+        .find(|diff| diff.path == "voting_delay")
+        .expect("voting delay of dev and main should be different");
+    assert_eq!(voting_delay.on_chain, dev["voting_delay"]);
+    assert_eq!(voting_delay.genesis, main["voting_delay"]);
+}
+
+#[stest::test]
+fn test_generate_and_validate_genesis() {
+    let spec = CustomNetworkSpec::load(
+        r#"
+        chain_name = "my_net"
+        chain_id = 123
+        base = "dev"
+        timestamp = 1621311100863
+        pre_mine_amount = 1000000000
+        boot_nodes = ["/ip4/127.0.0.1/tcp/9840"]
+
+        [consensus]
+        strategy = "dummy"
+        epoch_block_count = 100
+        "#,
+    )
+    .unwrap();
+    let temp_path = starcoin_config::temp_path();
+    let output_dir = temp_path.path().join("my_net");
+    let view = generate_genesis(&spec, output_dir.as_path()).unwrap();
+    assert_eq!(view.net, "my_net:123");
+    assert!(view.association_key_file.is_some());
+    assert_eq!(view.boot_nodes.len(), 1);
+    assert!(view.boot_nodes[0]
+        .seed
+        .ends_with(view.boot_nodes[0].peer_id.to_string().as_str()));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let key_files = vec![
+            view.association_key_file.clone().unwrap(),
+            view.boot_nodes[0].network_key_file.clone(),
+        ];
+        for key_file in key_files {
+            let mode = std::fs::metadata(key_file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+    // generate again to the same dir should fail.
+    assert!(generate_genesis(&spec, output_dir.as_path()).is_err());
+
+    let data_dir = temp_path.path().join("data");
+    std::fs::create_dir_all(data_dir.as_path()).unwrap();
+    assert!(
+        !validate_genesis(data_dir.as_path(), output_dir.as_path())
+            .unwrap()
+            .valid
+    );
+    for file in &["genesis", "genesis_config.json"] {
+        std::fs::copy(output_dir.join(file), data_dir.join(file)).unwrap();
+    }
+    let result = validate_genesis(data_dir.as_path(), output_dir.as_path()).unwrap();
+    assert!(result.valid, "{:?}", result);
+    assert_eq!(result.genesis_block_hash, view.genesis_block_hash);
+
+    // a genesis generated by a different timestamp mismatch.
+    let other_dir = temp_path.path().join("other");
+    let other_spec = CustomNetworkSpec {
+        timestamp: Some(1621311100864),
+        ..spec
+    };
+    generate_genesis(&other_spec, other_dir.as_path()).unwrap();
+    assert!(
+        !validate_genesis(data_dir.as_path(), other_dir.as_path())
+            .unwrap()
+            .valid
+    );
+}
+
+struct ModuleStateView(HashMap<AccessPath, Vec<u8>>);
+
+impl StateView for ModuleStateView {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        Ok(self.0.get(access_path).cloned())
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths.iter().map(|path| self.get(path)).collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
+
+#[stest::test]
+fn test_verify_package() {
+    let address = association_address();
+    let compile = |a_functions: &str| {
+        compile_modules_with_address(
+            address,
+            format!(
+                r#"
+                module B {{
+                    public fun b(): u64 {{ 1 }}
+                }}
+                module A {{
+                    use {:#x}::B;
+                    {}
+                }}
+                "#,
+                address, a_functions
+            )
+            .as_str(),
+        )
+    };
+    let modules = compile("public fun a(): u64 { B::b() }");
+    let (module_b, module_a) = (modules[0].clone(), modules[1].clone());
+    let package = Package::new(vec![module_a.clone()], None).unwrap();
+    let module_id = |module: &starcoin_vm_types::transaction::Module| {
+        CompiledModule::deserialize(module.code())
+            .unwrap()
+            .self_id()
+    };
+
+    // the dependency B is neither in the package nor on chain.
+    let mut state = ModuleStateView(HashMap::new());
+    let view = verify_package(&state, HashValue::zero(), &package).unwrap();
+    assert!(!view.passed);
+    assert_eq!(view.modules, vec!["A".to_string()]);
+    assert_eq!(view.diagnostics.len(), 1);
+    assert_eq!(view.diagnostics[0].check, VerifyCheck::Dependency);
+
+    state.0.insert(
+        AccessPath::from(&module_id(&module_b)),
+        module_b.code().to_vec(),
+    );
+    let view = verify_package(&state, HashValue::zero(), &package).unwrap();
+    assert!(view.passed);
+    assert!(view.diagnostics.is_empty());
+    assert!(view.compatibility.is_empty());
+
+    // the published A has a public function removed by the package.
+    let old_a = compile("public fun a(): u64 { B::b() } public fun c(): u64 { 2 }").remove(1);
+    state
+        .0
+        .insert(AccessPath::from(&module_id(&old_a)), old_a.code().to_vec());
+    let view = verify_package(&state, HashValue::zero(), &package).unwrap();
+    assert!(view.passed);
+    assert_eq!(view.diagnostics.len(), 1);
+    assert_eq!(view.diagnostics[0].check, VerifyCheck::Compatibility);
+    assert_eq!(view.diagnostics[0].severity, DiagnosticSeverity::Warning);
+    assert!(!view.compatibility["A"].fully_compatible);
+}
+
+#[stest::test]
+fn test_compile_files() {
+    let temp_path = starcoin_config::temp_path();
+    let source_dir = temp_path.path().join("sources");
+    std::fs::create_dir_all(source_dir.as_path()).unwrap();
+    std::fs::write(
+        source_dir.join("A.move"),
+        r#"
+        module A {
+            public fun alice(): address { {{alice}} }
+            public(script) fun hello(_account: signer, _x: u64) {}
+        }
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        source_dir.join("B.move"),
+        r#"
+        module B {
+            use {{sender}}::A;
+            public fun b(): address { A::alice() }
+        }
+        "#,
+    )
+    .unwrap();
+    let sender = association_address();
+    let mut named_addresses = std::collections::BTreeMap::new();
+    assert!(compile_files(source_dir.as_path(), sender, &named_addresses, &[], true).is_err());
+
+    named_addresses.insert("alice".to_string(), genesis_address());
+    let units = compile_files(source_dir.as_path(), sender, &named_addresses, &[], true).unwrap();
+    assert_eq!(units.len(), 2);
+    let out_dir = temp_path.path().join("out");
+    let view = write_compiled_units(out_dir.as_path(), units).unwrap();
+    assert_eq!(
+        view.compiled_files,
+        vec![out_dir.join("A.mv"), out_dir.join("B.mv")]
+    );
+    assert_eq!(view.result, out_dir.join("B.mv"));
+    assert_eq!(view.abi_files, vec![out_dir.join("A").join("hello.abi")]);
+    let abi: ScriptABI = bcs_ext::from_bytes(
+        std::fs::read(view.abi_files[0].as_path())
+            .unwrap()
+            .as_slice(),
+    )
+    .unwrap();
+    match abi {
+        ScriptABI::ScriptFunction(abi) => assert_eq!(abi.args().len(), 1),
+        _ => panic!("expect script function abi"),
+    }
+}
+
+#[stest::test]
+fn test_bench_parse_duration_and_latency() {
+    assert_eq!(parse_duration("60s").unwrap(), Duration::from_secs(60));
+    assert_eq!(parse_duration("60").unwrap(), Duration::from_secs(60));
+    assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+    assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+    assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+    assert!(parse_duration("1d").is_err());
+    assert!(parse_duration("s").is_err());
+
+    let latency = LatencyView::from_millis((1..=100).rev().collect());
+    assert_eq!(latency.p50, 50);
+    assert_eq!(latency.p90, 90);
+    assert_eq!(latency.p99, 99);
+    assert_eq!(latency.max, 100);
+    assert_eq!(LatencyView::from_millis(vec![]).max, 0);
+}
+
+#[stest::test]
+fn test_decode_txn_and_payload() {
+    let (chain_state, net) = test_helper::executor::prepare_genesis();
+    let receiver = AccountAddress::random();
+    let txn = starcoin_transaction_builder::raw_peer_to_peer_txn(
+        association_address(),
+        receiver,
+        None,
+        1000,
+        0,
+        1,
+        10000,
+        starcoin_vm_types::token::stc::STC_TOKEN_CODE.clone(),
+        3600,
+        net.chain_id(),
+    );
+    let blob = bcs_ext::to_bytes(&txn).unwrap();
+    let txn_view = match decode_blob(&chain_state, BlobType::Txn, blob.as_slice()).unwrap() {
+        DecodedView::Txn(txn_view) => txn_view,
+        view => panic!("expect txn, but got {:?}", view),
+    };
+    assert!(txn_view.transaction_hash.is_none());
+    assert_eq!(txn_view.sender, association_address());
+    let function = match txn_view.payload {
+        DecodedPayloadView::ScriptFunction(function) => function,
+        payload => panic!("expect script function, but got {:?}", payload),
+    };
+    assert_eq!(
+        function.function.0.module.name().as_str(),
+        "TransferScripts"
+    );
+    assert_eq!(function.function.0.function.as_str(), "peer_to_peer");
+    assert_eq!(function.args.len(), 3);
+    assert!(matches!(
+        function.args[0].value,
+        Some(AnnotatedMoveValueView::Address(addr)) if addr == receiver
+    ));
+    assert!(matches!(
+        function.args[2].value,
+        Some(AnnotatedMoveValueView::U128(StrView(1000)))
+    ));
+
+    // the raw args are kept if the ABI can not be resolved.
+    let payload = bcs_ext::to_bytes(txn.payload()).unwrap();
+    let empty_state = starcoin_statedb::ChainStateDB::mock();
+    match decode_blob(&empty_state, BlobType::Payload, payload.as_slice()).unwrap() {
+        DecodedView::Payload(DecodedPayloadView::ScriptFunction(function)) => {
+            assert!(function.args.is_empty());
+            assert_eq!(function.raw_args.len(), 3);
+        }
+        view => panic!("expect script function payload, but got {:?}", view),
+    }
+
+    assert!(decode_blob(&chain_state, BlobType::Event, blob.as_slice()).is_err());
+    assert_eq!(BlobType::from_str("Payload").unwrap(), BlobType::Payload);
+    assert!(BlobType::from_str("block").is_err());
+}
+
+#[stest::test]
+fn test_pending_txn_line() {
+    let (private_key, public_key) = starcoin_crypto::ed25519::genesis_key_pair();
+    let signed_txn = |payload: TransactionPayload| {
+        RawUserTransaction::new_with_default_gas_token(
+            association_address(),
+            0,
+            payload,
+            10000000,
+            1,
+            3600,
+            starcoin_vm_types::genesis_config::ChainId::test(),
+        )
+        .sign(&private_key, public_key.clone())
+        .unwrap()
+        .into_inner()
+    };
+    let state = ModuleStateView(HashMap::new());
+    let txn_hash = HashValue::random();
+
+    let script_txn = signed_txn(TransactionPayload::Script(
+        starcoin_vm_types::transaction::Script::new(vec![1, 2, 3], vec![], vec![]),
+    ));
+    let line = pending_txn_line(txn_hash, |_| Ok(Some(script_txn.clone())), &state).unwrap();
+    let view: PendingTxnView = serde_json::from_str(line.as_str()).unwrap();
+    assert_eq!(view.transaction_hash, txn_hash);
+    assert_eq!(view.sender, association_address());
+    assert!(matches!(view.payload, DecodedPayloadView::Script { .. }));
+
+    // the txn has left the txpool.
+    assert!(pending_txn_line(txn_hash, |_| Ok(None), &state).is_none());
+
+    // a txn failed to fetch is reported.
+    let line = pending_txn_line(txn_hash, |_| Err(format_err!("fetch failed")), &state).unwrap();
+    let view: PendingTxnErrorView = serde_json::from_str(line.as_str()).unwrap();
+    assert_eq!(view.transaction_hash, txn_hash);
+    assert!(view.error.contains("fetch failed"));
+
+    // a txn failed to decode is reported, the package can not be built by `Package::new` as the
+    // module is invalid.
+    let package = bcs_ext::from_bytes::<Package>(
+        bcs_ext::to_bytes(&(
+            association_address(),
+            vec![starcoin_vm_types::transaction::Module::new(vec![0u8; 4])],
+            Option::<ScriptFunction>::None,
+        ))
+        .unwrap()
+        .as_slice(),
+    )
+    .unwrap();
+    let package_txn = signed_txn(TransactionPayload::Package(package));
+    let line = pending_txn_line(txn_hash, |_| Ok(Some(package_txn.clone())), &state).unwrap();
+    let view: PendingTxnErrorView = serde_json::from_str(line.as_str()).unwrap();
+    assert_eq!(view.transaction_hash, txn_hash);
+    assert!(view.error.contains("invalid module in package"));
+}
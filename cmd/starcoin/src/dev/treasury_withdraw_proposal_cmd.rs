@@ -0,0 +1,117 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::sign_txn_helper::{get_dao_config, sign_txn_with_account_by_rpc_client};
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::HashValue;
+use starcoin_transaction_builder::build_treasury_withdraw_proposal;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::token::stc::stc_type_tag;
+use starcoin_vm_types::transaction::TransactionPayload;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "treasury_withdraw_proposal")]
+/// Propose to withdraw tokens from the treasury to the receiver by the TreasuryWithdrawDaoProposal,
+/// the tokens are released to the receiver linearly in the period after the proposal is executed
+/// by `dev treasury_withdraw_exe`.
+pub struct TreasuryWithdrawProposalOpt {
+    #[structopt(short = "s", long)]
+    /// hex encoded string, like 0x1, 0x12
+    sender: Option<AccountAddress>,
+
+    #[structopt(short = "t", long = "token", parse(try_from_str = parse_type_tag))]
+    /// the token to withdraw, default to 0x1::STC::STC
+    token: Option<TypeTag>,
+
+    #[structopt(long = "receiver")]
+    /// the receiver of the tokens.
+    receiver: AccountAddress,
+
+    #[structopt(long = "amount")]
+    /// how many tokens (in the min unit) to withdraw.
+    amount: u128,
+
+    #[structopt(long = "period")]
+    /// the period in seconds to release the tokens linearly.
+    period: u64,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+}
+
+pub struct TreasuryWithdrawProposalCommand;
+
+impl CommandAction for TreasuryWithdrawProposalCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = TreasuryWithdrawProposalOpt;
+    type ReturnItem = HashValue;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let cli_state = ctx.state();
+        let sender = cli_state.get_account_or_default(opt.sender)?.address;
+        let min_action_delay = get_dao_config(cli_state)?.min_action_delay;
+        let proposal = build_treasury_withdraw_proposal(
+            opt.token.clone().unwrap_or_else(stc_type_tag),
+            opt.receiver,
+            opt.amount,
+            opt.period,
+            min_action_delay,
+        );
+        let signed_txn = sign_txn_with_account_by_rpc_client(
+            cli_state,
+            sender,
+            opt.max_gas_amount,
+            opt.gas_price,
+            opt.expiration_time,
+            TransactionPayload::ScriptFunction(proposal),
+        )?;
+        let txn_hash = signed_txn.id();
+        cli_state.client().submit_transaction(signed_txn)?;
+
+        println!("txn {:#x} submitted.", txn_hash);
+
+        if opt.blocking {
+            cli_state.watch_txn(txn_hash)?;
+        }
+        Ok(txn_hash)
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::dev::module_diff_cmd::CompatibilityView;
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_types::transaction::Package;
+use starcoin_vm_types::access::ModuleAccess;
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::bytecode_verifier::{dependencies, verify_module};
+use starcoin_vm_types::compatibility::Compatibility;
+use starcoin_vm_types::errors::VMError;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::normalized::Module;
+use starcoin_vm_types::state_view::StateView;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Verify a package before publishing it, catching the failures before gas is spent:
+/// the bytecode verifier is run on every module, the dependencies are linked against the package
+/// and the modules on chain at the chosen block, and the upgraded modules are checked for compatibility.
+///  Some examples:
+///  ``` shell
+///  dev verify-package ./my_package.blob
+///  dev verify-package ./my_package.blob --block-number 1000
+///  ```
+#[derive(Debug, StructOpt)]
+#[structopt(name = "verify-package")]
+pub struct VerifyPackageOpt {
+    #[structopt(name = "package", parse(from_os_str))]
+    /// path of the package file, such as the blob file generated by `dev package`.
+    package: PathBuf,
+
+    #[structopt(long = "block-number")]
+    /// verify against the state after the block, if absent, use the latest state.
+    block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyCheck {
+    Deserialize,
+    Bytecode,
+    Dependency,
+    Compatibility,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// the package can not be published.
+    Error,
+    /// the package can be published, but requires attention, such as an enforced upgrade.
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyDiagnostic {
+    /// the module name, or the index of the module in the package if it can not be deserialized.
+    pub module: String,
+    pub check: VerifyCheck,
+    pub severity: DiagnosticSeverity,
+    /// the vm status code of the verifier error.
+    pub status: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyPackageView {
+    pub package_address: AccountAddress,
+    pub state_root: HashValue,
+    pub modules: Vec<String>,
+    /// the compatibility of the modules already published on chain.
+    pub compatibility: BTreeMap<String, CompatibilityView>,
+    pub diagnostics: Vec<VerifyDiagnostic>,
+    /// no error diagnostics, warnings are allowed.
+    pub passed: bool,
+}
+
+impl VerifyDiagnostic {
+    fn error(module: String, check: VerifyCheck, message: String) -> Self {
+        Self {
+            module,
+            check,
+            severity: DiagnosticSeverity::Error,
+            status: None,
+            message,
+        }
+    }
+
+    fn vm_error(module: String, check: VerifyCheck, error: VMError) -> Self {
+        Self {
+            module,
+            check,
+            severity: DiagnosticSeverity::Error,
+            status: Some(format!("{:?}", error.major_status())),
+            message: format!("{:?}", error),
+        }
+    }
+}
+
+fn get_module(state_view: &dyn StateView, module_id: &ModuleId) -> Result<Option<CompiledModule>> {
+    match state_view.get(&AccessPath::from(module_id))? {
+        Some(code) => Ok(Some(CompiledModule::deserialize(code.as_slice()).map_err(
+            |e| format_err!("deserialize module {} on chain error: {:?}", module_id, e),
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// Verify the modules of `package` against the modules in `state_view` at `state_root`.
+pub(crate) fn verify_package(
+    state_view: &dyn StateView,
+    state_root: HashValue,
+    package: &Package,
+) -> Result<VerifyPackageView> {
+    let mut diagnostics = vec![];
+    let mut modules = BTreeMap::new();
+    for (index, module) in package.modules().iter().enumerate() {
+        match CompiledModule::deserialize(module.code()) {
+            Ok(module) => {
+                modules.insert(module.self_id(), module);
+            }
+            Err(e) => diagnostics.push(VerifyDiagnostic::error(
+                format!("#{}", index),
+                VerifyCheck::Deserialize,
+                format!("{:?}", e),
+            )),
+        }
+    }
+
+    let mut compatibility = BTreeMap::new();
+    for (module_id, module) in &modules {
+        let name = module_id.name().to_string();
+        if let Err(e) = verify_module(module) {
+            diagnostics.push(VerifyDiagnostic::vm_error(
+                name.clone(),
+                VerifyCheck::Bytecode,
+                e,
+            ));
+        }
+
+        // the dependencies in the package are preferred, the others are loaded from chain.
+        let mut deps = vec![];
+        let mut missing = vec![];
+        for dep_id in module.immediate_dependencies() {
+            match modules.get(&dep_id) {
+                Some(dep) => deps.push(dep.clone()),
+                None => match get_module(state_view, &dep_id)? {
+                    Some(dep) => deps.push(dep),
+                    None => missing.push(dep_id.to_string()),
+                },
+            }
+        }
+        if !missing.is_empty() {
+            diagnostics.push(VerifyDiagnostic::error(
+                name.clone(),
+                VerifyCheck::Dependency,
+                format!("dependencies not found: {}", missing.join(", ")),
+            ));
+        } else if let Err(e) = dependencies::verify_module(module, deps.iter()) {
+            diagnostics.push(VerifyDiagnostic::vm_error(
+                name.clone(),
+                VerifyCheck::Dependency,
+                e,
+            ));
+        }
+
+        if let Some(old) = get_module(state_view, module_id)? {
+            let result = Compatibility::check(&Module::new(&old), &Module::new(module));
+            if !result.is_fully_compatible() {
+                diagnostics.push(VerifyDiagnostic {
+                    module: name.clone(),
+                    check: VerifyCheck::Compatibility,
+                    severity: DiagnosticSeverity::Warning,
+                    status: None,
+                    message: "the module is not compatible with the module on chain, an enforced upgrade is required".to_string(),
+                });
+            }
+            compatibility.insert(name, result.into());
+        }
+    }
+    Ok(VerifyPackageView {
+        package_address: package.package_address(),
+        state_root,
+        modules: modules
+            .keys()
+            .map(|module_id| module_id.name().to_string())
+            .collect(),
+        compatibility,
+        passed: diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity != DiagnosticSeverity::Error),
+        diagnostics,
+    })
+}
+
+pub struct VerifyPackageCommand;
+
+impl CommandAction for VerifyPackageCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = VerifyPackageOpt;
+    type ReturnItem = VerifyPackageView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let package: Package = bcs_ext::from_bytes(&std::fs::read(opt.package.as_path())?)?;
+        let state_root = match opt.block_number {
+            Some(number) => {
+                client
+                    .chain_get_block_by_number(number)?
+                    .ok_or_else(|| format_err!("block {} not exists", number))?
+                    .header
+                    .state_root
+            }
+            None => client.state_get_state_root()?,
+        };
+        let state_reader = RemoteStateReader::new_with_root(client, state_root);
+        verify_package(&state_reader, state_root, &package)
+    }
+}
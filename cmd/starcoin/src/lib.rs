@@ -8,6 +8,7 @@ pub mod account;
 pub mod chain;
 pub mod cli_state;
 pub mod contract;
+pub mod db;
 pub mod debug;
 pub mod dev;
 pub mod helper;
@@ -15,7 +16,9 @@ pub mod mutlisig_transaction;
 pub mod node;
 pub mod state;
 mod txpool;
+pub mod update;
 pub mod view;
+pub mod wallet;
 
 pub use cli_state::CliState;
 pub use starcoin_config::StarcoinOpt;
@@ -33,6 +36,7 @@ pub fn add_command(
                 .subcommand(account::AcceptTokenCommand)
                 .subcommand(account::ListCommand)
                 .subcommand(account::PartialSignTxnCommand)
+                .subcommand(account::SubmitTxnCommand)
                 .subcommand(account::UnlockCommand)
                 .subcommand(account::ExportCommand)
                 .subcommand(account::ImportCommand)
@@ -42,7 +46,15 @@ pub fn add_command(
                 .subcommand(account::ChangePasswordCmd)
                 .subcommand(account::SignMessageCmd)
                 .subcommand(account::VerifySignMessageCmd)
-                .subcommand(account::DefaultCommand),
+                .subcommand(account::DefaultCommand)
+                .subcommand(account::WatchCommand)
+                .subcommand(account::GasReportCommand)
+                .subcommand(
+                    Command::with_name("multisig").subcommand(account::MultisigRotateCommand),
+                )
+                .subcommand(
+                    Command::with_name("emergency").subcommand(account::EmergencyLockAllCommand),
+                ),
         )
         .command(
             Command::with_name("state")
@@ -54,6 +66,7 @@ pub fn add_command(
         .command(
             Command::with_name("node")
                 .subcommand(node::InfoCommand)
+                .subcommand(node::HealthCommand)
                 .subcommand(node::PeersCommand)
                 .subcommand(node::MetricsCommand)
                 .subcommand(
@@ -61,9 +74,8 @@ pub fn add_command(
                         .subcommand(node::service::ListCommand)
                         .subcommand(node::service::StartCommand)
                         .subcommand(node::service::CheckCommand)
-                        .subcommand(node::service::StopCommand)
-                    //TODO support shutdown by command    
-                    //.subcommand(node::service::ShutdownSystemCommand),
+                        .subcommand(node::service::StopCommand), //TODO support shutdown by command
+                                                                 //.subcommand(node::service::ShutdownSystemCommand),
                 )
                 .subcommand(
                     Command::with_name("sync")
@@ -71,16 +83,19 @@ pub fn add_command(
                         .subcommand(node::sync::StatusCommand)
                         .subcommand(node::sync::ProgressCommand)
                         .subcommand(node::sync::CancelCommand)
-                        .subcommand(node::sync::PeerScoreCommand)
+                        .subcommand(node::sync::PeerScoreCommand),
                 )
                 .subcommand(
-                Command::with_name("network")
-                    .subcommand(node::network::StateCommand)
-                    .subcommand(node::network::KnownPeersCommand)
-                    .subcommand(node::network::GetAddressCommand)
-                    .subcommand(node::network::AddPeerCommand)
-                    .subcommand(node::network::CallPeerCommand)
-            ),
+                    Command::with_name("network")
+                        .subcommand(node::network::StateCommand)
+                        .subcommand(node::network::KnownPeersCommand)
+                        .subcommand(node::network::PeersDetailCommand)
+                        .subcommand(node::network::GetAddressCommand)
+                        .subcommand(node::network::AddPeerCommand)
+                        .subcommand(node::network::RemovePeerCommand)
+                        .subcommand(node::network::BanPeerCommand)
+                        .subcommand(node::network::CallPeerCommand),
+                ),
         )
         .command(
             Command::with_name("chain")
@@ -119,11 +134,28 @@ pub fn add_command(
             Command::with_name("txpool")
                 .subcommand(txpool::PendingTxnCommand)
                 .subcommand(txpool::PendingTxnsCommand)
-                .subcommand(txpool::TxPoolStatusCommand),
+                .subcommand(txpool::TxPoolStatusCommand)
+                .subcommand(txpool::TxPoolInspectCommand),
+        )
+        .command(
+            Command::with_name("db")
+                .subcommand(db::BackupCommand)
+                .subcommand(db::CompactCommand)
+                .subcommand(db::ExportChainCommand)
+                .subcommand(db::VerifyExportCommand)
+                .stateless_subcommand(db::migrate_cmd::run_migrate)
+                .stateless_subcommand(db::repair_cmd::run_repair)
+                .stateless_subcommand(db::restore_cmd::run_restore)
+                .stateless_subcommand(|opt: db::verify_cmd::VerifyOpt| {
+                    db::verify_cmd::run_verify(opt).and_then(db::verify_cmd::check_verify_report)
+                })
+                .stateless_subcommand(db::import_chain_cmd::run_import_chain),
         )
         .command(
             Command::with_name("dev")
                 .subcommand(dev::GetCoinCommand)
+                .subcommand(dev::SeedCommand)
+                .subcommand(dev::ShowConfigCommand)
                 .subcommand(dev::CompileCommand)
                 .subcommand(dev::DeployCommand)
                 .subcommand(dev::DeriveAddressCommand)
@@ -136,16 +168,71 @@ pub fn add_command(
                 .subcommand(dev::UpgradeModuleQueueV2Command)
                 .subcommand(dev::UpgradeModuleExeCommand)
                 .subcommand(dev::UpgradeVMConfigProposalCommand)
+                .subcommand(dev::TreasuryWithdrawProposalCommand)
+                .subcommand(dev::TreasuryWithdrawExeCommand)
+                .subcommand(dev::ModuleDiffCommand)
+                .subcommand(dev::VerifyPackageCommand)
+                .subcommand(dev::PublishPackageCommand)
+                .subcommand(dev::ReplayTxnCommand)
+                .subcommand(dev::ForkExecCommand)
+                .stateless_subcommand(dev::run_localnet)
+                .subcommand(dev::DryRunCommand)
+                .subcommand(dev::ProposeCommand)
+                .subcommand(dev::CastVoteCommand)
+                .subcommand(dev::UnstakeVotesCommand)
+                .subcommand(
+                    Command::with_name("proposal")
+                        .subcommand(dev::proposal::ListProposalCommand)
+                        .subcommand(dev::proposal::ShowProposalCommand)
+                        .subcommand(dev::proposal::VoteProposalCommand)
+                        .subcommand(dev::proposal::QueueProposalCommand)
+                        .subcommand(dev::proposal::ExecuteProposalCommand)
+                        .subcommand(dev::proposal::RevokeProposalCommand)
+                        .subcommand(dev::proposal::WaitProposalCommand),
+                )
+                .subcommand(
+                    Command::with_name("state")
+                        .subcommand(dev::state::ExportStateCommand)
+                        .subcommand(dev::state::ImportStateCommand),
+                )
+                .subcommand(
+                    Command::with_name("genesis")
+                        .subcommand(dev::genesis::GenerateGenesisCommand)
+                        .subcommand(dev::genesis::ValidateGenesisCommand),
+                )
                 .subcommand(dev::PackageCmd)
                 .subcommand(dev::CallContractCommand)
+                .subcommand(dev::GasProfileCommand)
+                .subcommand(dev::BenchCommand)
+                .subcommand(dev::DecodeCommand)
+                .subcommand(dev::EstimateGasCommand)
+                .subcommand(dev::SignPeerIdentityCommand)
                 .subcommand(
                     Command::with_name("subscribe")
                         .subcommand(dev::SubscribeBlockCommand)
                         .subcommand(dev::SubscribeEventCommand)
-                        .subcommand(dev::SubscribeNewTxnCommand),
+                        .subcommand(dev::SubscribeNewTxnCommand)
+                        .subcommand(dev::SubscribePendingTxnCommand),
+                ),
+        )
+        .command(
+            Command::with_name("contract")
+                .subcommand(contract::GetContractDataCommand)
+                .subcommand(
+                    Command::with_name("abi")
+                        .subcommand(contract::AbiPublishCommand)
+                        .subcommand(contract::AbiFetchCommand),
                 ),
         )
-        .command(Command::with_name("contract").subcommand(contract::GetContractDataCommand))
+        .command(
+            Command::with_name("wallet")
+                .subcommand(
+                    Command::with_name("watch-only")
+                        .subcommand(wallet::WatchOnlySyncCommand)
+                        .subcommand(wallet::WatchOnlySubmitCommand),
+                )
+                .subcommand(Command::with_name("cold").subcommand(wallet::ColdSignCommand)),
+        )
         .command(
             Command::with_name("debug")
                 .subcommand(
@@ -159,6 +246,10 @@ pub fn add_command(
                 .subcommand(TxPoolStatusCommand)
                 .subcommand(SleepCommand)
                 .subcommand(GenBlockCommand)
-                .subcommand(debug::MoveExplain),
+                .subcommand(debug::MoveExplain)
+                .subcommand(debug::UsageMetricsCommand),
         )
+        // the update command replaces the binary itself, so it is executed without connecting to
+        // (or starting) a node.
+        .stateless_command(update::run_update)
 }
@@ -9,7 +9,9 @@ use starcoin_config::{Connect, APP_VERSION, CRATE_VERSION};
 use starcoin_logger::prelude::*;
 use starcoin_node::crash_handler;
 use starcoin_node_api::errors::NodeStartError;
+use starcoin_rpc_api::errors::ErrorCategory;
 use starcoin_rpc_client::RpcClient;
+use starcoin_storage::errors::StorageInitError;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,6 +19,14 @@ use std::time::Duration;
 /// Node start script can do auto task when meet this exist code.
 static EXIT_CODE_NEED_HELP: i32 = 120;
 
+/// The exit code of a failed command, decided by the category of the rpc error,
+/// see `ErrorCategory::exit_code`, and 1 for other errors.
+fn exit_code(e: &anyhow::Error) -> i32 {
+    ErrorCategory::of_error(e)
+        .map(ErrorCategory::exit_code)
+        .unwrap_or(1)
+}
+
 fn run() -> Result<()> {
     let logger_handle = starcoin_logger::init();
     let context = CmdContext::<CliState, StarcoinOpt>::with_default_action(
@@ -111,6 +121,11 @@ fn run() -> Result<()> {
             }
         },
     );
+    let context = if CliState::usage_metrics_enabled() {
+        context.with_usage_metrics(CliState::usage_metrics_file())
+    } else {
+        context
+    };
     add_command(context).exec()
 }
 
@@ -126,6 +141,10 @@ fn main() {
                         error!("{:?}, please fix config.", e);
                         std::process::exit(EXIT_CODE_NEED_HELP);
                     }
+                    NodeStartError::StorageInitError(StorageInitError::StorageVersionError(e)) => {
+                        error!("{:?}", e);
+                        std::process::exit(EXIT_CODE_NEED_HELP);
+                    }
                     NodeStartError::StorageInitError(e) => {
                         error!("{:?}, please clean your data dir.", e);
                         std::process::exit(EXIT_CODE_NEED_HELP);
@@ -146,12 +165,12 @@ fn main() {
                         }
                         CmdError::Other(e) => {
                             error!("Starcoin cmd return error: {:?}", e);
-                            std::process::exit(1);
+                            std::process::exit(exit_code(&e));
                         }
                     },
                     Err(e) => {
                         error!("Starcoin cmd exits abnormally: {:?}", e);
-                        std::process::exit(1);
+                        std::process::exit(exit_code(&e));
                     }
                 },
             }
@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::node::NodeHealth;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt, Default)]
+#[structopt(name = "health")]
+pub struct HealthOpt {}
+
+pub struct HealthCommand;
+
+impl CommandAction for HealthCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = HealthOpt;
+    type ReturnItem = NodeHealth;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let node_health = client.node_health()?;
+        Ok(node_health)
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod health_cmd;
 mod info_cmd;
 mod metrics_cmd;
 mod peers_cmd;
@@ -9,6 +10,7 @@ pub mod network;
 pub mod service;
 pub mod sync;
 
+pub use health_cmd::*;
 pub use info_cmd::*;
 pub use metrics_cmd::*;
 pub use peers_cmd::*;
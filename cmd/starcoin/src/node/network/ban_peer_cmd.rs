@@ -0,0 +1,39 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::peer_info::PeerId;
+use std::time::Duration;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ban_peer")]
+///Disconnect from the peer, and refuse its connections for the duration
+pub struct BanPeerOpt {
+    #[structopt(name = "peer_id")]
+    peer_id: PeerId,
+    #[structopt(long = "duration", short = "d", default_value = "3600")]
+    /// ban duration in seconds
+    duration: u64,
+}
+
+pub struct BanPeerCommand;
+
+impl CommandAction for BanPeerCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = BanPeerOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        client.node_admin_ban_peer(opt.peer_id.clone(), Duration::from_secs(opt.duration))
+    }
+}
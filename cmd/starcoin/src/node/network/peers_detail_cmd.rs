@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::types::PeerDetailView;
+use structopt::StructOpt;
+
+/// Show the diagnostic detail of the connected peers.
+#[derive(Debug, StructOpt, Default)]
+#[structopt(name = "peers_detail")]
+pub struct PeersDetailOpt {}
+
+pub struct PeersDetailCommand;
+
+impl CommandAction for PeersDetailCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = PeersDetailOpt;
+    type ReturnItem = Vec<PeerDetailView>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        client.network_get_peers_detail()
+    }
+}
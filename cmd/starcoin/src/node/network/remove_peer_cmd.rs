@@ -0,0 +1,34 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::peer_info::PeerId;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "remove_peer")]
+///Remove the peer from the reserved peers, and disconnect from it
+pub struct RemovePeerOpt {
+    #[structopt(name = "peer_id")]
+    peer_id: PeerId,
+}
+
+pub struct RemovePeerCommand;
+
+impl CommandAction for RemovePeerCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = RemovePeerOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        client.node_admin_remove_peer(ctx.opt().peer_id.clone())
+    }
+}
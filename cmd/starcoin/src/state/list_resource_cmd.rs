@@ -5,7 +5,7 @@ use crate::cli_state::CliState;
 use crate::StarcoinOpt;
 use anyhow::{format_err, Result};
 use scmd::{CommandAction, ExecContext};
-use starcoin_rpc_api::types::{AnnotatedMoveStructView, StructTagView};
+use starcoin_rpc_api::types::{AnnotatedMoveStructView, StructTagFilterView, StructTagView};
 use starcoin_vm_types::account_address::AccountAddress;
 use std::collections::BTreeMap;
 use structopt::StructOpt;
@@ -16,6 +16,11 @@ pub struct ListResourceOpt {
     #[structopt(name = "address")]
     /// address which the resources is under of.
     account_address: AccountAddress,
+
+    #[structopt(long = "filter")]
+    /// only list the resources whose type matches the pattern, such as 0x1::Account::Balance<*>,
+    /// the address, module and name can be `*`.
+    filter: Option<StructTagFilterView>,
 }
 
 pub struct ListResourceCmd;
@@ -34,10 +39,27 @@ impl CommandAction for ListResourceCmd {
         let opt = ctx.opt();
         let account_addr = opt.account_address;
 
-        let state = client
-            .get_account_state_set(account_addr)?
-            .ok_or_else(|| format_err!("Account with address {} state not exist.", account_addr))?;
+        if opt.filter.is_none() {
+            let state = client.get_account_state_set(account_addr)?.ok_or_else(|| {
+                format_err!("Account with address {} state not exist.", account_addr)
+            })?;
+            return Ok(state.resources);
+        }
 
-        Ok(state.resources)
+        let mut resources = BTreeMap::new();
+        let mut cursor = None;
+        loop {
+            let page = client
+                .state_list_resources(account_addr, opt.filter.clone(), cursor, None)?
+                .ok_or_else(|| {
+                    format_err!("Account with address {} state not exist.", account_addr)
+                })?;
+            resources.extend(page.resources);
+            match page.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        Ok(resources)
     }
 }
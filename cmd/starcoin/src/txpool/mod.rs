@@ -6,7 +6,7 @@ use crate::StarcoinOpt;
 use anyhow::Result;
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::HashValue;
-use starcoin_rpc_api::types::SignedUserTransactionView;
+use starcoin_rpc_api::types::{SignedUserTransactionView, TxPoolInspectView};
 use starcoin_txpool_api::TxPoolStatus;
 use starcoin_vm_types::account_address::AccountAddress;
 use structopt::StructOpt;
@@ -88,3 +88,34 @@ impl CommandAction for TxPoolStatusCommand {
         client.txpool_status()
     }
 }
+
+/// Get the summary of the txns in tx pool, grouped by sender and keyed by sequence number
+#[derive(Debug, StructOpt)]
+#[structopt(name = "inspect")]
+pub struct TxPoolInspectOpt {
+    #[structopt(
+        long = "cursor",
+        help = "only show the txns of the senders after the cursor, the next_cursor of the last page"
+    )]
+    cursor: Option<AccountAddress>,
+    #[structopt(long = "limit", help = "max count of the senders, at most 100")]
+    limit: Option<u64>,
+}
+
+pub struct TxPoolInspectCommand;
+
+impl CommandAction for TxPoolInspectCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = TxPoolInspectOpt;
+    type ReturnItem = TxPoolInspectView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        client.txpool_inspect(opt.cursor, opt.limit)
+    }
+}
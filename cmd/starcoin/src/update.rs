@@ -0,0 +1,337 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Self update of the starcoin binary from the release channel.
+//!
+//! The release metadata of a channel is a json `SignedReleaseMetadata`, the `ReleaseMetadata`
+//! is signed by the release key, and every platform binary is checked by the sha3-256 hash
+//! recorded in the signed metadata. The public key of the release key is pinned into the
+//! binary at build time, and an update never downgrades the binary.
+
+use anyhow::{bail, ensure, format_err, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use starcoin_config::CRATE_VERSION;
+use starcoin_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use starcoin_crypto::hash::{CryptoHash, CryptoHasher};
+use starcoin_crypto::{HashValue, Signature, ValidCryptoMaterialStringExt};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+use structopt::StructOpt;
+
+pub const DEFAULT_RELEASE_URL: &str = "https://github.com/starcoinorg/starcoin/releases/download";
+/// The hex encoded public key of the release key, pinned by the release build with the
+/// build env `STARCOIN_RELEASE_PUBLIC_KEY`. The binaries built without it can not self update.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("STARCOIN_RELEASE_PUBLIC_KEY");
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    Stable,
+    Nightly,
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseChannel::Stable => write!(f, "stable"),
+            ReleaseChannel::Nightly => write!(f, "nightly"),
+        }
+    }
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stable" => Ok(ReleaseChannel::Stable),
+            "nightly" => Ok(ReleaseChannel::Nightly),
+            _ => bail!("Unknown release channel: {}, expect stable or nightly", s),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    /// The platform of the binary, such as `linux-x86_64`, see `current_platform`.
+    pub platform: String,
+    pub url: String,
+    /// The sha3-256 hash of the binary.
+    pub hash: HashValue,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, CryptoHash)]
+pub struct ReleaseMetadata {
+    pub channel: ReleaseChannel,
+    pub version: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl ReleaseMetadata {
+    pub fn asset(&self, platform: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.platform == platform)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedReleaseMetadata {
+    pub metadata: ReleaseMetadata,
+    pub signature: Ed25519Signature,
+}
+
+impl SignedReleaseMetadata {
+    /// Verify the signature and return the metadata.
+    pub fn verify(self, public_key: &Ed25519PublicKey) -> Result<ReleaseMetadata> {
+        self.signature
+            .verify(&self.metadata, public_key)
+            .map_err(|e| format_err!("Invalid release metadata signature: {}", e))?;
+        Ok(self.metadata)
+    }
+}
+
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "update")]
+/// Update the starcoin binary to the latest release of the channel.
+/// The release metadata is verified by the release public key built into the binary,
+/// and the old binary is restored if the new binary fails to run.
+pub struct UpdateOpt {
+    #[structopt(long = "channel", default_value = "stable")]
+    /// the release channel, stable or nightly.
+    channel: ReleaseChannel,
+
+    #[structopt(long = "release-url", default_value = DEFAULT_RELEASE_URL)]
+    /// the base url of the release metadata, the metadata is at <release-url>/<channel>/metadata.json.
+    release_url: String,
+
+    #[structopt(long = "check")]
+    /// only check the latest release, do not update.
+    check: bool,
+
+    #[structopt(long = "force")]
+    /// reinstall the binary if the current version is same as the release version,
+    /// a release older than the current version is always rejected.
+    force: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateView {
+    pub channel: ReleaseChannel,
+    pub current_version: String,
+    pub release_version: String,
+    pub updated: bool,
+    /// The backup of the old binary.
+    pub backup: Option<PathBuf>,
+}
+
+fn http_get(url: &str) -> Result<Vec<u8>> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send()?.error_for_status()?;
+    Ok(response.bytes()?.to_vec())
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.as_os_str().to_owned();
+    file_name.push(".");
+    file_name.push(extension);
+    PathBuf::from(file_name)
+}
+
+/// Replace the `binary` with `new_binary`, the old binary is moved to `<binary>.bak`,
+/// and restored if the new binary can not run.
+pub(crate) fn swap_binary(binary: &Path, new_binary: &[u8]) -> Result<PathBuf> {
+    let new_path = with_extension(binary, "new");
+    let backup_path = with_extension(binary, "bak");
+    std::fs::write(new_path.as_path(), new_binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(new_path.as_path(), std::fs::Permissions::from_mode(0o755))?;
+    }
+    std::fs::rename(binary, backup_path.as_path())?;
+    if let Err(e) = std::fs::rename(new_path.as_path(), binary) {
+        std::fs::rename(backup_path.as_path(), binary)?;
+        bail!(
+            "Install new binary failed, rollback to the old binary: {}",
+            e
+        );
+    }
+    let check = Command::new(binary).arg("--version").output();
+    match &check {
+        Ok(output) if output.status.success() => Ok(backup_path),
+        _ => {
+            std::fs::rename(backup_path.as_path(), binary)?;
+            bail!(
+                "The new binary can not run ({:?}), rollback to the old binary.",
+                check
+            )
+        }
+    }
+}
+
+fn release_public_key() -> Result<Ed25519PublicKey> {
+    let key = RELEASE_PUBLIC_KEY.ok_or_else(|| {
+        format_err!("The binary is built without the release public key, it can not self update.")
+    })?;
+    Ed25519PublicKey::from_encoded_string(key)
+        .map_err(|e| format_err!("Invalid built-in release public key: {}", e))
+}
+
+/// Check the release version against the current version, return whether to install the release.
+/// A release older than the current version is rejected, and the same version is only
+/// reinstalled if `force` is set.
+pub(crate) fn check_release_version(current: &str, release: &str, force: bool) -> Result<bool> {
+    let current = Version::parse(current)
+        .map_err(|e| format_err!("Invalid current version {}: {}", current, e))?;
+    let release = Version::parse(release)
+        .map_err(|e| format_err!("Invalid release version {}: {}", release, e))?;
+    ensure!(
+        release >= current,
+        "The release version {} is older than the current version {}, refuse to downgrade.",
+        release,
+        current
+    );
+    Ok(release > current || force)
+}
+
+pub fn run_update(opt: UpdateOpt) -> Result<UpdateView> {
+    let public_key = release_public_key()?;
+    let metadata_url = format!(
+        "{}/{}/metadata.json",
+        opt.release_url.trim_end_matches('/'),
+        opt.channel
+    );
+    let signed_metadata: SignedReleaseMetadata =
+        serde_json::from_slice(http_get(metadata_url.as_str())?.as_slice())?;
+    let metadata = signed_metadata.verify(&public_key)?;
+    ensure!(
+        metadata.channel == opt.channel,
+        "Release channel mismatch, expect: {}, got: {}",
+        opt.channel,
+        metadata.channel
+    );
+    let mut view = UpdateView {
+        channel: opt.channel,
+        current_version: CRATE_VERSION.to_string(),
+        release_version: metadata.version.clone(),
+        updated: false,
+        backup: None,
+    };
+    let install = check_release_version(CRATE_VERSION, metadata.version.as_str(), opt.force)?;
+    if opt.check || !install {
+        return Ok(view);
+    }
+    let platform = current_platform();
+    let asset = metadata
+        .asset(platform.as_str())
+        .ok_or_else(|| format_err!("No release binary for platform {}", platform))?;
+    let binary = http_get(asset.url.as_str())?;
+    let hash = HashValue::sha3_256_of(binary.as_slice());
+    ensure!(
+        hash == asset.hash,
+        "Release binary hash mismatch, expect: {}, got: {}",
+        asset.hash,
+        hash
+    );
+    let current_exe = std::env::current_exe()?;
+    view.backup = Some(swap_binary(current_exe.as_path(), binary.as_slice())?);
+    view.updated = true;
+    Ok(view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::keygen::KeyGen;
+    use starcoin_crypto::SigningKey;
+
+    #[test]
+    fn test_verify_release_metadata() {
+        let (private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+        let metadata = ReleaseMetadata {
+            channel: ReleaseChannel::Stable,
+            version: "1.0.0".to_string(),
+            assets: vec![ReleaseAsset {
+                platform: current_platform(),
+                url: "http://localhost/starcoin".to_string(),
+                hash: HashValue::sha3_256_of(b"starcoin"),
+            }],
+        };
+        let signature = private_key.sign(&metadata);
+        let signed = SignedReleaseMetadata {
+            metadata: metadata.clone(),
+            signature,
+        };
+        let json = serde_json::to_string(&signed).unwrap();
+        let signed: SignedReleaseMetadata = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(signed.clone().verify(&public_key).unwrap(), metadata);
+        assert!(metadata.asset(current_platform().as_str()).is_some());
+
+        let mut tampered = signed.clone();
+        tampered.metadata.assets[0].hash = HashValue::random();
+        assert!(tampered.verify(&public_key).is_err());
+        let other_key = KeyGen::from_os_rng().generate_keypair().1;
+        assert!(signed.verify(&other_key).is_err());
+    }
+
+    #[test]
+    fn test_check_release_version() {
+        assert!(check_release_version("1.0.0", "1.0.1", false).unwrap());
+        assert!(!check_release_version("1.0.0", "1.0.0", false).unwrap());
+        assert!(check_release_version("1.0.0", "1.0.0", true).unwrap());
+        assert!(check_release_version("1.0.0-nightly.1", "1.0.0", false).unwrap());
+        assert!(check_release_version("1.0.1", "1.0.0", false).is_err());
+        assert!(check_release_version("1.0.1", "1.0.0", true).is_err());
+        assert!(check_release_version("1.0.0", "latest", false).is_err());
+    }
+
+    #[cfg(unix)]
+    fn write_script(path: &Path, exit_code: i32) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, format!("#!/bin/sh\nexit {}\n", exit_code)).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_swap_binary() {
+        let temp_path = starcoin_config::temp_path();
+        let binary = temp_path.path().join("starcoin");
+        write_script(binary.as_path(), 0);
+        let old_binary = std::fs::read(binary.as_path()).unwrap();
+
+        let new_binary = b"#!/bin/sh\necho new\nexit 0\n";
+        let backup = swap_binary(binary.as_path(), new_binary).unwrap();
+        assert_eq!(
+            std::fs::read(binary.as_path()).unwrap(),
+            new_binary.to_vec()
+        );
+        assert_eq!(std::fs::read(backup.as_path()).unwrap(), old_binary);
+        assert!(!with_extension(binary.as_path(), "new").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_swap_binary_rollback() {
+        let temp_path = starcoin_config::temp_path();
+        let binary = temp_path.path().join("starcoin");
+        write_script(binary.as_path(), 0);
+        let old_binary = std::fs::read(binary.as_path()).unwrap();
+
+        // The new binary fails to run, the old binary is restored.
+        let broken_binary = b"#!/bin/sh\nexit 1\n";
+        assert!(swap_binary(binary.as_path(), broken_binary).is_err());
+        assert_eq!(std::fs::read(binary.as_path()).unwrap(), old_binary);
+        assert!(!with_extension(binary.as_path(), "bak").exists());
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use starcoin_account_api::AccountInfo;
 use starcoin_crypto::HashValue;
 use starcoin_rpc_api::types::{
-    StrView, TransactionEventView, TransactionOutputAction, TransactionOutputView,
+    AnnotatedMoveValueView, DryRunOutputAction, DryRunOutputView, StrView, TransactionEventView,
     TransactionVMStatus,
 };
 use starcoin_types::account_address::AccountAddress;
@@ -18,6 +18,7 @@ use starcoin_vm_types::account_config::{BlockRewardEvent, ProposalCreatedEvent,
 use starcoin_vm_types::event::EventKey;
 use starcoin_vm_types::move_resource::MoveResource;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 //TODO add a derive to auto generate View Object
 
@@ -205,11 +206,20 @@ impl From<ContractEvent> for EventView {
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct OutputEventView {
+    #[serde(flatten)]
+    pub event: EventView,
+    /// The event data decoded by the move type of the event.
+    pub decoded_data: Option<AnnotatedMoveValueView>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TranscationOutputView {
-    pub write_set: Vec<TransactionOutputAction>,
+    /// The write set with the decoded resources.
+    pub write_set: Vec<DryRunOutputAction>,
     /// The list of events emitted during this transaction.
-    pub events: Vec<EventView>,
+    pub events: Vec<OutputEventView>,
 
     /// The amount of gas used during execution.
     pub gas_used: u64,
@@ -218,11 +228,18 @@ pub struct TranscationOutputView {
     pub status: TransactionVMStatus,
 }
 
-impl From<TransactionOutputView> for TranscationOutputView {
-    fn from(output: TransactionOutputView) -> Self {
+impl From<DryRunOutputView> for TranscationOutputView {
+    fn from(output: DryRunOutputView) -> Self {
         Self {
             write_set: output.write_set,
-            events: output.events.into_iter().map(|e| e.into()).collect(),
+            events: output
+                .events
+                .into_iter()
+                .map(|e| OutputEventView {
+                    event: e.event.into(),
+                    decoded_data: e.decoded_data,
+                })
+                .collect(),
             gas_used: output.gas_used.0,
             status: output.status,
         }
@@ -234,6 +251,11 @@ impl From<TransactionOutputView> for TranscationOutputView {
 pub enum ExecuteResultView {
     DryRun(TranscationOutputView),
     Run(ExecutionOutputView),
+    /// The txn is signed offline and saved to file, waiting for submitting by a online node.
+    Offline {
+        txn_hash: HashValue,
+        output_file: PathBuf,
+    },
 }
 
 #[derive(Serialize, Debug, Clone, Copy)]
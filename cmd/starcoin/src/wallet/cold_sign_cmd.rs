@@ -0,0 +1,63 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::wallet::{SignedTxnsFile, UnsignedTxnsFile, SIGNED_TXNS_FILE_EXTENSION};
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use std::env::current_dir;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "sign")]
+/// Sign the unsigned txns file built by `wallet watch-only sync` on the offline machine,
+/// output the signed txns file, which is submitted by `wallet watch-only submit` on the online machine.
+pub struct ColdSignOpt {
+    #[structopt(short = "i", long, parse(from_os_str))]
+    /// the unsigned txns file
+    input: PathBuf,
+
+    #[structopt(
+        short = "o",
+        long = "output-dir",
+        name = "output-dir",
+        parse(from_os_str)
+    )]
+    /// dir used to save the signed txns file. Default to current dir.
+    output_dir: Option<PathBuf>,
+}
+
+pub struct ColdSignCommand;
+
+impl CommandAction for ColdSignCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ColdSignOpt;
+    type ReturnItem = PathBuf;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let chain_id = ctx.state().net().chain_id();
+        let unsigned_txns = UnsignedTxnsFile::load(opt.input.as_path(), chain_id)?;
+        let signed_txns = unsigned_txns
+            .txns
+            .into_iter()
+            .map(|raw_txn| {
+                let sender = raw_txn.sender();
+                client.account_get(sender)?.ok_or_else(|| {
+                    format_err!("account {} not exists in the offline wallet", sender)
+                })?;
+                client.account_sign_txn(raw_txn)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let output_dir = opt.output_dir.clone().unwrap_or(current_dir()?);
+        SignedTxnsFile::new(chain_id, signed_txns)
+            .save(output_dir.as_path(), SIGNED_TXNS_FILE_EXTENSION)
+    }
+}
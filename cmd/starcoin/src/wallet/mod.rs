@@ -0,0 +1,161 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The split-machine custody workflow, the private keys of cold accounts never leave the offline machine:
+//! 1. `wallet watch-only sync` on the online machine tracks the balances and sequence numbers of
+//!    the cold accounts, and builds the unsigned txns to a `.unsigned-txns` file.
+//! 2. `wallet cold sign` on the offline machine signs the txns to a `.signed-txns` file.
+//! 3. `wallet watch-only submit` on the online machine submits the signed txns.
+
+use anyhow::{ensure, format_err, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use starcoin_crypto::HashValue;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+mod cold_sign_cmd;
+#[cfg(test)]
+mod tests;
+mod watch_only_submit_cmd;
+mod watch_only_sync_cmd;
+
+pub use cold_sign_cmd::*;
+pub use watch_only_submit_cmd::*;
+pub use watch_only_sync_cmd::*;
+
+/// The version of the txns file format, increase it on any incompatible change.
+pub const TXNS_FILE_VERSION: u8 = 1;
+pub const UNSIGNED_TXNS_FILE_EXTENSION: &str = "unsigned-txns";
+pub const SIGNED_TXNS_FILE_EXTENSION: &str = "signed-txns";
+static WATCH_ONLY_ACCOUNTS_FILE_NAME: &str = "watch_only_accounts.json";
+
+/// The txns exchanged between the online and offline machines, encoded by bcs.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TxnsFile<T> {
+    pub version: u8,
+    pub chain_id: ChainId,
+    pub txns: Vec<T>,
+}
+
+pub type UnsignedTxnsFile = TxnsFile<RawUserTransaction>;
+pub type SignedTxnsFile = TxnsFile<SignedUserTransaction>;
+
+impl<T> TxnsFile<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn new(chain_id: ChainId, txns: Vec<T>) -> Self {
+        Self {
+            version: TXNS_FILE_VERSION,
+            chain_id,
+            txns,
+        }
+    }
+
+    /// Load the txns file, and ensure it is built for the `chain_id`.
+    pub fn load(file: &Path, chain_id: ChainId) -> Result<Self> {
+        let bytes = std::fs::read(file)
+            .map_err(|e| format_err!("read txns file {} error: {}", file.display(), e))?;
+        let txns_file: Self = bcs_ext::from_bytes(bytes.as_slice())
+            .map_err(|e| format_err!("invalid txns file {}: {}", file.display(), e))?;
+        ensure!(
+            txns_file.version == TXNS_FILE_VERSION,
+            "unsupported txns file version {}, expect {}",
+            txns_file.version,
+            TXNS_FILE_VERSION
+        );
+        ensure!(
+            txns_file.chain_id == chain_id,
+            "txns file is built for chain {}, but current chain is {}",
+            txns_file.chain_id,
+            chain_id
+        );
+        Ok(txns_file)
+    }
+
+    /// Save the txns to `output_dir`, named by the hash of the content, return the file path.
+    pub fn save(&self, output_dir: &Path, extension: &str) -> Result<PathBuf> {
+        let bytes = bcs_ext::to_bytes(self)?;
+        let mut output_file = output_dir.to_path_buf();
+        output_file.push(
+            HashValue::sha3_256_of(bytes.as_slice())
+                .short_str()
+                .as_str(),
+        );
+        output_file.set_extension(extension);
+        std::fs::write(output_file.as_path(), bytes)?;
+        Ok(output_file)
+    }
+}
+
+/// A cold account tracked by the online machine, which has no private key of it.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WatchOnlyAccount {
+    pub address: AccountAddress,
+    /// the STC balance on chain at the last sync.
+    pub balance: u128,
+    /// the sequence number on chain at the last sync.
+    pub sequence_number: u64,
+    /// the sequence number of the next built txn,
+    /// ahead of the chain if some built txns are not submitted yet.
+    pub next_sequence_number: u64,
+}
+
+/// The watch-only accounts, saved in the cli data dir of each network.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WatchOnlyAccounts {
+    pub accounts: BTreeMap<AccountAddress, WatchOnlyAccount>,
+}
+
+impl WatchOnlyAccounts {
+    pub fn file(data_dir: &Path) -> PathBuf {
+        data_dir.join(WATCH_ONLY_ACCOUNTS_FILE_NAME)
+    }
+
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let file = Self::file(data_dir);
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(file)?;
+        Ok(serde_json::from_str(content.as_str())?)
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        std::fs::write(Self::file(data_dir), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Update the account by the state on chain, the built but not submitted txns are kept
+    /// in the next sequence number unless the chain has gone beyond it.
+    pub fn update(&mut self, address: AccountAddress, balance: u128, sequence_number: u64) {
+        let account = self
+            .accounts
+            .entry(address)
+            .or_insert_with(|| WatchOnlyAccount {
+                address,
+                balance,
+                sequence_number,
+                next_sequence_number: sequence_number,
+            });
+        account.balance = balance;
+        account.sequence_number = sequence_number;
+        account.next_sequence_number = account.next_sequence_number.max(sequence_number);
+    }
+
+    /// Allocate the sequence number for a new built txn of the account.
+    pub fn allocate_sequence_number(&mut self, address: AccountAddress) -> Result<u64> {
+        let account = self
+            .accounts
+            .get_mut(&address)
+            .ok_or_else(|| format_err!("account {} is not watched", address))?;
+        let sequence_number = account.next_sequence_number;
+        account.next_sequence_number += 1;
+        Ok(sequence_number)
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::wallet::{UnsignedTxnsFile, WatchOnlyAccounts, UNSIGNED_TXNS_FILE_EXTENSION};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::transaction::{RawUserTransaction, Script, TransactionPayload};
+
+#[test]
+fn test_txns_file() {
+    let dir = starcoin_config::temp_path();
+    let raw_txn = RawUserTransaction::new_with_default_gas_token(
+        AccountAddress::random(),
+        1,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        10_000_000,
+        1,
+        3600,
+        ChainId::test(),
+    );
+    let txns_file = UnsignedTxnsFile::new(ChainId::test(), vec![raw_txn]);
+    let file = txns_file
+        .save(dir.path(), UNSIGNED_TXNS_FILE_EXTENSION)
+        .unwrap();
+    let loaded = UnsignedTxnsFile::load(file.as_path(), ChainId::test()).unwrap();
+    assert_eq!(loaded, txns_file);
+    // the txns of other chain should be rejected.
+    assert!(UnsignedTxnsFile::load(file.as_path(), ChainId::new(254)).is_err());
+}
+
+#[test]
+fn test_watch_only_accounts() {
+    let dir = starcoin_config::temp_path();
+    let address = AccountAddress::random();
+    let mut accounts = WatchOnlyAccounts::load(dir.path()).unwrap();
+    accounts.update(address, 100, 3);
+    assert_eq!(accounts.allocate_sequence_number(address).unwrap(), 3);
+    assert_eq!(accounts.allocate_sequence_number(address).unwrap(), 4);
+    // the built txns are not submitted yet.
+    accounts.update(address, 90, 4);
+    assert_eq!(accounts.allocate_sequence_number(address).unwrap(), 5);
+    accounts.save(dir.path()).unwrap();
+    assert_eq!(WatchOnlyAccounts::load(dir.path()).unwrap(), accounts);
+    assert!(accounts
+        .allocate_sequence_number(AccountAddress::random())
+        .is_err());
+}
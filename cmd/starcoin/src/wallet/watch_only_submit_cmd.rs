@@ -0,0 +1,59 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::wallet::SignedTxnsFile;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::HashValue;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "submit")]
+/// Submit the signed txns file signed by `wallet cold sign` on the online machine.
+pub struct WatchOnlySubmitOpt {
+    #[structopt(name = "signed-txns-file", parse(from_os_str))]
+    /// the signed txns file
+    input: PathBuf,
+
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+}
+
+pub struct WatchOnlySubmitCommand;
+
+impl CommandAction for WatchOnlySubmitCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = WatchOnlySubmitOpt;
+    type ReturnItem = Vec<HashValue>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let signed_txns = SignedTxnsFile::load(opt.input.as_path(), ctx.state().net().chain_id())?;
+        let mut txn_hashes = vec![];
+        for signed_txn in signed_txns.txns {
+            let txn_hash = signed_txn.id();
+            client.submit_transaction(signed_txn)?;
+            println!("txn {:#x} submitted.", txn_hash);
+            txn_hashes.push(txn_hash);
+        }
+        if opt.blocking {
+            for txn_hash in txn_hashes.iter() {
+                ctx.state().watch_txn(*txn_hash)?;
+            }
+        }
+        Ok(txn_hashes)
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::wallet::{
+    UnsignedTxnsFile, WatchOnlyAccount, WatchOnlyAccounts, UNSIGNED_TXNS_FILE_EXTENSION,
+};
+use crate::StarcoinOpt;
+use anyhow::{format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_rpc_api::types::FunctionIdView;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::transaction::{parse_transaction_argument, TransactionArgument};
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::{RawUserTransaction, ScriptFunction};
+use starcoin_vm_types::transaction_argument::convert_txn_args;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::env::current_dir;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "sync")]
+/// Sync the balances and sequence numbers of the watched cold accounts on the online machine,
+/// and build an unsigned txn of a cold account if `--function` is present,
+/// the unsigned txn file is signed by `wallet cold sign` on the offline machine.
+pub struct WatchOnlySyncOpt {
+    #[structopt(long = "add")]
+    /// add the cold account addresses to watch.
+    add: Option<Vec<AccountAddress>>,
+
+    #[structopt(short = "s", long, requires = "script-function")]
+    /// the cold account to send the txn, added to watch automatically.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "function", name = "script-function", requires = "sender")]
+    /// script function to build the txn, example: 0x1::TransferScripts::peer_to_peer
+    script_function: Option<FunctionIdView>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    /// type tags for the script
+    type_tags: Option<Vec<TypeTag>>,
+
+    #[structopt(long = "arg", name = "transaction-args", parse(try_from_str = parse_transaction_argument))]
+    /// args for the script.
+    args: Option<Vec<TransactionArgument>>,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "86400",
+        help = "how long(in seconds) the txn stay alive, reserve enough time for the offline signing"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "g",
+        name = "max-gas-amount",
+        default_value = "10000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+
+    #[structopt(name = "output-dir", long = "output-dir", parse(from_os_str))]
+    /// dir used to save the unsigned txns file. Default to current dir.
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchOnlySyncView {
+    pub accounts: Vec<WatchOnlyAccount>,
+    pub unsigned_txns_file: Option<PathBuf>,
+}
+
+pub struct WatchOnlySyncCommand;
+
+impl CommandAction for WatchOnlySyncCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = WatchOnlySyncOpt;
+    type ReturnItem = WatchOnlySyncView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let cli_state = ctx.state();
+        let client = cli_state.client();
+        let mut watch_only_accounts = WatchOnlyAccounts::load(cli_state.data_dir())?;
+
+        let mut addresses = watch_only_accounts
+            .accounts
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        addresses.extend(opt.add.clone().unwrap_or_default());
+        addresses.extend(opt.sender);
+
+        let chain_state_reader = RemoteStateReader::new(client)?;
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        for address in addresses {
+            // the account not exists on chain yet is watched with zero balance and sequence number.
+            let sequence_number = account_state_reader
+                .get_account_resource(&address)?
+                .map(|resource| resource.sequence_number())
+                .unwrap_or_default();
+            let balance = account_state_reader
+                .get_balance(&address)?
+                .unwrap_or_default();
+            watch_only_accounts.update(address, balance, sequence_number);
+        }
+
+        let unsigned_txns_file = match (opt.sender, opt.script_function.clone()) {
+            (Some(sender), Some(script_function)) => {
+                let script_function = script_function.0;
+                let node_info = client.node_info()?;
+                let sequence_number = watch_only_accounts.allocate_sequence_number(sender)?;
+                let raw_txn = RawUserTransaction::new_script_function(
+                    sender,
+                    sequence_number,
+                    ScriptFunction::new(
+                        script_function.module,
+                        script_function.function,
+                        opt.type_tags.clone().unwrap_or_default(),
+                        convert_txn_args(&opt.args.clone().unwrap_or_default()),
+                    ),
+                    opt.max_gas_amount,
+                    opt.gas_price,
+                    opt.expiration_time + node_info.now_seconds,
+                    cli_state.net().chain_id(),
+                );
+                let output_dir = opt.output_dir.clone().unwrap_or(current_dir()?);
+                let file = UnsignedTxnsFile::new(cli_state.net().chain_id(), vec![raw_txn])
+                    .save(output_dir.as_path(), UNSIGNED_TXNS_FILE_EXTENSION)
+                    .map_err(|e| format_err!("save unsigned txns file error: {}", e))?;
+                Some(file)
+            }
+            _ => None,
+        };
+        watch_only_accounts.save(cli_state.data_dir())?;
+
+        Ok(WatchOnlySyncView {
+            accounts: watch_only_accounts
+                .accounts
+                .into_iter()
+                .map(|(_, a)| a)
+                .collect(),
+            unsigned_txns_file,
+        })
+    }
+}
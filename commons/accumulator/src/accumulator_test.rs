@@ -169,6 +169,42 @@ fn test_proof() {
     proof_verify(&accumulator, root_hash1, &batch1, 0);
 }
 
+#[test]
+fn test_batch_proofs() {
+    let mock_store = MockAccumulatorStore::new();
+    let accumulator = MerkleAccumulator::new(
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        vec![],
+        0,
+        0,
+        Arc::new(mock_store),
+    );
+    let batch1 = create_leaves(800..801);
+    accumulator.append(&batch1).unwrap();
+    assert_eq!(
+        accumulator.get_proofs(&[0]).unwrap(),
+        vec![accumulator.get_proof(0).unwrap().unwrap()]
+    );
+
+    let batch2 = create_leaves(801..1000);
+    let root_hash = accumulator.append(&batch2).unwrap();
+    accumulator.flush().unwrap();
+    let batch3 = create_leaves(1000..1003);
+    let root_hash2 = accumulator.append(&batch3).unwrap();
+    assert_ne!(root_hash, root_hash2);
+
+    let leaf_indices = vec![201, 0, 5, 6, 100, 199, 200, 201, 5];
+    let proofs = accumulator.get_proofs(&leaf_indices).unwrap();
+    assert_eq!(proofs.len(), leaf_indices.len());
+    for (leaf_index, proof) in leaf_indices.iter().zip(proofs.iter()) {
+        assert_eq!(proof, &accumulator.get_proof(*leaf_index).unwrap().unwrap());
+        let leaf = accumulator.get_leaf(*leaf_index).unwrap().unwrap();
+        proof.verify(root_hash2, leaf, *leaf_index).unwrap();
+    }
+    assert!(accumulator.get_proofs(&[]).unwrap().is_empty());
+    assert!(accumulator.get_proofs(&[0, 203]).is_err());
+}
+
 #[test]
 fn test_multiple_leaves() {
     let mut batch1 = create_leaves(600..608);
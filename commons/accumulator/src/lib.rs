@@ -3,11 +3,11 @@
 
 use crate::accumulator_info::AccumulatorInfo;
 use crate::node_index::NodeIndex;
-use crate::proof::AccumulatorProof;
 use crate::tree::AccumulatorTree;
-use anyhow::{ensure, format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 pub use node::AccumulatorNode;
 use parking_lot::Mutex;
+pub use proof::AccumulatorProof;
 use starcoin_crypto::HashValue;
 use std::sync::Arc;
 pub use tree_store::AccumulatorTreeStore;
@@ -41,6 +41,8 @@ pub trait Accumulator {
     fn get_node_by_position(&self, position: u64) -> Result<Option<HashValue>>;
     /// Get proof by leaf index.
     fn get_proof(&self, leaf_index: u64) -> Result<Option<AccumulatorProof>>;
+    /// Batch get proofs by leaf index, the proofs are in the order of the `leaf_indices`.
+    fn get_proofs(&self, leaf_indices: &[u64]) -> Result<Vec<AccumulatorProof>>;
     /// Get accumulator node by hash.
     fn get_node(&self, hash: HashValue) -> Result<Option<AccumulatorNode>>;
     /// Flush node to storage.
@@ -179,6 +181,25 @@ impl Accumulator for MerkleAccumulator {
         Ok(Some(AccumulatorProof::new(siblings)))
     }
 
+    fn get_proofs(&self, leaf_indices: &[u64]) -> Result<Vec<AccumulatorProof>> {
+        let tree_guard = self.tree.lock();
+        if let Some(leaf_index) = leaf_indices
+            .iter()
+            .find(|leaf_index| **leaf_index >= tree_guard.num_leaves)
+        {
+            bail!(
+                "get proofs invalid leaf_index {}, num_leaves {}",
+                leaf_index,
+                tree_guard.num_leaves
+            );
+        }
+        Ok(tree_guard
+            .get_batch_siblings(leaf_indices)?
+            .into_iter()
+            .map(AccumulatorProof::new)
+            .collect())
+    }
+
     fn get_node(&self, hash: HashValue) -> Result<Option<AccumulatorNode>> {
         self.tree.lock().get_node(hash)
     }
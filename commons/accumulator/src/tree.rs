@@ -235,6 +235,48 @@ impl AccumulatorTree {
         Ok(siblings)
     }
 
+    /// Get the siblings of many leaves in one pass from the root, every internal node on the paths
+    /// is read once, so the nodes shared by the paths are not read again. The siblings of every
+    /// leaf are ordered from the bottom level to the root level, same as `get_siblings`.
+    pub(crate) fn get_batch_siblings(&self, leaf_indices: &[u64]) -> Result<Vec<Vec<HashValue>>> {
+        let mut siblings = vec![vec![]; leaf_indices.len()];
+        if leaf_indices.is_empty() {
+            return Ok(siblings);
+        }
+        let root_pos = NodeIndex::root_from_leaf_count(self.num_leaves);
+        // (node index, node hash, the positions in `leaf_indices` of the leaves under the node)
+        let mut pending = vec![(
+            root_pos,
+            self.root_hash,
+            (0..leaf_indices.len()).collect::<Vec<_>>(),
+        )];
+        while let Some((index, hash, leaves)) = pending.pop() {
+            if index.is_leaf() || leaves.is_empty() {
+                continue;
+            }
+            let internal = match self.get_node(hash)? {
+                Some(AccumulatorNode::Internal(internal)) => internal,
+                _ => bail!("internal node {:?} not found by hash {}", index, hash),
+            };
+            let (left, right): (Vec<_>, Vec<_>) = leaves.into_iter().partition(|i| {
+                NodeIndex::from_leaf_index(leaf_indices[*i]).to_inorder_index()
+                    < index.to_inorder_index()
+            });
+            for i in left.iter() {
+                siblings[*i].push(internal.right());
+            }
+            for i in right.iter() {
+                siblings[*i].push(internal.left());
+            }
+            pending.push((index.left_child(), internal.left(), left));
+            pending.push((index.right_child(), internal.right(), right));
+        }
+        for leaf_siblings in siblings.iter_mut() {
+            leaf_siblings.reverse();
+        }
+        Ok(siblings)
+    }
+
     /// Get node hash by index.
     pub(crate) fn get_node_hash(&mut self, node_index: NodeIndex) -> Result<Option<HashValue>> {
         let idx = self.rightmost_leaf_index();
@@ -0,0 +1,387 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! BLS12-381 signatures in the minimal-pubkey-size setting (public keys in G1, signatures in G2),
+//! with the proof of possession ciphersuite, so signatures of the same message can be aggregated,
+//! and verified against the aggregated public key.
+//!
+//! Aggregating public keys is only safe when every key has proved the possession of its private
+//! key, otherwise it is vulnerable to rogue key attack, so the aggregations require the proof of
+//! possession of every key, see `Bls12381PrivateKey::create_proof_of_possession`.
+
+use crate::hash::CryptoHash;
+use crate::{CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use anyhow::{bail, ensure, Result};
+use blst::min_pk::{
+    AggregatePublicKey, AggregateSignature, PublicKey as BlstPublicKey, SecretKey as BlstSecretKey,
+    Signature as BlstSignature,
+};
+use blst::BLST_ERROR;
+use diem_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use rand::rngs::StdRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+pub const BLS12381_PRIVATE_KEY_LENGTH: usize = 32;
+pub const BLS12381_PUBLIC_KEY_LENGTH: usize = 48;
+pub const BLS12381_SIGNATURE_LENGTH: usize = 96;
+
+/// Domain separation tag of the proof of possession ciphersuite.
+const DST_BLS_SIG_POP: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Domain separation tag of the proof of possession of the ciphersuite, so a proof can never be
+/// used as a signature of a message.
+const DST_BLS_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS12-381 private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Bls12381PrivateKey(BlstSecretKey);
+
+/// A BLS12-381 public key, may be an aggregation of several public keys.
+#[derive(Clone, DeserializeKey, SerializeKey)]
+pub struct Bls12381PublicKey(BlstPublicKey);
+
+/// A BLS12-381 signature, may be an aggregation of several signatures.
+#[derive(Clone, DeserializeKey, SerializeKey)]
+pub struct Bls12381Signature(BlstSignature);
+
+impl Bls12381PrivateKey {
+    /// Generate a random private key.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        Self(BlstSecretKey::key_gen(&ikm, &[]).expect("ikm length is enough"))
+    }
+
+    /// Generate a private key with the fixed test seed, for testing only.
+    pub fn generate_for_testing() -> Self {
+        let mut rng = StdRng::from_seed(crate::test_utils::TEST_SEED);
+        Self::generate(&mut rng)
+    }
+
+    pub fn public_key(&self) -> Bls12381PublicKey {
+        Bls12381PublicKey(self.0.sk_to_pk())
+    }
+
+    /// Sign the crypto hash of the message.
+    pub fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> Bls12381Signature {
+        self.sign_arbitrary_message(message.hash().as_ref())
+    }
+
+    pub fn sign_arbitrary_message(&self, message: &[u8]) -> Bls12381Signature {
+        Bls12381Signature(self.0.sign(message, DST_BLS_SIG_POP, &[]))
+    }
+
+    /// Create the proof of possession of the private key, which is the signature of the public
+    /// key with the proof of possession tag.
+    pub fn create_proof_of_possession(&self) -> Bls12381Signature {
+        let public_key = self.public_key();
+        Bls12381Signature(
+            self.0
+                .sign(public_key.to_bytes().as_slice(), DST_BLS_POP, &[]),
+        )
+    }
+}
+
+impl Bls12381PublicKey {
+    /// Aggregate the public keys, the aggregated key can verify the aggregated signature of
+    /// the same message. Every public key is paired with its proof of possession.
+    pub fn aggregate(public_keys: &[(Bls12381PublicKey, Bls12381Signature)]) -> Result<Self> {
+        ensure!(!public_keys.is_empty(), "public keys should not be empty");
+        let keys = verify_proofs_of_possession(public_keys)?;
+        match AggregatePublicKey::aggregate(keys.as_slice(), true) {
+            Ok(key) => Ok(Self(key.to_public_key())),
+            Err(e) => bail!("aggregate bls public keys error: {:?}", e),
+        }
+    }
+}
+
+impl Bls12381Signature {
+    /// Aggregate the signatures of the same message.
+    pub fn aggregate(signatures: &[Bls12381Signature]) -> Result<Self> {
+        ensure!(!signatures.is_empty(), "signatures should not be empty");
+        let sigs = signatures.iter().map(|s| &s.0).collect::<Vec<_>>();
+        match AggregateSignature::aggregate(sigs.as_slice(), true) {
+            Ok(sig) => Ok(Self(sig.to_signature())),
+            Err(e) => bail!("aggregate bls signatures error: {:?}", e),
+        }
+    }
+
+    /// Verify the signature of the crypto hash of the message.
+    pub fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Bls12381PublicKey,
+    ) -> Result<()> {
+        self.verify_arbitrary_msg(message.hash().as_ref(), public_key)
+    }
+
+    pub fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Bls12381PublicKey,
+    ) -> Result<()> {
+        match self
+            .0
+            .verify(true, message, DST_BLS_SIG_POP, &[], &public_key.0, true)
+        {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => bail!("bls signature verify failed: {:?}", e),
+        }
+    }
+
+    /// Verify the signature is the proof of possession of the private key of `public_key`.
+    pub fn verify_proof_of_possession(&self, public_key: &Bls12381PublicKey) -> Result<()> {
+        match self.0.verify(
+            true,
+            public_key.to_bytes().as_slice(),
+            DST_BLS_POP,
+            &[],
+            &public_key.0,
+            true,
+        ) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => bail!(
+                "bls proof of possession of {} verify failed: {:?}",
+                public_key,
+                e
+            ),
+        }
+    }
+
+    /// Verify an aggregated signature of the same message signed by every one of `public_keys`,
+    /// every public key is paired with its proof of possession.
+    pub fn verify_aggregate<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_keys: &[(Bls12381PublicKey, Bls12381Signature)],
+    ) -> Result<()> {
+        ensure!(!public_keys.is_empty(), "public keys should not be empty");
+        let keys = verify_proofs_of_possession(public_keys)?;
+        match self.0.fast_aggregate_verify(
+            true,
+            message.hash().as_ref(),
+            DST_BLS_SIG_POP,
+            keys.as_slice(),
+        ) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => bail!("bls aggregate signature verify failed: {:?}", e),
+        }
+    }
+}
+
+fn verify_proofs_of_possession(
+    public_keys: &[(Bls12381PublicKey, Bls12381Signature)],
+) -> Result<Vec<&BlstPublicKey>> {
+    public_keys
+        .iter()
+        .map(|(public_key, proof)| {
+            proof.verify_proof_of_possession(public_key)?;
+            Ok(&public_key.0)
+        })
+        .collect()
+}
+
+impl ValidCryptoMaterial for Bls12381PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381PrivateKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, Self::Error> {
+        if bytes.len() != BLS12381_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        BlstSecretKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381PublicKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, Self::Error> {
+        if bytes.len() != BLS12381_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let key = BlstPublicKey::from_bytes(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        key.validate()
+            .map_err(|_| CryptoMaterialError::SmallSubgroupError)?;
+        Ok(Self(key))
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381Signature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, Self::Error> {
+        if bytes.len() != BLS12381_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        BlstSignature::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Bls12381PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381PrivateKey {}
+
+impl PartialEq for Bls12381PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381PublicKey {}
+
+impl Hash for Bls12381PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(self.to_bytes().as_slice())
+    }
+}
+
+impl PartialEq for Bls12381Signature {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381Signature {}
+
+impl Hash for Bls12381Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(self.to_bytes().as_slice())
+    }
+}
+
+impl fmt::Debug for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Display for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Display for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDiemCrypto as TestMessage;
+
+    #[test]
+    fn test_bls_sign_and_verify() {
+        let mut rng = rand::thread_rng();
+        let private_key = Bls12381PrivateKey::generate(&mut rng);
+        let public_key = private_key.public_key();
+        let message = TestMessage("hello".to_string());
+        let signature = private_key.sign(&message);
+        assert!(signature.verify(&message, &public_key).is_ok());
+        assert!(signature
+            .verify(&TestMessage("world".to_string()), &public_key)
+            .is_err());
+
+        let encoded = bcs_ext::to_bytes(&public_key).unwrap();
+        let decoded: Bls12381PublicKey = bcs_ext::from_bytes(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, public_key);
+        let decoded_private_key =
+            Bls12381PrivateKey::try_from(private_key.to_bytes().as_slice()).unwrap();
+        assert_eq!(decoded_private_key, private_key);
+    }
+
+    #[test]
+    fn test_bls_aggregate() {
+        let mut rng = rand::thread_rng();
+        let private_keys = (0..3)
+            .map(|_| Bls12381PrivateKey::generate(&mut rng))
+            .collect::<Vec<_>>();
+        let public_keys = private_keys
+            .iter()
+            .map(|k| (k.public_key(), k.create_proof_of_possession()))
+            .collect::<Vec<_>>();
+        let message = TestMessage("hello".to_string());
+        let signatures = private_keys
+            .iter()
+            .map(|k| k.sign(&message))
+            .collect::<Vec<_>>();
+        let signature = Bls12381Signature::aggregate(signatures.as_slice()).unwrap();
+        assert!(signature
+            .verify_aggregate(&message, public_keys.as_slice())
+            .is_ok());
+        let public_key = Bls12381PublicKey::aggregate(public_keys.as_slice()).unwrap();
+        assert!(signature.verify(&message, &public_key).is_ok());
+        // lack of one signer.
+        assert!(signature
+            .verify_aggregate(&message, &public_keys[..2])
+            .is_err());
+    }
+
+    #[test]
+    fn test_bls_aggregate_requires_proof_of_possession() {
+        let mut rng = rand::thread_rng();
+        let private_keys = (0..2)
+            .map(|_| Bls12381PrivateKey::generate(&mut rng))
+            .collect::<Vec<_>>();
+        let proof = private_keys[0].create_proof_of_possession();
+        assert!(proof
+            .verify_proof_of_possession(&private_keys[0].public_key())
+            .is_ok());
+        // the proof of another key.
+        assert!(proof
+            .verify_proof_of_possession(&private_keys[1].public_key())
+            .is_err());
+        // a signature of the public key is not a proof of possession.
+        let signature = private_keys[1]
+            .sign_arbitrary_message(private_keys[1].public_key().to_bytes().as_slice());
+        assert!(signature
+            .verify_proof_of_possession(&private_keys[1].public_key())
+            .is_err());
+        assert!(Bls12381PublicKey::aggregate(&[
+            (private_keys[0].public_key(), proof.clone()),
+            (private_keys[1].public_key(), proof),
+        ])
+        .is_err());
+    }
+}
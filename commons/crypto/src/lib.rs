@@ -6,6 +6,9 @@
 //! A library supplying various cryptographic primitives
 // just wrap diem-crypto.
 
+#[cfg(not(any(feature = "full", feature = "minimal")))]
+compile_error!("One of the features full or minimal must be enabled.");
+
 pub mod ed25519 {
     use crate::keygen::KeyGen;
     use crate::{Genesis, PrivateKey};
@@ -23,6 +26,7 @@ pub mod ed25519 {
     }
 }
 
+pub mod bls12381;
 pub mod hash;
 pub mod keygen;
 pub mod multi_ed25519;
@@ -1,12 +1,26 @@
-use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead};
-use anyhow::{bail, format_err, Result};
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+use anyhow::{bail, ensure, format_err, Result};
 use byteorder::{ReadBytesExt, WriteBytesExt};
+use hmac::Mac;
 use rand::RngCore;
 use std::io::{Cursor, Read, Write};
 
 pub const PBKDF2_DEFAULT_ITERATIONS: usize = 1000;
 pub const PBKDF2_SALT_SIZE: usize = 32;
 pub const AES_NONCE_SIZE: usize = 12;
+/// The size of the random salt to derive the subkey of a value encrypted by `encrypt_with_key`.
+pub const SUBKEY_SALT_SIZE: usize = 32;
+/// scrypt params recommended for interactive use, cost about 100ms and 32M memory.
+pub const SCRYPT_DEFAULT_LOG_N: u8 = 15;
+pub const SCRYPT_DEFAULT_R: u32 = 8;
+pub const SCRYPT_DEFAULT_P: u32 = 1;
+pub const SCRYPT_SALT_SIZE: usize = 32;
+/// The max scrypt params accepted from the encrypted data, so a crafted data can not force the
+/// decryption to use huge memory or CPU time. The memory cost is `128 * r * 2^log_n` bytes.
+pub const SCRYPT_MAX_LOG_N: u8 = 20;
+pub const SCRYPT_MAX_R: u32 = 32;
+pub const SCRYPT_MAX_P: u32 = 16;
+pub const SCRYPT_MAX_MEMORY: u64 = 1 << 30;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct KeyDerivationParams {
@@ -117,6 +131,39 @@ fn aes_decrypt(
     }
 }
 
+fn aes_encrypt_with_aad(
+    encryption_param: &EncryptionParams,
+    key: [u8; 32],
+    aad: &[u8],
+    plain: &[u8],
+) -> Vec<u8> {
+    let key = GenericArray::from(key);
+    let nonce = GenericArray::clone_from_slice(&encryption_param.nonce);
+    let cipher = aes_gcm::Aes256Gcm::new(key);
+    cipher
+        .encrypt(&nonce, Payload { msg: plain, aad })
+        .expect("encryption should never failure!")
+}
+fn aes_decrypt_with_aad(
+    encryption_param: &EncryptionParams,
+    key: [u8; 32],
+    aad: &[u8],
+    encrypted: &[u8],
+) -> Result<Vec<u8>> {
+    let key = GenericArray::from(key);
+    let nonce = GenericArray::clone_from_slice(&encryption_param.nonce);
+    let cipher = aes_gcm::Aes256Gcm::new(key);
+    cipher
+        .decrypt(
+            &nonce,
+            Payload {
+                msg: encrypted,
+                aad,
+            },
+        )
+        .map_err(|_| format_err!("decrypt error"))
+}
+
 pub fn encrypt(secret: &[u8], plain: &[u8]) -> Vec<u8> {
     let meta = Meta::generate();
     // 256-bit derived key
@@ -138,5 +185,169 @@ pub fn decrypt(secret: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
     aes_decrypt(&meta.encryption_params, dk, crypted)
 }
 
+/// Derive the subkey of a value from the 256-bit `key` and the random `salt`, by HMAC-SHA256.
+fn derive_subkey(key: &[u8; 32], salt: &[u8]) -> [u8; 32] {
+    let mut mac =
+        hmac::Hmac::<sha2::Sha256>::new_varkey(key).expect("HMAC accepts keys of any size");
+    mac.input(salt);
+    let mut subkey = [0u8; 32];
+    subkey.copy_from_slice(mac.result().code().as_slice());
+    subkey
+}
+
+/// Encrypt with a subkey derived from the 256-bit `key` and a random salt, without the expensive
+/// key derivation, for encrypting many small values with the same key, such as the db values.
+/// Every value has its own subkey, so the random nonces never repeat under a key however many
+/// values are encrypted. The `aad` is authenticated but not encrypted, it binds the ciphertext to
+/// its context, such as the db key of the value, so the ciphertext can not be moved to another
+/// context. The salt and the nonce are prepended to the result.
+pub fn encrypt_with_key(key: &[u8; 32], aad: &[u8], plain: &[u8]) -> Vec<u8> {
+    let mut salt = [0u8; SUBKEY_SALT_SIZE];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let encryption_params = EncryptionParams::generate();
+    let subkey = derive_subkey(key, &salt);
+    let mut ciphertext = aes_encrypt_with_aad(&encryption_params, subkey, aad, plain);
+    let mut result = Vec::with_capacity(SUBKEY_SALT_SIZE + AES_NONCE_SIZE + ciphertext.len());
+    result.extend_from_slice(&salt);
+    result.extend_from_slice(&encryption_params.nonce);
+    result.append(&mut ciphertext);
+    result
+}
+
+pub fn decrypt_with_key(key: &[u8; 32], aad: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+    if encrypted.len() <= SUBKEY_SALT_SIZE + AES_NONCE_SIZE {
+        bail!("invalid encrypted data");
+    }
+    let (salt, encrypted) = encrypted.split_at(SUBKEY_SALT_SIZE);
+    let (nonce_bytes, crypted) = encrypted.split_at(AES_NONCE_SIZE);
+    let mut nonce = [0u8; AES_NONCE_SIZE];
+    nonce.copy_from_slice(nonce_bytes);
+    aes_decrypt_with_aad(
+        &EncryptionParams { nonce },
+        derive_subkey(key, salt),
+        aad,
+        crypted,
+    )
+}
+
+const SCRYPT_META_LEN: usize = 1usize + 4 + 4 + SCRYPT_SALT_SIZE + AES_NONCE_SIZE;
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ScryptMeta {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: [u8; SCRYPT_SALT_SIZE],
+    encryption_params: EncryptionParams,
+}
+
+impl ScryptMeta {
+    pub fn generate() -> Self {
+        let mut salt = [0u8; SCRYPT_SALT_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            log_n: SCRYPT_DEFAULT_LOG_N,
+            r: SCRYPT_DEFAULT_R,
+            p: SCRYPT_DEFAULT_P,
+            salt,
+            encryption_params: EncryptionParams::generate(),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = std::io::Cursor::new(Vec::with_capacity(SCRYPT_META_LEN));
+        buf.write_u8(self.log_n).expect("should never fail");
+        buf.write_u32::<byteorder::BigEndian>(self.r)
+            .expect("should never fail");
+        buf.write_u32::<byteorder::BigEndian>(self.p)
+            .expect("should never fail");
+        buf.write_all(&self.salt).expect("should never fail");
+        buf.write_all(&self.encryption_params.nonce)
+            .expect("should never fail");
+        buf.into_inner()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        let mut buf = Cursor::new(buf);
+        let log_n = buf.read_u8()?;
+        let r = buf.read_u32::<byteorder::BigEndian>()?;
+        let p = buf.read_u32::<byteorder::BigEndian>()?;
+        let mut salt = [0u8; SCRYPT_SALT_SIZE];
+        buf.read_exact(&mut salt)?;
+        let mut nonce = [0u8; AES_NONCE_SIZE];
+        buf.read_exact(&mut nonce)?;
+        let meta = Self {
+            log_n,
+            r,
+            p,
+            salt,
+            encryption_params: EncryptionParams { nonce },
+        };
+        meta.check_bounds()?;
+        Ok(meta)
+    }
+
+    fn check_bounds(&self) -> Result<()> {
+        ensure!(
+            self.log_n > 0 && self.log_n <= SCRYPT_MAX_LOG_N,
+            "scrypt log_n {} should be in [1, {}]",
+            self.log_n,
+            SCRYPT_MAX_LOG_N
+        );
+        ensure!(
+            self.r > 0 && self.r <= SCRYPT_MAX_R,
+            "scrypt r {} should be in [1, {}]",
+            self.r,
+            SCRYPT_MAX_R
+        );
+        ensure!(
+            self.p > 0 && self.p <= SCRYPT_MAX_P,
+            "scrypt p {} should be in [1, {}]",
+            self.p,
+            SCRYPT_MAX_P
+        );
+        let memory = 128u64 * self.r as u64 * (1u64 << self.log_n);
+        ensure!(
+            memory <= SCRYPT_MAX_MEMORY,
+            "scrypt params need {} bytes memory, more than the max {}",
+            memory,
+            SCRYPT_MAX_MEMORY
+        );
+        Ok(())
+    }
+
+    fn derive_key(&self, secret: &[u8]) -> Result<[u8; 32]> {
+        let params = scrypt::ScryptParams::new(self.log_n, self.r, self.p)
+            .map_err(|_| format_err!("invalid scrypt params"))?;
+        let mut dk = [0u8; 32];
+        scrypt::scrypt(secret, &self.salt, &params, &mut dk)
+            .map_err(|_| format_err!("invalid scrypt output length"))?;
+        Ok(dk)
+    }
+}
+
+/// Encrypt with a scrypt derived key, which is much more expensive to brute force than `encrypt`,
+/// so the encrypted data can be transferred through insecure channels.
+pub fn scrypt_encrypt(secret: &[u8], plain: &[u8]) -> Vec<u8> {
+    let meta = ScryptMeta::generate();
+    let dk = meta
+        .derive_key(secret)
+        .expect("default scrypt params should be valid");
+    let mut ciphertext = aes_encrypt(&meta.encryption_params, dk, plain);
+    let mut result = meta.encode();
+    result.append(&mut ciphertext);
+    result
+}
+
+pub fn scrypt_decrypt(secret: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+    if encrypted.len() <= SCRYPT_META_LEN {
+        bail!("invalid encrypted data");
+    }
+    let meta = ScryptMeta::decode(&encrypted[0..SCRYPT_META_LEN])?;
+    let crypted = &encrypted[SCRYPT_META_LEN..];
+
+    let dk = meta.derive_key(secret)?;
+    aes_decrypt(&meta.encryption_params, dk, crypted)
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,4 +1,4 @@
-use crate::{decrypt, encrypt};
+use crate::{decrypt, decrypt_with_key, encrypt, encrypt_with_key, scrypt_decrypt, scrypt_encrypt};
 
 #[test]
 fn test_encryption() {
@@ -10,3 +10,54 @@ fn test_encryption() {
     let decrypted = decrypt(secret.as_bytes(), encrypted.as_slice()).unwrap();
     assert_eq!(decrypted.as_slice(), plain.as_bytes());
 }
+
+#[test]
+fn test_scrypt_encryption() {
+    let secret = "hello";
+    let plain = "world";
+    let encrypted = scrypt_encrypt(secret.as_bytes(), plain.as_bytes());
+    assert_ne!(encrypted.as_slice(), plain.as_bytes());
+
+    let decrypted = scrypt_decrypt(secret.as_bytes(), encrypted.as_slice()).unwrap();
+    assert_eq!(decrypted.as_slice(), plain.as_bytes());
+    assert!(scrypt_decrypt("wrong".as_bytes(), encrypted.as_slice()).is_err());
+}
+
+#[test]
+fn test_scrypt_decrypt_rejects_huge_params() {
+    let secret = "hello";
+    let encrypted = scrypt_encrypt(secret.as_bytes(), "world".as_bytes());
+    // the params are encoded as log_n(u8), r(u32 be), p(u32 be) at the head.
+    let mut huge_log_n = encrypted.clone();
+    huge_log_n[0] = 40;
+    assert!(scrypt_decrypt(secret.as_bytes(), huge_log_n.as_slice()).is_err());
+    let mut huge_r = encrypted.clone();
+    huge_r[1..5].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(scrypt_decrypt(secret.as_bytes(), huge_r.as_slice()).is_err());
+    let mut huge_p = encrypted;
+    huge_p[5..9].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(scrypt_decrypt(secret.as_bytes(), huge_p.as_slice()).is_err());
+}
+
+#[test]
+fn test_encryption_with_key() {
+    let key = [1u8; 32];
+    let aad = b"block_key";
+    let plain = "world";
+    let encrypted = encrypt_with_key(&key, aad, plain.as_bytes());
+    assert_ne!(encrypted, encrypt_with_key(&key, aad, plain.as_bytes()));
+
+    let decrypted = decrypt_with_key(&key, aad, encrypted.as_slice()).unwrap();
+    assert_eq!(decrypted.as_slice(), plain.as_bytes());
+    assert!(decrypt_with_key(&[2u8; 32], aad, encrypted.as_slice()).is_err());
+    // the ciphertext can not be moved to another context.
+    assert!(decrypt_with_key(&key, b"other_key", encrypted.as_slice()).is_err());
+    let mut tampered = encrypted.clone();
+    tampered[0] ^= 1;
+    assert!(decrypt_with_key(&key, aad, tampered.as_slice()).is_err());
+    // an empty value is encrypted to the salt, the nonce and the tag.
+    let encrypted = encrypt_with_key(&key, aad, &[]);
+    assert!(decrypt_with_key(&key, aad, encrypted.as_slice())
+        .unwrap()
+        .is_empty());
+}
@@ -565,6 +565,69 @@ fn test_1000_keys() {
     many_keys_get_proof_and_verify_tree_root(seed, 1000);
 }
 
+#[test]
+fn test_parallel_put() {
+    let mut rng: StdRng = StdRng::from_seed([5u8; 32]);
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let mut kvs = vec![];
+    for _i in 0..1000 {
+        let key = HashValue::random_with_rng(&mut rng);
+        let value = Blob::from(HashValue::random_with_rng(&mut rng).to_vec());
+        kvs.push((HashValueKey(key), value));
+    }
+    let (root, batch) = tree.put_blob_set(None, kvs.clone()).unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    // update, delete and insert keys, with some keys updated twice and some absent keys deleted.
+    let mut blob_set = vec![];
+    for (i, (key, _)) in kvs.iter().take(300).enumerate() {
+        let blob = match i % 3 {
+            0 => None,
+            _ => Some(Blob::from(HashValue::random_with_rng(&mut rng).to_vec())),
+        };
+        blob_set.push((*key, blob));
+    }
+    for (key, value) in kvs.iter().skip(300).take(10) {
+        blob_set.push((*key, Some(Blob::from(vec![0u8; 8]))));
+        blob_set.push((*key, Some(value.clone())));
+    }
+    for i in 0..100 {
+        let key = HashValueKey(HashValue::random_with_rng(&mut rng));
+        let blob = match i % 4 {
+            0 => None,
+            _ => Some(Blob::from(HashValue::random_with_rng(&mut rng).to_vec())),
+        };
+        blob_set.push((key, blob));
+    }
+    assert!(blob_set.len() >= PARALLEL_UPDATE_THRESHOLD);
+
+    let mut tree_cache = TreeCache::new(&db, Some(root));
+    for (key, blob) in blob_set.clone() {
+        JellyfishMerkleTree::put(key, blob, &mut tree_cache).unwrap();
+    }
+    tree_cache.freeze();
+    let (root_hashes_one_by_one, batch_one_by_one) = tree_cache.into();
+
+    let (new_root, batch) = tree.updates(Some(root), blob_set.clone()).unwrap();
+    assert_eq!(vec![new_root], root_hashes_one_by_one);
+    assert_eq!(batch, batch_one_by_one);
+
+    db.write_tree_update_batch(batch).unwrap();
+    let mut expected = HashMap::new();
+    for (key, blob) in blob_set {
+        expected.insert(key, blob);
+    }
+    for (key, blob) in expected {
+        let (value, proof) = tree.get_with_proof(new_root, key.key_hash()).unwrap();
+        assert_eq!(value, blob);
+        assert!(proof
+            .verify(new_root, key.key_hash(), blob.as_ref())
+            .is_ok());
+    }
+}
+
 fn many_versions_get_proof_and_verify_tree_root(seed: &[u8], num_versions: usize) {
     assert!(seed.len() < 32);
     let mut actual_seed = [0u8; 32];
@@ -89,20 +89,26 @@ pub mod tree_cache;
 use crate::iterator::JellyfishMerkleIterator;
 use anyhow::{bail, ensure, format_err, Result};
 use blob::Blob;
+use nibble::Nibble;
 use nibble_path::{skip_common_prefix, NibbleIterator, NibblePath};
 use node_type::{Child, Children, InternalNode, LeafNode, Node, NodeKey};
 use proof::{SparseMerkleProof, SparseMerkleRangeProof};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
+use rayon::prelude::*;
 use serde::{de::DeserializeOwned, Serialize};
 use starcoin_crypto::{hash::PlainCryptoHash, HashValue};
 use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
+use std::thread::{self, ThreadId};
 use tree_cache::TreeCache;
 
 /// The hardcoded maximum height of a [`JellyfishMerkleTree`] in nibbles.
 pub const ROOT_NIBBLE_HEIGHT: usize = HashValue::LENGTH * 2;
 
+/// A blob set with at least this many keys is applied to the subtrees of the root in parallel.
+pub const PARALLEL_UPDATE_THRESHOLD: usize = 64;
+
 /// `TreeReader` defines the interface between
 /// [`JellyfishMerkleTree`](struct.JellyfishMerkleTree.html)
 /// and underlying storage holding nodes.
@@ -122,6 +128,13 @@ pub trait TreeReader<K: RawKey> {
         //TODO
         unimplemented!()
     }
+
+    /// Read the nodes visible to the `owner` thread on the current thread, until `detach_reader`.
+    /// It is called on the workers of a parallel put, as the reader may have nodes only visible to
+    /// the thread the put is called on, such as the pending writes of a storage write batch.
+    fn attach_reader(&self, _owner: ThreadId) {}
+
+    fn detach_reader(&self) {}
 }
 
 pub trait TreeWriter<K: RawKey> {
@@ -171,7 +184,7 @@ where
     }
 }
 
-pub trait RawKey: Clone + Ord {
+pub trait RawKey: Clone + Ord + Send + Sync {
     /// Raw key's hash, will used as tree's nibble path
     /// Directly use origin byte's sha3_256 hash, do not use CryptoHash to add salt.
     fn key_hash(&self) -> HashValue {
@@ -190,7 +203,7 @@ pub trait RawKey: Clone + Ord {
 
 impl<T> RawKey for T
 where
-    T: Clone + Ord + Serialize + DeserializeOwned,
+    T: Clone + Ord + Send + Sync + Serialize + DeserializeOwned,
 {
     fn encode_key(&self) -> Result<Vec<u8>> {
         bcs_ext::to_bytes(self)
@@ -236,7 +249,7 @@ pub struct JellyfishMerkleTree<'a, K: RawKey, R: 'a + TreeReader<K>> {
 impl<'a, K, R> JellyfishMerkleTree<'a, K, R>
 where
     K: RawKey,
-    R: 'a + TreeReader<K>,
+    R: 'a + TreeReader<K> + Sync,
 {
     /// Creates a `JellyfishMerkleTree` backed by the given [`TreeReader`](trait.TreeReader.html).
     pub fn new(reader: &'a R) -> Self {
@@ -349,14 +362,20 @@ where
         blob_sets: Vec<Vec<(K, Option<Blob>)>>,
     ) -> Result<(Vec<HashValue>, TreeUpdateBatch<K>)> {
         let mut tree_cache = TreeCache::new(self.reader, state_root_hash);
-        for (_idx, blob_set) in blob_sets.into_iter().enumerate() {
+        for (idx, blob_set) in blob_sets.into_iter().enumerate() {
             assert!(
                 !blob_set.is_empty(),
                 "Transactions that output empty write set should not be included.",
             );
-            blob_set
-                .into_iter()
-                .try_for_each(|(key, blob)| Self::put(key, blob, &mut tree_cache))?;
+            // The subtree caches of the parallel put read the nodes from the reader, so only the
+            // first blob set, before any node is frozen in the cache, can be put in parallel.
+            if idx == 0 && blob_set.len() >= PARALLEL_UPDATE_THRESHOLD {
+                self.parallel_put(blob_set, &mut tree_cache)?;
+            } else {
+                blob_set
+                    .into_iter()
+                    .try_for_each(|(key, blob)| Self::put(key, blob, &mut tree_cache))?;
+            }
             // Freezes the current cache to make all contents in the current cache immutable.
             // TODO: maybe we should not freeze, check here again.
             tree_cache.freeze();
@@ -382,6 +401,128 @@ where
         Ok(())
     }
 
+    /// Put the `blob_set` to the subtrees of the root internal node in parallel, the keys are
+    /// grouped by their first nibble and every group is put to its subtree in the original order
+    /// with a separate cache, then the root is rebuilt from the new children. The result is the
+    /// same as putting the keys one by one with [`put`](struct.JellyfishMerkleTree.html#method.put).
+    fn parallel_put(
+        &self,
+        blob_set: Vec<(K, Option<Blob>)>,
+        tree_cache: &mut TreeCache<'a, R, K>,
+    ) -> Result<()> {
+        let root_node_key = *tree_cache.get_root_node_key();
+        let root_node = match tree_cache.get_node(&root_node_key)? {
+            Node::Internal(internal_node) => internal_node,
+            _ => {
+                return blob_set
+                    .into_iter()
+                    .try_for_each(|(key, blob)| Self::put(key, blob, tree_cache));
+            }
+        };
+
+        let mut groups: BTreeMap<Nibble, Vec<(K, Option<Blob>)>> = BTreeMap::new();
+        for (key, blob) in blob_set {
+            let nibble = NibblePath::new(key.key_hash().to_vec())
+                .nibbles()
+                .next()
+                .expect("Ran out of nibbles");
+            groups.entry(nibble).or_default().push((key, blob));
+        }
+
+        let owner = thread::current().id();
+        let results = groups
+            .into_par_iter()
+            .map(|(nibble, group)| {
+                let child = root_node
+                    .child(nibble)
+                    .map(|child| (child.hash, child.is_leaf));
+                self.reader.attach_reader(owner);
+                let result = self.put_subtree(root_node_key, child, group);
+                self.reader.detach_reader();
+                result
+                    .map(|(child, changed, subtree_cache)| (nibble, child, changed, subtree_cache))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut children: Children = root_node.into();
+        let mut changed = false;
+        for (nibble, child, child_changed, subtree_cache) in results {
+            tree_cache.merge(subtree_cache)?;
+            changed |= child_changed;
+            match child {
+                Some((child_key, is_leaf)) => {
+                    children.insert(nibble, Child::new(child_key, is_leaf));
+                }
+                None => {
+                    children.remove(&nibble);
+                }
+            }
+        }
+        // don't need to prune the root if no change happens.
+        if !changed {
+            return Ok(());
+        }
+        tree_cache.delete_node(&root_node_key, false);
+
+        let new_root_node_key = if children.is_empty() {
+            Node::<K>::new_null().hash()
+        } else if children.len() == 1
+            && children
+                .values()
+                .next()
+                .expect("must exist one child")
+                .is_leaf
+        {
+            children.values().next().expect("must exist one child").hash
+        } else {
+            let new_internal_node: Node<K> = InternalNode::new(children).into();
+            let new_internal_node_key = new_internal_node.hash();
+            tree_cache.put_node(new_internal_node_key, new_internal_node)?;
+            new_internal_node_key
+        };
+        tree_cache.set_root_node_key(new_root_node_key);
+        Ok(())
+    }
+
+    /// Put the keys of a group one by one to the subtree of the `child` of the root, with a
+    /// separate cache based on the root `root_node_key`. Returns the new child, whether the child
+    /// is changed by any put, and the cache.
+    fn put_subtree(
+        &self,
+        root_node_key: NodeKey,
+        mut child: Option<(NodeKey, bool)>,
+        group: Vec<(K, Option<Blob>)>,
+    ) -> Result<(Option<(NodeKey, bool)>, bool, TreeCache<'a, R, K>)> {
+        let mut subtree_cache = TreeCache::new(self.reader, Some(root_node_key));
+        let mut changed = false;
+        for (key, blob) in group {
+            let nibble_path = NibblePath::new(key.key_hash().to_vec());
+            let mut nibble_iter = nibble_path.nibbles();
+            // The first nibble is consumed by the root.
+            nibble_iter.next();
+            let new_child = match child {
+                Some((child_key, _)) => Some(Self::insert_at(
+                    child_key,
+                    &mut nibble_iter,
+                    key,
+                    blob,
+                    &mut subtree_cache,
+                )?),
+                None => match blob {
+                    Some(blob) => Some(Self::create_leaf_node(key, blob, &mut subtree_cache)?),
+                    None => None,
+                },
+            }
+            .and_then(|(node_key, node)| match node {
+                Node::Null => None,
+                _ => Some((node_key, node.is_leaf())),
+            });
+            changed |= new_child != child;
+            child = new_child;
+        }
+        Ok((child, changed, subtree_cache))
+    }
+
     /// Helper function for recursive insertion into the subtree that starts from the current
     /// [`NodeKey`](node_type/struct.NodeKey.html). Returns the newly inserted node.
     /// It is safe to use recursion here because the max depth is limited by the key length which
@@ -233,6 +233,24 @@ where
         }
     }
 
+    /// Merges the new nodes and the stale nodes of another cache based on the same version of the
+    /// tree, such as the cache of a subtree which is updated in parallel.
+    pub fn merge(&mut self, other: TreeCache<'a, R, K>) -> Result<()> {
+        for (node_key, node) in other.node_cache {
+            self.put_node(node_key, node)?;
+        }
+        for node_key in other.stale_node_index_cache {
+            if !self.stale_node_index_cache.insert(node_key) {
+                bail!(
+                    "Node with key {:?} gets stale twice unexpectedly.",
+                    node_key
+                );
+            }
+        }
+        self.num_stale_leaves += other.num_stale_leaves;
+        Ok(())
+    }
+
     /// Freezes all the contents in cache to be immutable and clear `node_cache`.
     pub fn freeze(&mut self) {
         let root_node_key = self.get_root_node_key();
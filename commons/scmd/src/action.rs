@@ -20,6 +20,12 @@ pub trait CommandAction {
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem>;
 
+    /// Run the command without initializing the state, if the opt makes the command not depend
+    /// on the state, such as an offline mode. Return None to run the command by `run`.
+    fn run_stateless(&self, _opt: &Self::Opt) -> Option<Result<Self::ReturnItem>> {
+        None
+    }
+
     fn into_cmd(self) -> Command<Self::State, Self::GlobalOpt, Self::Opt, Self::ReturnItem, Self>
     where
         Self: std::marker::Sized,
@@ -10,6 +10,20 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use structopt::StructOpt;
 
+pub(crate) type StatelessAction = Box<dyn Fn(&ArgMatches<'_>) -> Result<Value>>;
+
+pub(crate) fn stateless_action<Opt, ReturnItem, A>(action: A) -> StatelessAction
+where
+    Opt: StructOpt + 'static,
+    ReturnItem: serde::Serialize + 'static,
+    A: Fn(Opt) -> Result<ReturnItem> + 'static,
+{
+    Box::new(move |arg_matches| {
+        let item = action(Opt::from_clap(arg_matches))?;
+        Ok(serde_json::to_value(item)?)
+    })
+}
+
 pub(crate) trait CommandExec<State, GlobalOpt>
 where
     GlobalOpt: StructOpt + 'static,
@@ -22,6 +36,10 @@ where
         arg_matches: &ArgMatches<'_>,
     ) -> Result<Value>;
 
+    /// Execute the matched stateless subcommand, or the matched command by `CommandAction::run_stateless`,
+    /// return None if the matched command needs the state.
+    fn exec_stateless(&self, arg_matches: &ArgMatches<'_>) -> Option<Result<Value>>;
+
     fn get_app(&mut self) -> &mut App<'static, 'static>;
 }
 
@@ -73,6 +91,8 @@ where
     app: App<'static, 'static>,
     action: Option<Action>,
     subcommands: HashMap<String, Box<dyn CommandExec<State, GlobalOpt>>>,
+    /// The subcommands executed without initializing the state, see `stateless_subcommand`.
+    stateless_subcommands: HashMap<String, StatelessAction>,
     global_opt: PhantomData<GlobalOpt>,
     opt_type: PhantomData<Opt>,
 }
@@ -86,6 +106,7 @@ where
             app: App::new(name),
             action: None,
             subcommands: HashMap::new(),
+            stateless_subcommands: HashMap::new(),
             global_opt: PhantomData,
             opt_type: PhantomData,
         }
@@ -107,6 +128,7 @@ where
             app: Opt::clap(),
             action: Some(FnCommandAction::new(action)),
             subcommands: HashMap::new(),
+            stateless_subcommands: HashMap::new(),
             global_opt: PhantomData,
             opt_type: PhantomData,
         }
@@ -125,6 +147,7 @@ where
             app: Opt::clap(),
             action: None,
             subcommands: HashMap::new(),
+            stateless_subcommands: HashMap::new(),
             global_opt: PhantomData,
             opt_type: PhantomData,
         }
@@ -135,6 +158,7 @@ where
             app: Opt::clap(),
             action: Some(action),
             subcommands: HashMap::new(),
+            stateless_subcommands: HashMap::new(),
             global_opt: PhantomData,
             opt_type: PhantomData,
         }
@@ -162,10 +186,10 @@ where
     {
         let subcommand = subcommand.into();
         let name = subcommand.name();
-        if self.subcommands.contains_key(name) {
+        if self.subcommands.contains_key(name) || self.stateless_subcommands.contains_key(name) {
             panic!("Subcommand with name {} exist.", name);
         }
-        let order = self.subcommands.len();
+        let order = self.subcommands.len() + self.stateless_subcommands.len();
         self.app = self
             .app
             .subcommand(subcommand.app().clone().display_order(order));
@@ -174,8 +198,27 @@ where
         self
     }
 
+    /// Add a subcommand executed without initializing the state, see `CmdContext::stateless_command`.
+    pub fn stateless_subcommand<SubOpt, SubReturnItem, A>(mut self, action: A) -> Self
+    where
+        SubOpt: StructOpt + 'static,
+        SubReturnItem: serde::Serialize + 'static,
+        A: Fn(SubOpt) -> Result<SubReturnItem> + 'static,
+    {
+        let app = SubOpt::clap();
+        let name = app.get_name().to_string();
+        if self.subcommands.contains_key(&name) || self.stateless_subcommands.contains_key(&name) {
+            panic!("Subcommand with name {} exist.", name);
+        }
+        let order = self.subcommands.len() + self.stateless_subcommands.len();
+        self.app = self.app.subcommand(app.display_order(order));
+        self.stateless_subcommands
+            .insert(name, stateless_action(action));
+        self
+    }
+
     pub fn has_subcommand(&self) -> bool {
-        !self.subcommands.is_empty()
+        !self.subcommands.is_empty() || !self.stateless_subcommands.is_empty()
     }
 
     pub fn help_message(&mut self) -> String {
@@ -189,7 +232,10 @@ where
     fn exec_action(&mut self, ctx: &ExecContext<State, GlobalOpt, Opt>) -> Result<Value> {
         match &self.action {
             Some(action) => {
-                let ret = action.run(ctx)?;
+                let ret = match action.run_stateless(ctx.opt()) {
+                    Some(ret) => ret?,
+                    None => action.run(ctx)?,
+                };
                 Ok(serde_json::to_value(ret)?)
             }
             None => Err(anyhow::Error::msg(self.help_message())),
@@ -231,6 +277,11 @@ where
             match subcmd_name {
                 "" => self.exec_action(&ctx)?,
                 subcmd_name => {
+                    if let (Some(action), Some(subcmd_matches)) =
+                        (self.stateless_subcommands.get(subcmd_name), subcmd_matches)
+                    {
+                        return action(subcmd_matches);
+                    }
                     let subcmd = self.subcommands.get_mut(subcmd_name);
                     match (subcmd, subcmd_matches) {
                         (Some(subcmd), Some(subcmd_matches)) => {
@@ -248,6 +299,24 @@ where
         Ok(value)
     }
 
+    fn exec_stateless(&self, arg_matches: &ArgMatches<'_>) -> Option<Result<Value>> {
+        match arg_matches.subcommand() {
+            (subcmd_name, Some(subcmd_matches)) => {
+                if let Some(action) = self.stateless_subcommands.get(subcmd_name) {
+                    return Some(action(subcmd_matches));
+                }
+                self.subcommands
+                    .get(subcmd_name)
+                    .and_then(|subcmd| subcmd.exec_stateless(subcmd_matches))
+            }
+            _ => self
+                .action
+                .as_ref()?
+                .run_stateless(&Opt::from_clap(arg_matches))
+                .map(|ret| Ok(serde_json::to_value(ret?)?)),
+        }
+    }
+
     fn get_app(&mut self) -> &mut App<'static, 'static> {
         &mut self.app
     }
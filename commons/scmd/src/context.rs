@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::CmdError;
+use crate::usage::{command_name, record_usage};
+use crate::command::{stateless_action, StatelessAction};
 use crate::{print_action_result, Command, CommandAction, CommandExec, OutputFormat};
 use anyhow::Result;
-use clap::{crate_authors, App, Arg, SubCommand};
+use clap::{crate_authors, App, Arg, ArgMatches, SubCommand};
 use once_cell::sync::Lazy;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -12,6 +14,7 @@ use std::ffi::OsString;
 use std::fs::File;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 use structopt::StructOpt;
 
 pub use rustyline::{
@@ -40,12 +43,15 @@ where
 {
     app: App<'static, 'static>,
     commands: HashMap<String, Box<dyn CommandExec<State, GlobalOpt>>>,
+    /// The commands executed without initializing the state, see `stateless_command`.
+    stateless_commands: HashMap<String, StatelessAction>,
     default_action: Box<dyn FnOnce(App, GlobalOpt, State)>,
     state_initializer: Box<dyn FnOnce(&GlobalOpt) -> Result<State>>,
     console_support: Option<(
         Box<dyn FnOnce(&App, Arc<GlobalOpt>, Arc<State>) -> (ConsoleConfig, Option<PathBuf>)>,
         Box<dyn FnOnce(App, GlobalOpt, State)>,
     )>,
+    usage_metrics_file: Option<PathBuf>,
 }
 
 impl<State, GlobalOpt> CmdContext<State, GlobalOpt>
@@ -109,9 +115,11 @@ where
         Self {
             app,
             commands: HashMap::new(),
+            stateless_commands: HashMap::new(),
             default_action: Box::new(default_action),
             state_initializer: Box::new(state_initializer),
             console_support: None,
+            usage_metrics_file: None,
         }
     }
 
@@ -134,6 +142,13 @@ where
         self
     }
 
+    /// Enable the usage metrics, the name, duration and result of every executed command are
+    /// aggregated to the `file`. The usage metrics is disabled by default.
+    pub fn with_usage_metrics(mut self, file: PathBuf) -> Self {
+        self.usage_metrics_file = Some(file);
+        self
+    }
+
     //remove this after clap upgrade
     //use of deprecated item 'std::sync::ONCE_INIT': the `new` function is now preferred
     #[allow(deprecated)]
@@ -151,10 +166,10 @@ where
     {
         let command = command.into();
         let name = command.name();
-        if self.commands.contains_key(name) {
+        if self.commands.contains_key(name) || self.stateless_commands.contains_key(name) {
             panic!("Command with name {} exist.", name);
         }
-        let order = self.commands.len();
+        let order = self.commands.len() + self.stateless_commands.len();
         self.app = self
             .app
             .subcommand(command.app().clone().display_order(order));
@@ -162,6 +177,28 @@ where
         self
     }
 
+    /// Add a command executed without initializing the state, for the commands which should not
+    /// depend on the state, such as a command replaces the binary itself. The stateless command is
+    /// not available in the console. Use `Command::stateless_subcommand` to add a stateless
+    /// command under a command group, such as the commands which open the db of a stopped node.
+    pub fn stateless_command<Opt, ReturnItem, A>(mut self, action: A) -> Self
+    where
+        Opt: StructOpt + 'static,
+        ReturnItem: serde::Serialize + 'static,
+        A: Fn(Opt) -> Result<ReturnItem> + 'static,
+    {
+        let app = Opt::clap();
+        let name = app.get_name().to_string();
+        if self.commands.contains_key(&name) || self.stateless_commands.contains_key(&name) {
+            panic!("Command with name {} exist.", name);
+        }
+        let order = self.commands.len() + self.stateless_commands.len();
+        self.app = self.app.subcommand(app.display_order(order));
+        self.stateless_commands
+            .insert(name, stateless_action(action));
+        self
+    }
+
     pub fn print_help(&mut self) {
         self.app
             .print_long_help()
@@ -212,9 +249,32 @@ where
             .expect("parse output-format must success.");
 
         let global_opt = GlobalOpt::from_clap(&matches);
+        let (cmd_name, arg_matches) = matches.subcommand();
+        let usage_metrics_file = self.usage_metrics_file;
+        let start = Instant::now();
+        let stateless_result = arg_matches.and_then(|arg_matches| {
+            match (
+                self.stateless_commands.get(cmd_name),
+                self.commands.get(cmd_name),
+            ) {
+                (Some(action), _) => Some(action(arg_matches)),
+                (None, Some(cmd)) => cmd.exec_stateless(arg_matches),
+                (None, None) => None,
+            }
+        });
+        if let (Some(result), Some(arg_matches)) = (stateless_result, arg_matches) {
+            if let Some(file) = usage_metrics_file.as_ref() {
+                record_usage(
+                    file.as_path(),
+                    command_name(cmd_name, arg_matches).as_str(),
+                    start.elapsed(),
+                    result.is_ok(),
+                );
+            }
+            return Ok((output_format, result));
+        }
         let state = (self.state_initializer)(&global_opt)?;
 
-        let (cmd_name, arg_matches) = matches.subcommand();
         let default_action = self.default_action;
         let result = match cmd_name {
             "console" => {
@@ -229,6 +289,7 @@ where
                         init_action,
                         quit_action,
                         output_format,
+                        usage_metrics_file,
                     );
                     Ok(Value::Null)
                 } else {
@@ -243,7 +304,17 @@ where
                 let cmd = self.commands.get_mut(cmd_name);
                 match (cmd, arg_matches) {
                     (Some(cmd), Some(arg_matches)) => {
-                        cmd.exec(Arc::new(state), Arc::new(global_opt), arg_matches)
+                        let start = Instant::now();
+                        let result = cmd.exec(Arc::new(state), Arc::new(global_opt), arg_matches);
+                        if let Some(file) = usage_metrics_file.as_ref() {
+                            record_usage(
+                                file.as_path(),
+                                command_name(cmd_name, arg_matches).as_str(),
+                                start.elapsed(),
+                                result.is_ok(),
+                            );
+                        }
+                        result
                         //print_action_result(value, output_format)?;
                     }
                     _ => Err(CmdError::need_help(Self::app_help_message(&mut app)).into()),
@@ -263,6 +334,7 @@ where
         >,
         quit_action: Box<dyn FnOnce(App, GlobalOpt, State)>,
         mut output_format: OutputFormat,
+        usage_metrics_file: Option<PathBuf>,
     ) {
         //insert version, quit, history command
         let mut app = app
@@ -388,11 +460,20 @@ where
                                     let app = cmd.get_app();
                                     match app.get_matches_from_safe_borrow(params) {
                                         Ok(arg_matches) => {
+                                            let start = Instant::now();
                                             let result = cmd.exec(
                                                 state.clone(),
                                                 global_opt.clone(),
                                                 &arg_matches,
                                             );
+                                            if let Some(file) = usage_metrics_file.as_ref() {
+                                                record_usage(
+                                                    file.as_path(),
+                                                    command_name(cmd_name, &arg_matches).as_str(),
+                                                    start.elapsed(),
+                                                    result.is_ok(),
+                                                );
+                                            }
                                             if let Err(err) =
                                                 print_action_result(output_format, result, true)
                                             {
@@ -428,3 +509,61 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::format_err;
+
+    #[derive(Debug, StructOpt)]
+    struct GlobalOpt {}
+
+    #[derive(Debug, StructOpt)]
+    #[structopt(name = "echo")]
+    struct EchoOpt {
+        #[structopt(name = "message")]
+        message: String,
+    }
+
+    #[test]
+    fn test_stateless_command() {
+        let context = CmdContext::<(), GlobalOpt>::with_initializer("0.1.0", None, |_| {
+            Err(format_err!("The state should not be initialized."))
+        })
+        .stateless_command(|opt: EchoOpt| Ok(opt.message));
+        let message: String = context
+            .exec_with_args(vec!["test", "echo", "hello"])
+            .unwrap();
+        assert_eq!(message, "hello");
+
+        let context = CmdContext::<(), GlobalOpt>::with_initializer("0.1.0", None, |_| {
+            Err(format_err!("The state should not be initialized."))
+        })
+        .stateless_command(|opt: EchoOpt| Ok(opt.message));
+        assert!(context.exec_with_args::<Value>(vec!["test"]).is_err());
+    }
+
+    #[test]
+    fn test_stateless_subcommand() {
+        let context = CmdContext::<(), GlobalOpt>::with_initializer("0.1.0", None, |_| {
+            Err(format_err!("The state should not be initialized."))
+        })
+        .command(
+            Command::with_name("group").stateless_subcommand(|opt: EchoOpt| Ok(opt.message)),
+        );
+        let message: String = context
+            .exec_with_args(vec!["test", "group", "echo", "hello"])
+            .unwrap();
+        assert_eq!(message, "hello");
+
+        let context = CmdContext::<(), GlobalOpt>::with_initializer("0.1.0", None, |_| {
+            Err(format_err!("The state should not be initialized."))
+        })
+        .command(
+            Command::with_name("group").stateless_subcommand(|opt: EchoOpt| Ok(opt.message)),
+        );
+        assert!(context
+            .exec_with_args::<Value>(vec!["test", "group"])
+            .is_err());
+    }
+}
@@ -6,8 +6,10 @@ mod command;
 mod context;
 pub mod error;
 mod result;
+mod usage;
 
 pub use action::*;
 pub use command::*;
 pub use context::*;
 pub use result::*;
+pub use usage::{CommandUsage, UsageMetrics};
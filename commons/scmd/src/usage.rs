@@ -0,0 +1,99 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// The usage of a command, aggregated locally.
+/// Only the count, duration and result are recorded, the args of command are never recorded.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CommandUsage {
+    pub count: u64,
+    pub success: u64,
+    pub failure: u64,
+    pub total_duration_millis: u64,
+    pub max_duration_millis: u64,
+}
+
+/// The usage metrics of all commands, keyed by the full command name, such as `account create`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct UsageMetrics {
+    pub commands: BTreeMap<String, CommandUsage>,
+}
+
+impl UsageMetrics {
+    /// Load the usage metrics from file, return empty metrics if the file does not exist.
+    pub fn load(file: &Path) -> Result<Self> {
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(file)?;
+        Ok(serde_json::from_str(content.as_str())?)
+    }
+
+    pub fn save(&self, file: &Path) -> Result<()> {
+        std::fs::write(file, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, command: &str, duration: Duration, success: bool) {
+        let usage = self.commands.entry(command.to_string()).or_default();
+        let duration_millis = duration.as_millis() as u64;
+        usage.count += 1;
+        if success {
+            usage.success += 1;
+        } else {
+            usage.failure += 1;
+        }
+        usage.total_duration_millis += duration_millis;
+        usage.max_duration_millis = usage.max_duration_millis.max(duration_millis);
+    }
+}
+
+/// Record the usage of a command to the metrics file.
+/// Recording is best effort, any error is ignored, so it never breaks the command.
+pub(crate) fn record_usage(file: &Path, command: &str, duration: Duration, success: bool) {
+    if let Ok(mut metrics) = UsageMetrics::load(file) {
+        metrics.record(command, duration, success);
+        let _ = metrics.save(file);
+    }
+}
+
+/// Get the full name of the command in matches, only the names of subcommands are included.
+pub(crate) fn command_name(name: &str, matches: &ArgMatches) -> String {
+    let mut names = vec![name];
+    let mut matches = matches;
+    while let (name, Some(sub_matches)) = matches.subcommand() {
+        names.push(name);
+        matches = sub_matches;
+    }
+    names.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_metrics() {
+        let mut metrics = UsageMetrics::default();
+        metrics.record("account create", Duration::from_millis(10), true);
+        metrics.record("account create", Duration::from_millis(30), false);
+        metrics.record("chain info", Duration::from_millis(5), true);
+        let usage = metrics.commands.get("account create").unwrap();
+        assert_eq!(usage.count, 2);
+        assert_eq!(usage.success, 1);
+        assert_eq!(usage.failure, 1);
+        assert_eq!(usage.total_duration_millis, 40);
+        assert_eq!(usage.max_duration_millis, 30);
+
+        let file = std::env::temp_dir().join(format!("usage_metrics_{}.json", std::process::id()));
+        metrics.save(file.as_path()).unwrap();
+        assert_eq!(UsageMetrics::load(file.as_path()).unwrap(), metrics);
+        std::fs::remove_file(file.as_path()).unwrap();
+    }
+}
@@ -2,13 +2,132 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use std::path::Path;
 use systemstat::{Platform, System};
 
+const CGROUP_V2_MEMORY_MAX: &str = "/sys/fs/cgroup/memory.max";
+const CGROUP_V2_MEMORY_CURRENT: &str = "/sys/fs/cgroup/memory.current";
+const CGROUP_V2_CPU_MAX: &str = "/sys/fs/cgroup/cpu.max";
+const CGROUP_V1_MEMORY_LIMIT: &str = "/sys/fs/cgroup/memory/memory.limit_in_bytes";
+const CGROUP_V1_MEMORY_USAGE: &str = "/sys/fs/cgroup/memory/memory.usage_in_bytes";
+const CGROUP_V1_CPU_QUOTA: &str = "/sys/fs/cgroup/cpu/cpu.cfs_quota_us";
+const CGROUP_V1_CPU_PERIOD: &str = "/sys/fs/cgroup/cpu/cpu.cfs_period_us";
+
+/// Get the free memory size, if the process runs in a container with memory limit,
+/// the free size is bounded by the rest of the limit.
 pub fn get_free_mem_size() -> Result<u64> {
     let sys = System::new();
     let free = match sys.memory() {
         Ok(mem) => mem.free.as_u64(),
         Err(_x) => 0u64,
     };
-    Ok(free)
+    match get_cgroup_memory_limit() {
+        Some(limit) => {
+            let usage = get_cgroup_memory_usage().unwrap_or(0);
+            let rest = limit.saturating_sub(usage);
+            Ok(if free > 0 { free.min(rest) } else { rest })
+        }
+        None => Ok(free),
+    }
+}
+
+/// Get the total memory size, bounded by the container memory limit.
+pub fn get_total_mem_size() -> Result<u64> {
+    let sys = System::new();
+    let total = match sys.memory() {
+        Ok(mem) => mem.total.as_u64(),
+        Err(_x) => 0u64,
+    };
+    match get_cgroup_memory_limit() {
+        Some(limit) if total == 0 || limit < total => Ok(limit),
+        _ => Ok(total),
+    }
+}
+
+/// Get the number of available cpus, bounded by the container cpu quota.
+pub fn get_cpu_count() -> usize {
+    let cpus = num_cpus::get();
+    match get_cgroup_cpu_quota() {
+        Some(quota) => (quota.ceil() as usize).max(1).min(cpus),
+        None => cpus,
+    }
+}
+
+/// Get the memory limit of current cgroup, return None if no limit is set.
+pub fn get_cgroup_memory_limit() -> Option<u64> {
+    read_file(CGROUP_V2_MEMORY_MAX)
+        .or_else(|| read_file(CGROUP_V1_MEMORY_LIMIT))
+        .and_then(|s| parse_memory_limit(s.as_str()))
+}
+
+fn get_cgroup_memory_usage() -> Option<u64> {
+    read_file(CGROUP_V2_MEMORY_CURRENT)
+        .or_else(|| read_file(CGROUP_V1_MEMORY_USAGE))
+        .and_then(|s| s.trim().parse().ok())
+}
+
+/// Get the cpu quota of current cgroup in cpu count, return None if no quota is set.
+pub fn get_cgroup_cpu_quota() -> Option<f64> {
+    if let Some(cpu_max) = read_file(CGROUP_V2_CPU_MAX) {
+        return parse_cpu_max(cpu_max.as_str());
+    }
+    let quota = read_file(CGROUP_V1_CPU_QUOTA)?;
+    let period = read_file(CGROUP_V1_CPU_PERIOD)?;
+    parse_cpu_quota(quota.as_str(), period.as_str())
+}
+
+fn read_file(path: &str) -> Option<String> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// cgroup v1 use a huge number as unlimited, such as 9223372036854771712.
+const UNLIMITED_MEMORY_THRESHOLD: u64 = 1 << 60;
+
+fn parse_memory_limit(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if s == "max" {
+        return None;
+    }
+    s.parse::<u64>()
+        .ok()
+        .filter(|limit| *limit > 0 && *limit < UNLIMITED_MEMORY_THRESHOLD)
+}
+
+/// parse cgroup v2 `cpu.max`, the format is `$MAX $PERIOD`, `$MAX` is `max` if unlimited.
+fn parse_cpu_max(s: &str) -> Option<f64> {
+    let mut parts = s.split_whitespace();
+    let quota = parts.next()?;
+    let period = parts.next().unwrap_or("100000");
+    parse_cpu_quota(quota, period)
+}
+
+fn parse_cpu_quota(quota: &str, period: &str) -> Option<f64> {
+    let quota = quota.trim().parse::<i64>().ok().filter(|q| *q > 0)?;
+    let period = period.trim().parse::<i64>().ok().filter(|p| *p > 0)?;
+    Some(quota as f64 / period as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_limit() {
+        assert_eq!(parse_memory_limit("max\n"), None);
+        assert_eq!(parse_memory_limit("9223372036854771712\n"), None);
+        assert_eq!(parse_memory_limit("536870912\n"), Some(536870912));
+        assert_eq!(parse_memory_limit(""), None);
+    }
+
+    #[test]
+    fn test_parse_cpu_quota() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("150000 100000\n"), Some(1.5));
+        assert_eq!(parse_cpu_quota("-1", "100000"), None);
+        assert_eq!(parse_cpu_quota("200000\n", "100000\n"), Some(2.0));
+    }
 }
@@ -20,6 +20,13 @@ pub struct AccountVaultConfig {
     /// Default: account_vaults in data_dir
     dir: Option<PathBuf>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "vault-public-mode")]
+    /// Run the account service in public mode, for public rpc nodes.
+    /// In public mode, no private key can be created or loaded, and all signing apis are disabled.
+    /// This flag support both cli and config file, default is false.
+    public_mode: Option<bool>,
+
     #[serde(skip)]
     #[structopt(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -38,6 +45,10 @@ impl AccountVaultConfig {
             self.base().data_dir().join(path)
         }
     }
+
+    pub fn public_mode(&self) -> bool {
+        self.public_mode.unwrap_or(false)
+    }
 }
 
 impl ConfigModule for AccountVaultConfig {
@@ -46,6 +57,9 @@ impl ConfigModule for AccountVaultConfig {
         if opt.vault.dir.is_some() {
             self.dir = opt.vault.dir.clone();
         }
+        if opt.vault.public_mode.is_some() {
+            self.public_mode = opt.vault.public_mode;
+        }
         Ok(())
     }
 }
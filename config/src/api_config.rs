@@ -11,6 +11,7 @@ pub enum Api {
     Miner,
     NetworkManager,
     NodeManager,
+    NodeAdmin,
     Node,
     PubSub,
     State,
@@ -45,6 +46,7 @@ impl std::fmt::Display for Api {
             Self::Miner => "miner",
             Self::NetworkManager => "network_manager",
             Self::NodeManager => "node_manager",
+            Self::NodeAdmin => "node_admin",
             Self::Node => "node",
             Self::PubSub => "pubsub",
             Self::State => "state",
@@ -69,6 +71,7 @@ impl FromStr for Api {
             "miner" => Ok(Miner),
             "network_manager" => Ok(NetworkManager),
             "node_manager" => Ok(NodeManager),
+            "node_admin" => Ok(NodeAdmin),
             "node" => Ok(Node),
             "pubsub" => Ok(PubSub),
             "state" => Ok(State),
@@ -204,6 +207,7 @@ impl ApiSet {
                 public_list.insert(Api::NetworkManager);
                 public_list.insert(Api::SyncManager);
                 public_list.insert(Api::NodeManager);
+                public_list.insert(Api::NodeAdmin);
                 public_list
             }
 
@@ -10,6 +10,7 @@ use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use std::convert::TryFrom;
 use std::fs;
@@ -35,6 +36,7 @@ mod metrics_config;
 mod miner_config;
 mod network_config;
 mod rpc_config;
+mod snapshot_config;
 mod storage_config;
 mod stratum_config;
 mod sync_config;
@@ -60,12 +62,16 @@ pub use metrics_config::MetricsConfig;
 pub use miner_config::{MinerClientConfig, MinerConfig};
 pub use network_config::{NetworkConfig, NetworkRpcQuotaConfiguration};
 pub use rpc_config::{
-    ApiQuotaConfiguration, HttpConfiguration, IpcConfiguration, RpcConfig, TcpConfiguration,
-    WsConfiguration,
+    ApiKeyConfig, ApiKeyConfiguration, ApiQuotaConfiguration, GraphQLConfiguration,
+    HttpConfiguration, HttpTlsConfig, IpcConfiguration, RestConfiguration, RpcConfig,
+    TcpConfiguration, WsConfiguration,
 };
+pub use snapshot_config::SnapshotConfig;
 pub use starcoin_crypto::ed25519::genesis_key_pair;
 pub use starcoin_vm_types::time::{MockTimeService, RealTimeService, TimeService};
-pub use storage_config::{RocksdbConfig, StorageConfig, DEFAULT_CACHE_SIZE};
+pub use storage_config::{
+    CompactionWindow, EncryptionKeySource, RocksdbConfig, StorageConfig, DEFAULT_CACHE_SIZE,
+};
 pub use txpool_config::TxPoolConfig;
 
 pub static CRATE_VERSION: &str = crate_version!();
@@ -215,6 +221,9 @@ pub struct StarcoinOpt {
     #[serde(default)]
     #[structopt(flatten)]
     pub stratum: StratumConfig,
+    #[serde(default)]
+    #[structopt(flatten)]
+    pub snapshot: SnapshotConfig,
 }
 
 impl std::fmt::Display for StarcoinOpt {
@@ -393,6 +402,12 @@ impl BaseConfig {
             config.merge_with_opt(opt, base.clone())?;
             config
         };
+        info!(
+            "Detected resources: cpus: {}, total memory: {:?}, container memory limit: {:?}",
+            starcoin_system::get_cpu_count(),
+            starcoin_system::get_total_mem_size().ok(),
+            starcoin_system::get_cgroup_memory_limit()
+        );
         info!("Final config: {}", config);
         Ok(config)
     }
@@ -430,6 +445,8 @@ pub struct NodeConfig {
     pub logger: LoggerConfig,
     #[serde(default)]
     pub stratum: StratumConfig,
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
 }
 
 impl std::fmt::Display for NodeConfig {
@@ -475,6 +492,25 @@ impl NodeConfig {
     pub fn node_name(&self) -> String {
         self.network.node_name()
     }
+
+    /// A short fingerprint of the genesis values and the key config flags of the node, such as the
+    /// storage mode. Nodes of a network with different fingerprints are running mixed networks,
+    /// or stale or different configs.
+    pub fn fingerprint(&self, genesis_hash: HashValue) -> String {
+        let net = self.net();
+        let key = format!(
+            "{}:{}:{}:{}:{}:archive={}:prune_retain_blocks={:?}:cold_db={}",
+            genesis_hash,
+            net.id(),
+            net.chain_id(),
+            net.genesis_config().consensus(),
+            net.stdlib_version(),
+            self.storage.archive(),
+            self.storage.prune_retain_blocks(),
+            self.storage.cold_db_dir().is_some(),
+        );
+        HashValue::sha3_256_of(key.as_bytes()).to_hex()[..8].to_string()
+    }
 }
 
 impl NodeConfig {
@@ -489,7 +525,8 @@ impl NodeConfig {
         self.vault.merge_with_opt(opt, base.clone())?;
         self.metrics.merge_with_opt(opt, base.clone())?;
         self.logger.merge_with_opt(opt, base.clone())?;
-        self.stratum.merge_with_opt(opt, base)?;
+        self.stratum.merge_with_opt(opt, base.clone())?;
+        self.snapshot.merge_with_opt(opt, base)?;
         Ok(())
     }
 }
@@ -4,7 +4,9 @@
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use starcoin_types::account_address::AccountAddress;
 use std::sync::Arc;
+use std::time::Duration;
 use structopt::StructOpt;
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, StructOpt)]
@@ -31,6 +33,18 @@ pub struct MinerConfig {
     /// Miner client thread number, not work for dev network, default is 1
     pub miner_thread: Option<u16>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "miner-template-update-interval")]
+    /// The min interval in seconds to update the minting block template with the new txns in pool,
+    /// the update keeps the parent block of the template. Default is 0, which disables the update.
+    pub template_update_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "miner-author")]
+    /// The block author address, default is the default account of the node wallet.
+    /// Required when the vault is in public mode, the account must already exist on chain.
+    pub author: Option<AccountAddress>,
+
     #[serde(skip)]
     #[structopt(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -47,6 +61,12 @@ impl MinerConfig {
         self.disable_mint_empty_block
             .unwrap_or_else(|| self.base().net().is_dev())
     }
+    pub fn template_update_interval(&self) -> Option<Duration> {
+        match self.template_update_interval.unwrap_or(0) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
     pub fn miner_client_config(&self) -> Option<MinerClientConfig> {
         if self.disable_miner_client() {
             return None;
@@ -97,6 +117,12 @@ impl ConfigModule for MinerConfig {
         if opt.miner.block_gas_limit.is_some() {
             self.block_gas_limit = opt.miner.block_gas_limit;
         }
+        if opt.miner.template_update_interval.is_some() {
+            self.template_update_interval = opt.miner.template_update_interval;
+        }
+        if opt.miner.author.is_some() {
+            self.author = opt.miner.author;
+        }
 
         Ok(())
     }
@@ -11,13 +11,15 @@ use network_api::messages::{NotificationMessage, BLOCK_PROTOCOL_NAME};
 use network_p2p_types::{
     is_memory_addr, memory_addr,
     multiaddr::{Multiaddr, Protocol},
+    permission::PermissionConfig,
     MultiaddrWithPeerId,
 };
 use once_cell::sync::Lazy;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
-use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use starcoin_crypto::ValidCryptoMaterialStringExt;
 use starcoin_logger::prelude::*;
 use starcoin_types::peer_info::PeerId;
 use std::borrow::Cow;
@@ -242,6 +244,25 @@ pub struct NetworkConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[structopt(name = "unsupported-protocols", long, use_delimiter = true)]
     pub unsupported_protocols: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "network-ca", parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    /// The public key of the network CA, enable the permissioned network.
+    /// Peers should present the identity signed by the network CA in handshake, otherwise rejected,
+    /// and the connections with them are closed until the node restarts.
+    pub network_ca: Option<Ed25519PublicKey>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "node-identity", parse(try_from_str = Ed25519Signature::from_encoded_string))]
+    /// The identity of this node in permissioned network, which is the peer id and chain id signed by the network CA.
+    /// Generate it by `dev sign-peer-identity`.
+    pub node_identity: Option<Ed25519Signature>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "allowed-peers", use_delimiter = true)]
+    /// The peers allowed to connect without identity, enable the permissioned network.
+    /// multi peer id should use ',' as delimiter.
+    pub allowed_peers: Option<Vec<PeerId>>,
 }
 
 impl NetworkConfig {
@@ -382,6 +403,24 @@ impl NetworkConfig {
         }
     }
 
+    /// Return the permission config if the network is permissioned.
+    pub fn permission_config(&self) -> Option<PermissionConfig> {
+        if self.network_ca.is_none() && self.allowed_peers.is_none() {
+            return None;
+        }
+        Some(PermissionConfig {
+            network_ca: self.network_ca.clone(),
+            allowed_peers: self
+                .allowed_peers
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            identity: self.node_identity.clone(),
+        })
+    }
+
     pub fn supported_network_protocols(&self) -> Vec<Cow<'static, str>> {
         let protocols = NotificationMessage::protocols();
         if let Some(unsupported_protocols) = &self.unsupported_protocols {
@@ -464,6 +503,16 @@ impl ConfigModule for NetworkConfig {
             );
         }
 
+        if opt.network.network_ca.is_some() {
+            self.network_ca = opt.network.network_ca.clone();
+        }
+        if opt.network.node_identity.is_some() {
+            self.node_identity = opt.network.node_identity.clone();
+        }
+        if opt.network.allowed_peers.is_some() {
+            self.allowed_peers = opt.network.allowed_peers.clone();
+        }
+
         self.load_or_generate_keypair()?;
         self.generate_listen_address();
         Ok(())
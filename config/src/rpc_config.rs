@@ -5,26 +5,56 @@ use crate::{
     get_available_port_from, get_random_available_ports, parse_key_val, ApiQuotaConfig, ApiSet,
     BaseConfig, ConfigModule, QuotaDuration, StarcoinOpt,
 };
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use serde::{Deserialize, Serialize};
 use starcoin_logger::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use structopt::StructOpt;
 
 //10M
 const DEFAULT_MAX_REQUEST_BODY_SIZE: usize = 10 * 1024 * 1024;
+const DEFAULT_IPC_DIR: &str = "ipc";
 const DEFAULT_IPC_FILE: &str = "starcoin.ipc";
 const DEFAULT_HTTP_PORT: u16 = 9850;
 const DEFAULT_TCP_PORT: u16 = 9860;
 const DEFAULT_WEB_SOCKET_PORT: u16 = 9870;
+const DEFAULT_GRAPHQL_PORT: u16 = 9890;
+const DEFAULT_REST_PORT: u16 = 9900;
+const DEFAULT_GRAPHQL_MAX_DEPTH: usize = 8;
+const DEFAULT_GRAPHQL_MAX_COMPLEXITY: usize = 1000;
+const DEFAULT_WS_MAX_BUFFER_CAPACITY: usize = 10 * 1024 * 1024;
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 32;
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_IP: usize = 128;
+const DEFAULT_SUBSCRIPTION_BACKLOG: usize = 1024;
 // UNSPECIFIED is 0.0.0.0
 const DEFAULT_RPC_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 const DEFAULT_BLOCK_QUERY_MAX_RANGE: u64 = 32;
+const DEFAULT_ARCHIVE_RECENT_BLOCKS: u64 = 1000;
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+const DEFAULT_SLOW_CALL_THRESHOLD: u64 = 1000;
+const DEFAULT_API_KEY_HEADER: &str = "X-Api-Key";
+/// The apis which may take a long time, run on the heavy api thread pool by default.
+const DEFAULT_HEAVY_APIS: &[&str] = &[
+    "contract.call",
+    "contract.dry_run",
+    "contract.dry_run_raw",
+    "contract.get_code_disassembled",
+    "chain.get_blocks_by_number",
+    "chain.get_epoch_infos_by_number",
+    "chain.get_events",
+    "chain.get_events_after",
+    "chain.get_headers",
+    "node_admin.dump_state",
+    "state.get_balances",
+    "state.get_resources",
+    "txpool.content",
+];
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, StructOpt)]
 pub struct HttpConfiguration {
@@ -52,9 +82,24 @@ pub struct HttpConfiguration {
     ///max request body in bytes, Default is 10M
     pub max_request_body_size: Option<usize>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-max-response-body", long)]
+    /// Max response body in bytes, the response over the limit is replaced by an error. Default is no limit.
+    pub max_response_body_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-max-batch-size", long)]
+    /// Max calls of a batch request on http, Default is the `max-batch-size`.
+    pub max_batch_size: Option<usize>,
+
+    #[serde(default)]
+    #[structopt(name = "disable-http-compression", long)]
+    /// Disable the gzip/deflate compression of the http responses, which is negotiated by the `Accept-Encoding` header.
+    pub disable_compression: bool,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     #[structopt(name = "http-threads", long)]
-    /// How many thread to use for http service.
+    /// How many thread to use for http service. Default to the available cpu count, bounded by container cpu quota.
     pub threads: Option<usize>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -65,6 +110,40 @@ pub struct HttpConfiguration {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[structopt(name = "unsupported-rpc-protocols", long, use_delimiter = true)]
     unsupported_rpc_protocols: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-cors-origins", long, use_delimiter = true)]
+    /// The origins allowed to call the http jsonrpc from browsers, such as https://app.example.com, Default allows any origin.
+    pub cors_origins: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(skip)]
+    /// The methods an origin can call, such as `chain.*` or `node.info`, keyed by the origin.
+    /// The origins not listed can call all the methods. Only configurable in the config file.
+    pub cors_methods: Option<HashMap<String, Vec<String>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-tls-cert", long, parse(from_os_str))]
+    /// The PEM certificate chain file, the http jsonrpc is served over https if the cert and key are set.
+    pub tls_cert: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-tls-key", long, parse(from_os_str))]
+    /// The PEM private key file of the tls certificate, PKCS#8 or RSA.
+    pub tls_key: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "http-tls-client-ca", long, parse(from_os_str))]
+    /// The PEM CA certificates file, the clients are required to present a certificate signed by the CA if set.
+    pub tls_client_ca: Option<PathBuf>,
+}
+
+/// The tls settings of the http jsonrpc.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpTlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    pub client_ca: Option<PathBuf>,
 }
 
 impl HttpConfiguration {
@@ -73,7 +152,7 @@ impl HttpConfiguration {
             .unwrap_or(DEFAULT_MAX_REQUEST_BODY_SIZE)
     }
     pub fn threads(&self) -> usize {
-        self.threads.unwrap_or_else(num_cpus::get)
+        self.threads.unwrap_or_else(starcoin_system::get_cpu_count)
     }
     pub fn apis(&self) -> &ApiSet {
         self.apis.as_ref().unwrap_or(&ApiSet::UnsafeContext)
@@ -84,6 +163,25 @@ impl HttpConfiguration {
             .unwrap_or_else(|| vec!["X-Real-IP".to_string(), "X-Forwarded-For".to_string()])
     }
 
+    /// The tls settings, None if the http jsonrpc is served over plain http.
+    pub fn tls(&self) -> Result<Option<HttpTlsConfig>> {
+        match (self.tls_cert.as_ref(), self.tls_key.as_ref()) {
+            (Some(cert), Some(key)) => Ok(Some(HttpTlsConfig {
+                cert: cert.clone(),
+                key: key.clone(),
+                client_ca: self.tls_client_ca.clone(),
+            })),
+            (None, None) => {
+                ensure!(
+                    self.tls_client_ca.is_none(),
+                    "The http-tls-client-ca requires the http-tls-cert and http-tls-key."
+                );
+                Ok(None)
+            }
+            _ => bail!("The http-tls-cert and http-tls-key should be set together."),
+        }
+    }
+
     pub fn merge(&mut self, o: &Self) -> Result<()> {
         if o.disable {
             self.disable = true;
@@ -97,6 +195,15 @@ impl HttpConfiguration {
         if o.max_request_body_size.is_some() {
             self.max_request_body_size = o.max_request_body_size;
         }
+        if o.max_response_body_size.is_some() {
+            self.max_response_body_size = o.max_response_body_size;
+        }
+        if o.max_batch_size.is_some() {
+            self.max_batch_size = o.max_batch_size;
+        }
+        if o.disable_compression {
+            self.disable_compression = true;
+        }
         if o.threads.is_some() {
             self.threads = o.threads;
         }
@@ -125,6 +232,21 @@ impl HttpConfiguration {
                     .collect(),
             );
         }
+        if o.cors_origins.is_some() {
+            self.cors_origins = o.cors_origins.clone();
+        }
+        if o.cors_methods.is_some() {
+            self.cors_methods = o.cors_methods.clone();
+        }
+        if o.tls_cert.is_some() {
+            self.tls_cert = o.tls_cert.clone();
+        }
+        if o.tls_key.is_some() {
+            self.tls_key = o.tls_key.clone();
+        }
+        if o.tls_client_ca.is_some() {
+            self.tls_client_ca = o.tls_client_ca.clone();
+        }
         Ok(())
     }
 
@@ -147,6 +269,16 @@ pub struct TcpConfiguration {
     #[structopt(name = "tcp-port", long)]
     /// Default tcp port is 9860
     pub port: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "tcp-max-response-body", long)]
+    /// Max response body in bytes, the response over the limit is replaced by an error. Default is no limit.
+    pub max_response_body_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "tcp-max-batch-size", long)]
+    /// Max calls of a batch request on tcp, Default is the `max-batch-size`.
+    pub max_batch_size: Option<usize>,
 }
 
 impl TcpConfiguration {
@@ -164,6 +296,12 @@ impl TcpConfiguration {
         if o.port.is_some() {
             self.port = o.port;
         }
+        if o.max_response_body_size.is_some() {
+            self.max_response_body_size = o.max_response_body_size;
+        }
+        if o.max_batch_size.is_some() {
+            self.max_batch_size = o.max_batch_size;
+        }
         Ok(())
     }
 }
@@ -191,6 +329,47 @@ pub struct WsConfiguration {
     #[structopt(name = "websocket-max-request-body", long)]
     /// Max request body in bytes, Default is 10M
     pub max_request_body_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-response-body", long)]
+    /// Max response body in bytes, the response over the limit is replaced by an error. Default is no limit.
+    pub max_response_body_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-batch-size", long)]
+    /// Max calls of a batch request on websocket, Default is the `max-batch-size`.
+    pub max_batch_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-in-buffer", long)]
+    /// Max size in bytes of the buffer of the incoming frames of a connection, Default is 10M.
+    pub max_in_buffer_capacity: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-out-buffer", long)]
+    /// Max size in bytes of the buffer of the outgoing frames of a connection, a message larger than it
+    /// can not be sent. Default is 10M.
+    pub max_out_buffer_capacity: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-subscriptions-per-connection", long)]
+    /// Max active pubsub subscriptions of a connection, the new subscriptions over the limit are rejected.
+    /// Default is 32.
+    pub max_subscriptions_per_connection: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-max-subscriptions-per-ip", long)]
+    /// Max active pubsub subscriptions of all the connections from an ip. The ip of a websocket connection
+    /// is read by the http ip headers, and the websocket connections without the headers share one limit.
+    /// Default is 128.
+    pub max_subscriptions_per_ip: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "websocket-subscription-backlog", long)]
+    /// The latest notifications buffered for each newHeads and events subscription with cursor of
+    /// a connection, to resume the subscription from a cursor after a reconnection, the buffer is
+    /// kept for 5 minutes after the connection is closed. Default is 1024.
+    pub subscription_backlog: Option<usize>,
 }
 
 impl WsConfiguration {
@@ -201,6 +380,26 @@ impl WsConfiguration {
     pub fn apis(&self) -> &ApiSet {
         self.apis.as_ref().unwrap_or(&ApiSet::PubSub)
     }
+    pub fn max_in_buffer_capacity(&self) -> usize {
+        self.max_in_buffer_capacity
+            .unwrap_or(DEFAULT_WS_MAX_BUFFER_CAPACITY)
+    }
+    pub fn max_out_buffer_capacity(&self) -> usize {
+        self.max_out_buffer_capacity
+            .unwrap_or(DEFAULT_WS_MAX_BUFFER_CAPACITY)
+    }
+    pub fn max_subscriptions_per_connection(&self) -> usize {
+        self.max_subscriptions_per_connection
+            .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION)
+    }
+    pub fn max_subscriptions_per_ip(&self) -> usize {
+        self.max_subscriptions_per_ip
+            .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS_PER_IP)
+    }
+    pub fn subscription_backlog(&self) -> usize {
+        self.subscription_backlog
+            .unwrap_or(DEFAULT_SUBSCRIPTION_BACKLOG)
+    }
     pub fn merge(&mut self, o: &Self) -> Result<()> {
         if o.disable {
             self.disable = true;
@@ -214,6 +413,102 @@ impl WsConfiguration {
         if o.max_request_body_size.is_some() {
             self.max_request_body_size = o.max_request_body_size;
         }
+        if o.max_response_body_size.is_some() {
+            self.max_response_body_size = o.max_response_body_size;
+        }
+        if o.max_batch_size.is_some() {
+            self.max_batch_size = o.max_batch_size;
+        }
+        if o.max_in_buffer_capacity.is_some() {
+            self.max_in_buffer_capacity = o.max_in_buffer_capacity;
+        }
+        if o.max_out_buffer_capacity.is_some() {
+            self.max_out_buffer_capacity = o.max_out_buffer_capacity;
+        }
+        if o.max_subscriptions_per_connection.is_some() {
+            self.max_subscriptions_per_connection = o.max_subscriptions_per_connection;
+        }
+        if o.max_subscriptions_per_ip.is_some() {
+            self.max_subscriptions_per_ip = o.max_subscriptions_per_ip;
+        }
+        if o.subscription_backlog.is_some() {
+            self.subscription_backlog = o.subscription_backlog;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, StructOpt)]
+pub struct GraphQLConfiguration {
+    #[serde(default)]
+    #[structopt(name = "enable-graphql", long)]
+    /// enable the graphql endpoint of chain data, disabled by default. The graphql requests are
+    /// checked by the api keys and quotas of the http json rpc as the method `graphql.execute`
+    pub enable: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "graphql-port", long)]
+    /// Default graphql port is 9890
+    pub port: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "graphql-max-depth", long)]
+    /// Max nested depth of a graphql query, Default is 8
+    pub max_depth: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "graphql-max-complexity", long)]
+    /// Max complexity of a graphql query, every selected field counts 1 and a list field counts its
+    /// length times its item, Default is 1000
+    pub max_complexity: Option<usize>,
+}
+
+impl GraphQLConfiguration {
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.unwrap_or(DEFAULT_GRAPHQL_MAX_DEPTH)
+    }
+    pub fn max_complexity(&self) -> usize {
+        self.max_complexity
+            .unwrap_or(DEFAULT_GRAPHQL_MAX_COMPLEXITY)
+    }
+    pub fn merge(&mut self, o: &Self) -> Result<()> {
+        if o.enable {
+            self.enable = true;
+        }
+        if o.port.is_some() {
+            self.port = o.port;
+        }
+        if o.max_depth.is_some() {
+            self.max_depth = o.max_depth;
+        }
+        if o.max_complexity.is_some() {
+            self.max_complexity = o.max_complexity;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, StructOpt)]
+pub struct RestConfiguration {
+    #[serde(default)]
+    #[structopt(name = "enable-rest-api", long)]
+    /// enable the rest api gateway in front of the json rpc, only works if the node is built with the rest-api feature
+    pub enable: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "rest-port", long)]
+    /// Default rest api port is 9900
+    pub port: Option<u16>,
+}
+
+impl RestConfiguration {
+    pub fn merge(&mut self, o: &Self) -> Result<()> {
+        if o.enable {
+            self.enable = true;
+        }
+        if o.port.is_some() {
+            self.port = o.port;
+        }
         Ok(())
     }
 }
@@ -225,8 +520,27 @@ pub struct IpcConfiguration {
     pub disable: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[structopt(name = "ipc-apis", long, help = "rpc apiset to serve")]
+    #[structopt(
+        name = "ipc-apis",
+        long,
+        help = "rpc apiset to serve, default is all the apis include the admin apis"
+    )]
     pub apis: Option<ApiSet>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "ipc-methods", long, use_delimiter = true)]
+    /// The methods can be called by ipc, such as chain.*,node_admin.*, all methods of the apis are allowed if not set.
+    pub methods: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "ipc-max-response-body", long)]
+    /// Max response body in bytes, the response over the limit is replaced by an error. Default is no limit.
+    pub max_response_body_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "ipc-max-batch-size", long)]
+    /// Max calls of a batch request on ipc, Default is the `max-batch-size`.
+    pub max_batch_size: Option<usize>,
 }
 
 impl Default for IpcConfiguration {
@@ -234,6 +548,9 @@ impl Default for IpcConfiguration {
         Self {
             disable: false,
             apis: None,
+            methods: None,
+            max_response_body_size: None,
+            max_batch_size: None,
         }
     }
 }
@@ -249,6 +566,15 @@ impl IpcConfiguration {
         if o.apis.is_some() {
             self.apis = o.apis.clone();
         }
+        if o.methods.is_some() {
+            self.methods = o.methods.clone();
+        }
+        if o.max_response_body_size.is_some() {
+            self.max_response_body_size = o.max_response_body_size;
+        }
+        if o.max_batch_size.is_some() {
+            self.max_batch_size = o.max_batch_size;
+        }
         Ok(())
     }
 }
@@ -339,6 +665,104 @@ impl ApiQuotaConfiguration {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ApiKeyConfig {
+    /// The secret key, sent by the clients in the api key header.
+    pub key: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Quota of all the calls of the key, Default is the default api key quota.
+    pub quota: Option<ApiQuotaConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The methods the key can call, such as `chain.*` or `node.info`, all methods are allowed if not set.
+    pub methods: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, StructOpt)]
+pub struct ApiKeyConfiguration {
+    #[serde(default)]
+    #[structopt(name = "jsonrpc-require-api-key", long)]
+    /// Reject the calls without a valid api key. The http and rest clients send the api key by the api key header,
+    /// the websocket clients by the header or the `api_key` query parameter of the handshake, and the tcp clients
+    /// by the `rpc.auth` call with the api key. The ipc is not checked.
+    pub require_api_key: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "jsonrpc-api-key-header", long)]
+    /// The http and websocket handshake header of the api key, Default is X-Api-Key
+    pub api_key_header: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "jsonrpc-default-api-key-quota",
+        long,
+        help = "default quota of all the calls of an api key, eg: 100/s"
+    )]
+    pub default_api_key_quota: Option<ApiQuotaConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "jsonrpc-anonymous-methods", long, use_delimiter = true)]
+    /// The methods can be called without api key, such as chain.*,node.info, all methods are allowed if not set.
+    pub anonymous_methods: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(skip)]
+    /// The api keys by name, the name is used in logs and metrics. Only configurable in the config file.
+    pub keys: Option<HashMap<String, ApiKeyConfig>>,
+}
+
+impl ApiKeyConfiguration {
+    pub fn api_key_header(&self) -> String {
+        self.api_key_header
+            .clone()
+            .unwrap_or_else(|| DEFAULT_API_KEY_HEADER.to_string())
+    }
+
+    pub fn default_api_key_quota(&self) -> ApiQuotaConfig {
+        self.default_api_key_quota
+            .clone()
+            .unwrap_or(ApiQuotaConfig {
+                max_burst: NonZeroU32::new(100).expect("New NonZeroU32 should success."),
+                duration: QuotaDuration::Second,
+            })
+    }
+
+    pub fn keys(&self) -> HashMap<String, ApiKeyConfig> {
+        self.keys.clone().unwrap_or_default()
+    }
+
+    /// The api key check is enabled if any key, anonymous method or require api key is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.require_api_key
+            || self.anonymous_methods.is_some()
+            || self
+                .keys
+                .as_ref()
+                .map(|keys| !keys.is_empty())
+                .unwrap_or(false)
+    }
+
+    pub fn merge(&mut self, o: &Self) -> Result<()> {
+        if o.require_api_key {
+            self.require_api_key = true;
+        }
+        if o.api_key_header.is_some() {
+            self.api_key_header = o.api_key_header.clone();
+        }
+        if o.default_api_key_quota.is_some() {
+            self.default_api_key_quota = o.default_api_key_quota.clone();
+        }
+        if o.anonymous_methods.is_some() {
+            self.anonymous_methods = o.anonymous_methods.clone();
+        }
+        if o.keys.is_some() {
+            self.keys = o.keys.clone();
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize, Serialize, StructOpt)]
 #[serde(deny_unknown_fields)]
 pub struct RpcConfig {
@@ -358,10 +782,22 @@ pub struct RpcConfig {
     #[structopt(flatten)]
     pub ipc: IpcConfiguration,
 
+    #[serde(default)]
+    #[structopt(flatten)]
+    pub graphql: GraphQLConfiguration,
+
+    #[serde(default)]
+    #[structopt(flatten)]
+    pub rest: RestConfiguration,
+
     #[serde(default)]
     #[structopt(flatten)]
     pub api_quotas: ApiQuotaConfiguration,
 
+    #[serde(default)]
+    #[structopt(flatten)]
+    pub api_keys: ApiKeyConfiguration,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     #[structopt(long = "rpc-address")]
     /// Rpc address, default is 0.0.0.0
@@ -371,6 +807,47 @@ pub struct RpcConfig {
     #[structopt(long = "event-query-max-block-range")]
     pub block_query_max_range: Option<u64>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "archive-rpc")]
+    /// Websocket rpc address of an archive node, such as ws://127.0.0.1:9870,
+    /// the historical queries are forwarded to the archive node, and recent queries are served locally.
+    pub archive_rpc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "archive-recent-blocks")]
+    /// Only serve the queries of recent N blocks locally if the archive rpc is set, default is 1000.
+    pub archive_recent_blocks: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "heavy-api-threads")]
+    /// How many threads to run the heavy apis, such as dry-run and range queries.
+    /// Default to half of the available cpu count, at least 2.
+    pub heavy_api_threads: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+    name = "heavy-api-concurrency",
+    long,
+    help = "max concurrent calls of a heavy api, the calls over the limit wait in queue, eg: contract.dry_run=4",
+    number_of_values = 1,
+    parse(try_from_str = parse_key_val)
+    )]
+    /// Customize the heavy apis and their concurrency limits, merged with the default heavy apis,
+    /// whose limit is the heavy api threads.
+    pub heavy_api_concurrency: Option<Vec<(String, usize)>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "max-batch-size")]
+    /// Max calls of a batch request, the batch over the limit is rejected as a whole. Default is 100.
+    /// It can be overridden by the transports.
+    pub max_batch_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(long = "slow-call-threshold")]
+    /// Log the rpc calls slower than the threshold in milliseconds, and the slow http requests with
+    /// their redacted body. Default is 1000, 0 to disable the slow call log.
+    pub slow_call_threshold: Option<u64>,
+
     #[serde(skip)]
     #[structopt(skip)]
     http_address: Option<ListenAddress>,
@@ -383,6 +860,14 @@ pub struct RpcConfig {
     #[structopt(skip)]
     ws_address: Option<ListenAddress>,
 
+    #[serde(skip)]
+    #[structopt(skip)]
+    graphql_address: Option<ListenAddress>,
+
+    #[serde(skip)]
+    #[structopt(skip)]
+    rest_address: Option<ListenAddress>,
+
     #[serde(skip)]
     #[structopt(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -446,23 +931,71 @@ impl RpcConfig {
         self.ws_address.clone()
     }
 
+    pub fn get_graphql_address(&self) -> Option<ListenAddress> {
+        self.graphql_address.clone()
+    }
+
+    pub fn get_rest_address(&self) -> Option<ListenAddress> {
+        self.rest_address.clone()
+    }
+
     pub fn block_query_max_range(&self) -> u64 {
         self.block_query_max_range
             .unwrap_or(DEFAULT_BLOCK_QUERY_MAX_RANGE)
     }
 
+    pub fn archive_rpc(&self) -> Option<&str> {
+        self.archive_rpc.as_deref()
+    }
+
+    pub fn archive_recent_blocks(&self) -> u64 {
+        self.archive_recent_blocks
+            .unwrap_or(DEFAULT_ARCHIVE_RECENT_BLOCKS)
+    }
+
+    pub fn heavy_api_threads(&self) -> usize {
+        self.heavy_api_threads
+            .unwrap_or_else(|| (starcoin_system::get_cpu_count() / 2).max(2))
+    }
+
+    /// The heavy apis and their concurrency limits.
+    pub fn heavy_api_concurrency(&self) -> HashMap<String, usize> {
+        let mut limits: HashMap<String, usize> = DEFAULT_HEAVY_APIS
+            .iter()
+            .map(|method| (method.to_string(), self.heavy_api_threads()))
+            .collect();
+        limits.extend(self.heavy_api_concurrency.clone().unwrap_or_default());
+        limits
+    }
+
+    pub fn max_batch_size(&self) -> usize {
+        self.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE)
+    }
+
+    pub fn slow_call_threshold(&self) -> Option<Duration> {
+        match self
+            .slow_call_threshold
+            .unwrap_or(DEFAULT_SLOW_CALL_THRESHOLD)
+        {
+            0 => None,
+            threshold => Some(Duration::from_millis(threshold)),
+        }
+    }
+
     fn base(&self) -> &BaseConfig {
         self.base.as_ref().expect("Config should init.")
     }
 
     fn generate_address(&mut self) {
         let base = self.base();
-        let (http_port, tcp_port, ws_port) = if base.net().is_test() {
-            let ports = get_random_available_ports(3);
+        let (http_port, tcp_port, ws_port, graphql_port, rest_port) = if base.net().is_test() {
+            let ports = get_random_available_ports(5);
             (
                 self.http.port.unwrap_or(ports[0]),
                 self.tcp.port.unwrap_or(ports[1]),
                 self.ws.port.unwrap_or(ports[2]),
+                self.graphql.port.unwrap_or(ports[3]),
+                self.rest.port.unwrap_or(ports[4]),
             )
         } else if base.net().is_dev() {
             (
@@ -475,12 +1008,20 @@ impl RpcConfig {
                 self.ws
                     .port
                     .unwrap_or_else(|| get_available_port_from(DEFAULT_WEB_SOCKET_PORT)),
+                self.graphql
+                    .port
+                    .unwrap_or_else(|| get_available_port_from(DEFAULT_GRAPHQL_PORT)),
+                self.rest
+                    .port
+                    .unwrap_or_else(|| get_available_port_from(DEFAULT_REST_PORT)),
             )
         } else {
             (
                 self.http.port.unwrap_or(DEFAULT_HTTP_PORT),
                 self.tcp.port.unwrap_or(DEFAULT_TCP_PORT),
                 self.ws.port.unwrap_or(DEFAULT_WEB_SOCKET_PORT),
+                self.graphql.port.unwrap_or(DEFAULT_GRAPHQL_PORT),
+                self.rest.port.unwrap_or(DEFAULT_REST_PORT),
             )
         };
         self.http_address = if self.http.disable {
@@ -498,11 +1039,22 @@ impl RpcConfig {
         } else {
             Some(ListenAddress::new("ws", self.rpc_address(), ws_port))
         };
+        self.graphql_address = if self.graphql.enable {
+            Some(ListenAddress::new("http", self.rpc_address(), graphql_port))
+        } else {
+            None
+        };
+        self.rest_address = if self.rest.enable {
+            Some(ListenAddress::new("http", self.rpc_address(), rest_port))
+        } else {
+            None
+        };
     }
 
+    /// The ipc socket is in a dedicated dir of the data dir, which only the owner can access.
     #[cfg(not(windows))]
     fn get_ipc_file_by_base(base: &BaseConfig) -> PathBuf {
-        base.data_dir().join(DEFAULT_IPC_FILE)
+        base.data_dir().join(DEFAULT_IPC_DIR).join(DEFAULT_IPC_FILE)
     }
 
     #[cfg(windows)]
@@ -523,11 +1075,32 @@ impl ConfigModule for RpcConfig {
         if opt.rpc.block_query_max_range.is_some() {
             self.block_query_max_range = opt.rpc.block_query_max_range;
         }
+        if opt.rpc.archive_rpc.is_some() {
+            self.archive_rpc = opt.rpc.archive_rpc.clone();
+        }
+        if opt.rpc.archive_recent_blocks.is_some() {
+            self.archive_recent_blocks = opt.rpc.archive_recent_blocks;
+        }
+        if opt.rpc.heavy_api_threads.is_some() {
+            self.heavy_api_threads = opt.rpc.heavy_api_threads;
+        }
+        if opt.rpc.heavy_api_concurrency.is_some() {
+            self.heavy_api_concurrency = opt.rpc.heavy_api_concurrency.clone();
+        }
+        if opt.rpc.max_batch_size.is_some() {
+            self.max_batch_size = opt.rpc.max_batch_size;
+        }
+        if opt.rpc.slow_call_threshold.is_some() {
+            self.slow_call_threshold = opt.rpc.slow_call_threshold;
+        }
         self.http.merge(&opt.rpc.http)?;
         self.tcp.merge(&opt.rpc.tcp)?;
         self.ws.merge(&opt.rpc.ws)?;
         self.ipc.merge(&opt.rpc.ipc)?;
+        self.graphql.merge(&opt.rpc.graphql)?;
+        self.rest.merge(&opt.rpc.rest)?;
         self.api_quotas.merge(&opt.rpc.api_quotas)?;
+        self.api_keys.merge(&opt.rpc.api_keys)?;
 
         self.generate_address();
 
@@ -535,6 +1108,8 @@ impl ConfigModule for RpcConfig {
         info!("TCP rpc address: {:?}", self.get_tcp_address());
         info!("Websocket rpc address: {:?}", self.get_ws_address());
         info!("Ipc file path: {:?}", self.get_ipc_file());
+        info!("GraphQL address: {:?}", self.get_graphql_address());
+        info!("Rest api address: {:?}", self.get_rest_address());
 
         Ok(())
     }
@@ -0,0 +1,74 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{BaseConfig, ConfigModule, StarcoinOpt};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+pub static DEFAULT_SNAPSHOT_DIR: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("snapshots"));
+pub const DEFAULT_SNAPSHOT_RETAIN: u64 = 3;
+
+#[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, StructOpt)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotConfig {
+    /// produce a state snapshot at the first block of every `epoch_interval` epochs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "snapshot-epoch-interval",
+        long,
+        help = "produce a state snapshot at the first block of every N epochs, default 0 means disabled."
+    )]
+    epoch_interval: Option<u64>,
+
+    /// how many latest snapshots to retain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "snapshot-retain",
+        long,
+        help = "how many latest state snapshots to retain, default 3."
+    )]
+    retain: Option<u64>,
+
+    #[serde(skip)]
+    #[structopt(skip)]
+    base: Option<Arc<BaseConfig>>,
+}
+
+impl SnapshotConfig {
+    fn base(&self) -> &BaseConfig {
+        self.base.as_ref().expect("Config should init.")
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.base().data_dir().join(DEFAULT_SNAPSHOT_DIR.as_path())
+    }
+
+    pub fn epoch_interval(&self) -> u64 {
+        self.epoch_interval.unwrap_or(0)
+    }
+
+    pub fn retain(&self) -> u64 {
+        self.retain.unwrap_or(DEFAULT_SNAPSHOT_RETAIN).max(1)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.epoch_interval() > 0
+    }
+}
+
+impl ConfigModule for SnapshotConfig {
+    fn merge_with_opt(&mut self, opt: &StarcoinOpt, base: Arc<BaseConfig>) -> Result<()> {
+        self.base = Some(base);
+        if opt.snapshot.epoch_interval.is_some() {
+            self.epoch_interval = opt.snapshot.epoch_interval;
+        }
+        if opt.snapshot.retain.is_some() {
+            self.retain = opt.snapshot.retain;
+        }
+        Ok(())
+    }
+}
@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{ensure, format_err, Result};
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use structopt::StructOpt;
 
 /// Port selected RocksDB options for tuning underlying rocksdb instance of DiemDB.
@@ -23,6 +29,12 @@ pub struct RocksdbConfig {
         help = "rocksdb max total WAL sizes"
     )]
     pub max_total_wal_size: u64,
+    #[structopt(
+        name = "rocksdb-rate-limit",
+        long,
+        help = "rocksdb flush and compaction write rate limit in bytes per second, 0 means unlimited"
+    )]
+    pub rate_limit_bytes_per_sec: u64,
 }
 
 impl RocksdbConfig {
@@ -51,12 +63,202 @@ impl Default for RocksdbConfig {
             // For now we set the max total WAL size to be 1G. This config can be useful when column
             // families are updated at non-uniform frequencies.
             max_total_wal_size: 1u64 << 30,
+            rate_limit_bytes_per_sec: 0,
         }
     }
 }
 
 static DEFAULT_DB_DIR: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("starcoindb/db"));
+/// The mmap cache file is out of the db dir, so the db backup and the db tools do not touch it.
+static DEFAULT_STATE_MMAP_CACHE_FILE: Lazy<PathBuf> =
+    Lazy::new(|| PathBuf::from("cache/state_cache.mmap"));
 pub const DEFAULT_CACHE_SIZE: usize = 20000;
+pub const DEFAULT_STATE_CACHE_SIZE: usize = 100_000;
+pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 10000;
+const MIN_CACHE_SIZE: usize = 1000;
+/// Keep all the history by default.
+pub const DEFAULT_PRUNE_RETAIN_BLOCKS: u64 = 0;
+pub const DEFAULT_PRUNE_BATCH_BLOCKS: u64 = 100;
+pub const DEFAULT_COMPACTION_INTERVAL_HOURS: u64 = 24;
+pub const DEFAULT_COLD_AFTER_EPOCHS: u64 = 10;
+
+/// A range of UTC hours `start-end`, the end is exclusive, and the range may wrap around midnight,
+/// such as `22-4`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CompactionWindow {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl CompactionWindow {
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start <= self.end {
+            hour >= self.start && hour < self.end
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+impl FromStr for CompactionWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '-');
+        let start: u8 = parts.next().unwrap_or_default().trim().parse()?;
+        let end: u8 = parts
+            .next()
+            .ok_or_else(|| format_err!("Invalid compaction hours: {}, expect start-end", s))?
+            .trim()
+            .parse()?;
+        ensure!(
+            start < 24 && end <= 24 && start != end,
+            "Invalid compaction hours: {}, the hours should be in 0..24",
+            s
+        );
+        Ok(Self { start, end })
+    }
+}
+
+impl Serialize for CompactionWindow {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CompactionWindow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <String>::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(D::Error::custom)
+    }
+}
+
+impl fmt::Display for CompactionWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+/// The size of the db encryption key, the db values are encrypted with AES-256-GCM.
+pub const DB_ENCRYPTION_KEY_SIZE: usize = 32;
+
+/// Where the hex encoded db encryption key is loaded from:
+/// `file:<path>` reads the key from a file, the relative path is in the data dir,
+/// `env:<name>` reads the key from an environment variable,
+/// `cmd:<command>` runs a shell command which prints the key, such as a KMS decrypt command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncryptionKeySource {
+    File(PathBuf),
+    Env(String),
+    Command(String),
+}
+
+impl EncryptionKeySource {
+    /// Load the key, a relative key file is in the `data_dir`.
+    pub fn load(&self, data_dir: &Path) -> Result<[u8; DB_ENCRYPTION_KEY_SIZE]> {
+        let hex_key = match self {
+            Self::File(path) => fs::read_to_string(data_dir.join(path)).map_err(|e| {
+                format_err!("Read the db encryption key file {:?} failed: {}", path, e)
+            })?,
+            Self::Env(name) => std::env::var(name).map_err(|e| {
+                format_err!("Read the db encryption key env {} failed: {}", name, e)
+            })?,
+            Self::Command(command) => {
+                let output = Command::new("sh").arg("-c").arg(command).output()?;
+                ensure!(
+                    output.status.success(),
+                    "The db encryption key command failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                String::from_utf8(output.stdout)?
+            }
+        };
+        let key = hex::decode(hex_key.trim().trim_start_matches("0x"))
+            .map_err(|e| format_err!("Invalid hex db encryption key from {}: {}", self, e))?;
+        ensure!(
+            key.len() == DB_ENCRYPTION_KEY_SIZE,
+            "The db encryption key from {} should be {} bytes, but got {} bytes",
+            self,
+            DB_ENCRYPTION_KEY_SIZE,
+            key.len()
+        );
+        let mut result = [0u8; DB_ENCRYPTION_KEY_SIZE];
+        result.copy_from_slice(key.as_slice());
+        Ok(result)
+    }
+}
+
+impl FromStr for EncryptionKeySource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap_or_default();
+        let value = parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                format_err!(
+                    "Invalid db encryption key source: {}, expect file:<path>, env:<name> or cmd:<command>",
+                    s
+                )
+            })?;
+        match kind {
+            "file" => Ok(Self::File(PathBuf::from(value))),
+            "env" => Ok(Self::Env(value.to_string())),
+            "cmd" => Ok(Self::Command(value.to_string())),
+            _ => Err(format_err!(
+                "Unknown db encryption key source: {}, expect file, env or cmd",
+                kind
+            )),
+        }
+    }
+}
+
+impl Serialize for EncryptionKeySource {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptionKeySource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <String>::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(D::Error::custom)
+    }
+}
+
+impl fmt::Display for EncryptionKeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Env(name) => write!(f, "env:{}", name),
+            Self::Command(command) => write!(f, "cmd:{}", command),
+        }
+    }
+}
+
+const CONSTRAINED_MAX_OPEN_FILES: i32 = 256;
+/// If the total memory(or the container memory limit) is less than this size,
+/// the node is treated as running in a constrained environment, and use smaller default caches.
+const CONSTRAINED_MEM_SIZE: u64 = 4 * 1024 * 1024 * 1024; // 4G
+
+fn constrained_mem_size() -> Option<u64> {
+    starcoin_system::get_total_mem_size()
+        .ok()
+        .filter(|total| *total > 0 && *total < CONSTRAINED_MEM_SIZE)
+}
 
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, StructOpt)]
 #[serde(deny_unknown_fields)]
@@ -77,6 +279,111 @@ pub struct StorageConfig {
     #[structopt(name = "cache-sizes", long, help = "cache sizes")]
     pub cache_size: Option<usize>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "state-cache-size",
+        long,
+        help = "the number of the state nodes in the state cache, 0 means sharing the default cache"
+    )]
+    pub state_cache_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "block-cache-size",
+        long,
+        help = "the number of the blocks, headers, bodies and block infos in the block cache, 0 means sharing the default cache"
+    )]
+    pub block_cache_size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "state-mmap-cache-size",
+        long,
+        help = "the size in MB of the memory mapped second tier state cache, 0 means disabled"
+    )]
+    pub state_mmap_cache_size: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "state-mmap-cache-file",
+        long,
+        help = "the file of the memory mapped state cache, the relative path is in the data dir, default is cache/state_cache.mmap"
+    )]
+    pub state_mmap_cache_file: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "prune-retain-blocks",
+        long,
+        help = "prune the states, events and transaction infos older than the latest n blocks, 0 means never prune"
+    )]
+    pub prune_retain_blocks: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "archive",
+        long,
+        help = "run in archive mode, which keeps every historical state and indexes the account states by block number, can not be used with pruning"
+    )]
+    pub archive: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "prune-batch-blocks",
+        long,
+        help = "the number of blocks pruned in one pass"
+    )]
+    pub prune_batch_blocks: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "rocksdb-rate-limit",
+        long,
+        help = "rocksdb flush and compaction write rate limit in bytes per second, 0 means unlimited"
+    )]
+    pub rate_limit_bytes_per_sec: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "compaction-hours",
+        long,
+        help = "the off-peak UTC hours to run the scheduled db compaction, such as 2-5, the scheduled compaction is disabled if not set"
+    )]
+    pub compaction_hours: Option<CompactionWindow>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "compaction-interval",
+        long,
+        help = "the min interval hours between two scheduled db compactions"
+    )]
+    pub compaction_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "cold-db-dir",
+        long,
+        parse(from_os_str),
+        help = "the secondary storage dir, the blocks, transactions and events older than cold-after-epochs epochs are moved to, the relative dir is in the data dir"
+    )]
+    pub cold_db_dir: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "cold-after-epochs",
+        long,
+        help = "the number of the latest epochs whose blocks are kept in the db dir, the older blocks are moved to the cold-db-dir"
+    )]
+    pub cold_after_epochs: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "db-encryption-key",
+        long,
+        help = "encrypt the db values with the hex key from file:<path>, env:<name> or cmd:<command>, the encryption can only be enabled when the db is created"
+    )]
+    pub db_encryption_key: Option<EncryptionKeySource>,
+
     #[serde(skip)]
     #[structopt(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -93,15 +400,126 @@ impl StorageConfig {
 
     pub fn rocksdb_config(&self) -> RocksdbConfig {
         let default = RocksdbConfig::default();
+        let default_max_open_files = if constrained_mem_size().is_some() {
+            default.max_open_files.min(CONSTRAINED_MAX_OPEN_FILES)
+        } else {
+            default.max_open_files
+        };
         RocksdbConfig {
-            max_open_files: self.max_open_files.unwrap_or(default.max_open_files),
+            max_open_files: self.max_open_files.unwrap_or(default_max_open_files),
             max_total_wal_size: self
                 .max_total_wal_size
                 .unwrap_or(default.max_total_wal_size),
+            rate_limit_bytes_per_sec: self
+                .rate_limit_bytes_per_sec
+                .unwrap_or(default.rate_limit_bytes_per_sec),
         }
     }
+    /// The default cache sizes are scaled down by the memory size in constrained environment.
+    fn scaled_cache_size(default: usize) -> usize {
+        match constrained_mem_size() {
+            Some(total) => {
+                ((default as u64 * total / CONSTRAINED_MEM_SIZE) as usize).max(MIN_CACHE_SIZE)
+            }
+            None => default,
+        }
+    }
+
     pub fn cache_size(&self) -> usize {
-        self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE)
+        self.cache_size
+            .unwrap_or_else(|| Self::scaled_cache_size(DEFAULT_CACHE_SIZE))
+    }
+
+    /// The size of the dedicated state node cache, 0 means the state nodes share the default cache.
+    pub fn state_cache_size(&self) -> usize {
+        self.state_cache_size
+            .unwrap_or_else(|| Self::scaled_cache_size(DEFAULT_STATE_CACHE_SIZE))
+    }
+
+    /// The size of the dedicated block cache, 0 means the blocks share the default cache.
+    pub fn block_cache_size(&self) -> usize {
+        self.block_cache_size
+            .unwrap_or_else(|| Self::scaled_cache_size(DEFAULT_BLOCK_CACHE_SIZE))
+    }
+
+    /// The size in bytes of the second tier state cache, None means disabled.
+    pub fn state_mmap_cache_size(&self) -> Option<u64> {
+        self.state_mmap_cache_size
+            .filter(|size| *size > 0)
+            .map(|size| size * 1024 * 1024)
+    }
+
+    /// The file of the second tier state cache, it is recreated on every start.
+    pub fn state_mmap_cache_file(&self) -> PathBuf {
+        let file = self
+            .state_mmap_cache_file
+            .as_deref()
+            .unwrap_or_else(|| DEFAULT_STATE_MMAP_CACHE_FILE.as_path());
+        if file.is_absolute() {
+            file.to_path_buf()
+        } else {
+            self.base().data_dir().join(file)
+        }
+    }
+
+    /// The number of latest blocks whose data is retained, None means never prune.
+    pub fn prune_retain_blocks(&self) -> Option<u64> {
+        Some(
+            self.prune_retain_blocks
+                .unwrap_or(DEFAULT_PRUNE_RETAIN_BLOCKS),
+        )
+        .filter(|retain| *retain > 0)
+    }
+
+    /// In archive mode every historical state is kept, and the account states are indexed by the
+    /// block number, see `starcoin_storage::archive`.
+    pub fn archive(&self) -> bool {
+        self.archive.unwrap_or(false)
+    }
+
+    pub fn prune_batch_blocks(&self) -> u64 {
+        self.prune_batch_blocks
+            .unwrap_or(DEFAULT_PRUNE_BATCH_BLOCKS)
+            .max(1)
+    }
+
+    /// The off-peak hours of the scheduled compaction, None means the scheduled compaction is disabled.
+    pub fn compaction_hours(&self) -> Option<CompactionWindow> {
+        self.compaction_hours
+    }
+
+    pub fn compaction_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.compaction_interval
+                .unwrap_or(DEFAULT_COMPACTION_INTERVAL_HOURS)
+                .max(1)
+                * 3600,
+        )
+    }
+
+    /// The dir of the cold db, None means the hot/cold tiering is disabled.
+    pub fn cold_db_dir(&self) -> Option<PathBuf> {
+        self.cold_db_dir.as_ref().map(|dir| {
+            if dir.is_absolute() {
+                dir.clone()
+            } else {
+                self.base().data_dir().join(dir)
+            }
+        })
+    }
+
+    pub fn cold_after_epochs(&self) -> u64 {
+        self.cold_after_epochs
+            .unwrap_or(DEFAULT_COLD_AFTER_EPOCHS)
+            .max(1)
+    }
+
+    /// Load the db encryption key, None means the db is not encrypted.
+    pub fn db_encryption_key(&self) -> Result<Option<[u8; DB_ENCRYPTION_KEY_SIZE]>> {
+        self.db_encryption_key
+            .as_ref()
+            .map(|source| source.load(self.base().data_dir()))
+            .transpose()
     }
 }
 
@@ -117,6 +535,59 @@ impl ConfigModule for StorageConfig {
         if opt.storage.cache_size.is_some() {
             self.cache_size = opt.storage.cache_size;
         }
+        if opt.storage.state_cache_size.is_some() {
+            self.state_cache_size = opt.storage.state_cache_size;
+        }
+        if opt.storage.block_cache_size.is_some() {
+            self.block_cache_size = opt.storage.block_cache_size;
+        }
+        if opt.storage.state_mmap_cache_size.is_some() {
+            self.state_mmap_cache_size = opt.storage.state_mmap_cache_size;
+        }
+        if opt.storage.state_mmap_cache_file.is_some() {
+            self.state_mmap_cache_file = opt.storage.state_mmap_cache_file.clone();
+        }
+        if opt.storage.prune_retain_blocks.is_some() {
+            self.prune_retain_blocks = opt.storage.prune_retain_blocks;
+        }
+        if opt.storage.archive.is_some() {
+            self.archive = opt.storage.archive;
+        }
+        ensure!(
+            !self.archive() || self.prune_retain_blocks().is_none(),
+            "The archive mode can not be used with prune-retain-blocks"
+        );
+        if opt.storage.prune_batch_blocks.is_some() {
+            self.prune_batch_blocks = opt.storage.prune_batch_blocks;
+        }
+        if opt.storage.rate_limit_bytes_per_sec.is_some() {
+            self.rate_limit_bytes_per_sec = opt.storage.rate_limit_bytes_per_sec;
+        }
+        if opt.storage.compaction_hours.is_some() {
+            self.compaction_hours = opt.storage.compaction_hours;
+        }
+        if opt.storage.compaction_interval.is_some() {
+            self.compaction_interval = opt.storage.compaction_interval;
+        }
+        if opt.storage.cold_db_dir.is_some() {
+            self.cold_db_dir = opt.storage.cold_db_dir.clone();
+        }
+        if opt.storage.cold_after_epochs.is_some() {
+            self.cold_after_epochs = opt.storage.cold_after_epochs;
+        }
+        // The epoch boundaries of the cold blocks are read from the states of the old blocks.
+        ensure!(
+            self.cold_db_dir.is_none() || self.prune_retain_blocks().is_none(),
+            "The cold-db-dir can not be used with prune-retain-blocks"
+        );
+        if opt.storage.db_encryption_key.is_some() {
+            self.db_encryption_key = opt.storage.db_encryption_key.clone();
+        }
+        // The state nodes in the mmap cache file are not encrypted.
+        ensure!(
+            self.db_encryption_key.is_none() || self.state_mmap_cache_size().is_none(),
+            "The state mmap cache can not be used with db-encryption-key"
+        );
         Ok(())
     }
 }
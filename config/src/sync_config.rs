@@ -28,6 +28,15 @@ pub struct SyncConfig {
         help = "max retry times once sync block failed, default 15."
     )]
     max_retry_times: Option<u64>,
+
+    /// the synced blocks are written to the db in one write batch every N blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(
+        name = "write-batch-blocks",
+        long,
+        help = "the synced blocks are written to the db in one write batch every N blocks, default 10."
+    )]
+    write_batch_blocks: Option<u64>,
 }
 
 impl SyncConfig {
@@ -38,6 +47,10 @@ impl SyncConfig {
     pub fn max_retry_times(&self) -> u64 {
         self.max_retry_times.unwrap_or(15)
     }
+
+    pub fn write_batch_blocks(&self) -> u64 {
+        self.write_batch_blocks.unwrap_or(10).max(1)
+    }
 }
 
 impl ConfigModule for SyncConfig {
@@ -50,6 +63,10 @@ impl ConfigModule for SyncConfig {
             self.max_retry_times = opt.sync.max_retry_times;
         }
 
+        if opt.sync.write_batch_blocks.is_some() {
+            self.write_batch_blocks = opt.sync.write_batch_blocks;
+        }
+
         Ok(())
     }
 }
@@ -4,6 +4,7 @@
 use super::*;
 use crate::helper::to_toml;
 use starcoin_vm_types::gas_schedule::GasAlgebra;
+use std::time::Duration;
 
 #[test]
 fn test_generate_and_load() -> Result<()> {
@@ -129,6 +130,8 @@ fn test_example_config_compact() -> Result<()> {
             //Vault
             "--vault-dir",
             "/data/my_starcoin_vault",
+            "--vault-public-mode",
+            "true",
             //Stratum
             "--stratum-port",
             "8090",
@@ -140,6 +143,11 @@ fn test_example_config_compact() -> Result<()> {
             "true",
             "--slog-separate-store",
             "true",
+            //Snapshot
+            "--snapshot-epoch-interval",
+            "10",
+            "--snapshot-retain",
+            "5",
         ];
         let opt = StarcoinOpt::from_iter_safe(args)?;
         let config = NodeConfig::load_with_opt(&opt)?;
@@ -183,3 +191,235 @@ fn test_genesis_config_security() {
         );
     }
 }
+
+#[test]
+fn test_config_fingerprint() {
+    let genesis_hash = HashValue::random();
+    let config = NodeConfig::random_for_test();
+    let fingerprint = config.fingerprint(genesis_hash);
+    assert_eq!(fingerprint.len(), 8);
+    assert_eq!(fingerprint, config.fingerprint(genesis_hash));
+    assert_ne!(fingerprint, config.fingerprint(HashValue::random()));
+
+    let mut archive_config = config.clone();
+    archive_config.storage.archive = Some(true);
+    assert_ne!(fingerprint, archive_config.fingerprint(genesis_hash));
+
+    let dev_config = NodeConfig::load_with_opt(&StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(temp_path().path().to_path_buf()),
+        ..StarcoinOpt::default()
+    })
+    .unwrap();
+    assert_ne!(fingerprint, dev_config.fingerprint(genesis_hash));
+}
+
+#[test]
+fn test_heavy_api_concurrency() {
+    let mut config = RpcConfig {
+        heavy_api_threads: Some(4),
+        ..RpcConfig::default()
+    };
+    let limits = config.heavy_api_concurrency();
+    assert_eq!(limits.get("contract.dry_run"), Some(&4));
+    assert_eq!(limits.get("chain.info"), None);
+
+    config.heavy_api_concurrency = Some(vec![
+        ("contract.dry_run".to_string(), 1),
+        ("chain.get_block_txn_infos".to_string(), 2),
+    ]);
+    let limits = config.heavy_api_concurrency();
+    assert_eq!(limits.get("contract.dry_run"), Some(&1));
+    assert_eq!(limits.get("chain.get_block_txn_infos"), Some(&2));
+    assert_eq!(limits.get("chain.get_events"), Some(&4));
+}
+
+#[test]
+fn test_slow_call_threshold() {
+    let mut config = RpcConfig::default();
+    assert_eq!(
+        config.slow_call_threshold(),
+        Some(Duration::from_millis(1000))
+    );
+    config.slow_call_threshold = Some(200);
+    assert_eq!(
+        config.slow_call_threshold(),
+        Some(Duration::from_millis(200))
+    );
+    config.slow_call_threshold = Some(0);
+    assert_eq!(config.slow_call_threshold(), None);
+}
+
+#[test]
+fn test_prune_config() {
+    let mut config = StorageConfig::default();
+    assert_eq!(config.prune_retain_blocks(), None);
+    assert_eq!(config.prune_batch_blocks(), 100);
+    config.prune_retain_blocks = Some(1000);
+    config.prune_batch_blocks = Some(0);
+    assert_eq!(config.prune_retain_blocks(), Some(1000));
+    assert_eq!(config.prune_batch_blocks(), 1);
+}
+
+#[test]
+fn test_archive_config() {
+    let opt = |archive: bool, prune_retain_blocks: u64| StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(temp_path().path().to_path_buf()),
+        storage: StorageConfig {
+            archive: Some(archive),
+            prune_retain_blocks: Some(prune_retain_blocks),
+            ..StorageConfig::default()
+        },
+        ..StarcoinOpt::default()
+    };
+    assert!(!StorageConfig::default().archive());
+    let config = NodeConfig::load_with_opt(&opt(true, 0)).unwrap();
+    assert!(config.storage.archive());
+    assert_eq!(config.storage.prune_retain_blocks(), None);
+    assert!(NodeConfig::load_with_opt(&opt(false, 1000)).is_ok());
+    assert!(NodeConfig::load_with_opt(&opt(true, 1000)).is_err());
+}
+
+#[test]
+fn test_cold_db_config() {
+    let config = StorageConfig::default();
+    assert_eq!(config.cold_db_dir(), None);
+    assert_eq!(config.cold_after_epochs(), 10);
+
+    let base_data_dir = temp_path();
+    let config = NodeConfig::load_with_opt(&StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(base_data_dir.path().to_path_buf()),
+        storage: StorageConfig {
+            cold_db_dir: Some(PathBuf::from("cold")),
+            cold_after_epochs: Some(0),
+            ..StorageConfig::default()
+        },
+        ..StarcoinOpt::default()
+    })
+    .unwrap();
+    assert_eq!(
+        config.storage.cold_db_dir(),
+        Some(config.data_dir().join("cold"))
+    );
+    assert_eq!(config.storage.cold_after_epochs(), 1);
+
+    // the states of the old blocks are pruned.
+    assert!(NodeConfig::load_with_opt(&StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(base_data_dir.path().to_path_buf()),
+        storage: StorageConfig {
+            cold_db_dir: Some(PathBuf::from("cold")),
+            prune_retain_blocks: Some(1000),
+            ..StorageConfig::default()
+        },
+        ..StarcoinOpt::default()
+    })
+    .is_err());
+}
+
+#[test]
+fn test_cache_config() {
+    let mut config = StorageConfig::default();
+    assert!(config.state_cache_size() >= config.cache_size());
+    assert_eq!(config.state_mmap_cache_size(), None);
+    config.state_cache_size = Some(0);
+    config.state_mmap_cache_size = Some(64);
+    assert_eq!(config.state_cache_size(), 0);
+    assert_eq!(config.state_mmap_cache_size(), Some(64 * 1024 * 1024));
+
+    let base_data_dir = temp_path();
+    let opt = |file: Option<PathBuf>| StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(base_data_dir.path().to_path_buf()),
+        storage: StorageConfig {
+            state_mmap_cache_size: Some(64),
+            state_mmap_cache_file: file,
+            ..StorageConfig::default()
+        },
+        ..StarcoinOpt::default()
+    };
+    let config = NodeConfig::load_with_opt(&opt(None)).unwrap();
+    let file = config.storage.state_mmap_cache_file();
+    assert_eq!(file, config.data_dir().join("cache/state_cache.mmap"));
+    // the cache file is out of the db dir.
+    assert!(!file.starts_with(config.data_dir().join("starcoindb")));
+
+    let config = NodeConfig::load_with_opt(&opt(Some(PathBuf::from("/tmp/state.mmap")))).unwrap();
+    assert_eq!(
+        config.storage.state_mmap_cache_file(),
+        PathBuf::from("/tmp/state.mmap")
+    );
+}
+
+#[test]
+fn test_db_encryption_key_config() {
+    let source: EncryptionKeySource = "file:db.key".parse().unwrap();
+    assert_eq!(source, EncryptionKeySource::File(PathBuf::from("db.key")));
+    assert_eq!(source.to_string(), "file:db.key");
+    let source: EncryptionKeySource = "cmd:cat /tmp/db.key".parse().unwrap();
+    assert_eq!(
+        source,
+        EncryptionKeySource::Command("cat /tmp/db.key".to_string())
+    );
+    assert!("env:".parse::<EncryptionKeySource>().is_err());
+    assert!("kms:key".parse::<EncryptionKeySource>().is_err());
+
+    let base_data_dir = temp_path();
+    let key = [7u8; 32];
+    std::fs::write(base_data_dir.path().join("db.key"), hex::encode(key)).unwrap();
+    let source: EncryptionKeySource = "file:db.key".parse().unwrap();
+    assert_eq!(source.load(base_data_dir.path()).unwrap(), key);
+    std::fs::write(base_data_dir.path().join("db.key"), "0x0102").unwrap();
+    assert!(source.load(base_data_dir.path()).is_err());
+
+    let config = StorageConfig {
+        db_encryption_key: Some(source),
+        ..StorageConfig::default()
+    };
+    let config: StorageConfig = toml::from_str(to_toml(&config).unwrap().as_str()).unwrap();
+    assert_eq!(
+        config.db_encryption_key,
+        Some(EncryptionKeySource::File(PathBuf::from("db.key")))
+    );
+
+    let opt = StarcoinOpt {
+        net: Some(BuiltinNetworkID::Dev.into()),
+        base_data_dir: Some(base_data_dir.path().to_path_buf()),
+        storage: StorageConfig {
+            state_mmap_cache_size: Some(64),
+            db_encryption_key: Some("env:STARCOIN_DB_KEY".parse().unwrap()),
+            ..StorageConfig::default()
+        },
+        ..StarcoinOpt::default()
+    };
+    assert!(NodeConfig::load_with_opt(&opt).is_err());
+}
+
+#[test]
+fn test_compaction_window() {
+    let window: CompactionWindow = "2-5".parse().unwrap();
+    assert!(window.contains(2));
+    assert!(window.contains(4));
+    assert!(!window.contains(5));
+    assert!(!window.contains(12));
+
+    let window: CompactionWindow = "22-4".parse().unwrap();
+    assert!(window.contains(23));
+    assert!(window.contains(0));
+    assert!(!window.contains(4));
+    assert_eq!(window.to_string(), "22-4");
+
+    assert!("5-5".parse::<CompactionWindow>().is_err());
+    assert!("2-25".parse::<CompactionWindow>().is_err());
+    assert!("2".parse::<CompactionWindow>().is_err());
+
+    let config = StorageConfig {
+        compaction_hours: Some(window),
+        ..StorageConfig::default()
+    };
+    let config: StorageConfig = toml::from_str(to_toml(&config).unwrap().as_str()).unwrap();
+    assert_eq!(config.compaction_hours(), Some(window));
+    assert_eq!(config.compaction_interval(), Duration::from_secs(24 * 3600));
+}
@@ -25,7 +25,7 @@ pub struct TxPoolConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[structopt(name = "txpool-max-mem-usage", long)]
-    /// Maximal memory usage. Default to half of current free mem of system.
+    /// Maximal memory usage. Default to half of current free mem of system, or of the container memory limit.
     max_mem_usage: Option<u64>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -37,6 +37,13 @@ pub struct TxPoolConfig {
     #[structopt(name = "txpool-min-gas-price", long)]
     /// reject transaction whose gas_price is less than the min_gas_price. default to 1.
     min_gas_price: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[structopt(name = "txpool-gas-price-band", long)]
+    /// width of the gas price band when packing transactions into block.
+    /// Transactions in a higher band are packed first, and transactions in the same band are packed
+    /// by per-sender round-robin. Set to 0 to pack by gas price only. default to 1.
+    gas_price_band: Option<u64>,
 }
 
 impl TxPoolConfig {
@@ -69,6 +76,9 @@ impl TxPoolConfig {
     pub fn min_gas_price(&self) -> u64 {
         self.min_gas_price.unwrap_or(1)
     }
+    pub fn gas_price_band(&self) -> u64 {
+        self.gas_price_band.unwrap_or(1)
+    }
 }
 
 impl ConfigModule for TxPoolConfig {
@@ -89,6 +99,9 @@ impl ConfigModule for TxPoolConfig {
         if let Some(m) = txpool_opt.min_gas_price.as_ref() {
             self.min_gas_price = Some(*m);
         }
+        if let Some(m) = txpool_opt.gas_price_band.as_ref() {
+            self.gas_price_band = Some(*m);
+        }
         Ok(())
     }
 }
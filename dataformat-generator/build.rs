@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde_reflection::{Error, Samples, Tracer, TracerConfig};
+use starcoin_crypto::bls12381::Bls12381PrivateKey;
 use starcoin_crypto::ed25519::Ed25519PrivateKey;
 use starcoin_crypto::multi_ed25519::MultiEd25519PrivateKey;
 use starcoin_crypto::{
@@ -62,6 +63,11 @@ fn generate() -> Result<(), Error> {
         tracer.trace_value(&mut samples, &pri_key.public_key())?;
         tracer.trace_value(&mut samples, &pri_key.sign(&DummyObj::default()))?;
     }
+    {
+        let pri_key = Bls12381PrivateKey::generate_for_testing();
+        tracer.trace_value(&mut samples, &pri_key.public_key())?;
+        tracer.trace_value(&mut samples, &pri_key.sign(&DummyObj::default()))?;
+    }
 
     tracer.trace_type::<BlockMetadata>(&samples)?;
 
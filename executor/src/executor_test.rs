@@ -6,19 +6,23 @@ use crate::{encode_create_account_script_function, Account};
 use anyhow::anyhow;
 use anyhow::Result;
 use logger::prelude::*;
+use starcoin_crypto::bls12381::Bls12381PrivateKey;
 use starcoin_resource_viewer::MoveValueAnnotator;
 use starcoin_transaction_builder::{DEFAULT_EXPIRATION_TIME, DEFAULT_MAX_GAS_AMOUNT};
 use starcoin_types::identifier::Identifier;
 use starcoin_types::language_storage::ModuleId;
-use starcoin_types::transaction::{RawUserTransaction, ScriptFunction};
+use starcoin_types::transaction::{
+    DryRunTransaction, GasBreakdown, RawUserTransaction, ScriptFunction, SignedUserTransaction,
+};
 use starcoin_types::{
     account_config, block_metadata::BlockMetadata, transaction::Transaction,
     transaction::TransactionPayload, transaction::TransactionStatus,
 };
 use starcoin_vm_types::access_path::AccessPath;
 use starcoin_vm_types::account_config::genesis_address;
+use starcoin_vm_types::gas_schedule::{calculate_intrinsic_gas, AbstractMemorySize, GasAlgebra};
 use starcoin_vm_types::genesis_config::ChainId;
-use starcoin_vm_types::on_chain_config::{ConsensusConfig, OnChainConfig};
+use starcoin_vm_types::on_chain_config::{ConsensusConfig, OnChainConfig, VMConfig};
 use starcoin_vm_types::state_view::StateView;
 use starcoin_vm_types::token::stc::stc_type_tag;
 use starcoin_vm_types::value::{serialize_values, MoveValue};
@@ -626,6 +630,48 @@ fn test_validate_txn_chain_id() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_bls12381_txn_before_enabled() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1, 0, 50_000_000, 1, &net,
+    ));
+    let output1 = execute_and_apply(&chain_state, txn1);
+    assert_eq!(KeptVMStatus::Executed, output1.status().status().unwrap());
+
+    let account2 = Account::new();
+    let raw_txn = crate::build_transfer_txn(
+        *account1.address(),
+        *account2.address(),
+        Some(account2.auth_key()),
+        0,
+        1000,
+        1,
+        DEFAULT_MAX_GAS_AMOUNT,
+        net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        net.chain_id(),
+    );
+    let private_key = Bls12381PrivateKey::generate_for_testing();
+    let signature = private_key.sign(&raw_txn);
+    let txn = SignedUserTransaction::bls12381(raw_txn, private_key.public_key(), signature);
+    // The signature is valid, but the scheme is not enabled by the on-chain version yet.
+    assert!(txn.clone().check_signature().is_ok());
+
+    let output = crate::validate_transaction(&chain_state, txn.clone());
+    assert_eq!(
+        output.map(|status| status.status_code()),
+        Some(StatusCode::INVALID_SIGNATURE)
+    );
+    let output = execute_and_apply(&chain_state, Transaction::UserTransaction(txn));
+    assert_eq!(
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE),
+        *output.status()
+    );
+    Ok(())
+}
+
 #[stest::test]
 fn test_gas_charge_for_invalid_script_argument_txn() -> Result<()> {
     let (chain_state, net) = prepare_genesis();
@@ -760,6 +806,72 @@ fn test_execute_transfer_txn_with_starcoin_vm() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_dry_run_gas_breakdown() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1, 0, 50_000_000, 1, &net,
+    ));
+    let output1 = execute_and_apply(&chain_state, txn1);
+    assert_eq!(KeptVMStatus::Executed, output1.status().status().unwrap());
+
+    let account2 = Account::new();
+    let raw_txn = crate::build_transfer_txn(
+        *account1.address(),
+        *account2.address(),
+        Some(account2.auth_key()),
+        0,
+        1000,
+        1,
+        DEFAULT_MAX_GAS_AMOUNT,
+        net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        net.chain_id(),
+    );
+    let gas_schedule = VMConfig::fetch_config(&chain_state)?
+        .ok_or_else(|| anyhow!("vm config should exist"))?
+        .gas_schedule;
+    let intrinsic_gas = gas_schedule
+        .gas_constants
+        .to_external_units(calculate_intrinsic_gas(
+            AbstractMemorySize::new(raw_txn.txn_size() as u64),
+            &gas_schedule.gas_constants,
+        ))
+        .get();
+    let (_status, output, gas_breakdown) = StarcoinVM::new().dry_run_transaction(
+        &chain_state,
+        DryRunTransaction {
+            raw_txn: raw_txn.clone(),
+            public_key: account1.public_key(),
+        },
+    )?;
+    assert_eq!(KeptVMStatus::Executed, output.status().status().unwrap());
+    assert_eq!(gas_breakdown.intrinsic_gas, intrinsic_gas);
+    assert!(gas_breakdown.execution_gas > 0);
+    // the transfer creates the account of the receiver.
+    assert!(gas_breakdown.write_set_gas > 0);
+    assert_eq!(gas_breakdown.events_gas.len(), output.events().len());
+    assert!(gas_breakdown.events_gas.iter().all(|gas| *gas > 0));
+
+    // the dry run charges the same gas as the execution of the signed txn.
+    let signed_txn = account1.sign_txn(raw_txn.clone());
+    let executed_output = execute_and_apply(&chain_state, Transaction::UserTransaction(signed_txn));
+    assert_eq!(output.gas_used(), executed_output.gas_used());
+
+    // the discarded txn has no gas breakdown, the sequence number has been used.
+    let (_status, output, gas_breakdown) = StarcoinVM::new().dry_run_transaction(
+        &chain_state,
+        DryRunTransaction {
+            raw_txn,
+            public_key: account1.public_key(),
+        },
+    )?;
+    assert!(output.status().is_discarded());
+    assert_eq!(gas_breakdown, GasBreakdown::default());
+    Ok(())
+}
+
 #[stest::test]
 fn test_execute_multi_txn_with_same_account() -> Result<()> {
     let (chain_state, net) = prepare_genesis();
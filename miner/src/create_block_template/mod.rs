@@ -7,7 +7,7 @@ use consensus::Consensus;
 use crypto::hash::HashValue;
 use futures::executor::block_on;
 use logger::prelude::*;
-use starcoin_account_api::{AccountAsyncService, AccountInfo};
+use starcoin_account_api::AccountAsyncService;
 use starcoin_account_service::AccountService;
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
@@ -20,10 +20,12 @@ use starcoin_service_registry::{
 use starcoin_storage::{BlockStore, Storage, Store};
 use starcoin_txpool::TxPoolService;
 use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_vm_types::transaction::authenticator::AuthenticationKey;
 use starcoin_vm_types::transaction::SignedUserTransaction;
 use std::cmp::min;
 use std::{collections::HashMap, sync::Arc};
 use types::{
+    account_address::AccountAddress,
     block::{BlockHeader, BlockTemplate, ExecutedBlock},
     system_events::{NewBranch, NewHeadBlock},
 };
@@ -60,12 +62,23 @@ impl ServiceFactory<Self> for CreateBlockTemplateService {
         let startup_info = storage
             .get_startup_info()?
             .expect("Startup info should exist when service start.");
-        //TODO support get service ref by AsyncAPI;
-        let account_service = ctx.service_ref::<AccountService>()?;
-        let miner_account = block_on(async { account_service.get_default_account().await })?
-            .ok_or_else(|| {
-                format_err!("Default account should exist when CreateBlockTemplateService start.")
-            })?;
+        let (author, author_auth_key) = match config.miner.author {
+            // The configured author only has an address, so its account must exist on chain.
+            Some(author) => (author, None),
+            None => {
+                //TODO support get service ref by AsyncAPI;
+                let account_service = ctx.service_ref::<AccountService>()?;
+                let miner_account =
+                    block_on(async { account_service.get_default_account().await })?
+                        .ok_or_else(|| {
+                            format_err!("Default account should exist when CreateBlockTemplateService start, set the miner author in vault public mode.")
+                        })?;
+                (
+                    miner_account.address,
+                    Some(miner_account.public_key.authentication_key()),
+                )
+            }
+        };
         let txpool = ctx.get_shared::<TxPoolService>()?;
         let inner = Inner::new(
             config.net(),
@@ -73,7 +86,8 @@ impl ServiceFactory<Self> for CreateBlockTemplateService {
             startup_info.main,
             txpool,
             config.miner.block_gas_limit,
-            miner_account,
+            author,
+            author_auth_key,
         )?;
         Ok(Self { inner })
     }
@@ -169,7 +183,9 @@ pub struct Inner<P> {
     parent_uncle: HashMap<HashValue, Vec<HashValue>>,
     uncles: HashMap<HashValue, BlockHeader>,
     local_block_gas_limit: Option<u64>,
-    miner_account: AccountInfo,
+    author: AccountAddress,
+    /// Used to create the author account on chain if it does not exist.
+    author_auth_key: Option<AuthenticationKey>,
 }
 
 impl<P> Inner<P>
@@ -182,7 +198,8 @@ where
         block_id: HashValue,
         tx_provider: P,
         local_block_gas_limit: Option<u64>,
-        miner_account: AccountInfo,
+        author: AccountAddress,
+        author_auth_key: Option<AuthenticationKey>,
     ) -> Result<Self> {
         let chain = BlockChain::new(net.time_service(), block_id, storage.clone())?;
 
@@ -193,7 +210,8 @@ where
             parent_uncle: HashMap::new(),
             uncles: HashMap::new(),
             local_block_gas_limit,
-            miner_account,
+            author,
+            author_auth_key,
         })
     }
 
@@ -267,11 +285,16 @@ where
         let txns = self.tx_provider.get_txns(max_txns);
 
         let chain_state = self.chain.chain_state_reader();
-        let author = *self.miner_account.address();
-        let author_auth_key = if chain_state.exist_account(self.miner_account.address())? {
+        let author = self.author;
+        let author_auth_key = if chain_state.exist_account(&author)? {
             None
         } else {
-            Some(self.miner_account.public_key.authentication_key())
+            Some(self.author_auth_key.ok_or_else(|| {
+                format_err!(
+                    "Miner author {} does not exist on chain and has no authentication key.",
+                    author
+                )
+            })?)
         };
 
         let previous_header = self.chain.current_header();
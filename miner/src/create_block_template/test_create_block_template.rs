@@ -46,7 +46,8 @@ fn test_create_block_template_by_net(net: ChainNetworkID) {
         genesis_id,
         EmptyProvider,
         None,
-        miner_account,
+        miner_account.address,
+        Some(miner_account.public_key.authentication_key()),
     )
     .unwrap();
 
@@ -85,7 +86,8 @@ fn test_switch_main() {
             head_id,
             txpool.clone(),
             None,
-            miner_account.clone(),
+            miner_account.address,
+            Some(miner_account.public_key.authentication_key()),
         )
         .unwrap();
 
@@ -121,7 +123,8 @@ fn test_switch_main() {
                 head_id,
                 txpool.clone(),
                 None,
-                miner_account.clone(),
+                miner_account.address,
+                Some(miner_account.public_key.authentication_key()),
             )
             .unwrap();
 
@@ -196,7 +199,8 @@ fn test_do_uncles() {
             head_id,
             txpool.clone(),
             None,
-            miner_account.clone(),
+            miner_account.address,
+            Some(miner_account.public_key.authentication_key()),
         )
         .unwrap();
 
@@ -221,7 +225,8 @@ fn test_do_uncles() {
             genesis_id,
             txpool.clone(),
             None,
-            miner_account.clone(),
+            miner_account.address,
+            Some(miner_account.public_key.authentication_key()),
         )
         .unwrap();
 
@@ -289,7 +294,8 @@ fn test_new_head() {
         genesis_id,
         txpool,
         None,
-        miner_account,
+        miner_account.address,
+        Some(miner_account.public_key.authentication_key()),
     )
     .unwrap();
 
@@ -332,7 +338,8 @@ fn test_new_branch() {
         genesis_id,
         txpool.clone(),
         None,
-        miner_account.clone(),
+        miner_account.address,
+        Some(miner_account.public_key.authentication_key()),
     )
     .unwrap();
     for _i in 0..times {
@@ -356,7 +363,8 @@ fn test_new_branch() {
             new_head_id,
             txpool.clone(),
             None,
-            miner_account.clone(),
+            miner_account.address,
+            Some(miner_account.public_key.authentication_key()),
         )
         .unwrap();
         let block_template = inner.create_block_template().unwrap();
@@ -5,6 +5,7 @@ use crate::metrics::MINER_METRICS;
 use crate::task::MintTask;
 use anyhow::Result;
 use consensus::Consensus;
+use crypto::HashValue;
 use futures::executor::block_on;
 use logger::prelude::*;
 use starcoin_config::NodeConfig;
@@ -12,6 +13,8 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler, ServiceRef,
     ServiceRequest,
 };
+use starcoin_txpool_api::PropagateTransactions;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -36,9 +39,17 @@ impl ServiceRequest for MinerClientSubscribeRequest {
     type Response = Result<Option<MintBlockEvent>>;
 }
 
+/// Update the current mint task with the new txns in pool.
+#[derive(Clone, Debug)]
+struct UpdateMintTaskEvent;
+
 pub struct MinerService {
     config: Arc<NodeConfig>,
     current_task: Option<MintTask>,
+    /// The tasks replaced by the updates of the current task, they have the same parent block
+    /// as the current task, so the seals of them are still accepted.
+    stale_tasks: Vec<MintTask>,
+    update_task_scheduled: bool,
     create_block_template_service: ServiceRef<CreateBlockTemplateService>,
     client_subscribers_num: u32,
 }
@@ -52,11 +63,13 @@ impl ServiceHandler<Self, MinerClientSubscribeRequest> for MinerService {
         match msg {
             MinerClientSubscribeRequest::Add(num) => {
                 self.client_subscribers_num = num;
-                Ok(self.current_task.as_ref().map(|task| MintBlockEvent {
-                    strategy: task.block_template.strategy,
-                    minting_blob: task.minting_blob.clone(),
-                    difficulty: task.block_template.difficulty,
-                    block_number: task.block_template.number,
+                Ok(self.current_task.as_ref().map(|task| {
+                    MintBlockEvent::new(
+                        task.block_template.strategy,
+                        task.minting_blob.clone(),
+                        task.block_template.difficulty,
+                        task.block_template.number,
+                    )
                 }))
             }
             MinerClientSubscribeRequest::Remove(num) => {
@@ -75,6 +88,8 @@ impl ServiceFactory<MinerService> for MinerService {
         Ok(MinerService {
             config,
             current_task: None,
+            stale_tasks: vec![],
+            update_task_scheduled: false,
             create_block_template_service,
             client_subscribers_num: 0,
         })
@@ -85,12 +100,18 @@ impl ActorService for MinerService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.subscribe::<GenerateBlockEvent>();
         ctx.subscribe::<SubmitSealEvent>();
+        if self.config.miner.template_update_interval().is_some() {
+            ctx.subscribe::<PropagateTransactions>();
+        }
         Ok(())
     }
 
     fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.unsubscribe::<GenerateBlockEvent>();
         ctx.unsubscribe::<SubmitSealEvent>();
+        if self.config.miner.template_update_interval().is_some() {
+            ctx.unsubscribe::<PropagateTransactions>();
+        }
         Ok(())
     }
 }
@@ -131,6 +152,7 @@ impl MinerService {
                 );
             }
             self.current_task = Some(task);
+            self.stale_tasks.clear();
             ctx.broadcast(MintBlockEvent::new(
                 strategy,
                 mining_blob,
@@ -141,6 +163,59 @@ impl MinerService {
         }
     }
 
+    /// Update the current task with the new txns in pool, if the template still has the same
+    /// parent block. The update is broadcast with the added txns.
+    pub fn update_task(&mut self, ctx: &mut ServiceContext<MinerService>) -> Result<()> {
+        let (parent_hash, txns) = match self.current_task.as_ref() {
+            Some(task) => (
+                task.block_template.parent_hash,
+                task.block_template
+                    .body
+                    .transactions
+                    .iter()
+                    .map(|txn| txn.id())
+                    .collect::<HashSet<_>>(),
+            ),
+            None => return Ok(()),
+        };
+        let block_template = block_on(async {
+            self.create_block_template_service
+                .send(CreateBlockTemplateRequest)
+                .await?
+        })?;
+        // the template on a new parent block is dispatched by the GenerateBlockEvent.
+        if block_template.parent_hash != parent_hash {
+            return Ok(());
+        }
+        let added_txns: Vec<HashValue> = block_template
+            .body
+            .transactions
+            .iter()
+            .map(|txn| txn.id())
+            .filter(|id| !txns.contains(id))
+            .collect();
+        if added_txns.is_empty() {
+            return Ok(());
+        }
+        debug!(
+            "Update mint block template with {} new txns: {:?}",
+            added_txns.len(),
+            block_template
+        );
+        let event = MintBlockEvent::new(
+            block_template.strategy,
+            block_template.as_pow_header_blob(),
+            block_template.difficulty,
+            block_template.number,
+        )
+        .with_added_txns(added_txns);
+        if let Some(stale_task) = self.current_task.replace(MintTask::new(block_template)) {
+            self.stale_tasks.push(stale_task);
+        }
+        ctx.broadcast(event);
+        Ok(())
+    }
+
     pub fn finish_task(
         &mut self,
         nonce: u32,
@@ -148,15 +223,21 @@ impl MinerService {
         minting_blob: Vec<u8>,
         ctx: &mut ServiceContext<MinerService>,
     ) -> Result<()> {
-        match self.current_task.as_ref() {
-            None => {
+        // the seal of a task replaced by the update of the current task is still accepted.
+        let stale_task_idx = self
+            .stale_tasks
+            .iter()
+            .position(|task| task.minting_blob == minting_blob);
+        let task = match (stale_task_idx, self.current_task.as_ref()) {
+            (Some(idx), _) => &self.stale_tasks[idx],
+            (None, None) => {
                 debug!(
                     "MintTask is none, but got nonce: {}, extra:{:?} for minting_blob: {:?}, may be mint by other client.",
                     nonce, extra, minting_blob,
                 );
                 return Ok(());
             }
-            Some(task) => {
+            (None, Some(task)) => {
                 if task.minting_blob != minting_blob {
                     info!(
                         "[miner] Jobs hash mismatch expect: {}, got: {}, probably received old job result.",
@@ -165,24 +246,31 @@ impl MinerService {
                     );
                     return Ok(());
                 }
-                if let Err(e) = task.block_template.strategy.verify_blob(
-                    task.minting_blob.clone(),
-                    nonce,
-                    extra,
-                    task.block_template.difficulty,
-                ) {
-                    warn!(
-                        "Failed to verify blob: {}, nonce: {}, err: {}",
-                        hex::encode(task.minting_blob.as_slice()),
-                        nonce,
-                        e
-                    );
-                    return Ok(());
-                }
+                task
             }
+        };
+        if let Err(e) = task.block_template.strategy.verify_blob(
+            task.minting_blob.clone(),
+            nonce,
+            extra,
+            task.block_template.difficulty,
+        ) {
+            warn!(
+                "Failed to verify blob: {}, nonce: {}, err: {}",
+                hex::encode(task.minting_blob.as_slice()),
+                nonce,
+                e
+            );
+            return Ok(());
         }
 
-        if let Some(task) = self.current_task.take() {
+        let task = match stale_task_idx {
+            Some(idx) => Some(self.stale_tasks.swap_remove(idx)),
+            None => self.current_task.take(),
+        };
+        if let Some(task) = task {
+            self.current_task = None;
+            self.stale_tasks.clear();
             let block = task.finish(nonce, extra);
             info!("Mint new block: {}", block);
             ctx.broadcast(MinedBlock(Arc::new(block)));
@@ -218,3 +306,30 @@ impl EventHandler<Self, GenerateBlockEvent> for MinerService {
         }
     }
 }
+
+impl EventHandler<Self, PropagateTransactions> for MinerService {
+    fn handle_event(
+        &mut self,
+        _msg: PropagateTransactions,
+        ctx: &mut ServiceContext<MinerService>,
+    ) {
+        if !self.is_minting() || self.update_task_scheduled {
+            return;
+        }
+        if let Some(interval) = self.config.miner.template_update_interval() {
+            self.update_task_scheduled = true;
+            ctx.run_later(interval, |ctx| {
+                ctx.notify(UpdateMintTaskEvent);
+            });
+        }
+    }
+}
+
+impl EventHandler<Self, UpdateMintTaskEvent> for MinerService {
+    fn handle_event(&mut self, _msg: UpdateMintTaskEvent, ctx: &mut ServiceContext<MinerService>) {
+        self.update_task_scheduled = false;
+        if let Err(err) = self.update_task(ctx) {
+            error!("Failed to update mint task: {:?}", err);
+        }
+    }
+}
@@ -58,6 +58,8 @@ const DISCONNECT_REPUTATION_CHANGE: i32 = -256;
 /// Amount of time between the moment we disconnect from a node and the moment we remove it from
 /// the list.
 const FORGET_AFTER: Duration = Duration::from_secs(3600);
+/// The longer bans are saturated to this duration, so the ban deadline never overflows.
+const MAX_BAN_DURATION: Duration = Duration::from_secs(365 * 24 * 3600);
 
 #[derive(Debug)]
 enum Action {
@@ -69,6 +71,7 @@ enum Action {
     AddToPeersSet(SetId, PeerId),
     RemoveFromPeersSet(SetId, PeerId),
     PeerReputations((Sender<Vec<(PeerId, i32)>>, i32)),
+    BanPeer(PeerId, Duration),
 }
 
 /// Identifier of a set in the peerset.
@@ -187,6 +190,12 @@ impl PeersetHandle {
             .unbounded_send(Action::RemoveFromPeersSet(set_id, peer_id));
     }
 
+    /// Ban a peer for the given duration. The peer is disconnected from all the sets, and
+    /// its connections are refused until the ban expires, even if it is a reserved peer.
+    pub fn ban_peer(&self, peer_id: PeerId, duration: Duration) {
+        let _ = self.tx.unbounded_send(Action::BanPeer(peer_id, duration));
+    }
+
     pub fn reputations(&self, reputation_threshold: i32) -> Receiver<Vec<(PeerId, i32)>> {
         let (reputation_tx, reputation_rx) = oneshot::channel();
         let _ = self.tx.unbounded_send(Action::PeerReputations((
@@ -286,6 +295,9 @@ pub struct Peerset {
     created: Instant,
     /// Last time when we updated the reputations of connected nodes.
     latest_time_update: Instant,
+    /// Nodes that are banned until the given time. Their reputation stays at the minimum
+    /// until the ban expires.
+    banned_nodes: HashMap<PeerId, Instant>,
 }
 
 impl Peerset {
@@ -315,6 +327,7 @@ impl Peerset {
                 message_queue: VecDeque::new(),
                 created: now,
                 latest_time_update: now,
+                banned_nodes: HashMap::new(),
             }
         };
 
@@ -481,6 +494,20 @@ impl Peerset {
         }
     }
 
+    fn on_ban_peer(&mut self, peer_id: PeerId, duration: Duration) {
+        let duration = duration.min(MAX_BAN_DURATION);
+        debug!(target: "peerset", "Ban {} for {:?}", peer_id, duration);
+        match Instant::now().checked_add(duration) {
+            Some(banned_until) => {
+                self.banned_nodes.insert(peer_id, banned_until);
+            }
+            // the `Instant` of the platform can not hold the max ban duration, the peer is still
+            // banned by the reputation.
+            None => error!(target: "peerset", "Ban deadline of {} overflows", peer_id),
+        }
+        self.on_report_peer(peer_id, ReputationChange::new_fatal("Banned"));
+    }
+
     /// Updates the value of `self.latest_time_update` and performs all the updates that happen
     /// over time, such as reputation increases for staying connected.
     fn update_time(&mut self) {
@@ -495,12 +522,19 @@ impl Peerset {
             elapsed_now.as_secs() - elapsed_latest.as_secs()
         };
 
+        self.banned_nodes
+            .retain(|_, banned_until| *banned_until > now);
+
         // For each elapsed second, move the node reputation towards zero.
         // If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it
         // takes `ln(0.5) / ln(k)` seconds to reduce the reputation by half. Use this formula to
         // empirically determine a value of `k` that looks correct.
         for _ in 0..secs_diff {
             for peer_id in self.data.peers().cloned().collect::<Vec<_>>() {
+                // The reputation of the banned nodes does not recover until the ban expires.
+                if self.banned_nodes.contains_key(&peer_id) {
+                    continue;
+                }
                 // We use `k = 0.98`, so we divide by `50`. With that value, it takes 34.3 seconds
                 // to reduce the reputation by half.
                 fn reput_tick(reput: i32) -> i32 {
@@ -552,6 +586,9 @@ impl Peerset {
         // Try to connect to all the reserved nodes that we are not connected to.
         for set_index in 0..self.data.num_sets() {
             for reserved_node in &self.reserved_nodes[set_index].0 {
+                if self.banned_nodes.contains_key(reserved_node) {
+                    continue;
+                }
                 let entry = match self.data.peer(set_index, reserved_node) {
                     peersstate::Peer::Unknown(n) => n.discover(),
                     peersstate::Peer::NotConnected(n) => n,
@@ -767,6 +804,7 @@ impl Stream for Peerset {
                     let effective_peer_reputations = self.effective_peer_list(reputation_threshold);
                     let _ = tx.send(effective_peer_reputations);
                 }
+                Action::BanPeer(peer_id, duration) => self.on_ban_peer(peer_id, duration),
             }
         }
     }
@@ -979,4 +1017,83 @@ mod tests {
 
         futures::executor::block_on(fut);
     }
+
+    #[test]
+    fn test_peerset_ban_peer() {
+        let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+            sets: vec![SetConfig {
+                in_peers: 25,
+                out_peers: 25,
+                bootnodes: vec![],
+                reserved_nodes: Default::default(),
+                reserved_only: false,
+            }],
+        });
+
+        let peer_id = PeerId::random();
+        handle.ban_peer(peer_id, Duration::from_secs(60));
+
+        let fut = futures::future::poll_fn(move |cx| {
+            // We need one polling for the message to be processed.
+            assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+            peerset.incoming(SetId::from(0), peer_id, IncomingIndex(1));
+            if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+                assert_eq!(msg.unwrap(), Message::Reject(IncomingIndex(1)));
+            } else {
+                panic!()
+            }
+
+            // Unlike the reputation ban, the node is still refused while the ban is not expired.
+            thread::sleep(Duration::from_millis(1500));
+
+            peerset.incoming(SetId::from(0), peer_id, IncomingIndex(2));
+            if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+                assert_eq!(msg.unwrap(), Message::Reject(IncomingIndex(2)));
+            } else {
+                panic!()
+            }
+
+            Poll::Ready(())
+        });
+
+        futures::executor::block_on(fut);
+    }
+
+    #[test]
+    fn test_peerset_ban_peer_overflow() {
+        let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+            sets: vec![SetConfig {
+                in_peers: 25,
+                out_peers: 25,
+                bootnodes: vec![],
+                reserved_nodes: Default::default(),
+                reserved_only: false,
+            }],
+        });
+
+        let peer_id = PeerId::random();
+        // the deadline of the ban overflows `Instant` without the saturation.
+        handle.ban_peer(peer_id, Duration::from_secs(u64::max_value()));
+
+        let fut = futures::future::poll_fn(move |cx| {
+            // We need one polling for the message to be processed.
+            assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+            let banned_until = peerset.banned_nodes[&peer_id];
+            assert!(banned_until <= Instant::now() + MAX_BAN_DURATION);
+            assert!(banned_until > Instant::now() + MAX_BAN_DURATION / 2);
+
+            peerset.incoming(SetId::from(0), peer_id, IncomingIndex(1));
+            if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+                assert_eq!(msg.unwrap(), Message::Reject(IncomingIndex(1)));
+            } else {
+                panic!()
+            }
+
+            Poll::Ready(())
+        });
+
+        futures::executor::block_on(fut);
+    }
 }
@@ -69,6 +69,8 @@ pub enum BehaviourOut {
         /// Object that permits sending notifications to the peer.
         notifications_sink: NotificationsSink,
         info: Box<ChainInfo>,
+        /// The protocol version negotiated in the handshake.
+        version: u32,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
     },
@@ -128,6 +130,13 @@ pub enum BehaviourOut {
     },
 
     RandomKademliaStarted(ProtocolId),
+
+    /// A peer is not permitted to join the permissioned network, all the connections with it
+    /// should be closed.
+    PermissionDenied {
+        remote: PeerId,
+    },
+
     /// Events generated by a DHT as a response to get_value or put_value requests as well as the
     /// request duration.
     Dht(DhtEvent, Duration),
@@ -148,6 +157,7 @@ impl Behaviour {
         disco_config: DiscoveryConfig,
         request_response_protocols: Vec<request_responses::ProtocolConfig>,
     ) -> Result<Self, request_responses::RegisterError> {
+        let permissioned = protocol.is_permissioned();
         Ok(Behaviour {
             protocol,
             // debug_info: debug_info::DebugInfoBehaviour::new(user_agent, local_public_key),
@@ -155,6 +165,7 @@ impl Behaviour {
             discovery: disco_config.finish(),
             request_responses: request_responses::RequestResponsesBehaviour::new(
                 request_response_protocols.into_iter(),
+                permissioned,
             )?,
             events: VecDeque::new(),
         })
@@ -258,20 +269,27 @@ impl NetworkBehaviourEventProcess<CustomMessageOutcome> for Behaviour {
                 protocol,
                 notifications_sink,
                 info,
+                version,
                 notif_protocols,
                 rpc_protocols,
             } => {
+                // The peer has passed the handshake check, handle its inbound requests.
+                self.request_responses.permit_peer(remote);
                 self.events
                     .push_back(BehaviourOut::NotificationStreamOpened {
                         remote,
                         protocol,
                         notifications_sink,
                         info,
+                        version,
                         notif_protocols,
                         rpc_protocols,
                     });
             }
             CustomMessageOutcome::NotificationStreamClosed { remote, protocol } => {
+                if !self.protocol.is_open(&remote) {
+                    self.request_responses.revoke_peer(&remote);
+                }
                 self.events
                     .push_back(BehaviourOut::NotificationStreamClosed { remote, protocol });
             }
@@ -279,6 +297,11 @@ impl NetworkBehaviourEventProcess<CustomMessageOutcome> for Behaviour {
                 self.events
                     .push_back(BehaviourOut::NotificationsReceived { remote, messages });
             }
+            CustomMessageOutcome::PermissionDenied { remote } => {
+                self.request_responses.revoke_peer(&remote);
+                self.events
+                    .push_back(BehaviourOut::PermissionDenied { remote });
+            }
             CustomMessageOutcome::None => {}
             CustomMessageOutcome::NotificationStreamReplaced {
                 remote,
@@ -24,6 +24,7 @@ use zeroize::Zeroize;
 
 pub use crate::request_responses::{IncomingRequest, ProtocolConfig as RequestResponseConfig};
 pub use libp2p::{build_multiaddr, core::PublicKey, identity, wasm_ext::ExtTransport};
+pub use network_p2p_types::permission::PermissionConfig;
 pub use network_p2p_types::{parse_addr, parse_str_addr, MultiaddrWithPeerId};
 use starcoin_types::startup_info::ChainInfo;
 
@@ -101,6 +102,8 @@ pub struct NetworkConfiguration {
     pub client_version: String,
     /// Name of the node. Sent over the wire for debugging purposes.
     pub node_name: String,
+    /// The semantic version of the node, sent in handshake to reject incompatible peers.
+    pub node_version: String,
 
     pub transport: TransportConfig,
 
@@ -111,6 +114,8 @@ pub struct NetworkConfiguration {
     /// Require iterative Kademlia DHT queries to use disjoint paths for increased resiliency in the
     /// presence of potentially adversarial nodes.
     pub kademlia_disjoint_query_paths: bool,
+    /// If set, the network is permissioned, only permitted peers can connect.
+    pub permission: Option<PermissionConfig>,
 }
 
 /// Configuration for the transport layer.
@@ -154,6 +159,7 @@ impl Default for NetworkConfiguration {
             non_reserved_mode: NonReservedPeerMode::Accept,
             client_version: "unknown".into(),
             node_name: "unknown".into(),
+            node_version: env!("CARGO_PKG_VERSION").into(),
             transport: TransportConfig::Normal {
                 enable_mdns: false,
                 allow_private_ipv4: false,
@@ -163,6 +169,7 @@ impl Default for NetworkConfiguration {
             request_response_protocols: vec![],
             allow_non_globals_in_dht: false,
             kademlia_disjoint_query_paths: false,
+            permission: None,
         }
     }
 }
@@ -185,6 +192,7 @@ impl NetworkConfiguration {
             non_reserved_mode: NonReservedPeerMode::Accept,
             client_version: client_version.into(),
             node_name: node_name.into(),
+            node_version: env!("CARGO_PKG_VERSION").into(),
             transport: TransportConfig::Normal {
                 enable_mdns: false,
                 allow_private_ipv4: true,
@@ -194,6 +202,7 @@ impl NetworkConfiguration {
             request_response_protocols: vec![],
             allow_non_globals_in_dht: false,
             kademlia_disjoint_query_paths: false,
+            permission: None,
         }
     }
 
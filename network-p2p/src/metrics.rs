@@ -11,6 +11,7 @@ pub struct Metrics {
     pub connections_opened_total: UIntCounterVec,
     pub distinct_peers_connections_closed_total: IntCounter,
     pub distinct_peers_connections_opened_total: IntCounter,
+    pub handshake_rejected_total: UIntCounterVec,
     pub incoming_connections_errors_total: UIntCounterVec,
     pub incoming_connections_total: IntCounter,
     pub kademlia_query_duration: HistogramVec,
@@ -72,6 +73,16 @@ impl Metrics {
                 )?,
                 registry,
             )?,
+            handshake_rejected_total: register(
+                UIntCounterVec::new(
+                    Opts::new(
+                        "sub_libp2p_handshake_rejected_total",
+                        "Total number of peers rejected in handshake, by reason",
+                    ),
+                    &["reason"],
+                )?,
+                registry,
+            )?,
             incoming_connections_errors_total: register(
                 UIntCounterVec::new(
                     Opts::new(
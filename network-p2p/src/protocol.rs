@@ -3,7 +3,7 @@ pub mod generic_proto;
 pub mod message;
 
 use crate::protocol::generic_proto::{GenericProto, GenericProtoOut, NotificationsSink};
-use crate::protocol::message::generic::Status;
+use crate::protocol::message::generic::{PermissionedStatus, Status};
 use crate::utils::interval;
 use crate::{errors, DiscoveryNetBehaviour, Multiaddr};
 use bcs_ext::BCSCodec;
@@ -17,7 +17,11 @@ use libp2p::swarm::{IntoProtocolsHandler, ProtocolsHandler};
 use libp2p::swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters};
 use libp2p::PeerId;
 use log::Level;
+use network_p2p_types::permission::PermissionConfig;
 use sc_peerset::SetId;
+use semver::Version;
+use starcoin_crypto::ed25519::Ed25519Signature;
+use starcoin_metrics::UIntCounterVec;
 use starcoin_types::startup_info::{ChainInfo, ChainStatus};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -32,9 +36,15 @@ use std::time;
 /// Interval at which we perform time based maintenance
 const TICK_TIMEOUT: time::Duration = time::Duration::from_millis(1100);
 /// Current protocol version.
-pub(crate) const CURRENT_VERSION: u32 = 2;
+pub const CURRENT_VERSION: u32 = 3;
 /// Lowest version we support
 pub(crate) const MIN_VERSION: u32 = 1;
+/// The protocol version since which the handshake status carries the node version.
+pub(crate) const NODE_VERSION_SINCE: u32 = 3;
+/// The node version is carried as an extra entry of the rpc protocols in the handshake status,
+/// such as `/starcoin/node-version/1.0.0`, so the status keeps the same encoding, and the nodes
+/// of the older versions, which ignore the unknown rpc protocols, can still decode it.
+pub(crate) const NODE_VERSION_PREFIX: &str = "/starcoin/node-version/";
 
 pub(crate) const HARD_CORE_PROTOCOL_ID: sc_peerset::SetId = sc_peerset::SetId::from(0);
 
@@ -52,8 +62,101 @@ pub mod rep {
     pub const BAD_MESSAGE: Rep = Rep::new(-(1 << 12), "Bad message");
     /// Peer has different genesis.
     pub const GENESIS_MISMATCH: Rep = Rep::new_fatal("Genesis mismatch");
+    /// Peer has different chain id.
+    pub const CHAIN_ID_MISMATCH: Rep = Rep::new_fatal("Chain id mismatch");
+    /// Peer runs an incompatible node version.
+    pub const NODE_VERSION_MISMATCH: Rep = Rep::new_fatal("Incompatible node version");
     /// Peer is on unsupported protocol version.
     pub const BAD_PROTOCOL: Rep = Rep::new_fatal("Unsupported protocol");
+    /// Peer is not permitted to join the permissioned network.
+    pub const PERMISSION_DENIED: Rep = Rep::new_fatal("Permission denied");
+}
+
+/// The reason why a peer is rejected in the handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeRejectReason {
+    /// The handshake message can not be decoded.
+    BadHandshake,
+    PermissionDenied,
+    GenesisMismatch,
+    ChainIdMismatch,
+    UnsupportedProtocol,
+    IncompatibleNodeVersion,
+}
+
+impl HandshakeRejectReason {
+    /// The reason name, used as the label of metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadHandshake => "bad_handshake",
+            Self::PermissionDenied => "permission_denied",
+            Self::GenesisMismatch => "genesis_mismatch",
+            Self::ChainIdMismatch => "chain_id_mismatch",
+            Self::UnsupportedProtocol => "unsupported_protocol",
+            Self::IncompatibleNodeVersion => "incompatible_node_version",
+        }
+    }
+
+    fn reputation(&self) -> sc_peerset::ReputationChange {
+        match self {
+            Self::BadHandshake => rep::BAD_MESSAGE,
+            Self::PermissionDenied => rep::PERMISSION_DENIED,
+            Self::GenesisMismatch => rep::GENESIS_MISMATCH,
+            Self::ChainIdMismatch => rep::CHAIN_ID_MISMATCH,
+            Self::UnsupportedProtocol => rep::BAD_PROTOCOL,
+            Self::IncompatibleNodeVersion => rep::NODE_VERSION_MISMATCH,
+        }
+    }
+}
+
+impl std::fmt::Display for HandshakeRejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Check whether the node version of a peer is compatible with ours.
+/// Nodes with the same major version are compatible, or with the same minor version if the
+/// major version is 0.
+pub(crate) fn check_node_version(ours: &str, theirs: &str) -> Result<(), String> {
+    let ours = Version::parse(ours)
+        .map_err(|e| format!("invalid local node version `{}`: {}", ours, e))?;
+    let theirs =
+        Version::parse(theirs).map_err(|e| format!("invalid node version `{}`: {}", theirs, e))?;
+    if ours.major == theirs.major && (ours.major != 0 || ours.minor == theirs.minor) {
+        Ok(())
+    } else {
+        Err(format!(
+            "incompatible node version (ours: {} theirs: {})",
+            ours, theirs
+        ))
+    }
+}
+
+/// Split the node version entry from the rpc protocols of the handshake status.
+/// The protocol version used with a peer, the lower one of ours and the peer's.
+pub(crate) fn negotiated_version(theirs: u32) -> u32 {
+    theirs.min(CURRENT_VERSION)
+}
+
+pub(crate) fn split_node_version(
+    rpc_protocols: &[Cow<'static, str>],
+) -> (Vec<Cow<'static, str>>, Option<String>) {
+    let mut node_version = None;
+    let rpc_protocols = rpc_protocols
+        .iter()
+        .filter(
+            |protocol| match protocol.strip_prefix(NODE_VERSION_PREFIX) {
+                Some(version) => {
+                    node_version = Some(version.to_string());
+                    false
+                }
+                None => true,
+            },
+        )
+        .cloned()
+        .collect();
+    (rpc_protocols, node_version)
 }
 
 #[derive(Debug)]
@@ -64,6 +167,8 @@ pub enum CustomMessageOutcome {
         protocol: Cow<'static, str>,
         notifications_sink: NotificationsSink,
         info: Box<ChainInfo>,
+        /// The protocol version negotiated in the handshake.
+        version: u32,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
     },
@@ -83,6 +188,9 @@ pub enum CustomMessageOutcome {
         remote: PeerId,
         messages: Vec<(Cow<'static, str>, Bytes)>,
     },
+    /// A peer is not permitted to join the permissioned network, all the connections with it
+    /// should be closed.
+    PermissionDenied { remote: PeerId },
     None,
 }
 
@@ -127,6 +235,12 @@ pub struct Protocol {
     /// Entries are removed when the corresponding "substream closed" is later received.
     bad_handshake_substreams: HashSet<(PeerId, sc_peerset::SetId)>,
     chain_info: ChainInfo,
+    /// The permission of permissioned network, None if the network is public.
+    permission: Option<PermissionConfig>,
+    /// The semantic version of this node.
+    node_version: String,
+    /// Count the peers rejected in handshake by reason.
+    handshake_rejected_metric: Option<UIntCounterVec>,
 }
 
 impl NetworkBehaviour for Protocol {
@@ -257,24 +371,34 @@ impl NetworkBehaviour for Protocol {
                 set_id,
                 received_handshake,
                 notifications_sink,
-            } => match Status::decode(&received_handshake[..]) {
-                Ok(status) => {
+            } => match self.decode_handshake_msg(&received_handshake[..]) {
+                Ok((status, identity)) => {
                     let protocol_name = self.notif_protocols[usize::from(set_id)].clone();
                     self.on_peer_connected(
                         peer_id,
                         set_id,
                         protocol_name,
                         status,
+                        identity,
                         notifications_sink,
                     )
                 }
                 Err(err) => {
-                    error!(target: "network-p2p", "Couldn't decode handshake packet sent by {}: {:?}: {}", peer_id, hex::encode(received_handshake), err);
+                    debug!(target: "network-p2p", "Bad handshake packet sent by {}: {}", peer_id, hex::encode(received_handshake));
                     self.bad_handshake_substreams.insert((peer_id, set_id));
-                    self.peerset_handle.report_peer(peer_id, rep::BAD_MESSAGE);
-                    self.behaviour
-                        .disconnect_peer(&peer_id, HARD_CORE_PROTOCOL_ID);
-                    CustomMessageOutcome::None
+                    self.reject_peer(
+                        peer_id,
+                        HARD_CORE_PROTOCOL_ID,
+                        HandshakeRejectReason::BadHandshake,
+                        format!("couldn't decode handshake packet: {}", err),
+                    );
+                    // In permissioned network, the peer which can not present the identity in
+                    // handshake is not permitted.
+                    if self.is_permissioned() {
+                        CustomMessageOutcome::PermissionDenied { remote: peer_id }
+                    } else {
+                        CustomMessageOutcome::None
+                    }
                 }
             },
             GenericProtoOut::CustomProtocolClosed { peer_id, set_id } => {
@@ -344,6 +468,9 @@ impl Protocol {
         boot_node_ids: Arc<HashSet<PeerId>>,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
+        permission: Option<PermissionConfig>,
+        node_version: String,
+        handshake_rejected_metric: Option<UIntCounterVec>,
     ) -> errors::Result<(Protocol, sc_peerset::PeersetHandle)> {
         let mut important_peers = HashSet::new();
         important_peers.extend(boot_node_ids.iter());
@@ -359,6 +486,8 @@ impl Protocol {
                 notif_protocols.to_vec(),
                 rpc_protocols.to_vec(),
                 chain_info.clone(),
+                node_version.clone(),
+                permission.as_ref(),
             );
 
             let notif_protocol_wth_handshake: Vec<(Cow<'static, str>, Vec<u8>, u64)> =
@@ -390,10 +519,18 @@ impl Protocol {
             notif_protocols,
             rpc_protocols,
             bad_handshake_substreams: Default::default(),
+            permission,
+            node_version,
+            handshake_rejected_metric,
         };
         Ok((protocol, peerset_handle))
     }
 
+    /// Returns true if the network is permissioned.
+    pub fn is_permissioned(&self) -> bool {
+        self.permission.is_some()
+    }
+
     /// Returns the list of all the peers we have an open channel to.
     pub fn open_peers(&self) -> impl Iterator<Item = &PeerId> {
         self.behaviour.open_peers()
@@ -455,41 +592,17 @@ impl Protocol {
         set_id: SetId,
         protocol_name: Cow<'static, str>,
         status: Status,
+        identity: Option<Ed25519Signature>,
         notifications_sink: NotificationsSink,
     ) -> CustomMessageOutcome {
         debug!(target: "network-p2p", "New peer {} {:?}", who, status);
-        if status.info.genesis_hash() != self.chain_info.genesis_hash() {
-            if self.boot_node_ids.contains(&who) {
-                error!(
-                    target: "network-p2p",
-                    "Bootnode with peer id `{}` is on a different chain (our genesis: {} theirs: {})",
-                    who,
-                    self.chain_info.genesis_hash(),
-                    status.info.genesis_hash(),
-                );
+        if let Err((reason, detail)) = self.check_handshake(&who, &status, identity.as_ref()) {
+            self.reject_peer(who, set_id, reason, detail);
+            return if reason == HandshakeRejectReason::PermissionDenied {
+                CustomMessageOutcome::PermissionDenied { remote: who }
             } else {
-                log!(
-                    target: "network-p2p",
-                    if self.important_peers.contains(&who) { Level::Warn } else { Level::Debug },
-                    "Peer with id `{}` is on different chain (our genesis: {} theirs: {})",
-                    who,
-                    self.chain_info.genesis_hash(),
-                    status.info.genesis_hash(),
-                );
-            }
-            self.peerset_handle.report_peer(who, rep::GENESIS_MISMATCH);
-            self.behaviour.disconnect_peer(&who, set_id);
-            return CustomMessageOutcome::None;
-        }
-        if status.version < MIN_VERSION && CURRENT_VERSION < status.min_supported_version {
-            log!(
-                target: "network-p2p",
-                if self.important_peers.contains(&who) { Level::Warn } else { Level::Debug },
-                "Peer {:?} using unsupported protocol version {}", who, status.version
-            );
-            self.peerset_handle.report_peer(who, rep::BAD_PROTOCOL);
-            self.behaviour.disconnect_peer(&who, set_id);
-            return CustomMessageOutcome::None;
+                CustomMessageOutcome::None
+            };
         }
         debug!(target: "network-p2p", "Connected {}", who);
         let peer = Peer {
@@ -497,21 +610,112 @@ impl Protocol {
         };
         self.context_data.peers.insert(who, peer);
         debug!(target: "network-p2p", "Connected {}, Set id {:?}", who, set_id);
+        let (rpc_protocols, _) = split_node_version(status.rpc_protocols.as_slice());
         CustomMessageOutcome::NotificationStreamOpened {
             remote: who,
             protocol: protocol_name,
             notifications_sink,
             info: Box::new(status.info),
+            version: negotiated_version(status.version),
             notif_protocols: status.notif_protocols.to_vec(),
-            rpc_protocols: status.rpc_protocols.to_vec(),
+            rpc_protocols,
+        }
+    }
+
+    /// Check the handshake status of a peer, return the reject reason and the detail if the
+    /// peer is not permitted or incompatible with us.
+    fn check_handshake(
+        &self,
+        who: &PeerId,
+        status: &Status,
+        identity: Option<&Ed25519Signature>,
+    ) -> Result<(), (HandshakeRejectReason, String)> {
+        if let Some(permission) = self.permission.as_ref() {
+            permission
+                .check(self.chain_info.chain_id().id(), who, identity)
+                .map_err(|reason| (HandshakeRejectReason::PermissionDenied, reason))?;
+        }
+        if status.info.genesis_hash() != self.chain_info.genesis_hash() {
+            return Err((
+                HandshakeRejectReason::GenesisMismatch,
+                format!(
+                    "different genesis (ours: {} theirs: {})",
+                    self.chain_info.genesis_hash(),
+                    status.info.genesis_hash()
+                ),
+            ));
+        }
+        if status.info.chain_id() != self.chain_info.chain_id() {
+            return Err((
+                HandshakeRejectReason::ChainIdMismatch,
+                format!(
+                    "different chain id (ours: {} theirs: {})",
+                    self.chain_info.chain_id(),
+                    status.info.chain_id()
+                ),
+            ));
+        }
+        if status.version < MIN_VERSION || CURRENT_VERSION < status.min_supported_version {
+            return Err((
+                HandshakeRejectReason::UnsupportedProtocol,
+                format!(
+                    "unsupported protocol version {} (ours: {} min supported: {})",
+                    status.version, CURRENT_VERSION, MIN_VERSION
+                ),
+            ));
+        }
+        // The peers of the older protocol versions do not send the node version.
+        if status.version < NODE_VERSION_SINCE {
+            return Ok(());
+        }
+        match split_node_version(status.rpc_protocols.as_slice()) {
+            (_, Some(node_version)) => {
+                check_node_version(self.node_version.as_str(), node_version.as_str())
+                    .map_err(|reason| (HandshakeRejectReason::IncompatibleNodeVersion, reason))
+            }
+            (_, None) => Err((
+                HandshakeRejectReason::BadHandshake,
+                format!(
+                    "node version is missing in the handshake of protocol version {}",
+                    status.version
+                ),
+            )),
         }
     }
 
+    /// Disconnect the peer rejected in handshake, and record the reason in logs and metrics.
+    fn reject_peer(
+        &mut self,
+        who: PeerId,
+        set_id: SetId,
+        reason: HandshakeRejectReason,
+        detail: String,
+    ) {
+        log!(
+            target: "network-p2p",
+            if self.boot_node_ids.contains(&who) {
+                Level::Error
+            } else if self.important_peers.contains(&who) {
+                Level::Warn
+            } else {
+                Level::Info
+            },
+            "Reject peer {}, reason: {}, {}", who, reason, detail
+        );
+        if let Some(metric) = self.handshake_rejected_metric.as_ref() {
+            metric.with_label_values(&[reason.as_str()]).inc();
+        }
+        self.peerset_handle.report_peer(who, reason.reputation());
+        self.behaviour.disconnect_peer(&who, set_id);
+    }
+
     fn build_status(
         notif_protocols: Vec<Cow<'static, str>>,
-        rpc_protocols: Vec<Cow<'static, str>>,
+        mut rpc_protocols: Vec<Cow<'static, str>>,
         info: ChainInfo,
+        node_version: String,
     ) -> Status {
+        rpc_protocols.push(format!("{}{}", NODE_VERSION_PREFIX, node_version).into());
         message::generic::Status {
             version: CURRENT_VERSION,
             min_supported_version: MIN_VERSION,
@@ -525,10 +729,32 @@ impl Protocol {
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
         info: ChainInfo,
+        node_version: String,
+        permission: Option<&PermissionConfig>,
     ) -> Vec<u8> {
-        Self::build_status(notif_protocols, rpc_protocols, info)
+        let status = Self::build_status(notif_protocols, rpc_protocols, info, node_version);
+        match permission {
+            // the peers in permissioned network should present the identity in handshake.
+            Some(permission) => PermissionedStatus {
+                status,
+                identity: permission.identity.clone(),
+            }
             .encode()
-            .expect("Status encode should success.")
+            .expect("PermissionedStatus encode should success."),
+            None => status.encode().expect("Status encode should success."),
+        }
+    }
+
+    fn decode_handshake_msg(
+        &self,
+        handshake: &[u8],
+    ) -> anyhow::Result<(Status, Option<Ed25519Signature>)> {
+        if self.permission.is_some() {
+            let status = PermissionedStatus::decode(handshake)?;
+            Ok((status.status, status.identity))
+        } else {
+            Ok((Status::decode(handshake)?, None))
+        }
     }
 
     /// Called by peer when it is disconnecting
@@ -583,6 +809,8 @@ impl Protocol {
             self.notif_protocols.to_vec(),
             self.rpc_protocols.to_vec(),
             self.chain_info.clone(),
+            self.node_version.clone(),
+            self.permission.as_ref(),
         );
         self.behaviour
             .set_notif_protocol_handshake(HARD_CORE_PROTOCOL_ID, handshake_msg)
@@ -56,6 +56,8 @@ pub enum Event {
         /// The concerned protocol. Each protocol uses a different substream.
         protocol: Cow<'static, str>,
         info: Box<ChainInfo>,
+        /// The protocol version negotiated in the handshake.
+        version: u32,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
     },
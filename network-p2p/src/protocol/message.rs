@@ -1,6 +1,7 @@
 /// Generic types.
 pub mod generic {
     use serde::{Deserialize, Serialize};
+    use starcoin_crypto::ed25519::Ed25519Signature;
     use starcoin_types::startup_info::ChainInfo;
     use std::borrow::Cow;
 
@@ -23,7 +24,15 @@ pub mod generic {
         pub notif_protocols: Vec<Cow<'static, str>>,
         /// Tell other peer which rpc api we support.
         pub rpc_protocols: Vec<Cow<'static, str>>,
-        /// The info of the chain
+        /// The info of the chain, include the chain id and genesis hash.
         pub info: ChainInfo,
     }
+
+    /// Status sent on connection in permissioned network.
+    #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+    pub struct PermissionedStatus {
+        pub status: Status,
+        /// The identity of the node signed by the network CA.
+        pub identity: Option<Ed25519Signature>,
+    }
 }
@@ -59,7 +59,7 @@ pub use network_p2p_types::{
 use sc_peerset::ReputationChange;
 use std::{
     borrow::Cow,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     convert::TryFrom as _,
     io, iter,
     pin::Pin,
@@ -200,6 +200,10 @@ pub struct RequestResponsesBehaviour {
 
     /// Whenever an incoming request arrives, the arrival [`Instant`] is recorded here.
     pending_responses_arrival_time: HashMap<ProtocolRequestId, Instant>,
+
+    /// The peers which passed the permission check in handshake, None if the network is not
+    /// permissioned. In permissioned network, the inbound requests of other peers are dropped.
+    permitted_peers: Option<HashSet<PeerId>>,
 }
 
 /// Generated by the response builder and waiting to be processed.
@@ -214,7 +218,12 @@ struct RequestProcessingOutcome {
 impl RequestResponsesBehaviour {
     /// Creates a new behaviour. Must be passed a list of supported protocols. Returns an error if
     /// the same protocol is passed twice.
-    pub fn new(list: impl Iterator<Item = ProtocolConfig>) -> Result<Self, RegisterError> {
+    /// If `permissioned`, only the inbound requests of the peers permitted by `permit_peer` are
+    /// handled.
+    pub fn new(
+        list: impl Iterator<Item = ProtocolConfig>,
+        permissioned: bool,
+    ) -> Result<Self, RegisterError> {
         let mut protocols = HashMap::new();
         for protocol in list {
             let mut cfg = RequestResponseConfig::default();
@@ -249,9 +258,29 @@ impl RequestResponsesBehaviour {
             pending_requests: Default::default(),
             pending_responses: Default::default(),
             pending_responses_arrival_time: Default::default(),
+            permitted_peers: if permissioned {
+                Some(HashSet::new())
+            } else {
+                None
+            },
         })
     }
 
+    /// Permit to handle the inbound requests of `peer`, which has passed the permission check.
+    /// Does nothing if the network is not permissioned.
+    pub fn permit_peer(&mut self, peer: PeerId) {
+        if let Some(permitted_peers) = self.permitted_peers.as_mut() {
+            permitted_peers.insert(peer);
+        }
+    }
+
+    /// Revoke the permission of `peer` granted by `permit_peer`.
+    pub fn revoke_peer(&mut self, peer: &PeerId) {
+        if let Some(permitted_peers) = self.permitted_peers.as_mut() {
+            permitted_peers.remove(peer);
+        }
+    }
+
     /// Initiates sending a request.
     ///
     /// If there is no established connection to the target peer, the behavior is determined by the choice of `connect`.
@@ -353,6 +382,7 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
     }
 
     fn inject_disconnected(&mut self, peer_id: &PeerId) {
+        self.revoke_peer(peer_id);
         for (p, _) in self.protocols.values_mut() {
             NetworkBehaviour::inject_disconnected(p, peer_id)
         }
@@ -528,6 +558,21 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
                                     ..
                                 },
                         } => {
+                            let permitted = self
+                                .permitted_peers
+                                .as_ref()
+                                .map(|peers| peers.contains(&peer))
+                                .unwrap_or(true);
+                            if !permitted {
+                                // Dropping the `channel` is reported as an
+                                // `InboundFailure::ResponseOmission` event.
+                                log::debug!(
+                                    target: "sub-libp2p",
+                                    "Drop the request {:?} on protocol {:?} from the peer {} which is not permitted",
+                                    request_id, protocol, peer,
+                                );
+                                continue;
+                            }
                             self.pending_responses_arrival_time
                                 .insert((protocol.clone(), request_id).into(), Instant::now());
 
@@ -878,6 +923,13 @@ mod tests {
 
     fn build_swarm(
         list: impl Iterator<Item = ProtocolConfig>,
+    ) -> (Swarm<RequestResponsesBehaviour>, Multiaddr) {
+        build_swarm_with_permission(list, false)
+    }
+
+    fn build_swarm_with_permission(
+        list: impl Iterator<Item = ProtocolConfig>,
+        permissioned: bool,
     ) -> (Swarm<RequestResponsesBehaviour>, Multiaddr) {
         let keypair = Keypair::generate_ed25519();
 
@@ -891,7 +943,7 @@ mod tests {
             .multiplex(libp2p::yamux::YamuxConfig::default())
             .boxed();
 
-        let behaviour = RequestResponsesBehaviour::new(list).unwrap();
+        let behaviour = RequestResponsesBehaviour::new(list, permissioned).unwrap();
 
         let mut swarm = Swarm::new(transport, behaviour, keypair.public().into_peer_id());
         let listen_addr: Multiaddr = format!("/memory/{}", rand::random::<u64>())
@@ -1100,6 +1152,85 @@ mod tests {
         });
     }
 
+    /// Send a request to a permissioned swarm, which permits the requester if `permit`.
+    fn request_permissioned_swarm(permit: bool) -> Result<Vec<u8>, RequestFailure> {
+        let protocol_name = "/test/req-resp/1";
+        let mut pool = LocalPool::new();
+
+        let (tx, mut rx) = mpsc::channel::<IncomingRequest>(64);
+        pool.spawner()
+            .spawn_obj(
+                async move {
+                    while let Some(rq) = rx.next().await {
+                        let _ = rq.pending_response.send(super::OutgoingResponse {
+                            result: Ok(b"this is a response".to_vec()),
+                            reputation_changes: Vec::new(),
+                        });
+                    }
+                }
+                .boxed()
+                .into(),
+            )
+            .unwrap();
+        let protocol_config = |inbound_queue| ProtocolConfig {
+            name: From::from(protocol_name),
+            max_request_size: 1024,
+            max_response_size: 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+            inbound_queue,
+        };
+        let (mut requester, _) = build_swarm(iter::once(protocol_config(None)));
+        let (mut responder, responder_addr) =
+            build_swarm_with_permission(iter::once(protocol_config(Some(tx))), true);
+        if permit {
+            responder.permit_peer(*Swarm::local_peer_id(&requester));
+        }
+        Swarm::dial_addr(&mut requester, responder_addr).unwrap();
+
+        pool.spawner()
+            .spawn_obj(
+                async move {
+                    loop {
+                        let _ = responder.next_event().await;
+                    }
+                }
+                .boxed()
+                .into(),
+            )
+            .unwrap();
+
+        pool.run_until(async move {
+            let mut response_receiver = None;
+            loop {
+                match requester.next_event().await {
+                    SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                        let (sender, receiver) = oneshot::channel();
+                        requester.send_request(
+                            &peer_id,
+                            protocol_name,
+                            b"this is a request".to_vec(),
+                            sender,
+                            IfDisconnected::ImmediateError,
+                        );
+                        response_receiver = Some(receiver);
+                    }
+                    SwarmEvent::Behaviour(Event::RequestFinished { .. }) => break,
+                    _ => {}
+                }
+            }
+            response_receiver.unwrap().await.unwrap()
+        })
+    }
+
+    #[test]
+    fn permissioned_request_response() {
+        assert_eq!(
+            request_permissioned_swarm(true).unwrap(),
+            b"this is a response"
+        );
+        assert!(request_permissioned_swarm(false).is_err());
+    }
+
     /// A [`RequestId`] is a unique identifier among either all inbound or all outbound requests for
     /// a single [`RequestResponse`] behaviour. It is not guaranteed to be unique across multiple
     /// [`RequestResponse`] behaviours. Thus when handling [`RequestId`] in the context of multiple
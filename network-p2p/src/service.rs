@@ -217,6 +217,10 @@ impl NetworkWorker {
         }
         let peerset_config = sc_peerset::PeersetConfig { sets: sets_conf };
 
+        let metrics = params
+            .metrics_registry
+            .as_ref()
+            .and_then(|registry| Metrics::register(&registry).ok());
         let (protocol, peerset_handle) = Protocol::new(
             peerset_config,
             params.chain_info,
@@ -228,6 +232,11 @@ impl NetworkWorker {
                 .iter()
                 .map(|config| config.name.clone())
                 .collect(),
+            params.network_config.permission.clone(),
+            params.network_config.node_version.clone(),
+            metrics
+                .as_ref()
+                .map(|metrics| metrics.handshake_rejected_total.clone()),
         )?;
 
         // Build the swarm.
@@ -315,10 +324,6 @@ impl NetworkWorker {
         let external_addresses = Arc::new(Mutex::new(Vec::new()));
         let peers_notifications_sinks = Arc::new(Mutex::new(HashMap::new()));
 
-        let metrics = params
-            .metrics_registry
-            .as_ref()
-            .and_then(|registry| Metrics::register(&registry).ok());
         let service = Arc::new(NetworkService {
             bandwidth,
             external_addresses,
@@ -822,6 +827,20 @@ impl NetworkService {
         Ok(())
     }
 
+    /// Removes a `PeerId` from the reserved peers and the peer set, and disconnects from it.
+    /// The peer may be connected again if it is discovered later.
+    pub fn remove_peer(&self, peer: PeerId) {
+        self.peerset
+            .remove_reserved_peer(HARD_CORE_PROTOCOL_ID, peer);
+        self.peerset
+            .remove_from_peers_set(HARD_CORE_PROTOCOL_ID, peer);
+    }
+
+    /// Disconnects from a `PeerId` and refuses its connections for the given duration.
+    pub fn ban_peer(&self, peer: PeerId, duration: Duration) {
+        self.peerset.ban_peer(peer, duration);
+    }
+
     /// Returns the number of peers we're connected to.
     pub fn num_connected(&self) -> usize {
         self.num_connected.load(Ordering::Relaxed)
@@ -1141,11 +1160,17 @@ impl Future for NetworkWorker {
                     }
                 }
                 Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::RandomKademliaStarted(_))) => {}
+                Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::PermissionDenied { remote })) => {
+                    // Close all the connections with the peer and refuse the new ones, so it can
+                    // not send requests to us either.
+                    Swarm::ban_peer_id(&mut this.network_service, remote);
+                }
                 Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::NotificationStreamOpened {
                     remote,
                     protocol,
                     notifications_sink,
                     info,
+                    version,
                     notif_protocols,
                     rpc_protocols,
                 })) => {
@@ -1161,6 +1186,7 @@ impl Future for NetworkWorker {
                         remote,
                         protocol,
                         info,
+                        version,
                         notif_protocols,
                         rpc_protocols,
                     });
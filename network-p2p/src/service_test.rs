@@ -3,6 +3,7 @@
 
 use crate::config::RequestResponseConfig;
 use crate::protocol::message::generic::Status;
+use crate::protocol::{check_node_version, split_node_version, NODE_VERSION_PREFIX};
 use crate::service::NetworkStateInfo;
 use crate::{config, Event, NetworkService, NetworkWorker};
 use crate::{NetworkConfiguration, Params, ProtocolId};
@@ -464,6 +465,68 @@ fn test_handshake_fail() {
     assert_eq!(state2.connected_peers.len(), 0);
 }
 
+#[stest::test]
+fn test_handshake_fail_with_incompatible_node_version() {
+    let protocol = ProtocolId::from("starcoin");
+    let config1 = generate_config(vec![], vec![PROTOCOL_NAME.into()], vec![]);
+    let chain = ChainInfo::random();
+    let worker1 = NetworkWorker::new(Params::new(
+        config1.clone(),
+        protocol.clone(),
+        chain.clone(),
+        None,
+    ))
+    .unwrap();
+    let service1 = worker1.service().clone();
+
+    task::spawn(worker1);
+
+    let seed = config::MultiaddrWithPeerId {
+        multiaddr: config1.listen_addresses[0].clone(),
+        peer_id: service1.local_peer_id(),
+    };
+
+    let mut config2 = generate_config(vec![seed], vec![PROTOCOL_NAME.into()], vec![]);
+    config2.node_version = "999.0.0".to_string();
+
+    let worker2 = NetworkWorker::new(Params::new(config2, protocol, chain, None)).unwrap();
+    let service2 = worker2.service().clone();
+
+    task::spawn(worker2);
+
+    thread::sleep(Duration::from_secs(1));
+
+    let state1 = block_on(async { service1.network_state().await }).unwrap();
+    let state2 = block_on(async { service2.network_state().await }).unwrap();
+
+    assert_eq!(state1.connected_peers.len(), 0);
+    assert_eq!(state2.connected_peers.len(), 0);
+}
+
+#[test]
+fn test_check_node_version() {
+    assert!(check_node_version("1.0.0-beta.6", "1.2.0").is_ok());
+    assert!(check_node_version("1.0.0", "2.0.0").is_err());
+    assert!(check_node_version("0.9.1", "0.9.5").is_ok());
+    assert!(check_node_version("0.9.1", "0.10.0").is_err());
+    assert!(check_node_version("1.0.0", "unknown").is_err());
+}
+
+#[test]
+fn test_split_node_version() {
+    let rpc_protocols: Vec<Cow<'static, str>> = vec![
+        "/starcoin/rpc/1".into(),
+        format!("{}1.2.0", NODE_VERSION_PREFIX).into(),
+    ];
+    let (protocols, node_version) = split_node_version(rpc_protocols.as_slice());
+    assert_eq!(protocols, vec![Cow::from("/starcoin/rpc/1")]);
+    assert_eq!(node_version.as_deref(), Some("1.2.0"));
+    // the status of the older versions has no node version.
+    let (protocols, node_version) = split_node_version(&rpc_protocols[..1]);
+    assert_eq!(protocols.len(), 1);
+    assert!(node_version.is_none());
+}
+
 fn generate_config(
     boot_nodes: Vec<MultiaddrWithPeerId>,
     notif_protocols: Vec<Cow<'static, str>>,
@@ -568,11 +631,13 @@ fn test_support_protocol() {
         remote,
         protocol: _,
         info: _,
+        version,
         notif_protocols,
         rpc_protocols,
     } = open_event1
     {
         assert_eq!(&remote, service2.peer_id());
+        assert_eq!(version, crate::protocol::CURRENT_VERSION);
         assert_eq!(notif_protocols.len(), 1);
         assert_eq!(rpc_protocols.len(), 0);
     } else {
@@ -592,11 +657,13 @@ fn test_support_protocol() {
         remote,
         protocol: _,
         info: _,
+        version,
         notif_protocols,
         rpc_protocols,
     } = open_event2
     {
         assert_eq!(&remote, service1.peer_id());
+        assert_eq!(version, crate::protocol::CURRENT_VERSION);
         assert_eq!(notif_protocols.len(), 2);
         assert_eq!(rpc_protocols.len(), 1);
     } else {
@@ -8,6 +8,7 @@ use std::fmt;
 use std::str::FromStr;
 
 pub mod network_state;
+pub mod permission;
 
 pub use libp2p::core::{identity, multiaddr, Multiaddr, PeerId, PublicKey};
 pub use libp2p::request_response::{InboundFailure, OutboundFailure};
@@ -0,0 +1,128 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer permission of permissioned network.
+//! A peer can join a permissioned network if it is in the allowed peers, or it presents an
+//! identity signed by the network CA in the handshake.
+//! The identity is bound to the chain id, so it can not be used on another network which
+//! shares the same CA.
+
+use crate::PeerId;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use starcoin_crypto::hash::{CryptoHash, CryptoHasher};
+use starcoin_crypto::{Signature, SigningKey};
+use std::collections::HashSet;
+
+/// The message signed by the network CA to grant a peer to join the network of `chain_id`.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, CryptoHash)]
+pub struct PeerIdentity {
+    chain_id: u8,
+    peer_id: Vec<u8>,
+}
+
+impl PeerIdentity {
+    pub fn new(chain_id: u8, peer_id: &PeerId) -> Self {
+        Self {
+            chain_id,
+            peer_id: peer_id.to_bytes(),
+        }
+    }
+
+    /// Sign the identity of `peer_id` on the network of `chain_id` by the network CA private key.
+    pub fn sign(
+        chain_id: u8,
+        peer_id: &PeerId,
+        ca_private_key: &Ed25519PrivateKey,
+    ) -> Ed25519Signature {
+        ca_private_key.sign(&Self::new(chain_id, peer_id))
+    }
+
+    /// Verify the identity of `peer_id` on the network of `chain_id` is signed by the network CA.
+    pub fn verify(
+        chain_id: u8,
+        peer_id: &PeerId,
+        identity: &Ed25519Signature,
+        ca_public_key: &Ed25519PublicKey,
+    ) -> bool {
+        identity
+            .verify(&Self::new(chain_id, peer_id), ca_public_key)
+            .is_ok()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PermissionConfig {
+    /// The public key of the network CA, peers should present identity signed by it.
+    pub network_ca: Option<Ed25519PublicKey>,
+    /// The peers allowed to connect without identity.
+    pub allowed_peers: HashSet<PeerId>,
+    /// The identity of the local node signed by the network CA.
+    pub identity: Option<Ed25519Signature>,
+}
+
+impl PermissionConfig {
+    /// Check whether the peer is permitted to connect to the network of `chain_id`, return the
+    /// reason if rejected.
+    pub fn check(
+        &self,
+        chain_id: u8,
+        peer_id: &PeerId,
+        identity: Option<&Ed25519Signature>,
+    ) -> Result<(), String> {
+        if self.allowed_peers.contains(peer_id) {
+            return Ok(());
+        }
+        match (self.network_ca.as_ref(), identity) {
+            (Some(ca), Some(identity)) => {
+                if PeerIdentity::verify(chain_id, peer_id, identity, ca) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "identity is not signed by the network CA for chain {}",
+                        chain_id
+                    ))
+                }
+            }
+            (Some(_), None) => Err("identity is absent".to_string()),
+            (None, _) => Err("peer is not in the allowed peers".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::keygen::KeyGen;
+
+    #[test]
+    fn test_permission_check() {
+        let (ca_key, ca_public_key) = KeyGen::from_os_rng().generate_keypair();
+        let (other_ca_key, _) = KeyGen::from_os_rng().generate_keypair();
+        let peer_id = PeerId::random();
+        let allowed_peer_id = PeerId::random();
+        let config = PermissionConfig {
+            network_ca: Some(ca_public_key),
+            allowed_peers: vec![allowed_peer_id].into_iter().collect(),
+            identity: None,
+        };
+
+        let chain_id = 254;
+        let identity = PeerIdentity::sign(chain_id, &peer_id, &ca_key);
+        assert!(config.check(chain_id, &peer_id, Some(&identity)).is_ok());
+        assert!(config.check(chain_id, &peer_id, None).is_err());
+        assert!(config
+            .check(chain_id, &PeerId::random(), Some(&identity))
+            .is_err());
+        // the identity can not be used on another network with the same CA.
+        assert!(config.check(1, &peer_id, Some(&identity)).is_err());
+        assert!(config
+            .check(
+                chain_id,
+                &peer_id,
+                Some(&PeerIdentity::sign(chain_id, &peer_id, &other_ca_key))
+            )
+            .is_err());
+        assert!(config.check(chain_id, &allowed_peer_id, None).is_ok());
+    }
+}
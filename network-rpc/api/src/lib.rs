@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::AccumulatorNode;
 use starcoin_crypto::HashValue;
+use starcoin_state_api::snapshot::SnapshotInfo;
 use starcoin_state_api::StateWithProof;
 use starcoin_state_tree::StateNode;
 use starcoin_types::access_path::AccessPath;
@@ -31,6 +32,7 @@ pub const MAX_BLOCK_HEADER_REQUEST_SIZE: u64 = 1000;
 pub const MAX_TXN_REQUEST_SIZE: u64 = 1000;
 pub const MAX_BLOCK_INFO_REQUEST_SIZE: u64 = 1000;
 pub const MAX_BLOCK_IDS_REQUEST_SIZE: u64 = 10000;
+pub const MAX_STATE_SNAPSHOT_CHUNK_SIZE: u64 = 1024 * 1024;
 
 pub static RPC_INFO: Lazy<RpcInfo> = Lazy::new(|| RpcInfo::new(gen_client::get_rpc_info()));
 
@@ -184,6 +186,27 @@ impl RpcRequest for GetBlockIds {
     }
 }
 
+/// Get a chunk of the state snapshot file at block `block_hash`, see `get_state_snapshots`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetStateSnapshotChunk {
+    pub block_hash: HashValue,
+    pub offset: u64,
+    pub max_size: u64,
+}
+
+impl RpcRequest for GetStateSnapshotChunk {
+    fn verify(&self) -> Result<()> {
+        if self.max_size > MAX_STATE_SNAPSHOT_CHUNK_SIZE {
+            return Err(NetRpcError::new(
+                RpcErrorCode::BadRequest,
+                format!("max_size is too big > {}", MAX_STATE_SNAPSHOT_CHUNK_SIZE),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct Ping {
     ///ping message, return same msg.
@@ -278,4 +301,19 @@ pub trait NetworkRpc: Sized + Send + Sync + 'static {
         peer_id: PeerId,
         ids: Vec<HashValue>,
     ) -> BoxFuture<Result<Vec<Option<Block>>>>;
+
+    /// Get the state snapshots retained by the peer with block number >= `min_block_number`,
+    /// the `file` of the returned snapshot is the file name, without the peer's local dir.
+    fn get_state_snapshots(
+        &self,
+        peer_id: PeerId,
+        min_block_number: BlockNumber,
+    ) -> BoxFuture<Result<Vec<SnapshotInfo>>>;
+
+    /// Get a chunk of the state snapshot file, return empty if the offset reaches the end of file.
+    fn get_state_snapshot_chunk(
+        &self,
+        peer_id: PeerId,
+        req: GetStateSnapshotChunk,
+    ) -> BoxFuture<Result<Vec<u8>>>;
 }
@@ -22,6 +22,7 @@ use starcoin_service_registry::{
 use starcoin_state_service::ChainStateService;
 use starcoin_storage::{Storage, Store};
 use starcoin_types::peer_info::{PeerId, RpcInfo};
+use std::path::PathBuf;
 use std::sync::Arc;
 use txpool::TxPoolService;
 
@@ -54,8 +55,15 @@ impl NetworkRpcService {
         txpool_service: TxPoolService,
         state_service: ServiceRef<ChainStateService>,
         quotas: NetworkRpcQuotaConfiguration,
+        snapshot_dir: PathBuf,
     ) -> Self {
-        let rpc_impl = NetworkRpcImpl::new(storage, chain_service, txpool_service, state_service);
+        let rpc_impl = NetworkRpcImpl::new(
+            storage,
+            chain_service,
+            txpool_service,
+            state_service,
+            snapshot_dir,
+        );
         let rpc_server = NetworkRpcServer::new(rpc_impl.to_delegate());
 
         let limiters = ApiLimiters::new(
@@ -93,6 +101,7 @@ impl ServiceFactory<Self> for NetworkRpcService {
             txpool_service,
             state_service,
             quotas,
+            node_config.snapshot.dir(),
         ))
     }
 }
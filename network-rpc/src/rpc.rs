@@ -10,11 +10,12 @@ use network_rpc_core::NetRpcError;
 use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_network_rpc_api::{
     gen_server, BlockBody, GetAccountState, GetAccumulatorNodeByNodeHash, GetBlockHeadersByNumber,
-    GetBlockIds, GetStateWithProof, GetTxnsWithHash, GetTxnsWithSize, Ping, RpcRequest,
-    MAX_BLOCK_HEADER_REQUEST_SIZE, MAX_BLOCK_INFO_REQUEST_SIZE, MAX_BLOCK_REQUEST_SIZE,
+    GetBlockIds, GetStateSnapshotChunk, GetStateWithProof, GetTxnsWithHash, GetTxnsWithSize, Ping,
+    RpcRequest, MAX_BLOCK_HEADER_REQUEST_SIZE, MAX_BLOCK_INFO_REQUEST_SIZE, MAX_BLOCK_REQUEST_SIZE,
     MAX_TXN_REQUEST_SIZE,
 };
 use starcoin_service_registry::ServiceRef;
+use starcoin_state_api::snapshot::{load_snapshot_index, SnapshotInfo};
 use starcoin_state_api::{ChainStateAsyncService, StateWithProof};
 use starcoin_state_service::ChainStateService;
 use starcoin_storage::Store;
@@ -26,6 +27,9 @@ use starcoin_types::{
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
 };
 use state_tree::StateNode;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
 use std::sync::Arc;
 use txpool::TxPoolService;
 use txpool_api::TxPoolSyncService;
@@ -35,6 +39,7 @@ pub struct NetworkRpcImpl {
     chain_service: ServiceRef<ChainReaderService>,
     txpool_service: TxPoolService,
     state_service: ServiceRef<ChainStateService>,
+    snapshot_dir: PathBuf,
 }
 
 impl NetworkRpcImpl {
@@ -43,12 +48,14 @@ impl NetworkRpcImpl {
         chain_service: ServiceRef<ChainReaderService>,
         txpool: TxPoolService,
         state_service: ServiceRef<ChainStateService>,
+        snapshot_dir: PathBuf,
     ) -> Self {
         Self {
             chain_service,
             txpool_service: txpool,
             storage,
             state_service,
+            snapshot_dir,
         }
     }
 }
@@ -309,4 +316,47 @@ impl gen_server::NetworkRpc for NetworkRpcImpl {
         };
         Box::pin(fut)
     }
+
+    fn get_state_snapshots(
+        &self,
+        _peer_id: PeerId,
+        min_block_number: BlockNumber,
+    ) -> BoxFuture<Result<Vec<SnapshotInfo>>> {
+        let snapshot_dir = self.snapshot_dir.clone();
+        let fut = async move {
+            Ok(load_snapshot_index(snapshot_dir.as_path())?
+                .into_iter()
+                .filter(|s| s.block_number >= min_block_number)
+                .map(|mut s| {
+                    s.file = s.file.file_name().map(PathBuf::from).unwrap_or_default();
+                    s
+                })
+                .collect())
+        };
+        Box::pin(fut)
+    }
+
+    fn get_state_snapshot_chunk(
+        &self,
+        _peer_id: PeerId,
+        req: GetStateSnapshotChunk,
+    ) -> BoxFuture<Result<Vec<u8>>> {
+        let snapshot_dir = self.snapshot_dir.clone();
+        let fut = async move {
+            req.verify()?;
+            // only the retained snapshots in the index are served.
+            let snapshot = load_snapshot_index(snapshot_dir.as_path())?
+                .into_iter()
+                .find(|s| s.block_hash == req.block_hash)
+                .ok_or_else(|| {
+                    NetRpcError::client_err(format!("state snapshot {} not found", req.block_hash))
+                })?;
+            let mut file = File::open(snapshot.file.as_path())?;
+            file.seek(SeekFrom::Start(req.offset))?;
+            let mut chunk = vec![];
+            file.take(req.max_size).read_to_end(&mut chunk)?;
+            Ok(chunk)
+        };
+        Box::pin(fut)
+    }
 }
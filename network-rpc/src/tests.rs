@@ -6,8 +6,8 @@ use config::*;
 use futures::executor::block_on;
 use starcoin_logger::prelude::*;
 use starcoin_network_rpc_api::{
-    gen_client as starcoin_gen_client, GetBlockHeadersByNumber, GetBlockIds, GetStateWithProof,
-    Ping,
+    gen_client as starcoin_gen_client, GetBlockHeadersByNumber, GetBlockIds, GetStateSnapshotChunk,
+    GetStateWithProof, Ping, MAX_STATE_SNAPSHOT_CHUNK_SIZE,
 };
 use starcoin_node::NodeHandle;
 use starcoin_state_api::StateWithProof;
@@ -109,6 +109,26 @@ fn test_network_rpc() {
     });
     assert_eq!(2, blocks.len());
 
+    // the state snapshot is not enabled by default.
+    let snapshots = block_on(async {
+        client
+            .get_state_snapshots(peer_id_2.clone(), 0)
+            .await
+            .unwrap()
+    });
+    assert!(snapshots.is_empty());
+    let req = GetStateSnapshotChunk {
+        block_hash: blocks[0].as_ref().unwrap().id(),
+        offset: 0,
+        max_size: MAX_STATE_SNAPSHOT_CHUNK_SIZE,
+    };
+    let chunk = block_on(async {
+        client
+            .get_state_snapshot_chunk(peer_id_2.clone(), req)
+            .await
+    });
+    assert!(chunk.is_err(), "expect snapshot not found");
+
     handle2.stop().unwrap();
     handle1.stop().unwrap();
 }
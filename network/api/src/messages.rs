@@ -351,3 +351,27 @@ pub struct GetSelfPeer;
 impl ServiceRequest for GetSelfPeer {
     type Response = PeerInfo;
 }
+
+/// The traffic statistics of a connected peer, counted from the notification messages.
+#[derive(Eq, PartialEq, Deserialize, Serialize, Clone, Debug)]
+pub struct PeerTraffic {
+    /// The peer info, the chain status in it is updated by the latest block the peer announced.
+    pub peer_info: PeerInfo,
+    /// The protocol version negotiated in the handshake.
+    pub version: u32,
+    /// The bytes of the notification messages received from the peer.
+    pub bytes_in: u64,
+    /// The bytes of the notification messages sent to the peer.
+    pub bytes_out: u64,
+    /// The unix timestamp in milliseconds when the peer connected.
+    pub connected_at: u64,
+    /// The unix timestamp in milliseconds of the latest message received from the peer.
+    pub last_received_at: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GetPeersTraffic;
+
+impl ServiceRequest for GetPeersTraffic {
+    type Response = Vec<PeerTraffic>;
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::broadcast_score_metrics::BROADCAST_SCORE_METRICS;
+use crate::helper::get_unix_ts_as_millis;
 use crate::network_metrics::NetworkMetrics;
 use crate::{build_network_worker, Announcement};
 use anyhow::{format_err, Result};
@@ -11,8 +12,8 @@ use futures::FutureExt;
 use log::{debug, error, info, trace};
 use lru::LruCache;
 use network_api::messages::{
-    AnnouncementType, GetPeerById, GetPeerSet, GetSelfPeer, NotificationMessage, PeerEvent,
-    PeerMessage, PeerReputations, ReportReputation, TransactionsMessage,
+    AnnouncementType, GetPeerById, GetPeerSet, GetPeersTraffic, GetSelfPeer, NotificationMessage,
+    PeerEvent, PeerMessage, PeerReputations, PeerTraffic, ReportReputation, TransactionsMessage,
 };
 use network_api::peer_score::{BlockBroadcastEntry, HandleState, LinearScore, Score};
 use network_api::{BroadcastProtocolFilter, NetworkActor, PeerMessageHandler};
@@ -122,6 +123,7 @@ impl EventHandler<Self, Event> for NetworkActorService {
                 remote,
                 protocol,
                 info,
+                version,
                 notif_protocols,
                 rpc_protocols,
             } => {
@@ -132,8 +134,13 @@ impl EventHandler<Self, Event> for NetworkActorService {
                     remote, protocol, notif_protocols, rpc_protocols
                 );
                 let peer_event = PeerEvent::Open(remote.clone().into(), info.clone());
-                self.inner
-                    .on_peer_connected(remote.into(), *info, notif_protocols, rpc_protocols);
+                self.inner.on_peer_connected(
+                    remote.into(),
+                    *info,
+                    version,
+                    notif_protocols,
+                    rpc_protocols,
+                );
                 ctx.broadcast(peer_event);
             }
             Event::NotificationStreamClosed { remote, .. } => {
@@ -257,6 +264,20 @@ impl ServiceHandler<Self, GetPeerById> for NetworkActorService {
     }
 }
 
+impl ServiceHandler<Self, GetPeersTraffic> for NetworkActorService {
+    fn handle(
+        &mut self,
+        _msg: GetPeersTraffic,
+        _ctx: &mut ServiceContext<NetworkActorService>,
+    ) -> <GetPeersTraffic as ServiceRequest>::Response {
+        self.inner
+            .peers
+            .values()
+            .map(|peer| peer.get_traffic())
+            .collect()
+    }
+}
+
 impl ServiceHandler<Self, GetSelfPeer> for NetworkActorService {
     fn handle(
         &mut self,
@@ -277,20 +298,51 @@ pub struct Peer {
     known_transactions: LruCache<HashValue, ()>,
     /// Holds a set of blocks known to this peer.
     known_blocks: LruCache<HashValue, ()>,
+    /// The protocol version negotiated in the handshake.
+    version: u32,
+    bytes_in: u64,
+    bytes_out: u64,
+    connected_at: u64,
+    last_received_at: Option<u64>,
 }
 
 impl Peer {
-    fn new(peer_info: PeerInfo) -> Self {
+    fn new(peer_info: PeerInfo, version: u32) -> Self {
         Self {
             peer_info,
             known_blocks: LruCache::new(LRU_CACHE_SIZE),
             known_transactions: LruCache::new(LRU_CACHE_SIZE),
+            version,
+            bytes_in: 0,
+            bytes_out: 0,
+            connected_at: get_unix_ts_as_millis() as u64,
+            last_received_at: None,
         }
     }
 
     pub fn get_peer_info(&self) -> &PeerInfo {
         &self.peer_info
     }
+
+    fn on_received(&mut self, len: usize) {
+        self.bytes_in = self.bytes_in.saturating_add(len as u64);
+        self.last_received_at = Some(get_unix_ts_as_millis() as u64);
+    }
+
+    fn on_sent(&mut self, len: usize) {
+        self.bytes_out = self.bytes_out.saturating_add(len as u64);
+    }
+
+    pub fn get_traffic(&self) -> PeerTraffic {
+        PeerTraffic {
+            peer_info: self.peer_info.clone(),
+            version: self.version,
+            bytes_in: self.bytes_in,
+            bytes_out: self.bytes_out,
+            connected_at: self.connected_at,
+            last_received_at: self.last_received_at,
+        }
+    }
 }
 
 pub(crate) struct Inner {
@@ -333,7 +385,7 @@ impl Inner {
         Ok(Inner {
             config,
             network_service,
-            self_peer: Peer::new(self_info),
+            self_peer: Peer::new(self_info, network_p2p::protocol::CURRENT_VERSION),
             peers: HashMap::new(),
             peer_message_handler: Arc::new(peer_message_handler),
             metrics,
@@ -356,6 +408,7 @@ impl Inner {
         message: Bytes,
     ) -> Result<()> {
         if let Some(peer_info) = self.peers.get_mut(&peer_id) {
+            peer_info.on_received(message.len());
             let notification =
                 NotificationMessage::decode_notification(protocol.as_ref(), message.as_ref())?;
             let notification = match &notification {
@@ -473,6 +526,7 @@ impl Inner {
         &mut self,
         peer_id: PeerId,
         chain_info: ChainInfo,
+        version: u32,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
     ) {
@@ -490,12 +544,10 @@ impl Inner {
                 }
             })
             .or_insert_with(|| {
-                Peer::new(PeerInfo::new(
-                    peer_id,
-                    chain_info,
-                    notif_protocols,
-                    rpc_protocols,
-                ))
+                Peer::new(
+                    PeerInfo::new(peer_id, chain_info, notif_protocols, rpc_protocols),
+                    version,
+                )
             });
     }
 
@@ -533,6 +585,9 @@ impl Inner {
                 }
             }
         };
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.on_sent(data.len());
+        }
         self.network_service
             .write_notification(peer_id.into(), protocol_name, data);
     }
@@ -601,6 +656,7 @@ impl Inner {
                 for peer_id in selected_peers {
                     let peer = self.peers.get_mut(&peer_id).expect("peer should exists");
                     peer.known_blocks.put(id, ());
+                    peer.on_sent(message.len());
 
                     self.network_service.write_notification(
                         peer_id.into(),
@@ -686,6 +742,9 @@ impl Inner {
                         );
                         continue;
                     }
+                    if let Some(peer) = self.peers.get_mut(&peer_id) {
+                        peer.on_sent(data.len());
+                    }
                     self.network_service.write_notification(
                         peer_id.into(),
                         real_protocol_name,
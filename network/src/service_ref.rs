@@ -9,7 +9,7 @@ use futures::channel::oneshot::Receiver;
 use futures::future::BoxFuture;
 use futures::FutureExt;
 use log::warn;
-use network_api::messages::NotificationMessage;
+use network_api::messages::{GetPeersTraffic, NotificationMessage, PeerTraffic};
 use network_api::{NetworkService, PeerProvider, ReputationChange, SupportedRpcProtocol};
 use network_p2p_types::network_state::NetworkState;
 use network_p2p_types::{IfDisconnected, Multiaddr, RequestFailure};
@@ -19,6 +19,7 @@ use starcoin_types::peer_info::PeerId;
 use starcoin_types::peer_info::PeerInfo;
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
 
 //TODO Service registry should support custom service ref.
 #[derive(Clone)]
@@ -128,6 +129,14 @@ impl NetworkServiceRef {
             .map_err(|e| format_err!("{:?}", e))
     }
 
+    pub fn remove_peer(&self, peer_id: PeerId) {
+        self.network_service.remove_peer(peer_id.into())
+    }
+
+    pub fn ban_peer(&self, peer_id: PeerId, duration: Duration) {
+        self.network_service.ban_peer(peer_id.into(), duration)
+    }
+
     pub async fn network_state(&self) -> Result<NetworkState> {
         self.network_service
             .network_state()
@@ -151,4 +160,8 @@ impl NetworkServiceRef {
     pub async fn is_connected(&self, peer_id: PeerId) -> bool {
         self.network_service.is_connected(peer_id.into()).await
     }
+
+    pub async fn peers_traffic(&self) -> Result<Vec<PeerTraffic>> {
+        self.service_ref.send(GetPeersTraffic).await
+    }
 }
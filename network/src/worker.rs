@@ -101,7 +101,9 @@ pub fn build_network_worker(
         transport: transport_config,
         node_name,
         client_version: starcoin_config::APP_NAME_WITH_VERSION.clone(),
+        node_version: starcoin_config::CRATE_VERSION.to_string(),
         allow_non_globals_in_dht,
+        permission: network_config.permission_config(),
         ..NetworkConfiguration::default()
     };
     // protocol id is chain/{chain_id}, `RegisteredProtocol` will append `/starcoin` prefix
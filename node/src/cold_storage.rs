@@ -0,0 +1,181 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use starcoin_config::NodeConfig;
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_state_api::StateReaderExt;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::cold::ColdStorageMigrator;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_types::block::BlockNumber;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+/// The max number of blocks moved on one event, the service keeps moving by notifying itself until
+/// all the cold blocks are moved.
+const MIGRATE_BATCH_BLOCKS: u64 = 1000;
+
+/// Move the blocks, transactions and events older than the latest `cold_after_epochs` epochs to the
+/// cold db on the secondary storage path, if the `cold_db_dir` is configured. The epochs are read
+/// from the states of the old blocks, so the cold db can not be used with the pruning.
+pub struct ColdStorageService {
+    storage: Arc<Storage>,
+    migrator: Option<ColdStorageMigrator>,
+    cold_after_epochs: u64,
+}
+
+impl ColdStorageService {
+    /// The number of the last block before the latest `cold_after_epochs` epochs, None if there
+    /// are not enough epochs.
+    fn cold_block_boundary(&self) -> Result<Option<BlockNumber>> {
+        let chain_info = self
+            .storage
+            .get_chain_info()?
+            .ok_or_else(|| format_err!("Chain info is none."))?;
+        let mut state_root = chain_info.head().state_root();
+        let mut epoch_start = 0;
+        for _ in 0..self.cold_after_epochs {
+            epoch_start = ChainStateDB::new(self.storage.clone(), Some(state_root))
+                .get_epoch()?
+                .start_block_number();
+            if epoch_start == 0 {
+                return Ok(None);
+            }
+            state_root = self
+                .storage
+                .get_main_chain_block_header(epoch_start - 1)?
+                .ok_or_else(|| format_err!("Main chain block {} is none.", epoch_start - 1))?
+                .state_root();
+        }
+        Ok(Some(epoch_start - 1))
+    }
+
+    fn migrate(&self, ctx: &mut ServiceContext<Self>) {
+        let migrator = match self.migrator.as_ref() {
+            Some(migrator) => migrator,
+            None => return,
+        };
+        let result = self
+            .cold_block_boundary()
+            .and_then(|boundary| match boundary {
+                Some(boundary) => migrator.migrate(boundary, MIGRATE_BATCH_BLOCKS),
+                None => Ok(None),
+            });
+        match result {
+            Ok(Some(_)) => ctx.notify(MigrateColdStorageEvent),
+            Ok(None) => {}
+            Err(e) => error!("Move the old blocks to the cold db error: {:?}", e),
+        }
+    }
+}
+
+impl ServiceFactory<Self> for ColdStorageService {
+    fn create(ctx: &mut ServiceContext<ColdStorageService>) -> Result<ColdStorageService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        let migrator = ColdStorageMigrator::new(storage.clone());
+        let migrator = if config.storage.cold_db_dir().is_some() {
+            Some(migrator)
+        } else {
+            ensure!(
+                migrator.cold_block_number()?.is_none(),
+                "The old blocks have been moved to the cold db, the cold-db-dir must be set."
+            );
+            None
+        };
+        Ok(Self {
+            storage,
+            migrator,
+            cold_after_epochs: config.storage.cold_after_epochs(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MigrateColdStorageEvent;
+
+impl ActorService for ColdStorageService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.migrator.is_some() {
+            ctx.notify(MigrateColdStorageEvent);
+            ctx.run_interval(CHECK_INTERVAL, |ctx| ctx.notify(MigrateColdStorageEvent));
+        }
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, MigrateColdStorageEvent> for ColdStorageService {
+    fn handle_event(&mut self, _msg: MigrateColdStorageEvent, ctx: &mut ServiceContext<Self>) {
+        self.migrate(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_account_api::AccountInfo;
+    use starcoin_chain_mock::MockChain;
+    use starcoin_config::{temp_path, ChainNetwork, RocksdbConfig};
+    use starcoin_genesis::Genesis;
+    use starcoin_storage::db_storage::DBStorage;
+    use starcoin_storage::storage::StorageInstance;
+    use starcoin_storage::BLOCK_PREFIX_NAME;
+    use starcoin_types::startup_info::StartupInfo;
+
+    #[stest::test(timeout = 120)]
+    fn test_cold_storage_service() -> Result<()> {
+        let net = ChainNetwork::new_test();
+        let hot_dir = temp_path();
+        let cold_dir = temp_path();
+        let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(
+            DBStorage::new(hot_dir.path(), RocksdbConfig::default())?
+                .with_cold_db(DBStorage::new(cold_dir.path(), RocksdbConfig::default())?),
+        ))?);
+        let (chain_info, _) =
+            Genesis::init_and_check_storage(&net, storage.clone(), hot_dir.path())?;
+        let mut chain = MockChain::new_with_storage(
+            net.clone(),
+            storage.clone(),
+            chain_info.head().id(),
+            AccountInfo::random(),
+        )?;
+        // The head is in the third epoch.
+        let epoch_block_count = net.genesis_config().consensus_config.epoch_block_count;
+        chain.produce_and_apply_times(epoch_block_count * 2 + 2)?;
+        storage.save_startup_info(StartupInfo::new(chain.head().current_header().id()))?;
+
+        let service = |cold_after_epochs| ColdStorageService {
+            storage: storage.clone(),
+            migrator: Some(ColdStorageMigrator::new(storage.clone())),
+            cold_after_epochs,
+        };
+        assert_eq!(service(3).cold_block_boundary()?, None);
+        assert_eq!(
+            service(1).cold_block_boundary()?,
+            Some(epoch_block_count * 2 - 1)
+        );
+        let service = service(2);
+        let boundary = service.cold_block_boundary()?;
+        assert_eq!(boundary, Some(epoch_block_count - 1));
+
+        let migrator = service.migrator.as_ref().unwrap();
+        while migrator.migrate(boundary.unwrap(), 10)?.is_some() {}
+        assert_eq!(migrator.cold_block_number()?, boundary);
+        let hot_db = storage.db().unwrap();
+        for number in [1, epoch_block_count - 1, epoch_block_count].iter() {
+            let block_id = storage.get_main_chain_block_header(*number)?.unwrap().id();
+            // The moved blocks are read from the cold db.
+            assert!(storage.get_block_by_hash(block_id)?.is_some());
+            assert_eq!(
+                hot_db
+                    .get_raw(BLOCK_PREFIX_NAME, block_id.to_vec())?
+                    .is_some(),
+                *number >= epoch_block_count
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use chrono::{Timelike, Utc};
+use starcoin_config::{CompactionWindow, NodeConfig};
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::Storage;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Compact all the column families of the db in the configured off-peak hours, at most once per
+/// `compaction_interval`. The compaction runs in a dedicated thread, and is skipped if a manual
+/// compaction is running.
+pub struct DBCompactionService {
+    db: Option<Arc<DBStorage>>,
+    window: Option<CompactionWindow>,
+    interval: Duration,
+    last_compaction: Option<Instant>,
+}
+
+impl DBCompactionService {
+    pub fn new(
+        db: Option<Arc<DBStorage>>,
+        window: Option<CompactionWindow>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            db,
+            window,
+            interval,
+            last_compaction: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.db.is_some() && self.window.is_some()
+    }
+
+    fn try_compact(&mut self) {
+        let (db, window) = match (self.db.as_ref(), self.window) {
+            (Some(db), Some(window)) => (db, window),
+            _ => return,
+        };
+        if !window.contains(Utc::now().hour() as u8) || db.is_compacting() {
+            return;
+        }
+        if let Some(last) = self.last_compaction {
+            if last.elapsed() < self.interval {
+                return;
+            }
+        }
+        self.last_compaction = Some(Instant::now());
+        let db = db.clone();
+        std::thread::spawn(move || {
+            let begin = Instant::now();
+            match db.compact(None) {
+                Ok(cfs) => info!(
+                    "Scheduled db compaction of {} column families finished in {:?}",
+                    cfs.len(),
+                    begin.elapsed()
+                ),
+                Err(e) => warn!("Scheduled db compaction error: {:?}", e),
+            }
+        });
+    }
+}
+
+impl ServiceFactory<Self> for DBCompactionService {
+    fn create(ctx: &mut ServiceContext<DBCompactionService>) -> Result<DBCompactionService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        Ok(Self::new(
+            storage.db(),
+            config.storage.compaction_hours(),
+            config.storage.compaction_interval(),
+        ))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckCompactionEvent;
+
+impl ActorService for DBCompactionService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.is_enabled() {
+            ctx.run_interval(CHECK_INTERVAL, |ctx| ctx.notify(CheckCompactionEvent));
+        }
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, CheckCompactionEvent> for DBCompactionService {
+    fn handle_event(&mut self, _msg: CheckCompactionEvent, _ctx: &mut ServiceContext<Self>) {
+        self.try_compact();
+    }
+}
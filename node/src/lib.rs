@@ -26,7 +26,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Runtime;
 
+mod cold_storage;
 pub mod crash_handler;
+mod db_compaction;
 mod genesis_parameter_resolve;
 mod metrics;
 pub mod network_service_factory;
@@ -2,12 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use starcoin_config::NodeConfig;
+use once_cell::sync::Lazy;
+use starcoin_config::{NodeConfig, CRATE_VERSION};
+use starcoin_metrics::{register_int_gauge_vec, IntGaugeVec, Opts};
 use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+/// The node info as labels, the value is always 1, so other metrics can be grouped by the node info.
+pub static NODE_INFO: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        Opts::new("node_info", "node info, the value is always 1").namespace("starcoin"),
+        &["net", "chain_id", "fingerprint", "version"]
+    )
+    .expect("register node info metric should success.")
+});
+
+pub fn set_node_info(config: &NodeConfig, fingerprint: &str) {
+    NODE_INFO
+        .with_label_values(&[
+            config.net().id().to_string().as_str(),
+            config.net().chain_id().to_string().as_str(),
+            fingerprint,
+            CRATE_VERSION,
+        ])
+        .set(1);
+}
+
 #[derive(Clone)]
 pub struct MetricsActorService {
     push_url: String,
@@ -1,7 +1,9 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics::MetricsActorService;
+use crate::cold_storage::ColdStorageService;
+use crate::db_compaction::DBCompactionService;
+use crate::metrics::{set_node_info, MetricsActorService};
 use crate::network_service_factory::NetworkServiceFactory;
 use crate::peer_message_handler::NodePeerMessageHandler;
 use crate::rpc_service_factory::RpcServiceFactory;
@@ -34,10 +36,13 @@ use starcoin_service_registry::{
     ActorService, RegistryAsyncService, RegistryService, ServiceContext, ServiceFactory,
     ServiceHandler, ServiceRef,
 };
-use starcoin_state_service::ChainStateService;
+use starcoin_state_service::{
+    ArchiveIndexService, ChainStateService, StatePruningService, StateSnapshotService,
+};
 use starcoin_storage::cache_storage::CacheStorage;
 use starcoin_storage::db_storage::DBStorage;
 use starcoin_storage::errors::StorageInitError;
+use starcoin_storage::migration::migrate_on_start;
 use starcoin_storage::storage::StorageInstance;
 use starcoin_storage::Storage;
 use starcoin_stratum::service::{StratumService, StratumServiceFactory};
@@ -200,21 +205,48 @@ impl NodeService {
         registry.put_shared(logger_handle).await?;
 
         let bus = registry.service_ref::<BusService>().await?;
+        let encryption_key = config.storage.db_encryption_key()?;
+        let mut db = DBStorage::new_with_encryption(
+            config.storage.dir(),
+            config.storage.rocksdb_config(),
+            encryption_key,
+        )?;
+        if let Some(cold_db_dir) = config.storage.cold_db_dir() {
+            db = db.with_cold_db(DBStorage::new_with_encryption(
+                cold_db_dir,
+                config.storage.rocksdb_config(),
+                encryption_key,
+            )?);
+        }
         let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
-            CacheStorage::new_with_capacity(config.storage.cache_size()),
-            DBStorage::new(config.storage.dir(), config.storage.rocksdb_config())?,
+            CacheStorage::new_with_config(&config.storage)?,
+            db,
         ))?);
+        if let Some(db) = storage.db() {
+            migrate_on_start(db)?;
+        }
+        storage.check_storage_version()?;
         registry.put_shared(storage.clone()).await?;
         let (chain_info, genesis) =
             Genesis::init_and_check_storage(config.net(), storage.clone(), config.data_dir())?;
 
-        info!("Start node with chain info: {}", chain_info);
+        let fingerprint = config.fingerprint(genesis.block().id());
+        info!(
+            "Start node with chain info: {}, fingerprint: {}",
+            chain_info, fingerprint
+        );
+        set_node_info(&config, fingerprint.as_str());
 
         registry.put_shared(genesis).await?;
 
         let node_service = registry.register::<NodeService>().await?;
 
         registry.register::<ChainStateService>().await?;
+        registry.register::<StateSnapshotService>().await?;
+        registry.register::<StatePruningService>().await?;
+        registry.register::<ArchiveIndexService>().await?;
+        registry.register::<DBCompactionService>().await?;
+        registry.register::<ColdStorageService>().await?;
 
         let vault_config = &config.vault;
         let account_storage =
@@ -11,14 +11,17 @@ use starcoin_genesis::Genesis;
 use starcoin_logger::LoggerHandle;
 use starcoin_miner::MinerService;
 use starcoin_network::NetworkServiceRef;
+use starcoin_rpc_server::graphql::{build_schema, ChainData};
 use starcoin_rpc_server::module::{
     AccountRpcImpl, ChainRpcImpl, ContractRpcImpl, DebugRpcImpl, MinerRpcImpl,
-    NetworkManagerRpcImpl, NodeManagerRpcImpl, NodeRpcImpl, PubSubImpl, PubSubService,
-    StateRpcImpl, SyncManagerRpcImpl, TxPoolRpcImpl,
+    NetworkManagerRpcImpl, NodeAdminRpcImpl, NodeManagerRpcImpl, NodeRpcImpl, PubSubImpl,
+    PubSubService, StateRpcImpl, SyncManagerRpcImpl, TxPoolRpcImpl,
 };
 use starcoin_rpc_server::service::RpcService;
+use starcoin_rpc_server::ApiKeyMiddleware;
 use starcoin_service_registry::{ServiceContext, ServiceFactory};
 use starcoin_state_service::ChainStateService;
+use starcoin_storage::archive::AccountStateIndex;
 use starcoin_storage::Storage;
 use starcoin_sync::sync::SyncService;
 use starcoin_txpool::TxPoolService;
@@ -34,25 +37,47 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
         let storage = ctx.get_shared::<Arc<Storage>>()?;
         let log_handler = ctx.get_shared::<Arc<LoggerHandle>>()?;
         let network_service = ctx.get_shared::<NetworkServiceRef>()?;
-        let node_api = NodeRpcImpl::new(config.clone(), Some(network_service.clone()));
-        let node_manager_api = ctx
-            .service_ref_opt::<NodeService>()?
-            .map(|service_ref| NodeManagerRpcImpl::new(service_ref.clone()));
-        let sync_manager_api = ctx
-            .service_ref_opt::<SyncService>()?
-            .map(|service_ref| SyncManagerRpcImpl::new(service_ref.clone()));
+        let txpool_service = ctx.get_shared::<TxPoolService>()?;
+        let sync_service = ctx.service_ref_opt::<SyncService>()?.cloned();
+        let node_api = NodeRpcImpl::new(
+            config.clone(),
+            Some(network_service.clone()),
+            sync_service.clone(),
+            txpool_service.clone(),
+            genesis.block().id(),
+        );
+        let pubsub_service = ctx.service_ref::<PubSubService>()?.clone();
+        let node_manager_api = ctx.service_ref_opt::<NodeService>()?.map(|service_ref| {
+            NodeManagerRpcImpl::new(service_ref.clone(), pubsub_service.clone())
+        });
+        let sync_manager_api = sync_service.map(SyncManagerRpcImpl::new);
+        // The admin api rotates the keys of the middleware shared by the transports.
+        let api_key_middleware =
+            ApiKeyMiddleware::from_config(&config.rpc.api_keys, &config.rpc.api_quotas);
+        let node_admin_api = NodeAdminRpcImpl::new(
+            network_service.clone(),
+            log_handler.clone(),
+            storage.clone(),
+            api_key_middleware.clone(),
+        );
         let network_manager_api = NetworkManagerRpcImpl::new(network_service);
         let chain_api = ctx
             .service_ref_opt::<ChainReaderService>()?
             .map(|service_ref| {
                 ChainRpcImpl::new(config.clone(), genesis.block().id(), service_ref.clone())
             });
-        let txpool_service = ctx.get_shared::<TxPoolService>()?;
         let txpool_api = Some(TxPoolRpcImpl::new(txpool_service.clone()));
 
         let state_api = ctx
             .service_ref_opt::<ChainStateService>()?
-            .map(|service_ref| StateRpcImpl::new(service_ref.clone(), storage.clone()));
+            .map(|service_ref| {
+                let account_state_index = if config.storage.archive() {
+                    Some(AccountStateIndex::new(storage.clone()))
+                } else {
+                    None
+                };
+                StateRpcImpl::new(service_ref.clone(), storage.clone(), account_state_index)
+            });
         let chain_state_service = ctx.service_ref::<ChainStateService>()?.clone();
         let chain_service = ctx.service_ref::<ChainReaderService>()?.clone();
         let account_service = ctx.service_ref_opt::<AccountService>()?.cloned();
@@ -65,15 +90,19 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
                 chain_service.clone(),
             )
         });
-        let pubsub_service = ctx.service_ref::<PubSubService>()?.clone();
         let pubsub_api = Some(PubSubImpl::new(pubsub_service));
-        let debug_api = Some(DebugRpcImpl::new(config.clone(), log_handler));
+        let debug_api = Some(DebugRpcImpl::new(
+            config.clone(),
+            log_handler,
+            chain_service.clone(),
+            PlaygroudService::new(storage.clone()),
+        ));
         let miner_api = ctx
             .service_ref_opt::<MinerService>()?
             .map(|service_ref| MinerRpcImpl::new(service_ref.clone()));
 
         let contract_api = {
-            let dev_playground = PlaygroudService::new(storage);
+            let dev_playground = PlaygroudService::new(storage.clone());
 
             ContractRpcImpl::new(
                 config.clone(),
@@ -85,10 +114,29 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
             )
         };
 
-        Ok(RpcService::new_with_api(
+        let graphql_schema = if config.rpc.get_graphql_address().is_some() {
+            let data = ChainData {
+                chain: ctx.service_ref::<ChainReaderService>()?.clone(),
+                state: ctx.service_ref::<ChainStateService>()?.clone(),
+                state_store: storage.clone(),
+                block_query_max_range: config.rpc.block_query_max_range(),
+            };
+            Some(build_schema(
+                data,
+                config.rpc.graphql.max_depth(),
+                config.rpc.graphql.max_complexity(),
+            ))
+        } else {
+            None
+        };
+
+        let service = RpcService::new_with_api(
             config,
+            storage,
+            api_key_middleware,
             node_api,
             node_manager_api,
+            Some(node_admin_api),
             sync_manager_api,
             Some(network_manager_api),
             chain_api,
@@ -99,6 +147,10 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
             debug_api,
             miner_api,
             Some(contract_api),
-        ))
+        );
+        Ok(match graphql_schema {
+            Some(schema) => service.with_graphql(schema),
+            None => service,
+        })
     }
 }
@@ -7,8 +7,9 @@ pub use self::gen_client::Client as AccountClient;
 use crate::types::{StrView, TransactionRequest};
 use crate::FutureResult;
 use starcoin_account_api::AccountInfo;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
 use starcoin_types::account_address::AccountAddress;
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::sign_message::{SigningMessage, TypedMessage};
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 use starcoin_vm_types::token::token_code::TokenCode;
 
@@ -32,6 +33,25 @@ pub trait AccountApi {
     fn sign(&self, address: AccountAddress, data: SigningMessage)
         -> FutureResult<StrView<Vec<u8>>>;
 
+    /// Sign a schema described message with domain separation, return the AccountSignature bytes.
+    /// The domain chain_id must be the node's chain id.
+    #[rpc(name = "account.sign_typed_message")]
+    fn sign_typed_message(
+        &self,
+        address: AccountAddress,
+        message: TypedMessage,
+    ) -> FutureResult<StrView<Vec<u8>>>;
+
+    /// Verify the signature of a typed message, if the `address` is given,
+    /// also check the signer's public key matches the address's authentication key.
+    #[rpc(name = "account.verify_typed_message")]
+    fn verify_typed_message(
+        &self,
+        message: TypedMessage,
+        signature: StrView<Vec<u8>>,
+        address: Option<AccountAddress>,
+    ) -> FutureResult<bool>;
+
     /// sign a txn request, return hex encoded bcs_ext bytes of signed user txn.
     #[rpc(name = "account.sign_txn_request")]
     fn sign_txn_request(&self, txn_request: TransactionRequest) -> FutureResult<String>;
@@ -67,6 +87,16 @@ pub trait AccountApi {
     #[rpc(name = "account.export")]
     fn export(&self, address: AccountAddress, password: String) -> FutureResult<Vec<u8>>;
 
+    /// Update the local key shard of multisig account `address` to `new_public_key`,
+    /// after the account's auth key is rotated on chain. The address keeps unchanged.
+    #[rpc(name = "account.rotate_multisig_key")]
+    fn rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    ) -> FutureResult<AccountInfo>;
+
     #[rpc(name = "account.change_password")]
     // change account password, user need to unlock account first.
     fn change_account_password(
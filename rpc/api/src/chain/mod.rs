@@ -4,8 +4,10 @@
 pub use self::gen_client::Client as ChainClient;
 use crate::types::pubsub::EventFilter;
 use crate::types::{
-    BlockHeaderView, BlockSummaryView, BlockView, ChainId, ChainInfoView, EpochUncleSummaryView,
-    TransactionEventView, TransactionInfoView, TransactionView,
+    BlockHeaderView, BlockSummaryView, BlockView, ChainId, ChainInfoView, EpochInfoView,
+    EpochUncleSummaryView, EventCursorView, EventPageView, GetBlockOption, GetBlocksOption,
+    GetTransactionOption, TransactionEventView, TransactionInfoView, TransactionInfoWithProofView,
+    TransactionView,
 };
 use crate::FutureResult;
 use jsonrpc_core::Result;
@@ -22,25 +24,40 @@ pub trait ChainApi {
     /// Get main chain info
     #[rpc(name = "chain.info")]
     fn info(&self) -> FutureResult<ChainInfoView>;
-    /// Get chain block info
+    /// Get chain block info, the bcs encoded block is returned if `raw` of the `option` is true.
     #[rpc(name = "chain.get_block_by_hash")]
-    fn get_block_by_hash(&self, block_hash: HashValue) -> FutureResult<Option<BlockView>>;
-    /// Get chain blocks by number
+    fn get_block_by_hash(
+        &self,
+        block_hash: HashValue,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>>;
+    /// Get chain blocks by number, the bcs encoded block is returned if `raw` of the `option` is true.
     #[rpc(name = "chain.get_block_by_number")]
-    fn get_block_by_number(&self, number: BlockNumber) -> FutureResult<Option<BlockView>>;
+    fn get_block_by_number(
+        &self,
+        number: BlockNumber,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>>;
     /// Get latest `count` blocks before `number`. if `number` is absent, use head block number.
+    /// If `reverse` of the `option` is false, get `count` blocks from `number` in ascending order,
+    /// and `number` defaults to the genesis block number. The block txns are only hashes unless
+    /// `full_txns` of the `option` is true.
     #[rpc(name = "chain.get_blocks_by_number")]
     fn get_blocks_by_number(
         &self,
         number: Option<BlockNumber>,
         count: u64,
+        option: Option<GetBlocksOption>,
     ) -> FutureResult<Vec<BlockView>>;
     #[rpc(name = "chain.get_block_info_by_number")]
     fn get_block_info_by_number(&self, number: BlockNumber) -> FutureResult<Option<BlockInfo>>;
-    /// Get chain transactions
+    /// Get chain transactions, the bcs encoded txn is returned if `raw` of the `option` is true.
     #[rpc(name = "chain.get_transaction")]
-    fn get_transaction(&self, transaction_hash: HashValue)
-        -> FutureResult<Option<TransactionView>>;
+    fn get_transaction(
+        &self,
+        transaction_hash: HashValue,
+        option: Option<GetTransactionOption>,
+    ) -> FutureResult<Option<TransactionView>>;
     /// Get chain transactions
     #[rpc(name = "chain.get_transaction_info")]
     fn get_transaction_info(
@@ -60,6 +77,16 @@ pub trait ChainApi {
         idx: u64,
     ) -> FutureResult<Option<TransactionInfoView>>;
 
+    /// Get the txn info of a txn at `idx` of block `block_hash`, with the proof of its inclusion
+    /// in the txn accumulator of the block, so the clients can verify the txn and its events
+    /// by the block header without trusting the node.
+    #[rpc(name = "chain.get_transaction_proof")]
+    fn get_transaction_proof(
+        &self,
+        block_hash: HashValue,
+        idx: u64,
+    ) -> FutureResult<Option<TransactionInfoWithProofView>>;
+
     #[rpc(name = "chain.get_events_by_txn_hash")]
     fn get_events_by_txn_hash(
         &self,
@@ -69,6 +96,20 @@ pub trait ChainApi {
     #[rpc(name = "chain.get_events")]
     fn get_events(&self, filter: EventFilter) -> FutureResult<Vec<TransactionEventView>>;
 
+    /// Get at most `limit` events on main chain after the `cursor`, start from genesis if `cursor` is absent.
+    /// The cursor of every event and the `next_cursor` of the page are opaque and monotonic,
+    /// the consumer should persist the cursor after the events are processed, and resume from it.
+    /// Only `rpc.block_query_max_range` blocks are scanned in one call, so a page may be empty with a new `next_cursor`.
+    /// If the block of the `cursor` has been reverted by a reorg, an invalid params error is returned,
+    /// the events after the reorg point may have changed, the consumer should rollback to a cursor
+    /// at or before the common ancestor, and resume from it.
+    #[rpc(name = "chain.get_events_after")]
+    fn get_events_after(
+        &self,
+        cursor: Option<EventCursorView>,
+        limit: u64,
+    ) -> FutureResult<EventPageView>;
+
     /// Get current epoch info.
     #[rpc(name = "chain.epoch")]
     fn current_epoch(&self) -> FutureResult<EpochInfo>;
@@ -77,6 +118,16 @@ pub trait ChainApi {
     #[rpc(name = "chain.get_epoch_info_by_number")]
     fn get_epoch_info_by_number(&self, number: BlockNumber) -> FutureResult<EpochInfo>;
 
+    /// Get the infos of latest `count` epochs in descending order, from the epoch of the block `number`,
+    /// or from the current epoch if `number` is absent. The data of an ended epoch is read at its last block.
+    /// At most `rpc.block_query_max_range` epochs are returned in one call.
+    #[rpc(name = "chain.get_epoch_infos_by_number")]
+    fn get_epoch_infos_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> FutureResult<Vec<EpochInfoView>>;
+
     /// Get global time by number.
     #[rpc(name = "chain.get_global_time_by_number")]
     fn get_global_time_by_number(&self, number: BlockNumber) -> FutureResult<GlobalTimeOnChain>;
@@ -2,31 +2,79 @@ use jsonrpc_derive::rpc;
 
 pub use self::gen_client::Client as ContractClient;
 use crate::types::{
-    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunTransactionRequest,
-    StrView, TransactionOutputView,
+    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunOutputView,
+    DryRunTransactionRequest, FunctionIdView, StateRootOption, StrView,
 };
 use crate::FutureResult;
 use starcoin_vm_types::account_address::AccountAddress;
 use starcoin_vm_types::language_storage::{ModuleId, StructTag};
+use starcoin_vm_types::module_abi::{CompiledModuleABI, ModuleABI};
+use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
+use starcoin_vm_types::transaction::ScriptFunctionABI;
 
 #[rpc]
 pub trait ContractApi {
-    /// get code of module
+    /// get code of module, at the latest state if `state_root` is not set.
     #[rpc(name = "contract.get_code")]
-    fn get_code(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<StrView<Vec<u8>>>>;
+    fn get_code(
+        &self,
+        module_id: StrView<ModuleId>,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Option<StrView<Vec<u8>>>>;
+
+    /// get the disassembled code of module, with the bytecode of its functions,
+    /// at the latest state if `state_root` is not set.
+    #[rpc(name = "contract.get_code_disassembled")]
+    fn get_code_disassembled(
+        &self,
+        module_id: StrView<ModuleId>,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Option<String>>;
 
-    /// get resource data of `addr`
+    /// get resource data of `addr`, at the latest state if `state_root` is not set.
     #[rpc(name = "contract.get_resource")]
     fn get_resource(
         &self,
         addr: AccountAddress,
         resource_type: StrView<StructTag>,
+        state_root: Option<StateRootOption>,
     ) -> FutureResult<Option<AnnotatedMoveStructView>>;
 
     /// Call a move contract, return returned move values.
+    /// The contract is called on the latest state if `state_root` is not set.
     #[rpc(name = "contract.call")]
-    fn call(&self, call: ContractCall) -> FutureResult<Vec<AnnotatedMoveValueView>>;
+    fn call(
+        &self,
+        call: ContractCall,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Vec<AnnotatedMoveValueView>>;
 
+    /// Dry run a txn on the latest state, the resources of the write set and the events in the output are decoded,
+    /// and the gas used is split by category.
     #[rpc(name = "contract.dry_run")]
-    fn dry_run(&self, txn: DryRunTransactionRequest) -> FutureResult<TransactionOutputView>;
+    fn dry_run(&self, txn: DryRunTransactionRequest) -> FutureResult<DryRunOutputView>;
+
+    /// Dry run the hex of a bcs encoded `RawUserTransaction` on the latest state, with the sender's public key.
+    #[rpc(name = "contract.dry_run_raw")]
+    fn dry_run_raw(
+        &self,
+        raw_txn: String,
+        sender_public_key: StrView<AccountPublicKey>,
+    ) -> FutureResult<DryRunOutputView>;
+
+    /// Get the ABI attached to the module by the `ABIRegistry` at the module address.
+    #[rpc(name = "contract.get_abi")]
+    fn get_abi(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<ModuleABI>>;
+
+    /// Get the ABI parsed from the module code on chain, includes the exposed functions and the struct layouts.
+    #[rpc(name = "contract.get_module_abi")]
+    fn get_module_abi(
+        &self,
+        module_id: StrView<ModuleId>,
+    ) -> FutureResult<Option<CompiledModuleABI>>;
+
+    /// Resolve the ABI of a script function, the ABI attached by the `ABIRegistry` is preferred,
+    /// otherwise resolve from the module code, and the args are named by position.
+    #[rpc(name = "contract.resolve_function")]
+    fn resolve_function(&self, function_id: FunctionIdView) -> FutureResult<ScriptFunctionABI>;
 }
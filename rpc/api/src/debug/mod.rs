@@ -3,10 +3,12 @@
 
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use starcoin_crypto::HashValue;
 use starcoin_logger::LogPattern;
 
 pub use self::gen_client::Client as DebugClient;
-use crate::types::FactoryAction;
+use crate::types::{FactoryAction, TransactionTraceView};
+use crate::FutureResult;
 
 #[rpc]
 pub trait DebugApi {
@@ -29,4 +31,9 @@ pub trait DebugApi {
     /// Get and set txn factory status.
     #[rpc(name = "txfactory.status")]
     fn txfactory_status(&self, action: FactoryAction) -> Result<bool>;
+
+    /// Re-execute the txn on chain on the state before it, and trace the entry function,
+    /// the states it read and the gas used. Return None if the txn does not exist.
+    #[rpc(name = "debug.trace_transaction")]
+    fn trace_transaction(&self, txn_hash: HashValue) -> FutureResult<Option<TransactionTraceView>>;
 }
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpc_core::{Error, ErrorCode, Value};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::convert::TryFrom;
 use std::fmt;
 
 pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
@@ -11,3 +13,232 @@ pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
         data: Some(Value::String(format!("{:?}", details))),
     }
 }
+
+/// The category of a rpc error, every `RpcErrorCode` belongs to one category,
+/// the standard jsonrpc request errors belong to `Validation`, and unknown errors belong to `Internal`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorCategory {
+    /// The request or the transaction is invalid, retry with the same request will fail again.
+    Validation,
+    /// The transaction is rejected by the txpool, such as duplicated txn or sequence number too old.
+    TxPool,
+    /// Chain data related errors, such as the transaction is not found or failed to execute.
+    Chain,
+    /// The required state is not available on the node.
+    State,
+    /// Account related errors, such as the account is locked or the password is invalid.
+    Auth,
+    /// Unexpected errors of the node.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// The exit code of the cli when a command fails with an error of this category.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCategory::Validation => 10,
+            ErrorCategory::TxPool => 11,
+            ErrorCategory::Chain => 12,
+            ErrorCategory::State => 13,
+            ErrorCategory::Auth => 14,
+            ErrorCategory::Internal => 1,
+        }
+    }
+
+    /// Get the category of a jsonrpc error code.
+    pub fn from_code(code: &ErrorCode) -> Self {
+        match code {
+            ErrorCode::ParseError
+            | ErrorCode::InvalidRequest
+            | ErrorCode::MethodNotFound
+            | ErrorCode::InvalidParams => ErrorCategory::Validation,
+            ErrorCode::InternalError => ErrorCategory::Internal,
+            ErrorCode::ServerError(code) => RpcErrorCode::try_from(*code)
+                .map(RpcErrorCode::category)
+                .unwrap_or(ErrorCategory::Internal),
+        }
+    }
+
+    /// Get the category of a jsonrpc error, by the `RpcErrorCode` of the error if present.
+    pub fn from_error(err: &Error) -> Self {
+        RpcErrorCode::of_error(err)
+            .map(RpcErrorCode::category)
+            .unwrap_or_else(|| Self::from_code(&err.code))
+    }
+
+    /// Find the first jsonrpc error in the error chain, and return its category.
+    pub fn of_error(err: &anyhow::Error) -> Option<Self> {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<Error>())
+            .map(Self::from_error)
+    }
+}
+
+/// The key of the `RpcErrorCode` in the `data` object of an invalid params error.
+pub const ERROR_DATA_CODE_KEY: &str = "code";
+
+/// The stable error codes of starcoin rpc.
+/// The errors which are released with the standard invalid params code (`-32602`) keep that code,
+/// and the `RpcErrorCode` is added as the `code` field of the `data` object, see `to_invalid_params`.
+/// The other errors return the `RpcErrorCode` as the `code` of the jsonrpc error object.
+/// The new codes are grouped by category in ranges of 1000, a released code is never changed or reused.
+#[repr(i64)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum RpcErrorCode {
+    // Validation errors: -41000 ~ -41999
+    GasPriceTooLow = -41001,
+    GasTooLow = -41002,
+    GasLimitExceeded = -41003,
+    InsufficientBalance = -41004,
+    InvalidChainId = -41005,
+    InvalidSignature = -41006,
+    TxnNotAllowed = -41007,
+    TxnTooBig = -41008,
+    /// The subscription is rejected because the connection or the ip has too many active subscriptions.
+    SubscriptionLimitExceeded = -41009,
+
+    // TxPool errors: -42000 ~ -42999
+    TxnAlreadyImported = -42001,
+    SequenceNumberTooOld = -42002,
+    TooCheapToReplace = -42003,
+    TxnBanned = -42004,
+
+    // Chain errors: -43000 ~ -43999
+    TxnNotFound = -43001,
+
+    // Auth errors: -45000 ~ -45999
+    AccountNotExist = -45001,
+    AccountAlreadyExist = -45002,
+    AccountLocked = -45003,
+    InvalidPassword = -45004,
+    InvalidPrivateKey = -45005,
+    PrivateKeyMissing = -45006,
+    RemoveDefaultAccount = -45007,
+    TxnSignFailed = -45008,
+    /// The api key of the call is missing or invalid.
+    InvalidApiKey = -45009,
+    /// The method is not in the allowlist of the api key, of the anonymous calls or of the origin.
+    MethodNotAllowed = -45010,
+
+    // The released server error codes, which are not in the category ranges.
+    TxPoolFull = -50000,
+    /// The state is pruned or corrupt.
+    StateNotAvailable = -49999,
+    /// The txn is failed to execute, the vm status is in the error data.
+    TxnExecutionFailed = -49998,
+    AccountStoreError = -60000,
+    PublicModeForbidden = -59999,
+    /// The call is over the quota of the ip or of the api key.
+    RateLimitExceeded = -10000,
+}
+
+impl RpcErrorCode {
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            RpcErrorCode::TxPoolFull => ErrorCategory::TxPool,
+            RpcErrorCode::StateNotAvailable => ErrorCategory::State,
+            RpcErrorCode::TxnExecutionFailed => ErrorCategory::Chain,
+            RpcErrorCode::AccountStoreError => ErrorCategory::Internal,
+            RpcErrorCode::PublicModeForbidden => ErrorCategory::Auth,
+            RpcErrorCode::RateLimitExceeded => ErrorCategory::Auth,
+            code => match i64::from(code) {
+                -41999..=-41000 => ErrorCategory::Validation,
+                -42999..=-42000 => ErrorCategory::TxPool,
+                -43999..=-43000 => ErrorCategory::Chain,
+                -44999..=-44000 => ErrorCategory::State,
+                -45999..=-45000 => ErrorCategory::Auth,
+                _ => ErrorCategory::Internal,
+            },
+        }
+    }
+
+    /// Build a jsonrpc error with this code.
+    pub fn to_error(self, message: String, data: Option<Value>) -> Error {
+        Error {
+            code: ErrorCode::ServerError(self.into()),
+            message,
+            data,
+        }
+    }
+
+    /// Build a jsonrpc invalid params error, with this code in the `data` object.
+    pub fn to_invalid_params(self, message: String) -> Error {
+        let mut data = serde_json::Map::new();
+        data.insert(
+            ERROR_DATA_CODE_KEY.to_string(),
+            Value::from(i64::from(self)),
+        );
+        Error {
+            code: ErrorCode::InvalidParams,
+            message,
+            data: Some(Value::Object(data)),
+        }
+    }
+
+    /// Get the code of a jsonrpc error, return None if it's not a starcoin error.
+    pub fn of_error(err: &Error) -> Option<Self> {
+        match err.code {
+            ErrorCode::ServerError(code) => RpcErrorCode::try_from(code).ok(),
+            ErrorCode::InvalidParams => err
+                .data
+                .as_ref()
+                .and_then(|data| data.get(ERROR_DATA_CODE_KEY))
+                .and_then(Value::as_i64)
+                .and_then(|code| RpcErrorCode::try_from(code).ok()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_category() {
+        assert_eq!(
+            RpcErrorCode::SequenceNumberTooOld.category(),
+            ErrorCategory::TxPool
+        );
+        assert_eq!(
+            RpcErrorCode::InvalidPassword.category(),
+            ErrorCategory::Auth
+        );
+        let err = RpcErrorCode::StateNotAvailable.to_error("pruned".to_string(), None);
+        assert_eq!(err.code, ErrorCode::ServerError(-49999));
+        assert_eq!(
+            RpcErrorCode::of_error(&err),
+            Some(RpcErrorCode::StateNotAvailable)
+        );
+        assert_eq!(ErrorCategory::from_code(&err.code), ErrorCategory::State);
+        assert_eq!(
+            ErrorCategory::from_code(&ErrorCode::ServerError(-32000)),
+            ErrorCategory::Internal
+        );
+        assert_eq!(
+            ErrorCategory::from_code(&ErrorCode::ServerError(-10000)),
+            ErrorCategory::Auth
+        );
+        assert_eq!(RpcErrorCode::InvalidApiKey.category(), ErrorCategory::Auth);
+        assert_eq!(
+            ErrorCategory::from_code(&ErrorCode::InvalidParams),
+            ErrorCategory::Validation
+        );
+
+        // the released invalid params code is kept, and the code is in the data.
+        let old = RpcErrorCode::SequenceNumberTooOld.to_invalid_params("old".to_string());
+        assert_eq!(old.code, ErrorCode::InvalidParams);
+        assert_eq!(
+            RpcErrorCode::of_error(&old),
+            Some(RpcErrorCode::SequenceNumberTooOld)
+        );
+        assert_eq!(ErrorCategory::from_error(&old), ErrorCategory::TxPool);
+
+        let err: anyhow::Error = anyhow::Error::new(err).context("submit txn failed");
+        assert_eq!(ErrorCategory::of_error(&err), Some(ErrorCategory::State));
+        assert_eq!(
+            ErrorCategory::of_error(&anyhow::anyhow!("other error")),
+            None
+        );
+    }
+}
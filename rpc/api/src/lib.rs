@@ -14,6 +14,7 @@ pub mod metadata;
 pub mod miner;
 pub mod network_manager;
 pub mod node;
+pub mod node_admin;
 pub mod node_manager;
 pub mod pubsub;
 pub mod service;
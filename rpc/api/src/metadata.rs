@@ -2,23 +2,35 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpc_pubsub::{PubSubMetadata, Session};
+use parking_lot::RwLock;
 use std::sync::Arc;
 
 /// RPC methods metadata.
 #[derive(Clone, Default, Debug)]
 pub struct Metadata {
-    // /// Request origin
-    // pub origin: Origin,
+    /// The `Origin` header sent by the browsers.
+    pub origin: Option<String>,
     /// Request PubSub Session
     pub session: Option<Arc<Session>>,
     pub user: Option<String>,
+    /// The id of the persistent connection (ws, tcp, ipc), unique in the node process.
+    pub connection_id: Option<u64>,
+    /// The api key sent by the http client, or in the handshake of the websocket connection.
+    pub api_key: Option<String>,
+    /// The api key of the persistent connection (ws, tcp) authenticated by the `rpc.auth` call,
+    /// shared by the later calls of the connection.
+    pub connection_api_key: Option<Arc<RwLock<Option<String>>>>,
 }
 
 impl Metadata {
     pub fn new(session: Arc<Session>) -> Self {
         Self {
+            origin: None,
             session: Some(session),
             user: None,
+            connection_id: None,
+            api_key: None,
+            connection_api_key: None,
         }
     }
 }
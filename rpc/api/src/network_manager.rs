@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 pub use self::gen_client::Client as NetworkManagerClient;
-use crate::types::StrView;
+use crate::types::{PeerDetailView, StrView};
 use crate::FutureResult;
 use jsonrpc_derive::rpc;
 use network_p2p_types::network_state::NetworkState;
@@ -23,6 +23,11 @@ pub trait NetworkManagerApi {
     #[rpc(name = "network_manager.add_peer")]
     fn add_peer(&self, peer: String) -> FutureResult<()>;
 
+    /// Get the diagnostic detail of the connected peers, include the reputation, protocols,
+    /// version, round trip time, traffic and the latest block of every peer.
+    #[rpc(name = "network_manager.get_peers_detail")]
+    fn get_peers_detail(&self) -> FutureResult<Vec<PeerDetailView>>;
+
     /// Call peer's network rpc method.
     #[rpc(name = "network_manager.call")]
     fn call_peer(
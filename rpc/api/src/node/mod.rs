@@ -8,6 +8,10 @@ use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 use starcoin_config::ChainNetworkID;
+use starcoin_state_api::snapshot::SnapshotInfo;
+use starcoin_txpool_api::TxPoolStatus;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::sync_status::{SyncState, SyncStatus};
 use starcoin_vm_types::genesis_config::ConsensusStrategy;
 use std::collections::HashMap;
 
@@ -19,6 +23,10 @@ pub struct NodeInfo {
     pub net: ChainNetworkID,
     pub consensus: ConsensusStrategy,
     pub now_seconds: u64,
+    /// The fingerprint of the genesis and key configs, see `NodeConfig::fingerprint`.
+    /// It is empty if the node does not report it.
+    #[serde(default)]
+    pub fingerprint: String,
 }
 
 impl NodeInfo {
@@ -28,6 +36,7 @@ impl NodeInfo {
         net: ChainNetworkID,
         consensus: ConsensusStrategy,
         now_seconds: u64,
+        fingerprint: String,
     ) -> Self {
         Self {
             peer_info,
@@ -35,10 +44,95 @@ impl NodeInfo {
             net,
             consensus,
             now_seconds,
+            fingerprint,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+}
+
+/// The node health for load balancer health checks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeHealth {
+    pub status: HealthStatus,
+    /// Why the node is degraded, empty if the status is ok.
+    pub reasons: Vec<String>,
+    pub sync_state: SyncState,
+    pub current_number: BlockNumber,
+    /// The best block number known from peers, None if the node is not synchronizing.
+    pub target_number: Option<BlockNumber>,
+    /// How many blocks the local chain lags behind the target.
+    pub lag: u64,
+    pub peer_count: usize,
+    pub txpool_depth: usize,
+    /// The timestamp in milliseconds of the latest block.
+    pub last_block_timestamp: u64,
+    pub now_seconds: u64,
+}
+
+impl NodeHealth {
+    /// `require_peers` is false for the local nets, where a node without peers is still healthy.
+    pub fn new(
+        sync_status: &SyncStatus,
+        peer_count: usize,
+        txpool_status: &TxPoolStatus,
+        require_peers: bool,
+        now_seconds: u64,
+    ) -> Self {
+        let head = sync_status.chain_status().head();
+        let current_number = head.number();
+        let target_number = match sync_status.sync_status() {
+            SyncState::Synchronizing { target, .. } => Some(target.number()),
+            _ => None,
+        };
+        let lag = target_number
+            .map(|target| target.saturating_sub(current_number))
+            .unwrap_or_default();
+
+        let mut reasons = vec![];
+        if sync_status.is_prepare() {
+            reasons.push("sync status is not checked yet".to_string());
+        } else if !sync_status.is_nearly_synced() {
+            reasons.push(format!(
+                "chain is not nearly synced, lags {} blocks behind the target",
+                lag
+            ));
+        }
+        if require_peers && peer_count == 0 {
+            reasons.push("no connected peers".to_string());
+        }
+        if txpool_status.is_full {
+            reasons.push("txpool is full".to_string());
+        }
+        let status = if reasons.is_empty() {
+            HealthStatus::Ok
+        } else {
+            HealthStatus::Degraded
+        };
+        Self {
+            status,
+            reasons,
+            sync_state: sync_status.sync_status().clone(),
+            current_number,
+            target_number,
+            lag,
+            peer_count,
+            txpool_depth: txpool_status.txn_count,
+            last_block_timestamp: head.timestamp(),
+            now_seconds,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status == HealthStatus::Ok
+    }
+}
+
 #[rpc]
 pub trait NodeApi {
     /// Get node run status, just for api available check.
@@ -55,4 +149,14 @@ pub trait NodeApi {
 
     #[rpc(name = "node.metrics")]
     fn metrics(&self) -> Result<HashMap<String, String>>;
+
+    /// Get the state snapshots retained by the node, sorted by block number.
+    /// Return empty if the snapshot is not enabled or no snapshot is produced yet.
+    #[rpc(name = "node.snapshots")]
+    fn snapshots(&self) -> Result<Vec<SnapshotInfo>>;
+
+    /// Get the node health, include the sync state, peers and txpool, the status is DEGRADED
+    /// if the node is not nearly synced, has no peers, or the txpool is full.
+    #[rpc(name = "node.health")]
+    fn health(&self) -> FutureResult<NodeHealth>;
 }
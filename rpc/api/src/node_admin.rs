@@ -0,0 +1,58 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+pub use self::gen_client::Client as NodeAdminClient;
+use crate::types::{DBBackupView, StateDumpPageView};
+use crate::FutureResult;
+use jsonrpc_derive::rpc;
+use starcoin_crypto::HashValue;
+use std::collections::HashMap;
+
+/// The admin apis to manage the node at runtime. They are only served by ipc, or by the
+/// transports which require an api key for them.
+#[rpc]
+pub trait NodeAdminApi {
+    /// Add a reserved peer by its address with peer id, such as
+    /// `/ip4/127.0.0.1/tcp/9840/p2p/<peer_id>`.
+    #[rpc(name = "node_admin.add_peer")]
+    fn add_peer(&self, peer: String) -> FutureResult<()>;
+
+    /// Remove the peer from the reserved peers and the peer set, and disconnect from it.
+    #[rpc(name = "node_admin.remove_peer")]
+    fn remove_peer(&self, peer_id: String) -> FutureResult<()>;
+
+    /// Disconnect from the peer and refuse its connections for `duration` seconds.
+    #[rpc(name = "node_admin.ban_peer")]
+    fn ban_peer(&self, peer_id: String, duration: u64) -> FutureResult<()>;
+
+    /// Update log level, if logger_name is none, update global log level.
+    #[rpc(name = "node_admin.set_log_level")]
+    fn set_log_level(&self, logger_name: Option<String>, level: String) -> FutureResult<()>;
+
+    /// Compact the db column family, or all the column families if `cf` is none, return the
+    /// compacted column families. The call returns after the compaction finished.
+    #[rpc(name = "node_admin.compact_db")]
+    fn compact_db(&self, cf: Option<String>) -> FutureResult<Vec<String>>;
+
+    /// Take an incremental backup of the db to the `backup_dir` on the node's filesystem, the sst
+    /// files already backed up in the dir are shared. Use `starcoin db restore` to restore it.
+    #[rpc(name = "node_admin.backup_db")]
+    fn backup_db(&self, backup_dir: String) -> FutureResult<DBBackupView>;
+
+    /// Dump a page of the chain state at the `state_root`, at most `limit` accounts from the
+    /// account key hash `cursor`, the limit is capped by the server. Pass the returned
+    /// `next_cursor` to get the next page, it is none at the last page.
+    #[rpc(name = "node_admin.dump_state")]
+    fn dump_state(
+        &self,
+        state_root: HashValue,
+        cursor: Option<HashValue>,
+        limit: u64,
+    ) -> FutureResult<StateDumpPageView>;
+
+    /// Replace the secret of every configured api key with a new random secret, return the new
+    /// secrets by the key name. The new secrets are only kept in memory until the node restarts,
+    /// update the keys in the config file with them.
+    #[rpc(name = "node_admin.rotate_api_keys")]
+    fn rotate_api_keys(&self) -> FutureResult<HashMap<String, String>>;
+}
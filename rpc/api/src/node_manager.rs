@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 pub use self::gen_client::Client as NodeManagerClient;
+use crate::types::pubsub::SubscriptionView;
 use crate::FutureResult;
 use jsonrpc_derive::rpc;
 use starcoin_service_registry::{ServiceInfo, ServiceStatus};
@@ -22,4 +23,9 @@ pub trait NodeManagerApi {
 
     #[rpc(name = "node_manager.shutdown_system")]
     fn shutdown_system(&self) -> FutureResult<()>;
+
+    /// List the active pubsub subscriptions of all connections, with their age and delivery lag.
+    /// It is an admin api, so it is served with the node manager apis.
+    #[rpc(name = "node.subscriptions")]
+    fn subscriptions(&self) -> FutureResult<Vec<SubscriptionView>>;
 }
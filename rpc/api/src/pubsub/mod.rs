@@ -15,6 +15,19 @@ use crate::types::pubsub;
 /// $ netcat localhost 3030
 /// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["newPendingTransactions"]}
 /// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["events", {}]}
+/// ```
+/// The params are the server side filter of the subscription kind:
+/// ```bash
+/// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["newHeads", {"from_block": 100, "authors": ["0x..."]}]}
+/// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["events", {"addresses": ["0x1"], "type_tags": ["0x1::Account::DepositEvent"]}]}
+/// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["newPendingTransactions", {"senders": ["0x..."]}]}
+/// ```
+/// The `newHeads` and `events` notifications are sent with their cursors if `with_cursor` is set, and the
+/// subscriptions can be resumed after the cursor of the last processed notification after a reconnection,
+/// the resumed subscription keeps the filter of the original one:
+/// ```bash
+/// {"id":1,"jsonrpc":"2.0","method":"starcoin_subscribe","params":["events", {"addresses": ["0x1"], "resume_from": "1634352000000-3-100"}]}
+/// ```
 #[allow(clippy::needless_return)]
 #[rpc(server)]
 pub trait StarcoinPubSub {
@@ -32,7 +45,7 @@ pub trait StarcoinPubSub {
         meta: Self::Metadata,
         subscriber: typed::Subscriber<pubsub::Result>,
         kind: pubsub::Kind,
-        params: Option<pubsub::Params>,
+        params: Option<serde_json::Value>,
     );
 
     /// Unsubscribe from existing Starcoin subscription.
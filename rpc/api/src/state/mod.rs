@@ -6,11 +6,18 @@ use jsonrpc_derive::rpc;
 use starcoin_crypto::HashValue;
 use starcoin_types::{
     access_path::AccessPath, account_address::AccountAddress, account_state::AccountState,
+    block::BlockNumber,
 };
 
 pub use self::gen_client::Client as StateClient;
-use crate::types::{AccountStateSetView, StateWithProofView};
+use crate::types::{
+    AccountStateAtView, AccountStateSetView, AnnotatedMoveStructView, ListResourceView,
+    StateWithProofView, StrView, StructTagFilterView, StructTagView, TypeTagView,
+};
 
+/// The items of the Move tables are not readable by a `state.get_table_item` api, as the VM of
+/// this version has no table extension, and the state tree only stores the codes and resources
+/// of the accounts, see `DataPath`. A large table is read through the resource that holds it.
 #[rpc]
 pub trait StateApi {
     #[rpc(name = "state.get")]
@@ -28,6 +35,57 @@ pub trait StateApi {
         address: AccountAddress,
     ) -> FutureResult<Option<AccountStateSetView>>;
 
+    /// List the annotated resources of the account whose type matches the `filter`, ordered by the
+    /// struct tag, after the `cursor` and at most `limit`, so the clients need not load the whole
+    /// resource set of large accounts. The `limit` should be greater than 0.
+    #[rpc(name = "state.list_resources")]
+    fn list_resources(
+        &self,
+        address: AccountAddress,
+        filter: Option<StructTagFilterView>,
+        cursor: Option<StructTagView>,
+        limit: Option<u64>,
+    ) -> FutureResult<Option<ListResourceView>>;
+
+    /// Get the balances of the `token_type` of the addresses in the same order, STC if the
+    /// `token_type` is not set, and None for the account which has not accepted the token.
+    /// The max count of the addresses is limited by the server.
+    #[rpc(name = "state.get_balances")]
+    fn get_balances(
+        &self,
+        addresses: Vec<AccountAddress>,
+        token_type: Option<TypeTagView>,
+    ) -> FutureResult<Vec<Option<StrView<u128>>>>;
+
+    /// Get the annotated resource of the `resource_type` of the addresses in the same order,
+    /// None for the account which does not have the resource.
+    /// The max count of the addresses is limited by the server.
+    #[rpc(name = "state.get_resources")]
+    fn get_resources(
+        &self,
+        addresses: Vec<AccountAddress>,
+        resource_type: StructTagView,
+    ) -> FutureResult<Vec<Option<AnnotatedMoveStructView>>>;
+
+    /// Get the account state at the main chain block `block_number` by the account state index,
+    /// only available on the archive nodes.
+    #[rpc(name = "state.get_account_state_at")]
+    fn get_account_state_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+    ) -> FutureResult<Option<AccountStateAtView>>;
+
+    /// Get the balance of the `token_type` at the main chain block `block_number` by the account
+    /// state index, STC if the `token_type` is not set, only available on the archive nodes.
+    #[rpc(name = "state.get_balance_at")]
+    fn get_balance_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+        token_type: Option<TypeTagView>,
+    ) -> FutureResult<Option<StrView<u128>>>;
+
     #[rpc(name = "state.get_state_root")]
     fn get_state_root(&self) -> FutureResult<HashValue>;
 
@@ -37,4 +95,8 @@ pub trait StateApi {
         access_path: AccessPath,
         state_root: HashValue,
     ) -> FutureResult<StateWithProofView>;
+
+    /// Get the raw state tree node by the node hash, used to load the state tree lazily from a remote node.
+    #[rpc(name = "state.get_state_node_by_node_hash")]
+    fn get_state_node_by_node_hash(&self, node_hash: HashValue) -> FutureResult<Option<Vec<u8>>>;
 }
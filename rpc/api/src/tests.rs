@@ -1,6 +1,26 @@
-use crate::types::{ContractCall, TransactionArgumentView, TypeTagView};
+use crate::types::pubsub::{decode_transfers, PendingTransactionView, PendingTxnFilter};
+use crate::types::{
+    ContractCall, DryRunOutputView, TransactionArgumentView, TransactionOutputView, TypeTagView,
+};
+use starcoin_crypto::ed25519::genesis_key_pair;
+use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_address::AccountAddress;
+use starcoin_vm_types::account_config::{core_code_address, AccountResource};
+use starcoin_vm_types::contract_event::ContractEvent;
+use starcoin_vm_types::event::EventKey;
+use starcoin_vm_types::file_format::AbilitySet;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::move_resource::MoveResource;
 use starcoin_vm_types::token::stc::stc_type_tag;
+use starcoin_vm_types::transaction::{
+    RawUserTransaction, ScriptFunction, TransactionOutput, TransactionStatus,
+};
 use starcoin_vm_types::transaction_argument::TransactionArgument;
+use starcoin_vm_types::vm_status::KeptVMStatus;
+use starcoin_vm_types::write_set::{WriteOp, WriteSetMut};
 
 #[test]
 fn test_view_of_type_tag() {
@@ -32,3 +52,115 @@ fn test_deserialize() {
     let v = serde_json::from_str::<ContractCall>(s).unwrap();
     println!("{:?}", v);
 }
+
+#[test]
+fn test_dry_run_output_view() {
+    let address = AccountAddress::random();
+    let write_set = WriteSetMut::new(vec![
+        (
+            AccessPath::resource_access_path(address, AccountResource::struct_tag()),
+            WriteOp::Value(vec![1, 2, 3]),
+        ),
+        (
+            AccessPath::resource_access_path(address, AccountResource::struct_tag()),
+            WriteOp::Deletion,
+        ),
+    ])
+    .freeze()
+    .unwrap();
+    let events = vec![ContractEvent::new(
+        EventKey::random(),
+        0,
+        stc_type_tag(),
+        vec![1],
+    )];
+    let output = TransactionOutput::new(
+        write_set,
+        events,
+        100,
+        TransactionStatus::Keep(KeptVMStatus::Executed),
+    );
+    let resource = AnnotatedMoveStruct {
+        abilities: AbilitySet::EMPTY,
+        type_: AccountResource::struct_tag(),
+        value: vec![],
+    };
+    let view = DryRunOutputView::new(
+        output,
+        vec![Some(resource), None],
+        vec![Some(AnnotatedMoveValue::U64(1))],
+    );
+    assert!(view.write_set[0].value.is_some());
+    assert!(view.write_set[1].value.is_none());
+    assert!(view.events[0].decoded_data.is_some());
+
+    // the dry run output is compatible with the txn output.
+    let s = serde_json::to_string(&view).unwrap();
+    let output_view: TransactionOutputView = serde_json::from_str(s.as_str()).unwrap();
+    assert_eq!(output_view.write_set.len(), 2);
+    assert_eq!(output_view.events.len(), 1);
+    assert_eq!(output_view.gas_used.0, 100);
+}
+
+#[test]
+fn test_pending_txn_filter_by_receiver() {
+    let sender = AccountAddress::random();
+    let receiver = AccountAddress::random();
+    let function = ScriptFunction::new(
+        ModuleId::new(
+            core_code_address(),
+            Identifier::new("TransferScripts").unwrap(),
+        ),
+        Identifier::new("peer_to_peer_v2").unwrap(),
+        vec![stc_type_tag()],
+        vec![
+            bcs_ext::to_bytes(&receiver).unwrap(),
+            bcs_ext::to_bytes(&100u128).unwrap(),
+        ],
+    );
+    let (private_key, public_key) = genesis_key_pair();
+    let txn = RawUserTransaction::new_script_function(
+        sender,
+        0,
+        function,
+        10000,
+        1,
+        3600,
+        ChainId::test(),
+    )
+    .sign(&private_key, public_key.clone())
+    .unwrap()
+    .into_inner();
+
+    let transfers = decode_transfers(txn.payload());
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].receiver, receiver);
+    assert_eq!(transfers[0].amount.0, 100);
+    assert_eq!(transfers[0].token_type.0, stc_type_tag());
+
+    let filter = PendingTxnFilter {
+        receivers: vec![receiver],
+        ..Default::default()
+    };
+    assert!(filter.matching(&txn));
+    let filter = PendingTxnFilter {
+        senders: vec![sender],
+        receivers: vec![AccountAddress::random()],
+        ..Default::default()
+    };
+    assert!(!filter.matching(&txn));
+    // the receivers of the other payloads are unknown.
+    let filter = PendingTxnFilter {
+        receivers: vec![receiver],
+        ..Default::default()
+    };
+    let script_txn = RawUserTransaction::mock_by_sender(sender)
+        .sign(&private_key, public_key)
+        .unwrap()
+        .into_inner();
+    assert!(!filter.matching(&script_txn));
+
+    let view = PendingTransactionView::from(txn.clone());
+    assert_eq!(view.transaction_hash, txn.id());
+    assert_eq!(view.transfers, transfers);
+}
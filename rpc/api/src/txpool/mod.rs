@@ -6,18 +6,42 @@ use jsonrpc_derive::rpc;
 use starcoin_types::transaction::SignedUserTransaction;
 
 pub use self::gen_client::Client as TxPoolClient;
-use crate::types::{SignedUserTransactionView, StrView};
+use crate::types::{
+    SignedUserTransactionView, StrView, SubmitTransactionResultView, TxPoolContentView,
+    TxPoolInspectView,
+};
 use starcoin_crypto::HashValue;
 use starcoin_txpool_api::TxPoolStatus;
 use starcoin_types::account_address::AccountAddress;
 
 #[rpc]
 pub trait TxPoolApi {
+    /// Submit a txn to txpool, return the txn hash.
+    /// If the optional client `request_id` is provided, the node remembers it with the txn sender for a while,
+    /// and a duplicate submission with the same sender and request id returns the hash of
+    /// the originally submitted txn, without submitting the new txn.
     #[rpc(name = "txpool.submit_transaction")]
-    fn submit_transaction(&self, tx: SignedUserTransaction) -> FutureResult<HashValue>;
+    fn submit_transaction(
+        &self,
+        tx: SignedUserTransaction,
+        request_id: Option<String>,
+    ) -> FutureResult<HashValue>;
 
+    /// Same as `txpool.submit_transaction`, but the txn is hex encoded bcs bytes.
     #[rpc(name = "txpool.submit_hex_transaction")]
-    fn submit_hex_transaction(&self, tx: String) -> FutureResult<HashValue>;
+    fn submit_hex_transaction(
+        &self,
+        tx: String,
+        request_id: Option<String>,
+    ) -> FutureResult<HashValue>;
+
+    /// Submit a batch of txns to txpool, return the result of each txn in the same order,
+    /// a rejected txn does not affect the others. The max count of the txns is limited by the server.
+    #[rpc(name = "txpool.submit_transactions")]
+    fn submit_transactions(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> FutureResult<Vec<SubmitTransactionResultView>>;
 
     /// return current gas price
     #[rpc(name = "txpool.gas_price")]
@@ -43,4 +67,24 @@ pub trait TxPoolApi {
     /// or `None` if there are no pending transactions from that sender in txpool.
     #[rpc(name = "txpool.state")]
     fn state(&self) -> FutureResult<TxPoolStatus>;
+
+    /// Get the txns in txpool, grouped by sender and keyed by sequence number,
+    /// the `pending` txns are ready to be packaged, and the `queued` txns are not ready yet.
+    /// The txns of at most `limit` senders after the `cursor` sender are returned, in the order of
+    /// sender address, the `limit` should be greater than 0.
+    #[rpc(name = "txpool.content")]
+    fn content(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> FutureResult<TxPoolContentView>;
+
+    /// Same as `txpool.content`, but only return the summary of the txns,
+    /// such as the gas price and expiration.
+    #[rpc(name = "txpool.inspect")]
+    fn inspect(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> FutureResult<TxPoolInspectView>;
 }
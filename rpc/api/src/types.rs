@@ -9,6 +9,7 @@ pub use node_api_types::*;
 use bcs_ext::BCSCodec;
 use hex::FromHex;
 use jsonrpc_core_client::RpcChannel;
+use network_api::messages::PeerTraffic;
 use serde::de::Error;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
@@ -16,7 +17,9 @@ use starcoin_crypto::{CryptoMaterialError, HashValue, ValidCryptoMaterialStringE
 use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
 use starcoin_service_registry::ServiceRequest;
 use starcoin_state_api::{StateProof, StateWithProof};
+use starcoin_txpool_api::TxPoolContent;
 use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_state::AccountState;
 use starcoin_types::block::{
     Block, BlockBody, BlockHeader, BlockHeaderExtra, BlockInfo, BlockNumber, BlockSummary,
     EpochUncleSummary, UncleSummary,
@@ -26,20 +29,23 @@ use starcoin_types::event::EventKey;
 use starcoin_types::genesis_config;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_types::peer_info::{PeerId, PeerInfo};
-use starcoin_types::proof::SparseMerkleProof;
+use starcoin_types::proof::{AccumulatorProof, SparseMerkleProof};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::authenticator::{AuthenticationKey, TransactionAuthenticator};
-use starcoin_types::transaction::{RawUserTransaction, ScriptFunction, TransactionArgument};
+use starcoin_types::transaction::{
+    RawUserTransaction, ScriptFunction, TransactionArgument, TransactionInfoWithProof,
+};
 use starcoin_types::vm_error::AbortLocation;
 use starcoin_types::U256;
 use starcoin_vm_types::access_path::AccessPath;
 use starcoin_vm_types::block_metadata::BlockMetadata;
 use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::language_storage::{FunctionId, ModuleId, StructTag};
+use starcoin_vm_types::on_chain_resource::EpochInfo;
 use starcoin_vm_types::parser::{parse_transaction_argument, parse_type_tag};
 use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
 use starcoin_vm_types::transaction::{
-    Script, SignedUserTransaction, Transaction, TransactionInfo, TransactionOutput,
+    GasBreakdown, Script, SignedUserTransaction, Transaction, TransactionInfo, TransactionOutput,
     TransactionPayload, TransactionStatus,
 };
 use starcoin_vm_types::transaction_argument::convert_txn_args;
@@ -48,6 +54,7 @@ use starcoin_vm_types::write_set::WriteOp;
 use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
+use std::time::Duration;
 
 pub type ByteCode = Vec<u8>;
 
@@ -106,6 +113,53 @@ pub struct AccountStateSetView {
     pub resources: BTreeMap<StructTagView, AnnotatedMoveStructView>,
 }
 
+/// A pattern of resource types used by `state.list_resources`, such as `0x1::Account::Balance<*>`.
+/// The address, module and name can be `*` to match any, the type params match any if they are
+/// omitted or `<*>`, otherwise they must be equal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructTagFilter {
+    pub address: Option<AccountAddress>,
+    pub module: Option<Identifier>,
+    pub name: Option<Identifier>,
+    pub type_params: Option<Vec<TypeTag>>,
+}
+
+impl StructTagFilter {
+    pub fn matches(&self, struct_tag: &StructTag) -> bool {
+        self.address
+            .map_or(true, |address| address == struct_tag.address)
+            && self
+                .module
+                .as_ref()
+                .map_or(true, |module| module == &struct_tag.module)
+            && self
+                .name
+                .as_ref()
+                .map_or(true, |name| name == &struct_tag.name)
+            && self
+                .type_params
+                .as_ref()
+                .map_or(true, |type_params| type_params == &struct_tag.type_params)
+    }
+}
+
+pub type StructTagFilterView = StrView<StructTagFilter>;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct AccountStateAtView {
+    /// The number of the latest block at or before the queried block, at which the account state was changed.
+    pub changed_at: StrView<BlockNumber>,
+    pub account_state: AccountState,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+pub struct ListResourceView {
+    /// The matched resources after the cursor, ordered by the struct tag.
+    pub resources: BTreeMap<StructTagView, AnnotatedMoveStructView>,
+    /// The cursor to continue with, None if there are no more matched resources.
+    pub next_cursor: Option<StructTagView>,
+}
+
 #[derive(Default, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct TransactionRequest {
     /// Sender's address.
@@ -382,6 +436,27 @@ impl From<BlockHeader> for BlockHeaderView {
     }
 }
 
+impl From<BlockHeaderView> for BlockHeader {
+    fn from(header_view: BlockHeaderView) -> Self {
+        BlockHeader::new(
+            header_view.parent_hash,
+            header_view.timestamp.0,
+            header_view.number.0,
+            header_view.author,
+            header_view.author_auth_key,
+            header_view.txn_accumulator_root,
+            header_view.block_accumulator_root,
+            header_view.state_root,
+            header_view.gas_used.0,
+            header_view.difficulty,
+            header_view.body_hash,
+            genesis_config::ChainId::new(header_view.chain_id),
+            header_view.nonce,
+            header_view.extra,
+        )
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RawUserTransactionView {
     /// Sender's address.
@@ -426,6 +501,23 @@ impl TryFrom<RawUserTransaction> for RawUserTransactionView {
     }
 }
 
+impl TryFrom<RawUserTransactionView> for RawUserTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(view: RawUserTransactionView) -> Result<Self, Self::Error> {
+        Ok(RawUserTransaction::new(
+            view.sender,
+            view.sequence_number.0,
+            TransactionPayload::decode(view.payload.0.as_slice())?,
+            view.max_gas_amount.0,
+            view.gas_unit_price.0,
+            view.expiration_timestamp_secs.0,
+            genesis_config::ChainId::new(view.chain_id),
+            view.gas_token_code,
+        ))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct SignedUserTransactionView {
     pub transaction_hash: HashValue,
@@ -450,6 +542,132 @@ impl TryFrom<SignedUserTransaction> for SignedUserTransactionView {
     }
 }
 
+/// The txns in the txpool, grouped by sender and keyed by sequence number.
+pub type TxPoolTxnsView<T> = BTreeMap<AccountAddress, BTreeMap<u64, T>>;
+
+fn group_by_sender<T, F>(
+    txns: Vec<SignedUserTransaction>,
+    f: F,
+) -> anyhow::Result<TxPoolTxnsView<T>>
+where
+    F: Fn(SignedUserTransaction) -> anyhow::Result<T>,
+{
+    let mut grouped = TxPoolTxnsView::new();
+    for txn in txns {
+        grouped
+            .entry(txn.sender())
+            .or_insert_with(BTreeMap::new)
+            .insert(txn.sequence_number(), f(txn)?);
+    }
+    Ok(grouped)
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolContentView {
+    /// The txns ready to be packaged into a block.
+    pub pending: TxPoolTxnsView<SignedUserTransactionView>,
+    /// The txns not ready yet, such as waiting for the txns with smaller sequence number of the sender.
+    pub queued: TxPoolTxnsView<SignedUserTransactionView>,
+    /// The cursor of the next page, None if there are no more senders.
+    #[serde(default)]
+    pub next_cursor: Option<AccountAddress>,
+}
+
+impl TryFrom<TxPoolContent> for TxPoolContentView {
+    type Error = anyhow::Error;
+
+    fn try_from(content: TxPoolContent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pending: group_by_sender(content.pending, TryInto::try_into)?,
+            queued: group_by_sender(content.queued, TryInto::try_into)?,
+            next_cursor: None,
+        })
+    }
+}
+
+/// The summary of a txn in the txpool.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolTxnSummaryView {
+    pub transaction_hash: HashValue,
+    pub max_gas_amount: StrView<u64>,
+    pub gas_unit_price: StrView<u64>,
+    pub gas_token_code: String,
+    pub expiration_timestamp_secs: StrView<u64>,
+    /// The script function id, or `script` and `package` for the other payloads.
+    pub payload: String,
+}
+
+impl From<SignedUserTransaction> for TxPoolTxnSummaryView {
+    fn from(txn: SignedUserTransaction) -> Self {
+        let payload = match txn.payload() {
+            TransactionPayload::ScriptFunction(f) => FunctionId {
+                module: f.module().clone(),
+                function: f.function().to_owned(),
+            }
+            .to_string(),
+            TransactionPayload::Script(_) => "script".to_string(),
+            TransactionPayload::Package(_) => "package".to_string(),
+        };
+        Self {
+            transaction_hash: txn.id(),
+            max_gas_amount: txn.max_gas_amount().into(),
+            gas_unit_price: txn.gas_unit_price().into(),
+            gas_token_code: txn.gas_token_code().to_string(),
+            expiration_timestamp_secs: txn.expiration_timestamp_secs().into(),
+            payload,
+        }
+    }
+}
+
+/// The result of a txn submitted by `txpool.submit_transactions`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SubmitTransactionResultView {
+    pub transaction_hash: HashValue,
+    pub accepted: bool,
+    /// The reason of the rejected txn, same as the error of `txpool.submit_transaction`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc_core::Error>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolInspectView {
+    /// The txns ready to be packaged into a block.
+    pub pending: TxPoolTxnsView<TxPoolTxnSummaryView>,
+    /// The txns not ready yet, such as waiting for the txns with smaller sequence number of the sender.
+    pub queued: TxPoolTxnsView<TxPoolTxnSummaryView>,
+    /// The cursor of the next page, None if there are no more senders.
+    #[serde(default)]
+    pub next_cursor: Option<AccountAddress>,
+}
+
+impl TryFrom<TxPoolContent> for TxPoolInspectView {
+    type Error = anyhow::Error;
+
+    fn try_from(content: TxPoolContent) -> Result<Self, Self::Error> {
+        let summary = |txn| Ok(TxPoolTxnSummaryView::from(txn));
+        Ok(Self {
+            pending: group_by_sender(content.pending, summary)?,
+            queued: group_by_sender(content.queued, summary)?,
+            next_cursor: None,
+        })
+    }
+}
+
+impl TryFrom<SignedUserTransactionView> for SignedUserTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(view: SignedUserTransactionView) -> Result<Self, Self::Error> {
+        let txn = SignedUserTransaction::new(view.raw_txn.try_into()?, view.authenticator);
+        anyhow::ensure!(
+            txn.id() == view.transaction_hash,
+            "Transaction hash mismatch, expect: {}, got: {}",
+            view.transaction_hash,
+            txn.id()
+        );
+        Ok(txn)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct BlockMetadataView {
     /// Parent block hash.
@@ -521,10 +739,17 @@ pub struct TransactionView {
     pub transaction_index: u32,
     pub block_metadata: Option<BlockMetadataView>,
     pub user_transaction: Option<SignedUserTransactionView>,
+    /// The bcs encoded `BlockMetadata` or `SignedUserTransaction`, only returned if `raw` of the option is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<StrView<Vec<u8>>>,
 }
 
 impl TransactionView {
     pub fn new(txn: Transaction, block: &Block) -> anyhow::Result<Self> {
+        Self::new_with_raw(txn, block, false)
+    }
+
+    pub fn new_with_raw(txn: Transaction, block: &Block, raw: bool) -> anyhow::Result<Self> {
         let transaction_hash = txn.id();
         let block_hash = block.id();
         let block_number = block.header.number();
@@ -545,6 +770,14 @@ impl TransactionView {
             }
         };
 
+        let raw = if raw {
+            Some(StrView(match &txn {
+                Transaction::BlockMetadata(meta) => bcs_ext::to_bytes(meta)?,
+                Transaction::UserTransaction(t) => bcs_ext::to_bytes(t)?,
+            }))
+        } else {
+            None
+        };
         let (meta, txn) = match txn {
             Transaction::BlockMetadata(meta) => (Some(meta.into()), None),
             Transaction::UserTransaction(t) => (None, Some(t.try_into()?)),
@@ -556,6 +789,7 @@ impl TransactionView {
             transaction_index,
             block_metadata: meta,
             user_transaction: txn,
+            raw,
         })
     }
 }
@@ -593,15 +827,85 @@ impl From<Vec<HashValue>> for BlockTransactionsView {
     }
 }
 
+/// The option of `chain.get_blocks_by_number`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetBlocksOption {
+    /// Get the blocks before the number in descending order, default true.
+    pub reverse: bool,
+    /// Return the full txns of the blocks rather than the txn hashes, default false.
+    pub full_txns: bool,
+    /// Return the bcs encoded header and body of the blocks, default false.
+    pub raw: bool,
+}
+
+impl Default for GetBlocksOption {
+    fn default() -> Self {
+        Self {
+            reverse: true,
+            full_txns: false,
+            raw: false,
+        }
+    }
+}
+
+/// The option of `chain.get_block_by_hash` and `chain.get_block_by_number`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetBlockOption {
+    /// Return the bcs encoded header and body of the block, default false.
+    pub raw: bool,
+}
+
+/// The option of `chain.get_transaction`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetTransactionOption {
+    /// Return the bcs encoded txn, default false.
+    pub raw: bool,
+}
+
+/// The bcs encoded header and body of a block, the block hash is the crypto hash of the header.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RawBlockView {
+    pub header: StrView<Vec<u8>>,
+    pub body: StrView<Vec<u8>>,
+}
+
+impl RawBlockView {
+    pub fn try_from_block(block: &Block) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            header: StrView(bcs_ext::to_bytes(block.header())?),
+            body: StrView(bcs_ext::to_bytes(&block.body)?),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct BlockView {
     pub header: BlockHeaderView,
     pub body: BlockTransactionsView,
     pub uncles: Vec<BlockHeaderView>,
+    /// Only returned if `raw` of the option is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawBlockView>,
 }
 
 impl BlockView {
     pub fn try_from_block(block: Block, thin: bool) -> Result<Self, anyhow::Error> {
+        Self::try_from_block_with_raw(block, thin, false)
+    }
+
+    pub fn try_from_block_with_raw(
+        block: Block,
+        thin: bool,
+        raw: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let raw = if raw {
+            Some(RawBlockView::try_from_block(&block)?)
+        } else {
+            None
+        };
         let (header, body) = block.into_inner();
         let BlockBody {
             transactions,
@@ -620,10 +924,52 @@ impl BlockView {
                 .map(|h| h.into())
                 .collect(),
             body: txns_view,
+            raw,
         })
     }
 }
 
+impl TryFrom<BlockView> for Block {
+    type Error = anyhow::Error;
+
+    /// Recreate the block from a full block view, the block id is checked against the block hash of the view.
+    fn try_from(block_view: BlockView) -> Result<Self, Self::Error> {
+        let block_hash = block_view.header.block_hash;
+        let transactions = match block_view.body {
+            BlockTransactionsView::Full(txns) => txns
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?,
+            BlockTransactionsView::Hashes(_) => {
+                anyhow::bail!("Can not recreate block {} from txn hashes", block_hash)
+            }
+        };
+        // the block body without uncles is created with `None` uncles.
+        let uncles = if block_view.uncles.is_empty() {
+            None
+        } else {
+            Some(block_view.uncles.into_iter().map(Into::into).collect())
+        };
+        let block = Block::new(
+            block_view.header.into(),
+            BlockBody::new(transactions, uncles),
+        );
+        anyhow::ensure!(
+            block.id() == block_hash,
+            "Recreated block id {} mismatch with block hash {}",
+            block.id(),
+            block_hash
+        );
+        anyhow::ensure!(
+            block.header.body_hash() == block.body.hash(),
+            "Recreated block body hash {} mismatch with the body hash {} in header",
+            block.body.hash(),
+            block.header.body_hash()
+        );
+        Ok(block)
+    }
+}
+
 impl TryFrom<Block> for BlockView {
     type Error = anyhow::Error;
 
@@ -697,6 +1043,67 @@ impl TransactionInfoView {
     }
 }
 
+/// The txn info with the proof of its inclusion in the txn accumulator of the block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionInfoWithProofView {
+    pub transaction_info: TransactionInfoView,
+    /// The bcs bytes of the txn info, the hash of which is the leaf of the txn accumulator.
+    pub raw_transaction_info: StrView<Vec<u8>>,
+    /// The index of the txn info in the txn accumulator.
+    pub leaf_index: StrView<u64>,
+    pub proof: AccumulatorProof,
+    /// The txn accumulator root of the block header.
+    pub txn_accumulator_root: HashValue,
+}
+
+impl TransactionInfoWithProofView {
+    pub fn new(
+        txn_info_with_proof: TransactionInfoWithProof,
+        txn_block: &Block,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            raw_transaction_info: StrView(txn_info_with_proof.transaction_info.encode()?),
+            transaction_info: TransactionInfoView::new(
+                txn_info_with_proof.transaction_info,
+                txn_block,
+            )?,
+            leaf_index: txn_info_with_proof.leaf_index.into(),
+            proof: txn_info_with_proof.proof,
+            txn_accumulator_root: txn_block.header().txn_accumulator_root(),
+        })
+    }
+
+    /// Decode the raw txn info, and check the txn info view is the same as it.
+    pub fn txn_info_with_proof(&self) -> anyhow::Result<TransactionInfoWithProof> {
+        let txn_info = TransactionInfo::decode(self.raw_transaction_info.0.as_slice())?;
+        let view = &self.transaction_info;
+        anyhow::ensure!(
+            view.transaction_hash == txn_info.transaction_hash()
+                && view.state_root_hash == txn_info.state_root_hash()
+                && view.event_root_hash == txn_info.event_root_hash()
+                && view.gas_used.0 == txn_info.gas_used()
+                && view.status == TransactionVMStatus::from(txn_info.status().clone()),
+            "transaction info view does not match the raw transaction info"
+        );
+        Ok(TransactionInfoWithProof::new(
+            txn_info,
+            self.leaf_index.0,
+            self.proof.clone(),
+        ))
+    }
+
+    /// Verify the txn info with the trusted `txn_accumulator_root`, such as the one of a block
+    /// header synced by a light client, rather than the `txn_accumulator_root` returned by the node.
+    pub fn verify(&self, txn_accumulator_root: HashValue) -> anyhow::Result<()> {
+        self.txn_info_with_proof()?.verify(txn_accumulator_root)
+    }
+
+    /// Verify the `events` are all the events emitted by the txn, in order.
+    pub fn verify_events(&self, events: &[ContractEvent]) -> anyhow::Result<()> {
+        self.txn_info_with_proof()?.verify_events(events)
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum TransactionVMStatus {
@@ -783,6 +1190,61 @@ impl From<ContractEventInfo> for TransactionEventView {
         }
     }
 }
+/// The position of an event on the main chain, used as an opaque cursor by `chain.get_events_after`.
+/// The block hash is kept in the cursor to detect whether the block has been reverted by a reorg.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct EventCursor {
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    /// txn index in block, include the block metadata txn.
+    pub txn_index: u32,
+    /// event index in txn.
+    pub event_index: u32,
+}
+
+impl EventCursor {
+    pub fn new(
+        block_number: BlockNumber,
+        block_hash: HashValue,
+        txn_index: u32,
+        event_index: u32,
+    ) -> Self {
+        Self {
+            block_number,
+            block_hash,
+            txn_index,
+            event_index,
+        }
+    }
+
+    /// The cursor after all events of the block.
+    pub fn block_end(block_number: BlockNumber, block_hash: HashValue) -> Self {
+        Self::new(block_number, block_hash, u32::MAX, u32::MAX)
+    }
+
+    /// Is the event at this position after the `cursor`.
+    pub fn is_after(&self, cursor: &EventCursor) -> bool {
+        (self.block_number, self.txn_index, self.event_index)
+            > (cursor.block_number, cursor.txn_index, cursor.event_index)
+    }
+}
+
+pub type EventCursorView = StrView<EventCursor>;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct CursoredEventView {
+    pub cursor: EventCursorView,
+    pub event: TransactionEventView,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct EventPageView {
+    pub events: Vec<CursoredEventView>,
+    /// The cursor to continue with, it may be after the last returned event when there are blocks without more events.
+    /// None if no block is scanned.
+    pub next_cursor: Option<EventCursorView>,
+}
+
 impl From<ContractEvent> for TransactionEventView {
     fn from(event: ContractEvent) -> Self {
         TransactionEventView {
@@ -844,6 +1306,157 @@ impl From<TransactionOutput> for TransactionOutputView {
     }
 }
 
+/// The txn output of dry run, the resources of the write set and the event data are decoded by the move types.
+/// It contains all the fields of `TransactionOutputView`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunOutputView {
+    pub events: Vec<DryRunEventView>,
+    pub gas_used: StrView<u64>,
+    pub status: TransactionVMStatus,
+    pub write_set: Vec<DryRunOutputAction>,
+    /// The `gas_used` split by category, such as execution, write set and events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_breakdown: Option<GasBreakdownView>,
+}
+
+impl DryRunOutputView {
+    /// The `write_set_values` and `event_values` are the decoded values in the same order as the write set and events of the output.
+    pub fn new(
+        txn_output: TransactionOutput,
+        write_set_values: Vec<Option<AnnotatedMoveStruct>>,
+        event_values: Vec<Option<AnnotatedMoveValue>>,
+    ) -> Self {
+        let (write_set, events, gas_used, status) = txn_output.into_inner();
+        let mut write_set_values = write_set_values.into_iter();
+        let mut event_values = event_values.into_iter();
+        Self {
+            events: events
+                .into_iter()
+                .map(|event| DryRunEventView {
+                    event: event.into(),
+                    decoded_data: event_values.next().flatten().map(Into::into),
+                })
+                .collect(),
+            gas_used: gas_used.into(),
+            status: status.into(),
+            write_set: write_set
+                .into_iter()
+                .map(|(p, w)| DryRunOutputAction {
+                    access_path: p.into(),
+                    action: w.into(),
+                    value: write_set_values.next().flatten().map(Into::into),
+                })
+                .collect(),
+            gas_breakdown: None,
+        }
+    }
+
+    pub fn with_gas_breakdown(mut self, gas_breakdown: Option<GasBreakdown>) -> Self {
+        self.gas_breakdown = gas_breakdown.map(Into::into);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GasBreakdownView {
+    pub intrinsic_gas: StrView<u64>,
+    pub execution_gas: StrView<u64>,
+    pub write_set_gas: StrView<u64>,
+    /// In the same order as the events of the output.
+    pub events_gas: Vec<StrView<u64>>,
+    pub gas_schedule_version: StrView<u64>,
+}
+
+impl From<GasBreakdown> for GasBreakdownView {
+    fn from(gas: GasBreakdown) -> Self {
+        Self {
+            intrinsic_gas: gas.intrinsic_gas.into(),
+            execution_gas: gas.execution_gas.into(),
+            write_set_gas: gas.write_set_gas.into(),
+            events_gas: gas.events_gas.into_iter().map(Into::into).collect(),
+            gas_schedule_version: gas.gas_schedule_version.into(),
+        }
+    }
+}
+
+/// A Move call frame of a traced txn.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CallFrameView {
+    /// The depth of the frame, the entry function is 0.
+    pub depth: u32,
+    /// The module of the function, `None` for a script.
+    pub module: Option<ModuleIdView>,
+    pub function: Identifier,
+    pub ty_args: Vec<TypeTagView>,
+    /// The bcs encoded arguments.
+    pub args: Vec<StrView<Vec<u8>>>,
+    pub gas_used: StrView<u64>,
+}
+
+/// A state read by a traced txn, `value` is `None` if the state does not exist.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateReadView {
+    pub access_path: AccessPathView,
+    pub value: Option<StrView<Vec<u8>>>,
+}
+
+impl StateReadView {
+    pub fn new(access_path: AccessPath, value: Option<Vec<u8>>) -> Self {
+        Self {
+            access_path: access_path.into(),
+            value: value.map(StrView),
+        }
+    }
+}
+
+/// The trace of a txn on chain, re-executed on the state before it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionTraceView {
+    pub transaction_hash: HashValue,
+    pub block_hash: HashValue,
+    /// The state root the txn is re-executed on.
+    pub state_root: HashValue,
+    pub output: DryRunOutputView,
+    /// Only the entry function is traced.
+    pub frames: Vec<CallFrameView>,
+    /// The states read by the execution, in the order of the first read.
+    pub reads: Vec<StateReadView>,
+}
+
+impl From<DryRunOutputView> for TransactionOutputView {
+    fn from(output: DryRunOutputView) -> Self {
+        Self {
+            events: output.events.into_iter().map(|e| e.event).collect(),
+            gas_used: output.gas_used,
+            status: output.status,
+            write_set: output
+                .write_set
+                .into_iter()
+                .map(|w| TransactionOutputAction {
+                    access_path: w.access_path,
+                    action: w.action,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunOutputAction {
+    #[serde(flatten)]
+    pub access_path: AccessPathView,
+    pub action: WriteOpView,
+    /// The decoded resource, `None` for the code, the deletion, and the resource which can not be decoded.
+    pub value: Option<AnnotatedMoveStructView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunEventView {
+    #[serde(flatten)]
+    pub event: TransactionEventView,
+    pub decoded_data: Option<AnnotatedMoveValueView>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TransactionOutputAction {
     #[serde(flatten)]
@@ -921,6 +1534,64 @@ impl From<EpochUncleSummary> for EpochUncleSummaryView {
     }
 }
 
+/// The parameters and the accumulated data of an epoch, at the block `block_number`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EpochInfoView {
+    /// epoch number
+    pub number: StrView<u64>,
+    /// milli seconds
+    pub start_time: StrView<u64>,
+    pub start_block_number: StrView<BlockNumber>,
+    pub end_block_number: StrView<BlockNumber>,
+    /// milli seconds
+    pub block_time_target: StrView<u64>,
+    pub reward_per_block: StrView<u128>,
+    pub reward_per_uncle_percent: StrView<u64>,
+    pub block_difficulty_window: StrView<u64>,
+    pub max_uncles_per_block: StrView<u64>,
+    pub block_gas_limit: StrView<u64>,
+    pub strategy: u8,
+    /// The block which the epoch data is read at, the last block of the epoch if the epoch has ended.
+    pub block_number: StrView<BlockNumber>,
+    /// The blocks of the epoch until `block_number`.
+    pub blocks: StrView<u64>,
+    pub uncles: StrView<u64>,
+    /// uncles * 1000 / blocks, as the on chain epoch adjustment does.
+    pub uncle_rate: StrView<u64>,
+    pub total_reward: StrView<u128>,
+    pub total_gas: StrView<u128>,
+}
+
+impl EpochInfoView {
+    pub fn new(epoch_info: &EpochInfo, block_number: BlockNumber) -> Self {
+        let epoch = epoch_info.epoch();
+        let epoch_data = epoch_info.epoch_data();
+        let blocks = (block_number + 1).saturating_sub(epoch.start_block_number());
+        Self {
+            number: epoch.number().into(),
+            start_time: epoch.start_time().into(),
+            start_block_number: epoch.start_block_number().into(),
+            end_block_number: epoch.end_block_number().into(),
+            block_time_target: epoch.block_time_target().into(),
+            reward_per_block: epoch.reward_per_block().into(),
+            reward_per_uncle_percent: epoch.reward_per_uncle_percent().into(),
+            block_difficulty_window: epoch.block_difficulty_window().into(),
+            max_uncles_per_block: epoch.max_uncles_per_block().into(),
+            block_gas_limit: epoch.block_gas_limit().into(),
+            strategy: epoch.strategy().value(),
+            block_number: block_number.into(),
+            blocks: blocks.into(),
+            uncles: epoch_data.uncles().into(),
+            uncle_rate: (epoch_data.uncles() * 1000)
+                .checked_div(blocks)
+                .unwrap_or_default()
+                .into(),
+            total_reward: epoch_data.total_reward().into(),
+            total_gas: epoch_data.total_gas().into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChainInfoView {
     pub chain_id: u8,
@@ -962,6 +1633,72 @@ impl From<PeerInfo> for PeerInfoView {
     }
 }
 
+/// The diagnostic detail of a connected peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerDetailView {
+    /// The peer info with the supported protocols, the chain info in it is the latest block the peer announced.
+    pub peer_info: PeerInfoView,
+    /// The reputation score of the peer in the peer set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<i32>,
+    /// The protocol version negotiated in the handshake.
+    pub version: u32,
+    /// The latest ping round trip time in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt: Option<StrView<u64>>,
+    /// The bytes of the notification messages received from the peer.
+    pub bytes_in: StrView<u64>,
+    /// The bytes of the notification messages sent to the peer.
+    pub bytes_out: StrView<u64>,
+    /// The unix timestamp in milliseconds when the peer connected.
+    pub connected_at: StrView<u64>,
+    /// The unix timestamp in milliseconds of the latest message received from the peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_received_at: Option<StrView<u64>>,
+}
+
+impl PeerDetailView {
+    pub fn new(traffic: PeerTraffic, reputation: Option<i32>, rtt: Option<Duration>) -> Self {
+        Self {
+            peer_info: traffic.peer_info.into(),
+            reputation,
+            version: traffic.version,
+            rtt: rtt.map(|rtt| StrView(rtt.as_millis() as u64)),
+            bytes_in: traffic.bytes_in.into(),
+            bytes_out: traffic.bytes_out.into(),
+            connected_at: traffic.connected_at.into(),
+            last_received_at: traffic.last_received_at.map(Into::into),
+        }
+    }
+}
+
+/// A db backup taken by the node, see `node_admin.backup_db`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DBBackupView {
+    pub id: u64,
+    /// The backup dir of the backup.
+    pub dir: String,
+    /// The main chain head when the backup is taken.
+    pub block_number: StrView<BlockNumber>,
+    pub block_hash: HashValue,
+    /// Unix timestamp in seconds.
+    pub timestamp: StrView<u64>,
+    pub files: u64,
+    /// The total bytes of the backup files.
+    pub size: StrView<u64>,
+    /// The bytes of the new files of this backup, the files shared with the previous backups are not included.
+    pub new_bytes: StrView<u64>,
+}
+
+/// A page of the chain state dump, see `node_admin.dump_state`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateDumpPageView {
+    /// The bcs bytes of the `ChainStateSet` of the accounts in this page.
+    pub state: StrView<Vec<u8>>,
+    /// The account key hash to start the next page from, none if this is the last page.
+    pub next_cursor: Option<HashValue>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StateWithProofView {
     pub state: Option<StrView<Vec<u8>>>,
@@ -977,6 +1714,16 @@ impl StateWithProofView {
             self.account_state_proof.clone(),
         )
     }
+
+    /// Verify the state and the proof with the trusted `state_root`, so the state returned by an
+    /// untrusted node can be trusted as far as the state root is.
+    pub fn verify(&self, state_root: HashValue, access_path: AccessPath) -> anyhow::Result<()> {
+        self.state_proof().verify(
+            state_root,
+            access_path,
+            self.state.as_ref().map(|state| state.0.as_slice()),
+        )
+    }
 }
 
 impl From<StateWithProof> for StateWithProofView {
@@ -1116,6 +1863,69 @@ impl FromStr for StructTagView {
         }
     }
 }
+impl std::fmt::Display for StrView<StructTagFilter> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0.address {
+            Some(address) => write!(f, "{}", address)?,
+            None => write!(f, "*")?,
+        }
+        for part in &[&self.0.module, &self.0.name] {
+            match part {
+                Some(part) => write!(f, "::{}", part)?,
+                None => write!(f, "::*")?,
+            }
+        }
+        if let Some(type_params) = &self.0.type_params {
+            let type_params: Vec<String> = type_params.iter().map(|t| t.to_string()).collect();
+            write!(f, "<{}>", type_params.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for StructTagFilterView {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (head, type_params) = match s.find('<') {
+            Some(index) => {
+                anyhow::ensure!(s.ends_with('>'), "invalid struct tag filter: {}", s);
+                (&s[..index], Some(s[index + 1..s.len() - 1].trim()))
+            }
+            None => (s, None),
+        };
+        let parts: Vec<&str> = head.split("::").map(|part| part.trim()).collect();
+        anyhow::ensure!(parts.len() == 3, "invalid struct tag filter: {}", s);
+        let address = match parts[0] {
+            "*" => None,
+            address => Some(AccountAddress::from_str(address)?),
+        };
+        let module = match parts[1] {
+            "*" => None,
+            module => Some(Identifier::new(module)?),
+        };
+        let name = match parts[2] {
+            "*" => None,
+            name => Some(Identifier::new(name)?),
+        };
+        let type_params = match type_params {
+            None | Some("*") => None,
+            // parse the type params with a placeholder struct, as the head may have wildcards.
+            Some(type_params) => match parse_type_tag(&format!("0x1::M::S<{}>", type_params))? {
+                TypeTag::Struct(s) => Some(s.type_params),
+                t => anyhow::bail!("expect struct tag, actual: {}", t),
+            },
+        };
+        Ok(Self(StructTagFilter {
+            address,
+            module,
+            name,
+            type_params,
+        }))
+    }
+}
+
 impl std::fmt::Display for StrView<TransactionArgument> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.0)
@@ -1144,6 +1954,24 @@ impl FromStr for StrView<Vec<u8>> {
     }
 }
 
+impl std::fmt::Display for StrView<EventCursor> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "0x{}",
+            hex::encode(self.0.encode().map_err(|_| std::fmt::Error)?)
+        )
+    }
+}
+
+impl FromStr for StrView<EventCursor> {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))?;
+        Ok(Self(EventCursor::decode(bytes.as_slice())?))
+    }
+}
+
 impl std::fmt::Display for StrView<AccountPublicKey> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1260,6 +2088,16 @@ pub struct ContractCall {
     pub args: Vec<TransactionArgumentView>,
 }
 
+/// The historical state to query, by the state root, or by the block of the main chain.
+/// The state of a block is the state after the block is executed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateRootOption {
+    StateRoot(HashValue),
+    BlockHash(HashValue),
+    BlockNumber(BlockNumber),
+}
+
 #[derive(Debug, Clone)]
 pub struct ConnectLocal;
 
@@ -1269,7 +2107,11 @@ impl ServiceRequest for ConnectLocal {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{ByteCodeOrScriptFunction, FunctionId};
+    use crate::types::{
+        ByteCodeOrScriptFunction, EventCursor, EventCursorView, FunctionId, StructTagFilterView,
+        StructTagView,
+    };
+    use starcoin_crypto::HashValue;
     use starcoin_types::account_address::AccountAddress;
 
     #[test]
@@ -1290,4 +2132,48 @@ mod tests {
         let bytecode: ByteCodeOrScriptFunction = "0x123432ab34".parse().unwrap();
         assert!(matches!(bytecode, ByteCodeOrScriptFunction::ByteCode(_)));
     }
+
+    #[test]
+    fn test_event_cursor() {
+        let block_hash = HashValue::random();
+        let cursor = EventCursor::new(10, block_hash, 1, 2);
+        let view = EventCursorView::from(cursor);
+        let parsed: EventCursorView = view.to_string().parse().unwrap();
+        assert_eq!(parsed.0, cursor);
+
+        assert!(EventCursor::new(10, block_hash, 1, 3).is_after(&cursor));
+        assert!(EventCursor::new(10, block_hash, 2, 0).is_after(&cursor));
+        assert!(EventCursor::new(11, HashValue::random(), 0, 0).is_after(&cursor));
+        assert!(!EventCursor::new(10, block_hash, 1, 2).is_after(&cursor));
+        assert!(!EventCursor::new(10, block_hash, 0, 5).is_after(&cursor));
+        assert!(!EventCursor::new(10, block_hash, 5, 0)
+            .is_after(&EventCursor::block_end(10, block_hash)));
+    }
+
+    #[test]
+    fn test_struct_tag_filter() {
+        let balance: StructTagView = "0x1::Account::Balance<0x1::STC::STC>".parse().unwrap();
+        let account: StructTagView = "0x1::Account::Account".parse().unwrap();
+
+        let filter: StructTagFilterView = "0x1::Account::Balance<*>".parse().unwrap();
+        assert!(filter.0.matches(&balance.0));
+        assert!(!filter.0.matches(&account.0));
+
+        let filter: StructTagFilterView = "0x1::Account::*".parse().unwrap();
+        assert!(filter.0.matches(&balance.0));
+        assert!(filter.0.matches(&account.0));
+
+        let filter: StructTagFilterView = "*::*::Balance<0x1::STC::STC>".parse().unwrap();
+        assert!(filter.0.matches(&balance.0));
+        let filter: StructTagFilterView = "*::*::Balance<0x2::Token::Token>".parse().unwrap();
+        assert!(!filter.0.matches(&balance.0));
+
+        let parsed: StructTagFilterView = filter.to_string().parse().unwrap();
+        assert_eq!(parsed, filter);
+
+        assert!("0x1::Account".parse::<StructTagFilterView>().is_err());
+        assert!("0x1::Account::Balance<*"
+            .parse::<StructTagFilterView>()
+            .is_err());
+    }
 }
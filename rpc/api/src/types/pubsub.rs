@@ -2,17 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::errors;
-use crate::types::{BlockView, TransactionEventView};
+use crate::types::{BlockView, StrView, TransactionEventView, TxPoolTxnSummaryView, TypeTagView};
 use jsonrpc_core::error::Error as JsonRpcError;
-use serde::de::Error;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::{from_value, Value};
 use starcoin_crypto::HashValue;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::BlockHeader;
 use starcoin_types::event::EventKey;
-use starcoin_types::filter::Filter;
+use starcoin_types::filter::{EventCriteria, Filter};
+use starcoin_types::transaction::{SignedUserTransaction, TransactionPayload};
 use starcoin_types::U256;
+use starcoin_vm_types::account_config::core_code_address;
 use starcoin_vm_types::genesis_config::ConsensusStrategy;
 use std::convert::TryInto;
+use std::str::FromStr;
 
 /// Subscription kind.
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
@@ -37,8 +41,12 @@ pub enum Result {
     Block(Box<BlockView>),
     /// Transaction hash
     TransactionHash(Vec<HashValue>),
+    /// The decoded pending txns, if the `decode` of the pending txn filter is set.
+    PendingTransactions(Vec<PendingTransactionView>),
     Event(Box<TransactionEventView>),
     MintBlock(Box<MintBlock>),
+    /// The result with its cursor, if the cursor is enabled by the `CursorOption`.
+    Cursored(Box<CursoredNotification<Result>>),
 }
 
 impl Serialize for Result {
@@ -50,18 +58,120 @@ impl Serialize for Result {
             Result::Block(ref header) => header.serialize(serializer),
             Result::Event(ref evt) => evt.serialize(serializer),
             Result::TransactionHash(ref hash) => hash.serialize(serializer),
+            Result::PendingTransactions(ref txns) => txns.serialize(serializer),
             Result::MintBlock(ref block) => block.serialize(serializer), // Result::SyncState(ref sync) => sync.serialize(serializer),
+            Result::Cursored(ref notification) => notification.serialize(serializer),
         }
     }
 }
 
-/// Subscription kind.
+/// The cursor of a notification of a resumable subscription, formatted as `epoch-stream-seq`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotificationCursor {
+    /// The start time of the pubsub service, the cursors before a restart of the node are rejected.
+    pub epoch: u64,
+    /// The stream which buffers the notifications of the subscription.
+    pub stream: u64,
+    /// The sequence of the notification in the stream, starts from 1.
+    pub seq: u64,
+}
+
+impl std::fmt::Display for StrView<NotificationCursor> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.0.epoch, self.0.stream, self.0.seq)
+    }
+}
+
+impl FromStr for StrView<NotificationCursor> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parts = s
+            .split('-')
+            .map(u64::from_str)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        match parts.as_slice() {
+            [epoch, stream, seq] => Ok(Self(NotificationCursor {
+                epoch: *epoch,
+                stream: *stream,
+                seq: *seq,
+            })),
+            _ => anyhow::bail!("invalid notification cursor {}", s),
+        }
+    }
+}
+
+/// A notification with its cursor. The cursors of the notifications of a subscription are
+/// increasing, the notifications of a same cursor are sent together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursoredNotification<T> {
+    pub cursor: StrView<NotificationCursor>,
+    pub result: T,
+}
+
+/// The cursor option of a subscription, set in the same params object as the filter.
+/// Only the `newHeads` and `events` subscriptions support the cursor.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CursorOption {
+    /// Notify the results with their cursors.
+    #[serde(default)]
+    pub with_cursor: bool,
+    /// Resume the subscription after the cursor of the last processed notification, the
+    /// notifications buffered by the node after the cursor are sent first. It implies `with_cursor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_from: Option<StrView<NotificationCursor>>,
+}
+
+impl CursorOption {
+    const FIELDS: &'static [&'static str] = &["with_cursor", "resume_from"];
+
+    pub fn new(resume_from: Option<NotificationCursor>) -> Self {
+        Self {
+            with_cursor: true,
+            resume_from: resume_from.map(StrView),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.with_cursor || self.resume_from.is_some()
+    }
+
+    /// Take the cursor option out of the params object, the remaining params are the filter.
+    pub fn take_from(params: &mut Option<Value>) -> std::result::Result<Self, JsonRpcError> {
+        let object = match params {
+            Some(Value::Object(object)) => object,
+            _ => return Ok(Self::default()),
+        };
+        let option: serde_json::Map<String, Value> = Self::FIELDS
+            .iter()
+            .filter_map(|field| object.remove(*field).map(|v| (field.to_string(), v)))
+            .collect();
+        from_value(Value::Object(option)).map_err(|e| errors::invalid_params("cursor", e))
+    }
+
+    /// Merge the cursor option into the params object of the filter.
+    pub fn merge_into(&self, params: Value) -> Value {
+        match (params, serde_json::to_value(self)) {
+            (Value::Object(mut params), Ok(Value::Object(option))) => {
+                params.extend(option);
+                Value::Object(params)
+            }
+            (params, _) => params,
+        }
+    }
+}
+
+/// Subscription params, the server side filter of the subscription.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize)]
 pub enum Params {
     /// No parameters passed.
     None,
     /// Log parameters.
     Events(EventFilter),
+    Blocks(BlockFilter),
+    PendingTxns(PendingTxnFilter),
+    MintBlocks(MintBlockFilter),
 }
 
 impl Default for Params {
@@ -70,23 +180,214 @@ impl Default for Params {
     }
 }
 
-impl<'a> Deserialize<'a> for Params {
-    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Params, D::Error>
-    where
-        D: Deserializer<'a>,
-    {
-        let v: Value = Deserialize::deserialize(deserializer)?;
+impl Params {
+    /// Parse the params by the subscription kind, as the filters of different kinds may have the same fields.
+    pub fn parse(kind: &Kind, params: Option<Value>) -> std::result::Result<Params, JsonRpcError> {
+        let params = match params {
+            Some(params) if !params.is_null() => params,
+            _ => return Ok(Params::None),
+        };
+        match kind {
+            Kind::NewHeads => from_value(params)
+                .map(Params::Blocks)
+                .map_err(|e| errors::invalid_params("newHeads", e)),
+            Kind::Events => from_value(params)
+                .map(Params::Events)
+                .map_err(|e| errors::invalid_params("events", e)),
+            Kind::NewPendingTransactions => from_value(params)
+                .map(Params::PendingTxns)
+                .map_err(|e| errors::invalid_params("newPendingTransactions", e)),
+            Kind::NewMintBlock => from_value(params)
+                .map(Params::MintBlocks)
+                .map_err(|e| errors::invalid_params("newMintBlock", e)),
+        }
+    }
+}
+
+/// Filter of the new blocks.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct BlockFilter {
+    /// Only notify the blocks whose number is not less than it.
+    #[serde(default)]
+    pub from_block: Option<u64>,
+    /// Only notify the blocks mined by one of the authors, if not empty.
+    #[serde(default)]
+    pub authors: Vec<AccountAddress>,
+}
+
+impl BlockFilter {
+    pub fn matching(&self, header: &BlockHeader) -> bool {
+        self.from_block
+            .map(|from_block| header.number() >= from_block)
+            .unwrap_or(true)
+            && (self.authors.is_empty() || self.authors.contains(&header.author()))
+    }
+}
+
+/// Filter of the new pending txns.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct PendingTxnFilter {
+    /// Only notify the txns sent by one of the senders, if not empty.
+    #[serde(default)]
+    pub senders: Vec<AccountAddress>,
+    /// Only notify the txns transferring to one of the receivers, if not empty.
+    /// The receivers are decoded from the transfer functions of `0x1::TransferScripts`.
+    #[serde(default)]
+    pub receivers: Vec<AccountAddress>,
+    /// Notify the decoded txns instead of the txn hashes.
+    #[serde(default)]
+    pub decode: bool,
+}
+
+impl PendingTxnFilter {
+    /// The filter notifies all the txn hashes, without looking up the txns.
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty() && self.receivers.is_empty() && !self.decode
+    }
+
+    pub fn matching(&self, txn: &SignedUserTransaction) -> bool {
+        (self.senders.is_empty() || self.senders.contains(&txn.sender()))
+            && (self.receivers.is_empty()
+                || decode_transfers(txn.payload())
+                    .iter()
+                    .any(|transfer| self.receivers.contains(&transfer.receiver)))
+    }
+}
+
+/// A transfer decoded from the payload of a txn.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+pub struct TransferView {
+    pub receiver: AccountAddress,
+    pub amount: StrView<u128>,
+    pub token_type: TypeTagView,
+}
 
-        if v.is_null() {
-            return Ok(Params::None);
+/// Decode the transfers of the `0x1::TransferScripts` functions, empty for the other payloads
+/// or the args can not be decoded.
+pub fn decode_transfers(payload: &TransactionPayload) -> Vec<TransferView> {
+    let function = match payload {
+        TransactionPayload::ScriptFunction(function)
+            if function.module().address() == &core_code_address()
+                && function.module().name().as_str() == "TransferScripts" =>
+        {
+            function
+        }
+        _ => return vec![],
+    };
+    let token_type = match function.ty_args().first() {
+        Some(token_type) => token_type.clone(),
+        None => return vec![],
+    };
+    let args = function.args();
+    let arg = |index: usize| args.get(index).map(Vec::as_slice).unwrap_or_default();
+    let transfers: Option<Vec<(AccountAddress, u128)>> = match function.function().as_str() {
+        "peer_to_peer" | "peer_to_peer_with_metadata" => {
+            bcs_ext::from_bytes::<AccountAddress>(arg(0))
+                .and_then(|receiver| Ok((receiver, bcs_ext::from_bytes::<u128>(arg(2))?)))
+                .ok()
+                .map(|transfer| vec![transfer])
+        }
+        "peer_to_peer_v2" | "peer_to_peer_with_metadata_v2" => {
+            bcs_ext::from_bytes::<AccountAddress>(arg(0))
+                .and_then(|receiver| Ok((receiver, bcs_ext::from_bytes::<u128>(arg(1))?)))
+                .ok()
+                .map(|transfer| vec![transfer])
+        }
+        "batch_peer_to_peer" => bcs_ext::from_bytes::<Vec<AccountAddress>>(arg(0))
+            .and_then(|receivers| {
+                Ok(receivers
+                    .into_iter()
+                    .zip(bcs_ext::from_bytes::<Vec<u128>>(arg(2))?)
+                    .collect())
+            })
+            .ok(),
+        "peer_to_peer_batch" => bcs_ext::from_bytes::<Vec<u8>>(arg(0))
+            .and_then(|receivers| {
+                let amount = bcs_ext::from_bytes::<u128>(arg(2))?;
+                receivers
+                    .chunks(AccountAddress::LENGTH)
+                    .map(|receiver| Ok((AccountAddress::from_bytes(receiver)?, amount)))
+                    .collect()
+            })
+            .ok(),
+        _ => None,
+    };
+    transfers
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(receiver, amount)| TransferView {
+            receiver,
+            amount: StrView(amount),
+            token_type: StrView(token_type.clone()),
+        })
+        .collect()
+}
+
+/// The decoded summary of a new pending txn.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+pub struct PendingTransactionView {
+    pub transaction_hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: StrView<u64>,
+    pub max_gas_amount: StrView<u64>,
+    pub gas_unit_price: StrView<u64>,
+    pub gas_token_code: String,
+    pub expiration_timestamp_secs: StrView<u64>,
+    /// The script function id, or `script` and `package` for the other payloads.
+    pub payload: String,
+    /// The type args of the script function or script.
+    pub ty_args: Vec<TypeTagView>,
+    /// The bcs encoded args of the script function or script.
+    pub args: Vec<StrView<Vec<u8>>>,
+    /// The transfers decoded from the payload.
+    pub transfers: Vec<TransferView>,
+}
+
+impl From<SignedUserTransaction> for PendingTransactionView {
+    fn from(txn: SignedUserTransaction) -> Self {
+        let (ty_args, args) = match txn.payload() {
+            TransactionPayload::ScriptFunction(function) => {
+                (function.ty_args().to_vec(), function.args().to_vec())
+            }
+            TransactionPayload::Script(script) => {
+                (script.ty_args().to_vec(), script.args().to_vec())
+            }
+            TransactionPayload::Package(_) => (vec![], vec![]),
+        };
+        let transfers = decode_transfers(txn.payload());
+        let summary = TxPoolTxnSummaryView::from(txn.clone());
+        Self {
+            transaction_hash: summary.transaction_hash,
+            sender: txn.sender(),
+            sequence_number: txn.sequence_number().into(),
+            max_gas_amount: summary.max_gas_amount,
+            gas_unit_price: summary.gas_unit_price,
+            gas_token_code: summary.gas_token_code,
+            expiration_timestamp_secs: summary.expiration_timestamp_secs,
+            payload: summary.payload,
+            ty_args: ty_args.into_iter().map(StrView).collect(),
+            args: args.into_iter().map(StrView).collect(),
+            transfers,
         }
-        // Err(D::Error::custom("Invalid Pub-Sub parameters"));
-        from_value(v)
-            .map(Params::Events)
-            .map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
     }
 }
 
+/// Filter of the mint blocks.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct MintBlockFilter {
+    /// Also notify the template updates, which add the new txns to the template of the same
+    /// parent block. Only the new templates on the new parent blocks are notified by default.
+    #[serde(default)]
+    pub updates: bool,
+    /// Only notify an update when at least `min_added_txns` txns are added since the last
+    /// notified template, the skipped updates are merged into the notified one.
+    #[serde(default)]
+    pub min_added_txns: Option<u64>,
+}
+
 /// Filter
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
 #[serde(deny_unknown_fields)]
@@ -100,11 +401,52 @@ pub struct EventFilter {
     /// Event keys
     #[serde(default)]
     pub event_keys: Vec<EventKey>,
+    /// The addresses of the event handles
+    #[serde(default)]
+    pub addresses: Vec<AccountAddress>,
+    /// Event types
+    #[serde(default)]
+    pub type_tags: Vec<TypeTagView>,
+    /// The senders of the txns which emit the events
+    #[serde(default)]
+    pub senders: Vec<AccountAddress>,
+    /// The events must also match at least one of the criteria, if not empty.
+    #[serde(default)]
+    pub any_of: Vec<EventCriteriaView>,
     /// Limit: from latest to oldest
     #[serde(default)]
     pub limit: Option<usize>,
 }
 
+/// A group of event conditions, the specified fields are all required to match.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct EventCriteriaView {
+    #[serde(default)]
+    pub event_keys: Vec<EventKey>,
+    #[serde(default)]
+    pub addresses: Vec<AccountAddress>,
+    #[serde(default)]
+    pub type_tags: Vec<TypeTagView>,
+    #[serde(default)]
+    pub senders: Vec<AccountAddress>,
+}
+
+impl From<EventCriteriaView> for EventCriteria {
+    fn from(view: EventCriteriaView) -> Self {
+        Self {
+            event_keys: view.event_keys,
+            addresses: view.addresses,
+            type_tags: view
+                .type_tags
+                .into_iter()
+                .map(|type_tag| type_tag.0)
+                .collect(),
+            senders: view.senders,
+        }
+    }
+}
+
 impl TryInto<Filter> for EventFilter {
     type Error = JsonRpcError;
 
@@ -122,6 +464,14 @@ impl TryInto<Filter> for EventFilter {
             from_block: self.from_block.unwrap_or(0),
             to_block: self.to_block.unwrap_or(std::u64::MAX),
             event_keys: self.event_keys,
+            addresses: self.addresses,
+            type_tags: self
+                .type_tags
+                .into_iter()
+                .map(|type_tag| type_tag.0)
+                .collect(),
+            senders: self.senders,
+            any_of: self.any_of.into_iter().map(Into::into).collect(),
             limit: self.limit,
             reverse: true,
         })
@@ -136,4 +486,29 @@ pub struct MintBlock {
     pub minting_blob: String,
     pub difficulty: U256,
     pub block_number: u64,
+    /// The txns added since the last notified template of the same parent block,
+    /// absent if it is a new template on a new parent block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_txns: Option<Vec<HashValue>>,
+}
+
+/// An active subscription of the node, listed by `node.subscriptions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionView {
+    pub id: String,
+    pub kind: Kind,
+    /// The id of the connection which created the subscription.
+    pub connection_id: Option<u64>,
+    /// The ip of the connection, None if the transport can not get the ip, such as websocket and ipc.
+    pub ip: Option<String>,
+    /// The unix timestamp in milliseconds when the subscription is created.
+    pub created_at: u64,
+    pub age_secs: u64,
+    /// The notifications delivered to the connection.
+    pub delivered: u64,
+    /// The notifications queued but not delivered yet,
+    /// None if the subscription does not track the queue, such as newPendingTransactions.
+    pub delivery_lag: Option<u64>,
+    /// The unix timestamp in milliseconds of the last delivered notification.
+    pub last_delivered_at: Option<u64>,
 }
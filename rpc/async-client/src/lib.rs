@@ -0,0 +1,235 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+//! An async rpc client for the rust services. The calls are made by the typed clients generated
+//! from the rpc api traits, so the client types can not drift from the server types.
+//! The client keeps a pool of connections, a lost connection is reconnected by the next call on
+//! it, and the subscriptions made by the client are subscribed again after the reconnection.
+
+use anyhow::{anyhow, Result};
+use futures::lock::Mutex;
+use futures::stream::{BoxStream, StreamExt};
+use futures_timer::Delay;
+use jsonrpc_core_client::transports::{ipc, ws};
+use jsonrpc_core_client::{RpcChannel, RpcError, TypedSubscriptionStream};
+use serde::de::DeserializeOwned;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::service::RpcAsyncService;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use starcoin_rpc_client::RpcClientInner;
+
+/// The interval to subscribe again after a failed subscription.
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+enum ConnSource {
+    Ipc(PathBuf),
+    WebSocket(String),
+    Local(Box<RpcChannel>),
+}
+
+impl ConnSource {
+    async fn connect(&self) -> Result<RpcChannel, RpcError> {
+        match self.clone() {
+            ConnSource::Ipc(sock_path) => ipc::connect(sock_path).await,
+            ConnSource::WebSocket(url) => ws::try_connect(url.as_str())?.await,
+            ConnSource::Local(channel) => Ok(*channel),
+        }
+    }
+}
+
+impl std::fmt::Debug for ConnSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnSource::Ipc(path) => write!(f, "Ipc({})", path.as_path().to_string_lossy()),
+            ConnSource::WebSocket(url) => write!(f, "WebSocket({})", url),
+            ConnSource::Local(_) => write!(f, "Local"),
+        }
+    }
+}
+
+struct Connection {
+    /// Increased by every reconnection, so a failure of the old connection does not drop the new one.
+    generation: u64,
+    /// The typed clients of the connection, None if the connection is lost.
+    clients: Option<RpcClientInner>,
+}
+
+struct ConnectionPool {
+    conn_source: ConnSource,
+    /// The connections are locked while they are reconnected, so the callers of a lost connection
+    /// wait for a single reconnection instead of each making its own.
+    connections: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl ConnectionPool {
+    fn next_index(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len()
+    }
+
+    /// Get the clients of the connection, reconnect if it is lost.
+    async fn get(&self, index: usize) -> Result<(u64, RpcClientInner), RpcError> {
+        let mut connection = self.connections[index].lock().await;
+        if let Some(clients) = connection.clients.as_ref() {
+            return Ok((connection.generation, clients.clone()));
+        }
+        info!(
+            "Connection {} is lost, try reconnect by {:?}",
+            index, &self.conn_source
+        );
+        let clients: RpcClientInner = self.conn_source.connect().await?.into();
+        connection.generation += 1;
+        connection.clients = Some(clients.clone());
+        Ok((connection.generation, clients))
+    }
+
+    /// Mark the connection lost if it is still of the `generation`.
+    async fn lost(&self, index: usize, generation: u64) {
+        let mut connection = self.connections[index].lock().await;
+        if connection.generation == generation {
+            connection.clients = None;
+        }
+    }
+
+    async fn call<F, Fut, T>(&self, index: usize, f: F) -> Result<T, RpcError>
+    where
+        F: FnOnce(RpcClientInner) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        let (generation, clients) = self.get(index).await?;
+        let result = f(clients).await;
+        if let Err(RpcError::Other(e)) = &result {
+            error!("rpc error of connection {} due to {}", index, e);
+            self.lost(index, generation).await;
+        }
+        result
+    }
+}
+
+pub struct AsyncRpcClient {
+    pool: Arc<ConnectionPool>,
+}
+
+impl AsyncRpcClient {
+    async fn new(conn_source: ConnSource, pool_size: usize) -> Result<Self> {
+        let pool_size = pool_size.max(1);
+        let mut connections = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let clients: RpcClientInner = conn_source.connect().await.map_err(map_err)?.into();
+            connections.push(Mutex::new(Connection {
+                generation: 0,
+                clients: Some(clients),
+            }));
+        }
+        Ok(Self {
+            pool: Arc::new(ConnectionPool {
+                conn_source,
+                connections,
+                next: AtomicUsize::new(0),
+            }),
+        })
+    }
+
+    /// Connect to the websocket url with `pool_size` connections.
+    pub async fn connect_websocket(url: &str, pool_size: usize) -> Result<Self> {
+        Self::new(ConnSource::WebSocket(url.to_string()), pool_size).await
+    }
+
+    /// Connect to the ipc file with `pool_size` connections.
+    pub async fn connect_ipc<P: AsRef<Path>>(sock_path: P, pool_size: usize) -> Result<Self> {
+        Self::new(ConnSource::Ipc(sock_path.as_ref().to_path_buf()), pool_size).await
+    }
+
+    /// Connect to the rpc service in the same process, a single local channel is enough.
+    pub async fn connect_local<S>(rpc_service: S) -> Result<Self>
+    where
+        S: RpcAsyncService,
+    {
+        let channel = rpc_service.connect_local().await?;
+        Self::new(ConnSource::Local(Box::new(channel)), 1).await
+    }
+
+    /// Call by the typed clients of a pooled connection, the connections are used in turn.
+    /// A connection is dropped when a call on it fails by a transport error, and reconnected
+    /// by the next call on it.
+    pub async fn call<F, Fut, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(RpcClientInner) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        let index = self.pool.next_index();
+        self.pool.call(index, f).await.map_err(map_err)
+    }
+
+    /// Subscribe by the typed clients of a pooled connection, the error of the first subscription
+    /// is returned. When the subscription is lost, the connection is reconnected and `subscribe` is
+    /// called again, so the stream goes on after the reconnection, the notifications sent while
+    /// the connection is lost are missed, unless `subscribe` resumes from the last cursor.
+    pub async fn subscribe<F, Fut, T>(&self, subscribe: F) -> Result<BoxStream<'static, T>>
+    where
+        F: Fn(RpcClientInner) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<TypedSubscriptionStream<T>, RpcError>> + Send + 'static,
+        T: DeserializeOwned + Send + 'static,
+    {
+        let index = self.pool.next_index();
+        let (generation, clients) = self.pool.get(index).await.map_err(map_err)?;
+        let stream = subscribe(clients).await.map_err(map_err)?;
+        let state = (self.pool.clone(), subscribe, generation, Some(stream));
+        Ok(futures::stream::unfold(
+            state,
+            move |(pool, subscribe, mut generation, mut stream)| async move {
+                loop {
+                    if let Some(current) = stream.as_mut() {
+                        match current.next().await {
+                            Some(Ok(item)) => {
+                                return Some((item, (pool, subscribe, generation, stream)))
+                            }
+                            Some(Err(e)) => {
+                                warn!("Subscription of connection {} failed: {}", index, e)
+                            }
+                            None => info!("Subscription of connection {} is closed", index),
+                        }
+                        stream = None;
+                        pool.lost(index, generation).await;
+                    }
+                    let resubscribed = match pool.get(index).await {
+                        Ok((new_generation, clients)) => {
+                            generation = new_generation;
+                            subscribe(clients).await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    match resubscribed {
+                        Ok(new_stream) => stream = Some(new_stream),
+                        Err(e) => {
+                            debug!("Resubscribe on connection {} failed: {}", index, e);
+                            if let RpcError::Other(_) = e {
+                                pool.lost(index, generation).await;
+                            }
+                            Delay::new(RESUBSCRIBE_INTERVAL).await;
+                        }
+                    }
+                }
+            },
+        )
+        .boxed())
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.pool.connections.len()
+    }
+}
+
+fn map_err(rpc_err: RpcError) -> anyhow::Error {
+    match rpc_err {
+        // keep the jsonrpc error, so the caller can get the error code by `RpcErrorCode::of_error`.
+        RpcError::JsonRpcError(e) => e.into(),
+        e => anyhow!(format!("{}", e)),
+    }
+}
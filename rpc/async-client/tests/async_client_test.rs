@@ -0,0 +1,83 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use anyhow::Result;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use starcoin_config::NodeConfig;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_async_client::AsyncRpcClient;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[stest::test(timeout = 120)]
+fn test_async_client() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let mut rt = tokio::runtime::Runtime::new()?;
+    let client = rt.block_on(AsyncRpcClient::connect_local(node_handle.rpc_service()?))?;
+    assert_eq!(client.pool_size(), 1);
+    let mut blocks = rt.block_on(
+        client.subscribe(|clients| async move { clients.pubsub().subscribe_new_block().await }),
+    )?;
+    node_handle.generate_block()?;
+
+    let chain_info =
+        rt.block_on(client.call(|clients| async move { clients.chain().info().await }))?;
+    assert_eq!(chain_info.head.number.0, 1);
+    let block = rt.block_on(blocks.next()).expect("should get a new block");
+    assert_eq!(block.header.block_hash, chain_info.head.block_hash);
+
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_async_client_resubscribe() -> Result<()> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let url = config.rpc.get_ws_address().unwrap().to_string();
+    let node_handle = test_helper::run_node_by_config(config.clone())?;
+    std::thread::sleep(Duration::from_millis(300));
+
+    let mut rt = tokio::runtime::Runtime::new()?;
+    let client = rt.block_on(AsyncRpcClient::connect_websocket(url.as_str(), 1))?;
+    let mut blocks = rt.block_on(
+        client.subscribe(|clients| async move { clients.pubsub().subscribe_new_block().await }),
+    )?;
+    let (sender, mut receiver) = mpsc::unbounded();
+    rt.spawn(async move {
+        while let Some(block) = blocks.next().await {
+            if sender.unbounded_send(block).is_err() {
+                break;
+            }
+        }
+    });
+    let block = node_handle.generate_block()?;
+    let notified = rt
+        .block_on(receiver.next())
+        .expect("should get a new block");
+    assert_eq!(notified.header.block_hash, block.id());
+
+    let _e = node_handle.stop();
+    let node_handle = test_helper::run_node_by_config(config)?;
+    // wait for the subscription to be subscribed again on the reconnected connection.
+    std::thread::sleep(Duration::from_secs(3));
+
+    let block = node_handle.generate_block()?;
+    let notified = rt
+        .block_on(async_std::future::timeout(
+            Duration::from_secs(10),
+            receiver.next(),
+        ))?
+        .expect("should get a new block after the reconnection");
+    assert_eq!(notified.header.block_hash, block.id());
+    assert_eq!(notified.header.number.0, 2);
+    // the connection is reconnected by the subscription, so the call does not fail.
+    let chain_info =
+        rt.block_on(client.call(|clients| async move { clients.chain().info().await }))?;
+    assert_eq!(chain_info.head.block_hash, block.id());
+
+    let _e = node_handle.stop();
+    Ok(())
+}
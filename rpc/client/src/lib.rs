@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2
 
 use crate::chain_watcher::{ChainWatcher, StartSubscribe, WatchBlock, WatchTxn};
-use crate::pubsub_client::PubSubClient;
 use actix::{Addr, System};
 use anyhow::anyhow;
+use bcs_ext::BCSCodec;
 use futures::channel::oneshot;
 use futures::{TryStream, TryStreamExt};
 use jsonrpc_client_transports::RawClient;
@@ -14,26 +14,35 @@ use network_p2p_types::network_state::NetworkState;
 use parking_lot::Mutex;
 use serde_json::Value;
 use starcoin_account_api::AccountInfo;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
 use starcoin_crypto::HashValue;
 use starcoin_logger::{prelude::*, LogPattern};
-use starcoin_rpc_api::node::NodeInfo;
+use starcoin_rpc_api::node::{NodeHealth, NodeInfo};
 use starcoin_rpc_api::service::RpcAsyncService;
 use starcoin_rpc_api::types::pubsub::EventFilter;
-use starcoin_rpc_api::types::pubsub::MintBlock;
+use starcoin_rpc_api::types::pubsub::{
+    BlockFilter, CursoredNotification, MintBlock, MintBlockFilter, NotificationCursor,
+    PendingTransactionView, PendingTxnFilter, SubscriptionView,
+};
 use starcoin_rpc_api::types::{
-    AccountStateSetView, AnnotatedMoveStructView, AnnotatedMoveValueView, BlockHeaderView,
-    BlockSummaryView, BlockView, ChainId, ChainInfoView, ContractCall, DryRunTransactionRequest,
-    EpochUncleSummaryView, FactoryAction, PeerInfoView, SignedUserTransactionView,
-    StateWithProofView, StrView, TransactionInfoView, TransactionOutputView, TransactionRequest,
-    TransactionView,
+    AccountStateAtView, AccountStateSetView, AnnotatedMoveStructView, AnnotatedMoveValueView,
+    BlockHeaderView, BlockSummaryView, BlockView, ChainId, ChainInfoView, ContractCall,
+    DBBackupView, DryRunOutputView, DryRunTransactionRequest, EpochInfoView, EpochUncleSummaryView,
+    EventCursorView, EventPageView, FactoryAction, GetBlockOption, GetBlocksOption,
+    GetTransactionOption, ListResourceView, PeerDetailView, PeerInfoView,
+    SignedUserTransactionView, StateRootOption, StateWithProofView, StrView, StructTagFilterView,
+    StructTagView, SubmitTransactionResultView, TransactionInfoView, TransactionInfoWithProofView,
+    TransactionRequest, TransactionTraceView, TransactionView, TxPoolContentView,
+    TxPoolInspectView, TypeTagView,
 };
 use starcoin_rpc_api::{
     account::AccountClient, chain::ChainClient, contract_api::ContractClient, debug::DebugClient,
     miner::MinerClient, network_manager::NetworkManagerClient, node::NodeClient,
-    node_manager::NodeManagerClient, state::StateClient, sync_manager::SyncManagerClient,
-    txpool::TxPoolClient, types::TransactionEventView,
+    node_admin::NodeAdminClient, node_manager::NodeManagerClient, state::StateClient,
+    sync_manager::SyncManagerClient, txpool::TxPoolClient, types::TransactionEventView,
 };
 use starcoin_service_registry::{ServiceInfo, ServiceStatus};
+use starcoin_state_api::snapshot::SnapshotInfo;
 use starcoin_sync_api::{PeerScoreResponse, SyncProgressReport};
 use starcoin_txpool_api::TxPoolStatus;
 use starcoin_types::access_path::AccessPath;
@@ -42,7 +51,9 @@ use starcoin_types::account_state::AccountState;
 use starcoin_types::block::{BlockInfo, BlockNumber};
 use starcoin_types::peer_info::{Multiaddr, PeerId};
 use starcoin_types::sync_status::SyncStatus;
-use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use starcoin_types::transaction::authenticator::AccountPublicKey;
+use starcoin_types::transaction::{RawUserTransaction, ScriptFunctionABI, SignedUserTransaction};
+use starcoin_vm_types::module_abi::{CompiledModuleABI, ModuleABI};
 use starcoin_vm_types::on_chain_resource::{EpochInfo, GlobalTimeOnChain};
 use starcoin_vm_types::token::token_code::TokenCode;
 use std::collections::HashMap;
@@ -52,14 +63,21 @@ use std::time::Duration;
 
 pub mod chain_watcher;
 mod pubsub_client;
+mod remote_state_node_store;
 mod remote_state_reader;
 
+pub use crate::pubsub_client::PubSubClient;
+pub use crate::remote_state_node_store::RemoteStateNodeStore;
 pub use crate::remote_state_reader::RemoteStateReader;
 pub use jsonrpc_core::Params;
-use starcoin_types::sign_message::SigningMessage;
-use starcoin_vm_types::language_storage::{ModuleId, StructTag};
+use starcoin_types::sign_message::{SigningMessage, TypedMessage};
+use starcoin_types::state_set::ChainStateSet;
+use starcoin_vm_types::language_storage::{FunctionId, ModuleId, StructTag};
 use tokio::runtime::Runtime;
 
+/// The count of the accounts to fetch by one `node_admin.dump_state` call.
+const DUMP_STATE_PAGE_SIZE: u64 = 1000;
+
 #[derive(Clone)]
 enum ConnSource {
     Ipc(PathBuf),
@@ -67,6 +85,16 @@ enum ConnSource {
     Local(Box<RpcChannel>),
 }
 
+impl ConnSource {
+    async fn connect(&self) -> anyhow::Result<RpcChannel, jsonrpc_client_transports::RpcError> {
+        match self.clone() {
+            ConnSource::Ipc(sock_path) => ipc::connect(sock_path).await,
+            ConnSource::WebSocket(url) => ws::try_connect(url.as_str())?.await,
+            ConnSource::Local(channel) => Ok(*channel),
+        }
+    }
+}
+
 impl std::fmt::Debug for ConnSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -113,11 +141,7 @@ impl ConnectionProvider {
     async fn get_rpc_channel_async(
         &self,
     ) -> anyhow::Result<RpcChannel, jsonrpc_client_transports::RpcError> {
-        match self.conn_source.clone() {
-            ConnSource::Ipc(sock_path) => ipc::connect(sock_path).await,
-            ConnSource::WebSocket(url) => ws::try_connect(url.as_str())?.await,
-            ConnSource::Local(channel) => Ok(*channel),
-        }
+        self.conn_source.connect().await
     }
 }
 
@@ -217,6 +241,16 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn node_snapshots(&self) -> anyhow::Result<Vec<SnapshotInfo>> {
+        self.call_rpc_blocking(|inner| inner.node_client.snapshots())
+            .map_err(map_err)
+    }
+
+    pub fn node_health(&self) -> anyhow::Result<NodeHealth> {
+        self.call_rpc_blocking(|inner| inner.node_client.health())
+            .map_err(map_err)
+    }
+
     pub fn node_list_service(&self) -> anyhow::Result<Vec<ServiceInfo>> {
         self.call_rpc_blocking(|inner| inner.node_manager_client.list_service())
             .map_err(map_err)
@@ -237,11 +271,63 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn node_subscriptions(&self) -> anyhow::Result<Vec<SubscriptionView>> {
+        self.call_rpc_blocking(|inner| inner.node_manager_client.subscriptions())
+            .map_err(map_err)
+    }
+
     pub fn node_shutdown_system(&self) -> anyhow::Result<()> {
         self.call_rpc_blocking(|inner| inner.node_manager_client.shutdown_system())
             .map_err(map_err)
     }
 
+    pub fn node_admin_add_peer(&self, peer: String) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.node_admin_client.add_peer(peer))
+            .map_err(map_err)
+    }
+
+    pub fn node_admin_remove_peer(&self, peer_id: PeerId) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.node_admin_client.remove_peer(peer_id.to_string()))
+            .map_err(map_err)
+    }
+
+    pub fn node_admin_ban_peer(&self, peer_id: PeerId, duration: Duration) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .node_admin_client
+                .ban_peer(peer_id.to_string(), duration.as_secs())
+        })
+        .map_err(map_err)
+    }
+
+    pub fn node_admin_set_log_level(
+        &self,
+        logger_name: Option<String>,
+        level: Level,
+    ) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .node_admin_client
+                .set_log_level(logger_name, level.to_string())
+        })
+        .map_err(map_err)
+    }
+
+    pub fn node_admin_compact_db(&self, cf: Option<String>) -> anyhow::Result<Vec<String>> {
+        self.call_rpc_blocking(|inner| inner.node_admin_client.compact_db(cf))
+            .map_err(map_err)
+    }
+
+    pub fn node_admin_rotate_api_keys(&self) -> anyhow::Result<HashMap<String, String>> {
+        self.call_rpc_blocking(|inner| inner.node_admin_client.rotate_api_keys())
+            .map_err(map_err)
+    }
+
+    pub fn node_admin_backup_db(&self, backup_dir: String) -> anyhow::Result<DBBackupView> {
+        self.call_rpc_blocking(|inner| inner.node_admin_client.backup_db(backup_dir))
+            .map_err(map_err)
+    }
+
     pub fn next_sequence_number_in_txpool(
         &self,
         address: AccountAddress,
@@ -251,10 +337,33 @@ impl RpcClient {
     }
 
     pub fn submit_transaction(&self, txn: SignedUserTransaction) -> anyhow::Result<HashValue> {
-        self.call_rpc_blocking(|inner| inner.txpool_client.submit_transaction(txn))
+        self.call_rpc_blocking(|inner| inner.txpool_client.submit_transaction(txn, None))
+            .map_err(map_err)
+    }
+
+    pub fn submit_transactions(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> anyhow::Result<Vec<SubmitTransactionResultView>> {
+        self.call_rpc_blocking(|inner| inner.txpool_client.submit_transactions(txns))
             .map_err(map_err)
     }
 
+    /// Submit the txn with a client request id, a retry with the same request id returns
+    /// the hash of the originally submitted txn, see `txpool.submit_transaction`.
+    pub fn submit_transaction_with_request_id(
+        &self,
+        txn: SignedUserTransaction,
+        request_id: String,
+    ) -> anyhow::Result<HashValue> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .txpool_client
+                .submit_transaction(txn, Some(request_id))
+        })
+        .map_err(map_err)
+    }
+
     pub fn get_pending_txn_by_hash(
         &self,
         txn_hash: HashValue,
@@ -340,6 +449,29 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn account_sign_typed_message(
+        &self,
+        signer: AccountAddress,
+        message: TypedMessage,
+    ) -> anyhow::Result<StrView<Vec<u8>>> {
+        self.call_rpc_blocking(|inner| inner.account_client.sign_typed_message(signer, message))
+            .map_err(map_err)
+    }
+
+    pub fn account_verify_typed_message(
+        &self,
+        message: TypedMessage,
+        signature: StrView<Vec<u8>>,
+        address: Option<AccountAddress>,
+    ) -> anyhow::Result<bool> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .account_client
+                .verify_typed_message(message, signature, address)
+        })
+        .map_err(map_err)
+    }
+
     pub fn account_change_password(
         &self,
         address: AccountAddress,
@@ -388,6 +520,20 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn account_rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    ) -> anyhow::Result<AccountInfo> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .account_client
+                .rotate_multisig_key(address, new_public_key, password)
+        })
+        .map_err(map_err)
+    }
+
     pub fn account_accepted_tokens(
         &self,
         address: AccountAddress,
@@ -398,11 +544,20 @@ impl RpcClient {
 
     pub fn get_code(&self, module_id: ModuleId) -> anyhow::Result<Option<String>> {
         let result: Option<StrView<Vec<u8>>> = self
-            .call_rpc_blocking(|inner| inner.contract_client.get_code(StrView(module_id)))
+            .call_rpc_blocking(|inner| inner.contract_client.get_code(StrView(module_id), None))
             .map_err(map_err)?;
         Ok(result.map(|s| s.to_string()))
     }
 
+    pub fn get_code_disassembled(&self, module_id: ModuleId) -> anyhow::Result<Option<String>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .contract_client
+                .get_code_disassembled(StrView(module_id), None)
+        })
+        .map_err(map_err)
+    }
+
     pub fn get_resource(
         &self,
         addr: AccountAddress,
@@ -411,11 +566,47 @@ impl RpcClient {
         self.call_rpc_blocking(|inner| {
             inner
                 .contract_client
-                .get_resource(addr, StrView(resource_type))
+                .get_resource(addr, StrView(resource_type), None)
         })
         .map_err(map_err)
     }
 
+    /// Get the resource at a historical state.
+    pub fn get_resource_at(
+        &self,
+        addr: AccountAddress,
+        resource_type: StructTag,
+        state_root: StateRootOption,
+    ) -> anyhow::Result<Option<AnnotatedMoveStructView>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .contract_client
+                .get_resource(addr, StrView(resource_type), Some(state_root))
+        })
+        .map_err(map_err)
+    }
+
+    pub fn contract_get_abi(&self, module_id: ModuleId) -> anyhow::Result<Option<ModuleABI>> {
+        self.call_rpc_blocking(|inner| inner.contract_client.get_abi(StrView(module_id)))
+            .map_err(map_err)
+    }
+
+    pub fn contract_get_module_abi(
+        &self,
+        module_id: ModuleId,
+    ) -> anyhow::Result<Option<CompiledModuleABI>> {
+        self.call_rpc_blocking(|inner| inner.contract_client.get_module_abi(StrView(module_id)))
+            .map_err(map_err)
+    }
+
+    pub fn contract_resolve_function(
+        &self,
+        function_id: FunctionId,
+    ) -> anyhow::Result<ScriptFunctionABI> {
+        self.call_rpc_blocking(|inner| inner.contract_client.resolve_function(StrView(function_id)))
+            .map_err(map_err)
+    }
+
     pub fn state_get(&self, access_path: AccessPath) -> anyhow::Result<Option<Vec<u8>>> {
         self.call_rpc_blocking(|inner| inner.state_client.get(access_path))
             .map_err(map_err)
@@ -442,6 +633,71 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    /// Get the state at `access_path` of `state_root`, and verify the proof returned by the node
+    /// with the `state_root`, so the state of an untrusted node can be used.
+    pub fn state_get_verified_by_root(
+        &self,
+        access_path: AccessPath,
+        state_root: HashValue,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let state_with_proof =
+            self.state_get_with_proof_by_root(access_path.clone(), state_root)?;
+        state_with_proof
+            .verify(state_root, access_path.clone())
+            .map_err(|e| {
+                anyhow!(
+                    "Verify state proof of {} by root {} failed: {}",
+                    access_path,
+                    state_root,
+                    e
+                )
+            })?;
+        Ok(state_with_proof.state.map(|state| state.0))
+    }
+
+    pub fn state_get_state_node_by_node_hash(
+        &self,
+        node_hash: HashValue,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        self.call_rpc_blocking(|inner| inner.state_client.get_state_node_by_node_hash(node_hash))
+            .map_err(map_err)
+    }
+
+    pub fn node_admin_dump_state(
+        &self,
+        state_root: HashValue,
+        cursor: Option<HashValue>,
+        limit: u64,
+    ) -> anyhow::Result<(ChainStateSet, Option<HashValue>)> {
+        let page = self
+            .call_rpc_blocking(|inner| {
+                inner
+                    .node_admin_client
+                    .dump_state(state_root, cursor, limit)
+            })
+            .map_err(map_err)?;
+        Ok((
+            bcs_ext::from_bytes::<ChainStateSet>(page.state.0.as_slice())?,
+            page.next_cursor,
+        ))
+    }
+
+    /// Dump the whole chain state at the `state_root` page by page with `node_admin.dump_state`.
+    pub fn state_dump_by_root(&self, state_root: HashValue) -> anyhow::Result<ChainStateSet> {
+        let mut state_sets = vec![];
+        let mut cursor = None;
+        loop {
+            let (state_set, next_cursor) =
+                self.node_admin_dump_state(state_root, cursor, DUMP_STATE_PAGE_SIZE)?;
+            state_sets.extend(state_set.into_inner());
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        Ok(ChainStateSet::new(state_sets))
+    }
+
     pub fn state_get_state_root(&self) -> anyhow::Result<HashValue> {
         self.call_rpc_blocking(|inner| inner.state_client.get_state_root())
             .map_err(map_err)
@@ -463,8 +719,78 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn state_list_resources(
+        &self,
+        address: AccountAddress,
+        filter: Option<StructTagFilterView>,
+        cursor: Option<StructTagView>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<Option<ListResourceView>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .state_client
+                .list_resources(address, filter, cursor, limit)
+        })
+        .map_err(map_err)
+    }
+
+    pub fn state_get_balances(
+        &self,
+        addresses: Vec<AccountAddress>,
+        token_type: Option<TypeTagView>,
+    ) -> anyhow::Result<Vec<Option<StrView<u128>>>> {
+        self.call_rpc_blocking(|inner| inner.state_client.get_balances(addresses, token_type))
+            .map_err(map_err)
+    }
+
+    pub fn state_get_account_state_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<AccountStateAtView>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .state_client
+                .get_account_state_at(address, block_number)
+        })
+        .map_err(map_err)
+    }
+
+    pub fn state_get_balance_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+        token_type: Option<TypeTagView>,
+    ) -> anyhow::Result<Option<StrView<u128>>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .state_client
+                .get_balance_at(address, block_number, token_type)
+        })
+        .map_err(map_err)
+    }
+
+    pub fn state_get_resources(
+        &self,
+        addresses: Vec<AccountAddress>,
+        resource_type: StructTagView,
+    ) -> anyhow::Result<Vec<Option<AnnotatedMoveStructView>>> {
+        self.call_rpc_blocking(|inner| inner.state_client.get_resources(addresses, resource_type))
+            .map_err(map_err)
+    }
+
     pub fn contract_call(&self, call: ContractCall) -> anyhow::Result<Vec<AnnotatedMoveValueView>> {
-        self.call_rpc_blocking(|inner| inner.contract_client.call(call))
+        self.call_rpc_blocking(|inner| inner.contract_client.call(call, None))
+            .map_err(map_err)
+    }
+
+    /// Call the contract at a historical state.
+    pub fn contract_call_at(
+        &self,
+        call: ContractCall,
+        state_root: StateRootOption,
+    ) -> anyhow::Result<Vec<AnnotatedMoveValueView>> {
+        self.call_rpc_blocking(|inner| inner.contract_client.call(call, Some(state_root)))
             .map_err(map_err)
     }
 
@@ -496,6 +822,14 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn debug_trace_transaction(
+        &self,
+        txn_hash: HashValue,
+    ) -> anyhow::Result<Option<TransactionTraceView>> {
+        self.call_rpc_blocking(|inner| inner.debug_client.trace_transaction(txn_hash))
+            .map_err(map_err)
+    }
+
     pub fn sleep(&self, time: u64) -> anyhow::Result<()> {
         self.call_rpc_blocking(|inner| inner.debug_client.sleep(time))
             .map_err(map_err)
@@ -521,6 +855,15 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn get_epoch_infos_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> anyhow::Result<Vec<EpochInfoView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_epoch_infos_by_number(number, count))
+            .map_err(map_err)
+    }
+
     pub fn get_epoch_uncles_by_number(
         &self,
         number: BlockNumber,
@@ -554,7 +897,16 @@ impl RpcClient {
     }
 
     pub fn chain_get_block_by_hash(&self, hash: HashValue) -> anyhow::Result<Option<BlockView>> {
-        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_hash(hash))
+        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_hash(hash, None))
+            .map_err(map_err)
+    }
+
+    pub fn chain_get_block_by_hash_with_option(
+        &self,
+        hash: HashValue,
+        option: GetBlockOption,
+    ) -> anyhow::Result<Option<BlockView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_hash(hash, Some(option)))
             .map_err(map_err)
     }
 
@@ -562,7 +914,16 @@ impl RpcClient {
         &self,
         number: BlockNumber,
     ) -> anyhow::Result<Option<BlockView>> {
-        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_number(number))
+        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_number(number, None))
+            .map_err(map_err)
+    }
+
+    pub fn chain_get_block_by_number_with_option(
+        &self,
+        number: BlockNumber,
+        option: GetBlockOption,
+    ) -> anyhow::Result<Option<BlockView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_block_by_number(number, Some(option)))
             .map_err(map_err)
     }
 
@@ -579,15 +940,38 @@ impl RpcClient {
         number: Option<BlockNumber>,
         count: u64,
     ) -> anyhow::Result<Vec<BlockView>> {
-        self.call_rpc_blocking(|inner| inner.chain_client.get_blocks_by_number(number, count))
+        self.call_rpc_blocking(|inner| inner.chain_client.get_blocks_by_number(number, count, None))
             .map_err(map_err)
     }
 
+    pub fn chain_get_blocks_by_number_with_option(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+        option: GetBlocksOption,
+    ) -> anyhow::Result<Vec<BlockView>> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .chain_client
+                .get_blocks_by_number(number, count, Some(option))
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_get_transaction(
         &self,
         txn_id: HashValue,
     ) -> anyhow::Result<Option<TransactionView>> {
-        self.call_rpc_blocking(|inner| inner.chain_client.get_transaction(txn_id))
+        self.call_rpc_blocking(|inner| inner.chain_client.get_transaction(txn_id, None))
+            .map_err(map_err)
+    }
+
+    pub fn chain_get_transaction_with_option(
+        &self,
+        txn_id: HashValue,
+        option: GetTransactionOption,
+    ) -> anyhow::Result<Option<TransactionView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_transaction(txn_id, Some(option)))
             .map_err(map_err)
     }
 
@@ -607,6 +991,15 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn chain_get_events_after(
+        &self,
+        cursor: Option<EventCursorView>,
+        limit: u64,
+    ) -> anyhow::Result<EventPageView> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_events_after(cursor, limit))
+            .map_err(map_err)
+    }
+
     pub fn chain_get_block_txn_infos(
         &self,
         block_id: HashValue,
@@ -628,10 +1021,33 @@ impl RpcClient {
         .map_err(map_err)
     }
 
-    pub fn dry_run(&self, txn: DryRunTransactionRequest) -> anyhow::Result<TransactionOutputView> {
+    pub fn chain_get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        idx: u64,
+    ) -> anyhow::Result<Option<TransactionInfoWithProofView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_transaction_proof(block_id, idx))
+            .map_err(map_err)
+    }
+
+    pub fn dry_run(&self, txn: DryRunTransactionRequest) -> anyhow::Result<DryRunOutputView> {
         self.call_rpc_blocking(|inner| inner.contract_client.dry_run(txn))
             .map_err(map_err)
     }
+
+    pub fn dry_run_raw(
+        &self,
+        raw_txn: RawUserTransaction,
+        sender_public_key: AccountPublicKey,
+    ) -> anyhow::Result<DryRunOutputView> {
+        let raw_txn = hex::encode(raw_txn.encode()?);
+        self.call_rpc_blocking(|inner| {
+            inner
+                .contract_client
+                .dry_run_raw(raw_txn, StrView(sender_public_key))
+        })
+        .map_err(map_err)
+    }
     pub fn miner_submit(
         &self,
         minting_blob: String,
@@ -657,6 +1073,31 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn txpool_content(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<TxPoolContentView> {
+        self.call_rpc_blocking(|inner| inner.txpool_client.content(cursor, limit))
+            .map_err(map_err)
+    }
+
+    pub fn txpool_inspect(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<TxPoolInspectView> {
+        self.call_rpc_blocking(|inner| inner.txpool_client.inspect(cursor, limit))
+            .map_err(map_err)
+    }
+
+    /// The gas price suggested by the txpool.
+    pub fn txpool_gas_price(&self) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| inner.txpool_client.gas_price())
+            .map(|gas_price| gas_price.0)
+            .map_err(map_err)
+    }
+
     pub fn subscribe_events(
         &self,
         filter: EventFilter,
@@ -667,20 +1108,73 @@ impl RpcClient {
         })
         .map_err(map_err)
     }
+    pub fn subscribe_events_with_cursor(
+        &self,
+        filter: EventFilter,
+        resume_from: Option<NotificationCursor>,
+    ) -> anyhow::Result<
+        impl TryStream<Ok = CursoredNotification<TransactionEventView>, Error = anyhow::Error>,
+    > {
+        self.call_rpc_blocking(|inner| async move {
+            let res = inner
+                .pubsub_client
+                .subscribe_events_with_cursor(filter, resume_from)
+                .await;
+            res.map(|s| s.map_err(map_err))
+        })
+        .map_err(map_err)
+    }
+    pub fn subscribe_new_blocks_with_cursor(
+        &self,
+        filter: BlockFilter,
+        resume_from: Option<NotificationCursor>,
+    ) -> anyhow::Result<impl TryStream<Ok = CursoredNotification<BlockView>, Error = anyhow::Error>>
+    {
+        self.call_rpc_blocking(|inner| async move {
+            let res = inner
+                .pubsub_client
+                .subscribe_new_block_with_cursor(filter, resume_from)
+                .await;
+            res.map(|s| s.map_err(map_err))
+        })
+        .map_err(map_err)
+    }
     pub fn subscribe_new_blocks(
         &self,
+        filter: BlockFilter,
     ) -> anyhow::Result<impl TryStream<Ok = BlockView, Error = anyhow::Error>> {
         self.call_rpc_blocking(|inner| async move {
-            let res = inner.pubsub_client.subscribe_new_block().await;
+            let res = inner
+                .pubsub_client
+                .subscribe_new_block_by_filter(filter)
+                .await;
             res.map(|s| s.map_err(map_err))
         })
         .map_err(map_err)
     }
     pub fn subscribe_new_transactions(
         &self,
+        filter: PendingTxnFilter,
     ) -> anyhow::Result<impl TryStream<Ok = Vec<HashValue>, Error = anyhow::Error>> {
         self.call_rpc_blocking(|inner| async move {
-            let res = inner.pubsub_client.subscribe_new_transactions().await;
+            let res = inner
+                .pubsub_client
+                .subscribe_new_transactions_by_filter(filter)
+                .await;
+            res.map(|s| s.map_err(map_err))
+        })
+        .map_err(map_err)
+    }
+    pub fn subscribe_new_decoded_transactions(
+        &self,
+        filter: PendingTxnFilter,
+    ) -> anyhow::Result<impl TryStream<Ok = Vec<PendingTransactionView>, Error = anyhow::Error>>
+    {
+        self.call_rpc_blocking(|inner| async move {
+            let res = inner
+                .pubsub_client
+                .subscribe_new_decoded_transactions(filter)
+                .await;
             res.map(|s| s.map_err(map_err))
         })
         .map_err(map_err)
@@ -696,6 +1190,20 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    pub fn subscribe_new_mint_blocks_by_filter(
+        &self,
+        filter: MintBlockFilter,
+    ) -> anyhow::Result<impl TryStream<Ok = MintBlock, Error = anyhow::Error>> {
+        self.call_rpc_blocking(|inner| async move {
+            let res = inner
+                .pubsub_client
+                .subscribe_new_mint_block_by_filter(filter)
+                .await;
+            res.map(|s| s.map_err(map_err))
+        })
+        .map_err(map_err)
+    }
+
     pub async fn subscribe_new_mint_blocks_async(
         &self,
     ) -> anyhow::Result<impl TryStream<Ok = MintBlock, Error = anyhow::Error>> {
@@ -804,6 +1312,11 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn network_get_peers_detail(&self) -> anyhow::Result<Vec<PeerDetailView>> {
+        self.call_rpc_blocking(|inner| inner.network_client.get_peers_detail())
+            .map_err(map_err)
+    }
+
     pub fn network_add_peer(&self, peer: String) -> anyhow::Result<()> {
         self.call_rpc_blocking(|inner| inner.network_client.add_peer(peer))
             .map_err(map_err)
@@ -838,11 +1351,13 @@ impl RpcClient {
     }
 }
 
+/// The typed clients of a connection, generated from the rpc api traits.
 #[derive(Clone)]
-pub(crate) struct RpcClientInner {
+pub struct RpcClientInner {
     raw_client: RawClient,
     node_client: NodeClient,
     node_manager_client: NodeManagerClient,
+    node_admin_client: NodeAdminClient,
     txpool_client: TxPoolClient,
     account_client: AccountClient,
     state_client: StateClient,
@@ -861,6 +1376,7 @@ impl RpcClientInner {
             raw_client: channel.clone().into(),
             node_client: channel.clone().into(),
             node_manager_client: channel.clone().into(),
+            node_admin_client: channel.clone().into(),
             txpool_client: channel.clone().into(),
             account_client: channel.clone().into(),
             state_client: channel.clone().into(),
@@ -873,10 +1389,70 @@ impl RpcClientInner {
             network_client: channel.into(),
         }
     }
+
+    pub fn raw(&self) -> &RawClient {
+        &self.raw_client
+    }
+
+    pub fn node(&self) -> &NodeClient {
+        &self.node_client
+    }
+
+    pub fn node_manager(&self) -> &NodeManagerClient {
+        &self.node_manager_client
+    }
+
+    pub fn node_admin(&self) -> &NodeAdminClient {
+        &self.node_admin_client
+    }
+
+    pub fn txpool(&self) -> &TxPoolClient {
+        &self.txpool_client
+    }
+
+    pub fn account(&self) -> &AccountClient {
+        &self.account_client
+    }
+
+    pub fn state(&self) -> &StateClient {
+        &self.state_client
+    }
+
+    pub fn debug(&self) -> &DebugClient {
+        &self.debug_client
+    }
+
+    pub fn chain(&self) -> &ChainClient {
+        &self.chain_client
+    }
+
+    pub fn pubsub(&self) -> &PubSubClient {
+        &self.pubsub_client
+    }
+
+    pub fn contract(&self) -> &ContractClient {
+        &self.contract_client
+    }
+
+    pub fn miner(&self) -> &MinerClient {
+        &self.miner_client
+    }
+
+    pub fn sync(&self) -> &SyncManagerClient {
+        &self.sync_client
+    }
+
+    pub fn network(&self) -> &NetworkManagerClient {
+        &self.network_client
+    }
 }
 
 fn map_err(rpc_err: jsonrpc_client_transports::RpcError) -> anyhow::Error {
-    anyhow!(format!("{}", rpc_err))
+    match rpc_err {
+        // keep the jsonrpc error, so the caller can get the error code by `RpcErrorCode::of_error`.
+        jsonrpc_client_transports::RpcError::JsonRpcError(e) => e.into(),
+        e => anyhow!(format!("{}", e)),
+    }
 }
 
 impl From<RpcChannel> for RpcClientInner {
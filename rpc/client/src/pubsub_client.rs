@@ -3,7 +3,10 @@
 
 use jsonrpc_core_client::*;
 use starcoin_crypto::HashValue;
-use starcoin_rpc_api::types::pubsub::MintBlock;
+use starcoin_rpc_api::types::pubsub::{
+    BlockFilter, CursorOption, CursoredNotification, MintBlock, MintBlockFilter,
+    NotificationCursor, PendingTransactionView, PendingTxnFilter,
+};
 use starcoin_rpc_api::types::{pubsub::EventFilter, pubsub::Kind, BlockView, TransactionEventView};
 
 const STARCOIN_SUBSCRIPTION: &str = "starcoin_subscription";
@@ -41,37 +44,140 @@ impl PubSubClient {
             "Event",
         )
     }
+    /// Subscribe the events with their cursors, resume after the cursor if it is not None.
+    pub async fn subscribe_events_with_cursor(
+        &self,
+        filter: EventFilter,
+        resume_from: Option<NotificationCursor>,
+    ) -> Result<TypedSubscriptionStream<CursoredNotification<TransactionEventView>>, RpcError> {
+        let params = CursorOption::new(resume_from)
+            .merge_into(serde_json::to_value(filter).expect("filter to json should never fail"));
+        self.client.subscribe(
+            STARCOIN_SUBSCRIBE,
+            (Kind::Events, params),
+            STARCOIN_SUBSCRIPTION,
+            STARCOIN_UNSUBSCRIBE,
+            "CursoredNotification<Event>",
+        )
+    }
     pub async fn subscribe_new_block(
         &self,
     ) -> Result<TypedSubscriptionStream<BlockView>, RpcError> {
+        self.subscribe_new_block_by_filter(BlockFilter::default())
+            .await
+    }
+    /// The filter is not sent if it is the default, to be compatible with the nodes which do not support it.
+    pub async fn subscribe_new_block_by_filter(
+        &self,
+        filter: BlockFilter,
+    ) -> Result<TypedSubscriptionStream<BlockView>, RpcError> {
+        if filter == BlockFilter::default() {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                vec![Kind::NewHeads],
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "ThinBlock",
+            )
+        } else {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                (Kind::NewHeads, filter),
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "ThinBlock",
+            )
+        }
+    }
+    /// Subscribe the new blocks with their cursors, resume after the cursor if it is not None.
+    pub async fn subscribe_new_block_with_cursor(
+        &self,
+        filter: BlockFilter,
+        resume_from: Option<NotificationCursor>,
+    ) -> Result<TypedSubscriptionStream<CursoredNotification<BlockView>>, RpcError> {
+        let params = CursorOption::new(resume_from)
+            .merge_into(serde_json::to_value(filter).expect("filter to json should never fail"));
         self.client.subscribe(
             STARCOIN_SUBSCRIBE,
-            vec![Kind::NewHeads],
+            (Kind::NewHeads, params),
             STARCOIN_SUBSCRIPTION,
             STARCOIN_UNSUBSCRIBE,
-            "ThinBlock",
+            "CursoredNotification<ThinBlock>",
         )
     }
     pub async fn subscribe_new_transactions(
         &self,
     ) -> Result<TypedSubscriptionStream<Vec<HashValue>>, RpcError> {
+        self.subscribe_new_transactions_by_filter(PendingTxnFilter::default())
+            .await
+    }
+    /// The filter is not sent if it is the default, to be compatible with the nodes which do not support it.
+    pub async fn subscribe_new_transactions_by_filter(
+        &self,
+        filter: PendingTxnFilter,
+    ) -> Result<TypedSubscriptionStream<Vec<HashValue>>, RpcError> {
+        if filter == PendingTxnFilter::default() {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                vec![Kind::NewPendingTransactions],
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "Vec<HashValue>",
+            )
+        } else {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                (Kind::NewPendingTransactions, filter),
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "Vec<HashValue>",
+            )
+        }
+    }
+    /// Subscribe the decoded pending txns, the `decode` of the filter is always set.
+    pub async fn subscribe_new_decoded_transactions(
+        &self,
+        filter: PendingTxnFilter,
+    ) -> Result<TypedSubscriptionStream<Vec<PendingTransactionView>>, RpcError> {
+        let filter = PendingTxnFilter {
+            decode: true,
+            ..filter
+        };
         self.client.subscribe(
             STARCOIN_SUBSCRIBE,
-            vec![Kind::NewPendingTransactions],
+            (Kind::NewPendingTransactions, filter),
             STARCOIN_SUBSCRIPTION,
             STARCOIN_UNSUBSCRIBE,
-            "Vec<HashValue>",
+            "Vec<PendingTransactionView>",
         )
     }
     pub async fn subscribe_new_mint_block(
         &self,
     ) -> Result<TypedSubscriptionStream<MintBlock>, RpcError> {
-        self.client.subscribe(
-            STARCOIN_SUBSCRIBE,
-            vec![Kind::NewMintBlock],
-            STARCOIN_SUBSCRIPTION,
-            STARCOIN_UNSUBSCRIBE,
-            "MintBlock",
-        )
+        self.subscribe_new_mint_block_by_filter(MintBlockFilter::default())
+            .await
+    }
+    /// The filter is not sent if it is the default, to be compatible with the nodes which do not support it.
+    pub async fn subscribe_new_mint_block_by_filter(
+        &self,
+        filter: MintBlockFilter,
+    ) -> Result<TypedSubscriptionStream<MintBlock>, RpcError> {
+        if filter == MintBlockFilter::default() {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                vec![Kind::NewMintBlock],
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "MintBlock",
+            )
+        } else {
+            self.client.subscribe(
+                STARCOIN_SUBSCRIBE,
+                (Kind::NewMintBlock, filter),
+                STARCOIN_SUBSCRIPTION,
+                STARCOIN_UNSUBSCRIBE,
+                "MintBlock",
+            )
+        }
     }
 }
@@ -0,0 +1,54 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use crate::RpcClient;
+use anyhow::{ensure, Result};
+use starcoin_crypto::HashValue;
+use starcoin_state_tree::{StateNode, StateNodeStore};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A state node store which reads the missing nodes from a remote node and caches them in the local store,
+/// so the state tree of the remote node is loaded lazily, and the new nodes are only written to the local store.
+pub struct RemoteStateNodeStore {
+    local: Arc<dyn StateNodeStore>,
+    remote: Arc<RpcClient>,
+}
+
+impl RemoteStateNodeStore {
+    pub fn new(local: Arc<dyn StateNodeStore>, remote: Arc<RpcClient>) -> Self {
+        Self { local, remote }
+    }
+}
+
+impl StateNodeStore for RemoteStateNodeStore {
+    fn get(&self, hash: &HashValue) -> Result<Option<StateNode>> {
+        if let Some(node) = self.local.get(hash)? {
+            return Ok(Some(node));
+        }
+        match self.remote.state_get_state_node_by_node_hash(*hash)? {
+            Some(node) => {
+                let node = StateNode(node);
+                // Do not trust the remote node, the node must match the requested hash.
+                let node_hash = node.hash()?;
+                ensure!(
+                    node_hash == *hash,
+                    "The hash of the remote state node {} mismatch with the requested hash {}",
+                    node_hash,
+                    hash
+                );
+                self.local.put(*hash, node.clone())?;
+                Ok(Some(node))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn put(&self, key: HashValue, node: StateNode) -> Result<()> {
+        self.local.put(key, node)
+    }
+
+    fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()> {
+        self.local.write_nodes(nodes)
+    }
+}
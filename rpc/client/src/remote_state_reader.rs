@@ -10,6 +10,7 @@ use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_state::AccountState;
 use starcoin_types::state_set::{AccountStateSet, ChainStateSet};
 
+/// A `ChainStateReader` of a remote node, the states are verified with the state root by proof.
 pub struct RemoteStateReader<'a> {
     //TODO add cache.
     client: &'a RpcClient,
@@ -29,9 +30,11 @@ impl<'a> RemoteStateReader<'a> {
 
 impl<'a> ChainStateReader for RemoteStateReader<'a> {
     fn get_with_proof(&self, access_path: &AccessPath) -> Result<StateWithProof> {
-        self.client
-            .state_get_with_proof(access_path.clone())
-            .map(Into::into)
+        let state_with_proof = self
+            .client
+            .state_get_with_proof_by_root(access_path.clone(), self.state_root)?;
+        state_with_proof.verify(self.state_root, access_path.clone())?;
+        Ok(state_with_proof.into())
     }
 
     fn get_account_state(&self, address: &AccountAddress) -> Result<Option<AccountState>> {
@@ -53,11 +56,8 @@ impl<'a> ChainStateReader for RemoteStateReader<'a> {
 
 impl<'a> StateView for RemoteStateReader<'a> {
     fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
-        Ok(self
-            .client
-            .state_get_with_proof_by_root(access_path.clone(), self.state_root())?
-            .state
-            .map(|v| v.0))
+        self.client
+            .state_get_verified_by_root(access_path.clone(), self.state_root())
     }
 
     fn multi_get(&self, _access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
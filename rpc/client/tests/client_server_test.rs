@@ -2,11 +2,30 @@
 // SPDX-License-Identifier: Apache-2
 
 use anyhow::Result;
+use bcs_ext::BCSCodec;
 use futures::{StreamExt, TryStreamExt};
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_rpc_api::types::pubsub::MintBlock;
+use starcoin_rpc_api::types::{
+    AnnotatedMoveValueView, BlockTransactionsView, GetBlockOption, GetBlocksOption,
+    GetTransactionOption, StateRootOption, StrView,
+};
 use starcoin_rpc_client::RpcClient;
+use starcoin_types::access_path::AccessPath;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_config::{association_address, genesis_address, AccountResource};
+use starcoin_types::block::{BlockBody, BlockHeader};
+use starcoin_types::contract_event::ContractEvent;
+use starcoin_types::peer_info::PeerId;
+use starcoin_types::sign_message::TypedMessage;
+use starcoin_types::transaction::SignedUserTransaction;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::{ModuleId, StructTag};
+use starcoin_vm_types::module_abi::{FunctionVisibility, TypeABI};
+use starcoin_vm_types::move_resource::MoveResource;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -35,6 +54,13 @@ fn do_client_test() -> Result<()> {
     let status0 = local_client.node_info()?;
     info!("local_client status: {:?}", status0);
 
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let ipc_dir = ipc_file.parent().expect("ipc file should have a parent dir");
+        let mode = std::fs::metadata(ipc_dir)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
     let ipc_client = RpcClient::connect_ipc(ipc_file).expect("connect ipc fail.");
     let status1 = ipc_client.node_info()?;
     info!("ipc_client status: {:?}", status1);
@@ -139,3 +165,629 @@ fn test_client_reconnect_subscribe() -> Result<()> {
     assert_ne!(events2.len(), 0);
     Ok(())
 }
+
+#[stest::test(timeout = 120)]
+fn test_get_events_after() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let all_events = client.chain_get_events_after(None, u64::MAX)?;
+    assert!(!all_events.events.is_empty());
+
+    let mut cursor = None;
+    let mut events = vec![];
+    loop {
+        let page = client.chain_get_events_after(cursor, 1)?;
+        assert!(page.events.len() <= 1);
+        if page.events.is_empty() && page.next_cursor == cursor {
+            break;
+        }
+        events.extend(page.events);
+        cursor = page.next_cursor;
+    }
+    assert_eq!(events, all_events.events);
+    for pair in events.windows(2) {
+        assert!(pair[1].cursor.0.is_after(&pair[0].cursor.0));
+    }
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_state_get_verified_by_root() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let state_root = client.state_get_state_root()?;
+    let access_path = AccessPath::new(genesis_address(), AccountResource::resource_path());
+
+    let state_with_proof = client.state_get_with_proof_by_root(access_path.clone(), state_root)?;
+    assert!(state_with_proof.state.is_some());
+    state_with_proof.verify(state_root, access_path.clone())?;
+    // the proof can not be verified with other root.
+    assert!(state_with_proof
+        .verify(HashValue::random(), access_path.clone())
+        .is_err());
+
+    let state = client.state_get_verified_by_root(access_path.clone(), state_root)?;
+    assert_eq!(state, state_with_proof.state.map(|state| state.0));
+    // a tampered state can not be verified.
+    let mut tampered = client.state_get_with_proof_by_root(access_path.clone(), state_root)?;
+    tampered.state = Some(vec![0u8; 8].into());
+    assert!(tampered.verify(state_root, access_path).is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_state_bulk_query() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let addresses = vec![association_address(), AccountAddress::random()];
+
+    let balances = client.state_get_balances(addresses.clone(), None)?;
+    assert_eq!(balances.len(), 2);
+    assert!(balances[0].expect("association should have STC").0 > 0);
+    assert!(balances[1].is_none());
+
+    let resources =
+        client.state_get_resources(addresses.clone(), StrView(AccountResource::struct_tag()))?;
+    assert_eq!(resources.len(), 2);
+    assert!(resources[0].is_some());
+    assert!(resources[1].is_none());
+
+    // too many addresses in a call.
+    let addresses = (0..1001).map(|_| AccountAddress::random()).collect();
+    assert!(client.state_get_balances(addresses, None).is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_state_list_resources() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let all = client
+        .get_account_state_set(genesis_address())?
+        .expect("genesis account should exist")
+        .resources;
+
+    let filter = "0x1::Account::*".parse()?;
+    let page = client
+        .state_list_resources(genesis_address(), Some(filter), None, None)?
+        .expect("genesis account should exist");
+    assert!(!page.resources.is_empty());
+    assert!(page.resources.len() < all.len());
+    assert!(page
+        .resources
+        .keys()
+        .all(|struct_tag| struct_tag.0.module.as_str() == "Account"));
+
+    // list all resources page by page.
+    let mut listed = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
+            .state_list_resources(genesis_address(), None, cursor, Some(2))?
+            .expect("genesis account should exist");
+        assert!(page.resources.len() <= 2);
+        listed.extend(page.resources.into_iter().map(|(k, _)| k));
+        match page.next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+    assert_eq!(listed, all.into_iter().map(|(k, _)| k).collect::<Vec<_>>());
+    // an empty page can not carry the cursor of the next page.
+    assert!(client
+        .state_list_resources(genesis_address(), None, None, Some(0))
+        .is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_transaction_proof() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let block = client
+        .chain_get_block_by_number(0)?
+        .expect("genesis block should exist");
+    let block_hash = block.header.block_hash;
+    let txn_accumulator_root = block.header.txn_accumulator_root;
+
+    let txn_proof = client
+        .chain_get_transaction_proof(block_hash, 0)?
+        .expect("genesis txn should exist");
+    assert_eq!(txn_proof.txn_accumulator_root, txn_accumulator_root);
+    txn_proof.verify(txn_accumulator_root)?;
+    assert!(txn_proof.verify(HashValue::random()).is_err());
+
+    let events: Vec<ContractEvent> = client
+        .chain_get_events_by_txn_hash(txn_proof.transaction_info.transaction_hash)?
+        .into_iter()
+        .map(|event| {
+            ContractEvent::new(
+                event.event_key,
+                event.event_seq_number.0,
+                event.type_tag,
+                event.data.0,
+            )
+        })
+        .collect();
+    assert!(!events.is_empty());
+    txn_proof.verify_events(&events)?;
+    assert!(txn_proof.verify_events(&events[1..]).is_err());
+
+    // a tampered txn info can not be verified.
+    let mut tampered = txn_proof.clone();
+    tampered.transaction_info.event_root_hash = HashValue::random();
+    assert!(tampered.verify(txn_accumulator_root).is_err());
+
+    assert!(client
+        .chain_get_transaction_proof(block_hash, u32::MAX as u64)?
+        .is_none());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_raw_block_and_transaction() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let block = client
+        .chain_get_block_by_number(0)?
+        .expect("genesis block should exist");
+    assert!(block.raw.is_none());
+    let block_hash = block.header.block_hash;
+
+    let raw_block = client
+        .chain_get_block_by_hash_with_option(block_hash, GetBlockOption { raw: true })?
+        .and_then(|block| block.raw)
+        .expect("raw block should exist");
+    let header = BlockHeader::decode(raw_block.header.0.as_slice())?;
+    assert_eq!(header.id(), block_hash);
+    let body = BlockBody::decode(raw_block.body.0.as_slice())?;
+    assert_eq!(body.hash(), header.body_hash());
+    assert_eq!(
+        client
+            .chain_get_block_by_number_with_option(0, GetBlockOption { raw: true })?
+            .and_then(|block| block.raw),
+        Some(raw_block)
+    );
+
+    let txn_hash = client.chain_get_block_txn_infos(block_hash)?[0].transaction_hash;
+    let txn = client
+        .chain_get_transaction_with_option(txn_hash, GetTransactionOption { raw: true })?
+        .expect("genesis txn should exist");
+    let raw_txn = txn.raw.expect("raw txn should exist");
+    let signed_txn = SignedUserTransaction::decode(raw_txn.0.as_slice())?;
+    assert_eq!(signed_txn.id(), txn_hash);
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_epoch_infos_by_number() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+
+    // all the blocks are in the first epoch.
+    let epoch_infos = client.get_epoch_infos_by_number(None, 10)?;
+    assert_eq!(epoch_infos.len(), 1);
+    let epoch_info = &epoch_infos[0];
+    assert_eq!(epoch_info.number.0, 0);
+    assert_eq!(epoch_info.block_number.0, 3);
+    assert_eq!(
+        epoch_info.blocks.0,
+        epoch_info.block_number.0 + 1 - epoch_info.start_block_number.0
+    );
+    assert_eq!(epoch_info.uncles.0, 0);
+    assert_eq!(epoch_info.uncle_rate.0, 0);
+    assert_eq!(
+        epoch_info.block_time_target.0,
+        client.epoch_info()?.block_time_target()
+    );
+
+    let epoch_infos = client.get_epoch_infos_by_number(Some(1), 10)?;
+    assert_eq!(epoch_infos.len(), 1);
+    assert_eq!(epoch_infos[0].block_number.0, 1);
+    assert!(client.get_epoch_infos_by_number(None, 0)?.is_empty());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_peers_detail() -> Result<()> {
+    let node_config1 = Arc::new(NodeConfig::random_for_test());
+    let node1 = test_helper::run_node_by_config(node_config1.clone())?;
+    let client1 = RpcClient::connect_local(node1.rpc_service()?)?;
+    assert!(client1.network_get_peers_detail()?.is_empty());
+
+    let mut node_config2 = NodeConfig::random_for_test();
+    node_config2.network.seeds = vec![node_config1.network.self_address()].into();
+    let node2 = test_helper::run_node_by_config(Arc::new(node_config2))?;
+    let client2 = RpcClient::connect_local(node2.rpc_service()?)?;
+    let peer_id1 = client1.node_info()?.peer_info.peer_id;
+    let peer_id2 = client2.node_info()?.peer_info.peer_id;
+
+    // the block announced by node1 is counted by the traffic of the both sides.
+    node1.generate_block()?;
+    let (peers1, peers2) = loop {
+        let peers1 = client1.network_get_peers_detail()?;
+        let peers2 = client2.network_get_peers_detail()?;
+        if peers2
+            .iter()
+            .any(|peer| peer.last_received_at.is_some() && peer.bytes_in.0 > 0)
+        {
+            break (peers1, peers2);
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    };
+    assert_eq!(peers1.len(), 1);
+    assert_eq!(peers2.len(), 1);
+    let (peer1, peer2) = (&peers2[0], &peers1[0]);
+    assert_eq!(peer1.peer_info.peer_id, peer_id1);
+    assert_eq!(peer2.peer_info.peer_id, peer_id2);
+    // the both sides negotiate the same protocol version.
+    assert!(peer1.version > 0);
+    assert_eq!(peer1.version, peer2.version);
+    assert!(!peer2.peer_info.notif_protocols.is_empty());
+    assert!(!peer2.peer_info.rpc_protocols.is_empty());
+    assert!(peer2.reputation.is_some());
+    assert!(peer2.connected_at.0 > 0);
+    assert!(peer2.bytes_out.0 > 0);
+
+    client2.close();
+    client1.close();
+    node2.stop()?;
+    node1.stop()?;
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_blocks_by_number_with_option() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let desc_blocks = client.chain_get_blocks_by_number(None, 3)?;
+    assert_eq!(
+        desc_blocks
+            .iter()
+            .map(|block| block.header.number.0)
+            .collect::<Vec<_>>(),
+        vec![3, 2, 1]
+    );
+
+    let asc_blocks = client.chain_get_blocks_by_number_with_option(
+        Some(1),
+        10,
+        GetBlocksOption {
+            reverse: false,
+            full_txns: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        asc_blocks
+            .iter()
+            .map(|block| block.header.number.0)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!(asc_blocks
+        .iter()
+        .all(|block| matches!(block.body, BlockTransactionsView::Hashes(_))));
+
+    let full_blocks = client.chain_get_blocks_by_number_with_option(
+        None,
+        2,
+        GetBlocksOption {
+            reverse: false,
+            full_txns: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(full_blocks.len(), 2);
+    assert_eq!(full_blocks[0].header.number.0, 0);
+    assert!(full_blocks
+        .iter()
+        .all(|block| matches!(block.body, BlockTransactionsView::Full(_))));
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_node_admin() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    client.node_admin_set_log_level(None, Level::Info)?;
+    client.node_admin_set_log_level(Some("network_p2p".to_string()), Level::Debug)?;
+
+    let peer_id = PeerId::random();
+    client.node_admin_ban_peer(peer_id.clone(), Duration::from_secs(60))?;
+    client.node_admin_remove_peer(peer_id)?;
+
+    let cfs = client.node_admin_compact_db(Some("block".to_string()))?;
+    assert_eq!(cfs, vec!["block".to_string()]);
+    assert!(client
+        .node_admin_compact_db(Some("unknown_cf".to_string()))
+        .is_err());
+    assert!(!client.node_admin_compact_db(None)?.is_empty());
+
+    let backup_dir = starcoin_config::temp_path();
+    let backup_dir = backup_dir.path().to_string_lossy().to_string();
+    let backup = client.node_admin_backup_db(backup_dir.clone())?;
+    assert_eq!(backup.id, 1);
+    assert_eq!(backup.block_number.0, 0);
+    assert_eq!(client.node_admin_backup_db(backup_dir)?.id, 2);
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_node_health() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let chain_info = client.chain_info()?;
+    let health = client.node_health()?;
+    assert_eq!(health.current_number, chain_info.head.number.0);
+    assert_eq!(health.peer_count, 0);
+    assert_eq!(health.txpool_depth, 0);
+    // the test node has no peers, but it is still healthy on the local net.
+    if health.sync_state.is_synced() {
+        assert!(health.is_ok(), "unexpected health: {:?}", health);
+    }
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_resource_at_historical_state() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    node_handle.generate_block()?;
+    node_handle.generate_block()?;
+    let block_metadata = StructTag {
+        address: genesis_address(),
+        module: Identifier::new("Block")?,
+        name: Identifier::new("BlockMetadata")?,
+        type_params: vec![],
+    };
+    let block_number_at = |state_root: Option<StateRootOption>| -> Result<u64> {
+        let resource = match state_root {
+            Some(state_root) => {
+                client.get_resource_at(genesis_address(), block_metadata.clone(), state_root)?
+            }
+            None => client.get_resource(genesis_address(), block_metadata.clone())?,
+        }
+        .expect("BlockMetadata should exist");
+        match resource
+            .value
+            .iter()
+            .find(|(name, _)| name.as_str() == "number")
+        {
+            Some((_, AnnotatedMoveValueView::U64(number))) => Ok(number.0),
+            _ => anyhow::bail!("unexpected BlockMetadata: {:?}", resource),
+        }
+    };
+
+    assert_eq!(block_number_at(None)?, 2);
+    assert_eq!(block_number_at(Some(StateRootOption::BlockNumber(1)))?, 1);
+    let block = client
+        .chain_get_block_by_number(1)?
+        .expect("block 1 should exist");
+    assert_eq!(
+        block_number_at(Some(StateRootOption::BlockHash(block.header.block_hash)))?,
+        1
+    );
+    assert_eq!(
+        block_number_at(Some(StateRootOption::StateRoot(block.header.state_root)))?,
+        1
+    );
+    assert!(block_number_at(Some(StateRootOption::BlockNumber(100))).is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_debug_trace_transaction() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    node_handle.generate_block()?;
+    let genesis = client
+        .chain_get_block_by_number(0)?
+        .expect("genesis block should exist");
+    let block = client
+        .chain_get_block_by_number(1)?
+        .expect("block 1 should exist");
+    let txn_infos = client.chain_get_block_txn_infos(block.header.block_hash)?;
+    // the block metadata txn of the block.
+    let txn_info = txn_infos.first().expect("block 1 should have txns");
+
+    let trace = client
+        .debug_trace_transaction(txn_info.transaction_hash)?
+        .expect("the txn should be traced");
+    assert_eq!(trace.block_hash, block.header.block_hash);
+    assert_eq!(trace.state_root, genesis.header.state_root);
+    assert_eq!(trace.output.gas_used, txn_info.gas_used);
+    assert_eq!(trace.frames.len(), 1);
+    assert!(!trace.reads.is_empty());
+    // every state is recorded once, at its first read.
+    let read_paths: HashSet<_> = trace
+        .reads
+        .iter()
+        .map(|read| (read.access_path.address, read.access_path.path.clone()))
+        .collect();
+    assert_eq!(read_paths.len(), trace.reads.len());
+
+    assert!(client
+        .debug_trace_transaction(HashValue::random())?
+        .is_none());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_sign_typed_message() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+    let chain_id = node_handle.config().net().chain_id().id();
+
+    let account = client.account_create("".to_string())?;
+    client.account_unlock(account.address, "".to_string(), Duration::from_secs(60))?;
+    let typed_message = |chain_id: u8, app_name: &str| -> Result<TypedMessage> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "domain": {"chain_id": chain_id, "app_name": app_name},
+            "types": {
+                "Login": [
+                    {"name": "account", "type": "address"},
+                    {"name": "nonce", "type": "u64"},
+                ],
+            },
+            "primary_type": "Login",
+            "message": {"account": account.address, "nonce": 1},
+        }))?)
+    };
+
+    let message = typed_message(chain_id, "app")?;
+    let signature = client.account_sign_typed_message(account.address, message.clone())?;
+    assert!(client.account_verify_typed_message(
+        message.clone(),
+        signature.clone(),
+        Some(account.address)
+    )?);
+    assert!(!client.account_verify_typed_message(
+        typed_message(chain_id, "other_app")?,
+        signature.clone(),
+        None
+    )?);
+    assert!(!client.account_verify_typed_message(message, signature, Some(genesis_address()))?);
+    assert!(client
+        .account_sign_typed_message(
+            account.address,
+            typed_message(chain_id.wrapping_add(1), "app")?
+        )
+        .is_err());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_module_abi() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let signer_module = ModuleId::new(genesis_address(), Identifier::new("Signer")?);
+    let abi = client
+        .contract_get_module_abi(signer_module.clone())?
+        .expect("Signer module should exist");
+    assert_eq!(abi.module_id, signer_module);
+    let address_of = abi
+        .functions
+        .iter()
+        .find(|function| function.name == "address_of")
+        .expect("address_of should be exposed");
+    assert_eq!(address_of.visibility, FunctionVisibility::Public);
+    assert_eq!(
+        address_of.params,
+        vec![TypeABI::Reference(Box::new(TypeABI::Signer))]
+    );
+    assert_eq!(address_of.returns, vec![TypeABI::Address]);
+
+    let not_exists = ModuleId::new(genesis_address(), Identifier::new("NotExists")?);
+    assert!(client.contract_get_module_abi(not_exists)?.is_none());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
+
+#[stest::test(timeout = 120)]
+fn test_get_code_disassemble() -> Result<()> {
+    let node_handle = test_helper::run_test_node()?;
+    let client = RpcClient::connect_local(node_handle.rpc_service()?)?;
+
+    let signer_module = ModuleId::new(genesis_address(), Identifier::new("Signer")?);
+    let code = client
+        .get_code(signer_module.clone())?
+        .expect("Signer module should exist");
+    assert!(!code.is_empty());
+
+    let disassembled = client
+        .get_code_disassembled(signer_module)?
+        .expect("Signer module should be disassembled");
+    assert!(disassembled.contains("Signer"));
+    assert!(disassembled.contains("address_of"));
+
+    let not_exists = ModuleId::new(genesis_address(), Identifier::new("NotExists")?);
+    assert!(client.get_code_disassembled(not_exists)?.is_none());
+
+    client.close();
+    if let Err(e) = node_handle.stop() {
+        error!("node stop error: {:?}", e)
+    }
+    Ok(())
+}
@@ -6,6 +6,7 @@ use jsonrpc_core::{Call, FutureResponse, Id, Metadata, Middleware, Output, Reque
 use starcoin_logger::prelude::*;
 use starcoin_metrics::HistogramTimer;
 use std::fmt;
+use std::time::Duration;
 
 mod metrics;
 
@@ -30,6 +31,11 @@ impl fmt::Display for CallType {
     }
 }
 
+/// The methods whose params may carry secrets, such as passwords and private keys.
+const REDACTED_METHOD_PREFIXES: &[&str] = &["account."];
+/// The requests longer than this are truncated in the slow request log.
+const MAX_LOGGED_REQUEST_LEN: usize = 256;
+
 struct RpcCallRecord {
     id: String,
     method: String,
@@ -51,8 +57,25 @@ impl RpcCallRecord {
         }
     }
 
-    pub fn end(self, code: i64) {
+    fn from_call(call: &Call) -> Self {
+        match call {
+            Call::MethodCall(method_call) => RpcCallRecord::new(
+                id_to_string(&method_call.id),
+                Some(method_call.method.clone()),
+                CallType::MethodCall,
+            ),
+            Call::Notification(notification) => RpcCallRecord::new(
+                "0".to_owned(),
+                Some(notification.method.clone()),
+                CallType::Notification,
+            ),
+            Call::Invalid { id } => RpcCallRecord::new(id_to_string(id), None, CallType::Invalid),
+        }
+    }
+
+    pub fn end(self, output: Option<&Output>, slow_call_threshold: Option<Duration>) {
         let use_time = self.timer.stop_and_record();
+        let code = output_to_code(output);
 
         info!(
             "rpc_call\t{}\t{}\t{}\t{}\t{}",
@@ -66,6 +89,75 @@ impl RpcCallRecord {
                 &code.to_string(),
             ])
             .inc();
+        if let Some(threshold) = slow_call_threshold {
+            if use_time >= threshold.as_secs_f64() {
+                RPC_SLOW_CALLS
+                    .with_label_values(&[self.method.as_str()])
+                    .inc();
+                warn!(
+                    "slow_rpc_call\t{}\t{}\t{}\t{}",
+                    self.id, self.method, code, use_time
+                );
+            }
+        }
+    }
+}
+
+/// Redact the raw request for the log, the request which calls the methods that may carry secrets
+/// is hidden, and the long request, such as the one with a hex encoded transaction, is truncated.
+pub fn redact_request(request: &str) -> String {
+    if REDACTED_METHOD_PREFIXES
+        .iter()
+        .any(|prefix| request.contains(&format!("\"{}", prefix)))
+    {
+        return "<redacted>".to_owned();
+    }
+    if request.len() <= MAX_LOGGED_REQUEST_LEN {
+        return request.to_owned();
+    }
+    let mut end = MAX_LOGGED_REQUEST_LEN;
+    while !request.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...({} bytes)", &request[..end], request.len())
+}
+
+/// Record the payload sizes of the requests served by a transport, measured from the raw request
+/// and response strings, and log the slow requests with their redacted body.
+#[derive(Clone, Copy, Debug)]
+pub struct TransportRecorder {
+    transport: &'static str,
+    /// Log the requests slower than the threshold, disabled if None.
+    slow_call_threshold: Option<Duration>,
+}
+
+impl TransportRecorder {
+    pub fn new(transport: &'static str, slow_call_threshold: Option<Duration>) -> Self {
+        Self {
+            transport,
+            slow_call_threshold,
+        }
+    }
+
+    pub fn record(&self, request: &str, response: Option<&str>, elapsed: Duration) {
+        RPC_REQUEST_SIZE
+            .with_label_values(&[self.transport])
+            .observe(request.len() as f64);
+        if let Some(response) = response {
+            RPC_RESPONSE_SIZE
+                .with_label_values(&[self.transport])
+                .observe(response.len() as f64);
+        }
+        if let Some(threshold) = self.slow_call_threshold {
+            if elapsed >= threshold {
+                warn!(
+                    "slow_rpc_request\t{}\t{}\t{}",
+                    self.transport,
+                    elapsed.as_secs_f64(),
+                    redact_request(request)
+                );
+            }
+        }
     }
 }
 
@@ -77,27 +169,22 @@ fn id_to_string(id: &Id) -> String {
     }
 }
 
-impl From<&Call> for RpcCallRecord {
-    fn from(call: &Call) -> Self {
-        match call {
-            Call::MethodCall(method_call) => RpcCallRecord::new(
-                id_to_string(&method_call.id),
-                Some(method_call.method.clone()),
-                CallType::MethodCall,
-            ),
-            Call::Notification(notification) => RpcCallRecord::new(
-                "0".to_owned(),
-                Some(notification.method.clone()),
-                CallType::Notification,
-            ),
-            Call::Invalid { id } => RpcCallRecord::new(id_to_string(id), None, CallType::Invalid),
+/// Record the count and latency of the calls by method, and log the slow calls. The payload sizes
+/// are recorded by the transports from the raw strings, see `TransportRecorder`.
+#[derive(Clone, Copy, Default)]
+pub struct MetricMiddleware {
+    /// Log the calls slower than the threshold, disabled if None.
+    slow_call_threshold: Option<Duration>,
+}
+
+impl MetricMiddleware {
+    pub fn new(slow_call_threshold: Option<Duration>) -> Self {
+        Self {
+            slow_call_threshold,
         }
     }
 }
 
-#[derive(Clone)]
-pub struct MetricMiddleware;
-
 impl<M: Metadata> Middleware<M> for MetricMiddleware {
     type Future = FutureResponse;
     type CallFuture = NoopCallFuture;
@@ -115,9 +202,10 @@ impl<M: Metadata> Middleware<M> for MetricMiddleware {
         F: Fn(Call, M) -> X + Send + Sync,
         X: Future<Output = Option<Output>> + Send + 'static,
     {
-        let record: RpcCallRecord = (&call).into();
+        let slow_call_threshold = self.slow_call_threshold;
+        let record = RpcCallRecord::from_call(&call);
         let fut = next(call, meta).map(move |output| {
-            record.end(output_to_code(output.as_ref()));
+            record.end(output.as_ref(), slow_call_threshold);
             output
         });
         // must declare type to convert type then wrap with Either.
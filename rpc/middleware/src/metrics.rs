@@ -18,3 +18,37 @@ pub static RPC_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
 pub static RPC_HISTOGRAMS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!("starcoin_rpc_time", "Histogram of rpc request", &["method"]).unwrap()
 });
+
+/// The payload size buckets, from 256 bytes to 4 MB.
+const PAYLOAD_SIZE_BUCKETS: &[f64] = &[
+    256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0,
+];
+
+pub static RPC_REQUEST_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "starcoin_rpc_request_size",
+        "Histogram of rpc request payload bytes",
+        &["transport"],
+        PAYLOAD_SIZE_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+pub static RPC_RESPONSE_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "starcoin_rpc_response_size",
+        "Histogram of rpc response payload bytes",
+        &["transport"],
+        PAYLOAD_SIZE_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+pub static RPC_SLOW_CALLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "starcoin_rpc_slow_calls",
+        "Counters of the rpc calls slower than the slow call threshold",
+        &["method"]
+    )
+    .unwrap()
+});
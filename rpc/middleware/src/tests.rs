@@ -7,7 +7,8 @@ use std::time::Duration;
 
 #[stest::test]
 fn test_middleware() {
-    let mut io_handler = MetaIoHandler::with_middleware(MetricMiddleware);
+    let mut io_handler =
+        MetaIoHandler::with_middleware(MetricMiddleware::new(Some(Duration::from_millis(10))));
     io_handler.add_method("status", |_params: Params| async {
         let mut rng = rand::thread_rng();
         let sleep_time = rng.gen_range(1..50);
@@ -29,3 +30,35 @@ fn test_middleware() {
     }
     info!("metrics: {:?}", get_all_metrics());
 }
+
+#[stest::test]
+fn test_redact_request() {
+    let long_hex = format!("0x{}", "ab".repeat(200));
+    let request = format!(
+        r#"{{"jsonrpc":"2.0","method":"txpool.submit_hex_transaction","params":["{}"],"id":1}}"#,
+        long_hex
+    );
+    let redacted = redact_request(request.as_str());
+    assert!(redacted.ends_with(&format!("...({} bytes)", request.len())));
+    assert!(!redacted.contains(long_hex.as_str()));
+    assert!(redacted.contains("txpool.submit_hex_transaction"));
+
+    let request =
+        r#"{"jsonrpc":"2.0","method":"account.unlock","params":["0x1","password"],"id":1}"#;
+    assert_eq!(redact_request(request), "<redacted>");
+    let request = r#"{"jsonrpc":"2.0","method":"chain.info","params":[],"id":1}"#;
+    assert_eq!(redact_request(request), request);
+}
+
+#[stest::test]
+fn test_transport_recorder() {
+    let recorder = TransportRecorder::new("test", Some(Duration::from_millis(10)));
+    let request = r#"{"jsonrpc":"2.0","method":"chain.info","params":[],"id":1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+    recorder.record(request, Some(response), Duration::from_millis(20));
+    let request_size = RPC_REQUEST_SIZE.with_label_values(&["test"]);
+    assert_eq!(request_size.get_sample_count(), 1);
+    assert_eq!(request_size.get_sample_sum(), request.len() as f64);
+    let response_size = RPC_RESPONSE_SIZE.with_label_values(&["test"]);
+    assert_eq!(response_size.get_sample_sum(), response.len() as f64);
+}
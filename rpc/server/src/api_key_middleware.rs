@@ -0,0 +1,710 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Authenticate the calls by api key, so a public node can give its known clients their own quota
+//! and method allowlist. The calls without api key are limited by the anonymous method allowlist,
+//! and by the per ip quotas of the rate limit middleware as before.
+//!
+//! The http clients send the api key in the api key header, the ws clients in the header or the
+//! `api_key` query parameter of the handshake, and the ws and tcp clients can also authenticate
+//! their connection by the `rpc.auth` call with the api key. The invalid api keys sent by an ip are
+//! limited by the per ip quota, so the api keys can not be guessed.
+
+use crate::rate_limit_middleware::QuotaWrapper;
+use governor::clock::DefaultClock;
+use governor::state::{InMemoryState, NotKeyed};
+use governor::RateLimiter;
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{Call, Error, Failure, FutureResponse, Id, Middleware, Output, Params, Value};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use starcoin_config::{ApiKeyConfiguration, ApiQuotaConfig, ApiQuotaConfiguration, QuotaDuration};
+use starcoin_crypto::HashValue;
+use starcoin_metrics::{register_int_counter_vec, IntCounterVec};
+use starcoin_rpc_api::errors::RpcErrorCode;
+use starcoin_rpc_api::metadata::Metadata;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const ANONYMOUS: &str = "anonymous";
+
+/// The method to authenticate the calls of a persistent connection by api key, its params are the
+/// api key, such as `{"method": "rpc.auth", "params": ["key"]}`.
+pub(crate) const AUTH_METHOD: &str = "rpc.auth";
+
+static API_KEY_CALLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "starcoin_rpc_api_key_calls",
+        "Counters of the rpc calls by api key name and check result",
+        &["key", "result"]
+    )
+    .unwrap()
+});
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+struct ApiKey {
+    name: String,
+    /// The hash of the secret key, the secret key is not kept.
+    secret_hash: HashValue,
+    methods: Option<Vec<String>>,
+    limiter: DirectRateLimiter,
+}
+
+struct ApiKeys {
+    require_api_key: bool,
+    anonymous_methods: Option<Vec<String>>,
+    /// The api keys by the hash of the secret key, the secret keys are replaced by `rotate_keys`.
+    keys: RwLock<HashMap<HashValue, ApiKey>>,
+    invalid_keys: InvalidKeyLimiter,
+}
+
+/// Count the invalid api keys sent by every ip in the window of the per ip quota, an ip which
+/// sends more invalid api keys than the quota can not use any api key until the window ends.
+/// The clients without ip share one count.
+struct InvalidKeyLimiter {
+    max_invalid_keys: u32,
+    window: Duration,
+    invalid_keys: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl InvalidKeyLimiter {
+    fn new(quota: ApiQuotaConfig) -> Self {
+        let window = match quota.duration {
+            QuotaDuration::Second => Duration::from_secs(1),
+            QuotaDuration::Minute => Duration::from_secs(60),
+            QuotaDuration::Hour => Duration::from_secs(60 * 60),
+        };
+        Self {
+            max_invalid_keys: quota.max_burst.get(),
+            window,
+            invalid_keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_limited(&self, ip: &str) -> bool {
+        self.invalid_keys
+            .lock()
+            .get(ip)
+            .map_or(false, |(start, count)| {
+                start.elapsed() < self.window && *count >= self.max_invalid_keys
+            })
+    }
+
+    fn add_invalid_key(&self, ip: &str) {
+        let window = self.window;
+        let mut invalid_keys = self.invalid_keys.lock();
+        invalid_keys.retain(|_, (start, _)| start.elapsed() < window);
+        invalid_keys
+            .entry(ip.to_string())
+            .or_insert_with(|| (Instant::now(), 0))
+            .1 += 1;
+    }
+}
+
+fn secret_hash(api_key: &str) -> HashValue {
+    HashValue::sha3_256_of(api_key.as_bytes())
+}
+
+/// Compare in constant time, so the time of the check does not tell how many bytes are matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The method matches the pattern if they are equal, or the pattern ends with `*` and the method
+/// starts with the prefix, such as `chain.*`.
+pub(crate) fn method_allowed(patterns: Option<&Vec<String>>, method: &str) -> bool {
+    match patterns {
+        None => true,
+        Some(patterns) => patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => method.starts_with(prefix),
+                None => pattern == method,
+            }),
+    }
+}
+
+impl ApiKeys {
+    fn check(&self, method: &str, api_key: Option<&String>, ip: &str) -> Result<(), Error> {
+        let keys = self.keys.read();
+        let (name, result) = match api_key {
+            Some(_) if self.invalid_keys.is_limited(ip) => (
+                ANONYMOUS,
+                Err((
+                    "rate_limited",
+                    RpcErrorCode::RateLimitExceeded,
+                    "Too many invalid api keys, retry later".to_string(),
+                )),
+            ),
+            Some(api_key) => match Self::find(&keys, api_key) {
+                Some(key) => (key.name.as_str(), self.check_key(key, method)),
+                None => {
+                    self.invalid_keys.add_invalid_key(ip);
+                    (
+                        ANONYMOUS,
+                        Err((
+                            "invalid_key",
+                            RpcErrorCode::InvalidApiKey,
+                            "Invalid api key".to_string(),
+                        )),
+                    )
+                }
+            },
+            None if self.require_api_key => (
+                ANONYMOUS,
+                Err((
+                    "missing_key",
+                    RpcErrorCode::InvalidApiKey,
+                    "Api key is required".to_string(),
+                )),
+            ),
+            None if !method_allowed(self.anonymous_methods.as_ref(), method) => (
+                ANONYMOUS,
+                Err((
+                    "method_not_allowed",
+                    RpcErrorCode::MethodNotAllowed,
+                    format!("Method {} is not allowed without api key", method),
+                )),
+            ),
+            None => (ANONYMOUS, Ok(())),
+        };
+        match result {
+            Ok(()) => {
+                API_KEY_CALLS.with_label_values(&[name, "allowed"]).inc();
+                Ok(())
+            }
+            Err((label, code, message)) => {
+                API_KEY_CALLS.with_label_values(&[name, label]).inc();
+                Err(code.to_error(message, None))
+            }
+        }
+    }
+
+    /// Authenticate the connection of the `rpc.auth` call, the api key is used by the later calls
+    /// of the connection.
+    fn auth(&self, params: Params, meta: &Metadata) -> Result<Value, Error> {
+        let connection_api_key = meta.connection_api_key.as_ref().ok_or_else(|| {
+            Error::invalid_params(format!(
+                "{} is only for the ws and tcp connections, send the api key by header",
+                AUTH_METHOD
+            ))
+        })?;
+        let (api_key,): (String,) = params.parse()?;
+        let ip = meta.user.as_deref().unwrap_or_default();
+        if self.invalid_keys.is_limited(ip) {
+            API_KEY_CALLS
+                .with_label_values(&[ANONYMOUS, "rate_limited"])
+                .inc();
+            return Err(RpcErrorCode::RateLimitExceeded
+                .to_error("Too many invalid api keys, retry later".to_string(), None));
+        }
+        if Self::find(&self.keys.read(), &api_key).is_none() {
+            self.invalid_keys.add_invalid_key(ip);
+            API_KEY_CALLS
+                .with_label_values(&[ANONYMOUS, "invalid_key"])
+                .inc();
+            return Err(
+                RpcErrorCode::InvalidApiKey.to_error("Invalid api key".to_string(), None),
+            );
+        }
+        *connection_api_key.write() = Some(api_key);
+        Ok(Value::Bool(true))
+    }
+
+    /// Find the api key by the hash of the secret key.
+    fn find<'a>(keys: &'a HashMap<HashValue, ApiKey>, api_key: &str) -> Option<&'a ApiKey> {
+        let secret_hash = secret_hash(api_key);
+        keys.get(&secret_hash)
+            .filter(|key| constant_time_eq(&key.secret_hash.to_vec(), &secret_hash.to_vec()))
+    }
+
+    fn check_key(
+        &self,
+        key: &ApiKey,
+        method: &str,
+    ) -> Result<(), (&'static str, RpcErrorCode, String)> {
+        if !method_allowed(key.methods.as_ref(), method) {
+            return Err((
+                "method_not_allowed",
+                RpcErrorCode::MethodNotAllowed,
+                format!("Method {} is not allowed for api key {}", method, key.name),
+            ));
+        }
+        key.limiter.check().map_err(|e| {
+            (
+                "rate_limited",
+                RpcErrorCode::RateLimitExceeded,
+                format!("Quota of api key {} exceeded, {}", key.name, e),
+            )
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiKeyMiddleware {
+    api_keys: Option<Arc<ApiKeys>>,
+}
+
+impl ApiKeyMiddleware {
+    /// A middleware without any check, for the trusted transports, such as ipc.
+    pub fn disabled() -> Self {
+        Self { api_keys: None }
+    }
+
+    /// A middleware only checks the methods by the `methods` allowlist, for the trusted transports
+    /// with their own allowlist, such as ipc. All methods are allowed if the allowlist is None.
+    pub fn allowlist(methods: Option<Vec<String>>) -> Self {
+        match methods {
+            Some(methods) => Self {
+                api_keys: Some(Arc::new(ApiKeys {
+                    require_api_key: false,
+                    anonymous_methods: Some(methods),
+                    keys: RwLock::new(HashMap::new()),
+                    invalid_keys: InvalidKeyLimiter::new(
+                        ApiQuotaConfiguration::default().default_user_api_quota(),
+                    ),
+                })),
+            },
+            None => Self::disabled(),
+        }
+    }
+
+    /// The invalid api keys of an ip are limited by the per ip quota of `quotas`.
+    pub fn from_config(config: &ApiKeyConfiguration, quotas: &ApiQuotaConfiguration) -> Self {
+        if !config.is_enabled() {
+            return Self::disabled();
+        }
+        let default_quota = config.default_api_key_quota();
+        let keys = config
+            .keys()
+            .into_iter()
+            .map(|(name, key_config)| {
+                let quota = key_config.quota.unwrap_or_else(|| default_quota.clone());
+                let secret_hash = secret_hash(key_config.key.as_str());
+                (
+                    secret_hash,
+                    ApiKey {
+                        name,
+                        secret_hash,
+                        methods: key_config.methods,
+                        limiter: DirectRateLimiter::direct(QuotaWrapper::from(quota).0),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            api_keys: Some(Arc::new(ApiKeys {
+                require_api_key: config.require_api_key,
+                anonymous_methods: config.anonymous_methods.clone(),
+                keys: RwLock::new(keys),
+                invalid_keys: InvalidKeyLimiter::new(quotas.default_user_api_quota()),
+            })),
+        }
+    }
+
+    /// Replace the secret of every api key with a new random secret, the name, quota and method
+    /// allowlist of the keys are kept. Return the new secrets by the key name.
+    /// The new secrets are only kept in memory, the config file is not changed.
+    pub fn rotate_keys(&self) -> HashMap<String, String> {
+        let api_keys = match self.api_keys.as_ref() {
+            Some(api_keys) => api_keys,
+            None => return HashMap::new(),
+        };
+        let mut keys = api_keys.keys.write();
+        let mut secrets = HashMap::new();
+        *keys = std::mem::take(&mut *keys)
+            .into_iter()
+            .map(|(_, mut key)| {
+                let secret = HashValue::random().to_hex();
+                secrets.insert(key.name.clone(), secret.clone());
+                key.secret_hash = secret_hash(secret.as_str());
+                (key.secret_hash, key)
+            })
+            .collect();
+        secrets
+    }
+
+    /// Whether the calls to the method are refused without a valid api key.
+    pub fn is_protected(&self, method: &str) -> bool {
+        match self.api_keys.as_ref() {
+            Some(api_keys) => {
+                api_keys.require_api_key
+                    || !method_allowed(api_keys.anonymous_methods.as_ref(), method)
+            }
+            None => false,
+        }
+    }
+}
+
+impl Middleware<Metadata> for ApiKeyMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: Metadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let api_keys = match self.api_keys.as_ref() {
+            Some(api_keys) => api_keys,
+            None => return Either::Right(next(call, meta)),
+        };
+        let method = match &call {
+            Call::MethodCall(m) => Some((m.method.as_str(), m.jsonrpc, m.id.clone())),
+            Call::Notification(n) => Some((n.method.as_str(), n.jsonrpc, Id::Null)),
+            Call::Invalid { .. } => None,
+        };
+        if let Some((method, json_version, id)) = method {
+            if method == AUTH_METHOD {
+                let params = match &call {
+                    Call::MethodCall(m) => m.params.clone(),
+                    Call::Notification(n) => n.params.clone(),
+                    Call::Invalid { .. } => Params::None,
+                };
+                let output = Output::from(api_keys.auth(params, &meta), id, json_version);
+                return Either::Left(Box::pin(futures::future::ready(Some(output))));
+            }
+            let connection_api_key = meta
+                .connection_api_key
+                .as_ref()
+                .and_then(|api_key| api_key.read().clone());
+            if let Err(error) = api_keys.check(
+                method,
+                meta.api_key
+                    .as_ref()
+                    .or_else(|| connection_api_key.as_ref()),
+                meta.user.as_deref().unwrap_or_default(),
+            ) {
+                let output = Output::Failure(Failure {
+                    jsonrpc: json_version,
+                    error,
+                    id,
+                });
+                return Either::Left(Box::pin(futures::future::ready(Some(output))));
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{ErrorCode, MetaIoHandler};
+    use serde_json::json;
+    use starcoin_config::ApiKeyConfig;
+
+    fn handle(
+        io: &MetaIoHandler<Metadata, ApiKeyMiddleware>,
+        method: &str,
+        api_key: Option<&str>,
+    ) -> Value {
+        let meta = Metadata {
+            api_key: api_key.map(|key| key.to_string()),
+            ..Default::default()
+        };
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1});
+        let response =
+            futures::executor::block_on(io.handle_request(request.to_string().as_str(), meta))
+                .unwrap();
+        serde_json::from_str(response.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_api_key() {
+        let mut keys = HashMap::new();
+        keys.insert(
+            "explorer".to_string(),
+            ApiKeyConfig {
+                key: "explorer_key".to_string(),
+                quota: Some("2/s".parse().unwrap()),
+                methods: Some(vec!["chain.*".to_string()]),
+            },
+        );
+        keys.insert(
+            "admin".to_string(),
+            ApiKeyConfig {
+                key: "admin_key".to_string(),
+                quota: None,
+                methods: None,
+            },
+        );
+        let config = ApiKeyConfiguration {
+            anonymous_methods: Some(vec!["node.info".to_string()]),
+            keys: Some(keys),
+            ..Default::default()
+        };
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::from_config(
+            &config,
+            &ApiQuotaConfiguration::default(),
+        ));
+        for method in &["node.info", "chain.info", "txpool.state"] {
+            io.add_sync_method(method, |_params: Params| Ok(json!(true)));
+        }
+
+        assert_eq!(handle(&io, "node.info", None)["result"], json!(true));
+        assert_eq!(
+            handle(&io, "chain.info", None)["error"]["code"],
+            json!(i64::from(RpcErrorCode::MethodNotAllowed))
+        );
+        assert_eq!(
+            handle(&io, "node.info", Some("unknown_key"))["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+
+        assert_eq!(
+            handle(&io, "txpool.state", Some("explorer_key"))["error"]["code"],
+            json!(i64::from(RpcErrorCode::MethodNotAllowed))
+        );
+        assert_eq!(
+            handle(&io, "chain.info", Some("explorer_key"))["result"],
+            json!(true)
+        );
+        assert_eq!(
+            handle(&io, "chain.info", Some("explorer_key"))["result"],
+            json!(true)
+        );
+        assert_eq!(
+            handle(&io, "chain.info", Some("explorer_key"))["error"]["code"],
+            json!(i64::from(RpcErrorCode::RateLimitExceeded))
+        );
+        // the quota of a key is not shared with other keys.
+        assert_eq!(
+            handle(&io, "txpool.state", Some("admin_key"))["result"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_rotate_keys() {
+        let mut keys = HashMap::new();
+        keys.insert(
+            "admin".to_string(),
+            ApiKeyConfig {
+                key: "admin_key".to_string(),
+                quota: None,
+                methods: None,
+            },
+        );
+        let config = ApiKeyConfiguration {
+            require_api_key: true,
+            keys: Some(keys),
+            ..Default::default()
+        };
+        let middleware = ApiKeyMiddleware::from_config(&config, &ApiQuotaConfiguration::default());
+        let mut io = MetaIoHandler::with_middleware(middleware.clone());
+        io.add_sync_method("node.info", |_params: Params| Ok(json!(true)));
+        assert_eq!(
+            handle(&io, "node.info", Some("admin_key"))["result"],
+            json!(true)
+        );
+
+        let secrets = middleware.rotate_keys();
+        assert_eq!(secrets.len(), 1);
+        let new_key = secrets.get("admin").unwrap();
+        assert_ne!(new_key, "admin_key");
+        assert_eq!(
+            handle(&io, "node.info", Some("admin_key"))["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+        assert_eq!(
+            handle(&io, "node.info", Some(new_key.as_str()))["result"],
+            json!(true)
+        );
+
+        assert!(ApiKeyMiddleware::disabled().rotate_keys().is_empty());
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::allowlist(Some(vec![
+            "node_admin.*".to_string(),
+        ])));
+        for method in &["node_admin.ban_peer", "chain.info"] {
+            io.add_sync_method(method, |_params: Params| Ok(json!(true)));
+        }
+        assert_eq!(
+            handle(&io, "node_admin.ban_peer", None)["result"],
+            json!(true)
+        );
+        assert_eq!(
+            handle(&io, "chain.info", None)["error"]["code"],
+            json!(i64::from(RpcErrorCode::MethodNotAllowed))
+        );
+
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::allowlist(None));
+        io.add_sync_method("chain.info", |_params: Params| Ok(json!(true)));
+        assert_eq!(handle(&io, "chain.info", None)["result"], json!(true));
+    }
+
+    #[test]
+    fn test_require_api_key() {
+        let config = ApiKeyConfiguration {
+            require_api_key: true,
+            ..Default::default()
+        };
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::from_config(
+            &config,
+            &ApiQuotaConfiguration::default(),
+        ));
+        io.add_sync_method("node.info", |_params: Params| Ok(json!(true)));
+        assert_eq!(
+            handle(&io, "node.info", None)["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::disabled());
+        io.add_sync_method("node.info", |_params: Params| Ok(json!(true)));
+        assert_eq!(handle(&io, "node.info", None)["result"], json!(true));
+    }
+
+    #[test]
+    fn test_auth_connection() {
+        let mut keys = HashMap::new();
+        keys.insert(
+            "admin".to_string(),
+            ApiKeyConfig {
+                key: "admin_key".to_string(),
+                quota: None,
+                methods: None,
+            },
+        );
+        let config = ApiKeyConfiguration {
+            require_api_key: true,
+            keys: Some(keys),
+            ..Default::default()
+        };
+        let mut io = MetaIoHandler::with_middleware(ApiKeyMiddleware::from_config(
+            &config,
+            &ApiQuotaConfiguration::default(),
+        ));
+        io.add_sync_method("node.info", |_params: Params| Ok(json!(true)));
+        let call = |meta: Metadata, method: &str, params: Value| -> Value {
+            let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+            let response =
+                futures::executor::block_on(io.handle_request(request.to_string().as_str(), meta))
+                    .unwrap();
+            serde_json::from_str(response.as_str()).unwrap()
+        };
+        let meta = Metadata {
+            connection_api_key: Some(Arc::new(RwLock::new(None))),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            call(meta.clone(), "node.info", json!([]))["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+        assert_eq!(
+            call(meta.clone(), AUTH_METHOD, json!(["unknown_key"]))["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+        assert_eq!(
+            call(meta.clone(), AUTH_METHOD, json!(["admin_key"]))["result"],
+            json!(true)
+        );
+        assert_eq!(
+            call(meta.clone(), "node.info", json!([]))["result"],
+            json!(true)
+        );
+        // the auth only works for the calls of the same connection.
+        assert_eq!(
+            call(Metadata::default(), "node.info", json!([]))["error"]["code"],
+            json!(i64::from(RpcErrorCode::InvalidApiKey))
+        );
+        // the http calls send the api key by header.
+        assert_eq!(
+            call(Metadata::default(), AUTH_METHOD, json!(["admin_key"]))["error"]["code"],
+            json!(ErrorCode::InvalidParams.code())
+        );
+    }
+
+    #[test]
+    fn test_invalid_key_limit() {
+        let mut keys = HashMap::new();
+        keys.insert(
+            "admin".to_string(),
+            ApiKeyConfig {
+                key: "admin_key".to_string(),
+                quota: None,
+                methods: None,
+            },
+        );
+        let config = ApiKeyConfiguration {
+            require_api_key: true,
+            keys: Some(keys),
+            ..Default::default()
+        };
+        let quotas = ApiQuotaConfiguration {
+            default_user_api_quota: Some("2/m".parse().unwrap()),
+            ..Default::default()
+        };
+        let mut io =
+            MetaIoHandler::with_middleware(ApiKeyMiddleware::from_config(&config, &quotas));
+        io.add_sync_method("node.info", |_params: Params| Ok(json!(true)));
+        let call = |meta: Metadata, method: &str, params: Value| -> Value {
+            let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+            let response =
+                futures::executor::block_on(io.handle_request(request.to_string().as_str(), meta))
+                    .unwrap();
+            serde_json::from_str(response.as_str()).unwrap()
+        };
+        let connection = |ip: &str| Metadata {
+            user: Some(ip.to_string()),
+            connection_api_key: Some(Arc::new(RwLock::new(None))),
+            ..Default::default()
+        };
+
+        for _ in 0..2 {
+            assert_eq!(
+                call(connection("1.1.1.1"), AUTH_METHOD, json!(["unknown_key"]))["error"]["code"],
+                json!(i64::from(RpcErrorCode::InvalidApiKey))
+            );
+        }
+        // the ip can not use any api key after too many invalid keys.
+        assert_eq!(
+            call(connection("1.1.1.1"), AUTH_METHOD, json!(["admin_key"]))["error"]["code"],
+            json!(i64::from(RpcErrorCode::RateLimitExceeded))
+        );
+        let meta = Metadata {
+            api_key: Some("admin_key".to_string()),
+            user: Some("1.1.1.1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            call(meta, "node.info", json!([]))["error"]["code"],
+            json!(i64::from(RpcErrorCode::RateLimitExceeded))
+        );
+        // other ips are not limited.
+        assert_eq!(
+            call(connection("2.2.2.2"), AUTH_METHOD, json!(["admin_key"]))["result"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_is_protected() {
+        assert!(!ApiKeyMiddleware::disabled().is_protected("node_admin.ban_peer"));
+
+        let config = ApiKeyConfiguration {
+            anonymous_methods: Some(vec!["node.*".to_string()]),
+            ..Default::default()
+        };
+        let middleware = ApiKeyMiddleware::from_config(&config, &ApiQuotaConfiguration::default());
+        assert!(!middleware.is_protected("node.info"));
+        assert!(middleware.is_protected("node_admin.ban_peer"));
+
+        let config = ApiKeyConfiguration {
+            require_api_key: true,
+            ..Default::default()
+        };
+        assert!(
+            ApiKeyMiddleware::from_config(&config, &ApiQuotaConfiguration::default())
+                .is_protected("node.info")
+        );
+    }
+}
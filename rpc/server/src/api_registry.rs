@@ -1,25 +1,62 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::api_key_middleware::ApiKeyMiddleware;
+use crate::archive_middleware::ArchiveForwardMiddleware;
+use crate::batch_middleware::BatchLimitMiddleware;
+use crate::cors_middleware::CorsMiddleware;
+use crate::heavy_api_middleware::HeavyApiMiddleware;
 use crate::rate_limit_middleware::JsonApiRateLimitMiddleware;
 use jsonrpc_core::{MetaIoHandler, RemoteProcedure};
 use starcoin_config::{Api, ApiQuotaConfiguration};
+use starcoin_logger::prelude::*;
 use starcoin_rpc_api::metadata::Metadata;
 use starcoin_rpc_middleware::MetricMiddleware;
 use std::collections::HashMap;
 
-type Middlewares = (MetricMiddleware, JsonApiRateLimitMiddleware);
+/// The middlewares in calling order, grouped because the jsonrpc `Middleware` is only implemented
+/// for the tuples of at most four middlewares. The per ip quotas are checked before the api key,
+/// so the calls with guessed api keys are limited too.
+type Middlewares = (
+    (
+        BatchLimitMiddleware,
+        MetricMiddleware,
+        JsonApiRateLimitMiddleware,
+        ApiKeyMiddleware,
+    ),
+    (CorsMiddleware, ArchiveForwardMiddleware, HeavyApiMiddleware),
+);
 
 pub struct ApiRegistry {
     apis: HashMap<Api, MetaIoHandler<Metadata, Middlewares>>,
     quotas: ApiQuotaConfiguration,
+    batch_middleware: BatchLimitMiddleware,
+    metric_middleware: MetricMiddleware,
+    api_key_middleware: ApiKeyMiddleware,
+    cors_middleware: CorsMiddleware,
+    archive_middleware: ArchiveForwardMiddleware,
+    heavy_api_middleware: HeavyApiMiddleware,
 }
 
 impl ApiRegistry {
-    pub fn new(api_quotas: ApiQuotaConfiguration) -> ApiRegistry {
+    pub fn new(
+        api_quotas: ApiQuotaConfiguration,
+        batch_middleware: BatchLimitMiddleware,
+        metric_middleware: MetricMiddleware,
+        api_key_middleware: ApiKeyMiddleware,
+        cors_middleware: CorsMiddleware,
+        archive_middleware: ArchiveForwardMiddleware,
+        heavy_api_middleware: HeavyApiMiddleware,
+    ) -> ApiRegistry {
         Self {
             apis: Default::default(),
             quotas: api_quotas,
+            batch_middleware,
+            metric_middleware,
+            api_key_middleware,
+            cors_middleware,
+            archive_middleware,
+            heavy_api_middleware,
         }
     }
 
@@ -28,18 +65,81 @@ impl ApiRegistry {
         F: IntoIterator<Item = (String, RemoteProcedure<Metadata>)>,
     {
         let rate_limit_middleware = JsonApiRateLimitMiddleware::from_config(self.quotas.clone());
+        let batch_middleware = self.batch_middleware;
+        let metric_middleware = self.metric_middleware;
+        let api_key_middleware = self.api_key_middleware.clone();
+        let cors_middleware = self.cors_middleware.clone();
+        let archive_middleware = self.archive_middleware.clone();
+        let heavy_api_middleware = self.heavy_api_middleware.clone();
         let io_handler = self.apis.entry(api_type).or_insert_with(|| {
             MetaIoHandler::<Metadata, Middlewares>::with_middleware((
-                MetricMiddleware,
-                rate_limit_middleware,
+                (
+                    batch_middleware,
+                    metric_middleware,
+                    rate_limit_middleware,
+                    api_key_middleware,
+                ),
+                (cors_middleware, archive_middleware, heavy_api_middleware),
             ))
         });
         io_handler.extend_with(apis);
     }
 
+    /// Get the apis for the untrusted transports. The node admin apis are only served if all
+    /// of their calls are required to be authenticated by api key.
+    /// The `batch_middleware` carries the payload limits of the transport.
     pub fn get_apis(
         &self,
         api_types: impl IntoIterator<Item = Api>,
+        batch_middleware: BatchLimitMiddleware,
+    ) -> MetaIoHandler<Metadata, Middlewares> {
+        let api_types: Vec<Api> = api_types
+            .into_iter()
+            .filter(|api_type| {
+                if *api_type != Api::NodeAdmin {
+                    return true;
+                }
+                let protected = self.apis.get(api_type).map_or(true, |apis| {
+                    apis.iter()
+                        .all(|(method, _)| self.api_key_middleware.is_protected(method))
+                });
+                if !protected {
+                    warn!(
+                        "The {} apis are not protected by api key, only serve them by ipc.",
+                        api_type
+                    );
+                }
+                protected
+            })
+            .collect();
+        self.build_apis(api_types, batch_middleware, self.api_key_middleware.clone())
+    }
+
+    /// Get the apis without the api key check, for the trusted transports, such as ipc. The calls
+    /// are only checked by the `methods` allowlist of the transport, if it is present.
+    pub fn get_trusted_apis(
+        &self,
+        api_types: impl IntoIterator<Item = Api>,
+        batch_middleware: BatchLimitMiddleware,
+        methods: Option<Vec<String>>,
+    ) -> MetaIoHandler<Metadata, Middlewares> {
+        self.build_apis(
+            api_types,
+            batch_middleware,
+            ApiKeyMiddleware::allowlist(methods),
+        )
+    }
+
+    /// The default payload limits, for the transports without their own limits.
+    pub fn batch_middleware(&self) -> BatchLimitMiddleware {
+        self.batch_middleware
+    }
+
+    fn build_apis(
+        &self,
+        api_types: impl IntoIterator<Item = Api>,
+        batch_middleware: BatchLimitMiddleware,
+        api_key_middleware: ApiKeyMiddleware,
     ) -> MetaIoHandler<Metadata, Middlewares> {
         let rate_limit_middleware = JsonApiRateLimitMiddleware::from_config(self.quotas.clone());
         api_types
@@ -47,8 +147,17 @@ impl ApiRegistry {
             .map(|api_type| self.apis.get(&api_type))
             .fold(
                 MetaIoHandler::<Metadata, Middlewares>::with_middleware((
-                    MetricMiddleware,
-                    rate_limit_middleware,
+                    (
+                        batch_middleware,
+                        self.metric_middleware,
+                        rate_limit_middleware,
+                        api_key_middleware,
+                    ),
+                    (
+                        self.cors_middleware.clone(),
+                        self.archive_middleware.clone(),
+                        self.heavy_api_middleware.clone(),
+                    ),
                 )),
                 |mut init, apis| {
                     if let Some(apis) = apis {
@@ -0,0 +1,340 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Forward the historical queries to an archive node, so a node which only keeps recent data
+//! can serve the full rpc api by the help of an archive node.
+
+use anyhow::{format_err, Result};
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{
+    Call, Error, Failure, FutureResponse, MethodCall, Middleware, Output, Params, Success, Value,
+};
+use jsonrpc_core_client::transports::ws;
+use jsonrpc_core_client::{RawClient, RpcError};
+use parking_lot::Mutex;
+use starcoin_config::RpcConfig;
+use starcoin_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::metadata::Metadata;
+use starcoin_rpc_api::types::StateRootOption;
+use starcoin_state_tree::StateNodeStore;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_types::block::BlockNumber;
+use std::sync::Arc;
+
+/// The local chain data used to decide whether a query should be forwarded to the archive node.
+pub trait LocalChainData: Send + Sync {
+    fn head_block_number(&self) -> Result<BlockNumber>;
+    fn has_state(&self, state_root: HashValue) -> Result<bool>;
+}
+
+impl LocalChainData for Storage {
+    fn head_block_number(&self) -> Result<BlockNumber> {
+        let startup_info = self
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?;
+        self.get_block_header_by_hash(startup_info.main)?
+            .map(|header| header.number())
+            .ok_or_else(|| format_err!("Can not find head block by hash:{:?}", startup_info.main))
+    }
+
+    fn has_state(&self, state_root: HashValue) -> Result<bool> {
+        Ok(state_root == *SPARSE_MERKLE_PLACEHOLDER_HASH
+            || StateNodeStore::get(self, &state_root)?.is_some())
+    }
+}
+
+/// The rpc methods whose first param is a block number.
+const BLOCK_NUMBER_METHODS: &[&str] = &[
+    "chain.get_block_by_number",
+    "chain.get_blocks_by_number",
+    "chain.get_block_info_by_number",
+    "chain.get_epoch_info_by_number",
+    "chain.get_epoch_infos_by_number",
+    "chain.get_global_time_by_number",
+    "chain.get_epoch_uncles_by_number",
+    "chain.epoch_uncle_summary_by_number",
+];
+
+/// The rpc methods which query a historical state by a `StateRootOption` param, and the param index.
+const STATE_ROOT_OPTION_METHODS: &[(&str, usize)] = &[
+    ("contract.get_code", 1),
+    ("contract.get_code_disassembled", 1),
+    ("contract.get_resource", 2),
+    ("contract.call", 1),
+];
+
+pub(crate) struct ArchiveForwarder {
+    archive_rpc: String,
+    recent_blocks: u64,
+    local: Arc<dyn LocalChainData>,
+    client: Mutex<Option<RawClient>>,
+}
+
+impl ArchiveForwarder {
+    pub(crate) fn new(
+        archive_rpc: String,
+        recent_blocks: u64,
+        local: Arc<dyn LocalChainData>,
+    ) -> Self {
+        Self {
+            archive_rpc,
+            recent_blocks,
+            local,
+            client: Mutex::new(None),
+        }
+    }
+
+    fn is_old_block(&self, number: BlockNumber) -> Result<bool> {
+        Ok(number.saturating_add(self.recent_blocks) < self.local.head_block_number()?)
+    }
+
+    /// The queries of the blocks older than the recent blocks, the events from old blocks,
+    /// and the states not available locally, are forwarded to the archive node.
+    pub(crate) fn is_archive_query(&self, method: &str, params: &Params) -> Result<bool> {
+        let param = |index: usize| match params {
+            Params::Array(params) => params.get(index),
+            _ => None,
+        };
+        if BLOCK_NUMBER_METHODS.contains(&method) {
+            return match param(0).and_then(Value::as_u64) {
+                Some(number) => self.is_old_block(number),
+                None => Ok(false),
+            };
+        }
+        if let Some((_, index)) = STATE_ROOT_OPTION_METHODS
+            .iter()
+            .find(|(state_method, _)| *state_method == method)
+        {
+            return match param(*index).filter(|option| !option.is_null()) {
+                Some(option) => match serde_json::from_value(option.clone())? {
+                    StateRootOption::StateRoot(state_root) => {
+                        Ok(!self.local.has_state(state_root)?)
+                    }
+                    StateRootOption::BlockNumber(number) => self.is_old_block(number),
+                    // the block is resolved locally, the state of an unknown block is not forwarded.
+                    StateRootOption::BlockHash(_) => Ok(false),
+                },
+                None => Ok(false),
+            };
+        }
+        match method {
+            "chain.get_events" => {
+                match param(0)
+                    .and_then(|filter| filter.get("from_block"))
+                    .and_then(Value::as_u64)
+                {
+                    Some(from_block) => self.is_old_block(from_block),
+                    None => Ok(false),
+                }
+            }
+            // the account state index only exists on the archive node.
+            "state.get_account_state_at" | "state.get_balance_at" => Ok(true),
+            "state.get_with_proof_by_root" => match param(1) {
+                Some(state_root) => {
+                    let state_root: HashValue = serde_json::from_value(state_root.clone())?;
+                    Ok(!self.local.has_state(state_root)?)
+                }
+                None => Ok(false),
+            },
+            _ => Ok(false),
+        }
+    }
+
+    async fn client(&self) -> Result<RawClient, Error> {
+        let client = self.client.lock().clone();
+        if let Some(client) = client {
+            return Ok(client);
+        }
+        let connect_err = |e: RpcError| {
+            Error::internal_error_with_message(format!(
+                "connect archive node {} error: {}",
+                self.archive_rpc, e
+            ))
+        };
+        let client = ws::try_connect::<RawClient>(self.archive_rpc.as_str())
+            .map_err(connect_err)?
+            .await
+            .map_err(connect_err)?;
+        *self.client.lock() = Some(client.clone());
+        Ok(client)
+    }
+
+    async fn forward(&self, method: &str, params: Params) -> Result<Value, Error> {
+        let client = self.client().await?;
+        match client.call_method(method, params).await {
+            Ok(value) => Ok(value),
+            Err(RpcError::JsonRpcError(e)) => Err(e),
+            Err(e) => {
+                // reconnect at next call.
+                *self.client.lock() = None;
+                Err(Error::internal_error_with_message(format!(
+                    "forward {} to archive node {} error: {}",
+                    method, self.archive_rpc, e
+                )))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ArchiveForwardMiddleware {
+    forwarder: Option<Arc<ArchiveForwarder>>,
+}
+
+impl ArchiveForwardMiddleware {
+    /// Forward nothing if the archive rpc is not configured.
+    pub fn from_config(config: &RpcConfig, local: Arc<dyn LocalChainData>) -> Self {
+        Self {
+            forwarder: config.archive_rpc().map(|archive_rpc| {
+                info!(
+                    "Forward historical rpc queries to archive node: {}",
+                    archive_rpc
+                );
+                Arc::new(ArchiveForwarder::new(
+                    archive_rpc.to_string(),
+                    config.archive_recent_blocks(),
+                    local,
+                ))
+            }),
+        }
+    }
+}
+
+impl Middleware<Metadata> for ArchiveForwardMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: Metadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (forwarder, method_call) = match (&self.forwarder, &call) {
+            (Some(forwarder), Call::MethodCall(method_call)) => {
+                match forwarder.is_archive_query(&method_call.method, &method_call.params) {
+                    Ok(true) => (forwarder.clone(), method_call.clone()),
+                    Ok(false) => return Either::Right(next(call, meta)),
+                    Err(e) => {
+                        warn!(
+                            "Check archive query {} error, serve it locally: {:?}",
+                            method_call.method, e
+                        );
+                        return Either::Right(next(call, meta));
+                    }
+                }
+            }
+            _ => return Either::Right(next(call, meta)),
+        };
+        Either::Left(Box::pin(async move {
+            let MethodCall {
+                jsonrpc,
+                method,
+                params,
+                id,
+            } = method_call;
+            debug!("Forward rpc call {} to archive node", method);
+            let output = match forwarder.forward(method.as_str(), params).await {
+                Ok(result) => Output::Success(Success {
+                    jsonrpc,
+                    result,
+                    id,
+                }),
+                Err(error) => Output::Failure(Failure { jsonrpc, error, id }),
+            };
+            Some(output)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct MockLocalChainData {
+        head: BlockNumber,
+        state_root: HashValue,
+    }
+
+    impl LocalChainData for MockLocalChainData {
+        fn head_block_number(&self) -> Result<BlockNumber> {
+            Ok(self.head)
+        }
+
+        fn has_state(&self, state_root: HashValue) -> Result<bool> {
+            Ok(state_root == self.state_root)
+        }
+    }
+
+    #[test]
+    fn test_is_archive_query() {
+        let state_root = HashValue::random();
+        let forwarder = ArchiveForwarder::new(
+            "ws://127.0.0.1:9870".to_string(),
+            100,
+            Arc::new(MockLocalChainData {
+                head: 1000,
+                state_root,
+            }),
+        );
+        let is_archive_query = |method: &str, params: Value| {
+            let params = match params {
+                Value::Array(params) => Params::Array(params),
+                _ => Params::None,
+            };
+            forwarder.is_archive_query(method, &params).unwrap()
+        };
+        assert!(is_archive_query("chain.get_block_by_number", json!([1])));
+        assert!(!is_archive_query("chain.get_block_by_number", json!([900])));
+        assert!(is_archive_query(
+            "chain.get_blocks_by_number",
+            json!([10, 5])
+        ));
+        assert!(!is_archive_query(
+            "chain.get_blocks_by_number",
+            json!([null, 5])
+        ));
+        assert!(is_archive_query(
+            "chain.get_events",
+            json!([{"from_block": 1, "to_block": 10}])
+        ));
+        assert!(!is_archive_query(
+            "chain.get_events",
+            json!([{"to_block": 10}])
+        ));
+        assert!(!is_archive_query(
+            "state.get_with_proof_by_root",
+            json!(["0x1/0/0x1::Account::Account", state_root])
+        ));
+        assert!(is_archive_query(
+            "state.get_with_proof_by_root",
+            json!(["0x1/0/0x1::Account::Account", HashValue::random()])
+        ));
+        assert!(is_archive_query(
+            "state.get_balance_at",
+            json!(["0x1", 900])
+        ));
+        assert!(is_archive_query(
+            "contract.get_resource",
+            json!(["0x1", "0x1::Account::Account", {"block_number": 1}])
+        ));
+        assert!(!is_archive_query(
+            "contract.get_resource",
+            json!(["0x1", "0x1::Account::Account", {"state_root": state_root}])
+        ));
+        assert!(!is_archive_query(
+            "contract.get_resource",
+            json!(["0x1", "0x1::Account::Account"])
+        ));
+        assert!(is_archive_query(
+            "contract.call",
+            json!([{}, {"state_root": HashValue::random()}])
+        ));
+        assert!(!is_archive_query("contract.call", json!([{}, null])));
+        assert!(!is_archive_query("chain.info", json!([])));
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bound the size of the batch requests. The calls of a batch are handled concurrently and every call
+//! gets its own output, so a failed call does not fail the others, but a too large batch is rejected as
+//! a whole, as the calls of a batch are not rate limited together.
+//! The size of the serialized response is bounded too if the transport configures a limit, a too large
+//! response is replaced by an error, so the client can narrow the query instead of losing the connection.
+
+use futures::FutureExt;
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{
+    Error, ErrorCode, Failure, FutureResponse, Id, Middleware, Output, Request, Response, Version,
+};
+use starcoin_config::RpcConfig;
+use starcoin_rpc_api::metadata::Metadata;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BatchLimitMiddleware {
+    max_batch_size: usize,
+    max_response_size: Option<usize>,
+}
+
+impl BatchLimitMiddleware {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self {
+            max_batch_size,
+            max_response_size: None,
+        }
+    }
+
+    pub fn from_config(config: &RpcConfig) -> Self {
+        Self::new(config.max_batch_size())
+    }
+
+    /// The limits of a transport, which fallback to the global batch size.
+    pub fn for_transport(
+        config: &RpcConfig,
+        max_batch_size: Option<usize>,
+        max_response_size: Option<usize>,
+    ) -> Self {
+        Self {
+            max_batch_size: max_batch_size.unwrap_or_else(|| config.max_batch_size()),
+            max_response_size,
+        }
+    }
+
+    fn check(&self, request: &Request) -> Result<(), Error> {
+        match request {
+            Request::Batch(calls) if calls.is_empty() => Err(Error {
+                code: ErrorCode::InvalidRequest,
+                message: "Empty batch request".to_string(),
+                data: None,
+            }),
+            Request::Batch(calls) if calls.len() > self.max_batch_size => Err(Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Batch request of {} calls exceeds the limit {}",
+                    calls.len(),
+                    self.max_batch_size
+                ),
+                data: None,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn limit_response(max_response_size: usize, response: Response) -> Response {
+        let size = serde_json::to_vec(&response).map_or(0, |bytes| bytes.len());
+        if size <= max_response_size {
+            return response;
+        }
+        let id = match &response {
+            Response::Single(output) => output.id().clone(),
+            Response::Batch(_) => Id::Null,
+        };
+        Response::Single(Output::Failure(Failure {
+            jsonrpc: Some(Version::V2),
+            error: Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Response of {} bytes exceeds the limit {}, please narrow the query",
+                    size, max_response_size
+                ),
+                data: None,
+            },
+            id,
+        }))
+    }
+}
+
+impl Middleware<Metadata> for BatchLimitMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: Metadata, next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        match self.check(&request) {
+            Ok(()) => match self.max_response_size {
+                Some(max_response_size) => {
+                    Either::Left(Box::pin(next(request, meta).map(move |response| {
+                        response.map(|response| Self::limit_response(max_response_size, response))
+                    })))
+                }
+                None => Either::Right(next(request, meta)),
+            },
+            Err(error) => {
+                let response = Response::Single(Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error,
+                    id: Id::Null,
+                }));
+                Either::Left(Box::pin(futures::future::ready(Some(response))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+    use serde_json::json;
+
+    #[test]
+    fn test_batch_limit() {
+        let mut io = MetaIoHandler::<Metadata, _>::with_middleware(BatchLimitMiddleware::new(2));
+        io.add_sync_method("echo", |params: Params| match params {
+            Params::Array(mut params) if !params.is_empty() => Ok(params.remove(0)),
+            _ => Err(Error::invalid_params("expect one param")),
+        });
+        let handle = |request: Value| -> Value {
+            let response = futures::executor::block_on(
+                io.handle_request(request.to_string().as_str(), Metadata::default()),
+            )
+            .unwrap();
+            serde_json::from_str(response.as_str()).unwrap()
+        };
+
+        // every call of a batch gets its own output.
+        let response = handle(json!([
+            {"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1},
+            {"jsonrpc": "2.0", "method": "echo", "params": [], "id": 2},
+        ]));
+        assert_eq!(response[0]["result"], json!(1));
+        assert_eq!(response[1]["error"]["code"], json!(-32602));
+
+        let response = handle(json!([
+            {"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1},
+            {"jsonrpc": "2.0", "method": "echo", "params": [2], "id": 2},
+            {"jsonrpc": "2.0", "method": "echo", "params": [3], "id": 3},
+        ]));
+        assert_eq!(response["error"]["code"], json!(-32600));
+        assert_eq!(response["id"], Value::Null);
+
+        let response = handle(json!([]));
+        assert_eq!(response["error"]["code"], json!(-32600));
+
+        let response = handle(json!({"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1}));
+        assert_eq!(response["result"], json!(1));
+    }
+
+    #[test]
+    fn test_response_limit() {
+        let mut io = MetaIoHandler::<Metadata, _>::with_middleware(BatchLimitMiddleware {
+            max_batch_size: 2,
+            max_response_size: Some(100),
+        });
+        io.add_sync_method("repeat", |params: Params| match params {
+            Params::Array(params) if !params.is_empty() => Ok(Value::String(
+                "a".repeat(params[0].as_u64().unwrap_or_default() as usize),
+            )),
+            _ => Err(Error::invalid_params("expect one param")),
+        });
+        let handle = |request: Value| -> Value {
+            let response = futures::executor::block_on(
+                io.handle_request(request.to_string().as_str(), Metadata::default()),
+            )
+            .unwrap();
+            serde_json::from_str(response.as_str()).unwrap()
+        };
+
+        let response =
+            handle(json!({"jsonrpc": "2.0", "method": "repeat", "params": [10], "id": 1}));
+        assert_eq!(response["result"], json!("a".repeat(10)));
+
+        let response =
+            handle(json!({"jsonrpc": "2.0", "method": "repeat", "params": [100], "id": 1}));
+        assert_eq!(response["error"]["code"], json!(-32600));
+        assert_eq!(response["id"], json!(1));
+
+        let response = handle(json!([
+            {"jsonrpc": "2.0", "method": "repeat", "params": [40], "id": 1},
+            {"jsonrpc": "2.0", "method": "repeat", "params": [40], "id": 2},
+        ]));
+        assert_eq!(response["error"]["code"], json!(-32600));
+        assert_eq!(response["id"], Value::Null);
+    }
+}
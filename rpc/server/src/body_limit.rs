@@ -0,0 +1,49 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpc_http_server::hyper::body::HttpBody;
+use jsonrpc_http_server::hyper::{self, Body};
+
+/// Read the http body, None if it is larger than `limit`. The body is read chunk by chunk and the
+/// reading stops as soon as the limit is exceeded, so a large body is never buffered in whole.
+pub(crate) async fn read_body(mut body: Body, limit: usize) -> hyper::Result<Option<Vec<u8>>> {
+    if body.size_hint().lower() > limit as u64 {
+        return Ok(None);
+    }
+    let mut buf = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if buf.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(Some(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunked_body(chunks: Vec<&'static [u8]>) -> Body {
+        Body::wrap_stream(futures::stream::iter(
+            chunks.into_iter().map(Ok::<_, std::io::Error>),
+        ))
+    }
+
+    #[stest::test]
+    async fn test_read_body() {
+        let body = read_body(chunked_body(vec![b"ab", b"cd"]), 4)
+            .await
+            .unwrap();
+        assert_eq!(body, Some(b"abcd".to_vec()));
+
+        // the chunks are not all read if the limit is exceeded.
+        let body = chunked_body(vec![b"ab", b"cd", b"ef"]);
+        assert_eq!(read_body(body, 3).await.unwrap(), None);
+
+        // the body with a known length is refused before reading.
+        assert_eq!(read_body(Body::from("abcd"), 3).await.unwrap(), None);
+        assert_eq!(read_body(Body::empty(), 0).await.unwrap(), Some(vec![]));
+    }
+}
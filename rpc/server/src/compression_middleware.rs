@@ -0,0 +1,459 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compress the http json rpc responses by gzip or deflate, negotiated by the `Accept-Encoding` header.
+//! The http server can not transform the responses it produced, nor expose the raw request and
+//! response to record their sizes, so the post requests are handled by the middleware with a clone of
+//! the same io handler, which shares the middlewares and quotas of the http server. The https server
+//! handles its requests in the same way.
+//! The request body compressed by gzip or deflate, declared by the `Content-Encoding` header, is
+//! handled by the middleware too, the request body limit applies to both the compressed and the
+//! decompressed body.
+
+use crate::body_limit::read_body;
+use crate::cors_middleware::allow_origin;
+use crate::extractors::RpcExtractor;
+use flate2::read::{DeflateDecoder, GzDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use futures::TryFutureExt;
+use jsonrpc_core::{Error, ErrorCode, MetaIoHandler, Middleware, Response, Version};
+use jsonrpc_http_server::hyper::{self, header, Body, Method, StatusCode};
+use jsonrpc_http_server::{MetaExtractor, RequestMiddleware, RequestMiddlewareAction};
+use starcoin_rpc_api::metadata::Metadata;
+use starcoin_rpc_middleware::TransportRecorder;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The small responses are not worth compressing.
+const MIN_COMPRESS_SIZE: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(vec![], Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Deflate => {
+                let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Decompress the data, None if the decompressed data is larger than `limit`. The data is
+    /// decompressed chunk by chunk and the decompression stops as soon as the limit is exceeded.
+    fn decompress(self, data: &[u8], limit: usize) -> std::io::Result<Option<Vec<u8>>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(GzDecoder::new(data)),
+            Self::Deflate => Box::new(DeflateDecoder::new(data)),
+        };
+        let mut decompressed = vec![];
+        decoder
+            .take(limit as u64 + 1)
+            .read_to_end(&mut decompressed)?;
+        Ok(if decompressed.len() > limit {
+            None
+        } else {
+            Some(decompressed)
+        })
+    }
+}
+
+/// Parse the `Content-Encoding` header of the request, None if the body is not compressed, and
+/// Err if the encoding is not supported.
+fn content_encoding(request: &hyper::Request<Body>) -> Result<Option<Encoding>, String> {
+    let value = match request.headers().get(header::CONTENT_ENCODING) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    match value.to_str().map(|v| v.trim().to_lowercase()) {
+        Ok(v) if v.is_empty() || v == "identity" => Ok(None),
+        Ok(v) if v == "gzip" => Ok(Some(Encoding::Gzip)),
+        Ok(v) if v == "deflate" => Ok(Some(Encoding::Deflate)),
+        _ => Err(format!("Unsupported content encoding {:?}", value)),
+    }
+}
+
+/// Pick the encoding from the `Accept-Encoding` header, gzip is preferred, and the encodings with
+/// `q=0` are refused.
+pub fn accepted_encoding(accept_encoding: &str) -> Option<Encoding> {
+    let accepted: Vec<Encoding> = accept_encoding
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let encoding = match parts.next()?.to_lowercase().as_str() {
+                "gzip" => Encoding::Gzip,
+                "deflate" => Encoding::Deflate,
+                _ => return None,
+            };
+            let refused = parts.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .map_or(false, |q| q <= 0.0)
+            });
+            if refused {
+                None
+            } else {
+                Some(encoding)
+            }
+        })
+        .collect();
+    [Encoding::Gzip, Encoding::Deflate]
+        .iter()
+        .copied()
+        .find(|encoding| accepted.contains(encoding))
+}
+
+pub struct CompressionMiddleware<M>
+where
+    M: Middleware<Metadata>,
+{
+    io_handler: Arc<MetaIoHandler<Metadata, M>>,
+    extractor: Arc<RpcExtractor>,
+    max_request_body_size: usize,
+    cors_origins: Option<Vec<String>>,
+    /// Compress the responses if the client accepts, the responses are not compressed if false.
+    compression: bool,
+    recorder: TransportRecorder,
+}
+
+impl<M> CompressionMiddleware<M>
+where
+    M: Middleware<Metadata>,
+{
+    pub fn new(
+        io_handler: MetaIoHandler<Metadata, M>,
+        extractor: RpcExtractor,
+        max_request_body_size: usize,
+        cors_origins: Option<Vec<String>>,
+        compression: bool,
+        recorder: TransportRecorder,
+    ) -> Self {
+        Self {
+            io_handler: Arc::new(io_handler),
+            extractor: Arc::new(extractor),
+            max_request_body_size,
+            cors_origins,
+            compression,
+            recorder,
+        }
+    }
+}
+
+fn json_response(
+    status: StatusCode,
+    body: Vec<u8>,
+    encoding: Option<Encoding>,
+) -> hyper::Response<Body> {
+    let mut resp = hyper::Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    let headers = resp.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    headers.insert(
+        header::VARY,
+        header::HeaderValue::from_static("Accept-Encoding"),
+    );
+    if let Some(encoding) = encoding {
+        headers.insert(
+            header::CONTENT_ENCODING,
+            header::HeaderValue::from_static(encoding.as_str()),
+        );
+    }
+    resp
+}
+
+fn error_body(error: Error) -> Vec<u8> {
+    serde_json::to_vec(&Response::from(error, Some(Version::V2))).unwrap_or_default()
+}
+
+/// Set the `Access-Control-Allow-Origin` header of the response.
+pub(crate) fn with_allow_origin(
+    mut resp: hyper::Response<Body>,
+    allow_origin: Option<String>,
+) -> hyper::Response<Body> {
+    if let Some(value) = allow_origin.and_then(|origin| header::HeaderValue::from_str(&origin).ok())
+    {
+        resp.headers_mut()
+            .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+    }
+    resp
+}
+
+/// Handle a json rpc http request, the request body is decompressed by its `Content-Encoding`, and
+/// the response is compressed by the `encoding` if it is large enough. The sizes of the decompressed
+/// request and the uncompressed response are recorded by the `recorder`.
+pub(crate) async fn handle_http_request<M>(
+    io_handler: Arc<MetaIoHandler<Metadata, M>>,
+    meta: Metadata,
+    max_request_body_size: usize,
+    encoding: Option<Encoding>,
+    recorder: TransportRecorder,
+    request: hyper::Request<Body>,
+) -> hyper::Result<hyper::Response<Body>>
+where
+    M: Middleware<Metadata>,
+{
+    let too_large = || {
+        json_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            error_body(Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("Request body is larger than {}", max_request_body_size),
+                data: None,
+            }),
+            None,
+        )
+    };
+    let content_encoding = match content_encoding(&request) {
+        Ok(content_encoding) => content_encoding,
+        Err(message) => {
+            return Ok(json_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                error_body(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message,
+                    data: None,
+                }),
+                None,
+            ))
+        }
+    };
+    let body = match read_body(request.into_body(), max_request_body_size).await? {
+        Some(body) => body,
+        None => return Ok(too_large()),
+    };
+    let body = match content_encoding {
+        Some(content_encoding) => match content_encoding.decompress(&body, max_request_body_size) {
+            Ok(Some(body)) => body,
+            Ok(None) => return Ok(too_large()),
+            Err(_) => {
+                return Ok(json_response(
+                    StatusCode::BAD_REQUEST,
+                    error_body(Error::new(ErrorCode::ParseError)),
+                    None,
+                ))
+            }
+        },
+        None => body,
+    };
+    let request = match std::str::from_utf8(&body) {
+        Ok(request) => request,
+        Err(_) => {
+            return Ok(json_response(
+                StatusCode::OK,
+                error_body(Error::new(ErrorCode::ParseError)),
+                None,
+            ))
+        }
+    };
+    let start = Instant::now();
+    let response = io_handler.handle_request(request, meta).await;
+    recorder.record(request, response.as_deref(), start.elapsed());
+    let response = response.unwrap_or_default().into_bytes();
+    let encoding = match encoding {
+        Some(encoding) if response.len() >= MIN_COMPRESS_SIZE => encoding,
+        _ => return Ok(json_response(StatusCode::OK, response, None)),
+    };
+    Ok(match encoding.compress(&response) {
+        Ok(compressed) => json_response(StatusCode::OK, compressed, Some(encoding)),
+        Err(_) => json_response(StatusCode::OK, response, None),
+    })
+}
+
+impl<M> RequestMiddleware for CompressionMiddleware<M>
+where
+    M: Middleware<Metadata> + Send + Sync + 'static,
+{
+    fn on_request(&self, request: hyper::Request<Body>) -> RequestMiddlewareAction {
+        if request.method() != Method::POST {
+            return request.into();
+        }
+        let encoding = if self.compression {
+            request
+                .headers()
+                .get(header::ACCEPT_ENCODING)
+                .and_then(|v| v.to_str().ok())
+                .and_then(accepted_encoding)
+        } else {
+            None
+        };
+        let origin = request
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|v| v.to_str().ok());
+        // the request from a disallowed origin is rejected by the cors of the http server.
+        let allow_origin = match allow_origin(self.cors_origins.as_ref(), origin) {
+            Ok(allow_origin) => allow_origin,
+            Err(_) => return request.into(),
+        };
+        let meta = self.extractor.read_metadata(&request);
+        let response = handle_http_request(
+            self.io_handler.clone(),
+            meta,
+            self.max_request_body_size,
+            encoding,
+            self.recorder,
+            request,
+        );
+        RequestMiddlewareAction::Respond {
+            should_validate_hosts: true,
+            response: Box::pin(response.map_ok(move |resp| with_allow_origin(resp, allow_origin))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use jsonrpc_core::{Params, Value};
+    use std::io::Read;
+
+    #[test]
+    fn test_accepted_encoding() {
+        assert_eq!(accepted_encoding("gzip"), Some(Encoding::Gzip));
+        assert_eq!(
+            accepted_encoding("deflate, gzip;q=1.0"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            accepted_encoding("gzip;q=0, deflate"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(accepted_encoding("br, identity"), None);
+        assert_eq!(accepted_encoding(""), None);
+    }
+
+    #[stest::test]
+    async fn test_compressed_response() {
+        let mut io = MetaIoHandler::<Metadata>::default();
+        io.add_sync_method("repeat", |params: Params| {
+            let params: Vec<u64> = params.parse()?;
+            Ok(Value::String("a".repeat(params[0] as usize)))
+        });
+        let io = Arc::new(io);
+        let call = |size: u64, encoding: Option<Encoding>| {
+            let request = hyper::Request::post("/")
+                .body(Body::from(format!(
+                    r#"{{"jsonrpc": "2.0", "method": "repeat", "params": [{}], "id": 1}}"#,
+                    size
+                )))
+                .unwrap();
+            handle_http_request(
+                io.clone(),
+                Metadata::default(),
+                1024 * 1024,
+                encoding,
+                TransportRecorder::new("test", None),
+                request,
+            )
+        };
+
+        let resp = call(2048, Some(Encoding::Gzip)).await.unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_ENCODING], "gzip");
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(body.as_ref())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        let response: Value = serde_json::from_str(decoded.as_str()).unwrap();
+        assert_eq!(response["result"], Value::String("a".repeat(2048)));
+
+        let resp = call(2048, Some(Encoding::Deflate)).await.unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_ENCODING], "deflate");
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        let mut decoded = String::new();
+        DeflateDecoder::new(body.as_ref())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert!(decoded.contains(&"a".repeat(2048)));
+
+        // the small response is not compressed.
+        let resp = call(10, Some(Encoding::Gzip)).await.unwrap();
+        assert!(resp.headers().get(header::CONTENT_ENCODING).is_none());
+        let resp = call(2048, None).await.unwrap();
+        assert!(resp.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[stest::test]
+    async fn test_compressed_request() {
+        let mut io = MetaIoHandler::<Metadata>::default();
+        io.add_sync_method("echo", |params: Params| {
+            let params: Vec<Value> = params.parse()?;
+            Ok(params[0].clone())
+        });
+        let io = Arc::new(io);
+        let limit = 1024;
+        let call = |body: Vec<u8>, content_encoding: &str| {
+            let request = hyper::Request::post("/")
+                .header(header::CONTENT_ENCODING, content_encoding)
+                .body(Body::from(body))
+                .unwrap();
+            handle_http_request(
+                io.clone(),
+                Metadata::default(),
+                limit,
+                None,
+                TransportRecorder::new("test", None),
+                request,
+            )
+        };
+        let request = |padding: usize| {
+            format!(
+                r#"{{"jsonrpc": "2.0", "method": "echo", "params": ["{}"], "id": 1}}"#,
+                "a".repeat(padding)
+            )
+        };
+
+        let body = Encoding::Gzip.compress(request(10).as_bytes()).unwrap();
+        let resp = call(body, "gzip").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        let response: Value = serde_json::from_slice(body.as_ref()).unwrap();
+        assert_eq!(response["result"], Value::String("a".repeat(10)));
+
+        let body = Encoding::Deflate.compress(request(10).as_bytes()).unwrap();
+        let resp = call(body, "deflate").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // the compressed body is small, but the decompressed body is over the limit.
+        let body = Encoding::Gzip
+            .compress(request(100 * limit).as_bytes())
+            .unwrap();
+        assert!(body.len() < limit);
+        let resp = call(body, "gzip").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // the compressed body is over the limit.
+        let resp = call(vec![0u8; limit + 1], "gzip").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let resp = call(b"not gzip".to_vec(), "gzip").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = call(request(10).into_bytes(), "br").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
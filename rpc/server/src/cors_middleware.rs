@@ -0,0 +1,162 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Restrict the browser dApps by the `Origin` header. The http server only accepts the allowed
+//! origins, and this middleware limits the methods each origin can call, so a dApp can be exposed
+//! to the read apis without the account or admin apis.
+
+use crate::api_key_middleware::method_allowed;
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{Call, Error, Failure, FutureResponse, Id, Middleware, Output};
+use starcoin_config::HttpConfiguration;
+use starcoin_rpc_api::errors::RpcErrorCode;
+use starcoin_rpc_api::metadata::Metadata;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The `Access-Control-Allow-Origin` of the response to a request from the `origin`,
+/// or an error message if the origin is not allowed. Any origin is allowed if `origins` is None.
+pub(crate) fn allow_origin(
+    origins: Option<&Vec<String>>,
+    origin: Option<&str>,
+) -> Result<Option<String>, String> {
+    match (origins, origin) {
+        (None, _) => Ok(Some("*".to_string())),
+        // not a cross origin request.
+        (Some(_), None) => Ok(None),
+        (Some(origins), Some(origin)) => {
+            if origins
+                .iter()
+                .any(|allowed| allowed == "*" || allowed == origin)
+            {
+                Ok(Some(origin.to_string()))
+            } else {
+                Err(format!("Origin {} is not allowed", origin))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CorsMiddleware {
+    methods: Option<Arc<HashMap<String, Vec<String>>>>,
+}
+
+impl CorsMiddleware {
+    pub fn from_config(config: &HttpConfiguration) -> Self {
+        Self {
+            methods: config.cors_methods.clone().map(Arc::new),
+        }
+    }
+
+    fn check(&self, method: &str, origin: Option<&String>) -> Result<(), Error> {
+        let patterns = match (self.methods.as_ref(), origin) {
+            (Some(methods), Some(origin)) => methods.get(origin),
+            _ => None,
+        };
+        if patterns.is_some() && !method_allowed(patterns, method) {
+            return Err(RpcErrorCode::MethodNotAllowed.to_error(
+                format!(
+                    "Method {} is not allowed for origin {}",
+                    method,
+                    origin.map(String::as_str).unwrap_or_default()
+                ),
+                None,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Middleware<Metadata> for CorsMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: Metadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        if self.methods.is_none() || meta.origin.is_none() {
+            return Either::Right(next(call, meta));
+        }
+        let method = match &call {
+            Call::MethodCall(m) => Some((m.method.as_str(), m.jsonrpc, m.id.clone())),
+            Call::Notification(n) => Some((n.method.as_str(), n.jsonrpc, Id::Null)),
+            Call::Invalid { .. } => None,
+        };
+        if let Some((method, json_version, id)) = method {
+            if let Err(error) = self.check(method, meta.origin.as_ref()) {
+                let output = Output::Failure(Failure {
+                    jsonrpc: json_version,
+                    error,
+                    id,
+                });
+                return Either::Left(Box::pin(futures::future::ready(Some(output))));
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+    use serde_json::json;
+
+    fn handle(io: &MetaIoHandler<Metadata, CorsMiddleware>, method: &str, origin: &str) -> Value {
+        let meta = Metadata {
+            origin: Some(origin.to_string()),
+            ..Default::default()
+        };
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1});
+        let response =
+            futures::executor::block_on(io.handle_request(request.to_string().as_str(), meta))
+                .unwrap();
+        serde_json::from_str(response.as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_allow_origin() {
+        assert_eq!(
+            allow_origin(None, Some("https://a.com")),
+            Ok(Some("*".to_string()))
+        );
+        let origins = vec!["https://a.com".to_string()];
+        assert_eq!(
+            allow_origin(Some(&origins), Some("https://a.com")),
+            Ok(Some("https://a.com".to_string()))
+        );
+        assert!(allow_origin(Some(&origins), Some("https://b.com")).is_err());
+        assert_eq!(allow_origin(Some(&origins), None), Ok(None));
+    }
+
+    #[test]
+    fn test_cors_methods() {
+        let mut cors_methods = HashMap::new();
+        cors_methods.insert("https://a.com".to_string(), vec!["chain.*".to_string()]);
+        let mut config = HttpConfiguration::default();
+        config.cors_methods = Some(cors_methods);
+        let mut io = MetaIoHandler::with_middleware(CorsMiddleware::from_config(&config));
+        for method in &["chain.info", "account.sign"] {
+            io.add_sync_method(method, |_params: Params| Ok(json!(true)));
+        }
+
+        assert_eq!(
+            handle(&io, "chain.info", "https://a.com")["result"],
+            json!(true)
+        );
+        assert_eq!(
+            handle(&io, "account.sign", "https://a.com")["error"]["code"],
+            json!(i64::from(RpcErrorCode::MethodNotAllowed))
+        );
+        // the origins not listed can call all the methods.
+        assert_eq!(
+            handle(&io, "account.sign", "https://b.com")["result"],
+            json!(true)
+        );
+    }
+}
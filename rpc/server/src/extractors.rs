@@ -3,14 +3,57 @@
 
 use jsonrpc_http_server::hyper;
 use jsonrpc_pubsub::Session;
+use jsonrpc_ws_server::{ws, MiddlewareAction};
+use parking_lot::RwLock;
 use starcoin_rpc_api::metadata::Metadata;
+use std::cell::RefCell;
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The extractors of the persistent connections are called once per connection,
+/// so every connection gets a new id.
+fn next_connection_id() -> Option<u64> {
+    Some(NEXT_CONNECTION_ID.fetch_add(1, Ordering::SeqCst))
+}
+
+/// Parse the first ip of an ip header value, such as `X-Forwarded-For: client, proxy1`.
+fn parse_client_ip(value: &str) -> Option<IpAddr> {
+    value
+        .split(',')
+        .next()
+        .and_then(|s| s.trim().parse::<IpAddr>().ok())
+}
+
+/// The client ip of the ws connections whose ip is unknown. These connections share the limits
+/// per ip, so a client can not bypass the limits by omitting the ip headers.
+pub const UNKNOWN_WS_CLIENT_IP: &str = "unknown";
+
+/// The query parameter of the api key in the url of a ws connection, such as `ws://host/?api_key=key`.
+const WS_API_KEY_QUERY: &str = "api_key";
+
+/// The client info read from the handshake request of a ws connection.
+#[derive(Default)]
+struct WsHandshake {
+    client_ip: Option<IpAddr>,
+    api_key: Option<String>,
+}
+
+thread_local! {
+    /// The handshake of the ws connection being opened. The ws server calls the request middleware
+    /// and then the meta extractor of a new connection on its event loop thread, so
+    /// `WsRequestMiddleware` passes the handshake to `WsExtractor` by the thread local.
+    static WS_HANDSHAKE: RefCell<Option<WsHandshake>> = RefCell::new(None);
+}
+
 /// Common HTTP & IPC & TCP metadata extractor.
 #[derive(Default)]
 pub struct RpcExtractor {
     pub http_ip_headers: Vec<String>,
+    /// The http header of the api key.
+    pub http_api_key_header: Option<String>,
 }
 
 impl jsonrpc_http_server::MetaExtractor<Metadata> for RpcExtractor {
@@ -20,43 +63,128 @@ impl jsonrpc_http_server::MetaExtractor<Metadata> for RpcExtractor {
             if let Some(v) = _req.headers().get(header) {
                 if let Ok(s) = v.to_str() {
                     // if it's an valid ip.
-                    if let Some(Ok(ip)) = s.split(',').next().map(|s| s.trim().parse::<IpAddr>()) {
+                    if let Some(ip) = parse_client_ip(s) {
                         client_ip = Some(ip);
                     }
                 }
             }
         }
 
+        let api_key = self
+            .http_api_key_header
+            .as_ref()
+            .and_then(|header| _req.headers().get(header))
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.trim().to_string());
+
+        let origin = _req
+            .headers()
+            .get(hyper::header::ORIGIN)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
         Metadata {
+            origin,
             session: None,
             user: client_ip.map(|ip| ip.to_string()),
+            connection_id: None,
+            api_key,
+            connection_api_key: None,
         }
     }
 }
 impl jsonrpc_ipc_server::MetaExtractor<Metadata> for RpcExtractor {
     fn extract(&self, req: &jsonrpc_ipc_server::RequestContext) -> Metadata {
         Metadata {
+            origin: None,
             session: Some(Arc::new(Session::new(req.sender.clone()))),
             user: None,
+            connection_id: next_connection_id(),
+            api_key: None,
+            connection_api_key: None,
         }
     }
 }
 impl jsonrpc_tcp_server::MetaExtractor<Metadata> for RpcExtractor {
     fn extract(&self, context: &jsonrpc_tcp_server::RequestContext) -> Metadata {
         Metadata {
+            origin: None,
             session: Some(Arc::new(Session::new(context.sender.clone()))),
             user: Some(context.peer_addr.ip().to_string()),
+            connection_id: next_connection_id(),
+            api_key: None,
+            connection_api_key: Some(Arc::new(RwLock::new(None))),
+        }
+    }
+}
+
+/// Read the client ip and the api key of a ws connection from the handshake request. The client
+/// ip is only read by the configured ip headers, which should be set by a trusted proxy. The ws
+/// server does not expose the socket address of the connection, so the connections without the
+/// headers are counted as the `UNKNOWN_WS_CLIENT_IP`. The api key is read by the api key header,
+/// or the `api_key` query parameter of the url, as the browsers can not set the headers of a ws
+/// connection.
+pub struct WsRequestMiddleware {
+    pub ip_headers: Vec<String>,
+    pub api_key_header: Option<String>,
+}
+
+impl jsonrpc_ws_server::RequestMiddleware for WsRequestMiddleware {
+    fn process(&self, req: &ws::Request) -> MiddlewareAction {
+        let mut client_ip = None;
+        for header in self.ip_headers.iter() {
+            if let Some(v) = req.header(header) {
+                if let Some(ip) = std::str::from_utf8(v).ok().and_then(parse_client_ip) {
+                    client_ip = Some(ip);
+                }
+            }
         }
+
+        let api_key = self
+            .api_key_header
+            .as_ref()
+            .and_then(|header| req.header(header))
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .map(|s| s.trim().to_string())
+            .or_else(|| {
+                req.resource()
+                    .splitn(2, '?')
+                    .nth(1)?
+                    .split('&')
+                    .find_map(|param| {
+                        let mut pair = param.splitn(2, '=');
+                        match (pair.next(), pair.next()) {
+                            (Some(WS_API_KEY_QUERY), Some(value)) => Some(value.to_string()),
+                            _ => None,
+                        }
+                    })
+            });
+        WS_HANDSHAKE
+            .with(|handshake| *handshake.borrow_mut() = Some(WsHandshake { client_ip, api_key }));
+        MiddlewareAction::Proceed
     }
 }
 
+/// The ws metadata extractor, the client ip and the api key are read by `WsRequestMiddleware`.
 pub struct WsExtractor;
 impl jsonrpc_ws_server::MetaExtractor<Metadata> for WsExtractor {
     fn extract(&self, req: &jsonrpc_ws_server::RequestContext) -> Metadata {
         let session = Some(Arc::new(Session::new(req.sender())));
+        let handshake = WS_HANDSHAKE
+            .with(|handshake| handshake.borrow_mut().take())
+            .unwrap_or_default();
         Metadata {
+            origin: req.origin.clone(),
             session,
-            user: None,
+            user: Some(
+                handshake
+                    .client_ip
+                    .map(|ip| ip.to_string())
+                    .unwrap_or_else(|| UNKNOWN_WS_CLIENT_IP.to_string()),
+            ),
+            connection_id: next_connection_id(),
+            api_key: handshake.api_key,
+            connection_api_key: Some(Arc::new(RwLock::new(None))),
         }
     }
 }
@@ -0,0 +1,227 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional GraphQL endpoint of the chain data, so the explorers and dashboards can select the
+//! nested blocks, transactions, events and account resources they need in one request.
+//! It is served at `POST /graphql` of the graphql address, and `GET /graphql` serves the playground.
+//! The requests are dispatched to the `graphql.execute` json rpc method, so they share the api key
+//! and quota checks of the http json rpc.
+
+mod schema;
+#[cfg(test)]
+mod tests;
+
+pub use schema::{build_schema, ChainData, ChainSchema};
+
+use crate::body_limit::read_body;
+use crate::extractors::RpcExtractor;
+use anyhow::Result;
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+use futures::channel::oneshot;
+use jsonrpc_core::{
+    Call, Error, ErrorCode, Id, MetaIoHandler, MethodCall, Middleware, Output, Params, Request,
+    Response, Version,
+};
+use jsonrpc_http_server::hyper::service::{make_service_fn, service_fn};
+use jsonrpc_http_server::hyper::{self, header, Body, Method, Server, StatusCode};
+use jsonrpc_http_server::MetaExtractor;
+use serde_json::Value;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::errors::RpcErrorCode;
+use starcoin_rpc_api::metadata::Metadata;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const GRAPHQL_PATH: &str = "/graphql";
+/// The json rpc method the graphql requests are dispatched to, so they pass the api key, quota and
+/// metric middlewares of the json rpc, and can be allowed for an api key by this method name.
+pub const GRAPHQL_METHOD: &str = "graphql.execute";
+
+/// Add the graphql method to the json rpc `io_handler`.
+pub fn add_graphql_method<M>(io_handler: &mut MetaIoHandler<Metadata, M>, schema: ChainSchema)
+where
+    M: Middleware<Metadata>,
+{
+    io_handler.add_method_with_meta(GRAPHQL_METHOD, move |params: Params, _meta: Metadata| {
+        let schema = schema.clone();
+        async move {
+            let (request,): (async_graphql::Request,) = params.parse()?;
+            let response = schema.execute(request).await;
+            serde_json::to_value(&response).map_err(|e| Error {
+                code: ErrorCode::InternalError,
+                message: e.to_string(),
+                data: None,
+            })
+        }
+    });
+}
+
+fn status_of(error: &Error) -> StatusCode {
+    match RpcErrorCode::of_error(error) {
+        Some(RpcErrorCode::RateLimitExceeded) => return StatusCode::TOO_MANY_REQUESTS,
+        Some(RpcErrorCode::InvalidApiKey) => return StatusCode::UNAUTHORIZED,
+        Some(RpcErrorCode::MethodNotAllowed) => return StatusCode::FORBIDDEN,
+        _ => {}
+    }
+    match error.code {
+        ErrorCode::ParseError | ErrorCode::InvalidRequest | ErrorCode::InvalidParams => {
+            StatusCode::BAD_REQUEST
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Dispatch a graphql request to the graphql method of the json rpc, returns the status and the json body.
+pub async fn dispatch<M>(
+    io_handler: &MetaIoHandler<Metadata, M>,
+    body: &[u8],
+    meta: Metadata,
+) -> (StatusCode, Value)
+where
+    M: Middleware<Metadata>,
+{
+    let request = match serde_json::from_slice::<Value>(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Value::String(format!("Invalid graphql request: {}", e)),
+            )
+        }
+    };
+    let call = Call::MethodCall(MethodCall {
+        jsonrpc: Some(Version::V2),
+        method: GRAPHQL_METHOD.to_string(),
+        params: Params::Array(vec![request]),
+        id: Id::Num(0),
+    });
+    match io_handler
+        .handle_rpc_request(Request::Single(call), meta)
+        .await
+    {
+        Some(Response::Single(Output::Success(success))) => (StatusCode::OK, success.result),
+        Some(Response::Single(Output::Failure(failure))) => (
+            status_of(&failure.error),
+            serde_json::to_value(&failure.error).unwrap_or_default(),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Value::String("Unexpected json rpc response".to_string()),
+        ),
+    }
+}
+
+async fn serve_graphql<M>(
+    io_handler: Arc<MetaIoHandler<Metadata, M>>,
+    extractor: Arc<RpcExtractor>,
+    max_request_body_size: usize,
+    req: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, hyper::Error>
+where
+    M: Middleware<Metadata>,
+{
+    let mut resp = hyper::Response::new(Body::empty());
+    match (req.method(), req.uri().path()) {
+        (&Method::POST, GRAPHQL_PATH) => {
+            let meta = extractor.read_metadata(&req);
+            let body = match read_body(req.into_body(), max_request_body_size).await? {
+                Some(body) => body,
+                None => {
+                    *resp.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+                    return Ok(resp);
+                }
+            };
+            let (status, body) = dispatch(io_handler.as_ref(), &body, meta).await;
+            *resp.status_mut() = status;
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("application/json"),
+            );
+            *resp.body_mut() = Body::from(body.to_string());
+        }
+        (&Method::GET, GRAPHQL_PATH) => {
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/html; charset=utf-8"),
+            );
+            *resp.body_mut() = Body::from(playground_source(GraphQLPlaygroundConfig::new(
+                GRAPHQL_PATH,
+            )));
+        }
+        _ => {
+            *resp.status_mut() = StatusCode::NOT_FOUND;
+        }
+    };
+    Ok(resp)
+}
+
+pub struct GraphQLServer {
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl GraphQLServer {
+    /// Start the graphql server on its own thread and runtime, the requests are executed by the
+    /// graphql method of the `io_handler`.
+    pub fn start<M>(
+        address: SocketAddr,
+        io_handler: MetaIoHandler<Metadata, M>,
+        extractor: RpcExtractor,
+        max_request_body_size: usize,
+    ) -> Result<Self>
+    where
+        M: Middleware<Metadata> + Send + Sync + 'static,
+    {
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let io_handler = Arc::new(io_handler);
+        let extractor = Arc::new(extractor);
+        std::thread::Builder::new()
+            .name("graphql-server".to_string())
+            .spawn(move || {
+                let mut rt = match tokio::runtime::Builder::new()
+                    .basic_scheduler()
+                    .enable_io()
+                    .build()
+                {
+                    Ok(rt) => rt,
+                    Err(e) => {
+                        error!("Build graphql server runtime failed: {:?}", e);
+                        return;
+                    }
+                };
+                let result = rt.block_on(async move {
+                    let make_service = make_service_fn(move |_| {
+                        let io_handler = io_handler.clone();
+                        let extractor = extractor.clone();
+                        async move {
+                            Ok::<_, hyper::Error>(service_fn(move |req| {
+                                serve_graphql(
+                                    io_handler.clone(),
+                                    extractor.clone(),
+                                    max_request_body_size,
+                                    req,
+                                )
+                            }))
+                        }
+                    });
+                    Server::try_bind(&address)?
+                        .serve(make_service)
+                        .with_graceful_shutdown(async {
+                            shutdown_receiver.await.ok();
+                        })
+                        .await
+                });
+                if let Err(e) = result {
+                    error!("Graphql server at {} failed: {:?}", address, e);
+                }
+            })?;
+        Ok(Self {
+            shutdown: Some(shutdown_sender),
+        })
+    }
+
+    pub fn close(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
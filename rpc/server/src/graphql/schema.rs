@@ -0,0 +1,605 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Json, Object, Result, Schema};
+use bcs_ext::BCSCodec;
+use serde::Serialize;
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
+use starcoin_crypto::HashValue;
+use starcoin_resource_viewer::MoveValueAnnotator;
+use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_rpc_api::types::{
+    AnnotatedMoveStructView, AnnotatedMoveValueView, StrView, TransactionVMStatus,
+};
+use starcoin_service_registry::ServiceRef;
+use starcoin_state_api::ChainStateAsyncService;
+use starcoin_state_service::ChainStateService;
+use starcoin_state_tree::StateNodeStore;
+use starcoin_statedb::ChainStateDB;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::event::EventKey;
+use starcoin_types::filter::Filter;
+use starcoin_types::transaction::{Transaction, TransactionPayload};
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::{StructTag, TypeTag};
+use starcoin_vm_types::parser::parse_type_tag;
+use std::convert::TryInto;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub type ChainSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The complexity of a list field is the complexity of its item multiplied by the list length.
+/// The lists without a length argument, such as the transactions of a block, are weighted by this
+/// estimated length, as their real length is unknown before the query is executed.
+const ESTIMATED_LIST_LEN: usize = 10;
+/// The max events returned by the events query if the limit is absent.
+const DEFAULT_EVENTS_LIMIT: usize = 100;
+
+/// The services the queries are resolved by, shared by all the queries of the schema.
+#[derive(Clone)]
+pub struct ChainData {
+    pub chain: ServiceRef<ChainReaderService>,
+    pub state: ServiceRef<ChainStateService>,
+    pub state_store: Arc<dyn StateNodeStore>,
+    pub block_query_max_range: u64,
+}
+
+impl ChainData {
+    /// The state of the latest block, to annotate the move values.
+    async fn latest_state(&self) -> Result<ChainStateDB> {
+        let state_root = self.state.clone().state_root().await?;
+        Ok(ChainStateDB::new(
+            self.state_store.clone(),
+            Some(state_root),
+        ))
+    }
+}
+
+pub fn build_schema(data: ChainData, max_depth: usize, max_complexity: usize) -> ChainSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(data)
+        .limit_depth(max_depth)
+        .limit_complexity(max_complexity)
+        .finish()
+}
+
+fn chain_data<'a>(ctx: &Context<'a>) -> &'a ChainData {
+    ctx.data_unchecked::<ChainData>()
+}
+
+/// The hashes, addresses and event keys are in the same hex literal form as the json rpc.
+fn hex_literal<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+fn parse_hash(hash: &str) -> Result<HashValue> {
+    Ok(HashValue::from_hex_literal(hash)?)
+}
+
+fn parse_address(address: &str) -> Result<AccountAddress> {
+    Ok(AccountAddress::from_str(address)?)
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The head block of the main chain.
+    async fn head(&self, ctx: &Context<'_>) -> Result<BlockObject> {
+        let block = chain_data(ctx).chain.clone().main_head_block().await?;
+        Ok(BlockObject(Arc::new(block)))
+    }
+
+    /// Get a block by hash, or a block of the main chain by number.
+    async fn block(
+        &self,
+        ctx: &Context<'_>,
+        hash: Option<String>,
+        number: Option<BlockNumber>,
+    ) -> Result<Option<BlockObject>> {
+        let chain = chain_data(ctx).chain.clone();
+        let block = match (hash, number) {
+            (Some(hash), None) => chain.get_block_by_hash(parse_hash(&hash)?).await?,
+            (None, Some(number)) => chain.main_block_by_number(number).await?,
+            _ => return Err("expect one of hash and number".into()),
+        };
+        Ok(block.map(|block| BlockObject(Arc::new(block))))
+    }
+
+    /// Get `count` blocks of the main chain in reverse order, from the block `number`, or from the head block if the number is absent.
+    #[graphql(complexity = "count as usize * child_complexity")]
+    async fn blocks(
+        &self,
+        ctx: &Context<'_>,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> Result<Vec<BlockObject>> {
+        let data = chain_data(ctx);
+        if count > data.block_query_max_range {
+            return Err(format!(
+                "count is too large, max block range is {}",
+                data.block_query_max_range
+            )
+            .into());
+        }
+        let blocks = data
+            .chain
+            .clone()
+            .main_blocks_by_number(number, count)
+            .await?;
+        Ok(blocks
+            .into_iter()
+            .map(|block| BlockObject(Arc::new(block)))
+            .collect())
+    }
+
+    async fn transaction(
+        &self,
+        ctx: &Context<'_>,
+        hash: String,
+    ) -> Result<Option<TransactionObject>> {
+        let hash = parse_hash(&hash)?;
+        TransactionObject::load(chain_data(ctx), hash).await
+    }
+
+    /// Get the events of the main chain, the block range is bounded by the max block range of the event query.
+    /// At most 100 events are returned if the `limit` is absent.
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(complexity = "limit.unwrap_or(DEFAULT_EVENTS_LIMIT) * child_complexity")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        from_block: Option<BlockNumber>,
+        to_block: Option<BlockNumber>,
+        #[graphql(default)] event_keys: Vec<String>,
+        #[graphql(default)] addresses: Vec<String>,
+        #[graphql(default)] type_tags: Vec<String>,
+        limit: Option<usize>,
+    ) -> Result<Vec<EventObject>> {
+        let data = chain_data(ctx);
+        let to_block = match to_block {
+            Some(to_block) => to_block,
+            None => data.chain.clone().main_head_header().await?.number(),
+        };
+        let filter = EventFilter {
+            from_block,
+            to_block: Some(to_block),
+            event_keys: event_keys
+                .iter()
+                .map(|key| EventKey::from_str(key))
+                .collect::<anyhow::Result<_>>()?,
+            addresses: addresses
+                .iter()
+                .map(|address| parse_address(address))
+                .collect::<Result<_>>()?,
+            type_tags: type_tags
+                .iter()
+                .map(|type_tag| parse_type_tag(type_tag).map(StrView))
+                .collect::<anyhow::Result<_>>()?,
+            senders: vec![],
+            any_of: vec![],
+            limit: Some(limit.unwrap_or(DEFAULT_EVENTS_LIMIT)),
+        };
+        let filter: Filter = filter.try_into()?;
+        if filter
+            .to_block
+            .checked_sub(filter.from_block)
+            .filter(|range| *range > data.block_query_max_range)
+            .is_some()
+        {
+            return Err(format!(
+                "from_block is too far, max block range is {}",
+                data.block_query_max_range
+            )
+            .into());
+        }
+        let events = data.chain.clone().main_events(filter).await?;
+        Ok(events.into_iter().map(EventObject).collect())
+    }
+
+    /// The on chain state of an account at the head block.
+    async fn account(&self, address: String) -> Result<AccountObject> {
+        Ok(AccountObject(parse_address(&address)?))
+    }
+}
+
+pub struct BlockObject(Arc<Block>);
+
+#[Object(name = "Block")]
+impl BlockObject {
+    async fn hash(&self) -> String {
+        hex_literal(&self.0.id())
+    }
+
+    async fn header(&self) -> BlockHeaderObject {
+        BlockHeaderObject(self.0.header().clone())
+    }
+
+    /// The user transactions of the block, the block metadata transaction is not included.
+    #[graphql(complexity = "ESTIMATED_LIST_LEN * child_complexity")]
+    async fn transactions(&self) -> Vec<TransactionObject> {
+        self.0
+            .transactions()
+            .iter()
+            .map(|txn| TransactionObject {
+                txn: Transaction::UserTransaction(txn.clone()),
+                block: self.0.clone(),
+            })
+            .collect()
+    }
+
+    async fn transaction_count(&self) -> usize {
+        self.0.transactions().len()
+    }
+
+    #[graphql(complexity = "ESTIMATED_LIST_LEN * child_complexity")]
+    async fn uncles(&self) -> Vec<BlockHeaderObject> {
+        self.0
+            .uncles()
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .map(BlockHeaderObject)
+            .collect()
+    }
+}
+
+pub struct BlockHeaderObject(BlockHeader);
+
+#[Object(name = "BlockHeader")]
+impl BlockHeaderObject {
+    async fn hash(&self) -> String {
+        hex_literal(&self.0.id())
+    }
+
+    async fn parent_hash(&self) -> String {
+        hex_literal(&self.0.parent_hash())
+    }
+
+    async fn number(&self) -> BlockNumber {
+        self.0.number()
+    }
+
+    /// Block timestamp in milliseconds.
+    async fn timestamp(&self) -> u64 {
+        self.0.timestamp()
+    }
+
+    async fn author(&self) -> String {
+        hex_literal(&self.0.author())
+    }
+
+    async fn state_root(&self) -> String {
+        hex_literal(&self.0.state_root())
+    }
+
+    async fn txn_accumulator_root(&self) -> String {
+        hex_literal(&self.0.txn_accumulator_root())
+    }
+
+    async fn block_accumulator_root(&self) -> String {
+        hex_literal(&self.0.block_accumulator_root())
+    }
+
+    async fn body_hash(&self) -> String {
+        hex_literal(&self.0.body_hash())
+    }
+
+    async fn gas_used(&self) -> u64 {
+        self.0.gas_used()
+    }
+
+    /// The difficulty in decimal, as it may overflow the int.
+    async fn difficulty(&self) -> String {
+        self.0.difficulty().to_string()
+    }
+
+    async fn nonce(&self) -> u32 {
+        self.0.nonce()
+    }
+
+    async fn chain_id(&self) -> u8 {
+        self.0.chain_id().id()
+    }
+}
+
+pub struct TransactionObject {
+    txn: Transaction,
+    block: Arc<Block>,
+}
+
+impl TransactionObject {
+    async fn load(data: &ChainData, hash: HashValue) -> Result<Option<Self>> {
+        let chain = data.chain.clone();
+        let txn = match chain.get_transaction(hash).await? {
+            Some(txn) => txn,
+            None => return Ok(None),
+        };
+        let block = chain
+            .get_transaction_block(hash)
+            .await?
+            .ok_or_else(|| format!("cannot find the block of txn {}", hash))?;
+        Ok(Some(Self {
+            txn,
+            block: Arc::new(block),
+        }))
+    }
+
+    fn payload(&self) -> Option<&TransactionPayload> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(txn.payload()),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+}
+
+#[Object(name = "Transaction")]
+impl TransactionObject {
+    async fn hash(&self) -> String {
+        hex_literal(&self.txn.id())
+    }
+
+    /// The index of the txn in the block, the block metadata txn is 0.
+    async fn index(&self) -> u32 {
+        let hash = self.txn.id();
+        self.block
+            .transactions()
+            .iter()
+            .position(|txn| txn.id() == hash)
+            .map(|index| index + 1)
+            .unwrap_or_default() as u32
+    }
+
+    async fn block(&self) -> BlockObject {
+        BlockObject(self.block.clone())
+    }
+
+    async fn is_block_metadata(&self) -> bool {
+        matches!(self.txn, Transaction::BlockMetadata(_))
+    }
+
+    async fn sender(&self) -> Option<String> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(hex_literal(&txn.sender())),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+
+    async fn sequence_number(&self) -> Option<u64> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(txn.sequence_number()),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+
+    async fn max_gas_amount(&self) -> Option<u64> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(txn.max_gas_amount()),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+
+    async fn gas_unit_price(&self) -> Option<u64> {
+        match &self.txn {
+            Transaction::UserTransaction(txn) => Some(txn.gas_unit_price()),
+            Transaction::BlockMetadata(_) => None,
+        }
+    }
+
+    /// The script function called by the txn, such as 0x1::TransferScripts::peer_to_peer.
+    async fn function(&self) -> Option<String> {
+        match self.payload() {
+            Some(TransactionPayload::ScriptFunction(function)) => Some(format!(
+                "{}::{}::{}",
+                hex_literal(function.module().address()),
+                function.module().name(),
+                function.function()
+            )),
+            _ => None,
+        }
+    }
+
+    /// The hex of the bcs encoded payload.
+    async fn payload(&self) -> Result<Option<String>> {
+        Ok(match self.payload() {
+            Some(payload) => Some(hex_literal(&StrView(bcs_ext::to_bytes(payload)?))),
+            None => None,
+        })
+    }
+
+    async fn info(&self, ctx: &Context<'_>) -> Result<Option<TransactionInfoObject>> {
+        let info = chain_data(ctx)
+            .chain
+            .clone()
+            .get_transaction_info(self.txn.id())
+            .await?;
+        Ok(info.map(|info| TransactionInfoObject {
+            gas_used: info.gas_used(),
+            state_root_hash: info.state_root_hash(),
+            event_root_hash: info.event_root_hash(),
+            status: info.status().clone().into(),
+        }))
+    }
+
+    #[graphql(complexity = "ESTIMATED_LIST_LEN * child_complexity")]
+    async fn events(&self, ctx: &Context<'_>) -> Result<Vec<EventObject>> {
+        let events = chain_data(ctx)
+            .chain
+            .clone()
+            .get_events_by_txn_hash(self.txn.id())
+            .await?;
+        Ok(events.into_iter().map(EventObject).collect())
+    }
+}
+
+pub struct TransactionInfoObject {
+    gas_used: u64,
+    state_root_hash: HashValue,
+    event_root_hash: HashValue,
+    status: TransactionVMStatus,
+}
+
+#[Object(name = "TransactionInfo")]
+impl TransactionInfoObject {
+    async fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    async fn state_root_hash(&self) -> String {
+        hex_literal(&self.state_root_hash)
+    }
+
+    async fn event_root_hash(&self) -> String {
+        hex_literal(&self.event_root_hash)
+    }
+
+    async fn status(&self) -> Json<TransactionVMStatus> {
+        Json(self.status.clone())
+    }
+
+    async fn executed(&self) -> bool {
+        self.status == TransactionVMStatus::Executed
+    }
+}
+
+pub struct EventObject(ContractEventInfo);
+
+#[Object(name = "Event")]
+impl EventObject {
+    async fn block_hash(&self) -> String {
+        hex_literal(&self.0.block_hash)
+    }
+
+    async fn block_number(&self) -> BlockNumber {
+        self.0.block_number
+    }
+
+    async fn transaction_hash(&self) -> String {
+        hex_literal(&self.0.transaction_hash)
+    }
+
+    async fn transaction_index(&self) -> u32 {
+        self.0.transaction_index
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<Option<TransactionObject>> {
+        TransactionObject::load(chain_data(ctx), self.0.transaction_hash).await
+    }
+
+    async fn event_key(&self) -> String {
+        hex_literal(self.0.event.key())
+    }
+
+    async fn sequence_number(&self) -> u64 {
+        self.0.event.sequence_number()
+    }
+
+    async fn type_tag(&self) -> String {
+        self.0.event.type_tag().to_string()
+    }
+
+    /// The hex of the bcs encoded event data.
+    async fn data(&self) -> String {
+        hex_literal(&StrView(self.0.event.event_data().to_vec()))
+    }
+
+    /// The event data decoded by the modules at the head block, null if it can not be decoded.
+    async fn decoded_data(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Json<AnnotatedMoveValueView>>> {
+        let statedb = chain_data(ctx).latest_state().await?;
+        let annotator = MoveValueAnnotator::new(&statedb);
+        Ok(annotator
+            .view_contract_event(&self.0.event)
+            .ok()
+            .map(|value| Json(value.into())))
+    }
+}
+
+pub struct AccountObject(AccountAddress);
+
+#[Object(name = "Account")]
+impl AccountObject {
+    async fn address(&self) -> String {
+        hex_literal(&self.0)
+    }
+
+    /// The resources of the account, filtered by `typeTag` if it is present, such as 0x1::Account::Balance<0x1::STC::STC>.
+    #[graphql(complexity = "ESTIMATED_LIST_LEN * child_complexity")]
+    async fn resources(
+        &self,
+        ctx: &Context<'_>,
+        type_tag: Option<String>,
+    ) -> Result<Vec<ResourceObject>> {
+        let filter = match type_tag {
+            Some(type_tag) => match parse_type_tag(type_tag.as_str())? {
+                TypeTag::Struct(struct_tag) => Some(struct_tag),
+                _ => return Err("type tag of resource should be a struct".into()),
+            },
+            None => None,
+        };
+        let data = chain_data(ctx);
+        let state = match data
+            .state
+            .clone()
+            .get_account_state_set(self.0, None)
+            .await?
+        {
+            Some(state) => state,
+            None => return Ok(vec![]),
+        };
+        let statedb = data.latest_state().await?;
+        let annotator = MoveValueAnnotator::new(&statedb);
+        let mut resources = vec![];
+        for (k, v) in state.resource_set().cloned().unwrap_or_default().iter() {
+            let struct_tag = StructTag::decode(k.as_slice())?;
+            if matches!(&filter, Some(filter) if filter != &struct_tag) {
+                continue;
+            }
+            let value = annotator.view_struct(struct_tag.clone(), v.as_slice())?;
+            resources.push(ResourceObject {
+                type_tag: struct_tag,
+                value: value.into(),
+            });
+        }
+        Ok(resources)
+    }
+
+    /// The names of the modules published under the account.
+    async fn modules(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let state = chain_data(ctx)
+            .state
+            .clone()
+            .get_account_state_set(self.0, None)
+            .await?;
+        Ok(state
+            .and_then(|state| state.code_set().cloned())
+            .unwrap_or_default()
+            .iter()
+            .map(|(k, _)| Identifier::decode(k.as_slice()).map(|name| name.to_string()))
+            .collect::<anyhow::Result<_>>()?)
+    }
+}
+
+pub struct ResourceObject {
+    type_tag: StructTag,
+    value: AnnotatedMoveStructView,
+}
+
+#[Object(name = "Resource")]
+impl ResourceObject {
+    async fn type_tag(&self) -> String {
+        self.type_tag.to_string()
+    }
+
+    async fn value(&self) -> Json<AnnotatedMoveStructView> {
+        Json(self.value.clone())
+    }
+}
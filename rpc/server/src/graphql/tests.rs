@@ -0,0 +1,146 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api_key_middleware::ApiKeyMiddleware;
+use crate::graphql::{add_graphql_method, build_schema, dispatch, ChainData, ChainSchema};
+use anyhow::Result;
+use jsonrpc_core::MetaIoHandler;
+use jsonrpc_http_server::hyper::StatusCode;
+use serde_json::{json, Value};
+use starcoin_chain_service::ChainReaderService;
+use starcoin_config::{ApiKeyConfig, ApiKeyConfiguration, ApiQuotaConfiguration, NodeConfig};
+use starcoin_rpc_api::errors::RpcErrorCode;
+use starcoin_rpc_api::metadata::Metadata;
+use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+use starcoin_state_service::ChainStateService;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+async fn test_schema() -> Result<ChainSchema> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let (storage, _, _) = test_helper::Genesis::init_storage_for_test(config.net())?;
+    let registry = RegistryService::launch();
+    registry.put_shared(config.clone()).await?;
+    registry.put_shared(storage.clone()).await?;
+    let data = ChainData {
+        chain: registry.register::<ChainReaderService>().await?,
+        state: registry.register::<ChainStateService>().await?,
+        state_store: storage,
+        block_query_max_range: config.rpc.block_query_max_range(),
+    };
+    Ok(build_schema(data, 8, 1000))
+}
+
+async fn query(schema: &ChainSchema, query: &str) -> (Value, bool) {
+    let response = schema.execute(query).await;
+    let has_errors = !response.errors.is_empty();
+    (
+        serde_json::to_value(&response.data).expect("graphql data should be json"),
+        has_errors,
+    )
+}
+
+#[stest::test]
+async fn test_graphql_query_chain_data() -> Result<()> {
+    let schema = test_schema().await?;
+
+    let (data, has_errors) = query(
+        &schema,
+        "{ head { hash header { number parentHash } } block(number: 0) { hash transactionCount } }",
+    )
+    .await;
+    assert!(!has_errors, "{}", data);
+    assert_eq!(data["head"]["header"]["number"], json!(0));
+    assert_eq!(data["head"]["hash"], data["block"]["hash"]);
+    // only the selected fields are returned.
+    assert!(data["head"]["header"].get("author").is_none());
+
+    let (data, has_errors) = query(
+        &schema,
+        "{ account(address: \"0x00000000000000000000000000000001\") { modules } }",
+    )
+    .await;
+    assert!(!has_errors, "{}", data);
+    assert!(data["account"]["modules"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("Account")));
+    Ok(())
+}
+
+#[stest::test]
+async fn test_graphql_query_limits() -> Result<()> {
+    let schema = test_schema().await?;
+
+    let (_, has_errors) = query(&schema, "{ blocks(count: 1000) { hash } }").await;
+    assert!(has_errors);
+
+    let (_, has_errors) = query(
+        &schema,
+        "{ head { transactions { block { transactions { block { transactions { block { hash } } } } } } } }",
+    )
+    .await;
+    assert!(has_errors);
+    Ok(())
+}
+
+#[stest::test]
+async fn test_graphql_list_complexity() -> Result<()> {
+    let schema = test_schema().await?;
+
+    let (data, has_errors) = query(&schema, "{ blocks(count: 1) { transactions { hash } } }").await;
+    assert!(!has_errors, "{}", data);
+
+    // in the block range, but the nested lists are weighted by their length.
+    let (_, has_errors) = query(
+        &schema,
+        "{ blocks(count: 20) { transactions { events { typeTag } } } }",
+    )
+    .await;
+    assert!(has_errors);
+
+    let (_, has_errors) = query(&schema, "{ events(limit: 2000) { typeTag } }").await;
+    assert!(has_errors);
+    Ok(())
+}
+
+#[stest::test]
+async fn test_graphql_dispatch_by_json_rpc() -> Result<()> {
+    let schema = test_schema().await?;
+    let mut keys = HashMap::new();
+    keys.insert(
+        "explorer".to_string(),
+        ApiKeyConfig {
+            key: "explorer_key".to_string(),
+            quota: None,
+            methods: Some(vec!["graphql.*".to_string()]),
+        },
+    );
+    let config = ApiKeyConfiguration {
+        require_api_key: true,
+        keys: Some(keys),
+        ..Default::default()
+    };
+    let mut io_handler = MetaIoHandler::with_middleware(ApiKeyMiddleware::from_config(
+        &config,
+        &ApiQuotaConfiguration::default(),
+    ));
+    add_graphql_method(&mut io_handler, schema);
+    let body = json!({ "query": "{ head { header { number } } }" }).to_string();
+
+    let (status, resp) = dispatch(&io_handler, body.as_bytes(), Metadata::default()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(resp["code"], json!(i64::from(RpcErrorCode::InvalidApiKey)));
+
+    let meta = Metadata {
+        api_key: Some("explorer_key".to_string()),
+        ..Default::default()
+    };
+    let (status, resp) = dispatch(&io_handler, body.as_bytes(), meta.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["data"]["head"]["header"]["number"], json!(0));
+
+    let (status, _) = dispatch(&io_handler, b"not json", meta).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run the heavy apis, such as dry-run and range queries, on a bounded thread pool with
+//! per method concurrency limits, so an expensive call class can not starve the cheap calls.
+
+use futures::channel::oneshot;
+use futures::executor::ThreadPool;
+use jsonrpc_core::futures::future::Either;
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{Call, Error, Failure, FutureResponse, Id, Middleware, Output};
+use starcoin_config::RpcConfig;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::metadata::Metadata;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+struct HeavyApiPool {
+    pool: ThreadPool,
+    limits: HashMap<String, Arc<Semaphore>>,
+}
+
+#[derive(Clone, Default)]
+pub struct HeavyApiMiddleware {
+    inner: Option<Arc<HeavyApiPool>>,
+}
+
+impl HeavyApiMiddleware {
+    /// Run all apis on the async path if the heavy api thread pool can not be created.
+    pub fn from_config(config: &RpcConfig) -> Self {
+        let threads = config.heavy_api_threads();
+        let pool = match ThreadPool::builder()
+            .pool_size(threads)
+            .name_prefix("rpc-heavy-api-")
+            .create()
+        {
+            Ok(pool) => pool,
+            Err(e) => {
+                error!("Create rpc heavy api thread pool error: {:?}", e);
+                return Self::default();
+            }
+        };
+        let limits = config
+            .heavy_api_concurrency()
+            .into_iter()
+            .filter(|(_, limit)| *limit > 0)
+            .map(|(method, limit)| (method, Arc::new(Semaphore::new(limit))))
+            .collect::<HashMap<_, _>>();
+        info!("Run {} rpc heavy apis on {} threads", limits.len(), threads);
+        Self {
+            inner: Some(Arc::new(HeavyApiPool { pool, limits })),
+        }
+    }
+}
+
+impl Middleware<Metadata> for HeavyApiMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: Metadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Metadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return Either::Right(next(call, meta)),
+        };
+        let (method, jsonrpc, id) = match &call {
+            Call::MethodCall(m) => (m.method.as_str(), m.jsonrpc, Some(m.id.clone())),
+            Call::Notification(n) => (n.method.as_str(), n.jsonrpc, None),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let semaphore = match inner.limits.get(method) {
+            Some(semaphore) => semaphore.clone(),
+            None => return Either::Right(next(call, meta)),
+        };
+        let method = method.to_string();
+        let inner = inner.clone();
+        let fut = next(call, meta);
+        Either::Left(Box::pin(async move {
+            // the calls over the limit wait here, and do not occupy the pool threads.
+            let _permit = semaphore.acquire().await;
+            let (tx, rx) = oneshot::channel();
+            inner.pool.spawn_ok(async move {
+                let _ = tx.send(fut.await);
+            });
+            match rx.await {
+                Ok(output) => output,
+                Err(_) => {
+                    error!("Rpc heavy api call {} is canceled", method);
+                    id.map(|id: Id| {
+                        Output::Failure(Failure {
+                            jsonrpc,
+                            error: Error::internal_error(),
+                            id,
+                        })
+                    })
+                }
+            }
+        }))
+    }
+}
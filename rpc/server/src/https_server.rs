@@ -0,0 +1,460 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serve the http json rpc over tls, so the node can be exposed to the browser dApps without a
+//! reverse proxy. The http server of jsonrpc can not terminate tls, so the requests are handled
+//! like the compression middleware, by the same io handler, cors and payload limits.
+
+use crate::compression_middleware::{accepted_encoding, handle_http_request, with_allow_origin};
+use crate::cors_middleware::allow_origin;
+use crate::extractors::RpcExtractor;
+use crate::service::HEALTH_API;
+use anyhow::{format_err, Result};
+use futures::channel::oneshot;
+use futures::{StreamExt, TryFutureExt};
+use jsonrpc_core::{
+    Call, Id, MetaIoHandler, MethodCall, Middleware, Output, Params, Request, Response, Version,
+};
+use jsonrpc_http_server::hyper::server::accept;
+use jsonrpc_http_server::hyper::service::{make_service_fn, service_fn};
+use jsonrpc_http_server::hyper::{self, header, Body, Method, Server, StatusCode};
+use jsonrpc_http_server::MetaExtractor;
+use starcoin_config::HttpTlsConfig;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::metadata::Metadata;
+use starcoin_rpc_middleware::TransportRecorder;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::rustls::internal::pemfile;
+use tokio_rustls::rustls::{
+    AllowAnyAuthenticatedClient, NoClientAuth, RootCertStore, ServerConfig,
+};
+use tokio_rustls::TlsAcceptor;
+
+/// The tls handshakes in progress, a slow client does not block the others.
+const MAX_CONCURRENT_HANDSHAKES: usize = 128;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(path).map_err(|e| {
+        format_err!("Open tls file {:?} failed: {}", path, e)
+    })?))
+}
+
+pub fn load_tls_config(tls: &HttpTlsConfig) -> Result<ServerConfig> {
+    let certs = pemfile::certs(&mut open(&tls.cert)?)
+        .map_err(|_| format_err!("Invalid tls cert file {:?}", tls.cert))?;
+    let mut keys = pemfile::pkcs8_private_keys(&mut open(&tls.key)?)
+        .map_err(|_| format_err!("Invalid tls key file {:?}", tls.key))?;
+    if keys.is_empty() {
+        keys = pemfile::rsa_private_keys(&mut open(&tls.key)?)
+            .map_err(|_| format_err!("Invalid tls key file {:?}", tls.key))?;
+    }
+    let key = keys
+        .into_iter()
+        .next()
+        .ok_or_else(|| format_err!("No private key in tls key file {:?}", tls.key))?;
+    let mut config = match tls.client_ca.as_ref() {
+        Some(client_ca) => {
+            let mut roots = RootCertStore::empty();
+            let (valid, _) = roots
+                .add_pem_file(&mut open(client_ca)?)
+                .map_err(|_| format_err!("Invalid tls client ca file {:?}", client_ca))?;
+            if valid == 0 {
+                return Err(format_err!(
+                    "No ca certificate in tls client ca file {:?}",
+                    client_ca
+                ));
+            }
+            ServerConfig::new(AllowAnyAuthenticatedClient::new(roots))
+        }
+        None => ServerConfig::new(NoClientAuth::new()),
+    };
+    config.set_single_cert(certs, key)?;
+    Ok(config)
+}
+
+/// Call the health `method`, the same as the health api of the http server, the response is
+/// the result of the method, or 503 with the error message if the method fails.
+async fn health_response<M>(
+    io_handler: &MetaIoHandler<Metadata, M>,
+    method: &str,
+) -> hyper::Response<Body>
+where
+    M: Middleware<Metadata>,
+{
+    let call = Call::MethodCall(MethodCall {
+        jsonrpc: Some(Version::V2),
+        method: method.to_string(),
+        params: Params::None,
+        id: Id::Num(1),
+    });
+    let (status, body) = match io_handler
+        .handle_rpc_request(Request::Single(call), Metadata::default())
+        .await
+    {
+        Some(Response::Single(Output::Success(success))) => (
+            StatusCode::OK,
+            serde_json::to_string(&success.result).unwrap_or_default(),
+        ),
+        Some(Response::Single(Output::Failure(failure))) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            serde_json::to_string(&failure.error.message).unwrap_or_default(),
+        ),
+        _ => (StatusCode::SERVICE_UNAVAILABLE, String::new()),
+    };
+    let mut resp = hyper::Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    resp
+}
+
+fn empty_response(status: StatusCode) -> hyper::Response<Body> {
+    let mut resp = hyper::Response::new(Body::empty());
+    *resp.status_mut() = status;
+    resp
+}
+
+async fn serve_https<M>(
+    io_handler: Arc<MetaIoHandler<Metadata, M>>,
+    extractor: Arc<RpcExtractor>,
+    max_request_body_size: usize,
+    cors_origins: Arc<Option<Vec<String>>>,
+    recorder: TransportRecorder,
+    req: hyper::Request<Body>,
+) -> hyper::Result<hyper::Response<Body>>
+where
+    M: Middleware<Metadata>,
+{
+    let origin = req
+        .headers()
+        .get(header::ORIGIN)
+        .and_then(|v| v.to_str().ok());
+    let allow_origin = match allow_origin((*cors_origins).as_ref(), origin) {
+        Ok(allow_origin) => allow_origin,
+        Err(e) => {
+            debug!("Reject https rpc request: {}", e);
+            return Ok(empty_response(StatusCode::FORBIDDEN));
+        }
+    };
+    if req.method() == Method::OPTIONS {
+        let mut resp = empty_response(StatusCode::OK);
+        let headers = resp.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            header::HeaderValue::from_static("OPTIONS, POST"),
+        );
+        if let Some(request_headers) = req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                request_headers.clone(),
+            );
+        }
+        return Ok(with_allow_origin(resp, allow_origin));
+    }
+    if req.method() == Method::GET && req.uri().path() == HEALTH_API.0 {
+        return Ok(with_allow_origin(
+            health_response(io_handler.as_ref(), HEALTH_API.1).await,
+            allow_origin,
+        ));
+    }
+    if req.method() != Method::POST {
+        return Ok(empty_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+    let encoding = req
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .and_then(accepted_encoding);
+    let meta = extractor.read_metadata(&req);
+    handle_http_request(
+        io_handler,
+        meta,
+        max_request_body_size,
+        encoding,
+        recorder,
+        req,
+    )
+    .await
+    .map(|resp| with_allow_origin(resp, allow_origin))
+}
+
+pub struct HttpsServer {
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl HttpsServer {
+    /// Start the https json rpc server on its own thread and runtime.
+    pub fn start<M>(
+        address: SocketAddr,
+        io_handler: MetaIoHandler<Metadata, M>,
+        extractor: RpcExtractor,
+        max_request_body_size: usize,
+        cors_origins: Option<Vec<String>>,
+        recorder: TransportRecorder,
+        tls: &HttpTlsConfig,
+    ) -> Result<Self>
+    where
+        M: Middleware<Metadata> + Send + Sync + 'static,
+    {
+        let acceptor = TlsAcceptor::from(Arc::new(load_tls_config(tls)?));
+        // bind before the server thread, so the failure of the address is returned to the caller.
+        let std_listener = std::net::TcpListener::bind(&address)
+            .map_err(|e| format_err!("Bind https server at {} failed: {}", address, e))?;
+        std_listener.set_nonblocking(true)?;
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let io_handler = Arc::new(io_handler);
+        let extractor = Arc::new(extractor);
+        let cors_origins = Arc::new(cors_origins);
+        std::thread::Builder::new()
+            .name("https-server".to_string())
+            .spawn(move || {
+                let mut rt = match tokio::runtime::Builder::new()
+                    .basic_scheduler()
+                    .enable_io()
+                    .enable_time()
+                    .build()
+                {
+                    Ok(rt) => rt,
+                    Err(e) => {
+                        error!("Build https server runtime failed: {:?}", e);
+                        return;
+                    }
+                };
+                let result = rt.block_on(async move {
+                    let mut listener = tokio::net::TcpListener::from_std(std_listener)?;
+                    let incoming = listener
+                        .incoming()
+                        .filter_map(|stream| async move {
+                            stream
+                                .map_err(|e| debug!("Accept https connection failed: {:?}", e))
+                                .ok()
+                        })
+                        .map(move |stream| {
+                            tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                        })
+                        .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+                        .filter_map(|stream| async move {
+                            match stream {
+                                Ok(Ok(stream)) => Some(Ok::<_, std::io::Error>(stream)),
+                                Ok(Err(e)) => {
+                                    debug!("Https tls handshake failed: {:?}", e);
+                                    None
+                                }
+                                Err(_) => {
+                                    debug!("Https tls handshake timeout");
+                                    None
+                                }
+                            }
+                        });
+                    let make_service = make_service_fn(move |_| {
+                        let io_handler = io_handler.clone();
+                        let extractor = extractor.clone();
+                        let cors_origins = cors_origins.clone();
+                        async move {
+                            Ok::<_, hyper::Error>(service_fn(move |req| {
+                                serve_https(
+                                    io_handler.clone(),
+                                    extractor.clone(),
+                                    max_request_body_size,
+                                    cors_origins.clone(),
+                                    recorder,
+                                    req,
+                                )
+                            }))
+                        }
+                    });
+                    Server::builder(accept::from_stream(incoming))
+                        .serve(make_service)
+                        .with_graceful_shutdown(async {
+                            shutdown_receiver.await.ok();
+                        })
+                        .map_err(anyhow::Error::from)
+                        .await
+                });
+                if let Err(e) = result {
+                    error!("Https server at {} failed: {:?}", address, e);
+                }
+            })?;
+        Ok(Self {
+            shutdown: Some(shutdown_sender),
+        })
+    }
+
+    pub fn close(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{Params, Value};
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::rustls::ClientConfig;
+    use tokio_rustls::webpki::DNSNameRef;
+    use tokio_rustls::TlsConnector;
+
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/tls")
+            .join(name)
+    }
+
+    fn start_server(client_ca: Option<PathBuf>) -> (HttpsServer, SocketAddr) {
+        let mut io = MetaIoHandler::<Metadata>::default();
+        io.add_sync_method("status", |_: Params| Ok(Value::Bool(true)));
+        io.add_sync_method("echo", |params: Params| {
+            let params: Vec<Value> = params.parse()?;
+            Ok(params.into_iter().next().unwrap_or(Value::Null))
+        });
+        let tls = HttpTlsConfig {
+            cert: testdata("server.pem"),
+            key: testdata("server.key"),
+            client_ca,
+        };
+        let address: SocketAddr =
+            format!("127.0.0.1:{}", starcoin_config::get_random_available_port())
+                .parse()
+                .unwrap();
+        let server = HttpsServer::start(
+            address,
+            io,
+            RpcExtractor {
+                http_ip_headers: vec![],
+                http_api_key_header: None,
+            },
+            1024 * 1024,
+            None,
+            TransportRecorder::new("https", None),
+            &tls,
+        )
+        .unwrap();
+        (server, address)
+    }
+
+    fn client_config(client_cert: bool) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config
+            .root_store
+            .add_pem_file(&mut open(&testdata("ca.pem")).unwrap())
+            .unwrap();
+        if client_cert {
+            let certs = pemfile::certs(&mut open(&testdata("client.pem")).unwrap()).unwrap();
+            let key = pemfile::pkcs8_private_keys(&mut open(&testdata("client.key")).unwrap())
+                .unwrap()
+                .remove(0);
+            config.set_single_client_cert(certs, key).unwrap();
+        }
+        config
+    }
+
+    /// Send the raw http request over tls, and return the response.
+    async fn request(address: SocketAddr, config: ClientConfig, req: String) -> Result<String> {
+        let stream = tokio::net::TcpStream::connect(&address).await?;
+        let connector = TlsConnector::from(Arc::new(config));
+        let domain = DNSNameRef::try_from_ascii_str("localhost").unwrap();
+        let mut stream = connector.connect(domain, stream).await?;
+        stream.write_all(req.as_bytes()).await?;
+        let mut resp = vec![];
+        stream.read_to_end(&mut resp).await?;
+        Ok(String::from_utf8(resp)?)
+    }
+
+    fn post(body: &str) -> String {
+        format!(
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    fn get(path: &str) -> String {
+        format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        )
+    }
+
+    #[stest::test]
+    async fn test_https_call_and_status() {
+        let (server, address) = start_server(None);
+        let resp = request(
+            address,
+            client_config(false),
+            post(r#"{"jsonrpc":"2.0","method":"echo","params":["hello"],"id":1}"#),
+        )
+        .await
+        .unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200"), "{}", resp);
+        assert!(resp.contains(r#""result":"hello""#), "{}", resp);
+
+        let resp = request(address, client_config(false), get(HEALTH_API.0))
+            .await
+            .unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200"), "{}", resp);
+        assert!(resp.ends_with("true"), "{}", resp);
+
+        let resp = request(address, client_config(false), get("/other"))
+            .await
+            .unwrap();
+        assert!(resp.starts_with("HTTP/1.1 405"), "{}", resp);
+        server.close();
+    }
+
+    #[stest::test]
+    async fn test_https_status_unavailable() {
+        let io = MetaIoHandler::<Metadata>::default();
+        let resp = health_response(&io, HEALTH_API.1).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[stest::test]
+    async fn test_https_client_auth() {
+        let (server, address) = start_server(Some(testdata("ca.pem")));
+        let body = r#"{"jsonrpc":"2.0","method":"echo","params":[1],"id":1}"#;
+        // the server rejects the handshake without a client certificate, with tls 1.3 the
+        // client may only see it when reading the response.
+        let result = request(address, client_config(false), post(body)).await;
+        assert!(!matches!(result, Ok(resp) if !resp.is_empty()));
+        let resp = request(address, client_config(true), post(body))
+            .await
+            .unwrap();
+        assert!(resp.contains(r#""result":1"#), "{}", resp);
+        server.close();
+    }
+
+    #[stest::test]
+    async fn test_https_bind_failed() {
+        let (server, address) = start_server(None);
+        let tls = HttpTlsConfig {
+            cert: testdata("server.pem"),
+            key: testdata("server.key"),
+            client_ca: None,
+        };
+        let result = HttpsServer::start(
+            address,
+            MetaIoHandler::<Metadata>::default(),
+            RpcExtractor {
+                http_ip_headers: vec![],
+                http_api_key_header: None,
+            },
+            1024,
+            None,
+            TransportRecorder::new("https", None),
+            &tls,
+        );
+        assert!(result.is_err());
+        server.close();
+    }
+}
@@ -1,8 +1,21 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+mod api_key_middleware;
 mod api_registry;
+mod archive_middleware;
+mod batch_middleware;
+mod body_limit;
+mod compression_middleware;
+mod cors_middleware;
 mod extractors;
+pub mod graphql;
+mod heavy_api_middleware;
+mod https_server;
 pub mod module;
 mod rate_limit_middleware;
+#[cfg(feature = "rest-api")]
+pub mod rest;
 pub mod service;
+
+pub use api_key_middleware::ApiKeyMiddleware;
@@ -3,19 +3,26 @@
 
 use crate::module::helpers::TransactionRequestFiller;
 use crate::module::map_err;
+use anyhow::ensure;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
+use starcoin_account_api::error::AccountError;
 use starcoin_account_api::{AccountAsyncService, AccountInfo};
 use starcoin_chain_service::ChainAsyncService;
 use starcoin_config::NodeConfig;
+use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+use starcoin_crypto::ValidCryptoMaterial;
 use starcoin_rpc_api::types::{StrView, TransactionRequest};
 use starcoin_rpc_api::{account::AccountApi, FutureResult};
 use starcoin_state_api::ChainStateAsyncService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::token_code::TokenCode;
-use starcoin_types::sign_message::SigningMessage;
+use starcoin_types::account_config::AccountResource;
+use starcoin_types::sign_message::{SigningMessage, TypedMessage};
+use starcoin_types::transaction::authenticator::AccountSignature;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::convert::TryFrom;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -65,6 +72,14 @@ where
             node_config: self.node_config.clone(),
         }
     }
+
+    /// Reject the apis which create, load private keys or sign, if the node is in public mode.
+    fn ensure_not_public_mode(&self, operation: &str) -> Result<(), AccountError> {
+        if self.node_config.vault.public_mode() {
+            return Err(AccountError::PublicModeForbidden(operation.to_string()));
+        }
+        Ok(())
+    }
 }
 
 impl<S, Pool, State, Chain> AccountApi for AccountRpcImpl<S, Pool, State, Chain>
@@ -96,7 +111,9 @@ where
 
     fn create(&self, password: String) -> FutureResult<AccountInfo> {
         let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("create account");
         let fut = async move {
+            guard?;
             let result = service.create_account(password).await?;
             Ok(result)
         }
@@ -129,16 +146,74 @@ where
         data: SigningMessage,
     ) -> FutureResult<StrView<Vec<u8>>> {
         let account_service = self.account.clone();
+        let guard = self.ensure_not_public_mode("sign message");
         let f = async move {
+            guard?;
             let signature = account_service.sign_message(address, data).await?;
             Ok(signature.into())
         };
         Box::pin(f.map_err(map_err).boxed())
     }
 
+    fn sign_typed_message(
+        &self,
+        address: AccountAddress,
+        message: TypedMessage,
+    ) -> FutureResult<StrView<Vec<u8>>> {
+        let account_service = self.account.clone();
+        let guard = self.ensure_not_public_mode("sign typed message");
+        let chain_id = self.node_config.net().chain_id();
+        let f = async move {
+            guard?;
+            ensure!(
+                message.domain.chain_id == chain_id.id(),
+                "Typed message domain chain id {} mismatch with the node's chain id {}",
+                message.domain.chain_id,
+                chain_id
+            );
+            let signing_message = message.to_signing_message()?;
+            let signature = account_service
+                .sign_typed_message(address, signing_message)
+                .await?;
+            Ok(signature.to_bytes().into())
+        };
+        Box::pin(f.map_err(map_err).boxed())
+    }
+
+    fn verify_typed_message(
+        &self,
+        message: TypedMessage,
+        signature: StrView<Vec<u8>>,
+        address: Option<AccountAddress>,
+    ) -> FutureResult<bool> {
+        let chain_state = self.chain_state.clone();
+        let f = async move {
+            let signature = AccountSignature::try_from(signature.0.as_slice())?;
+            if signature.verify(&message.to_signing_message()?).is_err() {
+                return Ok(false);
+            }
+            if let Some(address) = address {
+                let public_key = signature.public_key();
+                return Ok(
+                    match chain_state.get_resource::<AccountResource>(address).await? {
+                        Some(account) => {
+                            public_key.authentication_key().to_vec() == account.authentication_key()
+                        }
+                        // The account is not created on chain, the address derives from the key.
+                        None => public_key.derived_address() == address,
+                    },
+                );
+            }
+            Ok(true)
+        };
+        Box::pin(f.map_err(map_err).boxed())
+    }
+
     fn sign_txn_request(&self, txn_request: TransactionRequest) -> FutureResult<String> {
         let me = self.clone();
+        let guard = self.ensure_not_public_mode("sign txn");
         let fut = async move {
+            guard?;
             let raw_txn = me
                 .txn_request_filler()
                 .fill_transaction(txn_request)
@@ -160,7 +235,9 @@ where
         signer: AccountAddress,
     ) -> FutureResult<SignedUserTransaction> {
         let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("sign txn");
         let fut = async move {
+            guard?;
             let result = service.sign_txn(raw_txn, signer).await?;
             Ok(result)
         }
@@ -175,7 +252,9 @@ where
         duration: Option<u32>,
     ) -> FutureResult<()> {
         let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("unlock account");
         let fut = async move {
+            guard?;
             service
                 .unlock_account(
                     address,
@@ -202,7 +281,9 @@ where
         password: String,
     ) -> FutureResult<AccountInfo> {
         let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("import account");
         let fut = async move {
+            guard?;
             let result = service
                 .import_account(address, private_key, password)
                 .await?;
@@ -215,7 +296,9 @@ where
     /// Return the private key as bytes for `address`
     fn export(&self, address: AccountAddress, password: String) -> FutureResult<Vec<u8>> {
         let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("export account");
         let fut = async move {
+            guard?;
             let result = service.export_account(address, password).await?;
             Ok(result)
         }
@@ -223,13 +306,34 @@ where
         Box::pin(fut.boxed())
     }
 
+    fn rotate_multisig_key(
+        &self,
+        address: AccountAddress,
+        new_public_key: MultiEd25519PublicKey,
+        password: String,
+    ) -> FutureResult<AccountInfo> {
+        let service = self.account.clone();
+        let guard = self.ensure_not_public_mode("rotate multisig key");
+        let fut = async move {
+            guard?;
+            let result = service
+                .rotate_multisig_key(address, new_public_key, password)
+                .await?;
+            Ok(result)
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
     fn change_account_password(
         &self,
         address: AccountAddress,
         new_password: String,
     ) -> FutureResult<()> {
         let account_service = self.account.clone();
+        let guard = self.ensure_not_public_mode("change password");
         let fut = async move {
+            guard?;
             account_service
                 .change_account_password(address, new_password)
                 .await
@@ -9,8 +9,10 @@ use starcoin_crypto::HashValue;
 use starcoin_rpc_api::chain::ChainApi;
 use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::types::{
-    BlockHeaderView, BlockSummaryView, BlockView, ChainId, ChainInfoView, EpochUncleSummaryView,
-    TransactionEventView, TransactionInfoView, TransactionView,
+    BlockHeaderView, BlockSummaryView, BlockView, ChainId, ChainInfoView, CursoredEventView,
+    EpochInfoView, EpochUncleSummaryView, EventCursor, EventCursorView, EventPageView,
+    GetBlockOption, GetBlocksOption, GetTransactionOption, StrView, TransactionEventView,
+    TransactionInfoView, TransactionInfoWithProofView, TransactionView,
 };
 use starcoin_rpc_api::FutureResult;
 use starcoin_types::block::{BlockInfo, BlockNumber};
@@ -63,24 +65,36 @@ where
         Box::pin(fut.boxed().map_err(map_err))
     }
 
-    fn get_block_by_hash(&self, hash: HashValue) -> FutureResult<Option<BlockView>> {
+    fn get_block_by_hash(
+        &self,
+        hash: HashValue,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>> {
         let service = self.service.clone();
-
+        let option = option.unwrap_or_default();
         let fut = async move {
             let result = service.get_block_by_hash(hash).await?;
-            result.map(|b| b.try_into()).transpose()
+            result
+                .map(|b| BlockView::try_from_block_with_raw(b, false, option.raw))
+                .transpose()
         }
         .map_err(map_err);
 
         Box::pin(fut.boxed())
     }
 
-    fn get_block_by_number(&self, number: u64) -> FutureResult<Option<BlockView>> {
+    fn get_block_by_number(
+        &self,
+        number: u64,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>> {
         let service = self.service.clone();
-
+        let option = option.unwrap_or_default();
         let fut = async move {
             let result = service.main_block_by_number(number).await?;
-            result.map(|b| b.try_into()).transpose()
+            result
+                .map(|b| BlockView::try_from_block_with_raw(b, false, option.raw))
+                .transpose()
         }
         .map_err(map_err);
 
@@ -103,25 +117,45 @@ where
         &self,
         number: Option<BlockNumber>,
         count: u64,
+        option: Option<GetBlocksOption>,
     ) -> FutureResult<Vec<BlockView>> {
         let service = self.service.clone();
         let config = self.config.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
-            let end_block_number = match number {
-                Some(num) => num,
-                None => service.clone().main_head_header().await?.number(),
+            let blocks = if option.reverse {
+                let end_block_number = match number {
+                    Some(num) => num,
+                    None => service.clone().main_head_header().await?.number(),
+                };
+
+                let max_return_num = count
+                    .min(end_block_number + 1)
+                    .min(config.rpc.block_query_max_range());
+                service
+                    .main_blocks_by_number(number, max_return_num)
+                    .await?
+            } else {
+                let start_block_number = number.unwrap_or(0);
+                let max_return_num = count.min(config.rpc.block_query_max_range());
+                let block_ids = service
+                    .clone()
+                    .get_block_ids(start_block_number, false, max_return_num)
+                    .await?;
+                service
+                    .get_blocks(block_ids.clone())
+                    .await?
+                    .into_iter()
+                    .zip(block_ids)
+                    .map(|(block, id)| {
+                        block.ok_or_else(|| anyhow::anyhow!("cannot find block by id {}", id))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
             };
 
-            let max_return_num = count
-                .min(end_block_number + 1)
-                .min(config.rpc.block_query_max_range());
-            let block = service
-                .main_blocks_by_number(number, max_return_num)
-                .await?;
-
-            block
+            blocks
                 .into_iter()
-                .map(|blk| BlockView::try_from_block(blk, true))
+                .map(|blk| BlockView::try_from_block_with_raw(blk, !option.full_txns, option.raw))
                 .collect::<Result<Vec<_>, _>>()
         }
         .map_err(map_err);
@@ -132,8 +166,10 @@ where
     fn get_transaction(
         &self,
         transaction_hash: HashValue,
+        option: Option<GetTransactionOption>,
     ) -> FutureResult<Option<TransactionView>> {
         let service = self.service.clone();
+        let option = option.unwrap_or_default();
         let fut = async move {
             let transaction = service.get_transaction(transaction_hash).await?;
             match transaction {
@@ -148,7 +184,7 @@ where
                                 transaction_hash
                             )
                         })?;
-                    TransactionView::new(t, &block).map(Some)
+                    TransactionView::new_with_raw(t, &block, option.raw).map(Some)
                 }
             }
         }
@@ -239,6 +275,31 @@ where
 
         Box::pin(fut.boxed())
     }
+
+    fn get_transaction_proof(
+        &self,
+        block_hash: HashValue,
+        idx: u64,
+    ) -> FutureResult<Option<TransactionInfoWithProofView>> {
+        let service = self.service.clone();
+        let fut = async move {
+            let block = service.get_block_by_hash(block_hash).await?;
+            match block {
+                None => Ok(None),
+                Some(block) => service
+                    .get_transaction_proof(block_hash, idx)
+                    .await?
+                    .map(|txn_info_with_proof| {
+                        TransactionInfoWithProofView::new(txn_info_with_proof, &block)
+                    })
+                    .transpose(),
+            }
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
     fn get_events_by_txn_hash(
         &self,
         txn_hash: HashValue,
@@ -288,6 +349,87 @@ where
         Box::pin(fut.boxed())
     }
 
+    fn get_events_after(
+        &self,
+        cursor: Option<EventCursorView>,
+        limit: u64,
+    ) -> FutureResult<EventPageView> {
+        let service = self.service.clone();
+        let config = self.config.clone();
+        let fut = async move {
+            let cursor = cursor.map(|c| c.0);
+            let start_number = match cursor {
+                Some(cursor) => {
+                    let header = service
+                        .main_block_header_by_number(cursor.block_number)
+                        .await?;
+                    if header.map(|h| h.id()) != Some(cursor.block_hash) {
+                        return Err(jsonrpc_core::Error::invalid_params(format!(
+                            "block {} of cursor at number {} is not on main chain, may be reverted by reorg",
+                            cursor.block_hash, cursor.block_number
+                        ))
+                        .into());
+                    }
+                    cursor.block_number
+                }
+                None => 0,
+            };
+            let head_number = service.main_head_header().await?.number();
+            let end_number =
+                head_number.min(start_number.saturating_add(config.rpc.block_query_max_range()));
+            let limit = limit as usize;
+
+            let mut events = vec![];
+            let mut next_cursor = cursor;
+            'outer: for block_number in start_number..=end_number {
+                let header = service
+                    .main_block_header_by_number(block_number)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("cannot find block {} on main chain", block_number)
+                    })?;
+                let block_id = header.id();
+                let txn_infos = service.get_block_txn_infos(block_id).await?;
+                for (txn_index, txn_info) in txn_infos.iter().enumerate() {
+                    let txn_index = txn_index as u32;
+                    if let Some(cursor) = cursor.as_ref() {
+                        if block_number == cursor.block_number && txn_index < cursor.txn_index {
+                            continue;
+                        }
+                    }
+                    let txn_events = service
+                        .get_events_by_txn_hash(txn_info.transaction_hash())
+                        .await?;
+                    for (event_index, event) in txn_events.into_iter().enumerate() {
+                        let position =
+                            EventCursor::new(block_number, block_id, txn_index, event_index as u32);
+                        if let Some(cursor) = cursor.as_ref() {
+                            if !position.is_after(cursor) {
+                                continue;
+                            }
+                        }
+                        if events.len() >= limit {
+                            break 'outer;
+                        }
+                        events.push(CursoredEventView {
+                            cursor: StrView(position),
+                            event: event.into(),
+                        });
+                        next_cursor = Some(position);
+                    }
+                }
+                next_cursor = Some(EventCursor::block_end(block_number, block_id));
+            }
+            Ok(EventPageView {
+                events,
+                next_cursor: next_cursor.map(StrView),
+            })
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
     fn current_epoch(&self) -> FutureResult<EpochInfo> {
         let service = self.service.clone();
         let fut = async move { service.epoch_info().await };
@@ -302,6 +444,35 @@ where
         Box::pin(fut.boxed().map_err(map_err))
     }
 
+    fn get_epoch_infos_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> FutureResult<Vec<EpochInfoView>> {
+        let service = self.service.clone();
+        let max_count = count.min(self.config.rpc.block_query_max_range());
+        let fut = async move {
+            let mut block_number = match number {
+                Some(num) => num,
+                None => service.main_head_header().await?.number(),
+            };
+            let mut epoch_infos = vec![];
+            while (epoch_infos.len() as u64) < max_count {
+                let epoch_info = service.get_epoch_info_by_number(block_number).await?;
+                epoch_infos.push(EpochInfoView::new(&epoch_info, block_number));
+                if epoch_info.start_block_number() == 0 {
+                    break;
+                }
+                // the last block of the previous epoch.
+                block_number = epoch_info.start_block_number() - 1;
+            }
+            Ok(epoch_infos)
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
     fn get_global_time_by_number(&self, number: BlockNumber) -> FutureResult<GlobalTimeOnChain> {
         let service = self.service.clone();
         let fut = async move { service.get_global_time_by_number(number).await };
@@ -1,26 +1,31 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::helpers::TransactionRequestFiller;
-use crate::module::map_err;
+use crate::module::helpers::{resolve_state_root, TransactionRequestFiller};
+use crate::module::{convert_to_rpc_error, map_err};
+use bcs_ext::BCSCodec;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use starcoin_account_api::AccountAsyncService;
 use starcoin_chain_service::ChainAsyncService;
 use starcoin_config::NodeConfig;
+use starcoin_dev::disassemble::disassemble_module;
 use starcoin_dev::playground::PlaygroudService;
 use starcoin_rpc_api::contract_api::ContractApi;
 use starcoin_rpc_api::types::{
-    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunTransactionRequest,
-    StrView, TransactionOutputView,
+    AnnotatedMoveStructView, AnnotatedMoveValueView, ContractCall, DryRunOutputView,
+    DryRunTransactionRequest, FunctionIdView, StateRootOption, StrView,
 };
 use starcoin_rpc_api::FutureResult;
 use starcoin_state_api::ChainStateAsyncService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::language_storage::{ModuleId, StructTag};
-use starcoin_types::transaction::DryRunTransaction;
+use starcoin_types::transaction::{DryRunTransaction, RawUserTransaction};
 use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::module_abi::{CompiledModuleABI, ModuleABI};
+use starcoin_vm_types::transaction::authenticator::AccountPublicKey;
+use starcoin_vm_types::transaction::ScriptFunctionABI;
 use std::sync::Arc;
 
 pub struct ContractRpcImpl<Account, Pool, State, Chain> {
@@ -74,30 +79,55 @@ where
     State: ChainStateAsyncService + 'static,
     Chain: ChainAsyncService + 'static,
 {
-    fn get_code(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<StrView<Vec<u8>>>> {
+    fn get_code(
+        &self,
+        module_id: StrView<ModuleId>,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Option<StrView<Vec<u8>>>> {
         let service = self.chain_state.clone();
+        let chain = self.chain.clone();
+        let playground = self.playground.clone();
         let f = async move {
-            let code = service.get(AccessPath::from(&module_id.0)).await?;
+            let state_root = resolve_state_root(service, &chain, state_root).await?;
+            let code = playground.get_state(state_root, &AccessPath::from(&module_id.0))?;
             Ok(code.map(StrView))
         };
         Box::pin(f.map_err(map_err).boxed())
     }
 
+    fn get_code_disassembled(
+        &self,
+        module_id: StrView<ModuleId>,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Option<String>> {
+        let service = self.chain_state.clone();
+        let chain = self.chain.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let state_root = resolve_state_root(service, &chain, state_root).await?;
+            playground
+                .get_state(state_root, &AccessPath::from(&module_id.0))?
+                .map(|code| disassemble_module(code.as_slice()))
+                .transpose()
+        };
+        Box::pin(f.map_err(map_err).boxed())
+    }
+
     fn get_resource(
         &self,
         addr: AccountAddress,
         resource_type: StrView<StructTag>,
+        state_root: Option<StateRootOption>,
     ) -> FutureResult<Option<AnnotatedMoveStructView>> {
         let service = self.chain_state.clone();
+        let chain = self.chain.clone();
         let playground = self.playground.clone();
         let f = async move {
-            let state_root = service.clone().state_root().await?;
-            let data = service
-                .get(AccessPath::resource_access_path(
-                    addr,
-                    resource_type.0.clone(),
-                ))
-                .await?;
+            let state_root = resolve_state_root(service, &chain, state_root).await?;
+            let data = playground.get_state(
+                state_root,
+                &AccessPath::resource_access_path(addr, resource_type.0.clone()),
+            )?;
             match data {
                 None => Ok(None),
                 Some(d) => {
@@ -109,8 +139,13 @@ where
         };
         Box::pin(f.map_err(map_err).boxed())
     }
-    fn call(&self, call: ContractCall) -> FutureResult<Vec<AnnotatedMoveValueView>> {
+    fn call(
+        &self,
+        call: ContractCall,
+        state_root: Option<StateRootOption>,
+    ) -> FutureResult<Vec<AnnotatedMoveValueView>> {
         let service = self.chain_state.clone();
+        let chain = self.chain.clone();
         let playground = self.playground.clone();
         let ContractCall {
             function_id,
@@ -118,7 +153,7 @@ where
             args,
         } = call;
         let f = async move {
-            let state_root = service.state_root().await?;
+            let state_root = resolve_state_root(service, &chain, state_root).await?;
             let output = playground.call_contract(
                 state_root,
                 function_id.0.module,
@@ -131,7 +166,7 @@ where
         .map_err(map_err);
         Box::pin(f.boxed())
     }
-    fn dry_run(&self, txn: DryRunTransactionRequest) -> FutureResult<TransactionOutputView> {
+    fn dry_run(&self, txn: DryRunTransactionRequest) -> FutureResult<DryRunOutputView> {
         let service = self.chain_state.clone();
         let txn_builder = self.txn_request_filler();
         let playground = self.playground.clone();
@@ -158,14 +193,85 @@ where
                 Some(p) => p.0,
             };
 
-            let output = playground.dry_run(
+            let (_, output, annotation) = playground.dry_run_annotated(
                 state_root,
                 DryRunTransaction {
                     raw_txn: txn,
                     public_key: sender_public_key,
                 },
             )?;
-            Ok(output.1.into())
+            Ok(
+                DryRunOutputView::new(output, annotation.write_set, annotation.events)
+                    .with_gas_breakdown(annotation.gas_breakdown),
+            )
+        }
+        .map_err(map_err);
+        Box::pin(f.boxed())
+    }
+
+    fn dry_run_raw(
+        &self,
+        raw_txn: String,
+        sender_public_key: StrView<AccountPublicKey>,
+    ) -> FutureResult<DryRunOutputView> {
+        let raw_txn = match hex::decode(raw_txn.strip_prefix("0x").unwrap_or(&raw_txn))
+            .map_err(convert_to_rpc_error)
+            .and_then(|txn_bytes| RawUserTransaction::decode(&txn_bytes).map_err(map_err))
+        {
+            Ok(raw_txn) => raw_txn,
+            Err(e) => return Box::pin(futures::future::err(e)),
+        };
+        let service = self.chain_state.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let state_root = service.state_root().await?;
+            let (_, output, annotation) = playground.dry_run_annotated(
+                state_root,
+                DryRunTransaction {
+                    raw_txn,
+                    public_key: sender_public_key.0,
+                },
+            )?;
+            Ok(
+                DryRunOutputView::new(output, annotation.write_set, annotation.events)
+                    .with_gas_breakdown(annotation.gas_breakdown),
+            )
+        }
+        .map_err(map_err);
+        Box::pin(f.boxed())
+    }
+
+    fn get_abi(&self, module_id: StrView<ModuleId>) -> FutureResult<Option<ModuleABI>> {
+        let service = self.chain_state.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let state_root = service.state_root().await?;
+            playground.get_module_abi(state_root, &module_id.0)
+        }
+        .map_err(map_err);
+        Box::pin(f.boxed())
+    }
+
+    fn get_module_abi(
+        &self,
+        module_id: StrView<ModuleId>,
+    ) -> FutureResult<Option<CompiledModuleABI>> {
+        let service = self.chain_state.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let state_root = service.state_root().await?;
+            playground.get_compiled_module_abi(state_root, &module_id.0)
+        }
+        .map_err(map_err);
+        Box::pin(f.boxed())
+    }
+
+    fn resolve_function(&self, function_id: FunctionIdView) -> FutureResult<ScriptFunctionABI> {
+        let service = self.chain_state.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let state_root = service.state_root().await?;
+            playground.resolve_function_abi(state_root, &function_id.0)
         }
         .map_err(map_err);
         Box::pin(f.boxed())
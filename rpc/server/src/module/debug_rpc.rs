@@ -1,45 +1,110 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
 use crate::module::txfactory_rpc::TxFactoryStatusHandle;
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::format_err;
+use futures::future::TryFutureExt;
+use futures::FutureExt;
 use jsonrpc_core::Result;
+use starcoin_chain_service::ChainAsyncService;
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_dev::playground::PlaygroudService;
 use starcoin_logger::prelude::LevelFilter;
 use starcoin_logger::{LogPattern, LoggerHandle};
 use starcoin_rpc_api::debug::DebugApi;
-use starcoin_rpc_api::types::FactoryAction;
+use starcoin_rpc_api::types::{
+    CallFrameView, DryRunOutputView, FactoryAction, StateReadView, TransactionTraceView,
+};
+use starcoin_rpc_api::FutureResult;
 use std::str::FromStr;
 use std::sync::Arc;
 
-pub struct DebugRpcImpl {
+pub struct DebugRpcImpl<C> {
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
+    chain: C,
+    playground: PlaygroudService,
 }
 
-impl DebugRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, log_handle: Arc<LoggerHandle>) -> Self {
-        Self { config, log_handle }
+impl<C> DebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        log_handle: Arc<LoggerHandle>,
+        chain: C,
+        playground: PlaygroudService,
+    ) -> Self {
+        Self {
+            config,
+            log_handle,
+            chain,
+            playground,
+        }
     }
 }
 
-impl DebugApi for DebugRpcImpl {
-    fn set_log_level(&self, logger_name: Option<String>, level: String) -> Result<()> {
-        let logger_name = logger_name.and_then(|s| {
-            let s = s.trim();
-            if s.is_empty() {
-                None
-            } else {
-                Some(s.to_string())
-            }
-        });
-        let level = LevelFilter::from_str(level.as_str()).map_err(to_invalid_param_err)?;
-        match logger_name {
-            None => self.log_handle.update_level(level),
-            Some(n) => self.log_handle.set_log_level(n, level),
+/// The state root before the txn, which is the state root of the previous txn in the block,
+/// or the state root of the parent block for the first txn.
+async fn state_root_before<C>(
+    chain: &C,
+    block_hash: HashValue,
+    txn_hash: HashValue,
+) -> anyhow::Result<HashValue>
+where
+    C: ChainAsyncService + 'static,
+{
+    let txn_infos = chain.get_block_txn_infos(block_hash).await?;
+    let index = txn_infos
+        .iter()
+        .position(|info| info.transaction_hash() == txn_hash)
+        .ok_or_else(|| format_err!("Txn {} is not in block {}", txn_hash, block_hash))?;
+    if index > 0 {
+        return Ok(txn_infos[index - 1].state_root_hash());
+    }
+    let header = chain
+        .get_header_by_hash(&block_hash)
+        .await?
+        .ok_or_else(|| format_err!("Block {} not found", block_hash))?;
+    let parent = chain
+        .get_header_by_hash(&header.parent_hash())
+        .await?
+        .ok_or_else(|| format_err!("Can not trace the txn {} of the genesis block", txn_hash))?;
+    Ok(parent.state_root())
+}
+
+/// Update log level, if logger_name is none or empty, update global log level.
+pub(crate) fn set_log_level(
+    log_handle: &LoggerHandle,
+    logger_name: Option<String>,
+    level: String,
+) -> Result<()> {
+    let logger_name = logger_name.and_then(|s| {
+        let s = s.trim();
+        if s.is_empty() {
+            None
+        } else {
+            Some(s.to_string())
         }
+    });
+    let level = LevelFilter::from_str(level.as_str()).map_err(to_invalid_param_err)?;
+    match logger_name {
+        None => log_handle.update_level(level),
+        Some(n) => log_handle.set_log_level(n, level),
+    }
 
-        Ok(())
+    Ok(())
+}
+
+impl<C> DebugApi for DebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    fn set_log_level(&self, logger_name: Option<String>, level: String) -> Result<()> {
+        set_log_level(self.log_handle.as_ref(), logger_name, level)
     }
 
     fn set_log_pattern(&self, pattern: LogPattern) -> Result<()> {
@@ -67,4 +132,51 @@ impl DebugApi for DebugRpcImpl {
     fn txfactory_status(&self, action: FactoryAction) -> Result<bool> {
         Ok(TxFactoryStatusHandle::handle_action(action))
     }
+
+    fn trace_transaction(&self, txn_hash: HashValue) -> FutureResult<Option<TransactionTraceView>> {
+        let chain = self.chain.clone();
+        let playground = self.playground.clone();
+        let f = async move {
+            let (txn, txn_info) = match (
+                chain.get_transaction(txn_hash).await?,
+                chain.get_transaction_info(txn_hash).await?,
+            ) {
+                (Some(txn), Some(txn_info)) => (txn, txn_info),
+                _ => return Ok(None),
+            };
+            let block_hash = txn_info.block_id();
+            let state_root = state_root_before(&chain, block_hash, txn_hash).await?;
+            let trace = playground.trace_transaction(state_root, txn)?;
+            Ok(Some(TransactionTraceView {
+                transaction_hash: txn_hash,
+                block_hash,
+                state_root,
+                output: DryRunOutputView::new(
+                    trace.output,
+                    trace.annotation.write_set,
+                    trace.annotation.events,
+                )
+                .with_gas_breakdown(trace.annotation.gas_breakdown),
+                frames: trace
+                    .frames
+                    .into_iter()
+                    .map(|frame| CallFrameView {
+                        depth: frame.depth,
+                        module: frame.module.map(Into::into),
+                        function: frame.function,
+                        ty_args: frame.ty_args.into_iter().map(Into::into).collect(),
+                        args: frame.args.into_iter().map(Into::into).collect(),
+                        gas_used: frame.gas_used.into(),
+                    })
+                    .collect(),
+                reads: trace
+                    .reads
+                    .into_iter()
+                    .map(|(access_path, value)| StateReadView::new(access_path, value))
+                    .collect(),
+            }))
+        }
+        .map_err(map_err);
+        Box::pin(f.boxed())
+    }
 }
@@ -1,7 +1,9 @@
+use anyhow::format_err;
 use starcoin_account_api::AccountAsyncService;
 use starcoin_chain_service::ChainAsyncService;
 use starcoin_config::NodeConfig;
-use starcoin_rpc_api::types::TransactionRequest;
+use starcoin_crypto::HashValue;
+use starcoin_rpc_api::types::{StateRootOption, TransactionRequest};
 use starcoin_state_api::ChainStateAsyncService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::account_config::AccountResource;
@@ -105,3 +107,28 @@ where
         Ok(raw_txn)
     }
 }
+
+/// Resolve the state root of the `state_root` option, the latest state root if it is None.
+pub(crate) async fn resolve_state_root<State, Chain>(
+    chain_state: State,
+    chain: &Chain,
+    state_root: Option<StateRootOption>,
+) -> anyhow::Result<HashValue>
+where
+    State: ChainStateAsyncService + 'static,
+    Chain: ChainAsyncService + 'static,
+{
+    let header = match state_root {
+        None => return chain_state.state_root().await,
+        Some(StateRootOption::StateRoot(state_root)) => return Ok(state_root),
+        Some(StateRootOption::BlockHash(block_hash)) => chain
+            .get_header_by_hash(&block_hash)
+            .await?
+            .ok_or_else(|| format_err!("Can not find block by hash {}", block_hash))?,
+        Some(StateRootOption::BlockNumber(number)) => chain
+            .main_block_header_by_number(number)
+            .await?
+            .ok_or_else(|| format_err!("Can not find block by number {}", number))?,
+    };
+    Ok(header.state_root())
+}
@@ -8,6 +8,7 @@ mod debug_rpc;
 mod helpers;
 mod miner_rpc;
 mod network_manager_rpc;
+mod node_admin_rpc;
 mod node_manager_rpc;
 mod node_rpc;
 mod pubsub;
@@ -22,9 +23,10 @@ pub use self::contract_rpc::ContractRpcImpl;
 pub use self::debug_rpc::DebugRpcImpl;
 pub use self::miner_rpc::MinerRpcImpl;
 pub use self::network_manager_rpc::NetworkManagerRpcImpl;
+pub use self::node_admin_rpc::NodeAdminRpcImpl;
 pub use self::node_manager_rpc::NodeManagerRpcImpl;
 pub use self::node_rpc::NodeRpcImpl;
-pub use self::pubsub::{PubSubImpl, PubSubService, PubSubServiceFactory};
+pub use self::pubsub::{ListSubscriptions, PubSubImpl, PubSubService, PubSubServiceFactory};
 pub use self::state_rpc::StateRpcImpl;
 pub use self::sync_manager_rpc::SyncManagerRpcImpl;
 pub use self::txfactory_rpc::TxFactoryStatusHandle;
@@ -35,6 +37,7 @@ use anyhow::Error;
 use hex::FromHexError;
 use jsonrpc_core::ErrorCode;
 use starcoin_account_api::error::AccountError;
+use starcoin_rpc_api::errors::RpcErrorCode;
 use starcoin_rpc_api::types::TransactionVMStatus;
 use starcoin_vm_types::transaction::{CallError, TransactionError, TransactionStatus};
 use starcoin_vm_types::vm_status::VMStatus;
@@ -89,68 +92,74 @@ impl From<anyhow::Error> for RpcError {
     }
 }
 
-const TXN_ERROR_BASE: i64 = -50000;
-const ACCOUNT_ERROR_BASE: i64 = -60000;
-
 impl From<AccountError> for RpcError {
     fn from(err: AccountError) -> Self {
-        let rpc_error = match err {
-            AccountError::StoreError(error) => jsonrpc_core::Error {
-                code: ErrorCode::ServerError(ACCOUNT_ERROR_BASE),
-                message: error.to_string(),
-                data: None,
-            },
-            e => jsonrpc_core::Error {
-                code: ErrorCode::InvalidParams,
-                message: e.to_string(),
-                data: None,
-            },
+        let message = err.to_string();
+        let code = match err {
+            AccountError::StoreError(_) => {
+                return RpcError(RpcErrorCode::AccountStoreError.to_error(message, None))
+            }
+            AccountError::PublicModeForbidden(_) => {
+                return RpcError(RpcErrorCode::PublicModeForbidden.to_error(message, None))
+            }
+            AccountError::AccountNotExist(_) => RpcErrorCode::AccountNotExist,
+            AccountError::AccountAlreadyExist(_) => RpcErrorCode::AccountAlreadyExist,
+            AccountError::AccountLocked(_) => RpcErrorCode::AccountLocked,
+            AccountError::RemoveDefaultAccountError(_) => RpcErrorCode::RemoveDefaultAccount,
+            AccountError::InvalidPassword(_) => RpcErrorCode::InvalidPassword,
+            AccountError::InvalidPrivateKey | AccountError::InvalidKeyRotation(..) => {
+                RpcErrorCode::InvalidPrivateKey
+            }
+            AccountError::TransactionSignError(_) => RpcErrorCode::TxnSignFailed,
+            AccountError::AccountPrivateKeyMissing(_) => RpcErrorCode::PrivateKeyMissing,
         };
-        RpcError(rpc_error)
+        RpcError(code.to_invalid_params(message))
     }
 }
 
 impl From<TransactionError> for RpcError {
     fn from(err: TransactionError) -> Self {
         let err_message = err.to_string();
-        let (err_code, err_data) = match err {
-            TransactionError::AlreadyImported
-            | TransactionError::Old
-            | TransactionError::InsufficientGasPrice { .. }
-            | TransactionError::TooCheapToReplace { .. }
-            | TransactionError::InsufficientGas { .. }
-            | TransactionError::InsufficientBalance { .. }
-            | TransactionError::GasLimitExceeded { .. }
-            | TransactionError::SenderBanned
+        let code = match err {
+            TransactionError::AlreadyImported => RpcErrorCode::TxnAlreadyImported,
+            TransactionError::Old => RpcErrorCode::SequenceNumberTooOld,
+            TransactionError::TooCheapToReplace { .. } => RpcErrorCode::TooCheapToReplace,
+            TransactionError::SenderBanned
             | TransactionError::RecipientBanned
-            | TransactionError::CodeBanned
-            | TransactionError::InvalidChainId
-            | TransactionError::InvalidSignature(..)
-            | TransactionError::NotAllowed
-            | TransactionError::TooBig => (ErrorCode::InvalidParams, None),
-            TransactionError::LimitReached => (ErrorCode::ServerError(TXN_ERROR_BASE), None),
+            | TransactionError::CodeBanned => RpcErrorCode::TxnBanned,
+            TransactionError::InsufficientGasPrice { .. } => RpcErrorCode::GasPriceTooLow,
+            TransactionError::InsufficientGas { .. } => RpcErrorCode::GasTooLow,
+            TransactionError::InsufficientBalance { .. } => RpcErrorCode::InsufficientBalance,
+            TransactionError::GasLimitExceeded { .. } => RpcErrorCode::GasLimitExceeded,
+            TransactionError::InvalidChainId => RpcErrorCode::InvalidChainId,
+            TransactionError::InvalidSignature(..) => RpcErrorCode::InvalidSignature,
+            TransactionError::NotAllowed => RpcErrorCode::TxnNotAllowed,
+            TransactionError::TooBig => RpcErrorCode::TxnTooBig,
+            TransactionError::LimitReached => {
+                return RpcError(RpcErrorCode::TxPoolFull.to_error(err_message, None))
+            }
             TransactionError::CallErr(call_err) => match call_err {
-                CallError::TransactionNotFound => (ErrorCode::InvalidParams, None),
+                CallError::TransactionNotFound => RpcErrorCode::TxnNotFound,
                 CallError::StatePruned | CallError::StateCorrupt => {
-                    (ErrorCode::ServerError(TXN_ERROR_BASE + 1), None)
+                    return RpcError(RpcErrorCode::StateNotAvailable.to_error(err_message, None))
+                }
+                CallError::ExecutionError(vm_status) => {
+                    return RpcError(
+                        RpcErrorCode::TxnExecutionFailed.to_error(
+                            err_message,
+                            Some(
+                                // translate to jsonrpc types
+                                serde_json::to_value(TransactionVMStatus::from(
+                                    TransactionStatus::from(vm_status),
+                                ))
+                                .expect("vm status to json should be ok"),
+                            ),
+                        ),
+                    );
                 }
-                CallError::ExecutionError(vm_status) => (
-                    ErrorCode::ServerError(TXN_ERROR_BASE + 2),
-                    Some(
-                        // translate to jsonrpc types
-                        serde_json::to_value(TransactionVMStatus::from(TransactionStatus::from(
-                            vm_status,
-                        )))
-                        .expect("vm status to json should be ok"),
-                    ),
-                ),
             },
         };
-        RpcError(jsonrpc_core::Error {
-            code: err_code,
-            message: err_message,
-            data: err_data,
-        })
+        RpcError(code.to_invalid_params(err_message))
     }
 }
 
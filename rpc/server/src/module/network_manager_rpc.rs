@@ -4,14 +4,16 @@
 use crate::module::map_err;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
+use network_api::PeerProvider;
 use network_p2p_types::network_state::NetworkState;
 use network_rpc_core::RawRpcClient;
 use starcoin_network::NetworkServiceRef;
 use starcoin_rpc_api::network_manager::NetworkManagerApi;
-use starcoin_rpc_api::types::StrView;
+use starcoin_rpc_api::types::{PeerDetailView, StrView};
 use starcoin_rpc_api::FutureResult;
 use starcoin_types::peer_info::{Multiaddr, PeerId};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::str::FromStr;
 
 pub struct NetworkManagerRpcImpl {
@@ -58,6 +60,34 @@ impl NetworkManagerApi for NetworkManagerRpcImpl {
         Box::pin(fut.boxed())
     }
 
+    fn get_peers_detail(&self) -> FutureResult<Vec<PeerDetailView>> {
+        let service = self.service.clone();
+        let fut = async move {
+            let traffics = service.peers_traffic().await?;
+            let network_state = service.network_state().await?;
+            let reputations = service
+                .reputations(i32::MIN)
+                .await?
+                .await?
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            Ok(traffics
+                .into_iter()
+                .map(|traffic| {
+                    let peer_id = traffic.peer_info.peer_id();
+                    let reputation = reputations.get(&peer_id).cloned();
+                    let rtt = network_state
+                        .connected_peers
+                        .get(&peer_id.to_string())
+                        .and_then(|peer| peer.latest_ping_time);
+                    PeerDetailView::new(traffic, reputation, rtt)
+                })
+                .collect())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
     fn call_peer(
         &self,
         peer_id: String,
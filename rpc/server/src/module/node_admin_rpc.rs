@@ -0,0 +1,164 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api_key_middleware::ApiKeyMiddleware;
+use crate::module::debug_rpc::set_log_level;
+use crate::module::map_err;
+use anyhow::format_err;
+use bcs_ext::BCSCodec;
+use futures::channel::oneshot;
+use futures::future::TryFutureExt;
+use futures::FutureExt;
+use starcoin_crypto::HashValue;
+use starcoin_logger::LoggerHandle;
+use starcoin_network::NetworkServiceRef;
+use starcoin_rpc_api::node_admin::NodeAdminApi;
+use starcoin_rpc_api::types::{DBBackupView, StateDumpPageView, StrView};
+use starcoin_rpc_api::FutureResult;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::backup::backup;
+use starcoin_storage::Storage;
+use starcoin_types::peer_info::PeerId;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The max count of the accounts returned by `node_admin.dump_state` at once.
+const MAX_DUMP_STATE_LIMIT: u64 = 1000;
+
+pub struct NodeAdminRpcImpl {
+    service: NetworkServiceRef,
+    log_handle: Arc<LoggerHandle>,
+    storage: Arc<Storage>,
+    api_key_middleware: ApiKeyMiddleware,
+}
+
+impl NodeAdminRpcImpl {
+    pub fn new(
+        service: NetworkServiceRef,
+        log_handle: Arc<LoggerHandle>,
+        storage: Arc<Storage>,
+        api_key_middleware: ApiKeyMiddleware,
+    ) -> Self {
+        Self {
+            service,
+            log_handle,
+            storage,
+            api_key_middleware,
+        }
+    }
+}
+
+impl NodeAdminApi for NodeAdminRpcImpl {
+    fn add_peer(&self, peer: String) -> FutureResult<()> {
+        let service = self.service.clone();
+        let fut = async move { service.add_peer(peer) }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn remove_peer(&self, peer_id: String) -> FutureResult<()> {
+        let service = self.service.clone();
+        let fut = async move {
+            let peer_id = PeerId::from_str(peer_id.as_str())?;
+            service.remove_peer(peer_id);
+            Ok(())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn ban_peer(&self, peer_id: String, duration: u64) -> FutureResult<()> {
+        let service = self.service.clone();
+        let fut = async move {
+            let peer_id = PeerId::from_str(peer_id.as_str())?;
+            service.ban_peer(peer_id, Duration::from_secs(duration));
+            Ok(())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn set_log_level(&self, logger_name: Option<String>, level: String) -> FutureResult<()> {
+        let result = set_log_level(self.log_handle.as_ref(), logger_name, level);
+        Box::pin(futures::future::ready(result))
+    }
+
+    fn compact_db(&self, cf: Option<String>) -> FutureResult<Vec<String>> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            let db = storage
+                .db()
+                .ok_or_else(|| format_err!("The storage has no db."))?;
+            // The compaction may take a long time, so run it out of the rpc runtime.
+            let (sender, receiver) = oneshot::channel();
+            std::thread::spawn(move || {
+                let _ = sender.send(db.compact(cf.as_deref()));
+            });
+            let cfs = receiver.await??;
+            Ok(cfs.into_iter().map(|cf| cf.to_string()).collect())
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn backup_db(&self, backup_dir: String) -> FutureResult<DBBackupView> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            let (sender, receiver) = oneshot::channel();
+            std::thread::spawn(move || {
+                let _ = sender.send(
+                    backup(storage.as_ref(), Path::new(backup_dir.as_str())).map(|info| {
+                        DBBackupView {
+                            id: info.id,
+                            dir: Path::new(backup_dir.as_str())
+                                .join(info.id.to_string())
+                                .to_string_lossy()
+                                .to_string(),
+                            block_number: info.block_number.into(),
+                            block_hash: info.block_hash,
+                            timestamp: info.timestamp.into(),
+                            files: info.files.len() as u64,
+                            size: info.size().into(),
+                            new_bytes: info.new_bytes.into(),
+                        }
+                    }),
+                );
+            });
+            receiver.await?
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn dump_state(
+        &self,
+        state_root: HashValue,
+        cursor: Option<HashValue>,
+        limit: u64,
+    ) -> FutureResult<StateDumpPageView> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            if limit == 0 {
+                anyhow::bail!("The limit of dump_state should be greater than 0.");
+            }
+            let statedb = ChainStateDB::new(storage, Some(state_root));
+            let (state_set, next_cursor) = statedb.dump_range(
+                cursor.unwrap_or_else(HashValue::zero),
+                limit.min(MAX_DUMP_STATE_LIMIT) as usize,
+            )?;
+            Ok(StateDumpPageView {
+                state: StrView(state_set.encode()?),
+                next_cursor,
+            })
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn rotate_api_keys(&self) -> FutureResult<HashMap<String, String>> {
+        let secrets = self.api_key_middleware.rotate_keys();
+        Box::pin(futures::future::ok(secrets))
+    }
+}
@@ -1,27 +1,32 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::map_err;
+use crate::module::{map_err, ListSubscriptions, PubSubService};
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use starcoin_node_api::node_service::NodeAsyncService;
 use starcoin_rpc_api::node_manager::NodeManagerApi;
+use starcoin_rpc_api::types::pubsub::SubscriptionView;
 use starcoin_rpc_api::FutureResult;
-use starcoin_service_registry::{ServiceInfo, ServiceStatus};
+use starcoin_service_registry::{ServiceInfo, ServiceRef, ServiceStatus};
 
 pub struct NodeManagerRpcImpl<S>
 where
     S: NodeAsyncService + 'static,
 {
     service: S,
+    pubsub_service: ServiceRef<PubSubService>,
 }
 
 impl<S> NodeManagerRpcImpl<S>
 where
     S: NodeAsyncService,
 {
-    pub fn new(service: S) -> Self {
-        Self { service }
+    pub fn new(service: S, pubsub_service: ServiceRef<PubSubService>) -> Self {
+        Self {
+            service,
+            pubsub_service,
+        }
     }
 }
 
@@ -74,4 +79,10 @@ where
         .map_err(map_err);
         Box::pin(fut.boxed())
     }
+
+    fn subscriptions(&self) -> FutureResult<Vec<SubscriptionView>> {
+        let service = self.pubsub_service.clone();
+        let fut = async move { service.send(ListSubscriptions).await }.map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
@@ -2,30 +2,61 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::module::map_err;
+use anyhow::format_err;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use jsonrpc_core::Result;
 use network_api::PeerProvider;
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
 use starcoin_network::NetworkServiceRef;
-use starcoin_rpc_api::node::{NodeApi, NodeInfo};
+use starcoin_rpc_api::node::{NodeApi, NodeHealth, NodeInfo};
 use starcoin_rpc_api::types::PeerInfoView;
 use starcoin_rpc_api::FutureResult;
+use starcoin_state_api::snapshot::{load_snapshot_index, SnapshotInfo};
+use starcoin_sync_api::SyncAsyncService;
+use starcoin_txpool::TxPoolService;
+use starcoin_txpool_api::TxPoolSyncService;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-pub struct NodeRpcImpl {
+pub struct NodeRpcImpl<S>
+where
+    S: SyncAsyncService + 'static,
+{
     config: Arc<NodeConfig>,
     service: Option<NetworkServiceRef>,
+    sync_service: Option<S>,
+    txpool_service: TxPoolService,
+    fingerprint: String,
 }
 
-impl NodeRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, service: Option<NetworkServiceRef>) -> Self {
-        Self { config, service }
+impl<S> NodeRpcImpl<S>
+where
+    S: SyncAsyncService,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        service: Option<NetworkServiceRef>,
+        sync_service: Option<S>,
+        txpool_service: TxPoolService,
+        genesis_hash: HashValue,
+    ) -> Self {
+        let fingerprint = config.fingerprint(genesis_hash);
+        Self {
+            config,
+            service,
+            sync_service,
+            txpool_service,
+            fingerprint,
+        }
     }
 }
 
-impl NodeApi for NodeRpcImpl {
+impl<S> NodeApi for NodeRpcImpl<S>
+where
+    S: SyncAsyncService,
+{
     fn status(&self) -> Result<bool> {
         //TODO check service status.
         Ok(true)
@@ -35,6 +66,7 @@ impl NodeApi for NodeRpcImpl {
         let service = self.service.clone().unwrap();
         let self_address = self.config.network.self_address().to_string();
         let net = self.config.net().clone();
+        let fingerprint = self.fingerprint.clone();
         let fut = async move {
             let peer_info = service.get_self_peer().await?;
             //TODO read consensus_strategy from Epoch.
@@ -45,6 +77,7 @@ impl NodeApi for NodeRpcImpl {
                 net.id().clone(),
                 consensus_strategy,
                 net.time_service().now_secs(),
+                fingerprint,
             );
             Ok(node_info)
         };
@@ -66,4 +99,32 @@ impl NodeApi for NodeRpcImpl {
     fn metrics(&self) -> Result<HashMap<String, String>> {
         Ok(starcoin_metrics::get_all_metrics())
     }
+
+    fn snapshots(&self) -> Result<Vec<SnapshotInfo>> {
+        load_snapshot_index(self.config.snapshot.dir().as_path()).map_err(map_err)
+    }
+
+    fn health(&self) -> FutureResult<NodeHealth> {
+        let service = self.service.clone();
+        let sync_service = self.sync_service.clone();
+        let txpool_status = self.txpool_service.status();
+        let net = self.config.net().clone();
+        let fut = async move {
+            let sync_service =
+                sync_service.ok_or_else(|| format_err!("Sync service is not available."))?;
+            let sync_status = sync_service.status().await?;
+            let peer_count = match service {
+                Some(service) => service.peer_set().await?.len(),
+                None => 0,
+            };
+            Ok(NodeHealth::new(
+                &sync_status,
+                peer_count,
+                &txpool_status,
+                !net.is_test_or_dev(),
+                net.time_service().now_secs(),
+            ))
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
 }
@@ -1,19 +1,27 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use self::accounting::{
+    SubscriptionAccounting, SubscriptionLimits, SubscriptionOwner, SubscriptionStats,
+};
+use self::backlog::{ResumableStreams, StreamSender};
 use anyhow::Result;
 use futures::channel::mpsc;
 use futures::future::AbortHandle;
 use futures::StreamExt;
 use jsonrpc_pubsub::typed::Subscriber;
 use jsonrpc_pubsub::SubscriptionId;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use serde_json::Value;
 use starcoin_chain_notify::message::{Event, Notification, ThinBlock};
+use starcoin_config::NodeConfig;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_miner::{MinerClientSubscribeRequest, MinerService};
 use starcoin_rpc_api::metadata::Metadata;
-use starcoin_rpc_api::types::pubsub::MintBlock;
+use starcoin_rpc_api::types::pubsub::{
+    BlockFilter, CursorOption, MintBlock, MintBlockFilter, PendingTxnFilter, SubscriptionView,
+};
 use starcoin_rpc_api::types::{BlockView, TransactionEventView};
 use starcoin_rpc_api::{errors, pubsub::StarcoinPubSub, types::pubsub};
 use starcoin_service_registry::{
@@ -29,7 +37,10 @@ use std::convert::TryInto;
 use std::fmt::Debug;
 use std::sync::mpsc::TrySendError;
 use std::sync::{atomic, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+mod accounting;
+mod backlog;
 #[cfg(test)]
 pub mod tests;
 
@@ -61,108 +72,183 @@ fn map_send_err<T>(err: &TrySendError<T>) -> jsonrpc_core::Error {
 impl PubSubImpl {
     fn inner_subscribe(
         &self,
-        _meta: Metadata,
+        meta: Metadata,
         subscriber: Subscriber<pubsub::Result>,
         kind: pubsub::Kind,
-        params: Option<pubsub::Params>,
+        mut params: Option<Value>,
     ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
-        match (kind, params) {
-            (pubsub::Kind::NewHeads, None) => self
-                .service
-                .try_send(SubscribeNewHeads(subscriber))
-                .map_err(|e| {
-                    let msg = map_send_err(&e);
-                    (
-                        match e {
-                            TrySendError::Disconnected(t) => t.0,
-                            TrySendError::Full(t) => t.0,
-                        },
-                        msg,
-                    )
-                }),
-            (pubsub::Kind::NewHeads, _) => Err((
-                subscriber,
-                errors::invalid_params("newHeads", "Expected no parameters."),
-            )),
-            (pubsub::Kind::NewPendingTransactions, None) => self
-                .service
-                .try_send(SubscribeNewPendingTxns { subscriber })
-                .map_err(|e| {
-                    let msg = map_send_err(&e);
-                    (
-                        match e {
-                            TrySendError::Disconnected(t) => t.subscriber,
-                            TrySendError::Full(t) => t.subscriber,
-                        },
-                        msg,
-                    )
-                }),
-            (pubsub::Kind::NewPendingTransactions, _) => Err((
+        let owner = SubscriptionOwner::from_metadata(&meta);
+        let cursor = match CursorOption::take_from(&mut params) {
+            Ok(cursor) => cursor,
+            Err(e) => return Err((subscriber, e)),
+        };
+        let params = match pubsub::Params::parse(&kind, params) {
+            Ok(params) => params,
+            Err(e) => return Err((subscriber, e)),
+        };
+        if cursor.is_enabled() && !matches!(kind, pubsub::Kind::NewHeads | pubsub::Kind::Events) {
+            return Err((
                 subscriber,
-                errors::invalid_params("newPendingTransactions", "Expected no parameters."),
-            )),
-            (pubsub::Kind::Events, Some(pubsub::Params::Events(filter))) => {
-                match filter.try_into() {
-                    Ok(f) => self
-                        .service
-                        .try_send(SubscribeEvents {
-                            subscriber,
-                            filter: f,
-                        })
-                        .map_err(|e| {
-                            let msg = map_send_err(&e);
-                            (
-                                match e {
-                                    TrySendError::Disconnected(t) => t.subscriber,
-                                    TrySendError::Full(t) => t.subscriber,
-                                },
-                                msg,
-                            )
-                        }),
-                    Err(e) => Err((subscriber, e)),
-                }
+                errors::invalid_params(
+                    "cursor",
+                    format!("The subscription {:?} does not support the cursor", kind),
+                ),
+            ));
+        }
+        match (kind, params) {
+            (pubsub::Kind::NewHeads, pubsub::Params::None) => {
+                self.subscribe_new_heads(subscriber, BlockFilter::default(), cursor, owner)
+            }
+            (pubsub::Kind::NewHeads, pubsub::Params::Blocks(filter)) => {
+                self.subscribe_new_heads(subscriber, filter, cursor, owner)
             }
+            (pubsub::Kind::NewPendingTransactions, pubsub::Params::None) => {
+                self.subscribe_new_pending_txns(subscriber, PendingTxnFilter::default(), owner)
+            }
+            (pubsub::Kind::NewPendingTransactions, pubsub::Params::PendingTxns(filter)) => {
+                self.subscribe_new_pending_txns(subscriber, filter, owner)
+            }
+            (pubsub::Kind::Events, pubsub::Params::Events(filter)) => match filter.try_into() {
+                Ok(f) => self
+                    .service
+                    .try_send(SubscribeEvents {
+                        subscriber,
+                        filter: f,
+                        cursor,
+                        owner,
+                    })
+                    .map_err(|e| {
+                        let msg = map_send_err(&e);
+                        (
+                            match e {
+                                TrySendError::Disconnected(t) => t.subscriber,
+                                TrySendError::Full(t) => t.subscriber,
+                            },
+                            msg,
+                        )
+                    }),
+                Err(e) => Err((subscriber, e)),
+            },
             (pubsub::Kind::Events, _) => Err((
                 subscriber,
                 errors::invalid_params("events", "Expected a filter object."),
             )),
-            (pubsub::Kind::NewMintBlock, _) => self
-                .service
-                .try_send(SubscribeMintBlock(subscriber))
-                .map_err(|e| {
-                    let msg = map_send_err(&e);
-                    (
-                        match e {
-                            TrySendError::Disconnected(t) => t.0,
-                            TrySendError::Full(t) => t.0,
-                        },
-                        msg,
-                    )
-                }),
+            (pubsub::Kind::NewMintBlock, pubsub::Params::None) => {
+                self.subscribe_new_mint_blocks(subscriber, MintBlockFilter::default(), owner)
+            }
+            (pubsub::Kind::NewMintBlock, pubsub::Params::MintBlocks(filter)) => {
+                self.subscribe_new_mint_blocks(subscriber, filter, owner)
+            }
+            (kind, params) => Err((
+                subscriber,
+                errors::invalid_params(
+                    "params",
+                    format!("Unexpected params {:?} of subscription {:?}", params, kind),
+                ),
+            )),
         }
     }
+
+    fn subscribe_new_heads(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        filter: BlockFilter,
+        cursor: CursorOption,
+        owner: SubscriptionOwner,
+    ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
+        self.service
+            .try_send(SubscribeNewHeads {
+                subscriber,
+                filter,
+                cursor,
+                owner,
+            })
+            .map_err(|e| {
+                let msg = map_send_err(&e);
+                (
+                    match e {
+                        TrySendError::Disconnected(t) => t.subscriber,
+                        TrySendError::Full(t) => t.subscriber,
+                    },
+                    msg,
+                )
+            })
+    }
+
+    fn subscribe_new_mint_blocks(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        filter: MintBlockFilter,
+        owner: SubscriptionOwner,
+    ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
+        self.service
+            .try_send(SubscribeMintBlock {
+                subscriber,
+                filter,
+                owner,
+            })
+            .map_err(|e| {
+                let msg = map_send_err(&e);
+                (
+                    match e {
+                        TrySendError::Disconnected(t) => t.subscriber,
+                        TrySendError::Full(t) => t.subscriber,
+                    },
+                    msg,
+                )
+            })
+    }
+
+    fn subscribe_new_pending_txns(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        filter: PendingTxnFilter,
+        owner: SubscriptionOwner,
+    ) -> Result<(), (Subscriber<pubsub::Result>, jsonrpc_core::Error)> {
+        self.service
+            .try_send(SubscribeNewPendingTxns {
+                subscriber,
+                filter,
+                owner,
+            })
+            .map_err(|e| {
+                let msg = map_send_err(&e);
+                (
+                    match e {
+                        TrySendError::Disconnected(t) => t.subscriber,
+                        TrySendError::Full(t) => t.subscriber,
+                    },
+                    msg,
+                )
+            })
+    }
 }
 
 impl StarcoinPubSub for PubSubImpl {
     type Metadata = Metadata;
     fn subscribe(
         &self,
-        _meta: Metadata,
+        meta: Metadata,
         subscriber: Subscriber<pubsub::Result>,
         kind: pubsub::Kind,
-        params: Option<pubsub::Params>,
+        params: Option<Value>,
     ) {
-        if let Err((subscriber, error)) = self.inner_subscribe(_meta, subscriber, kind, params) {
+        if let Err((subscriber, error)) = self.inner_subscribe(meta, subscriber, kind, params) {
             let _ = subscriber.reject(error);
         }
     }
 
     fn unsubscribe(
         &self,
-        _: Option<Self::Metadata>,
+        meta: Option<Self::Metadata>,
         id: SubscriptionId,
     ) -> jsonrpc_core::Result<bool> {
-        match self.service.try_send(Unsubscribe(id)) {
+        // the subscriptions are unsubscribed without the metadata when the connection is closed.
+        let unsubscribe = Unsubscribe {
+            id,
+            closed: meta.is_none(),
+        };
+        match self.service.try_send(unsubscribe) {
             Ok(()) => Ok(true),
             Err(TrySendError::Full(_)) => Err(jsonrpc_core::Error {
                 code: jsonrpc_core::ErrorCode::InternalError,
@@ -183,9 +269,16 @@ pub struct PubSubServiceFactory;
 impl ServiceFactory<PubSubService> for PubSubServiceFactory {
     fn create(ctx: &mut ServiceContext<PubSubService>) -> Result<PubSubService> {
         let miner_service = ctx.service_ref::<MinerService>()?.clone();
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let limits = SubscriptionLimits {
+            max_per_connection: config.rpc.ws.max_subscriptions_per_connection(),
+            max_per_ip: config.rpc.ws.max_subscriptions_per_ip(),
+        };
         Ok(PubSubService::new(
             ctx.get_shared::<TxPoolService>()?,
             miner_service,
+            limits,
+            config.rpc.ws.subscription_backlog(),
         ))
     }
 }
@@ -197,13 +290,28 @@ pub struct PubSubService {
 
     new_header_subscribers: HashMap<SubscriptionId, mpsc::UnboundedSender<NewHeadNotification>>,
     new_event_subscribers: HashMap<SubscriptionId, mpsc::UnboundedSender<NewEventNotification>>,
+    /// The id of the next resumable stream, shared by the subscription kinds.
+    next_stream: u64,
+    head_streams: ResumableStreams<NewHeadNotification>,
+    event_streams: ResumableStreams<NewEventNotification>,
     mint_block_subscribers: HashMap<SubscriptionId, mpsc::UnboundedSender<MintBlockEvent>>,
     new_pending_txn_tasks: Arc<RwLock<HashMap<SubscriptionId, AbortHandle>>>,
+    accounting: Arc<RwLock<SubscriptionAccounting>>,
 }
 
 impl PubSubService {
-    fn new(txpool: TxPoolService, miner_service: ServiceRef<MinerService>) -> Self {
+    fn new(
+        txpool: TxPoolService,
+        miner_service: ServiceRef<MinerService>,
+        limits: SubscriptionLimits,
+        backlog: usize,
+    ) -> Self {
         let subscriber_id = Arc::new(atomic::AtomicU64::new(0));
+        // the cursors of the resumable streams are invalid after the service restarts.
+        let epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
         Self {
             subscriber_id,
             txpool,
@@ -211,13 +319,97 @@ impl PubSubService {
             new_event_subscribers: Default::default(),
             new_header_subscribers: Default::default(),
             mint_block_subscribers: Default::default(),
+            next_stream: 1,
+            head_streams: ResumableStreams::new(epoch, backlog),
+            event_streams: ResumableStreams::new(epoch, backlog),
             new_pending_txn_tasks: Arc::new(RwLock::new(HashMap::default())),
+            accounting: Arc::new(RwLock::new(SubscriptionAccounting::new(limits))),
         }
     }
     fn next_id(&self) -> SubscriptionId {
         let id = self.subscriber_id.fetch_add(1, atomic::Ordering::SeqCst);
         SubscriptionId::Number(id)
     }
+
+    /// Reject the subscriber if the owner exceeds the subscription limits,
+    /// otherwise allocate the subscription id and start accounting it.
+    fn register(
+        &self,
+        subscriber: Subscriber<pubsub::Result>,
+        owner: SubscriptionOwner,
+        kind: pubsub::Kind,
+        track_sent: bool,
+    ) -> Option<(
+        Subscriber<pubsub::Result>,
+        SubscriptionId,
+        Arc<SubscriptionStats>,
+    )> {
+        let mut accounting = self.accounting.write();
+        if let Err(e) = accounting.check(&owner) {
+            debug!("[pubsub] Reject subscription of {:?}: {}", owner, e.message);
+            let _ = subscriber.reject(e);
+            return None;
+        }
+        let subscriber_id = self.next_id();
+        let stats = accounting.add(subscriber_id.clone(), kind, owner, track_sent);
+        Some((subscriber, subscriber_id, stats))
+    }
+
+    /// Open a resumable stream for the subscriber, or attach the stream to it if the
+    /// subscriber resumes from a cursor, the buffered notifications after the cursor are sent first.
+    #[allow(clippy::too_many_arguments)]
+    fn subscribe_resumable<M, H>(
+        &mut self,
+        ctx: &mut ServiceContext<Self>,
+        subscriber: Subscriber<pubsub::Result>,
+        owner: SubscriptionOwner,
+        kind: pubsub::Kind,
+        cursor: CursorOption,
+        handler: H,
+        streams: fn(&mut Self) -> &mut ResumableStreams<M>,
+    ) where
+        M: Clone + 'static,
+        H: EventHandler<M> + Send + 'static,
+    {
+        let replay = match &cursor.resume_from {
+            Some(resume_from) => match streams(self).replay(&resume_from.0) {
+                Ok(replay) => replay,
+                Err(e) => {
+                    let _ = subscriber.reject(e);
+                    return;
+                }
+            },
+            None => vec![],
+        };
+        let (sink, subscriber_id, stats) = match self.register(subscriber, owner, kind, true) {
+            Some(registered) => registered,
+            None => return,
+        };
+        let (sender, receiver): (StreamSender, _) = mpsc::unbounded();
+        for result in replay {
+            if sender.unbounded_send(Ok(result)).is_ok() {
+                stats.on_sent();
+            }
+        }
+        match &cursor.resume_from {
+            Some(resume_from) => {
+                streams(self).attach(resume_from.0.stream, subscriber_id.clone(), sender)
+            }
+            None => {
+                let stream = self.next_stream;
+                self.next_stream += 1;
+                streams(self).open(stream, Box::new(handler), subscriber_id.clone(), sender);
+            }
+        }
+        ctx.spawn(run_subscription(
+            receiver,
+            subscriber_id,
+            sink,
+            ForwardHandler,
+            stats,
+            self.accounting.clone(),
+        ));
+    }
 }
 
 type NewHeadNotification = Notification<ThinBlock>;
@@ -237,7 +429,8 @@ impl ActorService for PubSubService {
 
 impl ActorEventHandler<Self, NewHeadNotification> for PubSubService {
     fn handle_event(&mut self, msg: NewHeadNotification, _ctx: &mut ServiceContext<PubSubService>) {
-        send_to_all(&mut self.new_header_subscribers, msg);
+        self.head_streams.notify(msg.clone(), &self.accounting);
+        send_to_all(&mut self.new_header_subscribers, &self.accounting, msg);
     }
 }
 
@@ -247,18 +440,24 @@ impl ActorEventHandler<Self, NewEventNotification> for PubSubService {
         msg: NewEventNotification,
         _ctx: &mut ServiceContext<PubSubService>,
     ) {
-        send_to_all(&mut self.new_event_subscribers, msg);
+        self.event_streams.notify(msg.clone(), &self.accounting);
+        send_to_all(&mut self.new_event_subscribers, &self.accounting, msg);
     }
 }
 
 impl ActorEventHandler<Self, MintBlockEvent> for PubSubService {
     fn handle_event(&mut self, msg: MintBlockEvent, _ctx: &mut ServiceContext<PubSubService>) {
-        send_to_all(&mut self.mint_block_subscribers, msg);
+        send_to_all(&mut self.mint_block_subscribers, &self.accounting, msg);
     }
 }
 
 #[derive(Debug)]
-struct SubscribeNewHeads(Subscriber<pubsub::Result>);
+struct SubscribeNewHeads {
+    subscriber: Subscriber<pubsub::Result>,
+    filter: BlockFilter,
+    cursor: CursorOption,
+    owner: SubscriptionOwner,
+}
 
 impl ServiceRequest for SubscribeNewHeads {
     type Response = ();
@@ -266,22 +465,49 @@ impl ServiceRequest for SubscribeNewHeads {
 
 impl ServiceHandler<Self, SubscribeNewHeads> for PubSubService {
     fn handle(&mut self, msg: SubscribeNewHeads, ctx: &mut ServiceContext<Self>) {
-        let SubscribeNewHeads(sink) = msg;
+        let SubscribeNewHeads {
+            subscriber,
+            filter,
+            cursor,
+            owner,
+        } = msg;
+        let handler = NewHeadHandler { filter };
+        if cursor.is_enabled() {
+            return self.subscribe_resumable(
+                ctx,
+                subscriber,
+                owner,
+                pubsub::Kind::NewHeads,
+                cursor,
+                handler,
+                |service| &mut service.head_streams,
+            );
+        }
+        let (sink, subscriber_id, stats) =
+            match self.register(subscriber, owner, pubsub::Kind::NewHeads, true) {
+                Some(registered) => registered,
+                None => return,
+            };
         let (sender, receiver) = mpsc::unbounded();
-        let subscriber_id = self.next_id();
         self.new_header_subscribers
             .insert(subscriber_id.clone(), sender);
         ctx.spawn(run_subscription(
             receiver,
             subscriber_id,
             sink,
-            NewHeadHandler,
+            handler,
+            stats,
+            self.accounting.clone(),
         ));
     }
 }
 
 #[derive(Debug)]
-struct SubscribeMintBlock(Subscriber<pubsub::Result>);
+struct SubscribeMintBlock {
+    subscriber: Subscriber<pubsub::Result>,
+    filter: MintBlockFilter,
+    owner: SubscriptionOwner,
+}
 
 impl ServiceRequest for SubscribeMintBlock {
     type Response = ();
@@ -289,9 +515,17 @@ impl ServiceRequest for SubscribeMintBlock {
 
 impl ServiceHandler<Self, SubscribeMintBlock> for PubSubService {
     fn handle(&mut self, msg: SubscribeMintBlock, ctx: &mut ServiceContext<Self>) {
-        let SubscribeMintBlock(subscriber) = msg;
+        let SubscribeMintBlock {
+            subscriber,
+            filter,
+            owner,
+        } = msg;
+        let (subscriber, subscriber_id, stats) =
+            match self.register(subscriber, owner, pubsub::Kind::NewMintBlock, true) {
+                Some(registered) => registered,
+                None => return,
+            };
         let (sender, receiver) = mpsc::unbounded();
-        let subscriber_id = self.next_id();
         self.mint_block_subscribers
             .insert(subscriber_id.clone(), sender.clone());
         let miner_service = self.miner_service.clone();
@@ -300,18 +534,19 @@ impl ServiceHandler<Self, SubscribeMintBlock> for PubSubService {
             receiver,
             subscriber_id,
             subscriber,
-            NewMintBlockHandler,
+            NewMintBlockHandler::new(filter),
+            stats.clone(),
+            self.accounting.clone(),
         ));
         ctx.spawn(async move {
             match miner_service
                 .send(MinerClientSubscribeRequest::Add(subscribers_num))
                 .await
             {
-                Ok(Ok(Some(event))) => {
-                    if let Err(err) = sender.unbounded_send(event) {
-                        error!("[pubsub] Failed to send MintBlockEvent: {}", err);
-                    }
-                }
+                Ok(Ok(Some(event))) => match sender.unbounded_send(event) {
+                    Ok(()) => stats.on_sent(),
+                    Err(err) => error!("[pubsub] Failed to send MintBlockEvent: {}", err),
+                },
                 Ok(Ok(None)) => {}
                 _ => error!("[pubsub] Failed to send NewMinerClientRequest to miner service"),
             };
@@ -323,6 +558,8 @@ impl ServiceHandler<Self, SubscribeMintBlock> for PubSubService {
 struct SubscribeEvents {
     subscriber: Subscriber<pubsub::Result>,
     filter: Filter,
+    cursor: CursorOption,
+    owner: SubscriptionOwner,
 }
 
 impl ServiceRequest for SubscribeEvents {
@@ -331,16 +568,39 @@ impl ServiceRequest for SubscribeEvents {
 
 impl ServiceHandler<Self, SubscribeEvents> for PubSubService {
     fn handle(&mut self, msg: SubscribeEvents, ctx: &mut ServiceContext<Self>) {
-        let SubscribeEvents { subscriber, filter } = msg;
+        let SubscribeEvents {
+            subscriber,
+            filter,
+            cursor,
+            owner,
+        } = msg;
+        let handler = ContractEventHandler { filter };
+        if cursor.is_enabled() {
+            return self.subscribe_resumable(
+                ctx,
+                subscriber,
+                owner,
+                pubsub::Kind::Events,
+                cursor,
+                handler,
+                |service| &mut service.event_streams,
+            );
+        }
+        let (subscriber, subscriber_id, stats) =
+            match self.register(subscriber, owner, pubsub::Kind::Events, true) {
+                Some(registered) => registered,
+                None => return,
+            };
         let (sender, receiver) = mpsc::unbounded();
-        let subscriber_id = self.next_id();
         self.new_event_subscribers
             .insert(subscriber_id.clone(), sender);
         ctx.spawn(run_subscription(
             receiver,
             subscriber_id,
             subscriber,
-            ContractEventHandler { filter },
+            handler,
+            stats,
+            self.accounting.clone(),
         ));
     }
 }
@@ -348,6 +608,8 @@ impl ServiceHandler<Self, SubscribeEvents> for PubSubService {
 #[derive(Debug)]
 struct SubscribeNewPendingTxns {
     subscriber: Subscriber<pubsub::Result>,
+    filter: PendingTxnFilter,
+    owner: SubscriptionOwner,
 }
 
 impl ServiceRequest for SubscribeNewPendingTxns {
@@ -356,17 +618,37 @@ impl ServiceRequest for SubscribeNewPendingTxns {
 
 impl ServiceHandler<Self, SubscribeNewPendingTxns> for PubSubService {
     fn handle(&mut self, msg: SubscribeNewPendingTxns, ctx: &mut ServiceContext<Self>) {
-        let SubscribeNewPendingTxns { subscriber } = msg;
-        let subscriber_id = self.next_id();
+        let SubscribeNewPendingTxns {
+            subscriber,
+            filter,
+            owner,
+        } = msg;
+        // the pending txns are sent by the txpool directly, so the queued txns are not counted.
+        let (subscriber, subscriber_id, stats) = match self.register(
+            subscriber,
+            owner,
+            pubsub::Kind::NewPendingTransactions,
+            false,
+        ) {
+            Some(registered) => registered,
+            None => return,
+        };
         let tasks = self.new_pending_txn_tasks.clone();
+        let accounting = self.accounting.clone();
         let subscriber_id_clone = subscriber_id.clone();
         let receiver = self.txpool.subscribe_pending_txn();
+        let handler = TxnEventHandler {
+            txpool: self.txpool.clone(),
+            filter,
+        };
         let (f, abort_handle) = futures::future::abortable(async move {
             run_subscription(
                 receiver,
                 subscriber_id_clone.clone(),
                 subscriber,
-                TxnEventHandler,
+                handler,
+                stats,
+                accounting,
             )
             .await;
             // remove self from task list.
@@ -384,7 +666,12 @@ impl ServiceHandler<Self, SubscribeNewPendingTxns> for PubSubService {
 }
 
 #[derive(Debug)]
-struct Unsubscribe(SubscriptionId);
+struct Unsubscribe {
+    id: SubscriptionId,
+    /// Unsubscribed by the closed connection, the resumable stream of the subscription is kept
+    /// to be resumed.
+    closed: bool,
+}
 
 impl ServiceRequest for Unsubscribe {
     type Response = ();
@@ -392,41 +679,72 @@ impl ServiceRequest for Unsubscribe {
 
 impl ServiceHandler<Self, Unsubscribe> for PubSubService {
     fn handle(&mut self, msg: Unsubscribe, _ctx: &mut ServiceContext<Self>) {
-        self.new_header_subscribers.remove(&msg.0);
-        self.new_event_subscribers.remove(&msg.0);
-        self.mint_block_subscribers.remove(&msg.0);
+        let Unsubscribe { id, closed } = msg;
+        self.accounting.write().remove(&id);
+        self.new_header_subscribers.remove(&id);
+        self.new_event_subscribers.remove(&id);
+        self.head_streams.close(&id, !closed);
+        self.event_streams.close(&id, !closed);
+        self.mint_block_subscribers.remove(&id);
         self.miner_service
             .do_send(MinerClientSubscribeRequest::Remove(
                 self.mint_block_subscribers.len() as u32,
             ));
-        if let Some(h) = self.new_pending_txn_tasks.write().remove(&msg.0) {
+        if let Some(h) = self.new_pending_txn_tasks.write().remove(&id) {
             h.abort();
         }
     }
 }
 
+/// List the active subscriptions with their age and delivery lag.
+#[derive(Debug)]
+pub struct ListSubscriptions;
+
+impl ServiceRequest for ListSubscriptions {
+    type Response = Vec<SubscriptionView>;
+}
+
+impl ServiceHandler<Self, ListSubscriptions> for PubSubService {
+    fn handle(
+        &mut self,
+        _msg: ListSubscriptions,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Vec<SubscriptionView> {
+        self.accounting.read().list()
+    }
+}
+
 fn send_to_all<T: Clone>(
     subscriptions: &mut HashMap<SubscriptionId, mpsc::UnboundedSender<T>>,
+    accounting: &RwLock<SubscriptionAccounting>,
     msg: T,
 ) {
     let mut remove_outdated = vec![];
 
     for (id, ch) in subscriptions.iter() {
-        if let Err(err) = ch.unbounded_send(msg.clone()) {
-            if err.is_disconnected() {
+        match ch.unbounded_send(msg.clone()) {
+            Ok(()) => {
+                if let Some(stats) = accounting.read().stats(id) {
+                    stats.on_sent();
+                }
+            }
+            Err(err) if err.is_disconnected() => {
                 remove_outdated.push(id.clone());
-            } else if err.is_full() {
+            }
+            Err(err) if err.is_full() => {
                 log::error!(
                     "subscription {:?} fail to new messages, channel is full",
                     id
                 );
             }
+            Err(_) => {}
         }
     }
 
     // drop outdated subscribers.
     for id in remove_outdated {
         subscriptions.remove(&id);
+        accounting.write().remove(&id);
     }
 }
 
@@ -435,6 +753,8 @@ async fn run_subscription<M, Handler>(
     subscriber_id: SubscriptionId,
     subscriber: Subscriber<pubsub::Result>,
     event_handler: Handler,
+    stats: Arc<SubscriptionStats>,
+    accounting: Arc<RwLock<SubscriptionAccounting>>,
 ) where
     M: Send + 'static,
     Handler: EventHandler<M> + Send + 'static,
@@ -444,6 +764,7 @@ async fn run_subscription<M, Handler>(
         let forward = msg_channel
             .flat_map(move |m| {
                 let r = event_handler.handle(m);
+                stats.on_delivered();
                 futures::stream::iter(r.into_iter().map(Ok::<_, jsonrpc_pubsub::TransportError>))
             })
             .forward(sink)
@@ -452,45 +773,123 @@ async fn run_subscription<M, Handler>(
             log::warn!(target: "rpc", "Unable to send notification: {}", e);
         }
     }
+    // the subscription is closed by unsubscribe or the connection is closed.
+    accounting.write().remove(&subscriber_id);
 }
 
-trait EventHandler<M> {
+pub trait EventHandler<M> {
     fn handle(&self, msg: M) -> Vec<jsonrpc_core::Result<pubsub::Result>>;
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct TxnEventHandler;
+/// Forward the results of the resumable stream, which are handled and buffered by the stream.
+struct ForwardHandler;
+
+impl EventHandler<jsonrpc_core::Result<pubsub::Result>> for ForwardHandler {
+    fn handle(
+        &self,
+        msg: jsonrpc_core::Result<pubsub::Result>,
+    ) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        vec![msg]
+    }
+}
+
+#[derive(Clone)]
+pub struct TxnEventHandler {
+    txpool: TxPoolService,
+    filter: PendingTxnFilter,
+}
 
 impl EventHandler<Arc<[HashValue]>> for TxnEventHandler {
     fn handle(&self, msg: Arc<[HashValue]>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
-        vec![Ok(pubsub::Result::TransactionHash(msg.to_vec()))]
+        if self.filter.is_empty() {
+            return vec![Ok(pubsub::Result::TransactionHash(msg.to_vec()))];
+        }
+        // the txns which have left the txpool are skipped.
+        let txns: Vec<_> = msg
+            .iter()
+            .filter_map(|txn_hash| self.txpool.find_txn(txn_hash))
+            .filter(|txn| self.filter.matching(txn))
+            .collect();
+        if txns.is_empty() {
+            vec![]
+        } else if self.filter.decode {
+            vec![Ok(pubsub::Result::PendingTransactions(
+                txns.into_iter().map(Into::into).collect(),
+            ))]
+        } else {
+            vec![Ok(pubsub::Result::TransactionHash(
+                txns.iter().map(|txn| txn.id()).collect(),
+            ))]
+        }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct NewHeadHandler;
+#[derive(Clone, Debug)]
+pub struct NewHeadHandler {
+    filter: BlockFilter,
+}
 
 impl EventHandler<Notification<ThinBlock>> for NewHeadHandler {
     fn handle(&self, msg: Notification<ThinBlock>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
         let Notification(block) = msg;
+        if !self.filter.matching(&block.header) {
+            return vec![];
+        }
         vec![Ok(pubsub::Result::Block(Box::new(BlockView {
             header: block.header.into(),
             body: block.body.into(),
             uncles: vec![],
+            raw: None,
         })))]
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct NewMintBlockHandler;
+#[derive(Debug)]
+pub struct NewMintBlockHandler {
+    filter: MintBlockFilter,
+    /// The added txns of the updates skipped by the `min_added_txns` of the filter.
+    pending_added_txns: Mutex<Vec<HashValue>>,
+}
+
+impl NewMintBlockHandler {
+    pub fn new(filter: MintBlockFilter) -> Self {
+        Self {
+            filter,
+            pending_added_txns: Mutex::new(vec![]),
+        }
+    }
+}
 
 impl EventHandler<MintBlockEvent> for NewMintBlockHandler {
     fn handle(&self, msg: MintBlockEvent) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
+        let MintBlockEvent {
+            strategy,
+            minting_blob,
+            difficulty,
+            block_number,
+            added_txns,
+        } = msg;
+        let mut pending_added_txns = self.pending_added_txns.lock();
+        let added_txns = match added_txns {
+            None => {
+                pending_added_txns.clear();
+                None
+            }
+            Some(_) if !self.filter.updates => return vec![],
+            Some(added_txns) => {
+                pending_added_txns.extend(added_txns);
+                if (pending_added_txns.len() as u64) < self.filter.min_added_txns.unwrap_or(1) {
+                    return vec![];
+                }
+                Some(std::mem::take(&mut *pending_added_txns))
+            }
+        };
         vec![Ok(pubsub::Result::MintBlock(Box::new(MintBlock {
-            strategy: msg.strategy,
-            minting_blob: hex::encode(msg.minting_blob),
-            difficulty: msg.difficulty,
-            block_number: msg.block_number,
+            strategy,
+            minting_blob: hex::encode(minting_blob),
+            difficulty,
+            block_number,
+            added_txns,
         })))]
     }
 }
@@ -503,10 +902,10 @@ pub struct ContractEventHandler {
 impl EventHandler<Notification<Arc<[Event]>>> for ContractEventHandler {
     fn handle(&self, msg: Notification<Arc<[Event]>>) -> Vec<jsonrpc_core::Result<pubsub::Result>> {
         let Notification(events) = msg;
-        let filtered = events
-            .as_ref()
-            .iter()
-            .filter(|e| self.filter.matching(e.block_number, &e.contract_event));
+        let filtered = events.as_ref().iter().filter(|e| {
+            self.filter
+                .matching(e.block_number, e.sender, &e.contract_event)
+        });
         let filtered_events: Vec<_> = match self.filter.limit {
             None => filtered.collect(),
             Some(l) => {
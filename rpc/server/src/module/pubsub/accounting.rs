@@ -0,0 +1,193 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpc_pubsub::SubscriptionId;
+use starcoin_rpc_api::errors::RpcErrorCode;
+use starcoin_rpc_api::metadata::Metadata;
+use starcoin_rpc_api::types::pubsub::{Kind, SubscriptionView};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The connection which creates a subscription, the limits are counted by connection and by ip.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubscriptionOwner {
+    pub connection_id: Option<u64>,
+    pub ip: Option<String>,
+}
+
+impl SubscriptionOwner {
+    pub fn from_metadata(meta: &Metadata) -> Self {
+        Self {
+            connection_id: meta.connection_id,
+            ip: meta.user.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SubscriptionLimits {
+    pub max_per_connection: usize,
+    pub max_per_ip: usize,
+}
+
+/// The delivery counters of a subscription, updated by the subscription task.
+#[derive(Debug, Default)]
+pub struct SubscriptionStats {
+    sent: AtomicU64,
+    delivered: AtomicU64,
+    last_delivered_at: AtomicU64,
+}
+
+impl SubscriptionStats {
+    pub fn on_sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn on_delivered(&self) {
+        self.delivered.fetch_add(1, Ordering::Relaxed);
+        self.last_delivered_at
+            .store(now_millis(), Ordering::Relaxed);
+    }
+}
+
+struct SubscriptionEntry {
+    kind: Kind,
+    owner: SubscriptionOwner,
+    created_at: u64,
+    /// Whether the messages sent to the subscription are counted.
+    track_sent: bool,
+    stats: Arc<SubscriptionStats>,
+}
+
+/// Track the active subscriptions of the pubsub service and enforce the limits.
+pub struct SubscriptionAccounting {
+    limits: SubscriptionLimits,
+    subscriptions: HashMap<SubscriptionId, SubscriptionEntry>,
+}
+
+impl SubscriptionAccounting {
+    pub fn new(limits: SubscriptionLimits) -> Self {
+        Self {
+            limits,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    /// Check whether the owner can create a new subscription.
+    pub fn check(&self, owner: &SubscriptionOwner) -> Result<(), jsonrpc_core::Error> {
+        if let Some(connection_id) = owner.connection_id {
+            let count = self
+                .subscriptions
+                .values()
+                .filter(|entry| entry.owner.connection_id == Some(connection_id))
+                .count();
+            if count >= self.limits.max_per_connection {
+                return Err(RpcErrorCode::SubscriptionLimitExceeded.to_error(
+                    format!(
+                        "too many subscriptions of the connection, the limit is {}",
+                        self.limits.max_per_connection
+                    ),
+                    None,
+                ));
+            }
+        }
+        if let Some(ip) = owner.ip.as_ref() {
+            let count = self
+                .subscriptions
+                .values()
+                .filter(|entry| entry.owner.ip.as_ref() == Some(ip))
+                .count();
+            if count >= self.limits.max_per_ip {
+                return Err(RpcErrorCode::SubscriptionLimitExceeded.to_error(
+                    format!(
+                        "too many subscriptions of the ip {}, the limit is {}",
+                        ip, self.limits.max_per_ip
+                    ),
+                    None,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add(
+        &mut self,
+        id: SubscriptionId,
+        kind: Kind,
+        owner: SubscriptionOwner,
+        track_sent: bool,
+    ) -> Arc<SubscriptionStats> {
+        let stats = Arc::new(SubscriptionStats::default());
+        self.subscriptions.insert(
+            id,
+            SubscriptionEntry {
+                kind,
+                owner,
+                created_at: now_millis(),
+                track_sent,
+                stats: stats.clone(),
+            },
+        );
+        stats
+    }
+
+    pub fn remove(&mut self, id: &SubscriptionId) -> bool {
+        self.subscriptions.remove(id).is_some()
+    }
+
+    pub fn stats(&self, id: &SubscriptionId) -> Option<Arc<SubscriptionStats>> {
+        self.subscriptions.get(id).map(|entry| entry.stats.clone())
+    }
+
+    /// List the active subscriptions, sorted by created time.
+    pub fn list(&self) -> Vec<SubscriptionView> {
+        let now = now_millis();
+        let mut entries: Vec<_> = self.subscriptions.iter().collect();
+        entries.sort_by_key(|(id, entry)| {
+            let seq = match id {
+                SubscriptionId::Number(id) => *id,
+                SubscriptionId::String(_) => u64::MAX,
+            };
+            (entry.created_at, seq)
+        });
+        entries
+            .into_iter()
+            .map(|(id, entry)| {
+                let sent = entry.stats.sent.load(Ordering::Relaxed);
+                let delivered = entry.stats.delivered.load(Ordering::Relaxed);
+                let last_delivered_at = entry.stats.last_delivered_at.load(Ordering::Relaxed);
+                SubscriptionView {
+                    id: match id {
+                        SubscriptionId::Number(id) => id.to_string(),
+                        SubscriptionId::String(id) => id.clone(),
+                    },
+                    kind: entry.kind.clone(),
+                    connection_id: entry.owner.connection_id,
+                    ip: entry.owner.ip.clone(),
+                    created_at: entry.created_at,
+                    age_secs: now.saturating_sub(entry.created_at) / 1000,
+                    delivered,
+                    delivery_lag: if entry.track_sent {
+                        Some(sent.saturating_sub(delivered))
+                    } else {
+                        None
+                    },
+                    last_delivered_at: if last_delivered_at > 0 {
+                        Some(last_delivered_at)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,250 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::accounting::SubscriptionAccounting;
+use super::EventHandler;
+use crate::module::to_invalid_param_err;
+use anyhow::format_err;
+use futures::channel::mpsc;
+use jsonrpc_pubsub::SubscriptionId;
+use parking_lot::RwLock;
+use starcoin_rpc_api::types::pubsub::{self, CursoredNotification, NotificationCursor};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The streams detached by the closed connections are kept for the reconnections in the time.
+const DETACHED_STREAM_TTL: Duration = Duration::from_secs(300);
+/// The max detached streams kept, the oldest detached are dropped first.
+const MAX_DETACHED_STREAMS: usize = 1024;
+
+/// The latest notifications of a stream with their sequences, the subscriptions resumed
+/// from a cursor replay the buffered notifications after it.
+#[derive(Debug)]
+pub struct NotificationBacklog<T> {
+    capacity: usize,
+    /// The cursor of the next notification, the cursors start from 1.
+    next_cursor: u64,
+    notifications: VecDeque<(u64, T)>,
+}
+
+impl<T: Clone> NotificationBacklog<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            next_cursor: 1,
+            notifications: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Buffer the notification, return it with the allocated cursor.
+    pub fn push(&mut self, notification: T) -> (u64, T) {
+        let cursor = self.next_cursor;
+        self.next_cursor += 1;
+        if self.capacity > 0 {
+            if self.notifications.len() == self.capacity {
+                self.notifications.pop_front();
+            }
+            self.notifications.push_back((cursor, notification.clone()));
+        }
+        (cursor, notification)
+    }
+
+    /// The buffered notifications after the cursor, fail if some of them are evicted,
+    /// or the cursor is not allocated, such as the cursor before a restart of the node.
+    pub fn after(&self, cursor: u64) -> Result<Vec<(u64, T)>, jsonrpc_core::Error> {
+        if cursor >= self.next_cursor {
+            return Err(to_invalid_param_err(format_err!(
+                "Unknown cursor seq {}, the latest seq is {}",
+                cursor,
+                self.next_cursor - 1
+            )));
+        }
+        let oldest = self
+            .notifications
+            .front()
+            .map(|(cursor, _)| *cursor)
+            .unwrap_or(self.next_cursor);
+        if cursor + 1 < oldest {
+            return Err(to_invalid_param_err(format_err!(
+                "The notifications after cursor {} are evicted, the oldest buffered cursor is {}",
+                cursor,
+                oldest
+            )));
+        }
+        Ok(self
+            .notifications
+            .iter()
+            .filter(|(c, _)| *c > cursor)
+            .cloned()
+            .collect())
+    }
+}
+
+pub type StreamSender = mpsc::UnboundedSender<jsonrpc_core::Result<pubsub::Result>>;
+
+/// A resumable subscription, the notifications matched by its filter are buffered, and sent to
+/// the subscription attached to it. The stream is detached when the connection is closed, and
+/// attached again by the subscription resumed from its cursor.
+struct ResumableStream<M> {
+    handler: Box<dyn EventHandler<M> + Send>,
+    backlog: NotificationBacklog<pubsub::Result>,
+    attached: Option<(SubscriptionId, StreamSender)>,
+    detached_at: Option<Instant>,
+}
+
+impl<M> ResumableStream<M> {
+    fn detach(&mut self) {
+        self.attached = None;
+        self.detached_at = Some(Instant::now());
+    }
+}
+
+/// The resumable streams of a subscription kind.
+pub struct ResumableStreams<M> {
+    epoch: u64,
+    capacity: usize,
+    streams: HashMap<u64, ResumableStream<M>>,
+}
+
+impl<M: Clone> ResumableStreams<M> {
+    /// Create the streams with the `epoch` of the cursors, and buffer the latest `capacity`
+    /// notifications of each stream.
+    pub fn new(epoch: u64, capacity: usize) -> Self {
+        Self {
+            epoch,
+            capacity,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Open the `stream` attached to the subscription.
+    pub fn open(
+        &mut self,
+        stream: u64,
+        handler: Box<dyn EventHandler<M> + Send>,
+        subscription: SubscriptionId,
+        sender: StreamSender,
+    ) {
+        self.streams.insert(
+            stream,
+            ResumableStream {
+                handler,
+                backlog: NotificationBacklog::new(self.capacity),
+                attached: Some((subscription, sender)),
+                detached_at: None,
+            },
+        );
+    }
+
+    /// The buffered notifications after the cursor to replay, fail if the cursor is of another
+    /// epoch, its stream is expired, or some notifications after it are evicted.
+    pub fn replay(
+        &self,
+        cursor: &NotificationCursor,
+    ) -> Result<Vec<pubsub::Result>, jsonrpc_core::Error> {
+        if cursor.epoch != self.epoch {
+            return Err(to_invalid_param_err(format_err!(
+                "The cursor epoch {} mismatches the current epoch {}, the node has restarted",
+                cursor.epoch,
+                self.epoch
+            )));
+        }
+        let stream = self.streams.get(&cursor.stream).ok_or_else(|| {
+            to_invalid_param_err(format_err!(
+                "Unknown or expired cursor stream {}",
+                cursor.stream
+            ))
+        })?;
+        Ok(stream
+            .backlog
+            .after(cursor.seq)?
+            .into_iter()
+            .map(|(seq, result)| cursored(self.epoch, cursor.stream, seq, result))
+            .collect())
+    }
+
+    /// Attach the stream to the subscription resumed from it, the subscription attached before
+    /// is closed.
+    pub fn attach(&mut self, stream: u64, subscription: SubscriptionId, sender: StreamSender) {
+        if let Some(stream) = self.streams.get_mut(&stream) {
+            stream.attached = Some((subscription, sender));
+            stream.detached_at = None;
+        }
+    }
+
+    /// Detach the stream of the subscription whose connection is closed, or remove it if the
+    /// subscription is unsubscribed.
+    pub fn close(&mut self, subscription: &SubscriptionId, unsubscribed: bool) {
+        let stream = self
+            .streams
+            .iter()
+            .find_map(|(id, stream)| match &stream.attached {
+                Some((attached, _)) if attached == subscription => Some(*id),
+                _ => None,
+            });
+        if let Some(stream) = stream {
+            if unsubscribed {
+                self.streams.remove(&stream);
+            } else if let Some(stream) = self.streams.get_mut(&stream) {
+                stream.detach();
+            }
+        }
+    }
+
+    /// Buffer the results of the message of every stream, and send them to the attached
+    /// subscriptions, the streams detached for too long are dropped.
+    pub fn notify(&mut self, msg: M, accounting: &RwLock<SubscriptionAccounting>) {
+        self.remove_expired();
+        let epoch = self.epoch;
+        for (id, stream) in self.streams.iter_mut() {
+            for result in stream.handler.handle(msg.clone()) {
+                let result = result.map(|result| {
+                    let (seq, result) = stream.backlog.push(result);
+                    cursored(epoch, *id, seq, result)
+                });
+                let sent = match &stream.attached {
+                    Some((subscription, sender)) => {
+                        if sender.unbounded_send(result).is_ok() {
+                            if let Some(stats) = accounting.read().stats(subscription) {
+                                stats.on_sent();
+                            }
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    None => true,
+                };
+                // the subscription task is closed.
+                if !sent {
+                    stream.detach();
+                }
+            }
+        }
+    }
+
+    fn remove_expired(&mut self) {
+        self.streams.retain(|_, stream| match stream.detached_at {
+            Some(detached_at) => detached_at.elapsed() < DETACHED_STREAM_TTL,
+            None => true,
+        });
+        let mut detached: Vec<_> = self
+            .streams
+            .iter()
+            .filter_map(|(id, stream)| stream.detached_at.map(|at| (at, *id)))
+            .collect();
+        if detached.len() > MAX_DETACHED_STREAMS {
+            detached.sort();
+            for (_, id) in &detached[..detached.len() - MAX_DETACHED_STREAMS] {
+                self.streams.remove(id);
+            }
+        }
+    }
+}
+
+fn cursored(epoch: u64, stream: u64, seq: u64, result: pubsub::Result) -> pubsub::Result {
+    pubsub::Result::Cursored(Box::new(CursoredNotification {
+        cursor: NotificationCursor { epoch, stream, seq }.into(),
+        result,
+    }))
+}
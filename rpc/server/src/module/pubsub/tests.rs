@@ -1,23 +1,29 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::{PubSubImpl, PubSubService, PubSubServiceFactory};
+use super::backlog::NotificationBacklog;
+use crate::extractors::{WsExtractor, WsRequestMiddleware, UNKNOWN_WS_CLIENT_IP};
+use crate::module::{ListSubscriptions, PubSubImpl, PubSubService, PubSubServiceFactory};
 use anyhow::Result;
 use futures::StreamExt;
 use jsonrpc_core::{futures, MetaIoHandler};
 use jsonrpc_pubsub::Session;
+use jsonrpc_ws_server::ws;
 use serde_json::Value;
 use starcoin_account_api::AccountInfo;
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
 use starcoin_chain_notify::ChainNotifyHandlerService;
+use starcoin_config::get_random_available_port;
 use starcoin_consensus::Consensus;
-use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, PrivateKey};
+use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, HashValue, PrivateKey};
 use starcoin_executor::DEFAULT_EXPIRATION_TIME;
 use starcoin_logger::prelude::*;
+use starcoin_rpc_api::errors::RpcErrorCode;
 use starcoin_rpc_api::metadata::Metadata;
 use starcoin_rpc_api::pubsub::StarcoinPubSub;
-use starcoin_rpc_api::types::pubsub::MintBlock;
+use starcoin_rpc_api::types::pubsub::{Kind, MintBlock, NotificationCursor};
+use starcoin_rpc_api::types::StrView;
 use starcoin_service_registry::bus::{Bus, BusService};
 use starcoin_service_registry::RegistryAsyncService;
 use starcoin_state_api::StateReaderExt;
@@ -150,11 +156,14 @@ pub async fn test_subscribe_to_pending_transactions() -> Result<()> {
     let (sender, receiver) = futures::channel::mpsc::unbounded();
     metadata.session = Some(Arc::new(Session::new(sender)));
 
-    // Fail if params are provided
-    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newPendingTransactions"}, {}], "id": 1}"#;
-    let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: newPendingTransactions","data":"\"Expected no parameters.\""},"id":1}"#;
-    let resp = io.handle_request(request, metadata.clone()).await;
-    assert_eq!(resp, Some(response.to_owned()));
+    // Fail if the params is not a pending txn filter
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newPendingTransactions"}, {"from_block": 1}], "id": 1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await.unwrap();
+    assert!(
+        resp.contains("Couldn't parse parameters: newPendingTransactions"),
+        "unexpected response: {}",
+        resp
+    );
 
     // Subscribe
     let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newPendingTransactions"}], "id": 1}"#;
@@ -162,9 +171,27 @@ pub async fn test_subscribe_to_pending_transactions() -> Result<()> {
     let resp = io.handle_request(request, metadata.clone()).await;
     assert_eq!(resp, Some(response.to_owned()));
 
+    // Subscribe the txns of other sender, which is never notified.
+    let request = format!(
+        r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{{"type_name":"newPendingTransactions"}}, {{"senders": ["{}"]}}], "id": 1}}"#,
+        AccountInfo::random().address
+    );
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+    let resp = io.handle_request(request.as_str(), metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    // Subscribe the decoded txns transferring to the account.
+    let account = AccountInfo::random();
+    let request = format!(
+        r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{{"type_name":"newPendingTransactions"}}, {{"receivers": ["{}"], "decode": true}}], "id": 1}}"#,
+        account.address
+    );
+    let response = r#"{"jsonrpc":"2.0","result":2,"id":1}"#;
+    let resp = io.handle_request(request.as_str(), metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
     // Send new transactions
     let txn = {
-        let account = AccountInfo::random();
         let txn = starcoin_executor::build_transfer_from_association(
             account.address,
             Some(account.public_key.authentication_key()),
@@ -178,14 +205,37 @@ pub async fn test_subscribe_to_pending_transactions() -> Result<()> {
     let txn_id = txn.id();
     txpool_service.add_txns(vec![txn]).pop().unwrap().unwrap();
     let mut receiver = receiver;
-    let res = receiver.next().await.unwrap();
+    let mut notifications = vec![
+        receiver.next().await.unwrap(),
+        receiver.next().await.unwrap(),
+    ];
+    notifications.sort();
     let prefix = r#"{"jsonrpc":"2.0","method":"starcoin_subscription","params":{"subscription":0,"result":[""#;
     let suffix = r#""]}}"#;
     let response = format!("{}0x{}{}", prefix, txn_id.to_hex(), suffix);
-    assert_eq!(res, response);
+    assert_eq!(notifications[0], response);
+    let decoded: Value = serde_json::from_str(notifications[1].as_str())?;
+    assert_eq!(decoded["params"]["subscription"], 2);
+    let txn_view = &decoded["params"]["result"][0];
+    assert_eq!(
+        txn_view["transaction_hash"],
+        Value::String(format!("0x{}", txn_id.to_hex()))
+    );
+    assert_eq!(
+        txn_view["transfers"][0]["receiver"],
+        serde_json::to_value(account.address)?
+    );
+    assert_eq!(txn_view["transfers"][0]["amount"], "10000");
     // And unsubscribe
-    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_unsubscribe", "params": [0], "id": 1}"#;
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_unsubscribe", "params": [2], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_unsubscribe", "params": [1], "id": 1}"#;
     let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_unsubscribe", "params": [0], "id": 1}"#;
     let resp = io.handle_request(request, metadata).await;
     assert_eq!(resp, Some(response.to_owned()));
 
@@ -237,3 +287,415 @@ pub async fn test_subscribe_to_mint_block() -> Result<()> {
     assert_eq!(resp, Some(response.to_owned()));
     Ok(())
 }
+
+async fn next_mint_block(
+    receiver: &mut futures::channel::mpsc::UnboundedReceiver<String>,
+) -> Result<MintBlock> {
+    let res = timeout(Duration::from_secs(1), receiver.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Empty value"))?;
+    let r: Value = serde_json::from_str(&res)?;
+    Ok(serde_json::from_value(r["params"]["result"].clone())?)
+}
+
+#[stest::test]
+pub async fn test_subscribe_to_mint_block_updates() -> Result<()> {
+    let (_txpool_service, .., registry) = test_helper::start_txpool().await;
+    let bus = registry.service_ref::<BusService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let pubsub = PubSubImpl::new(service);
+    let pubsub = pubsub.to_delegate();
+
+    let mut io = MetaIoHandler::default();
+    io.extend_with(pubsub);
+
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newMintBlock"}, {"updates": true, "min_added_txns": 2}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    let diff = U256::from(1024);
+    bus.broadcast(MintBlockEvent::new(
+        ConsensusStrategy::Dummy,
+        vec![0u8; 76],
+        diff,
+        1,
+    ))?;
+    let mint_block = next_mint_block(&mut receiver).await?;
+    assert_eq!(mint_block.added_txns, None);
+
+    // the first update is skipped by min_added_txns, and merged into the next one.
+    let added_txns = vec![HashValue::random(), HashValue::random()];
+    bus.broadcast(
+        MintBlockEvent::new(ConsensusStrategy::Dummy, vec![1u8; 76], diff, 1)
+            .with_added_txns(added_txns[..1].to_vec()),
+    )?;
+    bus.broadcast(
+        MintBlockEvent::new(ConsensusStrategy::Dummy, vec![2u8; 76], diff, 1)
+            .with_added_txns(added_txns[1..].to_vec()),
+    )?;
+    let mint_block = next_mint_block(&mut receiver).await?;
+    assert_eq!(&mint_block.minting_blob, &hex::encode(vec![2u8; 76]));
+    assert_eq!(mint_block.added_txns, Some(added_txns));
+
+    // the subscription without updates only gets the new templates.
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newMintBlock"}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    let mut metadata = Metadata::default();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+    let resp = io.handle_request(request, metadata).await;
+    assert_eq!(resp, Some(response.to_owned()));
+    bus.broadcast(
+        MintBlockEvent::new(ConsensusStrategy::Dummy, vec![3u8; 76], diff, 1)
+            .with_added_txns(vec![HashValue::random()]),
+    )?;
+    bus.broadcast(MintBlockEvent::new(
+        ConsensusStrategy::Dummy,
+        vec![4u8; 76],
+        diff,
+        2,
+    ))?;
+    let mint_block = next_mint_block(&mut receiver).await?;
+    assert_eq!(mint_block.block_number, 2);
+    assert_eq!(mint_block.added_txns, None);
+    Ok(())
+}
+
+#[stest::test]
+pub async fn test_subscription_limits() -> Result<()> {
+    let (_txpool_service, _, config, _, registry) = test_helper::start_txpool().await;
+    let mut config = config.as_ref().clone();
+    config.rpc.ws.max_subscriptions_per_connection = Some(1);
+    config.rpc.ws.max_subscriptions_per_ip = Some(2);
+    registry.put_shared(Arc::new(config)).await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let pubsub = PubSubImpl::new(service.clone());
+    let pubsub = pubsub.to_delegate();
+
+    let mut io = MetaIoHandler::default();
+    io.extend_with(pubsub);
+
+    let mut receivers = vec![];
+    let mut connect = |connection_id: u64| {
+        let mut metadata = Metadata::default();
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        metadata.session = Some(Arc::new(Session::new(sender)));
+        metadata.connection_id = Some(connection_id);
+        metadata.user = Some("127.0.0.1".to_string());
+        receivers.push(receiver);
+        metadata
+    };
+    let connections = vec![connect(0), connect(1), connect(2)];
+
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newHeads"}], "id": 1}"#;
+    let subscribe = |metadata: &Metadata| {
+        let fut = io.handle_request(request, metadata.clone());
+        async move {
+            let resp: Value = serde_json::from_str(fut.await.unwrap().as_str()).unwrap();
+            resp
+        }
+    };
+    let limit_exceeded = Value::from(i64::from(RpcErrorCode::SubscriptionLimitExceeded));
+
+    let resp = subscribe(&connections[0]).await;
+    assert_eq!(resp["result"], Value::from(0));
+    // over the limit of the connection.
+    let resp = subscribe(&connections[0]).await;
+    assert_eq!(resp["error"]["code"], limit_exceeded);
+    let resp = subscribe(&connections[1]).await;
+    assert_eq!(resp["result"], Value::from(1));
+    // over the limit of the ip.
+    let resp = subscribe(&connections[2]).await;
+    assert_eq!(resp["error"]["code"], limit_exceeded);
+
+    let subscriptions = service.send(ListSubscriptions).await?;
+    assert_eq!(subscriptions.len(), 2);
+    assert_eq!(subscriptions[0].kind, Kind::NewHeads);
+    assert_eq!(subscriptions[0].connection_id, Some(0));
+    assert_eq!(subscriptions[0].ip.as_deref(), Some("127.0.0.1"));
+    assert_eq!(subscriptions[0].delivery_lag, Some(0));
+
+    // the quota is released after unsubscribe.
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_unsubscribe", "params": [0], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+    let resp = io.handle_request(request, connections[0].clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+    let resp = subscribe(&connections[2]).await;
+    assert_eq!(resp["result"], Value::from(2));
+
+    let subscriptions = service.send(ListSubscriptions).await?;
+    assert_eq!(
+        subscriptions
+            .iter()
+            .map(|subscription| subscription.connection_id)
+            .collect::<Vec<_>>(),
+        vec![Some(1), Some(2)]
+    );
+    Ok(())
+}
+
+/// A ws client which sends the ip header in the handshake if the ip is set, and subscribes the
+/// new heads when open.
+struct WsSubscribeClient {
+    out: ws::Sender,
+    ip: Option<String>,
+    responses: futures::channel::mpsc::UnboundedSender<Value>,
+}
+
+impl ws::Handler for WsSubscribeClient {
+    fn build_request(&mut self, url: &url::Url) -> ws::Result<ws::Request> {
+        let mut request = ws::Request::from_url(url)?;
+        if let Some(ip) = self.ip.as_ref() {
+            request
+                .headers_mut()
+                .push(("X-Real-IP".to_string(), ip.clone().into_bytes()));
+        }
+        Ok(request)
+    }
+
+    fn on_open(&mut self, _shake: ws::Handshake) -> ws::Result<()> {
+        self.out.send(
+            r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newHeads"}], "id": 1}"#,
+        )
+    }
+
+    fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
+        let resp: Value = serde_json::from_str(msg.as_text()?).unwrap();
+        let _ = self.responses.unbounded_send(resp);
+        Ok(())
+    }
+}
+
+#[stest::test]
+pub async fn test_subscription_ip_limit_over_websocket() -> Result<()> {
+    let (_txpool_service, _, config, _, registry) = test_helper::start_txpool().await;
+    let mut config = config.as_ref().clone();
+    config.rpc.ws.max_subscriptions_per_connection = Some(1);
+    config.rpc.ws.max_subscriptions_per_ip = Some(2);
+    registry.put_shared(Arc::new(config)).await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let pubsub = PubSubImpl::new(service.clone());
+    let mut io = MetaIoHandler::default();
+    io.extend_with(pubsub.to_delegate());
+
+    let address = format!("127.0.0.1:{}", get_random_available_port()).parse()?;
+    let _server = jsonrpc_ws_server::ServerBuilder::new(io)
+        .session_meta_extractor(WsExtractor)
+        .request_middleware(WsRequestMiddleware {
+            ip_headers: vec!["X-Real-IP".to_string()],
+            api_key_header: None,
+        })
+        .start(&address)?;
+
+    let (sender, mut responses) = futures::channel::mpsc::unbounded();
+    let mut subscribe = |ip: Option<&str>| {
+        let url = format!("ws://{}", address);
+        let ip = ip.map(|ip| ip.to_string());
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            let _ = ws::connect(url, |out| WsSubscribeClient {
+                out,
+                ip: ip.clone(),
+                responses: sender.clone(),
+            });
+        });
+        async {
+            timeout(Duration::from_secs(10), responses.next())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+    };
+    let limit_exceeded = Value::from(i64::from(RpcErrorCode::SubscriptionLimitExceeded));
+
+    // every connection subscribes once, the third connection of the ip is over the ip limit.
+    assert!(subscribe(Some("10.0.0.1")).await["result"].is_number());
+    assert!(subscribe(Some("10.0.0.1")).await["result"].is_number());
+    assert_eq!(
+        subscribe(Some("10.0.0.1")).await["error"]["code"],
+        limit_exceeded
+    );
+    assert!(subscribe(Some("10.0.0.2")).await["result"].is_number());
+    // the connections without the ip headers share the limit of the unknown ip.
+    assert!(subscribe(None).await["result"].is_number());
+    assert!(subscribe(None).await["result"].is_number());
+    assert_eq!(subscribe(None).await["error"]["code"], limit_exceeded);
+
+    let subscriptions = service.send(ListSubscriptions).await?;
+    assert_eq!(subscriptions.len(), 5);
+    assert_eq!(
+        subscriptions
+            .iter()
+            .filter(|subscription| subscription.ip.as_deref() == Some("10.0.0.1"))
+            .count(),
+        2
+    );
+    assert_eq!(
+        subscriptions
+            .iter()
+            .filter(|subscription| subscription.ip.as_deref() == Some(UNKNOWN_WS_CLIENT_IP))
+            .count(),
+        2
+    );
+    Ok(())
+}
+
+#[stest::test]
+pub async fn test_resume_new_heads_from_cursor() -> Result<()> {
+    let (_txpool_service, storage, config, _, registry) = test_helper::start_txpool().await;
+    let startup_info = storage.get_startup_info()?.unwrap();
+    let net = config.net();
+    let mut block_chain = BlockChain::new(net.time_service(), startup_info.main, storage)?;
+    let miner_account = AccountInfo::random();
+    let mut new_block = || -> Result<_> {
+        let (block_template, _) = block_chain.create_block_template(
+            *miner_account.address(),
+            Some(miner_account.public_key.authentication_key()),
+            None,
+            vec![],
+            vec![],
+            None,
+        )?;
+        let block = block_chain
+            .consensus()
+            .create_block(block_template, net.time_service().as_ref())?;
+        block_chain.apply(block)
+    };
+    let first_block = new_block()?;
+    let second_block = new_block()?;
+
+    let bus = registry.service_ref::<BusService>().await?;
+    let _notify_service = registry.register::<ChainNotifyHandlerService>().await?;
+    let service = registry
+        .register_by_factory::<PubSubService, PubSubServiceFactory>()
+        .await?;
+    let pubsub = PubSubImpl::new(service).to_delegate();
+    let mut io = MetaIoHandler::default();
+    io.extend_with(pubsub);
+
+    let mut metadata = Metadata::default();
+    let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+    metadata.session = Some(Arc::new(Session::new(sender)));
+
+    // The pending txns can not be notified with cursors.
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newPendingTransactions"}, {"with_cursor": true}], "id": 1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await.unwrap();
+    assert!(
+        resp.contains("Couldn't parse parameters: cursor"),
+        "unexpected response: {}",
+        resp
+    );
+
+    let request = r#"{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{"type_name":"newHeads"}, {"with_cursor": true}], "id": 1}"#;
+    let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+    let resp = io.handle_request(request, metadata.clone()).await;
+    assert_eq!(resp, Some(response.to_owned()));
+
+    bus.broadcast(NewHeadBlock(Arc::new(first_block)))?;
+    let res = timeout(Duration::from_secs(5), receiver.next())
+        .await?
+        .unwrap();
+    let notification: Value = serde_json::from_str(res.as_str())?;
+    let result = &notification["params"]["result"];
+    assert_eq!(result["result"]["header"]["number"], "1");
+    let cursor: StrView<NotificationCursor> = serde_json::from_value(result["cursor"].clone())?;
+    assert_eq!(cursor.0.seq, 1);
+
+    // The connection is closed, the notifications after it are buffered by the stream.
+    drop(metadata);
+    drop(receiver);
+    bus.broadcast(NewHeadBlock(Arc::new(second_block)))?;
+    tokio::time::delay_for(Duration::from_millis(500)).await;
+
+    // A new connection resumes from the cursor of the last received notification.
+    let mut resumed_metadata = Metadata::default();
+    let (sender, mut resumed_receiver) = futures::channel::mpsc::unbounded();
+    resumed_metadata.session = Some(Arc::new(Session::new(sender)));
+    let subscribe = |cursor: NotificationCursor| {
+        format!(
+            r#"{{"jsonrpc": "2.0", "method": "starcoin_subscribe", "params": [{{"type_name":"newHeads"}}, {{"resume_from": "{}"}}], "id": 1}}"#,
+            StrView(cursor)
+        )
+    };
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+    let resp = io
+        .handle_request(&subscribe(cursor.0), resumed_metadata.clone())
+        .await;
+    assert_eq!(resp, Some(response.to_owned()));
+    let res = timeout(Duration::from_secs(5), resumed_receiver.next())
+        .await?
+        .unwrap();
+    let resumed: Value = serde_json::from_str(res.as_str())?;
+    assert_eq!(resumed["params"]["subscription"], 1);
+    let result = &resumed["params"]["result"];
+    assert_eq!(result["result"]["header"]["number"], "2");
+    let resumed_cursor: StrView<NotificationCursor> =
+        serde_json::from_value(result["cursor"].clone())?;
+    assert_eq!(resumed_cursor.0, NotificationCursor { seq: 2, ..cursor.0 });
+
+    let assert_rejected = |resp: Option<String>, expect: &str| {
+        let resp = resp.unwrap();
+        assert!(resp.contains(expect), "unexpected response: {}", resp);
+    };
+    // The cursor is not allocated yet.
+    let resp = io
+        .handle_request(
+            &subscribe(NotificationCursor { seq: 3, ..cursor.0 }),
+            resumed_metadata.clone(),
+        )
+        .await;
+    assert_rejected(resp, "Unknown cursor seq 3");
+    // The cursor of another stream.
+    let resp = io
+        .handle_request(
+            &subscribe(NotificationCursor {
+                stream: cursor.0.stream + 1,
+                ..cursor.0
+            }),
+            resumed_metadata.clone(),
+        )
+        .await;
+    assert_rejected(resp, "Unknown or expired cursor stream");
+    // The cursor before the node restarts.
+    let resp = io
+        .handle_request(
+            &subscribe(NotificationCursor {
+                epoch: cursor.0.epoch - 1,
+                ..cursor.0
+            }),
+            resumed_metadata,
+        )
+        .await;
+    assert_rejected(resp, "mismatches the current epoch");
+    Ok(())
+}
+
+#[test]
+fn test_notification_backlog() {
+    let mut backlog = NotificationBacklog::new(2);
+    assert_eq!(backlog.push("a"), (1, "a"));
+    assert_eq!(backlog.after(0).unwrap(), vec![(1, "a")]);
+    backlog.push("b");
+    backlog.push("c");
+    assert_eq!(backlog.after(1).unwrap(), vec![(2, "b"), (3, "c")]);
+    assert!(backlog.after(3).unwrap().is_empty());
+    // the notification of cursor 1 is evicted.
+    assert!(backlog.after(0).is_err());
+    assert!(backlog.after(4).is_err());
+
+    let mut backlog = NotificationBacklog::new(0);
+    backlog.push("a");
+    assert!(backlog.after(1).unwrap().is_empty());
+    assert!(backlog.after(0).is_err());
+}
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::map_err;
+use crate::module::{map_err, to_invalid_param_err};
 use bcs_ext::BCSCodec;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
@@ -9,38 +9,73 @@ use starcoin_crypto::HashValue;
 use starcoin_resource_viewer::MoveValueAnnotator;
 use starcoin_rpc_api::state::StateApi;
 use starcoin_rpc_api::types::{
-    AccountStateSetView, AnnotatedMoveStructView, StateWithProofView, StrView, StructTagView,
+    AccountStateAtView, AccountStateSetView, AnnotatedMoveStructView, ListResourceView,
+    StateWithProofView, StrView, StructTagFilterView, StructTagView, TypeTagView,
 };
 use starcoin_rpc_api::FutureResult;
-use starcoin_state_api::ChainStateAsyncService;
-use starcoin_state_tree::StateNodeStore;
+use starcoin_state_api::{ChainStateAsyncService, ChainStateReader, StateReaderExt};
+use starcoin_state_tree::{StateNodeStore, StateTree};
 use starcoin_statedb::ChainStateDB;
+use starcoin_storage::archive::AccountStateIndex;
 use starcoin_types::{
     access_path::AccessPath, account_address::AccountAddress, account_state::AccountState,
+    block::BlockNumber,
 };
+use starcoin_vm_types::account_config::BalanceResource;
 use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::language_storage::StructTag;
+use starcoin_vm_types::state_view::StateView;
+use starcoin_vm_types::token::stc::stc_type_tag;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+/// The max count of the resources returned by `state.list_resources` at once.
+const MAX_LIST_RESOURCES_LIMIT: usize = 100;
+/// The max count of the addresses queried by `state.get_balances` and `state.get_resources` at once.
+const MAX_BULK_QUERY_ADDRESSES: usize = 1000;
+
+fn check_bulk_query_addresses(addresses: &[AccountAddress]) -> anyhow::Result<()> {
+    if addresses.len() > MAX_BULK_QUERY_ADDRESSES {
+        anyhow::bail!(
+            "Too many addresses: {}, the max is {}",
+            addresses.len(),
+            MAX_BULK_QUERY_ADDRESSES
+        );
+    }
+    Ok(())
+}
+
 pub struct StateRpcImpl<S>
 where
     S: ChainStateAsyncService + 'static,
 {
     service: S,
     state_store: Arc<dyn StateNodeStore>,
+    /// The account state index, only exists in archive mode.
+    account_state_index: Option<Arc<AccountStateIndex>>,
 }
 
 impl<S> StateRpcImpl<S>
 where
     S: ChainStateAsyncService,
 {
-    pub fn new(service: S, state_store: Arc<dyn StateNodeStore>) -> Self {
+    pub fn new(
+        service: S,
+        state_store: Arc<dyn StateNodeStore>,
+        account_state_index: Option<AccountStateIndex>,
+    ) -> Self {
         Self {
             service,
             state_store,
+            account_state_index: account_state_index.map(Arc::new),
         }
     }
+
+    fn account_state_index(&self) -> anyhow::Result<Arc<AccountStateIndex>> {
+        self.account_state_index.clone().ok_or_else(|| {
+            anyhow::format_err!("The account state index is only available in archive mode")
+        })
+    }
 }
 
 impl<S> StateApi for StateRpcImpl<S>
@@ -123,6 +158,182 @@ where
         Box::pin(fut.map_err(map_err).boxed())
     }
 
+    fn list_resources(
+        &self,
+        address: AccountAddress,
+        filter: Option<StructTagFilterView>,
+        cursor: Option<StructTagView>,
+        limit: Option<u64>,
+    ) -> FutureResult<Option<ListResourceView>> {
+        let state_service = self.service.clone();
+        let db = self.state_store.clone();
+        let fut = async move {
+            if limit == Some(0) {
+                anyhow::bail!("The limit of list_resources should be greater than 0.");
+            }
+            let state_root = state_service.clone().state_root().await?;
+            let state = state_service
+                .get_account_state_set(address, Some(state_root))
+                .await?;
+            let state = match state {
+                None => return Ok(None),
+                Some(state) => state,
+            };
+            let limit = limit
+                .map(|limit| limit as usize)
+                .unwrap_or(MAX_LIST_RESOURCES_LIMIT)
+                .min(MAX_LIST_RESOURCES_LIMIT);
+            let mut resources = state
+                .resource_set()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| Ok((StructTag::decode(k.as_slice())?, v)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            resources.retain(|(struct_tag, _)| {
+                filter
+                    .as_ref()
+                    .map_or(true, |filter| filter.0.matches(struct_tag))
+                    && cursor
+                        .as_ref()
+                        .map_or(true, |cursor| struct_tag > &cursor.0)
+            });
+            resources.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let next_cursor = if resources.len() > limit {
+                resources.truncate(limit);
+                resources
+                    .last()
+                    .map(|(struct_tag, _)| StrView(struct_tag.clone()))
+            } else {
+                None
+            };
+
+            let statedb = ChainStateDB::new(db, Some(state_root));
+            let annotator = MoveValueAnnotator::new(&statedb);
+            let resources = resources
+                .into_iter()
+                .map(|(struct_tag, v)| {
+                    let struct_data = annotator.view_struct(struct_tag.clone(), v.as_slice())?;
+                    Ok((StrView(struct_tag), struct_data.into()))
+                })
+                .collect::<anyhow::Result<BTreeMap<StructTagView, AnnotatedMoveStructView>>>()?;
+            Ok(Some(ListResourceView {
+                resources,
+                next_cursor,
+            }))
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
+
+    fn get_balances(
+        &self,
+        addresses: Vec<AccountAddress>,
+        token_type: Option<TypeTagView>,
+    ) -> FutureResult<Vec<Option<StrView<u128>>>> {
+        if let Err(e) = check_bulk_query_addresses(&addresses) {
+            return Box::pin(futures::future::err(to_invalid_param_err(e)));
+        }
+        let state_service = self.service.clone();
+        let db = self.state_store.clone();
+        let fut = async move {
+            let state_root = state_service.state_root().await?;
+            let statedb = ChainStateDB::new(db, Some(state_root));
+            let token_type = token_type.map(|t| t.0).unwrap_or_else(stc_type_tag);
+            addresses
+                .into_iter()
+                .map(|address| {
+                    Ok(statedb
+                        .get_balance_by_type(address, token_type.clone())?
+                        .map(StrView))
+                })
+                .collect()
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
+
+    fn get_resources(
+        &self,
+        addresses: Vec<AccountAddress>,
+        resource_type: StructTagView,
+    ) -> FutureResult<Vec<Option<AnnotatedMoveStructView>>> {
+        if let Err(e) = check_bulk_query_addresses(&addresses) {
+            return Box::pin(futures::future::err(to_invalid_param_err(e)));
+        }
+        let state_service = self.service.clone();
+        let db = self.state_store.clone();
+        let fut = async move {
+            let state_root = state_service.state_root().await?;
+            let statedb = ChainStateDB::new(db, Some(state_root));
+            let annotator = MoveValueAnnotator::new(&statedb);
+            addresses
+                .into_iter()
+                .map(|address| {
+                    let access_path =
+                        AccessPath::resource_access_path(address, resource_type.0.clone());
+                    match statedb.get(&access_path)? {
+                        Some(data) => Ok(Some(
+                            annotator
+                                .view_struct(resource_type.0.clone(), data.as_slice())?
+                                .into(),
+                        )),
+                        None => Ok(None),
+                    }
+                })
+                .collect()
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
+
+    fn get_account_state_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+    ) -> FutureResult<Option<AccountStateAtView>> {
+        let index = match self.account_state_index() {
+            Ok(index) => index,
+            Err(e) => return Box::pin(futures::future::err(map_err(e))),
+        };
+        let fut = async move {
+            Ok(index.get_account_state_at(address, block_number)?.map(
+                |(changed_at, account_state)| AccountStateAtView {
+                    changed_at: StrView(changed_at),
+                    account_state,
+                },
+            ))
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
+
+    fn get_balance_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+        token_type: Option<TypeTagView>,
+    ) -> FutureResult<Option<StrView<u128>>> {
+        let index = match self.account_state_index() {
+            Ok(index) => index,
+            Err(e) => return Box::pin(futures::future::err(map_err(e))),
+        };
+        let db = self.state_store.clone();
+        let fut = async move {
+            let account_state = match index.get_account_state_at(address, block_number)? {
+                Some((_, account_state)) => account_state,
+                None => return Ok(None),
+            };
+            let token_type = token_type.map(|t| t.0).unwrap_or_else(stc_type_tag);
+            // Only the resource tree of the account is walked, instead of the global state tree.
+            let resource_tree =
+                StateTree::<StructTag>::new(db, Some(account_state.resource_root()));
+            match resource_tree.get(&BalanceResource::struct_tag_for_token(token_type))? {
+                Some(bytes) => Ok(Some(StrView(
+                    bcs_ext::from_bytes::<BalanceResource>(bytes.as_slice())?.token(),
+                ))),
+                None => Ok(None),
+            }
+        };
+        Box::pin(fut.map_err(map_err).boxed())
+    }
+
     fn get_state_root(&self) -> FutureResult<HashValue> {
         let fut = self.service.clone().state_root().map_err(map_err);
         Box::pin(fut)
@@ -141,4 +352,14 @@ where
             .map_err(map_err);
         Box::pin(fut)
     }
+
+    fn get_state_node_by_node_hash(&self, node_hash: HashValue) -> FutureResult<Option<Vec<u8>>> {
+        let fut = futures::future::ready(
+            self.state_store
+                .get(&node_hash)
+                .map(|node| node.map(|node| node.0))
+                .map_err(map_err),
+        );
+        Box::pin(fut)
+    }
 }
@@ -3,24 +3,71 @@
 
 use crate::module::{convert_to_rpc_error, map_err};
 use bcs_ext::BCSCodec;
+use lru::LruCache;
+use parking_lot::Mutex;
 use starcoin_crypto::HashValue;
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
-use starcoin_rpc_api::types::{SignedUserTransactionView, StrView};
+use starcoin_rpc_api::types::{
+    SignedUserTransactionView, StrView, SubmitTransactionResultView, TxPoolContentView,
+    TxPoolInspectView,
+};
 use starcoin_rpc_api::{txpool::TxPoolApi, FutureResult};
-use starcoin_txpool_api::{TxPoolStatus, TxPoolSyncService};
+use starcoin_txpool_api::{TxPoolContent, TxPoolStatus, TxPoolSyncService};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::SignedUserTransaction;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::time::{Duration, Instant};
 
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
 
+/// How long the node remembers a client request id of submitted txn.
+const REQUEST_ID_TTL: Duration = Duration::from_secs(600);
+const REQUEST_ID_CACHE_SIZE: usize = 100_000;
+const REQUEST_ID_MAX_LEN: usize = 128;
+/// The max count of the txns submitted by `txpool.submit_transactions` at once.
+const MAX_SUBMIT_TRANSACTIONS: usize = 100;
+/// The max count of the senders whose txns are returned by `txpool.content` and `txpool.inspect` at once.
+const MAX_CONTENT_SENDERS: usize = 100;
+
+/// The recently submitted txns by sender and client request id, used to make the submission idempotent.
+/// The request id is scoped by the txn sender, so a client can not shadow the request ids of other senders.
+struct RequestIdCache {
+    cache: LruCache<(AccountAddress, String), (HashValue, Instant)>,
+    ttl: Duration,
+}
+
+impl RequestIdCache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            cache: LruCache::new(capacity),
+            ttl,
+        }
+    }
+
+    fn get(&mut self, sender: AccountAddress, request_id: &str) -> Option<HashValue> {
+        let key = (sender, request_id.to_string());
+        let (txn_hash, submitted_at) = *self.cache.peek(&key)?;
+        if submitted_at.elapsed() > self.ttl {
+            self.cache.pop(&key);
+            return None;
+        }
+        Some(txn_hash)
+    }
+
+    fn put(&mut self, sender: AccountAddress, request_id: String, txn_hash: HashValue) {
+        self.cache
+            .put((sender, request_id), (txn_hash, Instant::now()));
+    }
+}
+
 pub struct TxPoolRpcImpl<S>
 where
     S: TxPoolSyncService + 'static,
 {
     service: S,
+    request_ids: Mutex<RequestIdCache>,
 }
 
 impl<S> TxPoolRpcImpl<S>
@@ -28,43 +75,117 @@ where
     S: TxPoolSyncService,
 {
     pub fn new(service: S) -> Self {
-        Self { service }
+        Self {
+            service,
+            request_ids: Mutex::new(RequestIdCache::new(REQUEST_ID_CACHE_SIZE, REQUEST_ID_TTL)),
+        }
     }
-}
 
-impl<S> TxPoolApi for TxPoolRpcImpl<S>
-where
-    S: TxPoolSyncService,
-{
-    fn submit_transaction(&self, txn: SignedUserTransaction) -> FutureResult<HashValue> {
+    /// The txns of at most `limit` senders after the `cursor` sender, and the cursor of the next page.
+    fn content_page(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<(TxPoolContent, Option<AccountAddress>)> {
+        let limit = match limit {
+            Some(0) => anyhow::bail!("The limit of the txpool content should be greater than 0."),
+            Some(limit) => (limit as usize).min(MAX_CONTENT_SENDERS),
+            None => MAX_CONTENT_SENDERS,
+        };
+        Ok(self.service.content(None).page(cursor, limit))
+    }
+
+    fn add_txn(&self, txn: SignedUserTransaction) -> Result<HashValue, jsonrpc_core::Error> {
         let txn_hash = txn.id();
-        let result: Result<(), jsonrpc_core::Error> = self
-            .service
+        self.service
             .add_txns(vec![txn])
             .pop()
             .expect("txpool should return result")
-            .map_err(convert_to_rpc_error);
+            .map(|_| txn_hash)
+            .map_err(convert_to_rpc_error)
+    }
+
+    fn submit(
+        &self,
+        txn: SignedUserTransaction,
+        request_id: Option<String>,
+    ) -> Result<HashValue, jsonrpc_core::Error> {
+        let request_id = match request_id {
+            Some(request_id) => request_id,
+            None => return self.add_txn(txn),
+        };
+        if request_id.is_empty() || request_id.len() > REQUEST_ID_MAX_LEN {
+            return Err(jsonrpc_core::Error::invalid_params(format!(
+                "request id should not be empty or longer than {}",
+                REQUEST_ID_MAX_LEN
+            )));
+        }
+        // hold the lock during submission, to avoid concurrent submissions with the same request id.
+        let mut request_ids = self.request_ids.lock();
+        let sender = txn.sender();
+        if let Some(txn_hash) = request_ids.get(sender, request_id.as_str()) {
+            return Ok(txn_hash);
+        }
+        let txn_hash = self.add_txn(txn)?;
+        request_ids.put(sender, request_id, txn_hash);
+        Ok(txn_hash)
+    }
+}
 
-        Box::pin(futures::future::ready(result.map(|_| txn_hash)))
+impl<S> TxPoolApi for TxPoolRpcImpl<S>
+where
+    S: TxPoolSyncService,
+{
+    fn submit_transaction(
+        &self,
+        txn: SignedUserTransaction,
+        request_id: Option<String>,
+    ) -> FutureResult<HashValue> {
+        let result = self.submit(txn, request_id);
+        Box::pin(futures::future::ready(result))
     }
 
-    fn submit_hex_transaction(&self, tx: String) -> FutureResult<HashValue> {
+    fn submit_hex_transaction(
+        &self,
+        tx: String,
+        request_id: Option<String>,
+    ) -> FutureResult<HashValue> {
         let tx = tx.strip_prefix("0x").unwrap_or_else(|| tx.as_str());
         let result = hex::decode(tx)
             .map_err(convert_to_rpc_error)
             .and_then(|txn_bytes| SignedUserTransaction::decode(&txn_bytes).map_err(map_err))
-            .and_then(|txn| {
-                let txn_hash = txn.id();
-                self.service
-                    .add_txns(vec![txn])
-                    .pop()
-                    .expect("txpool should return result")
-                    .map(|_| txn_hash)
-                    .map_err(convert_to_rpc_error)
-            });
+            .and_then(|txn| self.submit(txn, request_id));
         Box::pin(futures::future::ready(result))
     }
 
+    fn submit_transactions(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> FutureResult<Vec<SubmitTransactionResultView>> {
+        if txns.len() > MAX_SUBMIT_TRANSACTIONS {
+            return Box::pin(futures::future::err(jsonrpc_core::Error::invalid_params(
+                format!(
+                    "Too many txns: {}, the max is {}",
+                    txns.len(),
+                    MAX_SUBMIT_TRANSACTIONS
+                ),
+            )));
+        }
+        let txn_hashes: Vec<HashValue> = txns.iter().map(|txn| txn.id()).collect();
+        let results = self
+            .service
+            .add_txns(txns)
+            .into_iter()
+            .zip(txn_hashes)
+            .map(|(result, transaction_hash)| SubmitTransactionResultView {
+                transaction_hash,
+                accepted: result.is_ok(),
+                error: result.err().map(convert_to_rpc_error),
+            })
+            .collect();
+        Box::pin(futures::future::ok(results))
+    }
+
     fn gas_price(&self) -> FutureResult<StrView<u64>> {
         let gas_price = 1u64;
         Box::pin(futures::future::ok(gas_price.into()))
@@ -103,6 +224,36 @@ where
         let state = self.service.status();
         Box::pin(futures::future::ok(state))
     }
+
+    fn content(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> FutureResult<TxPoolContentView> {
+        let content = self
+            .content_page(cursor, limit)
+            .and_then(|(page, next_cursor)| {
+                let mut view = TxPoolContentView::try_from(page)?;
+                view.next_cursor = next_cursor;
+                Ok(view)
+            });
+        Box::pin(futures::future::ready(content.map_err(map_err)))
+    }
+
+    fn inspect(
+        &self,
+        cursor: Option<AccountAddress>,
+        limit: Option<u64>,
+    ) -> FutureResult<TxPoolInspectView> {
+        let inspect = self
+            .content_page(cursor, limit)
+            .and_then(|(page, next_cursor)| {
+                let mut view = TxPoolInspectView::try_from(page)?;
+                view.next_cursor = next_cursor;
+                Ok(view)
+            });
+        Box::pin(futures::future::ready(inspect.map_err(map_err)))
+    }
 }
 
 #[cfg(test)]
@@ -110,7 +261,13 @@ mod tests {
     use super::*;
     use futures::executor::block_on;
     use jsonrpc_core::IoHandler;
+    use starcoin_crypto::ed25519::genesis_key_pair;
+    use starcoin_rpc_api::errors::RpcErrorCode;
     use starcoin_txpool_mock_service::MockTxPoolService;
+    use starcoin_types::genesis_config::ChainId;
+    use starcoin_types::transaction::{
+        RawUserTransaction, Script, TransactionError, TransactionPayload,
+    };
 
     #[test]
     fn test_submit_transaction() {
@@ -140,4 +297,159 @@ mod tests {
             response
         );
     }
+
+    fn mock_txn(sender: AccountAddress, sequence_number: u64) -> SignedUserTransaction {
+        let (private_key, public_key) = genesis_key_pair();
+        RawUserTransaction::new_with_default_gas_token(
+            sender,
+            sequence_number,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            0,
+            0,
+            u64::max_value(),
+            ChainId::test(),
+        )
+        .sign(&private_key, public_key)
+        .unwrap()
+        .into_inner()
+    }
+
+    #[test]
+    fn test_submit_transaction_with_request_id() {
+        let txpool_rpc = TxPoolRpcImpl::new(MockTxPoolService::new());
+        let sender = AccountAddress::random();
+        let txn1 = mock_txn(sender, 0);
+        let txn2 = mock_txn(sender, 1);
+        let txn_hash1 =
+            block_on(txpool_rpc.submit_transaction(txn1, Some("r1".to_string()))).unwrap();
+        // the duplicate submission of the same sender returns the original txn hash.
+        let txn_hash =
+            block_on(txpool_rpc.submit_transaction(txn2.clone(), Some("r1".to_string()))).unwrap();
+        assert_eq!(txn_hash, txn_hash1);
+        let txn_hash2 =
+            block_on(txpool_rpc.submit_transaction(txn2.clone(), Some("r2".to_string()))).unwrap();
+        assert_eq!(txn_hash2, txn2.id());
+        assert!(block_on(txpool_rpc.submit_transaction(txn2, Some("".to_string()))).is_err());
+
+        // the same request id of another sender does not hit the cache.
+        let other_txn = mock_txn(AccountAddress::random(), 0);
+        let other_hash =
+            block_on(txpool_rpc.submit_transaction(other_txn.clone(), Some("r1".to_string())))
+                .unwrap();
+        assert_eq!(other_hash, other_txn.id());
+
+        let mut cache = RequestIdCache::new(10, Duration::from_millis(0));
+        cache.put(sender, "r1".to_string(), txn_hash1);
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(cache.get(sender, "r1").is_none());
+    }
+
+    #[test]
+    fn test_submit_transactions() {
+        let txpool_rpc = TxPoolRpcImpl::new(MockTxPoolService::new());
+        let txns = vec![SignedUserTransaction::mock(), SignedUserTransaction::mock()];
+        let results = block_on(txpool_rpc.submit_transactions(txns.clone())).unwrap();
+        assert_eq!(results.len(), 2);
+        for (result, txn) in results.iter().zip(txns) {
+            assert_eq!(result.transaction_hash, txn.id());
+            assert!(result.accepted);
+            assert!(result.error.is_none());
+        }
+
+        let txns = (0..=MAX_SUBMIT_TRANSACTIONS)
+            .map(|_| SignedUserTransaction::mock())
+            .collect();
+        assert!(block_on(txpool_rpc.submit_transactions(txns)).is_err());
+    }
+
+    #[test]
+    fn test_submit_transactions_with_rejected() {
+        let txpool_service = MockTxPoolService::new();
+        let txns = vec![
+            SignedUserTransaction::mock(),
+            SignedUserTransaction::mock(),
+            SignedUserTransaction::mock(),
+        ];
+        txpool_service.reject_txn(
+            txns[1].id(),
+            TransactionError::InvalidSignature("invalid signature".to_string()),
+        );
+        txpool_service.reject_txn(txns[2].id(), TransactionError::Old);
+        let txpool_rpc = TxPoolRpcImpl::new(txpool_service.clone());
+        let results = block_on(txpool_rpc.submit_transactions(txns.clone())).unwrap();
+        assert_eq!(results.len(), 3);
+        for (result, txn) in results.iter().zip(txns.iter()) {
+            assert_eq!(result.transaction_hash, txn.id());
+        }
+        assert!(results[0].accepted);
+        assert!(results[0].error.is_none());
+        // the rejected txns have the same errors as `txpool.submit_transaction`.
+        assert!(!results[1].accepted);
+        assert_eq!(
+            RpcErrorCode::of_error(results[1].error.as_ref().unwrap()),
+            Some(RpcErrorCode::InvalidSignature)
+        );
+        assert!(!results[2].accepted);
+        assert_eq!(
+            RpcErrorCode::of_error(results[2].error.as_ref().unwrap()),
+            Some(RpcErrorCode::SequenceNumberTooOld)
+        );
+        // only the accepted txn is added to the pool.
+        assert_eq!(
+            txpool_service.get_pending_txns(None, None),
+            vec![txns[0].clone()]
+        );
+    }
+
+    #[test]
+    fn test_content_and_inspect() {
+        let txn = SignedUserTransaction::mock();
+        let txpool_rpc = TxPoolRpcImpl::new(MockTxPoolService::new_with_txns(vec![txn.clone()]));
+
+        let content = block_on(txpool_rpc.content(None, None)).unwrap();
+        assert!(content.queued.is_empty());
+        assert!(content.next_cursor.is_none());
+        let pending = &content.pending[&txn.sender()][&txn.sequence_number()];
+        assert_eq!(pending.transaction_hash, txn.id());
+
+        let inspect = block_on(txpool_rpc.inspect(None, None)).unwrap();
+        let summary = &inspect.pending[&txn.sender()][&txn.sequence_number()];
+        assert_eq!(summary.transaction_hash, txn.id());
+        assert_eq!(summary.gas_unit_price.0, txn.gas_unit_price());
+
+        // the sequence number keys are serialized as strings.
+        let json = serde_json::to_value(&inspect).unwrap();
+        let txns_of_sender = json["pending"]
+            .as_object()
+            .unwrap()
+            .values()
+            .next()
+            .unwrap();
+        assert!(txns_of_sender[txn.sequence_number().to_string()].is_object());
+    }
+
+    #[test]
+    fn test_content_page() {
+        let txns: Vec<SignedUserTransaction> =
+            (0..3).map(|_| SignedUserTransaction::mock()).collect();
+        let mut senders: Vec<AccountAddress> = txns.iter().map(|txn| txn.sender()).collect();
+        senders.sort();
+        let txpool_rpc = TxPoolRpcImpl::new(MockTxPoolService::new_with_txns(txns));
+
+        let page = block_on(txpool_rpc.inspect(None, Some(2))).unwrap();
+        assert_eq!(
+            page.pending.keys().cloned().collect::<Vec<_>>(),
+            senders[..2].to_vec()
+        );
+        assert_eq!(page.next_cursor, Some(senders[1]));
+
+        let page = block_on(txpool_rpc.content(page.next_cursor, Some(2))).unwrap();
+        assert_eq!(
+            page.pending.keys().cloned().collect::<Vec<_>>(),
+            senders[2..].to_vec()
+        );
+        assert!(page.next_cursor.is_none());
+
+        assert!(block_on(txpool_rpc.content(None, Some(0))).is_err());
+    }
 }
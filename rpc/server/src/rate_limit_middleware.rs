@@ -1,6 +1,6 @@
 use jsonrpc_core::futures::future::Either;
 use jsonrpc_core::futures::Future;
-use jsonrpc_core::{Call, Error, ErrorCode, Failure, FutureResponse, Id, Middleware, Output};
+use jsonrpc_core::{Call, Failure, FutureResponse, Id, Middleware, Output};
 
 type MethodName = String;
 
@@ -8,9 +8,11 @@ use api_limiter::ApiLimiters;
 pub use api_limiter::Quota;
 use jsonrpc_core::middleware::NoopCallFuture;
 use starcoin_config::{ApiQuotaConfig, ApiQuotaConfiguration, QuotaDuration};
+use starcoin_rpc_api::errors::RpcErrorCode;
 use starcoin_rpc_api::metadata::Metadata;
+use std::sync::Arc;
 
-struct QuotaWrapper(Quota);
+pub(crate) struct QuotaWrapper(pub(crate) Quota);
 
 impl From<ApiQuotaConfig> for QuotaWrapper {
     fn from(c: ApiQuotaConfig) -> Self {
@@ -23,9 +25,10 @@ impl From<ApiQuotaConfig> for QuotaWrapper {
     }
 }
 
-#[derive(Debug)]
+/// The limiters are shared by the clones, such as the io handler of the http compression middleware.
+#[derive(Clone, Debug)]
 pub struct JsonApiRateLimitMiddleware {
-    limiters: ApiLimiters<MethodName, String>,
+    limiters: Arc<ApiLimiters<MethodName, String>>,
 }
 
 impl JsonApiRateLimitMiddleware {
@@ -44,7 +47,9 @@ impl JsonApiRateLimitMiddleware {
                 .map(|(k, v)| (k, Into::<QuotaWrapper>::into(v).0))
                 .collect(),
         );
-        Self { limiters }
+        Self {
+            limiters: Arc::new(limiters),
+        }
     }
 }
 
@@ -69,11 +74,7 @@ impl Middleware<Metadata> for JsonApiRateLimitMiddleware {
                 Err(e) => {
                     let output = Output::Failure(Failure {
                         jsonrpc: json_version,
-                        error: Error {
-                            code: ErrorCode::ServerError(-10000),
-                            message: e.to_string(),
-                            data: None,
-                        },
+                        error: RpcErrorCode::RateLimitExceeded.to_error(e.to_string(), None),
                         id,
                     });
                     Either::Left(Box::pin(futures::future::ready(Some(output))))
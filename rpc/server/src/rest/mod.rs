@@ -0,0 +1,290 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A rest api gateway in front of the json rpc, for the tools which can not easily consume json rpc.
+//! Every route is dispatched to a json rpc method, so the rest api shares the api set, middlewares
+//! and quotas of the http json rpc. The OpenAPI description is served at `GET /v1/openapi.json`.
+
+mod routes;
+
+pub use routes::{find_route, openapi, ParamLocation, ParamType, RestParam, RestRoute, ROUTES};
+
+use crate::body_limit::read_body;
+use crate::extractors::RpcExtractor;
+use anyhow::Result;
+use futures::channel::oneshot;
+use jsonrpc_core::{
+    Call, Error, ErrorCode, Id, MetaIoHandler, MethodCall, Middleware, Output, Params, Request,
+    Response, Version,
+};
+use jsonrpc_http_server::hyper::service::{make_service_fn, service_fn};
+use jsonrpc_http_server::hyper::{self, header, Body, Method, Server, StatusCode};
+use jsonrpc_http_server::MetaExtractor;
+use serde_json::{json, Value};
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::errors::{ErrorCategory, RpcErrorCode};
+use starcoin_rpc_api::metadata::Metadata;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const OPENAPI_PATH: &str = "/v1/openapi.json";
+
+fn status_of(error: &Error) -> StatusCode {
+    match RpcErrorCode::of_error(error) {
+        Some(RpcErrorCode::TxnNotFound) => return StatusCode::NOT_FOUND,
+        Some(RpcErrorCode::TxPoolFull) => return StatusCode::SERVICE_UNAVAILABLE,
+        Some(RpcErrorCode::RateLimitExceeded) => return StatusCode::TOO_MANY_REQUESTS,
+        Some(RpcErrorCode::InvalidApiKey) => return StatusCode::UNAUTHORIZED,
+        Some(RpcErrorCode::MethodNotAllowed) => return StatusCode::FORBIDDEN,
+        Some(code) => match code.category() {
+            ErrorCategory::Validation | ErrorCategory::TxPool => return StatusCode::BAD_REQUEST,
+            _ => {}
+        },
+        None => {}
+    }
+    match error.code {
+        ErrorCode::ParseError | ErrorCode::InvalidRequest | ErrorCode::InvalidParams => {
+            StatusCode::BAD_REQUEST
+        }
+        // the api is not enabled.
+        ErrorCode::MethodNotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Value) {
+    (status, json!({ "message": message }))
+}
+
+/// Dispatch a rest request to the json rpc method of the route, returns the status and the json body.
+pub async fn dispatch<M>(
+    io_handler: &MetaIoHandler<Metadata, M>,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    meta: Metadata,
+) -> (StatusCode, Value)
+where
+    M: Middleware<Metadata>,
+{
+    let (route, path_params) = match find_route(method, path) {
+        Some(route) => route,
+        None => {
+            return message(
+                StatusCode::NOT_FOUND,
+                format!("No route for {} {}", method, path),
+            )
+        }
+    };
+    let body = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(body) => Some(body),
+            Err(e) => return message(StatusCode::BAD_REQUEST, format!("Invalid json body: {}", e)),
+        }
+    };
+    let params = match route.rpc_params(&path_params, body.as_ref()) {
+        Ok(params) => params,
+        Err(e) => return message(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let call = Call::MethodCall(MethodCall {
+        jsonrpc: Some(Version::V2),
+        method: route.rpc_method.to_string(),
+        params: Params::Array(params),
+        id: Id::Num(0),
+    });
+    match io_handler
+        .handle_rpc_request(Request::Single(call), meta)
+        .await
+    {
+        Some(Response::Single(Output::Success(success))) => {
+            let result = match route.result_field {
+                Some(field) => success.result.get(field).cloned().unwrap_or(Value::Null),
+                None => success.result,
+            };
+            if result.is_null() {
+                message(StatusCode::NOT_FOUND, "Not found".to_string())
+            } else {
+                (StatusCode::OK, result)
+            }
+        }
+        Some(Response::Single(Output::Failure(failure))) => (
+            status_of(&failure.error),
+            serde_json::to_value(&failure.error).unwrap_or_default(),
+        ),
+        _ => message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Unexpected json rpc response".to_string(),
+        ),
+    }
+}
+
+async fn serve_rest<M>(
+    io_handler: Arc<MetaIoHandler<Metadata, M>>,
+    extractor: Arc<RpcExtractor>,
+    max_request_body_size: usize,
+    req: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, hyper::Error>
+where
+    M: Middleware<Metadata>,
+{
+    let (status, body) = if req.method() == Method::GET && req.uri().path() == OPENAPI_PATH {
+        (StatusCode::OK, openapi(env!("CARGO_PKG_VERSION")))
+    } else {
+        let meta = extractor.read_metadata(&req);
+        let method = req.method().to_string();
+        let path = req.uri().path().to_string();
+        match read_body(req.into_body(), max_request_body_size).await? {
+            Some(body) => dispatch(io_handler.as_ref(), &method, &path, &body, meta).await,
+            None => message(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Request body is larger than {}", max_request_body_size),
+            ),
+        }
+    };
+    let mut resp = hyper::Response::new(Body::from(body.to_string()));
+    *resp.status_mut() = status;
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Ok(resp)
+}
+
+pub struct RestServer {
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl RestServer {
+    /// Start the rest api server on its own thread and runtime.
+    pub fn start<M>(
+        address: SocketAddr,
+        io_handler: MetaIoHandler<Metadata, M>,
+        extractor: RpcExtractor,
+        max_request_body_size: usize,
+    ) -> Result<Self>
+    where
+        M: Middleware<Metadata> + Send + Sync + 'static,
+    {
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let io_handler = Arc::new(io_handler);
+        let extractor = Arc::new(extractor);
+        std::thread::Builder::new()
+            .name("rest-server".to_string())
+            .spawn(move || {
+                let mut rt = match tokio::runtime::Builder::new()
+                    .basic_scheduler()
+                    .enable_io()
+                    .build()
+                {
+                    Ok(rt) => rt,
+                    Err(e) => {
+                        error!("Build rest server runtime failed: {:?}", e);
+                        return;
+                    }
+                };
+                let result = rt.block_on(async move {
+                    let make_service = make_service_fn(move |_| {
+                        let io_handler = io_handler.clone();
+                        let extractor = extractor.clone();
+                        async move {
+                            Ok::<_, hyper::Error>(service_fn(move |req| {
+                                serve_rest(
+                                    io_handler.clone(),
+                                    extractor.clone(),
+                                    max_request_body_size,
+                                    req,
+                                )
+                            }))
+                        }
+                    });
+                    Server::try_bind(&address)?
+                        .serve(make_service)
+                        .with_graceful_shutdown(async {
+                            shutdown_receiver.await.ok();
+                        })
+                        .await
+                });
+                if let Err(e) = result {
+                    error!("Rest server at {} failed: {:?}", address, e);
+                }
+            })?;
+        Ok(Self {
+            shutdown: Some(shutdown_sender),
+        })
+    }
+
+    pub fn close(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn io_handler() -> MetaIoHandler<Metadata> {
+        let mut io = MetaIoHandler::default();
+        io.add_sync_method("chain.get_block_by_hash", |params: Params| {
+            let params: Vec<Value> = params.parse()?;
+            Ok(if params[0] == json!("0x01") {
+                json!({"header": {"block_hash": "0x01"}})
+            } else {
+                Value::Null
+            })
+        });
+        io.add_sync_method("state.get_account_state_set", |_params: Params| {
+            Ok(json!({"codes": {}, "resources": {"0x1::Account::Account": {}}}))
+        });
+        io.add_sync_method("txpool.submit_hex_transaction", |_params: Params| {
+            Err::<Value, _>(Error {
+                code: ErrorCode::ServerError(RpcErrorCode::GasPriceTooLow as i64),
+                message: "gas price too low".to_string(),
+                data: None,
+            })
+        });
+        io
+    }
+
+    async fn request(method: &str, path: &str, body: &str) -> (StatusCode, Value) {
+        dispatch(
+            &io_handler(),
+            method,
+            path,
+            body.as_bytes(),
+            Metadata::default(),
+        )
+        .await
+    }
+
+    #[stest::test]
+    async fn test_dispatch() {
+        let (status, body) = request("GET", "/v1/blocks/0x01", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["header"]["block_hash"], json!("0x01"));
+
+        let (status, _) = request("GET", "/v1/blocks/0x02", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = request("GET", "/v1/accounts/0x1/resources", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.get("0x1::Account::Account").is_some());
+
+        // the api is not enabled.
+        let (status, _) = request("GET", "/v1/transactions/0x01", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = request("POST", "/v1/transactions", "{}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, body) =
+            request("POST", "/v1/transactions", "{\"transaction\": \"0x01\"}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], json!(RpcErrorCode::GasPriceTooLow as i64));
+
+        let (status, _) = request("DELETE", "/v1/blocks/0x01", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,378 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, format_err, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParamLocation {
+    Path,
+    Body,
+}
+
+impl ParamLocation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParamLocation::Path => "path",
+            ParamLocation::Body => "body",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParamType {
+    String,
+    Integer,
+}
+
+impl ParamType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Integer => "integer",
+        }
+    }
+}
+
+/// A param of the rest api, the params of a route are passed to the json rpc method by position.
+#[derive(Clone, Copy, Debug)]
+pub struct RestParam {
+    pub name: &'static str,
+    pub location: ParamLocation,
+    pub param_type: ParamType,
+    pub required: bool,
+    pub description: &'static str,
+}
+
+/// A rest api handled by a json rpc method, the OpenAPI description is generated from the routes.
+#[derive(Clone, Copy, Debug)]
+pub struct RestRoute {
+    /// The http method, GET or POST.
+    pub method: &'static str,
+    /// The path template, the path params are in braces, such as `/v1/blocks/{hash}`.
+    pub path: &'static str,
+    pub rpc_method: &'static str,
+    pub summary: &'static str,
+    pub params: &'static [RestParam],
+    /// Only return the field of the json rpc result if it is set.
+    pub result_field: Option<&'static str>,
+}
+
+const HASH_PARAM: RestParam = RestParam {
+    name: "hash",
+    location: ParamLocation::Path,
+    param_type: ParamType::String,
+    required: true,
+    description: "the hex literal of the hash, such as 0x...",
+};
+
+pub const ROUTES: &[RestRoute] = &[
+    RestRoute {
+        method: "GET",
+        path: "/v1/blocks/{hash}",
+        rpc_method: "chain.get_block_by_hash",
+        summary: "Get a block by hash",
+        params: &[HASH_PARAM],
+        result_field: None,
+    },
+    RestRoute {
+        method: "GET",
+        path: "/v1/blocks/by_number/{number}",
+        rpc_method: "chain.get_block_by_number",
+        summary: "Get a block of the main chain by number",
+        params: &[RestParam {
+            name: "number",
+            location: ParamLocation::Path,
+            param_type: ParamType::Integer,
+            required: true,
+            description: "the block number",
+        }],
+        result_field: None,
+    },
+    RestRoute {
+        method: "GET",
+        path: "/v1/accounts/{address}/resources",
+        rpc_method: "state.get_account_state_set",
+        summary: "Get the resources of an account, decoded by the on chain modules",
+        params: &[RestParam {
+            name: "address",
+            location: ParamLocation::Path,
+            param_type: ParamType::String,
+            required: true,
+            description: "the account address, such as 0x1",
+        }],
+        result_field: Some("resources"),
+    },
+    RestRoute {
+        method: "POST",
+        path: "/v1/transactions",
+        rpc_method: "txpool.submit_hex_transaction",
+        summary: "Submit a signed txn to the txpool, return the txn hash",
+        params: &[
+            RestParam {
+                name: "transaction",
+                location: ParamLocation::Body,
+                param_type: ParamType::String,
+                required: true,
+                description: "the hex of the bcs encoded SignedUserTransaction",
+            },
+            RestParam {
+                name: "request_id",
+                location: ParamLocation::Body,
+                param_type: ParamType::String,
+                required: false,
+                description: "the client request id to make the submission idempotent",
+            },
+        ],
+        result_field: None,
+    },
+    RestRoute {
+        method: "GET",
+        path: "/v1/transactions/{hash}",
+        rpc_method: "chain.get_transaction",
+        summary: "Get a txn by hash",
+        params: &[HASH_PARAM],
+        result_field: None,
+    },
+    RestRoute {
+        method: "GET",
+        path: "/v1/transactions/{hash}/info",
+        rpc_method: "chain.get_transaction_info",
+        summary: "Get the execution info of a txn by hash",
+        params: &[HASH_PARAM],
+        result_field: None,
+    },
+    RestRoute {
+        method: "GET",
+        path: "/v1/transactions/{hash}/events",
+        rpc_method: "chain.get_events_by_txn_hash",
+        summary: "Get the events of a txn by hash",
+        params: &[HASH_PARAM],
+        result_field: None,
+    },
+];
+
+impl RestRoute {
+    /// Returns the path params if the route matches the method and path.
+    pub fn matches(&self, method: &str, path: &str) -> Option<HashMap<&'static str, String>> {
+        if !self.method.eq_ignore_ascii_case(method) {
+            return None;
+        }
+        let templates: Vec<&'static str> = self.path.trim_matches('/').split('/').collect();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        if templates.len() != segments.len() {
+            return None;
+        }
+        let mut path_params = HashMap::new();
+        for (template, segment) in templates.into_iter().zip(segments) {
+            if template.starts_with('{') && template.ends_with('}') {
+                if segment.is_empty() {
+                    return None;
+                }
+                path_params.insert(
+                    template.trim_start_matches('{').trim_end_matches('}'),
+                    segment.to_string(),
+                );
+            } else if template != segment {
+                return None;
+            }
+        }
+        Some(path_params)
+    }
+
+    /// Build the positional params of the json rpc call.
+    pub fn rpc_params(
+        &self,
+        path_params: &HashMap<&'static str, String>,
+        body: Option<&Value>,
+    ) -> Result<Vec<Value>> {
+        let mut params = vec![];
+        for param in self.params {
+            let value = match param.location {
+                ParamLocation::Path => path_params
+                    .get(param.name)
+                    .map(|v| typed(param, v))
+                    .transpose()?,
+                ParamLocation::Body => body
+                    .and_then(|body| body.get(param.name))
+                    .filter(|v| !v.is_null())
+                    .cloned(),
+            };
+            match value {
+                Some(value) => params.push(value),
+                None if param.required => {
+                    bail!("missing {} param: {}", param.location.as_str(), param.name)
+                }
+                None => params.push(Value::Null),
+            }
+        }
+        Ok(params)
+    }
+}
+
+fn typed(param: &RestParam, value: &str) -> Result<Value> {
+    Ok(match param.param_type {
+        ParamType::String => Value::String(value.to_string()),
+        ParamType::Integer => {
+            let value = value
+                .parse::<u64>()
+                .map_err(|e| format_err!("invalid param {}: {}", param.name, e))?;
+            json!(value)
+        }
+    })
+}
+
+pub fn find_route(
+    method: &str,
+    path: &str,
+) -> Option<(&'static RestRoute, HashMap<&'static str, String>)> {
+    ROUTES
+        .iter()
+        .find_map(|route| route.matches(method, path).map(|params| (route, params)))
+}
+
+fn operation(route: &RestRoute) -> Value {
+    let parameters: Vec<Value> = route
+        .params
+        .iter()
+        .filter(|param| param.location != ParamLocation::Body)
+        .map(|param| {
+            json!({
+                "name": param.name,
+                "in": param.location.as_str(),
+                "required": param.required,
+                "description": param.description,
+                "schema": {"type": param.param_type.as_str()},
+            })
+        })
+        .collect();
+    let body_params: Vec<&RestParam> = route
+        .params
+        .iter()
+        .filter(|param| param.location == ParamLocation::Body)
+        .collect();
+    let mut operation = json!({
+        "summary": route.summary,
+        "description": format!("Handled by the json rpc method `{}`.", route.rpc_method),
+        "operationId": route.rpc_method.replace('.', "_"),
+        "parameters": parameters,
+        "responses": {
+            "200": {
+                "description": match route.result_field {
+                    Some(field) => format!("The `{}` of the json rpc result", field),
+                    None => "The json rpc result".to_string(),
+                },
+                "content": {"application/json": {"schema": {}}},
+            },
+            "400": {"description": "Invalid params, the body is the json rpc error"},
+            "401": {"description": "Missing or invalid api key"},
+            "403": {"description": "The method is not allowed for the api key"},
+            "404": {"description": "Not found"},
+            "429": {"description": "Too many requests"},
+            "500": {"description": "Server error, the body is the json rpc error"},
+        },
+        "x-json-rpc-method": route.rpc_method,
+    });
+    if !body_params.is_empty() {
+        let properties: Map<String, Value> = body_params
+            .iter()
+            .map(|param| {
+                (
+                    param.name.to_string(),
+                    json!({
+                        "type": param.param_type.as_str(),
+                        "description": param.description,
+                    }),
+                )
+            })
+            .collect();
+        let required: Vec<&str> = body_params
+            .iter()
+            .filter(|param| param.required)
+            .map(|param| param.name)
+            .collect();
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {"application/json": {"schema": {
+                "type": "object",
+                "required": required,
+                "properties": properties,
+            }}},
+        });
+    }
+    operation
+}
+
+/// The OpenAPI 3 description of the rest api, generated from the routes.
+pub fn openapi(version: &str) -> Value {
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let item = paths
+            .entry(route.path.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[route.method.to_lowercase()] = operation(route);
+    }
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Starcoin REST API",
+            "description": "The rest api gateway in front of the starcoin json rpc.",
+            "version": version,
+        },
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_route() {
+        let (route, params) = find_route("GET", "/v1/blocks/0x01").unwrap();
+        assert_eq!(route.rpc_method, "chain.get_block_by_hash");
+        assert_eq!(params.get("hash").unwrap(), "0x01");
+
+        let (route, params) = find_route("GET", "/v1/blocks/by_number/10").unwrap();
+        assert_eq!(route.rpc_method, "chain.get_block_by_number");
+        assert_eq!(route.rpc_params(&params, None).unwrap(), vec![json!(10)]);
+
+        let (route, _) = find_route("GET", "/v1/accounts/0x1/resources").unwrap();
+        assert_eq!(route.result_field, Some("resources"));
+
+        assert!(find_route("GET", "/v1/transactions").is_none());
+        assert!(find_route("GET", "/v1/blocks/").is_none());
+        assert!(find_route("GET", "/v1/blocks/by_number/abc")
+            .map(|(route, params)| route.rpc_params(&params, None))
+            .unwrap()
+            .is_err());
+
+        let (route, params) = find_route("POST", "/v1/transactions").unwrap();
+        assert!(route.rpc_params(&params, None).is_err());
+        assert_eq!(
+            route
+                .rpc_params(&params, Some(&json!({"transaction": "0x01"})))
+                .unwrap(),
+            vec![json!("0x01"), Value::Null]
+        );
+    }
+
+    #[test]
+    fn test_openapi() {
+        let doc = openapi("1.0.0");
+        for route in ROUTES {
+            let operation = &doc["paths"][route.path][route.method.to_lowercase()];
+            assert_eq!(operation["x-json-rpc-method"], json!(route.rpc_method));
+        }
+        let submit = &doc["paths"]["/v1/transactions"]["post"];
+        assert_eq!(
+            submit["requestBody"]["content"]["application/json"]["schema"]["required"],
+            json!(["transaction"])
+        );
+        assert_eq!(
+            doc["paths"]["/v1/blocks/{hash}"]["get"]["parameters"][0]["in"],
+            json!("path")
+        );
+    }
+}
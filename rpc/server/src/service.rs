@@ -1,9 +1,17 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::api_key_middleware::ApiKeyMiddleware;
 use crate::api_registry::ApiRegistry;
-use crate::extractors::{RpcExtractor, WsExtractor};
-use anyhow::Result;
+use crate::archive_middleware::ArchiveForwardMiddleware;
+use crate::batch_middleware::BatchLimitMiddleware;
+use crate::compression_middleware::CompressionMiddleware;
+use crate::cors_middleware::CorsMiddleware;
+use crate::extractors::{RpcExtractor, WsExtractor, WsRequestMiddleware};
+use crate::graphql::{add_graphql_method, ChainSchema, GraphQLServer};
+use crate::heavy_api_middleware::HeavyApiMiddleware;
+use crate::https_server::HttpsServer;
+use anyhow::{ensure, Result};
 use futures::stream::*;
 use futures::{FutureExt, StreamExt};
 use jsonrpc_core::futures::channel::mpsc;
@@ -20,6 +28,7 @@ use starcoin_logger::prelude::*;
 use starcoin_rpc_api::contract_api::ContractApi;
 use starcoin_rpc_api::metadata::Metadata;
 use starcoin_rpc_api::network_manager::NetworkManagerApi;
+use starcoin_rpc_api::node_admin::NodeAdminApi;
 use starcoin_rpc_api::node_manager::NodeManagerApi;
 use starcoin_rpc_api::sync_manager::SyncManagerApi;
 use starcoin_rpc_api::types::ConnectLocal;
@@ -27,26 +36,46 @@ use starcoin_rpc_api::{
     account::AccountApi, chain::ChainApi, debug::DebugApi, miner::MinerApi, node::NodeApi,
     pubsub::StarcoinPubSub, state::StateApi, txpool::TxPoolApi,
 };
+use starcoin_rpc_middleware::{MetricMiddleware, TransportRecorder};
 use starcoin_service_registry::{ActorService, ServiceContext, ServiceHandler};
+use starcoin_storage::Storage;
 use std::collections::HashSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
+/// The path and the method of the health api of the http and https servers.
+pub(crate) const HEALTH_API: (&str, &str) = ("/status", "status");
+
 pub struct RpcService {
     config: Arc<NodeConfig>,
     api_registry: ApiRegistry,
     ipc: Option<jsonrpc_ipc_server::Server>,
     http: Option<jsonrpc_http_server::Server>,
+    https: Option<HttpsServer>,
     tcp: Option<jsonrpc_tcp_server::Server>,
     ws: Option<jsonrpc_ws_server::Server>,
+    #[cfg(feature = "rest-api")]
+    rest: Option<crate::rest::RestServer>,
+    graphql_schema: Option<ChainSchema>,
+    graphql: Option<GraphQLServer>,
 }
 
 impl ActorService for RpcService {
     fn started(&mut self, _ctx: &mut ServiceContext<Self>) -> Result<()> {
         self.ipc = self.start_ipc()?;
         self.http = self.start_http()?;
+        self.https = self.start_https()?;
         self.tcp = self.start_tcp()?;
         self.ws = self.start_ws()?;
+        #[cfg(feature = "rest-api")]
+        {
+            self.rest = self.start_rest()?;
+        }
+        #[cfg(not(feature = "rest-api"))]
+        if self.config.rpc.get_rest_address().is_some() {
+            warn!("Rest api is enabled, but the node is not built with the rest-api feature.");
+        }
+        self.graphql = self.start_graphql()?;
         Ok(())
     }
 
@@ -63,16 +92,30 @@ impl RpcService {
             api_registry,
             ipc: None,
             http: None,
+            https: None,
             tcp: None,
             ws: None,
+            #[cfg(feature = "rest-api")]
+            rest: None,
+            graphql_schema: None,
+            graphql: None,
         }
     }
 
+    /// Serve the graphql `schema` if the graphql address is configured.
+    pub fn with_graphql(mut self, schema: ChainSchema) -> Self {
+        self.graphql_schema = Some(schema);
+        self
+    }
+
     #[allow(clippy::too_many_arguments)]
-    pub fn new_with_api<C, N, NM, SM, NWM, T, A, S, D, P, M, Contract>(
+    pub fn new_with_api<C, N, NM, NA, SM, NWM, T, A, S, D, P, M, Contract>(
         config: Arc<NodeConfig>,
+        storage: Arc<Storage>,
+        api_key_middleware: ApiKeyMiddleware,
         node_api: N,
         node_manager_api: Option<NM>,
+        node_admin_api: Option<NA>,
         sync_manager_api: Option<SM>,
         network_manager_api: Option<NWM>,
         chain_api: Option<C>,
@@ -87,6 +130,7 @@ impl RpcService {
     where
         N: NodeApi,
         NM: NodeManagerApi,
+        NA: NodeAdminApi,
         SM: SyncManagerApi,
         NWM: NetworkManagerApi,
         C: ChainApi,
@@ -98,7 +142,20 @@ impl RpcService {
         M: MinerApi,
         Contract: ContractApi,
     {
-        let mut api_registry = ApiRegistry::new(config.rpc.api_quotas.clone());
+        let batch_middleware = BatchLimitMiddleware::from_config(&config.rpc);
+        let metric_middleware = MetricMiddleware::new(config.rpc.slow_call_threshold());
+        let cors_middleware = CorsMiddleware::from_config(&config.rpc.http);
+        let archive_middleware = ArchiveForwardMiddleware::from_config(&config.rpc, storage);
+        let heavy_api_middleware = HeavyApiMiddleware::from_config(&config.rpc);
+        let mut api_registry = ApiRegistry::new(
+            config.rpc.api_quotas.clone(),
+            batch_middleware,
+            metric_middleware,
+            api_key_middleware,
+            cors_middleware,
+            archive_middleware,
+            heavy_api_middleware,
+        );
 
         api_registry.register(Api::Node, NodeApi::to_delegate(node_api));
         if let Some(node_manager_api) = node_manager_api {
@@ -107,6 +164,9 @@ impl RpcService {
                 NodeManagerApi::to_delegate(node_manager_api),
             );
         }
+        if let Some(node_admin_api) = node_admin_api {
+            api_registry.register(Api::NodeAdmin, NodeAdminApi::to_delegate(node_admin_api));
+        }
         if let Some(sync_manager_api) = sync_manager_api {
             api_registry.register(
                 Api::SyncManager,
@@ -152,33 +212,94 @@ impl RpcService {
         } else {
             let ipc_file = self.config.rpc.get_ipc_file();
             let apis: HashSet<Api> = self.config.rpc.ipc.apis().list_apis();
-            let io_handler = self.api_registry.get_apis(apis);
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.ipc.max_batch_size,
+                self.config.rpc.ipc.max_response_body_size,
+            );
+            // the ipc is local, so it is not checked by api key, but by its own method allowlist.
+            let io_handler = self.api_registry.get_trusted_apis(
+                apis,
+                batch_middleware,
+                self.config.rpc.ipc.methods.clone(),
+            );
 
+            // the ipc serves the admin apis without api key, so only the node owner can connect,
+            // the socket is bound in a dedicated dir created by the node which only the owner can access,
+            // the mode of an existing dir is never changed, the node refuses to start if it is accessible by others.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+                if let Some(ipc_dir) = ipc_file.parent() {
+                    if ipc_dir.exists() {
+                        let metadata = std::fs::symlink_metadata(ipc_dir)?;
+                        ensure!(
+                            metadata.is_dir() && metadata.permissions().mode() & 0o077 == 0,
+                            "Ipc dir {:?} should be a dir only accessible by the owner",
+                            ipc_dir
+                        );
+                    } else {
+                        std::fs::DirBuilder::new().mode(0o700).create(ipc_dir)?;
+                    }
+                }
+            }
             info!("Ipc rpc server start at :{:?}", ipc_file);
-            Some(
-                jsonrpc_ipc_server::ServerBuilder::new(io_handler)
-                    .session_meta_extractor(RpcExtractor::default())
-                    .start(ipc_file.to_str().expect("Path to string should success."))?,
-            )
+            let server = jsonrpc_ipc_server::ServerBuilder::new(io_handler)
+                .session_meta_extractor(RpcExtractor::default())
+                .start(ipc_file.to_str().expect("Path to string should success."))?;
+            Some(server)
         })
     }
 
     fn start_http(&self) -> Result<Option<jsonrpc_http_server::Server>> {
+        // the http address is served by the https server if the tls is configured.
+        if self.config.rpc.http.tls()?.is_some() {
+            return Ok(None);
+        }
         Ok(if let Some(addr) = self.config.rpc.get_http_address() {
             let address = addr.into();
             let apis = self.config.rpc.http.apis().list_apis();
-            let io_handler = self.api_registry.get_apis(apis);
-            let http = jsonrpc_http_server::ServerBuilder::new(io_handler)
-                .meta_extractor(RpcExtractor {
-                    http_ip_headers: self.config.rpc.http.ip_headers(),
-                })
-                .cors(DomainsValidation::AllowOnly(vec![
-                    AccessControlAllowOrigin::Null,
-                    AccessControlAllowOrigin::Any,
-                ]))
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.http.max_batch_size,
+                self.config.rpc.http.max_response_body_size,
+            );
+            let io_handler = self.api_registry.get_apis(apis, batch_middleware);
+            let extractor = || RpcExtractor {
+                http_ip_headers: self.config.rpc.http.ip_headers(),
+                http_api_key_header: Some(self.config.rpc.api_keys.api_key_header()),
+            };
+            let http = jsonrpc_http_server::ServerBuilder::new(io_handler.clone())
+                .request_middleware(CompressionMiddleware::new(
+                    io_handler,
+                    extractor(),
+                    self.config.rpc.http.max_request_body_size(),
+                    self.config.rpc.http.cors_origins.clone(),
+                    !self.config.rpc.http.disable_compression,
+                    TransportRecorder::new("http", self.config.rpc.slow_call_threshold()),
+                ))
+                .meta_extractor(extractor())
+                .cors(DomainsValidation::AllowOnly(
+                    match self.config.rpc.http.cors_origins.as_ref() {
+                        Some(origins) => origins
+                            .iter()
+                            .map(|origin| {
+                                if origin == "*" {
+                                    AccessControlAllowOrigin::Any
+                                } else {
+                                    AccessControlAllowOrigin::Value(origin.as_str().into())
+                                }
+                            })
+                            .collect(),
+                        None => vec![
+                            AccessControlAllowOrigin::Null,
+                            AccessControlAllowOrigin::Any,
+                        ],
+                    },
+                ))
                 .threads(self.config.rpc.http.threads())
                 .max_request_body_size(self.config.rpc.http.max_request_body_size())
-                .health_api(("/status", "status"))
+                .health_api(HEALTH_API)
                 .start_http(&address)?;
             info!("Rpc: http server start at :{}", address);
             Some(http)
@@ -187,12 +308,53 @@ impl RpcService {
         })
     }
 
+    fn start_https(&self) -> Result<Option<HttpsServer>> {
+        let tls = match self.config.rpc.http.tls()? {
+            Some(tls) => tls,
+            None => return Ok(None),
+        };
+        Ok(if let Some(addr) = self.config.rpc.get_http_address() {
+            let address = addr.into();
+            let apis = self.config.rpc.http.apis().list_apis();
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.http.max_batch_size,
+                self.config.rpc.http.max_response_body_size,
+            );
+            let io_handler = self.api_registry.get_apis(apis, batch_middleware);
+            let https = HttpsServer::start(
+                address,
+                io_handler,
+                RpcExtractor {
+                    http_ip_headers: self.config.rpc.http.ip_headers(),
+                    http_api_key_header: Some(self.config.rpc.api_keys.api_key_header()),
+                },
+                self.config.rpc.http.max_request_body_size(),
+                self.config.rpc.http.cors_origins.clone(),
+                TransportRecorder::new("https", self.config.rpc.slow_call_threshold()),
+                &tls,
+            )?;
+            info!(
+                "Rpc: https server start at :{}, client cert required: {}",
+                address,
+                tls.client_ca.is_some()
+            );
+            Some(https)
+        } else {
+            None
+        })
+    }
+
     fn start_tcp(&self) -> Result<Option<jsonrpc_tcp_server::Server>> {
         Ok(if let Some(addr) = self.config.rpc.get_tcp_address() {
             let address = addr.into();
             let apis = self.config.rpc.tcp.apis().list_apis();
-
-            let io_handler = self.api_registry.get_apis(apis);
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.tcp.max_batch_size,
+                self.config.rpc.tcp.max_response_body_size,
+            );
+            let io_handler = self.api_registry.get_apis(apis, batch_middleware);
             let tcp_server = jsonrpc_tcp_server::ServerBuilder::new(io_handler)
                 .session_meta_extractor(RpcExtractor::default())
                 .start(&address)?;
@@ -207,10 +369,21 @@ impl RpcService {
         Ok(if let Some(addr) = self.config.rpc.get_ws_address() {
             let address = addr.into();
             let apis = self.config.rpc.ws.apis().list_apis();
-            let io_handler = self.api_registry.get_apis(apis);
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.ws.max_batch_size,
+                self.config.rpc.ws.max_response_body_size,
+            );
+            let io_handler = self.api_registry.get_apis(apis, batch_middleware);
             let ws_server = jsonrpc_ws_server::ServerBuilder::new(io_handler)
                 .session_meta_extractor(WsExtractor)
+                .request_middleware(WsRequestMiddleware {
+                    ip_headers: self.config.rpc.http.ip_headers(),
+                    api_key_header: Some(self.config.rpc.api_keys.api_key_header()),
+                })
                 .max_payload(self.config.rpc.ws.max_request_body_size())
+                .max_in_buffer_capacity(self.config.rpc.ws.max_in_buffer_capacity())
+                .max_out_buffer_capacity(self.config.rpc.ws.max_out_buffer_capacity())
                 .start(&address)?;
             info!("Rpc: websocket server start at: {}", address);
             Some(ws_server)
@@ -219,6 +392,65 @@ impl RpcService {
         })
     }
 
+    #[cfg(feature = "rest-api")]
+    fn start_rest(&self) -> Result<Option<crate::rest::RestServer>> {
+        Ok(if let Some(addr) = self.config.rpc.get_rest_address() {
+            let address = addr.into();
+            // the rest api is in front of the http json rpc, so it serves the same apis.
+            let apis = self.config.rpc.http.apis().list_apis();
+            let batch_middleware = BatchLimitMiddleware::for_transport(
+                &self.config.rpc,
+                self.config.rpc.http.max_batch_size,
+                self.config.rpc.http.max_response_body_size,
+            );
+            let io_handler = self.api_registry.get_apis(apis, batch_middleware);
+            let rest_server = crate::rest::RestServer::start(
+                address,
+                io_handler,
+                RpcExtractor {
+                    http_ip_headers: self.config.rpc.http.ip_headers(),
+                    http_api_key_header: Some(self.config.rpc.api_keys.api_key_header()),
+                },
+                self.config.rpc.http.max_request_body_size(),
+            )?;
+            info!("Rpc: rest api server start at: {}", address);
+            Some(rest_server)
+        } else {
+            None
+        })
+    }
+
+    fn start_graphql(&self) -> Result<Option<GraphQLServer>> {
+        let (addr, schema) = match (
+            self.config.rpc.get_graphql_address(),
+            self.graphql_schema.clone(),
+        ) {
+            (Some(addr), Some(schema)) => (addr, schema),
+            _ => return Ok(None),
+        };
+        let address = addr.into();
+        // the graphql requests pass the middlewares of the http json rpc.
+        let apis = self.config.rpc.http.apis().list_apis();
+        let batch_middleware = BatchLimitMiddleware::for_transport(
+            &self.config.rpc,
+            self.config.rpc.http.max_batch_size,
+            self.config.rpc.http.max_response_body_size,
+        );
+        let mut io_handler = self.api_registry.get_apis(apis, batch_middleware);
+        add_graphql_method(&mut io_handler, schema);
+        let graphql_server = GraphQLServer::start(
+            address,
+            io_handler,
+            RpcExtractor {
+                http_ip_headers: self.config.rpc.http.ip_headers(),
+                http_api_key_header: Some(self.config.rpc.api_keys.api_key_header()),
+            },
+            self.config.rpc.http.max_request_body_size(),
+        )?;
+        info!("Rpc: graphql server start at: {}", address);
+        Ok(Some(graphql_server))
+    }
+
     pub fn close(&mut self) {
         if let Some(ipc) = self.ipc.take() {
             ipc.close();
@@ -226,12 +458,22 @@ impl RpcService {
         if let Some(http) = self.http.take() {
             http.close();
         }
+        if let Some(https) = self.https.take() {
+            https.close();
+        }
         if let Some(tcp) = self.tcp.take() {
             tcp.close();
         }
         if let Some(ws) = self.ws.take() {
             ws.close();
         }
+        #[cfg(feature = "rest-api")]
+        if let Some(rest) = self.rest.take() {
+            rest.close();
+        }
+        if let Some(graphql) = self.graphql.take() {
+            graphql.close();
+        }
         info!("Rpc Sever is closed.");
     }
 }
@@ -264,7 +506,9 @@ pub fn connect_local(
 impl ServiceHandler<Self, ConnectLocal> for RpcService {
     fn handle(&mut self, _msg: ConnectLocal, ctx: &mut ServiceContext<RpcService>) -> RpcChannel {
         let apis = ApiSet::All.list_apis();
-        let io_handler = self.api_registry.get_apis(apis);
+        let io_handler =
+            self.api_registry
+                .get_trusted_apis(apis, self.api_registry.batch_middleware(), None);
         //remove middleware.
         let mut local_io_handler = MetaIoHandler::default();
         local_io_handler.extend_with(io_handler.iter().map(|(n, f)| (n.clone(), f.clone())));
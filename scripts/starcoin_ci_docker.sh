@@ -44,7 +44,7 @@ function start_txfactory() {
   eval $(docker-machine env $host_name)
   docker_rebuild
   docker rm -f $name 1>/dev/null
-  docker run -td --restart=on-failure:10 -v $cfg_root/$starcoin_name:/.starcoin --name $name --entrypoint "/starcoin/starcoin_txfactory" starcoin:latest --ipc-path /.starcoin/$net/starcoin.ipc $@
+  docker run -td --restart=on-failure:10 -v $cfg_root/$starcoin_name:/.starcoin --name $name --entrypoint "/starcoin/starcoin_txfactory" starcoin:latest --ipc-path /.starcoin/$net/ipc/starcoin.ipc $@
   check_errs $? "Docker run txfactory error"
 }
 
@@ -3,7 +3,7 @@ set -x
 cfg_path="/.starcoin"
 net="$1"
 shift;
-rm -f $cfg_path/$net/starcoin.ipc &>/dev/null
+rm -f $cfg_path/$net/ipc/starcoin.ipc &>/dev/null
 rm -f $cfg_path/$net/peers.json &>/dev/null
 ./starcoin -d /.starcoin -n $net $@
 ret=$?
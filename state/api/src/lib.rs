@@ -22,6 +22,7 @@ pub use starcoin_vm_types::state_view::StateView;
 mod chain_state;
 pub mod message;
 pub mod mock;
+pub mod snapshot;
 
 #[async_trait::async_trait]
 pub trait ChainStateAsyncService: Clone + std::marker::Unpin + Send + Sync {
@@ -0,0 +1,52 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::state_set::ChainStateSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// The index file in the snapshot dir, which records the retained snapshots.
+pub const SNAPSHOT_INDEX_FILE: &str = "index.json";
+pub const SNAPSHOT_FILE_EXTENSION: &str = "snapshot";
+
+/// The full chain state at a block, saved as bcs in the snapshot file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    pub state_root: HashValue,
+    pub state: ChainStateSet,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    pub epoch: u64,
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    pub state_root: HashValue,
+    /// The snapshot file, a bcs serialized `StateSnapshot`.
+    pub file: PathBuf,
+    /// The snapshot file size in bytes.
+    pub size: u64,
+}
+
+/// Load the snapshot index in `dir`, sorted by block number, return empty if no snapshot.
+pub fn load_snapshot_index(dir: &Path) -> Result<Vec<SnapshotInfo>> {
+    let index_file = dir.join(SNAPSHOT_INDEX_FILE);
+    if !index_file.exists() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_reader(File::open(index_file)?)?)
+}
+
+pub fn save_snapshot_index(dir: &Path, snapshots: &[SnapshotInfo]) -> Result<()> {
+    // write to a temp file first, to avoid a broken index file when crash.
+    let tmp_file = dir.join(format!("{}.tmp", SNAPSHOT_INDEX_FILE));
+    serde_json::to_writer_pretty(File::create(tmp_file.as_path())?, snapshots)?;
+    std::fs::rename(tmp_file, dir.join(SNAPSHOT_INDEX_FILE))?;
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use starcoin_config::NodeConfig;
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_storage::archive::AccountStateIndex;
+use starcoin_storage::Storage;
+use starcoin_types::system_events::NewHeadBlock;
+use std::sync::Arc;
+
+/// The max number of blocks indexed on one event, the service keeps indexing by notifying itself
+/// until it catches up with the head, so a large backlog does not block the other events long.
+const INDEX_BATCH_BLOCKS: u64 = 1000;
+
+/// Index the account states of the new main chain blocks in archive mode, see
+/// `starcoin_storage::archive`. The blocks before the archive mode is enabled are indexed on start.
+pub struct ArchiveIndexService {
+    index: Option<AccountStateIndex>,
+}
+
+impl ArchiveIndexService {
+    pub fn new(index: Option<AccountStateIndex>) -> Self {
+        Self { index }
+    }
+
+    fn index_blocks(&self, ctx: &mut ServiceContext<Self>) {
+        if let Some(index) = self.index.as_ref() {
+            match index.index(INDEX_BATCH_BLOCKS) {
+                Ok(Some(_)) => ctx.notify(IndexAccountStateEvent),
+                Ok(None) => {}
+                Err(e) => error!("Index the account states error: {:?}", e),
+            }
+        }
+    }
+}
+
+impl ServiceFactory<Self> for ArchiveIndexService {
+    fn create(ctx: &mut ServiceContext<ArchiveIndexService>) -> Result<ArchiveIndexService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        let index = if config.storage.archive() {
+            Some(AccountStateIndex::new(storage))
+        } else {
+            None
+        };
+        Ok(Self::new(index))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexAccountStateEvent;
+
+impl ActorService for ArchiveIndexService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.index.is_some() {
+            ctx.subscribe::<NewHeadBlock>();
+            ctx.notify(IndexAccountStateEvent);
+        }
+        Ok(())
+    }
+
+    fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.index.is_some() {
+            ctx.unsubscribe::<NewHeadBlock>();
+        }
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, NewHeadBlock> for ArchiveIndexService {
+    fn handle_event(&mut self, _msg: NewHeadBlock, ctx: &mut ServiceContext<Self>) {
+        self.index_blocks(ctx);
+    }
+}
+
+impl EventHandler<Self, IndexAccountStateEvent> for ArchiveIndexService {
+    fn handle_event(&mut self, _msg: IndexAccountStateEvent, ctx: &mut ServiceContext<Self>) {
+        self.index_blocks(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_state_api::ChainStateReader;
+    use starcoin_statedb::ChainStateDB;
+    use starcoin_types::account_address::AccountAddress;
+    use starcoin_types::account_config::genesis_address;
+
+    #[stest::test(timeout = 120)]
+    fn test_account_state_index() -> Result<()> {
+        let node_handle = test_helper::run_test_node()?;
+        let storage = node_handle.storage();
+        let mut blocks = vec![];
+        for _i in 0..4 {
+            blocks.push(node_handle.generate_block()?);
+        }
+        let head = blocks.last().unwrap();
+
+        let index = AccountStateIndex::new(storage.clone());
+        assert!(index.get_account_state_at(genesis_address(), 0).is_err());
+        assert!(index.index(2)?.is_some());
+        assert_eq!(index.indexed_block_number()?, Some(1));
+        while index.index(2)?.is_some() {}
+        assert_eq!(index.indexed_block_number()?, Some(head.header().number()));
+        assert!(index.index(2)?.is_none());
+
+        let random_address = AccountAddress::random();
+        for block in blocks.iter() {
+            let number = block.header().number();
+            let state_db = ChainStateDB::new(storage.clone(), Some(block.header().state_root()));
+            let (changed_at, account_state) = index
+                .get_account_state_at(genesis_address(), number)?
+                .expect("genesis account must exist");
+            assert!(changed_at <= number);
+            assert_eq!(
+                Some(account_state),
+                state_db.get_account_state(&genesis_address())?
+            );
+            assert!(index
+                .get_account_state_at(random_address, number)?
+                .is_none());
+        }
+        assert!(index
+            .get_account_state_at(genesis_address(), head.header().number() + 1)
+            .is_err());
+        node_handle.stop()
+    }
+}
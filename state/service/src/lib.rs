@@ -1,6 +1,12 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod archive;
+mod pruning;
 mod service;
+mod snapshot;
 
+pub use archive::ArchiveIndexService;
+pub use pruning::StatePruningService;
 pub use service::ChainStateService;
+pub use snapshot::StateSnapshotService;
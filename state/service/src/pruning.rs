@@ -0,0 +1,189 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use starcoin_config::NodeConfig;
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_storage::pruner::StoragePruner;
+use starcoin_storage::Storage;
+use starcoin_types::system_events::NewHeadBlock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Prune the old blocks' states, events and transaction infos on new head blocks, the pruning is
+/// disabled in archive mode. The pruning runs in a dedicated thread until there are not enough
+/// blocks to prune, the new head blocks during a run are skipped.
+pub struct StatePruningService {
+    pruner: Option<Arc<StoragePruner>>,
+    running: Arc<AtomicBool>,
+}
+
+impl StatePruningService {
+    pub fn new(pruner: Option<StoragePruner>) -> Self {
+        Self {
+            pruner: pruner.map(Arc::new),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn try_prune(&self) {
+        let pruner = match self.pruner.as_ref() {
+            Some(pruner) => pruner.clone(),
+            None => return,
+        };
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let running = self.running.clone();
+        std::thread::spawn(move || {
+            loop {
+                match pruner.prune() {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Prune storage error: {:?}", e);
+                        break;
+                    }
+                }
+            }
+            running.store(false, Ordering::SeqCst);
+        });
+    }
+}
+
+impl ServiceFactory<Self> for StatePruningService {
+    fn create(ctx: &mut ServiceContext<StatePruningService>) -> Result<StatePruningService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        let pruner = config.storage.prune_retain_blocks().map(|retain_blocks| {
+            StoragePruner::new(storage, retain_blocks, config.storage.prune_batch_blocks())
+        });
+        Ok(Self::new(pruner))
+    }
+}
+
+impl ActorService for StatePruningService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.pruner.is_some() {
+            ctx.subscribe::<NewHeadBlock>();
+        }
+        Ok(())
+    }
+
+    fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.pruner.is_some() {
+            ctx.unsubscribe::<NewHeadBlock>();
+        }
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, NewHeadBlock> for StatePruningService {
+    fn handle_event(&mut self, _msg: NewHeadBlock, _ctx: &mut ServiceContext<Self>) {
+        self.try_prune();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_account_api::AccountInfo;
+    use starcoin_chain_mock::MockChain;
+    use starcoin_state_api::{ChainStateReader, StateNodeStore};
+    use starcoin_statedb::ChainStateDB;
+    use starcoin_storage::BlockStore;
+    use starcoin_types::account_config::genesis_address;
+    use starcoin_types::block::Block;
+
+    #[stest::test(timeout = 120)]
+    fn test_prune_storage() -> Result<()> {
+        let node_handle = test_helper::run_test_node()?;
+        let storage = node_handle.storage();
+        let genesis_id = storage.get_genesis()?.expect("genesis must exist");
+        let genesis = storage
+            .get_block_by_hash(genesis_id)?
+            .expect("genesis must exist");
+        let mut blocks = vec![];
+        for _i in 0..6 {
+            blocks.push(node_handle.generate_block()?);
+        }
+        let first = blocks.first().unwrap();
+        let head = blocks.last().unwrap();
+        assert!(!storage.get_block_txn_info_ids(first.id())?.is_empty());
+
+        let pruner = StoragePruner::new(storage.clone(), 2, 1);
+        while pruner.prune()?.is_some() {}
+        assert_eq!(
+            pruner.pruned_block_number()?,
+            Some(head.header().number() - 2)
+        );
+
+        assert!(storage.get_block_txn_info_ids(first.id())?.is_empty());
+        assert!(StateNodeStore::get(storage.as_ref(), &first.header().state_root())?.is_none());
+        for block in [&genesis, head].iter() {
+            let state_db = ChainStateDB::new(storage.clone(), Some(block.header().state_root()));
+            assert!(state_db.get_account_state(&genesis_address())?.is_some());
+        }
+        node_handle.stop()
+    }
+    fn fork_blocks(
+        node_handle: &test_helper::NodeHandle,
+        parent: &Block,
+        count: u64,
+    ) -> Result<Vec<Block>> {
+        let mut fork = MockChain::new_with_storage(
+            node_handle.config().net().clone(),
+            node_handle.storage(),
+            parent.id(),
+            AccountInfo::random(),
+        )?;
+        let mut blocks = vec![];
+        for _i in 0..count {
+            let block = fork.produce()?;
+            fork.apply(block.clone())?;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    fn assert_state_readable(storage: Arc<Storage>, block: &Block) -> Result<()> {
+        let state_db = ChainStateDB::new(storage, Some(block.header().state_root()));
+        assert!(state_db.get_account_state(&genesis_address())?.is_some());
+        Ok(())
+    }
+
+    #[stest::test(timeout = 120)]
+    fn test_prune_storage_with_forks() -> Result<()> {
+        let node_handle = test_helper::run_test_node()?;
+        let storage = node_handle.storage();
+        let mut blocks = vec![];
+        for _i in 0..2 {
+            blocks.push(node_handle.generate_block()?);
+        }
+        // A fork block at height 2, which is below the retained blocks after 4 more blocks.
+        let dead_fork = fork_blocks(&node_handle, &blocks[0], 1)?;
+        for _i in 0..4 {
+            blocks.push(node_handle.generate_block()?);
+        }
+        let pruner = StoragePruner::new(storage.clone(), 2, 1);
+        while pruner.prune()?.is_some() {}
+        assert_eq!(pruner.pruned_block_number()?, Some(4));
+        assert!(
+            StateNodeStore::get(storage.as_ref(), &dead_fork[0].header().state_root())?.is_none()
+        );
+
+        // A fork from block 4 with blocks in the retained blocks, the pruning stops below it.
+        let live_fork = fork_blocks(&node_handle, &blocks[3], 5)?;
+        for _i in 0..4 {
+            blocks.push(node_handle.generate_block()?);
+        }
+        while pruner.prune()?.is_some() {}
+        assert_eq!(pruner.pruned_block_number()?, Some(4));
+        assert_state_readable(storage.clone(), &blocks[3])?;
+        for block in live_fork.iter().chain(blocks.last()) {
+            assert_state_readable(storage.clone(), block)?;
+        }
+        node_handle.stop()
+    }
+}
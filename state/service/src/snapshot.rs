@@ -0,0 +1,313 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use starcoin_config::{NodeConfig, SnapshotConfig};
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_state_api::snapshot::{
+    load_snapshot_index, save_snapshot_index, SnapshotInfo, StateSnapshot, SNAPSHOT_FILE_EXTENSION,
+};
+use starcoin_state_api::{ChainStateReader, StateReaderExt};
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_types::block::BlockHeader;
+use starcoin_types::system_events::NewHeadBlock;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+
+/// Produce a state snapshot at the first block of every `epoch_interval` epochs,
+/// and only retain the latest snapshots, the snapshots are recorded in the index file of the snapshot dir.
+/// The new head blocks are handled by a background thread, because dumping the state takes a long time.
+pub struct StateSnapshotService {
+    config: SnapshotConfig,
+    storage: Arc<Storage>,
+    sender: Option<Sender<BlockHeader>>,
+}
+
+impl StateSnapshotService {
+    pub fn new(config: SnapshotConfig, storage: Arc<Storage>) -> Self {
+        Self {
+            config,
+            storage,
+            sender: None,
+        }
+    }
+}
+
+/// Produce and retain the snapshots in the snapshot dir, run in the background thread of `StateSnapshotService`.
+pub(crate) struct SnapshotProducer {
+    dir: PathBuf,
+    epoch_interval: u64,
+    retain: usize,
+    storage: Arc<Storage>,
+    head: Option<BlockHeader>,
+}
+
+impl SnapshotProducer {
+    pub(crate) fn new(
+        dir: PathBuf,
+        epoch_interval: u64,
+        retain: u64,
+        storage: Arc<Storage>,
+    ) -> Self {
+        Self {
+            dir,
+            epoch_interval,
+            retain: retain.max(1) as usize,
+            storage,
+            head: None,
+        }
+    }
+
+    /// Handle a new head, remove the snapshots on the forked branch if the head is switched,
+    /// and produce a snapshot if the head is the first block of a snapshot epoch.
+    pub(crate) fn on_new_head(&mut self, header: BlockHeader) -> Result<Option<SnapshotInfo>> {
+        let switched = match &self.head {
+            Some(head) => head.id() != header.parent_hash(),
+            None => true,
+        };
+        self.head = Some(header.clone());
+        if switched {
+            let removed = self.remove_forked_snapshots(&header)?;
+            if !removed.is_empty() {
+                info!(
+                    "Remove {} state snapshots on the forked branch after the head switched to {}",
+                    removed.len(),
+                    header.id()
+                );
+            }
+        }
+        self.try_snapshot(&header)
+    }
+
+    /// Remove the snapshots which are not ancestors of `head`, such as the snapshots on a forked branch after reorg.
+    pub(crate) fn remove_forked_snapshots(&self, head: &BlockHeader) -> Result<Vec<SnapshotInfo>> {
+        let snapshots = load_snapshot_index(self.dir.as_path())?;
+        let min_number = match snapshots.first() {
+            Some(snapshot) => snapshot.block_number,
+            None => return Ok(vec![]),
+        };
+        let mut ancestors = HashMap::new();
+        let mut header = head.clone();
+        loop {
+            if snapshots.iter().any(|s| s.block_number == header.number()) {
+                ancestors.insert(header.number(), header.id());
+            }
+            if header.number() <= min_number {
+                break;
+            }
+            header = self
+                .storage
+                .get_block_header_by_hash(header.parent_hash())?
+                .ok_or_else(|| format_err!("cannot find block header {}", header.parent_hash()))?;
+        }
+        let (retained, forked): (Vec<_>, Vec<_>) = snapshots
+            .into_iter()
+            .partition(|s| ancestors.get(&s.block_number) == Some(&s.block_hash));
+        if !forked.is_empty() {
+            save_snapshot_index(self.dir.as_path(), retained.as_slice())?;
+            remove_snapshot_files(forked.as_slice());
+        }
+        Ok(forked)
+    }
+
+    fn try_snapshot(&self, header: &BlockHeader) -> Result<Option<SnapshotInfo>> {
+        let state_db = ChainStateDB::new(self.storage.clone(), Some(header.state_root()));
+        let epoch = state_db.get_epoch()?;
+        if header.number() != epoch.start_block_number()
+            || epoch.number() % self.epoch_interval != 0
+        {
+            return Ok(None);
+        }
+        let dir = self.dir.as_path();
+        let mut snapshots = load_snapshot_index(dir)?;
+        if snapshots.iter().any(|s| s.block_hash == header.id()) {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(dir)?;
+        let snapshot = StateSnapshot {
+            block_number: header.number(),
+            block_hash: header.id(),
+            state_root: header.state_root(),
+            state: state_db.dump()?,
+        };
+        let mut file = dir.join(format!(
+            "{}-{}",
+            header.number(),
+            &header.id().to_hex()[..8]
+        ));
+        file.set_extension(SNAPSHOT_FILE_EXTENSION);
+        bcs_ext::serialize_into(&mut File::create(file.as_path())?, &snapshot)?;
+        let info = SnapshotInfo {
+            epoch: epoch.number(),
+            block_number: header.number(),
+            block_hash: header.id(),
+            state_root: header.state_root(),
+            size: std::fs::metadata(file.as_path())?.len(),
+            file,
+        };
+        snapshots.push(info.clone());
+        snapshots.sort_by_key(|s| s.block_number);
+        let expired = if snapshots.len() > self.retain {
+            snapshots
+                .drain(..snapshots.len() - self.retain)
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+        save_snapshot_index(dir, snapshots.as_slice())?;
+        remove_snapshot_files(expired.as_slice());
+        Ok(Some(info))
+    }
+}
+
+fn remove_snapshot_files(snapshots: &[SnapshotInfo]) {
+    for snapshot in snapshots {
+        if let Err(e) = std::fs::remove_file(snapshot.file.as_path()) {
+            warn!("Remove snapshot {:?} error: {:?}", snapshot.file, e);
+        }
+    }
+}
+
+impl ServiceFactory<Self> for StateSnapshotService {
+    fn create(ctx: &mut ServiceContext<StateSnapshotService>) -> Result<StateSnapshotService> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        Ok(Self::new(config.snapshot.clone(), storage))
+    }
+}
+
+impl ActorService for StateSnapshotService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.config.is_enabled() {
+            let mut producer = SnapshotProducer::new(
+                self.config.dir(),
+                self.config.epoch_interval(),
+                self.config.retain(),
+                self.storage.clone(),
+            );
+            let (sender, receiver) = channel::<BlockHeader>();
+            std::thread::Builder::new()
+                .name("state-snapshot".to_string())
+                .spawn(move || {
+                    // exit when the sender is dropped at service stopped.
+                    while let Ok(header) = receiver.recv() {
+                        let number = header.number();
+                        match producer.on_new_head(header) {
+                            Ok(Some(info)) => info!(
+                                "Produce state snapshot at epoch {} block {}: {:?}",
+                                info.epoch, info.block_number, info.file
+                            ),
+                            Ok(None) => {}
+                            Err(e) => {
+                                error!("Produce state snapshot at block {} error: {:?}", number, e)
+                            }
+                        }
+                    }
+                })?;
+            self.sender = Some(sender);
+            ctx.subscribe::<NewHeadBlock>();
+        }
+        Ok(())
+    }
+
+    fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        if self.config.is_enabled() {
+            ctx.unsubscribe::<NewHeadBlock>();
+        }
+        self.sender = None;
+        Ok(())
+    }
+}
+
+impl EventHandler<Self, NewHeadBlock> for StateSnapshotService {
+    fn handle_event(&mut self, msg: NewHeadBlock, _ctx: &mut ServiceContext<Self>) {
+        let NewHeadBlock(block) = msg;
+        if let Some(sender) = &self.sender {
+            if let Err(e) = sender.send(block.header().clone()) {
+                error!("Send new head to the state snapshot thread error: {:?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::HashValue;
+    use starcoin_state_api::snapshot::SNAPSHOT_INDEX_FILE;
+
+    #[stest::test(timeout = 120)]
+    fn test_produce_snapshot() -> Result<()> {
+        let node_handle = test_helper::run_test_node()?;
+        let storage = node_handle.storage();
+        let genesis_id = storage.get_genesis()?.expect("genesis must exist");
+        let genesis = storage
+            .get_block_header_by_hash(genesis_id)?
+            .expect("genesis must exist");
+        let blocks = (0..3)
+            .map(|_| node_handle.generate_block())
+            .collect::<Result<Vec<_>>>()?;
+
+        let dir = starcoin_config::temp_path();
+        let mut producer = SnapshotProducer::new(dir.path().to_path_buf(), 1, 2, storage.clone());
+        // the genesis is the first block of epoch 0.
+        let info = producer
+            .on_new_head(genesis.clone())?
+            .expect("snapshot at genesis");
+        assert_eq!(info.block_hash, genesis.id());
+        for block in blocks.iter() {
+            assert!(producer.on_new_head(block.header().clone())?.is_none());
+        }
+        // the same block is not snapshot twice.
+        assert!(producer.on_new_head(genesis.clone())?.is_none());
+
+        let snapshots = load_snapshot_index(dir.path())?;
+        assert_eq!(snapshots, vec![info.clone()]);
+        assert!(dir.path().join(SNAPSHOT_INDEX_FILE).exists());
+        let snapshot: StateSnapshot = bcs_ext::from_bytes(std::fs::read(info.file)?.as_slice())?;
+        assert_eq!(snapshot.state_root, genesis.state_root());
+        assert!(!snapshot.state.is_empty());
+        node_handle.stop()
+    }
+
+    #[stest::test(timeout = 120)]
+    fn test_remove_forked_snapshots() -> Result<()> {
+        let node_handle = test_helper::run_test_node()?;
+        let storage = node_handle.storage();
+        let genesis_id = storage.get_genesis()?.expect("genesis must exist");
+        let genesis = storage
+            .get_block_header_by_hash(genesis_id)?
+            .expect("genesis must exist");
+        let blocks = (0..3)
+            .map(|_| node_handle.generate_block())
+            .collect::<Result<Vec<_>>>()?;
+        let head = blocks.last().unwrap().header().clone();
+
+        let dir = starcoin_config::temp_path();
+        let mut producer = SnapshotProducer::new(dir.path().to_path_buf(), 1, 3, storage.clone());
+        let info = producer.on_new_head(genesis)?.expect("snapshot at genesis");
+        // a snapshot on a forked branch at block 1.
+        let forked_file = dir.path().join("1-forked.snapshot");
+        std::fs::write(forked_file.as_path(), b"forked")?;
+        let forked = SnapshotInfo {
+            epoch: 0,
+            block_number: 1,
+            block_hash: HashValue::random(),
+            state_root: HashValue::random(),
+            file: forked_file.clone(),
+            size: 6,
+        };
+        save_snapshot_index(dir.path(), &[info.clone(), forked.clone()])?;
+
+        // the head is not a child of the last handled head, so it is a switch.
+        assert!(producer.on_new_head(head)?.is_none());
+        assert_eq!(load_snapshot_index(dir.path())?, vec![info]);
+        assert!(!forked_file.exists());
+        node_handle.stop()
+    }
+}
@@ -5,10 +5,33 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use starcoin_crypto::hash::HashValue;
 use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
+use std::thread::ThreadId;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StateNode(pub Vec<u8>);
 
+impl StateNode {
+    /// Recompute the hash of the node, it does not depend on the raw key type of the tree.
+    pub fn hash(&self) -> Result<HashValue> {
+        Ok(Node::<EncodedKey>::decode(self.0.as_slice())?.hash())
+    }
+}
+
+/// A raw key which keeps the encoded key bytes of a leaf node as is,
+/// so the leaf node can be decoded without knowing the raw key type.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct EncodedKey(Vec<u8>);
+
+impl RawKey for EncodedKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+
+    fn decode_key(bytes: &[u8]) -> Result<Self> {
+        Ok(EncodedKey(bytes.to_vec()))
+    }
+}
+
 impl<K> TryFrom<Node<K>> for StateNode
 where
     K: RawKey,
@@ -50,8 +73,29 @@ impl Serialize for StateNode {
     }
 }
 
+/// The keys of the state nodes written by the flushes of the state trees, and of the nodes replaced
+/// by them, which are not in the flushed states any more.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateNodeChanges {
+    pub written: Vec<HashValue>,
+    pub stale: Vec<HashValue>,
+}
+
+impl StateNodeChanges {
+    pub fn extend(&mut self, other: StateNodeChanges) {
+        self.written.extend(other.written);
+        self.stale.extend(other.stale);
+    }
+}
+
 pub trait StateNodeStore: std::marker::Send + std::marker::Sync {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>>;
     fn put(&self, key: HashValue, node: StateNode) -> Result<()>;
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()>;
+
+    /// Read the nodes visible to the `owner` thread on the current thread, until
+    /// `detach_reader`, see `TreeReader::attach_reader`.
+    fn attach_reader(&self, _owner: ThreadId) {}
+
+    fn detach_reader(&self) {}
 }
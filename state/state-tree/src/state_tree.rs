@@ -14,6 +14,7 @@ use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::ops::DerefMut;
 use std::sync::Arc;
+use std::thread::ThreadId;
 
 pub struct StateCache<K: RawKey> {
     root_hash: HashValue,
@@ -75,6 +76,8 @@ pub struct StateTree<K: RawKey> {
     storage_root_hash: RwLock<HashValue>,
     updates: RwLock<BTreeMap<K, Option<Blob>>>,
     cache: Mutex<StateCache<K>>,
+    /// The nodes written and replaced by the flushes, until they are taken.
+    node_changes: Mutex<StateNodeChanges>,
 }
 
 impl<K> Clone for StateTree<K>
@@ -98,6 +101,7 @@ where
             storage_root_hash: RwLock::new(state_root_hash),
             updates: RwLock::new(BTreeMap::new()),
             cache: Mutex::new(StateCache::new(state_root_hash)),
+            node_changes: Mutex::new(StateNodeChanges::default()),
         }
     }
 
@@ -196,13 +200,27 @@ where
         for (nk, n) in change_sets.node_batch.into_iter() {
             node_map.insert(nk, n.try_into()?);
         }
+        let written = node_map.keys().copied().collect();
         self.storage.write_nodes(node_map)?;
+        self.node_changes.lock().extend(StateNodeChanges {
+            written,
+            stale: change_sets
+                .stale_node_index_batch
+                .into_iter()
+                .map(|index| index.node_key)
+                .collect(),
+        });
         // and then advance the storage root hash
         *self.storage_root_hash.write() = root_hash;
         self.cache.lock().reset(root_hash);
         Ok(())
     }
 
+    /// Take the nodes written and replaced by the flushes since the last take.
+    pub fn take_node_changes(&self) -> StateNodeChanges {
+        std::mem::take(&mut *self.node_changes.lock())
+    }
+
     /// Dump tree to state set.
     pub fn dump(&self) -> Result<StateSet> {
         let cur_root_hash = self.root_hash();
@@ -221,6 +239,34 @@ where
         Ok(StateSet::new(states))
     }
 
+    /// Dump at most `limit` states of the tree, start from the state whose key hash is `start_key_hash`,
+    /// return the states and the key hash of the next state if there are more states.
+    pub fn dump_range(
+        &self,
+        start_key_hash: HashValue,
+        limit: usize,
+    ) -> Result<(StateSet, Option<HashValue>)> {
+        let cur_root_hash = self.root_hash();
+        let mut cache_guard = self.cache.lock();
+        let cache = cache_guard.deref_mut();
+        let reader = CachedTreeReader {
+            store: self.storage.as_ref(),
+            cache,
+        };
+        let iterator = JellyfishMerkleIterator::new(&reader, cur_root_hash, start_key_hash)?;
+        let mut states = vec![];
+        let mut next_key_hash = None;
+        for item in iterator {
+            let item = item?;
+            if states.len() >= limit {
+                next_key_hash = Some(item.0.key_hash());
+                break;
+            }
+            states.push((item.0.encode_key()?, item.1.into()));
+        }
+        Ok((StateSet::new(states), next_key_hash))
+    }
+
     /// passing None value with a key means delete the key
     fn updates(&self, updates: Vec<(K, Option<Blob>)>) -> Result<HashValue> {
         let cur_root_hash = self.root_hash();
@@ -313,4 +359,12 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn attach_reader(&self, owner: ThreadId) {
+        self.store.attach_reader(owner);
+    }
+
+    fn detach_reader(&self) {
+        self.store.detach_reader();
+    }
 }
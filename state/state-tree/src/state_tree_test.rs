@@ -166,6 +166,45 @@ pub fn test_state_dump() -> Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_state_dump_range() -> Result<()> {
+    let s = MockStateNodeStore::new();
+    let state = StateTree::new(Arc::new(s), None);
+    for i in 0..5u8 {
+        state.put(HashValueKey(HashValue::random()), vec![i]);
+    }
+    state.commit()?;
+
+    let mut dumped = vec![];
+    let mut cursor = Some(HashValue::zero());
+    while let Some(start) = cursor {
+        let (state_set, next) = state.dump_range(start, 2)?;
+        assert!(state_set.len() <= 2);
+        dumped.extend(state_set.iter().cloned());
+        cursor = next;
+    }
+    assert_eq!(dumped, state.dump()?.iter().cloned().collect::<Vec<_>>());
+    assert_eq!(5, dumped.len());
+    Ok(())
+}
+
+#[test]
+pub fn test_state_node_hash() -> Result<()> {
+    let s = Arc::new(MockStateNodeStore::new());
+    let state = StateTree::<String>::new(s.clone(), None);
+    for i in 0..5u8 {
+        state.put(format!("key{}", i), vec![i]);
+    }
+    state.commit()?;
+    state.flush()?;
+    let nodes = s.all_nodes();
+    assert!(!nodes.is_empty());
+    for (hash, node) in nodes {
+        assert_eq!(node.hash()?, hash);
+    }
+    Ok(())
+}
+
 #[test]
 pub fn test_repeat_commit() -> Result<()> {
     let s = MockStateNodeStore::new();
@@ -14,13 +14,13 @@ pub use starcoin_state_api::{
     ChainState, ChainStateReader, ChainStateWriter, StateProof, StateWithProof,
 };
 use starcoin_state_tree::mock::MockStateNodeStore;
-use starcoin_state_tree::{StateNodeStore, StateTree};
+use starcoin_state_tree::{StateNodeChanges, StateNodeStore, StateTree};
 use starcoin_types::write_set::{WriteOp, WriteSet, WriteSetMut};
 use starcoin_types::{
     access_path::{AccessPath, DataType},
     account_address::AccountAddress,
     account_state::AccountState,
-    state_set::{AccountStateSet, ChainStateSet},
+    state_set::{AccountStateSet, ChainStateSet, StateSet},
 };
 use starcoin_vm_types::access_path::{DataPath, ModuleName};
 use starcoin_vm_types::language_storage::StructTag;
@@ -188,6 +188,14 @@ impl AccountStateObject {
         Ok(())
     }
 
+    fn take_node_changes(&self) -> StateNodeChanges {
+        let mut changes = self.resource_tree.lock().take_node_changes();
+        if let Some(code_tree) = self.code_tree.lock().as_ref() {
+            changes.extend(code_tree.take_node_changes());
+        }
+        changes
+    }
+
     fn to_state_set(&self) -> Result<AccountStateSet> {
         let code_root = self
             .code_tree
@@ -212,6 +220,8 @@ pub struct ChainStateDB {
     state_tree: StateTree<AccountAddress>,
     cache: Mutex<LruCache<AccountAddress, CacheItem>>,
     updates: RwLock<HashSet<AccountAddress>>,
+    /// The nodes written and replaced by the flushes of the global and the account trees.
+    node_changes: Mutex<StateNodeChanges>,
 }
 
 static DEFAULT_CACHE_SIZE: usize = 10240;
@@ -227,9 +237,16 @@ impl ChainStateDB {
             state_tree: StateTree::new(store, root_hash),
             cache: Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)),
             updates: RwLock::new(HashSet::new()),
+            node_changes: Mutex::new(StateNodeChanges::default()),
         }
     }
 
+    /// Take the state nodes written and replaced by the flushes since the last take, the pruner
+    /// deletes the replaced nodes when the older states are pruned.
+    pub fn take_node_changes(&self) -> StateNodeChanges {
+        std::mem::take(&mut *self.node_changes.lock())
+    }
+
     /// Fork a new statedb base current statedb
     pub fn fork(&self) -> Self {
         Self::new(self.store.clone(), Some(self.state_root()))
@@ -242,6 +259,7 @@ impl ChainStateDB {
             state_tree: StateTree::new(self.store.clone(), Some(root_hash)),
             cache: Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)),
             updates: RwLock::new(HashSet::new()),
+            node_changes: Mutex::new(StateNodeChanges::default()),
         }
     }
 
@@ -307,6 +325,52 @@ impl ChainStateDB {
                 None => Ok(None),
             })
     }
+
+    /// Dump the states of at most `limit` accounts, start from the account whose key hash is `start_key_hash`,
+    /// return the states and the key hash of the next account if there are more accounts.
+    /// The states of an account are always in one page.
+    pub fn dump_range(
+        &self,
+        start_key_hash: HashValue,
+        limit: usize,
+    ) -> Result<(ChainStateSet, Option<HashValue>)> {
+        let (global_states, next_key_hash) = self.state_tree.dump_range(start_key_hash, limit)?;
+        Ok((self.to_chain_state_set(global_states)?, next_key_hash))
+    }
+
+    fn to_chain_state_set(&self, global_states: StateSet) -> Result<ChainStateSet> {
+        let mut account_states = vec![];
+        for (address_bytes, account_state_bytes) in global_states.iter() {
+            let account_state: AccountState = account_state_bytes.as_slice().try_into()?;
+
+            let mut state_sets = vec![];
+            for (idx, storage_root) in account_state.storage_roots().iter().enumerate() {
+                let state_set = match storage_root {
+                    Some(storage_root) => {
+                        let data_type = DataType::from_index(idx as u8)?;
+                        match data_type {
+                            DataType::CODE => {
+                                Some(self.new_state_tree::<ModuleName>(*storage_root).dump()?)
+                            }
+                            DataType::RESOURCE => {
+                                Some(self.new_state_tree::<StructTag>(*storage_root).dump()?)
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
+                state_sets.push(state_set);
+            }
+            let account_state_set = AccountStateSet::new(state_sets);
+
+            account_states.push((
+                AccountAddress::decode_key(address_bytes.as_slice())?,
+                account_state_set,
+            ));
+        }
+        Ok(ChainStateSet::new(account_states))
+    }
 }
 
 impl ChainState for ChainStateDB {}
@@ -393,37 +457,7 @@ impl ChainStateReader for ChainStateDB {
         //TODO check cache dirty object.
         //TODO performance optimize.
         let global_states = self.state_tree.dump()?;
-        let mut account_states = vec![];
-        for (address_bytes, account_state_bytes) in global_states.iter() {
-            let account_state: AccountState = account_state_bytes.as_slice().try_into()?;
-
-            let mut state_sets = vec![];
-            for (idx, storage_root) in account_state.storage_roots().iter().enumerate() {
-                let state_set = match storage_root {
-                    Some(storage_root) => {
-                        let data_type = DataType::from_index(idx as u8)?;
-                        match data_type {
-                            DataType::CODE => {
-                                Some(self.new_state_tree::<ModuleName>(*storage_root).dump()?)
-                            }
-                            DataType::RESOURCE => {
-                                Some(self.new_state_tree::<StructTag>(*storage_root).dump()?)
-                            }
-                        }
-                    }
-                    None => None,
-                };
-
-                state_sets.push(state_set);
-            }
-            let account_state_set = AccountStateSet::new(state_sets);
-
-            account_states.push((
-                AccountAddress::decode_key(address_bytes.as_slice())?,
-                account_state_set,
-            ));
-        }
-        Ok(ChainStateSet::new(account_states))
+        self.to_chain_state_set(global_states)
     }
 }
 
@@ -530,13 +564,17 @@ impl ChainStateWriter for ChainStateDB {
     fn flush(&self) -> Result<()> {
         //cache flush
         let mut locks = self.updates.write();
+        let mut node_changes = self.node_changes.lock();
         for address in locks.iter() {
             let account_state_object = self.get_account_state_object(address, false)?;
             account_state_object.flush()?;
+            node_changes.extend(account_state_object.take_node_changes());
         }
         locks.clear();
         // self tree flush
-        self.state_tree.flush()
+        self.state_tree.flush()?;
+        node_changes.extend(self.state_tree.take_node_changes());
+        Ok(())
     }
 }
 
@@ -0,0 +1,337 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The account state index of the archive mode.
+//!
+//! In archive mode no state is pruned, so the state of every block is readable by its state root,
+//! but an account read by a state root walks the global state tree from the root. The index maps
+//! the (address, block number) at which the account state is changed on the main chain to the new
+//! account state, so the account state at block N is a single seek to the latest change at or
+//! before N, then only the resource or code tree of the account is walked.
+
+use crate::define_storage;
+use crate::storage::{CodecKVStore, KeyCodec, ValueCodec};
+use crate::{
+    BlockStore, Storage, ACCOUNT_STATE_INDEX_PREFIX_NAME, ARCHIVE_INDEXED_BLOCK_PREFIX_NAME,
+};
+use anyhow::{bail, ensure, format_err, Result};
+use bcs_ext::BCSCodec;
+use crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use crypto::HashValue;
+use forkable_jellyfish_merkle::nibble::Nibble;
+use forkable_jellyfish_merkle::node_type::{InternalNode, Node};
+use logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_state_store_api::StateNodeStore;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::block::BlockNumber;
+use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AccountStateIndexKey {
+    pub address: AccountAddress,
+    pub block_number: BlockNumber,
+}
+
+impl AccountStateIndexKey {
+    pub fn new(address: AccountAddress, block_number: BlockNumber) -> Self {
+        Self {
+            address,
+            block_number,
+        }
+    }
+}
+
+/// The key is the address followed by the big endian block number, so the changes of an account
+/// are sorted by the block number.
+impl KeyCodec for AccountStateIndexKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = self.address.to_vec();
+        key.extend_from_slice(&self.block_number.to_be_bytes());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == AccountAddress::LENGTH + 8,
+            "Invalid account state index key length: {}",
+            data.len()
+        );
+        let (address, number) = data.split_at(AccountAddress::LENGTH);
+        Ok(Self {
+            address: AccountAddress::from_bytes(address)?,
+            block_number: u64::from_be_bytes(number.try_into()?),
+        })
+    }
+}
+
+impl ValueCodec for AccountState {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.clone().try_into()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        AccountState::try_from(data)
+    }
+}
+
+/// The addresses indexed at a main chain block, used to remove the entries of the block when it is
+/// rolled back by a reorg.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IndexedBlock {
+    pub block_id: HashValue,
+    pub addresses: Vec<AccountAddress>,
+}
+
+impl ValueCodec for IndexedBlock {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+define_storage!(
+    AccountStateIndexStorage,
+    AccountStateIndexKey,
+    AccountState,
+    ACCOUNT_STATE_INDEX_PREFIX_NAME
+);
+
+define_storage!(
+    IndexedBlockStorage,
+    BlockNumber,
+    IndexedBlock,
+    ARCHIVE_INDEXED_BLOCK_PREFIX_NAME
+);
+
+/// Build and query the account state index, the index follows the main chain block by block.
+pub struct AccountStateIndex {
+    storage: Arc<Storage>,
+}
+
+impl AccountStateIndex {
+    pub fn new(storage: Arc<Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// The number of the latest indexed main chain block.
+    pub fn indexed_block_number(&self) -> Result<Option<BlockNumber>> {
+        self.storage
+            .chain_info_storage
+            .get_archive_indexed_block_number()
+    }
+
+    /// Index at most `max_blocks` main chain blocks after the indexed block, the indexed blocks
+    /// which are not on the main chain any more are removed first. Return the new indexed block
+    /// number, or None if all the main chain blocks have been indexed.
+    pub fn index(&self, max_blocks: u64) -> Result<Option<BlockNumber>> {
+        let accumulator = self.storage.head_block_accumulator()?;
+        let indexed = self.rollback(&accumulator)?;
+        let start = indexed.map(|number| number + 1).unwrap_or(0);
+        if start >= accumulator.num_leaves() {
+            return Ok(None);
+        }
+        if let Some(pruned) = self.storage.chain_info_storage.get_pruned_block_number()? {
+            if start <= pruned {
+                bail!(
+                    "The states of the blocks before {} have been pruned, can not index block {}",
+                    pruned + 1,
+                    start
+                );
+            }
+        }
+        let end = (start + max_blocks.max(1)).min(accumulator.num_leaves()) - 1;
+        let mut parent_state_root = match indexed {
+            Some(number) => Some(self.state_root(&accumulator, number)?),
+            None => None,
+        };
+        for number in start..=end {
+            let block_id = Self::block_id(&accumulator, number)?;
+            let state_root = self.state_root(&accumulator, number)?;
+            let accounts = self.changed_accounts(parent_state_root, state_root)?;
+            let addresses = accounts.iter().map(|(address, _)| *address).collect();
+            self.storage.account_state_index_storage.put_all(
+                accounts
+                    .into_iter()
+                    .map(|(address, state)| (AccountStateIndexKey::new(address, number), state))
+                    .collect(),
+            )?;
+            self.storage.indexed_block_storage.put(
+                number,
+                IndexedBlock {
+                    block_id,
+                    addresses,
+                },
+            )?;
+            self.storage
+                .chain_info_storage
+                .save_archive_indexed_block_number(number)?;
+            parent_state_root = Some(state_root);
+        }
+        debug!("Indexed the account states of blocks [{}, {}]", start, end);
+        Ok(Some(end))
+    }
+
+    /// Get the account state at the main chain block `block_number`, and the number of the block
+    /// at which the account state was last changed, None if the account does not exist at the block.
+    pub fn get_account_state_at(
+        &self,
+        address: AccountAddress,
+        block_number: BlockNumber,
+    ) -> Result<Option<(BlockNumber, AccountState)>> {
+        let indexed = self
+            .indexed_block_number()?
+            .ok_or_else(|| format_err!("The account state index is empty"))?;
+        ensure!(
+            block_number <= indexed,
+            "The block {} is not indexed yet, the latest indexed block is {}",
+            block_number,
+            indexed
+        );
+        let db = self
+            .storage
+            .db()
+            .ok_or_else(|| format_err!("The account state index requires the db storage"))?;
+        let mut iter = db.rev_iter(ACCOUNT_STATE_INDEX_PREFIX_NAME)?;
+        iter.seek_for_prev(AccountStateIndexKey::new(address, block_number).encode_key()?)?;
+        match iter.next().transpose()? {
+            Some((key, value)) => {
+                let key = AccountStateIndexKey::decode_key(&key)?;
+                if key.address != address {
+                    return Ok(None);
+                }
+                Ok(Some((
+                    key.block_number,
+                    AccountState::decode_value(&value)?,
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the entries of the indexed blocks which are not on the main chain, return the latest
+    /// indexed block number after the rollback.
+    fn rollback(&self, accumulator: &MerkleAccumulator) -> Result<Option<BlockNumber>> {
+        let mut indexed = self.indexed_block_number()?;
+        while let Some(number) = indexed {
+            let block = self
+                .storage
+                .indexed_block_storage
+                .get(number)?
+                .ok_or_else(|| format_err!("Indexed block {} is none.", number))?;
+            if accumulator.get_leaf(number)? == Some(block.block_id) {
+                break;
+            }
+            ensure!(
+                number > 0,
+                "The indexed genesis block {} is not on the main chain",
+                block.block_id
+            );
+            self.storage.account_state_index_storage.delete_all(
+                block
+                    .addresses
+                    .into_iter()
+                    .map(|address| AccountStateIndexKey::new(address, number))
+                    .collect(),
+            )?;
+            self.storage.indexed_block_storage.remove(number)?;
+            self.storage
+                .chain_info_storage
+                .save_archive_indexed_block_number(number - 1)?;
+            info!("Rollback the account state index of block {}", number);
+            indexed = Some(number - 1);
+        }
+        Ok(indexed)
+    }
+
+    /// Diff the global state trees, return the accounts whose state is changed. The subtrees with
+    /// the same hash at the same position are skipped, the accounts whose leaf is only moved in the
+    /// tree may be returned too.
+    fn changed_accounts(
+        &self,
+        parent_state_root: Option<HashValue>,
+        state_root: HashValue,
+    ) -> Result<Vec<(AccountAddress, AccountState)>> {
+        let mut accounts = vec![];
+        let mut pending = vec![(state_root, parent_state_root)];
+        while let Some((hash, parent_hash)) = pending.pop() {
+            if Some(hash) == parent_hash {
+                continue;
+            }
+            let node = match self.get_node(hash)? {
+                Some(node) => node,
+                None => continue,
+            };
+            match node {
+                Node::Internal(internal) => {
+                    let parent_internal = match parent_hash {
+                        Some(parent_hash) => match self.get_node(parent_hash) {
+                            Ok(Some(Node::Internal(parent_internal))) => Some(parent_internal),
+                            _ => None,
+                        },
+                        None => None,
+                    };
+                    pending.extend(Self::children(&internal, parent_internal.as_ref()));
+                }
+                Node::Leaf(leaf) => accounts.push((
+                    *leaf.raw_key(),
+                    AccountState::try_from(leaf.blob().as_ref())?,
+                )),
+                Node::Null => {}
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn children(
+        internal: &InternalNode,
+        parent_internal: Option<&InternalNode>,
+    ) -> Vec<(HashValue, Option<HashValue>)> {
+        (0..16u8)
+            .filter_map(|n| {
+                let nibble = Nibble::from(n);
+                internal.child(nibble).map(|child| {
+                    let parent_child = parent_internal
+                        .and_then(|parent| parent.child(nibble))
+                        .map(|child| child.hash);
+                    (child.hash, parent_child)
+                })
+            })
+            .collect()
+    }
+
+    fn get_node(&self, hash: HashValue) -> Result<Option<Node<AccountAddress>>> {
+        // The placeholder of the empty tree is not stored.
+        if hash == *SPARSE_MERKLE_PLACEHOLDER_HASH {
+            return Ok(None);
+        }
+        let node = StateNodeStore::get(self.storage.as_ref(), &hash)?
+            .ok_or_else(|| format_err!("State node {} is none.", hash))?;
+        Node::<AccountAddress>::decode(&node.0).map(Some)
+    }
+
+    fn block_id(accumulator: &MerkleAccumulator, number: BlockNumber) -> Result<HashValue> {
+        accumulator
+            .get_leaf(number)?
+            .ok_or_else(|| format_err!("Main chain block {} is not in accumulator.", number))
+    }
+
+    fn state_root(
+        &self,
+        accumulator: &MerkleAccumulator,
+        number: BlockNumber,
+    ) -> Result<HashValue> {
+        let block_id = Self::block_id(accumulator, number)?;
+        Ok(self
+            .storage
+            .get_block_header_by_hash(block_id)?
+            .ok_or_else(|| format_err!("Block header {} is none.", block_id))?
+            .state_root())
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Incremental db backups based on rocksdb checkpoints.
+//!
+//! The layout of a backup dir:
+//! ```text
+//! shared/<sst name>-<checksum prefix>.sst  the sst files shared by all the backups
+//! <id>/backup.json                          the `BackupInfo` of the backup
+//! <id>/<file name>                          the other files of the checkpoint, such as MANIFEST
+//! ```
+//! The sst files are immutable, so a backup only copies the sst files not in the shared dir yet.
+//! The checkpoint is created beside the db, so its sst files are hard links of the db files, and an
+//! sst file backed up before, found by its name, size and modified time, is not read again.
+
+use crate::{BlockStore, Storage};
+use anyhow::{ensure, format_err, Result};
+use crypto::HashValue;
+use serde::{Deserialize, Serialize};
+use starcoin_types::block::BlockNumber;
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const BACKUP_INFO_FILE: &str = "backup.json";
+const SHARED_DIR: &str = "shared";
+const SST_EXTENSION: &str = "sst";
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// The file name in the db dir.
+    pub name: String,
+    /// The file path relative to the backup dir.
+    pub path: String,
+    pub size: u64,
+    /// The sha3-256 of the file content.
+    pub checksum: HashValue,
+    /// The modified time of the db file in nanoseconds, None for the backups taken before it is
+    /// recorded.
+    #[serde(default)]
+    pub modified: Option<u64>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub id: u64,
+    /// The main chain head when the backup is taken, the backup may contain a few later blocks.
+    pub block_number: BlockNumber,
+    pub block_hash: HashValue,
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+    pub files: Vec<BackupFile>,
+    /// The bytes of the new files of this backup, the shared sst files are not included.
+    pub new_bytes: u64,
+}
+
+impl BackupInfo {
+    pub fn size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+}
+
+/// List the backups of the backup dir, order by id.
+pub fn list_backups(backup_dir: &Path) -> Result<Vec<BackupInfo>> {
+    let mut backups = vec![];
+    if !backup_dir.exists() {
+        return Ok(backups);
+    }
+    for entry in fs::read_dir(backup_dir)? {
+        let path = entry?.path().join(BACKUP_INFO_FILE);
+        if path.is_file() {
+            backups.push(serde_json::from_slice::<BackupInfo>(&fs::read(path)?)?);
+        }
+    }
+    backups.sort_by_key(|backup| backup.id);
+    Ok(backups)
+}
+
+/// Take a new backup of the storage to the backup dir. Only the hot db is backed up, the cold db
+/// on the secondary storage path is not changed after the data is moved to it, and should be copied
+/// separately.
+pub fn backup(storage: &Storage, backup_dir: &Path) -> Result<BackupInfo> {
+    let db = storage
+        .db()
+        .ok_or_else(|| format_err!("The storage has no db to backup."))?;
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info is none."))?;
+    let head = storage
+        .get_block_header_by_hash(startup_info.main)?
+        .ok_or_else(|| format_err!("Head block {} is none.", startup_info.main))?;
+    let backups = list_backups(backup_dir)?;
+    let shared_files = backups
+        .iter()
+        .flat_map(|backup| backup.files.iter())
+        .filter_map(|file| {
+            file.modified
+                .filter(|_| file.path.starts_with(SHARED_DIR))
+                .map(|modified| ((file.name.clone(), file.size, modified), file.clone()))
+        })
+        .collect::<HashMap<_, _>>();
+
+    fs::create_dir_all(backup_dir.join(SHARED_DIR))?;
+    let (id, dir) = create_backup_dir(
+        backup_dir,
+        backups.last().map(|backup| backup.id + 1).unwrap_or(1),
+    )?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let checkpoint_dir = db.path().with_file_name(format!(
+        ".backup-checkpoint-{}-{}",
+        std::process::id(),
+        timestamp.as_nanos()
+    ));
+    db.create_checkpoint(checkpoint_dir.as_path())?;
+    let result = backup_files(backup_dir, id, checkpoint_dir.as_path(), &shared_files);
+    fs::remove_dir_all(checkpoint_dir.as_path())?;
+    let (mut files, new_bytes) = result?;
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let info = BackupInfo {
+        id,
+        block_number: head.number(),
+        block_hash: head.id(),
+        timestamp: timestamp.as_secs(),
+        files,
+        new_bytes,
+    };
+    fs::write(
+        dir.join(BACKUP_INFO_FILE),
+        serde_json::to_vec_pretty(&info)?,
+    )?;
+    Ok(info)
+}
+
+/// Create the dir of a new backup from the id `start`, the id is taken by the creation of the dir,
+/// so the concurrent backups to the same backup dir get different ids.
+fn create_backup_dir(backup_dir: &Path, start: u64) -> Result<(u64, PathBuf)> {
+    let mut id = start;
+    loop {
+        let dir = backup_dir.join(id.to_string());
+        match fs::create_dir(dir.as_path()) {
+            Ok(()) => return Ok((id, dir)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => id += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Copy the checkpoint files to the backup dir, except the shared sst files backed up before.
+/// Return the files of the backup and the bytes copied.
+fn backup_files(
+    backup_dir: &Path,
+    id: u64,
+    checkpoint_dir: &Path,
+    shared_files: &HashMap<(String, u64, u64), BackupFile>,
+) -> Result<(Vec<BackupFile>, u64)> {
+    let mut files = vec![];
+    let mut new_bytes = 0;
+    for entry in fs::read_dir(checkpoint_dir)? {
+        let file = entry?.path();
+        let name = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format_err!("Invalid checkpoint file {:?}", file))?
+            .to_string();
+        let metadata = fs::metadata(file.as_path())?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_nanos() as u64;
+        let is_sst = file.extension().and_then(|ext| ext.to_str()) == Some(SST_EXTENSION);
+        if is_sst {
+            if let Some(shared) = shared_files.get(&(name.clone(), metadata.len(), modified)) {
+                if backup_dir.join(shared.path.as_str()).exists() {
+                    files.push(shared.clone());
+                    continue;
+                }
+            }
+        }
+        let content = fs::read(file.as_path())?;
+        let checksum = HashValue::sha3_256_of(content.as_slice());
+        let path = if is_sst {
+            let stem = name.trim_end_matches(".sst");
+            format!(
+                "{}/{}-{}.{}",
+                SHARED_DIR,
+                stem,
+                &checksum.to_hex()[..16],
+                SST_EXTENSION
+            )
+        } else {
+            format!("{}/{}", id, name)
+        };
+        let target = backup_dir.join(path.as_str());
+        if !target.exists() {
+            // Write to a temp file first, so a concurrent backup never reads a partial shared file.
+            let temp = target.with_file_name(format!(".{}-{}.tmp", name, id));
+            fs::write(temp.as_path(), content.as_slice())?;
+            fs::rename(temp.as_path(), target.as_path())?;
+            new_bytes += content.len() as u64;
+        }
+        files.push(BackupFile {
+            name,
+            path,
+            size: content.len() as u64,
+            checksum,
+            modified: Some(modified),
+        });
+    }
+    Ok((files, new_bytes))
+}
+
+/// Restore the backup to the db dir, the db dir must not exist, and every file is verified by its
+/// checksum.
+pub fn restore(backup_dir: &Path, id: u64, db_dir: &Path) -> Result<BackupInfo> {
+    let info = list_backups(backup_dir)?
+        .into_iter()
+        .find(|backup| backup.id == id)
+        .ok_or_else(|| format_err!("Can not find backup {} in {:?}", id, backup_dir))?;
+    ensure!(
+        !db_dir.exists(),
+        "The db dir {:?} already exists, please remove it first.",
+        db_dir
+    );
+    fs::create_dir_all(db_dir)?;
+    for file in &info.files {
+        let content = fs::read(backup_dir.join(file.path.as_str()))?;
+        ensure!(
+            content.len() as u64 == file.size
+                && HashValue::sha3_256_of(content.as_slice()) == file.checksum,
+            "The backup file {} is corrupted, checksum mismatch.",
+            file.path
+        );
+        fs::write(db_dir.join(file.name.as_str()), content)?;
+    }
+    Ok(info)
+}
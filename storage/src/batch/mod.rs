@@ -3,7 +3,10 @@
 
 use crate::storage::{CodecWriteBatch, KeyCodec, ValueCodec, WriteOp};
 use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 #[derive(Debug, Default, Clone)]
 pub struct WriteBatch {
@@ -54,3 +57,126 @@ where
         Ok(WriteBatch::new_with_rows(rows?))
     }
 }
+
+/// The writes of many column families collected into one write batch, which is written to the db
+/// atomically, see `DBStorage::begin_batch`. The batches can be nested, a savepoint is kept for
+/// every nested batch, so the writes of a nested batch can be discarded alone.
+#[derive(Debug, Default)]
+pub struct PendingWriteBatch {
+    rows: Vec<(String, Vec<u8>, WriteOp<Vec<u8>>)>,
+    /// column family => key => the index of the latest write of the key in `rows`.
+    latest: HashMap<String, HashMap<Vec<u8>, usize>>,
+    /// The row counts at which the nested batches begin.
+    savepoints: Vec<usize>,
+    /// Set while the batch is detached from the threads, see `DBStorage::detach_batch`.
+    detached: Option<DetachedBatch>,
+}
+
+/// Counts a detached batch in the detached batches of the db, until the batch is attached again
+/// or dropped.
+#[derive(Debug)]
+struct DetachedBatch(Arc<AtomicUsize>);
+
+impl Drop for DetachedBatch {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl PendingWriteBatch {
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The nesting depth of the batch, 0 if all the nested batches are committed or discarded.
+    pub fn depth(&self) -> usize {
+        self.savepoints.len()
+    }
+
+    pub fn put(&mut self, prefix_name: &str, key: Vec<u8>, op: WriteOp<Vec<u8>>) {
+        self.latest
+            .entry(prefix_name.to_string())
+            .or_default()
+            .insert(key.clone(), self.rows.len());
+        self.rows.push((prefix_name.to_string(), key, op));
+    }
+
+    pub fn put_batch(&mut self, prefix_name: &str, batch: WriteBatch) {
+        for (key, op) in batch.rows {
+            self.put(prefix_name, key, op);
+        }
+    }
+
+    /// The latest write of the key in the batch, None if the key is not written.
+    pub fn get(&self, prefix_name: &str, key: &[u8]) -> Option<&WriteOp<Vec<u8>>> {
+        self.latest
+            .get(prefix_name)
+            .and_then(|keys| keys.get(key))
+            .map(|index| &self.rows[*index].2)
+    }
+
+    pub(crate) fn set_detached(&mut self, detached_batches: Arc<AtomicUsize>) {
+        detached_batches.fetch_add(1, Ordering::SeqCst);
+        self.detached = Some(DetachedBatch(detached_batches));
+    }
+
+    pub(crate) fn set_attached(&mut self) {
+        self.detached = None;
+    }
+
+    pub(crate) fn begin(&mut self) {
+        self.savepoints.push(self.rows.len());
+    }
+
+    /// Merge the innermost nested batch into the outer one, return the new depth.
+    pub(crate) fn commit(&mut self) -> usize {
+        self.savepoints.pop();
+        self.depth()
+    }
+
+    /// Drop the writes of the innermost nested batch, return the new depth.
+    pub(crate) fn discard(&mut self) -> usize {
+        if let Some(savepoint) = self.savepoints.pop() {
+            for (prefix_name, key, _) in self.rows.drain(savepoint..) {
+                if let Some(keys) = self.latest.get_mut(&prefix_name) {
+                    keys.remove(&key);
+                }
+            }
+            // The earlier writes of the dropped keys are the latest again.
+            for (index, (prefix_name, key, _)) in self.rows.iter().enumerate() {
+                let keys = self.latest.entry(prefix_name.clone()).or_default();
+                match keys.get_mut(key) {
+                    Some(latest) => *latest = (*latest).max(index),
+                    None => {
+                        keys.insert(key.clone(), index);
+                    }
+                }
+            }
+        }
+        self.depth()
+    }
+
+    /// The latest writes of every column family, the overwritten writes are skipped.
+    pub fn batches(&self) -> BTreeMap<String, WriteBatch> {
+        let mut batches: BTreeMap<String, WriteBatch> = BTreeMap::new();
+        for (index, (prefix_name, key, op)) in self.rows.iter().enumerate() {
+            let is_latest = self
+                .latest
+                .get(prefix_name)
+                .and_then(|keys| keys.get(key))
+                .map_or(false, |latest| *latest == index);
+            if is_latest {
+                batches
+                    .entry(prefix_name.clone())
+                    .or_default()
+                    .rows
+                    .push((key.clone(), op.clone()));
+            }
+        }
+        batches
+    }
+}
@@ -3,7 +3,7 @@
 use crate::define_storage;
 use crate::storage::{CodecKVStore, StorageInstance, ValueCodec};
 use crate::{
-    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_PREFIX_NAME,
+    BLOCK_BLOOM_PREFIX_NAME, BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_PREFIX_NAME,
     BLOCK_TRANSACTIONS_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME, FAILED_BLOCK_PREFIX_NAME,
 };
 use anyhow::{bail, Result};
@@ -12,6 +12,7 @@ use crypto::HashValue;
 use logger::prelude::*;
 use serde::{Deserialize, Serialize};
 use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use starcoin_types::block_bloom::BlockBloom;
 use starcoin_types::peer_info::PeerId;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -69,6 +70,12 @@ define_storage!(
     FailedBlock,
     FAILED_BLOCK_PREFIX_NAME
 );
+define_storage!(
+    BlockBloomStorage,
+    HashValue,
+    BlockBloom,
+    BLOCK_BLOOM_PREFIX_NAME
+);
 
 #[derive(Clone)]
 pub struct BlockStorage {
@@ -78,6 +85,7 @@ pub struct BlockStorage {
     block_txns_store: BlockTransactionsStorage,
     block_txn_infos_store: BlockTransactionInfosStorage,
     failed_block_storage: FailedBlockStorage,
+    bloom_store: BlockBloomStorage,
 }
 
 impl ValueCodec for Block {
@@ -110,6 +118,16 @@ impl ValueCodec for BlockBody {
     }
 }
 
+impl ValueCodec for BlockBloom {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
 impl ValueCodec for FailedBlock {
     fn encode_value(&self) -> Result<Vec<u8>> {
         self.encode()
@@ -128,7 +146,8 @@ impl BlockStorage {
             body_store: BlockBodyStorage::new(instance.clone()),
             block_txns_store: BlockTransactionsStorage::new(instance.clone()),
             block_txn_infos_store: BlockTransactionInfosStorage::new(instance.clone()),
-            failed_block_storage: FailedBlockStorage::new(instance),
+            failed_block_storage: FailedBlockStorage::new(instance.clone()),
+            bloom_store: BlockBloomStorage::new(instance),
         }
     }
     pub fn save(&self, block: Block) -> Result<()> {
@@ -195,6 +214,12 @@ impl BlockStorage {
         }
     }
 
+    /// get txn ids for `block_id`.
+    /// return None, if block_id not exists.
+    pub fn get_transaction_ids(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
+        self.block_txns_store.get(block_id)
+    }
+
     /// get txn info ids for `block_id`.
     /// return None, if block_id not exists.
     pub fn get_transaction_info_ids(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
@@ -217,6 +242,10 @@ impl BlockStorage {
         self.block_txn_infos_store.put(block_id, txn_info_ids)
     }
 
+    pub fn delete_transaction_infos(&self, block_id: HashValue) -> Result<()> {
+        self.block_txn_infos_store.remove(block_id)
+    }
+
     pub fn save_failed_block(
         &self,
         block_id: HashValue,
@@ -228,6 +257,10 @@ impl BlockStorage {
             .put(block_id, (block, peer_id, failed).into())
     }
 
+    pub fn delete_failed_block(&self, block_id: HashValue) -> Result<()> {
+        self.failed_block_storage.remove(block_id)
+    }
+
     pub fn get_failed_block_by_id(
         &self,
         block_id: HashValue,
@@ -237,4 +270,12 @@ impl BlockStorage {
             None => Ok(None),
         }
     }
+
+    pub fn save_bloom(&self, block_id: HashValue, bloom: BlockBloom) -> Result<()> {
+        self.bloom_store.put(block_id, bloom)
+    }
+
+    pub fn get_bloom(&self, block_id: HashValue) -> Result<Option<BlockBloom>> {
+        self.bloom_store.get(block_id)
+    }
 }
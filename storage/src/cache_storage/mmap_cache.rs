@@ -0,0 +1,100 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use memmap::{MmapMut, MmapOptions};
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryInto;
+use std::fs::{self, OpenOptions};
+use std::hash::Hasher;
+use std::path::Path;
+
+const SLOT_SIZE: usize = 1024;
+/// The used flag, the key length (u16) and the value length (u32).
+const SLOT_HEADER_SIZE: usize = 7;
+
+/// A direct mapped cache in a memory mapped file. Every key is hashed to a fixed size slot, and a
+/// put overwrites the slot, so an entry is evicted when another key is put to the same slot.
+/// The entries larger than a slot are not cached.
+///
+/// The file is recreated when opened, nothing is kept across restarts.
+pub struct MmapCache {
+    map: Mutex<MmapMut>,
+    slots: usize,
+}
+
+impl MmapCache {
+    pub fn open(path: &Path, size: u64) -> Result<Self> {
+        let slots = size as usize / SLOT_SIZE;
+        ensure!(slots > 0, "The mmap cache size {} is too small", size);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((slots * SLOT_SIZE) as u64)?;
+        // The file is only accessed by this cache, the mapping is valid while the cache is alive.
+        let map = unsafe { MmapOptions::new().map_mut(&file)? };
+        Ok(Self {
+            map: Mutex::new(map),
+            slots,
+        })
+    }
+
+    fn slot(&self, key: &[u8]) -> usize {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(key);
+        (hasher.finish() % self.slots as u64) as usize * SLOT_SIZE
+    }
+
+    /// Return the value length of the slot at `offset` if the slot holds the `key`.
+    fn find(map: &[u8], offset: usize, key: &[u8]) -> Option<usize> {
+        let slot = &map[offset..offset + SLOT_SIZE];
+        if slot[0] == 0 {
+            return None;
+        }
+        let key_len = u16::from_le_bytes(slot[1..3].try_into().ok()?) as usize;
+        let value_len = u32::from_le_bytes(slot[3..7].try_into().ok()?) as usize;
+        let key_end = SLOT_HEADER_SIZE + key_len;
+        if key_end + value_len > SLOT_SIZE || &slot[SLOT_HEADER_SIZE..key_end] != key {
+            return None;
+        }
+        Some(value_len)
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let offset = self.slot(key);
+        let map = self.map.lock();
+        let value_len = Self::find(&map, offset, key)?;
+        let value_start = offset + SLOT_HEADER_SIZE + key.len();
+        Some(map[value_start..value_start + value_len].to_vec())
+    }
+
+    pub fn put(&self, key: &[u8], value: &[u8]) {
+        if SLOT_HEADER_SIZE + key.len() + value.len() > SLOT_SIZE {
+            return;
+        }
+        let offset = self.slot(key);
+        let mut map = self.map.lock();
+        let slot = &mut map[offset..offset + SLOT_SIZE];
+        let key_end = SLOT_HEADER_SIZE + key.len();
+        slot[0] = 1;
+        slot[1..3].copy_from_slice(&(key.len() as u16).to_le_bytes());
+        slot[3..7].copy_from_slice(&(value.len() as u32).to_le_bytes());
+        slot[SLOT_HEADER_SIZE..key_end].copy_from_slice(key);
+        slot[key_end..key_end + value.len()].copy_from_slice(value);
+    }
+
+    pub fn remove(&self, key: &[u8]) {
+        let offset = self.slot(key);
+        let mut map = self.map.lock();
+        if Self::find(&map, offset, key).is_some() {
+            map[offset] = 0;
+        }
+    }
+}
@@ -2,42 +2,202 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::batch::WriteBatch;
-use crate::metrics::{record_metrics, CACHE_ITEMS};
+use crate::cache_storage::mmap_cache::MmapCache;
+use crate::metrics::{record_metrics, CACHE_HITS, CACHE_ITEMS, CACHE_TIER_ITEMS};
 use crate::storage::{CacheObject, InnerStore, WriteOp};
+use crate::{
+    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME, BLOCK_PREFIX_NAME,
+    STATE_NODE_PREFIX_NAME,
+};
 use anyhow::{Error, Result};
 use lru::LruCache;
 use parking_lot::Mutex;
-use starcoin_config::DEFAULT_CACHE_SIZE;
-pub struct CacheStorage {
+use starcoin_config::{StorageConfig, DEFAULT_CACHE_SIZE};
+
+pub mod mmap_cache;
+
+/// A LRU cache of some column families.
+struct LruTier {
+    name: &'static str,
     cache: Mutex<LruCache<Vec<u8>, CacheObject>>,
 }
 
+impl LruTier {
+    fn new(name: &'static str, size: usize) -> Self {
+        Self {
+            name,
+            cache: Mutex::new(LruCache::new(size)),
+        }
+    }
+
+    fn record_hit(&self, hit: bool) {
+        record_cache_hit(self.name, hit);
+    }
+
+    fn record_len(&self, len: usize) {
+        CACHE_TIER_ITEMS
+            .with_label_values(&[self.name])
+            .set(len as i64);
+        if self.name == DEFAULT_TIER_NAME {
+            CACHE_ITEMS.set(len as u64);
+        }
+    }
+}
+
+const DEFAULT_TIER_NAME: &str = "default";
+
+fn record_cache_hit(name: &str, hit: bool) {
+    CACHE_HITS
+        .with_label_values(&[name, if hit { "hit" } else { "miss" }])
+        .inc();
+}
+
+pub struct CacheStorage {
+    /// The cache of the column families without a dedicated cache.
+    cache: LruTier,
+    /// The dedicated cache of the state nodes, None if the state nodes share the default cache.
+    state_cache: Option<LruTier>,
+    /// The dedicated cache of the blocks, headers, bodies and block infos,
+    /// None if they share the default cache.
+    block_cache: Option<LruTier>,
+    /// The second tier of the state node cache, the state nodes evicted from the first tier are
+    /// moved to it.
+    state_mmap_cache: Option<MmapCache>,
+}
+
 impl CacheStorage {
     pub fn new() -> Self {
+        Self::new_with_capacity(DEFAULT_CACHE_SIZE)
+    }
+    pub fn new_with_capacity(size: usize) -> Self {
         CacheStorage {
-            cache: Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)),
+            cache: LruTier::new(DEFAULT_TIER_NAME, size),
+            state_cache: None,
+            block_cache: None,
+            state_mmap_cache: None,
         }
     }
-    pub fn new_with_capacity(size: usize) -> Self {
+
+    /// Create the caches sized by the storage config.
+    pub fn new_with_config(config: &StorageConfig) -> Result<Self> {
+        let state_mmap_cache = match config.state_mmap_cache_size() {
+            Some(size) => Some(MmapCache::open(
+                config.state_mmap_cache_file().as_path(),
+                size,
+            )?),
+            None => None,
+        };
+        Ok(Self::new_with_tiers(
+            config.cache_size(),
+            config.state_cache_size(),
+            config.block_cache_size(),
+            state_mmap_cache,
+        ))
+    }
+
+    /// A zero `state_cache_size` or `block_cache_size` means sharing the default cache.
+    pub fn new_with_tiers(
+        cache_size: usize,
+        state_cache_size: usize,
+        block_cache_size: usize,
+        state_mmap_cache: Option<MmapCache>,
+    ) -> Self {
         CacheStorage {
-            cache: Mutex::new(LruCache::new(size)),
+            cache: LruTier::new(DEFAULT_TIER_NAME, cache_size),
+            state_cache: Some(state_cache_size)
+                .filter(|size| *size > 0)
+                .map(|size| LruTier::new("state", size)),
+            block_cache: Some(block_cache_size)
+                .filter(|size| *size > 0)
+                .map(|size| LruTier::new("block", size)),
+            state_mmap_cache,
+        }
+    }
+
+    fn tier(&self, prefix_name: &str) -> &LruTier {
+        let tier = match prefix_name {
+            STATE_NODE_PREFIX_NAME => self.state_cache.as_ref(),
+            BLOCK_PREFIX_NAME
+            | BLOCK_HEADER_PREFIX_NAME
+            | BLOCK_BODY_PREFIX_NAME
+            | BLOCK_INFO_PREFIX_NAME => self.block_cache.as_ref(),
+            _ => None,
+        };
+        tier.unwrap_or(&self.cache)
+    }
+
+    fn tiers(&self) -> impl Iterator<Item = &LruTier> {
+        std::iter::once(&self.cache)
+            .chain(self.state_cache.iter())
+            .chain(self.block_cache.iter())
+    }
+
+    fn second_tier(&self, prefix_name: &str) -> Option<&MmapCache> {
+        if prefix_name == STATE_NODE_PREFIX_NAME {
+            self.state_mmap_cache.as_ref()
+        } else {
+            None
+        }
+    }
+
+    fn get_composed(&self, prefix_name: &str, key: Vec<u8>) -> Option<CacheObject> {
+        let tier = self.tier(prefix_name);
+        let obj = tier.cache.lock().get(&key).cloned();
+        tier.record_hit(obj.is_some());
+        if obj.is_some() {
+            return obj;
         }
+        let second_tier = self.second_tier(prefix_name)?;
+        let value = second_tier.get(&key);
+        record_cache_hit("state_mmap", value.is_some());
+        let obj = CacheObject::Value(value?);
+        self.put_composed(prefix_name, key, obj.clone());
+        Some(obj)
     }
+
+    fn put_composed(&self, prefix_name: &str, key: Vec<u8>, obj: CacheObject) {
+        let tier = self.tier(prefix_name);
+        let second_tier = self.second_tier(prefix_name);
+        let evicted = {
+            let mut cache = tier.cache.lock();
+            let evicted =
+                if second_tier.is_some() && !cache.contains(&key) && cache.len() >= cache.cap() {
+                    cache.pop_lru()
+                } else {
+                    None
+                };
+            if let Some(second_tier) = second_tier {
+                second_tier.remove(&key);
+            }
+            cache.put(key, obj);
+            tier.record_len(cache.len());
+            evicted
+        };
+        if let (Some(second_tier), Some((key, CacheObject::Value(value)))) = (second_tier, evicted)
+        {
+            second_tier.put(&key, &value);
+        }
+    }
+
+    fn remove_composed(&self, prefix_name: &str, key: Vec<u8>) {
+        let tier = self.tier(prefix_name);
+        let mut cache = tier.cache.lock();
+        cache.pop(&key);
+        tier.record_len(cache.len());
+        if let Some(second_tier) = self.second_tier(prefix_name) {
+            second_tier.remove(&key);
+        }
+    }
+
     pub fn get_obj(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<CacheObject>> {
         record_metrics("cache", prefix_name, "get").end_with(|| {
-            Ok(self
-                .cache
-                .lock()
-                .get(&compose_key(prefix_name.to_string(), key))
-                .cloned())
+            Ok(self.get_composed(prefix_name, compose_key(prefix_name.to_string(), key)))
         })
     }
 
     pub fn put_obj(&self, prefix_name: &str, key: Vec<u8>, obj: CacheObject) -> Result<()> {
         record_metrics("cache", prefix_name, "put").end_with(|| {
-            let mut cache = self.cache.lock();
-            cache.put(compose_key(prefix_name.to_string(), key), obj);
-            CACHE_ITEMS.set(cache.len() as u64);
+            self.put_composed(prefix_name, compose_key(prefix_name.to_string(), key), obj);
             Ok(())
         })
     }
@@ -72,38 +232,34 @@ impl InnerStore for CacheStorage {
     fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
         record_metrics("cache", prefix_name, "get").end_with(|| {
             Ok(self
-                .cache
-                .lock()
-                .get(&compose_key(prefix_name.to_string(), key))
+                .get_composed(prefix_name, compose_key(prefix_name.to_string(), key))
                 .and_then(|v| v.into()))
         })
     }
 
     fn put(&self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
         record_metrics("cache", prefix_name, "put").end_with(|| {
-            let mut cache = self.cache.lock();
-            cache.put(
+            self.put_composed(
+                prefix_name,
                 compose_key(prefix_name.to_string(), key),
                 CacheObject::Value(value),
             );
-            CACHE_ITEMS.set(cache.len() as u64);
             Ok(())
         })
     }
 
     fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool> {
         record_metrics("cache", prefix_name, "contains_key").end_with(|| {
-            Ok(self
-                .cache
-                .lock()
-                .contains(&compose_key(prefix_name.to_string(), key)))
+            let key = compose_key(prefix_name.to_string(), key);
+            Ok(self.tier(prefix_name).cache.lock().contains(&key)
+                || self
+                    .second_tier(prefix_name)
+                    .map_or(false, |second_tier| second_tier.get(&key).is_some()))
         })
     }
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()> {
         record_metrics("cache", prefix_name, "remove").end_with(|| {
-            self.cache
-                .lock()
-                .pop(&compose_key(prefix_name.to_string(), key));
+            self.remove_composed(prefix_name, compose_key(prefix_name.to_string(), key));
             Ok(())
         })
     }
@@ -121,13 +277,18 @@ impl InnerStore for CacheStorage {
     }
 
     fn get_len(&self) -> Result<u64, Error> {
-        Ok(self.cache.lock().len() as u64)
+        Ok(self
+            .tiers()
+            .map(|tier| tier.cache.lock().len() as u64)
+            .sum())
     }
 
     fn keys(&self) -> Result<Vec<Vec<u8>>, Error> {
         let mut all_keys = vec![];
-        for (key, _) in self.cache.lock().iter() {
-            all_keys.push(key.to_vec());
+        for tier in self.tiers() {
+            for (key, _) in tier.cache.lock().iter() {
+                all_keys.push(key.to_vec());
+            }
         }
         Ok(all_keys)
     }
@@ -3,7 +3,7 @@
 
 use crate::storage::{ColumnFamily, InnerStorage, KVStore};
 use crate::CHAIN_INFO_PREFIX_NAME;
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use crypto::HashValue;
 use starcoin_types::startup_info::StartupInfo;
 use std::convert::TryInto;
@@ -25,6 +25,11 @@ pub type ChainInfoStorage = InnerStorage<ChainInfoColumnFamily>;
 impl ChainInfoStorage {
     const STARTUP_INFO_KEY: &'static str = "startup_info";
     const GENESIS_KEY: &'static str = "genesis";
+    const STORAGE_VERSION_KEY: &'static str = "storage_version";
+    const PRUNED_BLOCK_NUMBER_KEY: &'static str = "pruned_block_number";
+    const ARCHIVE_INDEXED_BLOCK_NUMBER_KEY: &'static str = "archive_indexed_block_number";
+    const COLD_BLOCK_NUMBER_KEY: &'static str = "cold_block_number";
+    const IMPORTED_BLOCK_NUMBER_KEY: &'static str = "imported_block_number";
 
     pub fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
         self.get(Self::STARTUP_INFO_KEY.as_bytes())
@@ -55,4 +60,105 @@ impl ChainInfoStorage {
             genesis_block_hash.to_vec(),
         )
     }
+
+    pub fn get_storage_version(&self) -> Result<Option<u64>> {
+        self.get(Self::STORAGE_VERSION_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(u64::from_be_bytes(
+                    bytes
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| format_err!("Invalid storage version bytes: {:?}", bytes))?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_storage_version(&self, version: u64) -> Result<()> {
+        self.put(
+            Self::STORAGE_VERSION_KEY.as_bytes().to_vec(),
+            version.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// The number of the latest main chain block whose data has been pruned.
+    pub fn get_pruned_block_number(&self) -> Result<Option<u64>> {
+        self.get(Self::PRUNED_BLOCK_NUMBER_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(u64::from_be_bytes(
+                    bytes.as_slice().try_into().map_err(|_| {
+                        format_err!("Invalid pruned block number bytes: {:?}", bytes)
+                    })?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_pruned_block_number(&self, number: u64) -> Result<()> {
+        self.put(
+            Self::PRUNED_BLOCK_NUMBER_KEY.as_bytes().to_vec(),
+            number.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// The number of the latest main chain block whose account states have been indexed.
+    pub fn get_archive_indexed_block_number(&self) -> Result<Option<u64>> {
+        self.get(Self::ARCHIVE_INDEXED_BLOCK_NUMBER_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(u64::from_be_bytes(
+                    bytes.as_slice().try_into().map_err(|_| {
+                        format_err!("Invalid archive indexed block number bytes: {:?}", bytes)
+                    })?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_archive_indexed_block_number(&self, number: u64) -> Result<()> {
+        self.put(
+            Self::ARCHIVE_INDEXED_BLOCK_NUMBER_KEY.as_bytes().to_vec(),
+            number.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// The number of the latest main chain block whose data has been moved to the cold db.
+    pub fn get_cold_block_number(&self) -> Result<Option<u64>> {
+        self.get(Self::COLD_BLOCK_NUMBER_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(u64::from_be_bytes(
+                    bytes
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| format_err!("Invalid cold block number bytes: {:?}", bytes))?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_cold_block_number(&self, number: u64) -> Result<()> {
+        self.put(
+            Self::COLD_BLOCK_NUMBER_KEY.as_bytes().to_vec(),
+            number.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// The number of the last block saved after the head by `db import-chain --verify-only`.
+    pub fn get_imported_block_number(&self) -> Result<Option<u64>> {
+        self.get(Self::IMPORTED_BLOCK_NUMBER_KEY.as_bytes())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(u64::from_be_bytes(
+                    bytes.as_slice().try_into().map_err(|_| {
+                        format_err!("Invalid imported block number bytes: {:?}", bytes)
+                    })?,
+                ))),
+                None => Ok(None),
+            })
+    }
+
+    pub fn save_imported_block_number(&self, number: u64) -> Result<()> {
+        self.put(
+            Self::IMPORTED_BLOCK_NUMBER_KEY.as_bytes().to_vec(),
+            number.to_be_bytes().to_vec(),
+        )
+    }
 }
@@ -0,0 +1,98 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_storage::DBStorage;
+use crate::{
+    Storage, BLOCK_BODY_PREFIX_NAME, BLOCK_PREFIX_NAME, BLOCK_TRANSACTIONS_PREFIX_NAME,
+    BLOCK_TRANSACTION_INFOS_PREFIX_NAME, CONTRACT_EVENT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
+    TRANSACTION_PREFIX_NAME,
+};
+use anyhow::{format_err, Result};
+use crypto::HashValue;
+use logger::prelude::*;
+use starcoin_accumulator::Accumulator;
+use starcoin_types::block::BlockNumber;
+use std::sync::Arc;
+
+/// Move the blocks, transactions, transaction infos and events of the old main chain blocks to the
+/// cold db on the secondary storage path, see `DBStorage::with_cold_db`. The block headers, block
+/// infos, accumulators and states are always kept in the hot db.
+pub struct ColdStorageMigrator {
+    storage: Arc<Storage>,
+}
+
+impl ColdStorageMigrator {
+    pub fn new(storage: Arc<Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// The number of the latest main chain block whose data has been moved to the cold db.
+    pub fn cold_block_number(&self) -> Result<Option<BlockNumber>> {
+        self.storage.chain_info_storage.get_cold_block_number()
+    }
+
+    /// Move at most `max_blocks` main chain blocks after the cold block and not after `to_block`
+    /// to the cold db. Return the new cold block number, or None if there are no blocks to move.
+    pub fn migrate(&self, to_block: BlockNumber, max_blocks: u64) -> Result<Option<BlockNumber>> {
+        let db = self
+            .storage
+            .db()
+            .filter(|db| db.has_cold_db())
+            .ok_or_else(|| format_err!("The cold db is not configured"))?;
+        let accumulator = self.storage.head_block_accumulator()?;
+        let start = self
+            .cold_block_number()?
+            .map(|number| number + 1)
+            .unwrap_or(0);
+        let end = to_block
+            .min(start.saturating_add(max_blocks.max(1)) - 1)
+            .min(accumulator.num_leaves().saturating_sub(1));
+        if accumulator.num_leaves() == 0 || start > end {
+            return Ok(None);
+        }
+        let mut moved = 0;
+        for number in start..=end {
+            let block_id = accumulator
+                .get_leaf(number)?
+                .ok_or_else(|| format_err!("Main chain block {} is not in accumulator.", number))?;
+            moved += self.move_block(db.as_ref(), block_id)?;
+            self.storage
+                .chain_info_storage
+                .save_cold_block_number(number)?;
+        }
+        info!(
+            "Moved blocks [{}, {}] to the cold db, {} entries moved.",
+            start, end, moved
+        );
+        Ok(Some(end))
+    }
+
+    fn move_block(&self, db: &DBStorage, block_id: HashValue) -> Result<usize> {
+        let txn_ids = self
+            .storage
+            .block_storage
+            .get_transaction_ids(block_id)?
+            .unwrap_or_default();
+        let txn_info_ids = self
+            .storage
+            .block_storage
+            .get_transaction_info_ids(block_id)?
+            .unwrap_or_default();
+        let to_keys = |ids: &[HashValue]| ids.iter().map(|id| id.to_vec()).collect::<Vec<_>>();
+
+        let mut moved = db.move_to_cold(TRANSACTION_PREFIX_NAME, to_keys(&txn_ids))?;
+        for prefix_name in &[TRANSACTION_INFO_PREFIX_NAME, CONTRACT_EVENT_PREFIX_NAME] {
+            moved += db.move_to_cold(prefix_name, to_keys(&txn_info_ids))?;
+        }
+        // The block entries are moved at last, the transaction ids are read from them.
+        for prefix_name in &[
+            BLOCK_TRANSACTIONS_PREFIX_NAME,
+            BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+            BLOCK_BODY_PREFIX_NAME,
+            BLOCK_PREFIX_NAME,
+        ] {
+            moved += db.move_to_cold(prefix_name, vec![block_id.to_vec()])?;
+        }
+        Ok(moved)
+    }
+}
@@ -1,31 +1,104 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{PendingWriteBatch, WriteBatch};
 use crate::errors::StorageInitError;
 use crate::metrics::{record_metrics, STORAGE_ITER_BYTES};
 use crate::storage::{ColumnFamilyName, InnerStore, WriteOp};
-use crate::{DEFAULT_PREFIX_NAME, VEC_PREFIX_NAME};
-use anyhow::{ensure, format_err, Error, Result};
+use crate::{COLD_PREFIX_NAMES, DEFAULT_PREFIX_NAME, VEC_PREFIX_NAME};
+use anyhow::{bail, ensure, format_err, Error, Result};
+use parking_lot::Mutex;
 use rocksdb::{Options, ReadOptions, WriteBatch as DBWriteBatch, WriteOptions, DB};
 use starcoin_config::RocksdbConfig;
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
+
+/// The key of the encryption check value in the default column family, the value is the
+/// `ENCRYPTION_CHECK_PLAIN` encrypted with the db encryption key.
+const ENCRYPTION_CHECK_KEY: &[u8] = b"encryption_check";
+const ENCRYPTION_CHECK_PLAIN: &[u8] = b"starcoin";
+
+/// The associated data of an encrypted value, the length prefixed column family name and the key,
+/// so an encrypted value can not be copied to another key or column family.
+pub(crate) fn value_aad(prefix_name: &str, key: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(4 + prefix_name.len() + key.len());
+    aad.extend_from_slice(&(prefix_name.len() as u32).to_be_bytes());
+    aad.extend_from_slice(prefix_name.as_bytes());
+    aad.extend_from_slice(key);
+    aad
+}
+
+thread_local! {
+    /// The count of the write batches the current thread has or reads, of all the dbs, so the
+    /// reads of the other threads do not lock `DBStorage::pending`.
+    static THREAD_BATCHES: Cell<usize> = Cell::new(0);
+}
+
+fn thread_batches() -> usize {
+    THREAD_BATCHES.with(|count| count.get())
+}
+
+fn inc_thread_batches() {
+    THREAD_BATCHES.with(|count| count.set(count.get() + 1));
+}
+
+fn dec_thread_batches() {
+    THREAD_BATCHES.with(|count| count.set(count.get().saturating_sub(1)));
+}
+
+/// The write batches of the threads, and the workers reading them.
+#[derive(Default)]
+struct PendingBatches {
+    batches: HashMap<ThreadId, PendingWriteBatch>,
+    /// worker thread => the thread whose batch the worker reads, see `attach_batch_reader`.
+    readers: HashMap<ThreadId, ThreadId>,
+}
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct DBStorage {
     db: DB,
     cfs: Vec<ColumnFamilyName>,
+    compacting: AtomicBool,
+    /// The db on the secondary storage path, the cold data of `COLD_PREFIX_NAMES` is moved to.
+    cold: Option<Box<DBStorage>>,
+    /// The values are encrypted with the key if it is set, the keys are not encrypted, so the
+    /// iterators keep the order of the keys.
+    encryption_key: Option<[u8; 32]>,
+    /// The write batches of the threads, see `begin_batch`.
+    pending: Mutex<PendingBatches>,
+    /// The count of the batches detached by `detach_batch` and not attached or dropped yet.
+    detached_batches: Arc<AtomicUsize>,
 }
 
 impl DBStorage {
     pub fn new<P: AsRef<Path> + Clone>(
         db_root_path: P,
         rocksdb_config: RocksdbConfig,
+    ) -> Result<Self> {
+        Self::new_with_encryption(db_root_path, rocksdb_config, None)
+    }
+
+    /// Open the db with the values encrypted by the `encryption_key`, the encryption can only be
+    /// enabled when the db is created, and the same key is required to open the db.
+    pub fn new_with_encryption<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        rocksdb_config: RocksdbConfig,
+        encryption_key: Option<[u8; 32]>,
     ) -> Result<Self> {
         let path = db_root_path.as_ref().join("starcoindb");
-        Self::open_with_cfs(path, VEC_PREFIX_NAME.to_vec(), false, rocksdb_config)
+        Self::open_with_encryption(
+            path,
+            VEC_PREFIX_NAME.to_vec(),
+            false,
+            rocksdb_config,
+            encryption_key,
+        )
     }
 
     pub fn open_with_cfs(
@@ -33,8 +106,19 @@ impl DBStorage {
         column_families: Vec<ColumnFamilyName>,
         readonly: bool,
         rocksdb_config: RocksdbConfig,
+    ) -> Result<Self> {
+        Self::open_with_encryption(root_path, column_families, readonly, rocksdb_config, None)
+    }
+
+    pub fn open_with_encryption(
+        root_path: impl AsRef<Path>,
+        column_families: Vec<ColumnFamilyName>,
+        readonly: bool,
+        rocksdb_config: RocksdbConfig,
+        encryption_key: Option<[u8; 32]>,
     ) -> Result<Self> {
         let path = root_path.as_ref();
+        let db_exists = Self::db_exists(path);
 
         let cfs_set: HashSet<_> = column_families.iter().collect();
         {
@@ -43,7 +127,7 @@ impl DBStorage {
                 "Duplicate column family name found.",
             );
         }
-        if Self::db_exists(path) {
+        if db_exists {
             let cf_vec = Self::list_cf(path)?;
             let mut db_cfs_set: HashSet<_> = cf_vec.iter().collect();
             db_cfs_set.remove(&DEFAULT_PREFIX_NAME.to_string());
@@ -81,10 +165,308 @@ impl DBStorage {
             Self::open_inner(&rocksdb_opts, path, column_families.clone())?
         };
 
-        Ok(DBStorage {
+        let db = DBStorage {
             db,
             cfs: column_families,
-        })
+            compacting: AtomicBool::new(false),
+            cold: None,
+            encryption_key,
+            pending: Mutex::new(PendingBatches::default()),
+            detached_batches: Arc::new(AtomicUsize::new(0)),
+        };
+        db.check_encryption(db_exists, readonly)?;
+        Ok(db)
+    }
+
+    /// Check the encryption key matches the db, the encryption check value is written when the
+    /// encrypted db is created.
+    fn check_encryption(&self, db_exists: bool, readonly: bool) -> Result<()> {
+        let check_value = self.db.get(ENCRYPTION_CHECK_KEY)?;
+        match (self.encryption_key.as_ref(), check_value) {
+            (Some(key), Some(check_value)) => {
+                let plain = starcoin_decrypt::decrypt_with_key(
+                    key,
+                    value_aad(DEFAULT_PREFIX_NAME, ENCRYPTION_CHECK_KEY).as_slice(),
+                    check_value.as_slice(),
+                )
+                .map_err(|_| format_err!("The db encryption key is wrong."))?;
+                ensure!(
+                    plain.as_slice() == ENCRYPTION_CHECK_PLAIN,
+                    "The db encryption key is wrong."
+                );
+            }
+            (Some(_), None) => {
+                if db_exists || readonly {
+                    bail!(
+                        "The db is not encrypted, the encryption can only be enabled on a new db."
+                    );
+                }
+                self.db.put_opt(
+                    ENCRYPTION_CHECK_KEY,
+                    self.encrypt_value(
+                        DEFAULT_PREFIX_NAME,
+                        ENCRYPTION_CHECK_KEY,
+                        ENCRYPTION_CHECK_PLAIN,
+                    ),
+                    &Self::default_write_options(),
+                )?;
+            }
+            (None, Some(_)) => bail!("The db is encrypted, the db encryption key is required."),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_key.is_some()
+    }
+
+    /// Every value written to this db is encrypted by this function, bound to its column family
+    /// and key.
+    fn encrypt_value<'v>(&self, prefix_name: &str, key: &[u8], value: &'v [u8]) -> Cow<'v, [u8]> {
+        match self.encryption_key.as_ref() {
+            Some(encryption_key) => Cow::Owned(starcoin_decrypt::encrypt_with_key(
+                encryption_key,
+                value_aad(prefix_name, key).as_slice(),
+                value,
+            )),
+            None => Cow::Borrowed(value),
+        }
+    }
+
+    fn decrypt_value(
+        encryption_key: Option<&[u8; 32]>,
+        prefix_name: &str,
+        key: &[u8],
+        value: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        match encryption_key {
+            Some(encryption_key) => starcoin_decrypt::decrypt_with_key(
+                encryption_key,
+                value_aad(prefix_name, key).as_slice(),
+                value.as_slice(),
+            )
+            .map_err(|e| format_err!("Decrypt the value of {} failed: {}", prefix_name, e)),
+            None => Ok(value),
+        }
+    }
+
+    /// Get the value as it is stored in this db, which is encrypted if the db is encrypted.
+    pub fn get_raw(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        Ok(self.db.get_cf(cf_handle, key.as_slice())?)
+    }
+
+    /// Read the column families of `COLD_PREFIX_NAMES` from the `cold` db too, if the key is not
+    /// in this db. The data is moved to the cold db by `move_to_cold`.
+    pub fn with_cold_db(mut self, cold: DBStorage) -> Self {
+        self.cold = Some(Box::new(cold));
+        self
+    }
+
+    pub fn has_cold_db(&self) -> bool {
+        self.cold.is_some()
+    }
+
+    fn cold_db(&self, prefix_name: &str) -> Option<&DBStorage> {
+        self.cold
+            .as_deref()
+            .filter(|_| COLD_PREFIX_NAMES.contains(&prefix_name))
+    }
+
+    /// Move the entries of the keys to the cold db, the entries are written to the cold db before
+    /// they are deleted from this db, so they are always readable. Return the count of the moved
+    /// entries.
+    pub fn move_to_cold(&self, prefix_name: &str, keys: Vec<Vec<u8>>) -> Result<usize> {
+        let cold = self.cold_db(prefix_name).ok_or_else(|| {
+            format_err!("Column family {} can not be moved to cold db", prefix_name)
+        })?;
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let mut cold_batch = WriteBatch::new();
+        let mut hot_batch = WriteBatch::new();
+        for key in keys {
+            if let Some(value) = self.db.get_cf(cf_handle, key.as_slice())? {
+                // The cold db encrypts the value with its own key.
+                let value = Self::decrypt_value(
+                    self.encryption_key.as_ref(),
+                    prefix_name,
+                    key.as_slice(),
+                    value,
+                )?;
+                cold_batch.put(key.clone(), value)?;
+                hot_batch.delete(key)?;
+            }
+        }
+        let moved = cold_batch.rows.len();
+        if moved > 0 {
+            cold.write_batch(prefix_name, cold_batch)?;
+            self.write_hot_batch(prefix_name, hot_batch)?;
+        }
+        Ok(moved)
+    }
+
+    /// Collect the writes of the current thread into one write batch, which is written to the db
+    /// atomically by `commit_batch`, instead of writing and syncing every write. The batches can be
+    /// nested, only the outermost batch is written to the db, and `discard_batch` drops the writes
+    /// of the innermost batch. The pending writes are only readable by the current thread, and the
+    /// workers attached by `attach_batch_reader`, the iterators do not see them.
+    pub fn begin_batch(&self) {
+        let mut pending = self.pending.lock();
+        let thread_id = thread::current().id();
+        if !pending.batches.contains_key(&thread_id) {
+            inc_thread_batches();
+        }
+        pending.batches.entry(thread_id).or_default().begin();
+    }
+
+    /// Commit the innermost batch of the current thread. If it is the outermost batch, the batch
+    /// is written to the db, and the written batches of the column families are returned.
+    pub fn commit_batch(&self) -> Result<Option<BTreeMap<String, WriteBatch>>> {
+        let thread_id = thread::current().id();
+        let batches = {
+            let mut pending = self.pending.lock();
+            let batch = pending
+                .batches
+                .get_mut(&thread_id)
+                .ok_or_else(|| format_err!("There is no write batch to commit."))?;
+            if batch.commit() > 0 {
+                return Ok(None);
+            }
+            batch.batches()
+        };
+        // The batch is kept readable until it is written.
+        let result = record_metrics("db", "batch", "pending").end_with(|| {
+            for (prefix_name, batch) in &batches {
+                self.write_cold_deletions(prefix_name, batch)?;
+            }
+            self.write_hot_batches(
+                batches
+                    .iter()
+                    .map(|(prefix_name, batch)| (prefix_name.as_str(), batch)),
+            )
+        });
+        if self.pending.lock().batches.remove(&thread_id).is_some() {
+            dec_thread_batches();
+        }
+        result.map(|_| Some(batches))
+    }
+
+    /// Drop the writes of the innermost batch of the current thread.
+    pub fn discard_batch(&self) {
+        let thread_id = thread::current().id();
+        let mut pending = self.pending.lock();
+        if let Some(batch) = pending.batches.get_mut(&thread_id) {
+            if batch.discard() == 0 {
+                pending.batches.remove(&thread_id);
+                dec_thread_batches();
+            }
+        }
+    }
+
+    /// Take the batch of the current thread, so it can be continued by `attach_batch` on another
+    /// thread, such as by an async task. The detached batch is not readable, but it is still open
+    /// for `has_open_batch` until it is attached again or dropped.
+    pub fn detach_batch(&self) -> Option<PendingWriteBatch> {
+        let mut pending = self.pending.lock();
+        let mut batch = pending.batches.remove(&thread::current().id());
+        if let Some(batch) = batch.as_mut() {
+            batch.set_detached(self.detached_batches.clone());
+            dec_thread_batches();
+        }
+        batch
+    }
+
+    /// Continue the batch detached by `detach_batch` on the current thread.
+    pub fn attach_batch(&self, mut batch: PendingWriteBatch) -> Result<()> {
+        let mut pending = self.pending.lock();
+        let thread_id = thread::current().id();
+        ensure!(
+            !pending.batches.contains_key(&thread_id),
+            "The current thread already has a write batch."
+        );
+        batch.set_attached();
+        pending.batches.insert(thread_id, batch);
+        inc_thread_batches();
+        Ok(())
+    }
+
+    /// Read the pending writes of the batch of the `owner` thread on the current thread, such as
+    /// by the workers of a parallel execution, until `detach_batch_reader`. The writes of the
+    /// current thread go to its own batch, or to the db if it has none.
+    pub fn attach_batch_reader(&self, owner: ThreadId) {
+        let thread_id = thread::current().id();
+        if thread_id == owner {
+            return;
+        }
+        if self
+            .pending
+            .lock()
+            .readers
+            .insert(thread_id, owner)
+            .is_none()
+        {
+            inc_thread_batches();
+        }
+    }
+
+    pub fn detach_batch_reader(&self) {
+        if self
+            .pending
+            .lock()
+            .readers
+            .remove(&thread::current().id())
+            .is_some()
+        {
+            dec_thread_batches();
+        }
+    }
+
+    /// Whether the writes of the current thread are collected into a write batch.
+    pub fn is_batching(&self) -> bool {
+        thread_batches() > 0
+            && self
+                .pending
+                .lock()
+                .batches
+                .contains_key(&thread::current().id())
+    }
+
+    /// Whether any thread has an open write batch, or an open batch is detached.
+    pub fn has_open_batch(&self) -> bool {
+        let pending = self.pending.lock();
+        !pending.batches.is_empty() || self.detached_batches.load(Ordering::SeqCst) > 0
+    }
+
+    /// The pending write of the key, the batch of the current thread is checked first, then the
+    /// batch it reads by `attach_batch_reader`.
+    /// Return None if the key is not written by any batch, Some(None) if it is deleted.
+    pub fn get_pending(&self, prefix_name: &str, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        if thread_batches() == 0 {
+            return None;
+        }
+        let thread_id = thread::current().id();
+        let pending = self.pending.lock();
+        pending
+            .batches
+            .get(&thread_id)
+            .into_iter()
+            .chain(
+                pending
+                    .readers
+                    .get(&thread_id)
+                    .and_then(|owner| pending.batches.get(owner)),
+            )
+            .find_map(|batch| batch.get(prefix_name, key))
+            .map(|write_op| match write_op {
+                WriteOp::Value(value) => Some(value.clone()),
+                WriteOp::Deletion => None,
+            })
+    }
+
+    fn collect_pending(&self, prefix_name: &str, rows: Vec<(Vec<u8>, WriteOp<Vec<u8>>)>) {
+        if let Some(batch) = self.pending.lock().batches.get_mut(&thread::current().id()) {
+            batch.put_batch(prefix_name, WriteBatch::new_with_rows(rows));
+        }
     }
 
     fn open_inner(
@@ -136,6 +518,49 @@ impl DBStorage {
         Ok(())
     }
 
+    /// Compact the whole key range of the column family, or of all the column families if `cf_name`
+    /// is none, return the compacted column families. Only one compaction can run at the same time.
+    pub fn compact(&self, cf_name: Option<&str>) -> Result<Vec<ColumnFamilyName>> {
+        let cfs = match cf_name {
+            Some(cf_name) => vec![*self
+                .cfs
+                .iter()
+                .find(|cf| **cf == cf_name)
+                .ok_or_else(|| format_err!("Unknown column family: {}", cf_name))?],
+            None => self.cfs.clone(),
+        };
+        ensure!(
+            self.compacting
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok(),
+            "A compaction is already running."
+        );
+        let result = cfs.iter().try_for_each(|cf_name| -> Result<()> {
+            let cf_handle = self.get_cf_handle(cf_name)?;
+            self.db
+                .compact_range_cf(cf_handle, None::<&[u8]>, None::<&[u8]>);
+            Ok(())
+        });
+        self.compacting.store(false, Ordering::SeqCst);
+        result.map(|_| cfs)
+    }
+
+    /// The dir of the db.
+    pub fn path(&self) -> &Path {
+        self.db.path()
+    }
+
+    /// Create a rocksdb checkpoint of the db in the dir, the dir must not exist. The files are
+    /// hard linked if the dir is on the same filesystem as the db.
+    pub fn create_checkpoint(&self, dir: &Path) -> Result<()> {
+        rocksdb::checkpoint::Checkpoint::new(&self.db)?.create_checkpoint(dir)?;
+        Ok(())
+    }
+
+    pub fn is_compacting(&self) -> bool {
+        self.compacting.load(Ordering::SeqCst)
+    }
+
     /// List cf
     pub fn list_cf(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
         Ok(rocksdb::DB::list_cf(&rocksdb::Options::default(), path)?)
@@ -155,6 +580,50 @@ impl DBStorage {
         })
     }
 
+    /// Writes a group of records wrapped in a WriteBatch to this db only.
+    fn write_hot_batch(&self, prefix_name: &str, batch: WriteBatch) -> Result<()> {
+        record_metrics("db", "batch", prefix_name)
+            .end_with(|| self.write_hot_batches(std::iter::once((prefix_name, &batch))))
+    }
+
+    /// Writes the batches of the column families to this db only, in one atomic write.
+    fn write_hot_batches<'a>(
+        &self,
+        batches: impl IntoIterator<Item = (&'a str, &'a WriteBatch)>,
+    ) -> Result<()> {
+        let mut db_batch = DBWriteBatch::default();
+        for (prefix_name, batch) in batches {
+            let cf_handle = self.get_cf_handle(prefix_name)?;
+            for (key, write_op) in &batch.rows {
+                match write_op {
+                    WriteOp::Value(value) => {
+                        db_batch.put_cf(cf_handle, key, self.encrypt_value(prefix_name, key, value))
+                    }
+                    WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                };
+            }
+        }
+        self.db
+            .write_opt(db_batch, &Self::default_write_options())?;
+        Ok(())
+    }
+
+    /// The deleted keys may have been moved to the cold db, delete them from the cold db too.
+    fn write_cold_deletions(&self, prefix_name: &str, batch: &WriteBatch) -> Result<()> {
+        if let Some(cold) = self.cold_db(prefix_name) {
+            let mut cold_batch = WriteBatch::new();
+            for (key, write_op) in &batch.rows {
+                if let WriteOp::Deletion = write_op {
+                    cold_batch.delete(key.clone())?;
+                }
+            }
+            if !cold_batch.rows.is_empty() {
+                cold.write_batch(prefix_name, cold_batch)?;
+            }
+        }
+        Ok(())
+    }
+
     fn default_write_options() -> WriteOptions {
         let mut opts = WriteOptions::new();
         opts.set_sync(true);
@@ -165,6 +634,9 @@ impl DBStorage {
         let mut db_opts = Options::default();
         db_opts.set_max_open_files(config.max_open_files);
         db_opts.set_max_total_wal_size(config.max_total_wal_size);
+        if config.rate_limit_bytes_per_sec > 0 {
+            db_opts.set_ratelimiter(config.rate_limit_bytes_per_sec as i64, 100 * 1000, 10);
+        }
         db_opts
     }
     fn iter_with_direction(
@@ -177,6 +649,8 @@ impl DBStorage {
             self.db
                 .raw_iterator_cf_opt(cf_handle, ReadOptions::default()),
             direction,
+            prefix_name.to_string(),
+            self.encryption_key.as_ref(),
         ))
     }
 
@@ -199,14 +673,23 @@ pub enum ScanDirection {
 pub struct SchemaIterator<'a> {
     db_iter: rocksdb::DBRawIterator<'a>,
     direction: ScanDirection,
+    prefix_name: String,
+    encryption_key: Option<&'a [u8; 32]>,
     phantom: PhantomData<Vec<u8>>,
 }
 
 impl<'a> SchemaIterator<'a> {
-    fn new(db_iter: rocksdb::DBRawIterator<'a>, direction: ScanDirection) -> Self {
+    fn new(
+        db_iter: rocksdb::DBRawIterator<'a>,
+        direction: ScanDirection,
+        prefix_name: String,
+        encryption_key: Option<&'a [u8; 32]>,
+    ) -> Self {
         SchemaIterator {
             db_iter,
             direction,
+            prefix_name,
+            encryption_key,
             phantom: PhantomData,
         }
     }
@@ -249,6 +732,7 @@ impl<'a> SchemaIterator<'a> {
             ScanDirection::Forward => self.db_iter.next(),
             ScanDirection::Backward => self.db_iter.prev(),
         }
+        let value = DBStorage::decrypt_value(self.encryption_key, &self.prefix_name, &key, value)?;
 
         Ok(Some((key, value)))
     }
@@ -264,21 +748,46 @@ impl<'a> Iterator for SchemaIterator<'a> {
 
 impl InnerStore for DBStorage {
     fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
-        record_metrics("db", prefix_name, "get").end_with(|| {
+        if let Some(value) = self.get_pending(prefix_name, key.as_slice()) {
+            return Ok(value);
+        }
+        let result = record_metrics("db", prefix_name, "get").end_with(|| {
             let cf_handle = self.get_cf_handle(prefix_name)?;
             let result = self.db.get_cf(cf_handle, key.as_slice())?;
-            Ok(result)
-        })
+            result
+                .map(|value| {
+                    Self::decrypt_value(
+                        self.encryption_key.as_ref(),
+                        prefix_name,
+                        key.as_slice(),
+                        value,
+                    )
+                })
+                .transpose()
+        })?;
+        match (result, self.cold_db(prefix_name)) {
+            (None, Some(cold)) => record_metrics("cold_db", prefix_name, "get")
+                .end_with(|| cold.get(prefix_name, key)),
+            (result, _) => Ok(result),
+        }
     }
 
     fn put(&self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+        if self.is_batching() {
+            self.collect_pending(prefix_name, vec![(key, WriteOp::Value(value))]);
+            return Ok(());
+        }
         STORAGE_ITER_BYTES
             .with_label_values(&[prefix_name])
             .observe((key.len() + value.len()) as f64);
         record_metrics("db", prefix_name, "put").end_with(|| {
             let cf_handle = self.get_cf_handle(prefix_name)?;
-            self.db
-                .put_cf_opt(cf_handle, &key, &value, &Self::default_write_options())?;
+            self.db.put_cf_opt(
+                cf_handle,
+                &key,
+                self.encrypt_value(prefix_name, &key, &value),
+                &Self::default_write_options(),
+            )?;
             Ok(())
         })
     }
@@ -292,6 +801,13 @@ impl InnerStore for DBStorage {
         })
     }
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()> {
+        if self.is_batching() {
+            self.collect_pending(prefix_name, vec![(key, WriteOp::Deletion)]);
+            return Ok(());
+        }
+        if let Some(cold) = self.cold_db(prefix_name) {
+            cold.remove(prefix_name, key.clone())?;
+        }
         record_metrics("db", prefix_name, "remove").end_with(|| {
             let cf_handle = self.get_cf_handle(prefix_name)?;
             self.db.delete_cf(cf_handle, &key)?;
@@ -301,19 +817,12 @@ impl InnerStore for DBStorage {
 
     /// Writes a group of records wrapped in a WriteBatch.
     fn write_batch(&self, prefix_name: &str, batch: WriteBatch) -> Result<()> {
-        record_metrics("db", "batch", prefix_name).end_with(|| {
-            let mut db_batch = DBWriteBatch::default();
-            let cf_handle = self.get_cf_handle(prefix_name)?;
-            for (key, write_op) in &batch.rows {
-                match write_op {
-                    WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
-                    WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
-                };
-            }
-            self.db
-                .write_opt(db_batch, &Self::default_write_options())?;
-            Ok(())
-        })
+        if self.is_batching() {
+            self.collect_pending(prefix_name, batch.rows);
+            return Ok(());
+        }
+        self.write_cold_deletions(prefix_name, &batch)?;
+        self.write_hot_batch(prefix_name, batch)
     }
 
     fn get_len(&self) -> Result<u64> {
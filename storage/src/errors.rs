@@ -8,4 +8,6 @@ use thiserror::Error;
 pub enum StorageInitError {
     #[error("Storage check error {0:?}.")]
     StorageCheckError(Error),
+    #[error("Storage version error {0:?}.")]
+    StorageVersionError(Error),
 }
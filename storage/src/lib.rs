@@ -4,48 +4,67 @@
 use crate::accumulator::{
     AccumulatorStorage, BlockAccumulatorStorage, TransactionAccumulatorStorage,
 };
+use crate::archive::{AccountStateIndexStorage, IndexedBlockStorage};
 use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
 use crate::chain_info::ChainInfoStorage;
 use crate::contract_event::ContractEventStorage;
-use crate::state_node::StateStorage;
-use crate::storage::{CodecKVStore, CodecWriteBatch, ColumnFamilyName, StorageInstance};
+use crate::db_storage::DBStorage;
+use crate::migration::StorageVersion;
+use crate::state_node::{
+    StateNodeChangeKey, StateNodeChangeSet, StateNodeChangeStorage, StateNodeRefStorage,
+    StateNodeWriteKey, StateNodeWriteStorage, StateStorage,
+};
+use crate::storage::{
+    CodecKVStore, CodecWriteBatch, ColumnFamilyName, PendingWriteBatch, SchemaStorage,
+    StorageInstance,
+};
 use crate::transaction::TransactionStorage;
 use crate::transaction_info::{TransactionInfoHashStorage, TransactionInfoStorage};
 use anyhow::{bail, format_err, Error, Result};
 use crypto::HashValue;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use starcoin_accumulator::node::AccumulatorStoreType;
-use starcoin_accumulator::AccumulatorTreeStore;
-use starcoin_state_store_api::{StateNode, StateNodeStore};
+use starcoin_accumulator::{Accumulator, AccumulatorTreeStore, MerkleAccumulator};
+use starcoin_state_store_api::{StateNode, StateNodeChanges, StateNodeStore};
+use starcoin_types::block_bloom::BlockBloom;
 use starcoin_types::contract_event::ContractEvent;
 use starcoin_types::peer_info::PeerId;
 use starcoin_types::startup_info::{ChainInfo, ChainStatus};
 use starcoin_types::transaction::{BlockTransactionInfo, Transaction};
 use starcoin_types::{
-    block::{Block, BlockBody, BlockHeader, BlockInfo},
+    block::{Block, BlockBody, BlockHeader, BlockInfo, BlockNumber},
     startup_info::StartupInfo,
 };
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::sync::Arc;
+use std::thread::ThreadId;
 
 pub mod accumulator;
+pub mod archive;
+pub mod backup;
 pub mod batch;
 pub mod block;
 pub mod block_info;
 pub mod cache_storage;
 pub mod chain_info;
+pub mod cold;
 pub mod contract_event;
 pub mod db_storage;
 pub mod errors;
 mod metrics;
+pub mod migration;
+pub mod pruner;
+pub mod repair;
 pub mod state_node;
 pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod transaction;
 pub mod transaction_info;
+pub mod verifier;
 
 #[macro_use]
 pub mod storage_macros;
@@ -65,6 +84,12 @@ pub const TRANSACTION_INFO_PREFIX_NAME: ColumnFamilyName = "transaction_info";
 pub const TRANSACTION_INFO_HASH_PREFIX_NAME: ColumnFamilyName = "transaction_info_hash";
 pub const CONTRACT_EVENT_PREFIX_NAME: ColumnFamilyName = "contract_event";
 pub const FAILED_BLOCK_PREFIX_NAME: ColumnFamilyName = "failed_block";
+pub const ACCOUNT_STATE_INDEX_PREFIX_NAME: ColumnFamilyName = "account_state_index";
+pub const ARCHIVE_INDEXED_BLOCK_PREFIX_NAME: ColumnFamilyName = "archive_indexed_block";
+pub const BLOCK_BLOOM_PREFIX_NAME: ColumnFamilyName = "block_bloom";
+pub const STATE_NODE_CHANGE_PREFIX_NAME: ColumnFamilyName = "state_node_change";
+pub const STATE_NODE_WRITE_PREFIX_NAME: ColumnFamilyName = "state_node_write";
+pub const STATE_NODE_REF_PREFIX_NAME: ColumnFamilyName = "state_node_ref";
 
 ///db storage use prefix_name vec to init
 /// Please note that adding a prefix needs to be added in vec simultaneously, remember！！
@@ -85,6 +110,26 @@ pub static VEC_PREFIX_NAME: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
         TRANSACTION_INFO_HASH_PREFIX_NAME,
         CONTRACT_EVENT_PREFIX_NAME,
         FAILED_BLOCK_PREFIX_NAME,
+        ACCOUNT_STATE_INDEX_PREFIX_NAME,
+        ARCHIVE_INDEXED_BLOCK_PREFIX_NAME,
+        BLOCK_BLOOM_PREFIX_NAME,
+        STATE_NODE_CHANGE_PREFIX_NAME,
+        STATE_NODE_WRITE_PREFIX_NAME,
+        STATE_NODE_REF_PREFIX_NAME,
+    ]
+});
+
+/// The column families of the old blocks, transactions and events, which can be moved to the cold
+/// db on the secondary storage path, see `cold::ColdStorageMigrator`.
+pub static COLD_PREFIX_NAMES: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
+    vec![
+        BLOCK_PREFIX_NAME,
+        BLOCK_BODY_PREFIX_NAME,
+        BLOCK_TRANSACTIONS_PREFIX_NAME,
+        BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+        TRANSACTION_PREFIX_NAME,
+        TRANSACTION_INFO_PREFIX_NAME,
+        CONTRACT_EVENT_PREFIX_NAME,
     ]
 });
 
@@ -138,6 +183,18 @@ pub trait BlockStore {
         &self,
         block_id: HashValue,
     ) -> Result<Option<(Block, Option<PeerId>, String)>>;
+
+    fn save_block_bloom(&self, block_id: HashValue, bloom: BlockBloom) -> Result<()>;
+
+    /// get the event bloom of block `block_id`.
+    /// return None, if the block is not found or is committed before the bloom is saved.
+    fn get_block_bloom(&self, block_id: HashValue) -> Result<Option<BlockBloom>>;
+
+    /// get the number of the last block saved after the head without executing, by
+    /// `db import-chain --verify-only`, the sync executes these blocks from the local store.
+    fn get_imported_block_number(&self) -> Result<Option<BlockNumber>>;
+
+    fn save_imported_block_number(&self, number: BlockNumber) -> Result<()>;
 }
 
 pub trait BlockTransactionInfoStore {
@@ -185,6 +242,14 @@ pub struct Storage {
     block_info_storage: BlockInfoStorage,
     event_storage: ContractEventStorage,
     chain_info_storage: ChainInfoStorage,
+    account_state_index_storage: AccountStateIndexStorage,
+    indexed_block_storage: IndexedBlockStorage,
+    state_node_change_storage: StateNodeChangeStorage,
+    state_node_write_storage: StateNodeWriteStorage,
+    state_node_ref_storage: StateNodeRefStorage,
+    /// Held by the pruner while it checks and deletes the state nodes, and by the commit of a write
+    /// batch, so a node is not deleted after a block rewrites it, see `pruner::StoragePruner`.
+    state_node_lock: Arc<Mutex<()>>,
 }
 
 impl Storage {
@@ -202,7 +267,13 @@ impl Storage {
                 AccumulatorStorage::new_transaction_accumulator_storage(instance.clone()),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
             event_storage: ContractEventStorage::new(instance.clone()),
-            chain_info_storage: ChainInfoStorage::new(instance),
+            chain_info_storage: ChainInfoStorage::new(instance.clone()),
+            account_state_index_storage: AccountStateIndexStorage::new(instance.clone()),
+            indexed_block_storage: IndexedBlockStorage::new(instance.clone()),
+            state_node_change_storage: StateNodeChangeStorage::new(instance.clone()),
+            state_node_write_storage: StateNodeWriteStorage::new(instance.clone()),
+            state_node_ref_storage: StateNodeRefStorage::new(instance),
+            state_node_lock: Arc::new(Mutex::new(())),
         })
     }
 
@@ -215,6 +286,42 @@ impl Storage {
     ) -> AccumulatorStorage<TransactionAccumulatorStorage> {
         self.transaction_accumulator_storage.clone()
     }
+
+    /// The underlying db, None if the storage is a pure cache storage.
+    pub fn db(&self) -> Option<Arc<DBStorage>> {
+        self.instance().db()
+    }
+
+    fn instance(&self) -> &StorageInstance {
+        self.state_node_storage.get_store().instance()
+    }
+
+    /// The block accumulator of the main chain head block.
+    pub(crate) fn head_block_accumulator(&self) -> Result<MerkleAccumulator> {
+        let startup_info = self
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info is none."))?;
+        let head_info = self
+            .get_block_info(startup_info.main)?
+            .ok_or_else(|| format_err!("Block info {} is none.", startup_info.main))?;
+        Ok(MerkleAccumulator::new_with_info(
+            head_info.block_accumulator_info,
+            self.get_accumulator_store(AccumulatorStoreType::Block),
+        ))
+    }
+
+    /// Get the header of the main chain block by the block number.
+    pub fn get_main_chain_block_header(&self, number: BlockNumber) -> Result<Option<BlockHeader>> {
+        match self.head_block_accumulator()?.get_leaf(number)? {
+            Some(block_id) => self.get_block_header_by_hash(block_id),
+            None => Ok(None),
+        }
+    }
+
+    /// Check the storage version is same as the current version, see `migration::check_storage_version`.
+    pub fn check_storage_version(&self) -> Result<()> {
+        migration::check_storage_version(&self.chain_info_storage)
+    }
 }
 
 impl StateNodeStore for Storage {
@@ -230,6 +337,14 @@ impl StateNodeStore for Storage {
         let batch = CodecWriteBatch::new_puts(nodes.into_iter().collect());
         self.state_node_storage.write_batch(batch)
     }
+
+    fn attach_reader(&self, owner: ThreadId) {
+        self.instance().attach_batch_reader(owner);
+    }
+
+    fn detach_reader(&self) {
+        self.instance().detach_batch_reader();
+    }
 }
 
 impl Display for Storage {
@@ -257,7 +372,9 @@ impl BlockStore for Storage {
     }
 
     fn save_genesis(&self, genesis_hash: HashValue) -> Result<()> {
-        self.chain_info_storage.save_genesis(genesis_hash)
+        self.chain_info_storage.save_genesis(genesis_hash)?;
+        self.chain_info_storage
+            .save_storage_version(StorageVersion::current_version().get_version())
     }
 
     fn get_chain_info(&self) -> Result<Option<ChainInfo>> {
@@ -349,6 +466,22 @@ impl BlockStore for Storage {
     ) -> Result<Option<(Block, Option<PeerId>, String)>> {
         self.block_storage.get_failed_block_by_id(block_id)
     }
+
+    fn save_block_bloom(&self, block_id: HashValue, bloom: BlockBloom) -> Result<()> {
+        self.block_storage.save_bloom(block_id, bloom)
+    }
+
+    fn get_block_bloom(&self, block_id: HashValue) -> Result<Option<BlockBloom>> {
+        self.block_storage.get_bloom(block_id)
+    }
+
+    fn get_imported_block_number(&self) -> Result<Option<BlockNumber>> {
+        self.chain_info_storage.get_imported_block_number()
+    }
+
+    fn save_imported_block_number(&self, number: BlockNumber) -> Result<()> {
+        self.chain_info_storage.save_imported_block_number(number)
+    }
 }
 
 impl BlockInfoStore for Storage {
@@ -473,6 +606,29 @@ pub trait Store:
         &self,
         accumulator_type: AccumulatorStoreType,
     ) -> Arc<dyn AccumulatorTreeStore>;
+
+    /// Save the state nodes written and replaced by the execution of the block, the replaced nodes
+    /// are deleted when the block is pruned, see `pruner::StoragePruner`.
+    fn save_state_node_changes(
+        &self,
+        block_number: BlockNumber,
+        block_id: HashValue,
+        parent_id: HashValue,
+        changes: StateNodeChanges,
+    ) -> Result<()>;
+
+    /// Collect the writes of the current thread into one atomic db write batch, until the batch is
+    /// committed or discarded, see `DBStorage::begin_batch`.
+    fn begin_write_batch(&self);
+
+    fn commit_write_batch(&self) -> Result<()>;
+
+    fn discard_write_batch(&self);
+
+    /// Take the write batch of the current thread, to continue it on another thread.
+    fn detach_write_batch(&self) -> Option<PendingWriteBatch>;
+
+    fn attach_write_batch(&self, batch: PendingWriteBatch) -> Result<()>;
 }
 
 pub trait IntoSuper<Super: ?Sized> {
@@ -509,4 +665,52 @@ impl Store for Storage {
             }
         }
     }
+
+    fn save_state_node_changes(
+        &self,
+        block_number: BlockNumber,
+        block_id: HashValue,
+        parent_id: HashValue,
+        changes: StateNodeChanges,
+    ) -> Result<()> {
+        self.state_node_write_storage.put_all(
+            changes
+                .written
+                .iter()
+                .map(|node_key| (StateNodeWriteKey::new(*node_key, block_number), block_id))
+                .collect(),
+        )?;
+        self.state_node_change_storage.put(
+            StateNodeChangeKey::new(block_number, block_id),
+            StateNodeChangeSet {
+                parent_id,
+                written: changes.written,
+                stale: changes.stale,
+            },
+        )
+    }
+
+    fn begin_write_batch(&self) {
+        // Begin the batch under the lock checked by `StoragePruner::prune`, which stops the pruning
+        // by `has_open_batch` once a batch is open.
+        let _guard = self.state_node_lock.lock();
+        self.instance().begin_batch()
+    }
+
+    fn commit_write_batch(&self) -> Result<()> {
+        let _guard = self.state_node_lock.lock();
+        self.instance().commit_batch()
+    }
+
+    fn discard_write_batch(&self) {
+        self.instance().discard_batch()
+    }
+
+    fn detach_write_batch(&self) -> Option<PendingWriteBatch> {
+        self.instance().detach_batch()
+    }
+
+    fn attach_write_batch(&self, batch: PendingWriteBatch) -> Result<()> {
+        self.instance().attach_batch(batch)
+    }
 }
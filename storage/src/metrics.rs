@@ -3,8 +3,8 @@
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use starcoin_metrics::{
-    self, register_histogram_vec, register_int_counter_vec, register_uint_gauge, HistogramTimer,
-    HistogramVec, IntCounterVec, UIntGauge,
+    self, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    register_uint_gauge, HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec, UIntGauge,
 };
 
 pub static STORAGE_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -40,6 +40,24 @@ pub static STORAGE_TIMES: Lazy<HistogramVec> = Lazy::new(|| {
 pub static CACHE_ITEMS: Lazy<UIntGauge> =
     Lazy::new(|| register_uint_gauge!("starcoin_cache_items", "How many items in cache").unwrap());
 
+pub static CACHE_TIER_ITEMS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "starcoin_cache_tier_items",
+        "How many items in every cache",
+        &["cache"]
+    )
+    .unwrap()
+});
+
+pub static CACHE_HITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "starcoin_cache_hits",
+        "Counters of the cache hits and misses",
+        &["cache", "result"]
+    )
+    .unwrap()
+});
+
 #[allow(clippy::upper_case_acronyms)]
 pub enum ResultType {
     NONE,
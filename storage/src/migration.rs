@@ -0,0 +1,324 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The schema version of the storage and the migrations between versions.
+//!
+//! The version is saved in the chain info column family, a db initialized before the version was
+//! introduced is treated as `StorageVersion::V1`. Every migration upgrades the db to its version,
+//! and the registered migrations are applied in order by `migrate`.
+
+use crate::batch::WriteBatch;
+use crate::block::FailedBlock;
+use crate::chain_info::ChainInfoStorage;
+use crate::db_storage::DBStorage;
+use crate::errors::StorageInitError;
+use crate::storage::{ColumnFamilyName, InnerStore, StorageInstance, ValueCodec};
+use crate::FAILED_BLOCK_PREFIX_NAME;
+use anyhow::{bail, format_err, Result};
+use logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// The count of the rows written to db in one batch.
+const MIGRATION_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum StorageVersion {
+    /// The legacy storage without the version saved.
+    V1 = 1,
+    V2 = 2,
+}
+
+impl StorageVersion {
+    pub fn current_version() -> Self {
+        StorageVersion::V2
+    }
+
+    pub fn get_version(&self) -> u64 {
+        *self as u64
+    }
+
+    pub fn from_version(version: u64) -> Result<Self> {
+        Ok(match version {
+            1 => StorageVersion::V1,
+            2 => StorageVersion::V2,
+            _ => bail!("Unsupported storage version: {}", version),
+        })
+    }
+}
+
+impl Display for StorageVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "V{}", self.get_version())
+    }
+}
+
+/// Read the storage version of the db, return None if the db is not initialized.
+pub fn read_storage_version(chain_info: &ChainInfoStorage) -> Result<Option<StorageVersion>> {
+    match chain_info.get_storage_version()? {
+        Some(version) => Ok(Some(StorageVersion::from_version(version)?)),
+        None if chain_info.get_genesis()?.is_some() => Ok(Some(StorageVersion::V1)),
+        None => Ok(None),
+    }
+}
+
+/// Check the storage version before opening the db for the node,
+/// save the current version if the db is not initialized.
+pub fn check_storage_version(chain_info: &ChainInfoStorage) -> Result<()> {
+    let current = StorageVersion::current_version();
+    match read_storage_version(chain_info)? {
+        None => chain_info.save_storage_version(current.get_version()),
+        Some(version) if version == current => Ok(()),
+        Some(version) if version > current => Err(StorageInitError::StorageVersionError(
+            format_err!(
+                "The storage version {} is newer than the supported version {}, please upgrade the node.",
+                version,
+                current
+            ),
+        )
+        .into()),
+        Some(version) => Err(StorageInitError::StorageVersionError(format_err!(
+            "The storage version {} is older than the supported version {}, please run `starcoin db migrate --data-dir <data_dir>` first.",
+            version,
+            current
+        ))
+        .into()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MigrationStats {
+    pub scanned: u64,
+    pub updated: u64,
+    pub deleted: u64,
+}
+
+/// The progress of a column family rewrite, reported every batch.
+#[derive(Clone, Debug)]
+pub struct MigrationProgress {
+    pub version: StorageVersion,
+    pub cf: ColumnFamilyName,
+    pub stats: MigrationStats,
+}
+
+pub struct MigrationContext<'a> {
+    db: Arc<DBStorage>,
+    version: StorageVersion,
+    dry_run: bool,
+    progress: &'a dyn Fn(&MigrationProgress),
+}
+
+impl<'a> MigrationContext<'a> {
+    pub fn new(
+        db: Arc<DBStorage>,
+        version: StorageVersion,
+        dry_run: bool,
+        progress: &'a dyn Fn(&MigrationProgress),
+    ) -> Self {
+        Self {
+            db,
+            version,
+            dry_run,
+            progress,
+        }
+    }
+
+    pub fn db(&self) -> &DBStorage {
+        self.db.as_ref()
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Rewrite every row of the column family `cf` by `f`, `f` returns the new key and value of
+    /// the row, or None to delete the row. If the key is changed, the old key is deleted.
+    /// Nothing is written in dry run mode, but the stats are same as the real run.
+    pub fn rewrite_cf<F>(&self, cf: ColumnFamilyName, mut f: F) -> Result<MigrationStats>
+    where
+        F: FnMut(&[u8], &[u8]) -> Result<Option<(Vec<u8>, Vec<u8>)>>,
+    {
+        let mut stats = MigrationStats::default();
+        let mut batch = WriteBatch::new();
+        let mut iter = self.db.iter(cf)?;
+        iter.seek_to_first();
+        for item in iter {
+            let (key, value) = item?;
+            stats.scanned += 1;
+            match f(key.as_slice(), value.as_slice())? {
+                Some((new_key, new_value)) => {
+                    if new_key == key && new_value == value {
+                        continue;
+                    }
+                    if new_key != key {
+                        batch.delete(key)?;
+                    }
+                    batch.put(new_key, new_value)?;
+                    stats.updated += 1;
+                }
+                None => {
+                    batch.delete(key)?;
+                    stats.deleted += 1;
+                }
+            }
+            if batch.rows.len() >= MIGRATION_BATCH_SIZE {
+                self.flush(cf, &mut batch, stats)?;
+            }
+        }
+        self.flush(cf, &mut batch, stats)?;
+        Ok(stats)
+    }
+
+    fn flush(
+        &self,
+        cf: ColumnFamilyName,
+        batch: &mut WriteBatch,
+        stats: MigrationStats,
+    ) -> Result<()> {
+        if !self.dry_run && !batch.rows.is_empty() {
+            self.db.write_batch(cf, batch.clone())?;
+        }
+        batch.clear()?;
+        (self.progress)(&MigrationProgress {
+            version: self.version,
+            cf,
+            stats,
+        });
+        Ok(())
+    }
+}
+
+pub trait StorageMigration {
+    /// The version of the storage after the migration.
+    fn version(&self) -> StorageVersion;
+
+    fn description(&self) -> &'static str;
+
+    fn migrate(&self, ctx: &MigrationContext) -> Result<MigrationStats>;
+}
+
+/// Remove the failed blocks which can not be decoded by the current `FailedBlock`,
+/// they are only kept for debugging and can not be read by the node anymore.
+struct FailedBlockMigration;
+
+impl StorageMigration for FailedBlockMigration {
+    fn version(&self) -> StorageVersion {
+        StorageVersion::V2
+    }
+
+    fn description(&self) -> &'static str {
+        "remove the undecodable failed blocks"
+    }
+
+    fn migrate(&self, ctx: &MigrationContext) -> Result<MigrationStats> {
+        ctx.rewrite_cf(FAILED_BLOCK_PREFIX_NAME, |key, value| {
+            Ok(match FailedBlock::decode_value(value) {
+                Ok(_) => Some((key.to_vec(), value.to_vec())),
+                Err(_) => None,
+            })
+        })
+    }
+}
+
+/// The registered migrations, in version order.
+pub fn migrations() -> Vec<Box<dyn StorageMigration>> {
+    vec![Box::new(FailedBlockMigration)]
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MigrationStepReport {
+    pub version: StorageVersion,
+    pub description: String,
+    pub stats: MigrationStats,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// The version before the migration, None if the db is not initialized.
+    pub from: Option<StorageVersion>,
+    pub to: StorageVersion,
+    pub dry_run: bool,
+    pub steps: Vec<MigrationStepReport>,
+}
+
+/// Apply the migrations newer than the version of `db`, the version is saved after every step,
+/// so an interrupted migration can be continued by running again.
+pub fn migrate(
+    db: Arc<DBStorage>,
+    dry_run: bool,
+    progress: &dyn Fn(&MigrationProgress),
+) -> Result<MigrationReport> {
+    let chain_info = ChainInfoStorage::new(StorageInstance::DB { db: db.clone() });
+    let current = StorageVersion::current_version();
+    let from = read_storage_version(&chain_info)?;
+    let mut report = MigrationReport {
+        from,
+        to: current,
+        dry_run,
+        steps: vec![],
+    };
+    let from = match from {
+        Some(from) => from,
+        // A fresh db is initialized with the current version when the node starts.
+        None => return Ok(report),
+    };
+    if from > current {
+        bail!(
+            "The storage version {} is newer than the supported version {}",
+            from,
+            current
+        );
+    }
+    for migration in migrations()
+        .into_iter()
+        .filter(|migration| migration.version() > from)
+    {
+        let ctx = MigrationContext::new(db.clone(), migration.version(), dry_run, progress);
+        let stats = migration.migrate(&ctx)?;
+        if !dry_run {
+            chain_info.save_storage_version(migration.version().get_version())?;
+        }
+        report.steps.push(MigrationStepReport {
+            version: migration.version(),
+            description: migration.description().to_string(),
+            stats,
+        });
+    }
+    Ok(report)
+}
+
+/// Apply the migrations to the db of an older version when the node starts, same as
+/// `starcoin db migrate`. Return None if the db is not initialized or is not older than the
+/// current version, `check_storage_version` reports the newer version.
+/// It must run before the db is read through the cache, the migration writes the db directly.
+pub fn migrate_on_start(db: Arc<DBStorage>) -> Result<Option<MigrationReport>> {
+    let chain_info = ChainInfoStorage::new(StorageInstance::DB { db: db.clone() });
+    match read_storage_version(&chain_info)? {
+        Some(version) if version < StorageVersion::current_version() => {
+            info!(
+                "Migrate the storage from version {} to {}",
+                version,
+                StorageVersion::current_version()
+            );
+            let report = migrate(db, false, &|progress| {
+                debug!(
+                    "[{}] {}: scanned {}, updated {}, deleted {}",
+                    progress.version,
+                    progress.cf,
+                    progress.stats.scanned,
+                    progress.stats.updated,
+                    progress.stats.deleted
+                )
+            })?;
+            for step in &report.steps {
+                info!(
+                    "Migrated the storage to version {} ({}): {:?}",
+                    step.version, step.description, step.stats
+                );
+            }
+            Ok(Some(report))
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state_node::{StateNodeChangeKey, StateNodeChangeSet, StateNodeWriteKey};
+use crate::storage::{CodecKVStore, CodecWriteBatch, KeyCodec, ValueCodec};
+use crate::{BlockStore, Storage, STATE_NODE_CHANGE_PREFIX_NAME, STATE_NODE_WRITE_PREFIX_NAME};
+use anyhow::{format_err, Result};
+use crypto::HashValue;
+use logger::prelude::*;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_types::block::BlockNumber;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Delete the state nodes, events and transaction infos of the main chain blocks older than the
+/// latest `retain_blocks` blocks. The genesis block is never pruned.
+///
+/// Every executed block records the state nodes it writes, and the nodes of its parent state it
+/// replaces, see `Store::save_state_node_changes`. The pruner counts the trees referencing every
+/// node block by block along the main chain, a node replaced by a pruned block is deleted if no tree
+/// of the block's state references it, and no later block, main chain or fork, writes it again. The
+/// nodes written by the forks at the pruned heights are deleted in the same way. The pruning stops
+/// below the forks which have blocks in the retained blocks, so their parent states stay readable.
+/// The nodes of the genesis state are never deleted.
+///
+/// The state nodes of the chains executed before the changes are recorded are not pruned, as the
+/// references of their nodes are unknown, only the block data is pruned.
+///
+/// A block is not pruned while a write batch is open, as the pending writes of the batch, such as
+/// the state nodes written again by an executing block, are not visible to the pruner.
+pub struct StoragePruner {
+    storage: Arc<Storage>,
+    retain_blocks: u64,
+    batch_blocks: u64,
+}
+
+impl StoragePruner {
+    pub fn new(storage: Arc<Storage>, retain_blocks: u64, batch_blocks: u64) -> Self {
+        Self {
+            storage,
+            retain_blocks,
+            batch_blocks: batch_blocks.max(1),
+        }
+    }
+
+    /// The number of the latest pruned main chain block.
+    pub fn pruned_block_number(&self) -> Result<Option<BlockNumber>> {
+        self.storage.chain_info_storage.get_pruned_block_number()
+    }
+
+    /// Prune at most `batch_blocks` blocks, return the new pruned block number, or None if there
+    /// are not enough blocks to prune, or a write batch is open.
+    pub fn prune(&self) -> Result<Option<BlockNumber>> {
+        let accumulator = self.storage.head_block_accumulator()?;
+        let head_number = accumulator.num_leaves().saturating_sub(1);
+        // The blocks after the retained number are retained.
+        let retained_number = head_number.saturating_sub(self.retain_blocks);
+        let pruned = self.pruned_block_number()?.unwrap_or(0);
+        let mut end = pruned + self.batch_blocks;
+        if retained_number < end {
+            return Ok(None);
+        }
+        if let Some(fork_number) =
+            self.retained_fork_number(&accumulator, pruned + 1, end, retained_number)?
+        {
+            if fork_number <= pruned + 1 {
+                debug!(
+                    "Wait for the fork at block {} to leave the retained blocks.",
+                    fork_number
+                );
+                return Ok(None);
+            }
+            end = fork_number - 1;
+        }
+        let genesis_id = Self::block_id(&accumulator, 0)?;
+        let prune_state_nodes = self
+            .storage
+            .state_node_change_storage
+            .get(StateNodeChangeKey::new(0, genesis_id))?
+            .is_some();
+        if !prune_state_nodes {
+            debug!("The state node changes are not recorded, only prune the block data.");
+        }
+
+        let mut deleted = 0;
+        let mut last_pruned = None;
+        for number in pruned + 1..=end {
+            let block_id = Self::block_id(&accumulator, number)?;
+            // No batch is begun while the lock is held.
+            let _guard = self.storage.state_node_lock.lock();
+            let instance = self.storage.instance();
+            if instance.db().map_or(false, |db| db.has_open_batch()) {
+                debug!("Wait for the open write batches to prune block {}.", number);
+                break;
+            }
+            // The changes of a block are written in one batch, so the counts are not applied twice
+            // if the pruning is interrupted.
+            instance.begin_batch();
+            match self.prune_block(number, block_id, prune_state_nodes) {
+                Ok(block_deleted) => {
+                    instance.commit_batch()?;
+                    deleted += block_deleted;
+                    last_pruned = Some(number);
+                }
+                Err(e) => {
+                    instance.discard_batch();
+                    return Err(e);
+                }
+            }
+        }
+        if let Some(last_pruned) = last_pruned {
+            info!(
+                "Pruned blocks [{}, {}], {} state nodes deleted.",
+                pruned + 1,
+                last_pruned,
+                deleted
+            );
+        }
+        Ok(last_pruned)
+    }
+
+    fn block_id(accumulator: &MerkleAccumulator, number: BlockNumber) -> Result<HashValue> {
+        accumulator
+            .get_leaf(number)?
+            .ok_or_else(|| format_err!("Main chain block {} is not in accumulator.", number))
+    }
+
+    /// Find the lowest number of the fork blocks in [start, end] which have descendants after the
+    /// retained number.
+    fn retained_fork_number(
+        &self,
+        accumulator: &MerkleAccumulator,
+        start: BlockNumber,
+        end: BlockNumber,
+        retained_number: BlockNumber,
+    ) -> Result<Option<BlockNumber>> {
+        // The fork blocks at the previous height, and the numbers of their lowest fork ancestors.
+        let mut forks = HashMap::new();
+        for number in start..accumulator.num_leaves() {
+            if number > end && forks.is_empty() {
+                break;
+            }
+            let main_block_id = Self::block_id(accumulator, number)?;
+            let mut next_forks = HashMap::new();
+            for (key, change_set) in self.block_changes_at(number)? {
+                if key.block_id == main_block_id {
+                    continue;
+                }
+                let fork_number = match forks.get(&change_set.parent_id) {
+                    Some(fork_number) => *fork_number,
+                    None if number <= end => number,
+                    None => continue,
+                };
+                next_forks.insert(key.block_id, fork_number);
+            }
+            if number > retained_number && !next_forks.is_empty() {
+                return Ok(next_forks.values().min().copied());
+            }
+            forks = next_forks;
+        }
+        Ok(None)
+    }
+
+    /// Prune the main chain block and the fork blocks at its height, return the number of the
+    /// deleted state nodes.
+    fn prune_block(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+        prune_state_nodes: bool,
+    ) -> Result<usize> {
+        let block_changes = self.block_changes_at(number)?;
+        let mut deleted = 0;
+        if prune_state_nodes {
+            let mut candidates = HashSet::new();
+            for (key, change_set) in &block_changes {
+                if key.block_id == block_id {
+                    self.update_ref_counts(change_set)?;
+                    candidates.extend(change_set.stale.iter().copied());
+                } else {
+                    candidates.extend(change_set.written.iter().copied());
+                }
+            }
+            for node_key in candidates {
+                if self.delete_state_node(number, node_key)? {
+                    deleted += 1;
+                }
+            }
+        }
+        self.storage
+            .state_node_change_storage
+            .delete_all(block_changes.into_iter().map(|(key, _)| key).collect())?;
+        self.prune_block_data(block_id)?;
+        self.storage
+            .chain_info_storage
+            .save_pruned_block_number(number)?;
+        Ok(deleted)
+    }
+
+    fn update_ref_counts(&self, change_set: &StateNodeChangeSet) -> Result<()> {
+        let mut deltas: HashMap<HashValue, i64> = HashMap::new();
+        for node_key in &change_set.written {
+            *deltas.entry(*node_key).or_default() += 1;
+        }
+        for node_key in &change_set.stale {
+            *deltas.entry(*node_key).or_default() -= 1;
+        }
+        let mut batch = CodecWriteBatch::new();
+        for (node_key, delta) in deltas {
+            let count = self
+                .storage
+                .state_node_ref_storage
+                .get(node_key)?
+                .unwrap_or(0) as i64
+                + delta;
+            // The count of a genesis node is negative after it is replaced, the genesis nodes are
+            // never deleted.
+            if count > 0 {
+                batch.put(node_key, count as u64)?;
+            } else {
+                batch.delete(node_key)?;
+            }
+        }
+        self.storage.state_node_ref_storage.write_batch(batch)
+    }
+
+    /// Delete the node if it is not referenced by the state of the main chain block `number`, and
+    /// not written by the genesis or the blocks after `number`.
+    fn delete_state_node(&self, number: BlockNumber, node_key: HashValue) -> Result<bool> {
+        if self.storage.state_node_ref_storage.get(node_key)?.is_some() {
+            return Ok(false);
+        }
+        let writes = self.node_writes(node_key)?;
+        if writes
+            .iter()
+            .any(|write| write.block_number == 0 || write.block_number > number)
+        {
+            return Ok(false);
+        }
+        self.storage.state_node_write_storage.delete_all(writes)?;
+        self.storage.state_node_storage.remove(node_key)?;
+        Ok(true)
+    }
+
+    fn prune_block_data(&self, block_id: HashValue) -> Result<()> {
+        for txn_info_id in self.storage.get_block_txn_info_ids(block_id)? {
+            if let Some(txn_info) = self
+                .storage
+                .transaction_info_storage
+                .get_transaction_info(txn_info_id)?
+            {
+                self.storage
+                    .transaction_info_hash_storage
+                    .remove_transaction_info_id(txn_info.transaction_hash(), txn_info_id)?;
+            }
+            self.storage.event_storage.remove(txn_info_id)?;
+            self.storage.transaction_info_storage.remove(txn_info_id)?;
+        }
+        self.storage
+            .block_storage
+            .delete_transaction_infos(block_id)
+    }
+
+    /// The recorded state node changes of the blocks at the height, main chain or fork.
+    fn block_changes_at(
+        &self,
+        number: BlockNumber,
+    ) -> Result<Vec<(StateNodeChangeKey, StateNodeChangeSet)>> {
+        let prefix = number.to_be_bytes().to_vec();
+        self.scan_prefix(STATE_NODE_CHANGE_PREFIX_NAME, prefix)?
+            .into_iter()
+            .map(|(key, value)| {
+                Ok((
+                    StateNodeChangeKey::decode_key(&key)?,
+                    StateNodeChangeSet::decode_value(&value)?,
+                ))
+            })
+            .collect()
+    }
+
+    fn node_writes(&self, node_key: HashValue) -> Result<Vec<StateNodeWriteKey>> {
+        self.scan_prefix(STATE_NODE_WRITE_PREFIX_NAME, node_key.to_vec())?
+            .into_iter()
+            .map(|(key, _)| StateNodeWriteKey::decode_key(&key))
+            .collect()
+    }
+
+    /// The rows of the column family whose key starts with the prefix, nothing is recorded if the
+    /// storage is a pure cache storage.
+    fn scan_prefix(&self, prefix_name: &str, prefix: Vec<u8>) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let db = match self.storage.db() {
+            Some(db) => db,
+            None => return Ok(vec![]),
+        };
+        let mut iter = db.iter(prefix_name)?;
+        iter.seek(prefix.clone())?;
+        let mut rows = vec![];
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            rows.push((key, value));
+        }
+        Ok(rows)
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline repair of the db.
+//!
+//! The blocks, transaction infos, events and states are the primary data, which can only be
+//! recovered by executing the blocks again. The other entries of a main chain block are derived
+//! from them: the block header and body, the block txn ids, the transactions, the txn hash index,
+//! the event bloom, the accumulator nodes and the block info. The repairer walks the main chain by
+//! the parent hashes of the blocks, checks the primary data of every block and writes the derived
+//! entries again. If the primary data of a block is damaged, the chain is truncated back to the
+//! parent of the block, and the block infos of the truncated blocks are deleted, so the node
+//! executes the blocks from there again.
+
+use crate::storage::CodecKVStore;
+use crate::{
+    BlockInfoStore, BlockStore, BlockTransactionInfoStore, ContractEventStore, Storage, Store,
+    TransactionStore,
+};
+use anyhow::{bail, ensure, format_err, Result};
+use crypto::hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use crypto::HashValue;
+use logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::inmemory::InMemoryAccumulator;
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_state_store_api::StateNodeStore;
+use starcoin_types::block::{Block, BlockHeader, BlockInfo, BlockNumber};
+use starcoin_types::block_bloom::BlockBloom;
+use starcoin_types::contract_event::ContractEvent;
+use starcoin_types::startup_info::StartupInfo;
+use starcoin_types::transaction::{BlockTransactionInfo, Transaction};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The progress callback is called every `PROGRESS_BLOCKS` blocks.
+const PROGRESS_BLOCKS: u64 = 10000;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DamagedBlock {
+    pub block_number: BlockNumber,
+    pub block_id: HashValue,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub from_block: BlockNumber,
+    pub repaired_blocks: u64,
+    pub repaired_txns: u64,
+    /// The first main chain block whose primary data is damaged, the chain is truncated to its parent.
+    pub damaged_block: Option<DamagedBlock>,
+    /// The head block after the repair.
+    pub head_number: BlockNumber,
+    pub head_hash: HashValue,
+}
+
+/// The primary data of a block, checked against the block header.
+struct PrimaryData {
+    block: Block,
+    txn_infos: Vec<BlockTransactionInfo>,
+    events: Vec<Vec<ContractEvent>>,
+    txns: Vec<Transaction>,
+}
+
+pub struct StorageRepairer {
+    storage: Arc<Storage>,
+}
+
+impl StorageRepairer {
+    pub fn new(storage: Arc<Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// Repair the main chain blocks from `from_block` to the head. The default `from_block` is the
+    /// first block which is not pruned. The block info of the parent of `from_block` must be intact.
+    pub fn repair(
+        &self,
+        from_block: Option<BlockNumber>,
+        progress: &dyn Fn(&RepairReport),
+    ) -> Result<RepairReport> {
+        let head_id = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info is none, the db is not initialized."))?
+            .main;
+        let block_ids = self.main_chain_block_ids(head_id)?;
+        let head_number = (block_ids.len() - 1) as BlockNumber;
+        let pruned_block_number = self.storage.chain_info_storage.get_pruned_block_number()?;
+        let first_unpruned = pruned_block_number.map_or(0, |pruned| pruned + 1);
+        let from_block = from_block.unwrap_or(first_unpruned);
+        ensure!(
+            from_block >= first_unpruned,
+            "The transaction infos of the blocks before {} have been pruned, can not repair from block {}",
+            first_unpruned,
+            from_block
+        );
+        if from_block > head_number {
+            bail!(
+                "The from block {} is after the head block {}",
+                from_block,
+                head_number
+            );
+        }
+        let mut parent = if from_block == 0 {
+            None
+        } else {
+            let parent_id = block_ids[from_block as usize - 1];
+            let parent_header = self
+                .storage
+                .get_block_header_by_hash(parent_id)?
+                .ok_or_else(|| format_err!("The block header of block {} is none.", parent_id))?;
+            let parent_info = self.storage.get_block_info(parent_id)?.ok_or_else(|| {
+                format_err!(
+                    "The block info of block {} is none, please repair from an earlier block.",
+                    parent_id
+                )
+            })?;
+            Some((parent_header, parent_info))
+        };
+
+        let mut report = RepairReport {
+            from_block,
+            repaired_blocks: 0,
+            repaired_txns: 0,
+            damaged_block: None,
+            head_number,
+            head_hash: head_id,
+        };
+        for number in from_block..=head_number {
+            let block_id = block_ids[number as usize];
+            let primary = match self.check_primary_data(number, block_id, parent.as_ref()) {
+                Ok(primary) => primary,
+                Err(e) => {
+                    warn!("The block {} ({}) is damaged: {}", number, block_id, e);
+                    report.damaged_block = Some(DamagedBlock {
+                        block_number: number,
+                        block_id,
+                        detail: e.to_string(),
+                    });
+                    break;
+                }
+            };
+            report.repaired_txns += primary.txns.len() as u64;
+            parent = Some(self.rebuild_derived_data(primary, parent.as_ref())?);
+            report.repaired_blocks += 1;
+            if report.repaired_blocks % PROGRESS_BLOCKS == 0 {
+                progress(&report);
+            }
+        }
+
+        if let Some(damaged) = &report.damaged_block {
+            if damaged.block_number == 0 {
+                bail!("The genesis block is damaged, please clean the data dir and sync again.");
+            }
+            report.head_number = damaged.block_number - 1;
+            report.head_hash = block_ids[report.head_number as usize];
+            // The entries are deleted before the head is moved, so the truncation is done again if
+            // it is interrupted.
+            self.truncate(&block_ids[damaged.block_number as usize..])?;
+            self.storage
+                .save_startup_info(StartupInfo::new(report.head_hash))?;
+        }
+        info!(
+            "Repaired {} blocks from block {}, the head is block {}.",
+            report.repaired_blocks, from_block, report.head_number
+        );
+        Ok(report)
+    }
+
+    /// Delete the entries which mark the blocks executed or failed, so the blocks are executed again
+    /// when they are synced or applied, instead of being skipped.
+    fn truncate(&self, block_ids: &[HashValue]) -> Result<()> {
+        for block_id in block_ids {
+            self.storage.block_info_storage.remove(*block_id)?;
+            self.storage
+                .block_storage
+                .delete_transaction_infos(*block_id)?;
+            self.storage.block_storage.delete_failed_block(*block_id)?;
+        }
+        Ok(())
+    }
+
+    /// The ids of the main chain blocks indexed by the block number, walked back from the head by
+    /// the parent hashes. The block accumulator is only used to skip a block which can not be read.
+    fn main_chain_block_ids(&self, head_id: HashValue) -> Result<Vec<HashValue>> {
+        let mut block_ids = vec![];
+        let mut block_id = head_id;
+        let mut number = None;
+        let mut accumulator = None;
+        loop {
+            block_ids.push(block_id);
+            if let Some(block) = self.load_block(block_id) {
+                if number.map_or(true, |number| block.header().number() == number) {
+                    if block.header().is_genesis() {
+                        break;
+                    }
+                    block_id = block.header().parent_hash();
+                    number = Some(block.header().number() - 1);
+                    continue;
+                }
+            }
+            if accumulator.is_none() {
+                accumulator = Some(self.storage.head_block_accumulator()?);
+            }
+            let accumulator = accumulator.as_ref().expect("accumulator is loaded");
+            let current = match number {
+                Some(number) => number,
+                None => accumulator.num_leaves().saturating_sub(1),
+            };
+            if current == 0 {
+                bail!(
+                    "The genesis block {} is damaged, please clean the data dir and sync again.",
+                    block_id
+                );
+            }
+            block_id = accumulator.get_leaf(current - 1)?.ok_or_else(|| {
+                format_err!(
+                    "The block {} is damaged and the main chain block {} is not in the block accumulator, please clean the data dir and sync again.",
+                    current,
+                    current - 1
+                )
+            })?;
+            number = Some(current - 1);
+        }
+        block_ids.reverse();
+        Ok(block_ids)
+    }
+
+    /// Load the block, or rebuild it from the header and body if the block entry is damaged.
+    fn load_block(&self, block_id: HashValue) -> Option<Block> {
+        if let Ok(Some(block)) = self.storage.get_block(block_id) {
+            if block.id() == block_id {
+                return Some(block);
+            }
+        }
+        let header = self.storage.get_block_header_by_hash(block_id).ok()??;
+        let body = self.storage.get_body(block_id).ok()??;
+        if header.id() == block_id && body.hash() == header.body_hash() {
+            Some(Block::new(header, body))
+        } else {
+            None
+        }
+    }
+
+    /// Load and check the primary data of the block, return an error if it is damaged.
+    fn check_primary_data(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+        parent: Option<&(BlockHeader, BlockInfo)>,
+    ) -> Result<PrimaryData> {
+        let block = self
+            .load_block(block_id)
+            .ok_or_else(|| format_err!("the block is missing or corrupt"))?;
+        let header = block.header();
+        ensure!(
+            header.number() == number,
+            "the block number is {}",
+            header.number()
+        );
+        let parent_root = match parent {
+            Some((parent_header, parent_info)) => {
+                ensure!(
+                    header.parent_hash() == parent_header.id(),
+                    "the parent hash {} is not the previous block {}",
+                    header.parent_hash(),
+                    parent_header.id()
+                );
+                parent_info.block_accumulator_info.accumulator_root
+            }
+            None => *ACCUMULATOR_PLACEHOLDER_HASH,
+        };
+        ensure!(
+            header.block_accumulator_root() == parent_root,
+            "the block accumulator root of the header is {}, the parent's is {}",
+            header.block_accumulator_root(),
+            parent_root
+        );
+
+        let txn_info_ids = self
+            .storage
+            .block_storage
+            .get_transaction_info_ids(block_id)?
+            .ok_or_else(|| format_err!("the block txn info ids are missing"))?;
+        let accumulator = self.new_accumulator(
+            AccumulatorStoreType::Transaction,
+            parent.map(|(_, info)| info),
+        );
+        let txn_accumulator_root = accumulator.append(&txn_info_ids)?;
+        ensure!(
+            txn_accumulator_root == header.txn_accumulator_root(),
+            "the recomputed txn accumulator root is {}, the header's is {}",
+            txn_accumulator_root,
+            header.txn_accumulator_root()
+        );
+        let mut txn_infos = vec![];
+        let mut events = vec![];
+        for txn_info_id in txn_info_ids {
+            let txn_info = self
+                .storage
+                .get_transaction_info(txn_info_id)?
+                .ok_or_else(|| format_err!("txn info {} is missing", txn_info_id))?;
+            ensure!(
+                txn_info.id() == txn_info_id && txn_info.block_id() == block_id,
+                "txn info {} is corrupt",
+                txn_info_id
+            );
+            let txn_events = self
+                .storage
+                .get_contract_events(txn_info_id)?
+                .ok_or_else(|| format_err!("the events of txn info {} are missing", txn_info_id))?;
+            let event_hashes: Vec<_> = txn_events.iter().map(|e| e.crypto_hash()).collect();
+            ensure!(
+                InMemoryAccumulator::from_leaves(event_hashes.as_slice()).root_hash()
+                    == txn_info.event_root_hash(),
+                "the events of txn info {} are corrupt",
+                txn_info_id
+            );
+            txn_infos.push(txn_info);
+            events.push(txn_events);
+        }
+
+        let state_root = header.state_root();
+        if state_root != *SPARSE_MERKLE_PLACEHOLDER_HASH {
+            ensure!(
+                StateNodeStore::get(self.storage.as_ref(), &state_root)?.is_some(),
+                "the state root {} is missing",
+                state_root
+            );
+        }
+
+        // The block metadata txn and the user txns are derived from the block, the txn ids are
+        // the txn hashes of the txn infos.
+        let mut derived: HashMap<HashValue, Transaction> = block
+            .transactions()
+            .iter()
+            .cloned()
+            .map(|txn| (txn.id(), Transaction::UserTransaction(txn)))
+            .collect();
+        if let Some((parent_header, _)) = parent {
+            let metadata = Transaction::BlockMetadata(block.to_metadata(parent_header.gas_used()));
+            derived.insert(metadata.id(), metadata);
+        }
+        let mut txns = vec![];
+        for txn_info in &txn_infos {
+            let txn_id = txn_info.transaction_hash();
+            let txn = match derived.remove(&txn_id) {
+                Some(txn) => txn,
+                None => self
+                    .storage
+                    .get_transaction(txn_id)?
+                    .ok_or_else(|| format_err!("txn {} is missing", txn_id))?,
+            };
+            txns.push(txn);
+        }
+        for txn in block.transactions() {
+            ensure!(
+                !derived.contains_key(&txn.id()),
+                "the user txn {} has no txn info",
+                txn.id()
+            );
+        }
+        Ok(PrimaryData {
+            block,
+            txn_infos,
+            events,
+            txns,
+        })
+    }
+
+    /// Write the derived entries of the block, return the header and the block info of the block.
+    fn rebuild_derived_data(
+        &self,
+        primary: PrimaryData,
+        parent: Option<&(BlockHeader, BlockInfo)>,
+    ) -> Result<(BlockHeader, BlockInfo)> {
+        let PrimaryData {
+            block,
+            txn_infos,
+            events,
+            txns,
+        } = primary;
+        let block_id = block.id();
+        let header = block.header().clone();
+        let parent_info = parent.map(|(_, info)| info);
+
+        let bloom =
+            BlockBloom::from_txn_events(txns.iter().zip(events.iter()).map(|(txn, events)| {
+                let sender = match txn {
+                    Transaction::UserTransaction(txn) => Some(txn.sender()),
+                    _ => None,
+                };
+                (sender, events.as_slice())
+            }));
+        self.storage.save_block_bloom(block_id, bloom)?;
+        for txn_info in &txn_infos {
+            let txn_hash = txn_info.transaction_hash();
+            match self.storage.get_transaction_info_ids_by_hash(txn_hash) {
+                Ok(ids) if ids.contains(&txn_info.id()) => {}
+                Ok(_) => self
+                    .storage
+                    .transaction_info_hash_storage
+                    .save_transaction_infos(vec![txn_info.clone()])?,
+                // The txn info ids of the other branches in the corrupt entry are dropped.
+                Err(_) => self
+                    .storage
+                    .transaction_info_hash_storage
+                    .put(txn_hash, vec![txn_info.id()])?,
+            }
+        }
+        self.storage
+            .save_block_transaction_ids(block_id, txns.iter().map(|txn| txn.id()).collect())?;
+        self.storage.save_transaction_batch(txns)?;
+        self.storage.commit_block(block)?;
+
+        let txn_accumulator = self.new_accumulator(AccumulatorStoreType::Transaction, parent_info);
+        let txn_info_ids: Vec<_> = txn_infos.iter().map(|info| info.id()).collect();
+        txn_accumulator.append(&txn_info_ids)?;
+        txn_accumulator.flush()?;
+        let block_accumulator = self.new_accumulator(AccumulatorStoreType::Block, parent_info);
+        block_accumulator.append(&[block_id])?;
+        block_accumulator.flush()?;
+        let total_difficulty = parent_info
+            .map(|info| info.total_difficulty)
+            .unwrap_or_default()
+            + header.difficulty();
+        let block_info = BlockInfo::new(
+            block_id,
+            total_difficulty,
+            txn_accumulator.get_info(),
+            block_accumulator.get_info(),
+        );
+        self.storage.save_block_info(block_info.clone())?;
+        Ok((header, block_info))
+    }
+
+    fn new_accumulator(
+        &self,
+        store_type: AccumulatorStoreType,
+        parent_info: Option<&BlockInfo>,
+    ) -> MerkleAccumulator {
+        let store = self.storage.get_accumulator_store(store_type);
+        match parent_info {
+            Some(info) => MerkleAccumulator::new_with_info(
+                match store_type {
+                    AccumulatorStoreType::Block => info.block_accumulator_info.clone(),
+                    AccumulatorStoreType::Transaction => info.txn_accumulator_info.clone(),
+                },
+                store,
+            ),
+            None => MerkleAccumulator::new_empty(store),
+        }
+    }
+}
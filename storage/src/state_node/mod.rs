@@ -2,11 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::define_storage;
-use crate::storage::ValueCodec;
-use crate::STATE_NODE_PREFIX_NAME;
-use anyhow::Result;
+use crate::storage::{KeyCodec, ValueCodec};
+use crate::{
+    STATE_NODE_CHANGE_PREFIX_NAME, STATE_NODE_PREFIX_NAME, STATE_NODE_REF_PREFIX_NAME,
+    STATE_NODE_WRITE_PREFIX_NAME,
+};
+use anyhow::{ensure, Result};
+use bcs_ext::BCSCodec;
 use crypto::HashValue;
+use serde::{Deserialize, Serialize};
 use starcoin_state_store_api::StateNode;
+use starcoin_types::block::BlockNumber;
+use std::convert::TryInto;
 
 define_storage!(StateStorage, HashValue, StateNode, STATE_NODE_PREFIX_NAME);
 
@@ -18,3 +25,134 @@ impl ValueCodec for StateNode {
         Ok(StateNode(data.to_vec()))
     }
 }
+
+/// The key of the state node changes of an executed block, main chain or fork.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct StateNodeChangeKey {
+    pub block_number: BlockNumber,
+    pub block_id: HashValue,
+}
+
+impl StateNodeChangeKey {
+    pub fn new(block_number: BlockNumber, block_id: HashValue) -> Self {
+        Self {
+            block_number,
+            block_id,
+        }
+    }
+}
+
+/// The key is the big endian block number followed by the block id, so the blocks at a height are
+/// found by a prefix seek.
+impl KeyCodec for StateNodeChangeKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = self.block_number.to_be_bytes().to_vec();
+        key.extend(self.block_id.to_vec());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == 8 + HashValue::LENGTH,
+            "Invalid state node change key length: {}",
+            data.len()
+        );
+        let (number, block_id) = data.split_at(8);
+        Ok(Self {
+            block_number: u64::from_be_bytes(number.try_into()?),
+            block_id: HashValue::from_slice(block_id)?,
+        })
+    }
+}
+
+/// The state nodes written by the block, and the nodes of the parent state replaced by them. A node
+/// shared by several trees is listed once for every tree.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateNodeChangeSet {
+    pub parent_id: HashValue,
+    pub written: Vec<HashValue>,
+    pub stale: Vec<HashValue>,
+}
+
+impl ValueCodec for StateNodeChangeSet {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+/// The key of a write of the state node by a block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct StateNodeWriteKey {
+    pub node_key: HashValue,
+    pub block_number: BlockNumber,
+}
+
+impl StateNodeWriteKey {
+    pub fn new(node_key: HashValue, block_number: BlockNumber) -> Self {
+        Self {
+            node_key,
+            block_number,
+        }
+    }
+}
+
+/// The key is the node key followed by the big endian block number, so the writes of a node are
+/// found by a prefix seek.
+impl KeyCodec for StateNodeWriteKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = self.node_key.to_vec();
+        key.extend_from_slice(&self.block_number.to_be_bytes());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == HashValue::LENGTH + 8,
+            "Invalid state node write key length: {}",
+            data.len()
+        );
+        let (node_key, number) = data.split_at(HashValue::LENGTH);
+        Ok(Self {
+            node_key: HashValue::from_slice(node_key)?,
+            block_number: u64::from_be_bytes(number.try_into()?),
+        })
+    }
+}
+
+impl ValueCodec for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(u64::from_be_bytes(data.try_into()?))
+    }
+}
+
+define_storage!(
+    StateNodeChangeStorage,
+    StateNodeChangeKey,
+    StateNodeChangeSet,
+    STATE_NODE_CHANGE_PREFIX_NAME
+);
+
+/// The value is the id of the block which writes the node.
+define_storage!(
+    StateNodeWriteStorage,
+    StateNodeWriteKey,
+    HashValue,
+    STATE_NODE_WRITE_PREFIX_NAME
+);
+
+/// The number of the trees referencing the node in the state of the latest pruned block, maintained
+/// by the pruner, the zero counts are not stored.
+define_storage!(
+    StateNodeRefStorage,
+    HashValue,
+    u64,
+    STATE_NODE_REF_PREFIX_NAME
+);
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-pub use crate::batch::WriteBatch;
+pub use crate::batch::{PendingWriteBatch, WriteBatch};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
 use anyhow::{bail, Result};
@@ -13,6 +13,7 @@ use std::convert::TryInto;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::thread::ThreadId;
 
 /// Type alias to improve readability.
 pub type ColumnFamilyName = &'static str;
@@ -109,6 +110,60 @@ impl StorageInstance {
             _ => None,
         }
     }
+
+    /// Collect the writes of the current thread into one db write batch, see
+    /// `DBStorage::begin_batch`. The cache is updated when the batch is written to the db. The cache
+    /// instance writes to the cache directly, so its writes can not be discarded.
+    pub fn begin_batch(&self) {
+        if let Some(db) = self.db() {
+            db.begin_batch();
+        }
+    }
+
+    pub fn commit_batch(&self) -> Result<()> {
+        match self {
+            StorageInstance::CACHE { cache: _ } => Ok(()),
+            StorageInstance::DB { db } => db.commit_batch().map(|_| ()),
+            StorageInstance::CacheAndDb { cache, db } => {
+                if let Some(batches) = db.commit_batch()? {
+                    for (prefix_name, batch) in batches {
+                        cache.write_batch_obj(prefix_name.as_str(), batch)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn discard_batch(&self) {
+        if let Some(db) = self.db() {
+            db.discard_batch();
+        }
+    }
+
+    pub fn detach_batch(&self) -> Option<PendingWriteBatch> {
+        self.db().and_then(|db| db.detach_batch())
+    }
+
+    pub fn attach_batch(&self, batch: PendingWriteBatch) -> Result<()> {
+        match self.db() {
+            Some(db) => db.attach_batch(batch),
+            None => Ok(()),
+        }
+    }
+
+    /// Read the pending writes of the batch of the `owner` thread on the current thread.
+    pub fn attach_batch_reader(&self, owner: ThreadId) {
+        if let Some(db) = self.db() {
+            db.attach_batch_reader(owner);
+        }
+    }
+
+    pub fn detach_batch_reader(&self) {
+        if let Some(db) = self.db() {
+            db.detach_batch_reader();
+        }
+    }
 }
 
 impl InnerStore for StorageInstance {
@@ -117,6 +172,10 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.get(prefix_name, key),
             StorageInstance::DB { db } => db.get(prefix_name, key),
             StorageInstance::CacheAndDb { cache, db } => {
+                // the pending writes are not in the cache yet.
+                if let Some(value) = db.get_pending(prefix_name, key.as_slice()) {
+                    return Ok(value);
+                }
                 // first get from cache
                 if let Ok(Some(cache_obj)) = cache.get_obj(prefix_name, key.clone()) {
                     match cache_obj {
@@ -144,9 +203,13 @@ impl InnerStore for StorageInstance {
         match self {
             StorageInstance::CACHE { cache } => cache.put(prefix_name, key, value),
             StorageInstance::DB { db } => db.put(prefix_name, key, value),
-            StorageInstance::CacheAndDb { cache, db } => db
-                .put(prefix_name, key.clone(), value.clone())
-                .and_then(|_| cache.put_obj(prefix_name, key, CacheObject::Value(value))),
+            StorageInstance::CacheAndDb { cache, db } => {
+                if db.is_batching() {
+                    return db.put(prefix_name, key, value);
+                }
+                db.put(prefix_name, key.clone(), value.clone())
+                    .and_then(|_| cache.put_obj(prefix_name, key, CacheObject::Value(value)))
+            }
         }
     }
 
@@ -155,6 +218,9 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.contains_key(prefix_name, key),
             StorageInstance::DB { db } => db.contains_key(prefix_name, key),
             StorageInstance::CacheAndDb { cache, db } => {
+                if let Some(value) = db.get_pending(prefix_name, key.as_slice()) {
+                    return Ok(value.is_some());
+                }
                 match cache.get_obj(prefix_name, key.clone()) {
                     Ok(Some(cache_obj)) => match cache_obj {
                         CacheObject::Value(_value) => Ok(true),
@@ -171,6 +237,9 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.remove(prefix_name, key),
             StorageInstance::DB { db } => db.remove(prefix_name, key),
             StorageInstance::CacheAndDb { cache, db } => {
+                if db.is_batching() {
+                    return db.remove(prefix_name, key);
+                }
                 match db.remove(prefix_name, key.clone()) {
                     Ok(_) => cache.remove(prefix_name, key),
                     _ => bail!("db storage remove error."),
@@ -184,6 +253,9 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.write_batch(prefix_name, batch),
             StorageInstance::DB { db } => db.write_batch(prefix_name, batch),
             StorageInstance::CacheAndDb { cache, db } => {
+                if db.is_batching() {
+                    return db.write_batch(prefix_name, batch);
+                }
                 match db.write_batch(prefix_name, batch.clone()) {
                     Ok(_) => cache.write_batch_obj(prefix_name, batch),
                     Err(err) => bail!("write batch db error: {}", err),
@@ -236,6 +308,10 @@ where
             cf: PhantomData,
         }
     }
+
+    pub fn instance(&self) -> &StorageInstance {
+        &self.instance
+    }
 }
 
 impl<CF> KVStore for InnerStorage<CF>
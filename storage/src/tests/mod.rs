@@ -1,6 +1,12 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 mod test_accumulator;
+mod test_backup;
 mod test_batch;
 mod test_block;
+mod test_cold;
+mod test_encryption;
+mod test_migration;
+mod test_repair;
 mod test_storage;
+mod test_verifier;
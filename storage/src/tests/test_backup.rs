@@ -0,0 +1,116 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backup::{backup, list_backups, restore};
+use crate::cache_storage::CacheStorage;
+use crate::db_storage::DBStorage;
+use crate::storage::StorageInstance;
+use crate::{BlockStore, Storage};
+use anyhow::Result;
+use starcoin_config::RocksdbConfig;
+use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use starcoin_types::startup_info::StartupInfo;
+use std::path::Path;
+
+fn open_storage(dir: &Path) -> Result<Storage> {
+    Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(),
+        DBStorage::new(dir, RocksdbConfig::default())?,
+    ))
+}
+
+fn commit_head(storage: &Storage) -> Result<Block> {
+    let block = Block::new(BlockHeader::random(), BlockBody::new_empty());
+    storage.commit_block(block.clone())?;
+    storage.save_startup_info(StartupInfo::new(block.id()))?;
+    Ok(block)
+}
+
+#[test]
+fn test_backup_and_restore() -> Result<()> {
+    let db_dir = starcoin_config::temp_path();
+    let backup_dir = starcoin_config::temp_path();
+    let storage = open_storage(db_dir.path())?;
+
+    let block1 = commit_head(&storage)?;
+    storage.db().unwrap().flush_all()?;
+    let backup1 = backup(&storage, backup_dir.path())?;
+    assert_eq!(backup1.id, 1);
+    assert_eq!(backup1.block_hash, block1.id());
+    assert!(backup1.new_bytes > 0);
+
+    let block2 = commit_head(&storage)?;
+    let backup2 = backup(&storage, backup_dir.path())?;
+    assert_eq!(backup2.id, 2);
+    assert_eq!(backup2.block_hash, block2.id());
+    // the sst files of the first backup are shared.
+    assert!(backup2.new_bytes < backup2.size());
+    assert_eq!(list_backups(backup_dir.path())?.len(), 2);
+
+    let restore_dir = starcoin_config::temp_path();
+    let restore_db_dir = restore_dir.path().join("starcoindb");
+    restore(backup_dir.path(), 1, restore_db_dir.as_path())?;
+    {
+        let restored = open_storage(restore_dir.path())?;
+        assert_eq!(restored.get_startup_info()?.unwrap().main, block1.id());
+        assert!(restored.get_block(block2.id())?.is_none());
+    }
+    // the db dir must not exist.
+    assert!(restore(backup_dir.path(), 2, restore_db_dir.as_path()).is_err());
+
+    let file = backup2
+        .files
+        .iter()
+        .find(|file| file.path.starts_with("2/"))
+        .unwrap();
+    std::fs::write(backup_dir.path().join(file.path.as_str()), b"corrupted")?;
+    let restore_dir = starcoin_config::temp_path();
+    assert!(restore(
+        backup_dir.path(),
+        2,
+        restore_dir.path().join("starcoindb").as_path()
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_restore_and_roll_forward() -> Result<()> {
+    let db_dir = starcoin_config::temp_path();
+    let backup_dir = starcoin_config::temp_path();
+    let storage = open_storage(db_dir.path())?;
+    let block1 = commit_head(&storage)?;
+    backup(&storage, backup_dir.path())?;
+    let block2 = commit_head(&storage)?;
+    backup(&storage, backup_dir.path())?;
+
+    // roll forward a restored old backup, its new sst files may have the same names as the sst
+    // files of the later backups.
+    let restore_dir = starcoin_config::temp_path();
+    restore(
+        backup_dir.path(),
+        1,
+        restore_dir.path().join("starcoindb").as_path(),
+    )?;
+    let block3 = {
+        let restored = open_storage(restore_dir.path())?;
+        let block3 = commit_head(&restored)?;
+        restored.db().unwrap().flush_all()?;
+        let backup3 = backup(&restored, backup_dir.path())?;
+        assert_eq!(backup3.id, 3);
+        assert_eq!(backup3.block_hash, block3.id());
+        block3
+    };
+
+    let restore_dir = starcoin_config::temp_path();
+    restore(
+        backup_dir.path(),
+        3,
+        restore_dir.path().join("starcoindb").as_path(),
+    )?;
+    let restored = open_storage(restore_dir.path())?;
+    assert_eq!(restored.get_startup_info()?.unwrap().main, block3.id());
+    assert!(restored.get_block(block1.id())?.is_some());
+    assert!(restored.get_block(block2.id())?.is_none());
+    Ok(())
+}
@@ -4,8 +4,9 @@
 use crate::batch::WriteBatch;
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
-use crate::storage::{CodecWriteBatch, InnerStore, ValueCodec};
-use crate::DEFAULT_PREFIX_NAME;
+use crate::storage::{CodecWriteBatch, InnerStore, StorageInstance, ValueCodec};
+use crate::{BLOCK_INFO_PREFIX_NAME, DEFAULT_PREFIX_NAME};
+use anyhow::Result;
 use crypto::HashValue;
 use starcoin_config::RocksdbConfig;
 use starcoin_types::transaction::{BlockTransactionInfo, TransactionInfo};
@@ -150,3 +151,94 @@ fn test_batch_comm() {
     let result = db.write_batch(DEFAULT_PREFIX_NAME, new_batch2);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_pending_write_batch() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let instance = StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default())?,
+    );
+    let db = instance.db().unwrap();
+    let (key1, key2) = (HashValue::random().to_vec(), HashValue::random().to_vec());
+
+    instance.begin_batch();
+    instance.put(DEFAULT_PREFIX_NAME, key1.clone(), vec![1])?;
+    assert_eq!(
+        instance.get(DEFAULT_PREFIX_NAME, key1.clone())?,
+        Some(vec![1])
+    );
+    assert_eq!(db.get_raw(DEFAULT_PREFIX_NAME, key1.clone())?, None);
+
+    // The writes of a discarded nested batch are dropped.
+    instance.begin_batch();
+    instance.put(DEFAULT_PREFIX_NAME, key1.clone(), vec![2])?;
+    instance.remove(DEFAULT_PREFIX_NAME, key1.clone())?;
+    assert_eq!(instance.get(DEFAULT_PREFIX_NAME, key1.clone())?, None);
+    instance.discard_batch();
+    assert_eq!(
+        instance.get(DEFAULT_PREFIX_NAME, key1.clone())?,
+        Some(vec![1])
+    );
+
+    // A committed nested batch is merged into the outer batch.
+    instance.begin_batch();
+    let mut batch = WriteBatch::new();
+    batch.put(key2.clone(), vec![3])?;
+    instance.write_batch(BLOCK_INFO_PREFIX_NAME, batch)?;
+    instance.commit_batch()?;
+    assert_eq!(db.get_raw(BLOCK_INFO_PREFIX_NAME, key2.clone())?, None);
+
+    // The pending writes are only readable by the other threads attached to the batch.
+    let owner = std::thread::current().id();
+    let other_db = db.clone();
+    let other_key = key2.clone();
+    let (value, attached_value) = std::thread::spawn(move || -> Result<_> {
+        let value = other_db.get(BLOCK_INFO_PREFIX_NAME, other_key.clone())?;
+        other_db.attach_batch_reader(owner);
+        let attached_value = other_db.get(BLOCK_INFO_PREFIX_NAME, other_key)?;
+        other_db.detach_batch_reader();
+        Ok((value, attached_value))
+    })
+    .join()
+    .unwrap()?;
+    assert_eq!(value, None);
+    assert_eq!(attached_value, Some(vec![3]));
+
+    let batch = instance.detach_batch().unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(instance.get(DEFAULT_PREFIX_NAME, key1.clone())?, None);
+    // A detached batch is still open until it is attached again or dropped.
+    assert!(db.has_open_batch());
+    instance.begin_batch();
+    drop(instance.detach_batch());
+    assert!(db.has_open_batch());
+    let batch = std::thread::spawn(move || -> Result<_> {
+        db.attach_batch(batch)?;
+        Ok(db.detach_batch())
+    })
+    .join()
+    .unwrap()?
+    .unwrap();
+    instance.attach_batch(batch)?;
+    instance.commit_batch()?;
+    let db = instance.db().unwrap();
+    assert!(!db.is_batching());
+    assert!(!db.has_open_batch());
+    assert_eq!(
+        db.get_raw(DEFAULT_PREFIX_NAME, key1.clone())?,
+        Some(vec![1])
+    );
+    assert_eq!(
+        db.get_raw(BLOCK_INFO_PREFIX_NAME, key2.clone())?,
+        Some(vec![3])
+    );
+    assert_eq!(
+        instance
+            .cache()
+            .unwrap()
+            .get(BLOCK_INFO_PREFIX_NAME, key2)?,
+        Some(vec![3])
+    );
+    Ok(())
+}
@@ -0,0 +1,47 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cold::ColdStorageMigrator;
+use crate::db_storage::DBStorage;
+use crate::storage::{InnerStore, StorageInstance};
+use crate::tests::test_verifier::commit_chain;
+use crate::{BlockStore, Storage, BLOCK_HEADER_PREFIX_NAME, BLOCK_PREFIX_NAME};
+use anyhow::Result;
+use starcoin_config::RocksdbConfig;
+use std::sync::Arc;
+
+#[test]
+fn test_move_to_cold_db() -> Result<()> {
+    let hot_dir = starcoin_config::temp_path();
+    let cold_dir = starcoin_config::temp_path();
+    let db = Arc::new(
+        DBStorage::new(hot_dir.path(), RocksdbConfig::default())?
+            .with_cold_db(DBStorage::new(cold_dir.path(), RocksdbConfig::default())?),
+    );
+    let storage = Arc::new(Storage::new(StorageInstance::DB { db: db.clone() })?);
+    let blocks = commit_chain(&storage, 4)?;
+    let migrator = ColdStorageMigrator::new(storage.clone());
+    assert_eq!(migrator.cold_block_number()?, None);
+
+    assert_eq!(migrator.migrate(1, 10)?, Some(1));
+    assert_eq!(migrator.cold_block_number()?, Some(1));
+    assert_eq!(migrator.migrate(1, 10)?, None);
+    assert_eq!(db.iter(BLOCK_PREFIX_NAME)?.count(), 2);
+    // the headers are always kept in the hot db.
+    assert_eq!(db.iter(BLOCK_HEADER_PREFIX_NAME)?.count(), 4);
+    for block in blocks.iter() {
+        assert_eq!(storage.get_block(block.id())?, Some(block.clone()));
+    }
+
+    assert_eq!(migrator.migrate(10, 1)?, Some(2));
+    assert_eq!(migrator.migrate(10, 10)?, Some(3));
+    assert_eq!(db.iter(BLOCK_PREFIX_NAME)?.count(), 0);
+    assert_eq!(storage.get_block(blocks[3].id())?, Some(blocks[3].clone()));
+
+    db.remove(BLOCK_PREFIX_NAME, blocks[0].id().to_vec())?;
+    assert_eq!(storage.get_block(blocks[0].id())?, None);
+    assert!(db
+        .move_to_cold(BLOCK_HEADER_PREFIX_NAME, vec![blocks[0].id().to_vec()])
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cold::ColdStorageMigrator;
+use crate::db_storage::{value_aad, DBStorage};
+use crate::storage::{InnerStore, StorageInstance};
+use crate::tests::test_verifier::commit_chain;
+use crate::{BlockStore, Storage, BLOCK_HEADER_PREFIX_NAME, BLOCK_PREFIX_NAME};
+use anyhow::Result;
+use bcs_ext::BCSCodec;
+use starcoin_config::RocksdbConfig;
+use std::sync::Arc;
+
+#[test]
+fn test_encrypted_db() -> Result<()> {
+    let key = [1u8; 32];
+    let dir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new_with_encryption(
+        dir.path(),
+        RocksdbConfig::default(),
+        Some(key),
+    )?);
+    assert!(db.is_encrypted());
+    let storage = Arc::new(Storage::new(StorageInstance::DB { db: db.clone() })?);
+    let blocks = commit_chain(&storage, 3)?;
+    let block_key = blocks[1].id().to_vec();
+    let plain = blocks[1].encode()?;
+    assert_eq!(
+        db.get(BLOCK_PREFIX_NAME, block_key.clone())?,
+        Some(plain.clone())
+    );
+    let raw = db.get_raw(BLOCK_PREFIX_NAME, block_key.clone())?.unwrap();
+    assert_ne!(raw, plain);
+    assert_eq!(
+        starcoin_decrypt::decrypt_with_key(
+            &key,
+            value_aad(BLOCK_PREFIX_NAME, block_key.as_slice()).as_slice(),
+            raw.as_slice()
+        )?,
+        plain
+    );
+    // the encrypted value is bound to its column family and key.
+    assert!(starcoin_decrypt::decrypt_with_key(
+        &key,
+        value_aad(BLOCK_PREFIX_NAME, blocks[2].id().to_vec().as_slice()).as_slice(),
+        raw.as_slice()
+    )
+    .is_err());
+    assert!(starcoin_decrypt::decrypt_with_key(
+        &key,
+        value_aad(BLOCK_HEADER_PREFIX_NAME, block_key.as_slice()).as_slice(),
+        raw.as_slice()
+    )
+    .is_err());
+    for item in db.iter(BLOCK_PREFIX_NAME)? {
+        let (key, value) = item?;
+        assert!(blocks
+            .iter()
+            .any(|block| block.id().to_vec() == key && block.encode().unwrap() == value));
+    }
+    drop(storage);
+    drop(db);
+
+    assert!(DBStorage::new(dir.path(), RocksdbConfig::default()).is_err());
+    assert!(
+        DBStorage::new_with_encryption(dir.path(), RocksdbConfig::default(), Some([2u8; 32]))
+            .is_err()
+    );
+    let db = DBStorage::new_with_encryption(dir.path(), RocksdbConfig::default(), Some(key))?;
+    let storage = Storage::new(StorageInstance::new_db_instance(db))?;
+    for block in blocks.iter() {
+        assert_eq!(storage.get_block(block.id())?, Some(block.clone()));
+    }
+
+    let plain_dir = starcoin_config::temp_path();
+    drop(DBStorage::new(plain_dir.path(), RocksdbConfig::default())?);
+    assert!(
+        DBStorage::new_with_encryption(plain_dir.path(), RocksdbConfig::default(), Some(key))
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_move_to_encrypted_cold_db() -> Result<()> {
+    let hot_dir = starcoin_config::temp_path();
+    let cold_dir = starcoin_config::temp_path();
+    let key = Some([1u8; 32]);
+    let db = Arc::new(
+        DBStorage::new_with_encryption(hot_dir.path(), RocksdbConfig::default(), key)?
+            .with_cold_db(DBStorage::new_with_encryption(
+                cold_dir.path(),
+                RocksdbConfig::default(),
+                key,
+            )?),
+    );
+    let storage = Arc::new(Storage::new(StorageInstance::DB { db })?);
+    let blocks = commit_chain(&storage, 3)?;
+    let migrator = ColdStorageMigrator::new(storage.clone());
+    assert_eq!(migrator.migrate(1, 10)?, Some(1));
+    for block in blocks.iter() {
+        assert_eq!(storage.get_block(block.id())?, Some(block.clone()));
+    }
+    Ok(())
+}
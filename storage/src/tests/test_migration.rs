@@ -0,0 +1,136 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chain_info::ChainInfoStorage;
+use crate::db_storage::DBStorage;
+use crate::migration::{
+    check_storage_version, migrate, migrate_on_start, read_storage_version, MigrationContext,
+    StorageVersion,
+};
+use crate::storage::{InnerStore, StorageInstance};
+use crate::{BlockStore, Storage, DEFAULT_PREFIX_NAME, FAILED_BLOCK_PREFIX_NAME};
+use anyhow::Result;
+use crypto::HashValue;
+use starcoin_config::RocksdbConfig;
+use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use std::sync::Arc;
+
+fn new_db() -> Result<(starcoin_config::DataDirPath, Arc<DBStorage>)> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path(), RocksdbConfig::default())?);
+    Ok((tmpdir, db))
+}
+
+/// Init a db as the legacy storage, which has the genesis but no version.
+fn init_legacy_db(db: &Arc<DBStorage>) -> Result<ChainInfoStorage> {
+    let chain_info = ChainInfoStorage::new(StorageInstance::DB { db: db.clone() });
+    chain_info.save_genesis(HashValue::random())?;
+    Ok(chain_info)
+}
+
+#[test]
+fn test_storage_version() -> Result<()> {
+    let (_tmpdir, db) = new_db()?;
+    let chain_info = ChainInfoStorage::new(StorageInstance::DB { db: db.clone() });
+    assert_eq!(read_storage_version(&chain_info)?, None);
+    chain_info.save_genesis(HashValue::random())?;
+    assert_eq!(read_storage_version(&chain_info)?, Some(StorageVersion::V1));
+    assert!(check_storage_version(&chain_info).is_err());
+
+    let (_tmpdir, db) = new_db()?;
+    let storage = Storage::new(StorageInstance::DB { db })?;
+    storage.check_storage_version()?;
+    storage.save_genesis(HashValue::random())?;
+    storage.check_storage_version()?;
+    Ok(())
+}
+
+#[test]
+fn test_migrate_failed_block() -> Result<()> {
+    let (_tmpdir, db) = new_db()?;
+    let chain_info = init_legacy_db(&db)?;
+    let storage = Storage::new(StorageInstance::DB { db: db.clone() })?;
+    let block = Block::new(BlockHeader::random(), BlockBody::new_empty());
+    storage.save_failed_block(block.id(), block.clone(), None, "test".to_string())?;
+    let invalid_id = HashValue::random();
+    db.put(FAILED_BLOCK_PREFIX_NAME, invalid_id.to_vec(), vec![1, 2, 3])?;
+
+    let report = migrate(db.clone(), true, &|_| {})?;
+    assert_eq!(report.from, Some(StorageVersion::V1));
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(report.steps[0].stats.scanned, 2);
+    assert_eq!(report.steps[0].stats.deleted, 1);
+    // dry run does not change the db.
+    assert!(db
+        .get(FAILED_BLOCK_PREFIX_NAME, invalid_id.to_vec())?
+        .is_some());
+    assert_eq!(read_storage_version(&chain_info)?, Some(StorageVersion::V1));
+
+    let report = migrate(db.clone(), false, &|_| {})?;
+    assert_eq!(report.steps[0].stats.deleted, 1);
+    assert!(db
+        .get(FAILED_BLOCK_PREFIX_NAME, invalid_id.to_vec())?
+        .is_none());
+    assert!(storage.get_failed_block_by_id(block.id())?.is_some());
+    assert_eq!(
+        read_storage_version(&chain_info)?,
+        Some(StorageVersion::current_version())
+    );
+    check_storage_version(&chain_info)?;
+
+    // migrate again does nothing.
+    let report = migrate(db, false, &|_| {})?;
+    assert!(report.steps.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_migrate_on_start() -> Result<()> {
+    let (_tmpdir, db) = new_db()?;
+    // a fresh db is not migrated.
+    assert!(migrate_on_start(db)?.is_none());
+
+    let (_tmpdir, db) = new_db()?;
+    let chain_info = init_legacy_db(&db)?;
+    db.put(
+        FAILED_BLOCK_PREFIX_NAME,
+        HashValue::random().to_vec(),
+        vec![1],
+    )?;
+    let report = migrate_on_start(db.clone())?.unwrap();
+    assert_eq!(report.from, Some(StorageVersion::V1));
+    assert_eq!(report.steps[0].stats.deleted, 1);
+    check_storage_version(&chain_info)?;
+    // the migrated db is not migrated again.
+    assert!(migrate_on_start(db)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_rewrite_cf() -> Result<()> {
+    let (_tmpdir, db) = new_db()?;
+    for i in 0u8..10 {
+        db.put(DEFAULT_PREFIX_NAME, vec![i], vec![i])?;
+    }
+    let ctx = MigrationContext::new(db.clone(), StorageVersion::V2, false, &|_| {});
+    let stats = ctx.rewrite_cf(DEFAULT_PREFIX_NAME, |key, value| {
+        Ok(match key[0] {
+            // delete
+            0 => None,
+            // re-key
+            1 => Some((vec![100], value.to_vec())),
+            // re-encode
+            2 => Some((key.to_vec(), vec![200])),
+            _ => Some((key.to_vec(), value.to_vec())),
+        })
+    })?;
+    assert_eq!(stats.scanned, 10);
+    assert_eq!(stats.updated, 2);
+    assert_eq!(stats.deleted, 1);
+    assert!(db.get(DEFAULT_PREFIX_NAME, vec![0])?.is_none());
+    assert!(db.get(DEFAULT_PREFIX_NAME, vec![1])?.is_none());
+    assert_eq!(db.get(DEFAULT_PREFIX_NAME, vec![100])?, Some(vec![1]));
+    assert_eq!(db.get(DEFAULT_PREFIX_NAME, vec![2])?, Some(vec![200]));
+    assert_eq!(db.get(DEFAULT_PREFIX_NAME, vec![3])?, Some(vec![3]));
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_storage::DBStorage;
+use crate::repair::StorageRepairer;
+use crate::storage::{InnerStore, StorageInstance};
+use crate::tests::test_verifier::commit_chain;
+use crate::verifier::StorageVerifier;
+use crate::{
+    BlockInfoStore, BlockStore, Storage, BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME,
+    BLOCK_TRANSACTIONS_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+};
+use anyhow::Result;
+use starcoin_config::RocksdbConfig;
+use std::sync::Arc;
+
+#[test]
+fn test_repair() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path(), RocksdbConfig::default())?);
+    let storage = Arc::new(Storage::new(StorageInstance::DB { db: db.clone() })?);
+    let blocks = commit_chain(&storage, 5)?;
+    let verifier = StorageVerifier::new(storage.clone(), true);
+    let repairer = StorageRepairer::new(storage.clone());
+
+    // The derived entries are rebuilt.
+    db.remove(BLOCK_TRANSACTIONS_PREFIX_NAME, blocks[1].id().to_vec())?;
+    db.put(
+        BLOCK_INFO_PREFIX_NAME,
+        blocks[2].id().to_vec(),
+        vec![1, 2, 3],
+    )?;
+    db.remove(BLOCK_HEADER_PREFIX_NAME, blocks[3].id().to_vec())?;
+    assert!(!verifier.verify(0, &|_| {})?.issues.is_empty());
+    let report = repairer.repair(None, &|_| {})?;
+    assert_eq!(report.repaired_blocks, 5);
+    assert!(report.damaged_block.is_none());
+    assert_eq!(report.head_hash, blocks[4].id());
+    let report = verifier.verify(0, &|_| {})?;
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+    // The chain is truncated to the parent of the block whose primary data is damaged.
+    db.remove(BLOCK_TRANSACTION_INFOS_PREFIX_NAME, blocks[3].id().to_vec())?;
+    let report = repairer.repair(Some(2), &|_| {})?;
+    assert_eq!(report.repaired_blocks, 1);
+    assert_eq!(report.damaged_block.unwrap().block_number, 3);
+    assert_eq!(report.head_number, 2);
+    assert_eq!(report.head_hash, blocks[2].id());
+    assert_eq!(storage.get_startup_info()?.unwrap().main, blocks[2].id());
+    // The truncated blocks are executed again when they are synced.
+    for block in &blocks[3..] {
+        assert!(storage.get_block_info(block.id())?.is_none());
+        assert!(storage
+            .block_storage
+            .get_transaction_info_ids(block.id())?
+            .is_none());
+    }
+    let report = verifier.verify(0, &|_| {})?;
+    assert_eq!(report.head_hash, blocks[2].id());
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    Ok(())
+}
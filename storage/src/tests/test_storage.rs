@@ -3,12 +3,13 @@
 
 extern crate chrono;
 
+use crate::cache_storage::mmap_cache::MmapCache;
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
 use crate::storage::{CodecKVStore, InnerStore, StorageInstance, ValueCodec, CACHE_NONE_OBJECT};
 use crate::{
-    BlockTransactionInfoStore, Storage, DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
-    VEC_PREFIX_NAME,
+    BlockTransactionInfoStore, Storage, BLOCK_PREFIX_NAME, DEFAULT_PREFIX_NAME,
+    STATE_NODE_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME, VEC_PREFIX_NAME,
 };
 use anyhow::Result;
 use crypto::HashValue;
@@ -218,3 +219,30 @@ fn test_missing_key_handle() -> Result<()> {
     assert_eq!(contains, false);
     Ok(())
 }
+
+#[test]
+fn test_cache_tiers() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let mmap_cache = MmapCache::open(tmpdir.path().join("state_cache").as_path(), 1 << 20)?;
+    let cache = CacheStorage::new_with_tiers(2, 2, 0, Some(mmap_cache));
+    let keys: Vec<_> = (0..3).map(|_| HashValue::random().to_vec()).collect();
+    for key in &keys {
+        cache.put(STATE_NODE_PREFIX_NAME, key.clone(), key.clone())?;
+    }
+    // the blocks share the default cache, the state nodes do not evict them.
+    cache.put(BLOCK_PREFIX_NAME, keys[0].clone(), keys[0].clone())?;
+    assert_eq!(cache.get_len()?, 3);
+    // the evicted state nodes are moved to the second tier.
+    for key in &keys {
+        assert_eq!(
+            cache.get(STATE_NODE_PREFIX_NAME, key.clone())?,
+            Some(key.clone())
+        );
+    }
+    cache.remove(STATE_NODE_PREFIX_NAME, keys[0].clone())?;
+    cache.remove(STATE_NODE_PREFIX_NAME, keys[1].clone())?;
+    assert!(!cache.contains_key(STATE_NODE_PREFIX_NAME, keys[0].clone())?);
+    assert!(!cache.contains_key(STATE_NODE_PREFIX_NAME, keys[1].clone())?);
+    assert!(cache.contains_key(BLOCK_PREFIX_NAME, keys[0].clone())?);
+    Ok(())
+}
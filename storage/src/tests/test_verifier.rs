@@ -0,0 +1,115 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_storage::DBStorage;
+use crate::storage::{InnerStore, StorageInstance};
+use crate::verifier::{IssueKind, StorageVerifier};
+use crate::{
+    BlockInfoStore, BlockStore, Storage, Store, BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME,
+};
+use anyhow::Result;
+use crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use crypto::HashValue;
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_config::RocksdbConfig;
+use starcoin_types::block::{Block, BlockBody, BlockHeaderBuilder, BlockInfo};
+use starcoin_types::startup_info::StartupInfo;
+use starcoin_uint::U256;
+use std::sync::Arc;
+
+/// Commit a main chain of empty blocks, return the blocks.
+pub(crate) fn commit_chain(storage: &Storage, len: u64) -> Result<Vec<Block>> {
+    let block_accumulator =
+        MerkleAccumulator::new_empty(storage.get_accumulator_store(AccumulatorStoreType::Block));
+    let txn_accumulator = MerkleAccumulator::new_empty(
+        storage.get_accumulator_store(AccumulatorStoreType::Transaction),
+    );
+    let mut blocks: Vec<Block> = vec![];
+    for number in 0..len {
+        let body = BlockBody::new_empty();
+        let header = BlockHeaderBuilder::random()
+            .with_number(number)
+            .with_parent_hash(
+                blocks
+                    .last()
+                    .map(|block| block.id())
+                    .unwrap_or_else(HashValue::zero),
+            )
+            .with_parent_block_accumulator_root(block_accumulator.root_hash())
+            .with_accumulator_root(txn_accumulator.append(&[])?)
+            .with_state_root(*SPARSE_MERKLE_PLACEHOLDER_HASH)
+            .with_difficulty(U256::from(1))
+            .with_body_hash(body.hash())
+            .build();
+        let block = Block::new(header, body);
+        block_accumulator.append(&[block.id()])?;
+        block_accumulator.flush()?;
+        storage.commit_block(block.clone())?;
+        storage.save_block_transaction_ids(block.id(), vec![])?;
+        storage.save_block_txn_info_ids(block.id(), vec![])?;
+        storage.save_block_info(BlockInfo::new(
+            block.id(),
+            U256::from(number + 1),
+            txn_accumulator.get_info(),
+            block_accumulator.get_info(),
+        ))?;
+        storage.save_startup_info(StartupInfo::new(block.id()))?;
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+#[test]
+fn test_verify_and_quarantine() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path(), RocksdbConfig::default())?);
+    let storage = Arc::new(Storage::new(StorageInstance::DB { db: db.clone() })?);
+    let blocks = commit_chain(&storage, 4)?;
+    let verifier = StorageVerifier::new(storage.clone(), true);
+
+    let report = verifier.verify(0, &|_| {})?;
+    assert_eq!(report.head_number, 3);
+    assert_eq!(report.verified_blocks, 4);
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert_eq!(verifier.verify(2, &|_| {})?.verified_blocks, 2);
+
+    db.put(
+        BLOCK_INFO_PREFIX_NAME,
+        blocks[2].id().to_vec(),
+        vec![1, 2, 3],
+    )?;
+    db.remove(BLOCK_HEADER_PREFIX_NAME, blocks[3].id().to_vec())?;
+    let report = verifier.verify(0, &|_| {})?;
+    assert_eq!(report.first_bad_block(), Some(2));
+    assert_eq!(report.bad_block_ids(), vec![blocks[2].id(), blocks[3].id()]);
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| issue.block_number == 2 && issue.kind == IssueKind::Corrupt));
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| issue.block_number == 3 && issue.kind == IssueKind::Missing));
+
+    let quarantine_dir = starcoin_config::temp_path();
+    let quarantined = verifier
+        .quarantine(&report, quarantine_dir.path())?
+        .unwrap();
+    assert_eq!(quarantined.head_number, 1);
+    assert_eq!(quarantined.head_hash, blocks[1].id());
+    assert!(storage.get_block(blocks[2].id())?.is_none());
+    assert!(quarantine_dir
+        .path()
+        .join(blocks[2].id().to_hex())
+        .join(BLOCK_INFO_PREFIX_NAME)
+        .exists());
+
+    let report = verifier.verify(0, &|_| {})?;
+    assert_eq!(report.head_hash, blocks[1].id());
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert!(verifier
+        .quarantine(&report, quarantine_dir.path())?
+        .is_none());
+    Ok(())
+}
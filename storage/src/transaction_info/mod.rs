@@ -61,6 +61,23 @@ impl TransactionInfoHashStorage {
         }
         self.write_batch(batch)
     }
+
+    /// Remove the txn info id from the ids of the `txn_hash`, and the `txn_hash` if no id left.
+    pub(crate) fn remove_transaction_info_id(
+        &self,
+        txn_hash: HashValue,
+        txn_info_id: HashValue,
+    ) -> Result<(), Error> {
+        if let Some(mut id_vec) = self.get(txn_hash)? {
+            id_vec.retain(|id| *id != txn_info_id);
+            if id_vec.is_empty() {
+                self.remove(txn_hash)?;
+            } else {
+                self.put(txn_hash, id_vec)?;
+            }
+        }
+        Ok(())
+    }
 }
 impl TransactionInfoStorage {
     pub(crate) fn get_transaction_info(
@@ -0,0 +1,753 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline consistency verification of the db.
+//!
+//! The verifier walks the main chain by the block accumulator of the head block. For every block it
+//! recomputes the block and transaction accumulator roots, the event roots and the hashes of the
+//! state tree nodes, and cross-checks the block, block info, transaction info and txn hash index
+//! entries. The missing or corrupt entries are collected into a `VerifyReport` instead of failing.
+
+use crate::storage::{InnerStore, KeyCodec};
+use crate::{
+    BlockInfoStore, BlockStore, BlockTransactionInfoStore, ContractEventStore, Storage, Store,
+    TransactionStore, BLOCK_BLOOM_PREFIX_NAME, BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME,
+    BLOCK_INFO_PREFIX_NAME, BLOCK_PREFIX_NAME, BLOCK_TRANSACTIONS_PREFIX_NAME,
+    BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+};
+use anyhow::{bail, format_err, Result};
+use crypto::hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use crypto::HashValue;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::RawKey;
+use logger::prelude::*;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::inmemory::InMemoryAccumulator;
+use starcoin_accumulator::node::AccumulatorStoreType;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_state_store_api::StateNodeStore;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::block::{Block, BlockInfo, BlockNumber};
+use starcoin_types::identifier::Identifier;
+use starcoin_types::language_storage::StructTag;
+use starcoin_types::startup_info::StartupInfo;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The progress callback is called every `PROGRESS_BLOCKS` blocks.
+const PROGRESS_BLOCKS: u64 = 10000;
+
+/// The max count of the verified state nodes remembered, so the subtrees shared by the states of the
+/// blocks are not verified again. An evicted subtree is verified again when it is reached.
+const VERIFIED_STATE_NODES_CACHE_SIZE: usize = 1 << 20;
+
+/// The column families keyed by block id, which are moved out of the db on quarantine.
+const BLOCK_PREFIX_NAMES: [&str; 7] = [
+    BLOCK_PREFIX_NAME,
+    BLOCK_HEADER_PREFIX_NAME,
+    BLOCK_BODY_PREFIX_NAME,
+    BLOCK_INFO_PREFIX_NAME,
+    BLOCK_TRANSACTIONS_PREFIX_NAME,
+    BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+    BLOCK_BLOOM_PREFIX_NAME,
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum IssueKind {
+    /// The entry does not exist.
+    Missing,
+    /// The entry can not be read or decoded.
+    Corrupt,
+    /// The entry is not consistent with the recomputed value or with the other entries.
+    Mismatch,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VerifyIssue {
+    pub block_number: BlockNumber,
+    /// None if the block id can not be read from the block accumulator.
+    pub block_id: Option<HashValue>,
+    pub kind: IssueKind,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub head_number: BlockNumber,
+    pub head_hash: HashValue,
+    pub from_block: BlockNumber,
+    /// The blocks whose state and transaction infos have been pruned, their state is not verified.
+    pub pruned_block_number: Option<BlockNumber>,
+    pub verified_blocks: u64,
+    pub verified_txns: u64,
+    /// The count of the state node verifications, a node may be verified more than once.
+    pub verified_state_nodes: u64,
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// The number of the first main chain block with issues.
+    pub fn first_bad_block(&self) -> Option<BlockNumber> {
+        self.issues.iter().map(|issue| issue.block_number).min()
+    }
+
+    /// The ids of the blocks with issues, in block number order.
+    pub fn bad_block_ids(&self) -> Vec<HashValue> {
+        let mut ids = vec![];
+        for issue in &self.issues {
+            if let Some(id) = issue.block_id {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuarantineReport {
+    /// The quarantined blocks, the entries of every block are moved to `<dir>/<block_id>/`.
+    pub blocks: Vec<HashValue>,
+    /// The new head block, the parent of the first bad block.
+    pub head_number: BlockNumber,
+    pub head_hash: HashValue,
+}
+
+pub struct StorageVerifier {
+    storage: Arc<Storage>,
+    verify_state: bool,
+}
+
+impl StorageVerifier {
+    /// If `verify_state` is false, only the existence of the state roots is checked, instead of
+    /// walking the whole state trees.
+    pub fn new(storage: Arc<Storage>, verify_state: bool) -> Self {
+        Self {
+            storage,
+            verify_state,
+        }
+    }
+
+    /// Verify the main chain blocks from `from_block` to the head.
+    pub fn verify(
+        &self,
+        from_block: BlockNumber,
+        progress: &dyn Fn(&VerifyReport),
+    ) -> Result<VerifyReport> {
+        let head_id = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info is none, the db is not initialized."))?
+            .main;
+        let head_info = self
+            .storage
+            .get_block_info(head_id)?
+            .ok_or_else(|| format_err!("The block info of the head block {} is none.", head_id))?;
+        let accumulator = MerkleAccumulator::new_with_info(
+            head_info.block_accumulator_info,
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Block),
+        );
+        let head_number = accumulator.num_leaves().saturating_sub(1);
+        if from_block > head_number {
+            bail!(
+                "The from block {} is after the head block {}",
+                from_block,
+                head_number
+            );
+        }
+        let mut report = VerifyReport {
+            head_number,
+            head_hash: head_id,
+            from_block,
+            pruned_block_number: self.storage.chain_info_storage.get_pruned_block_number()?,
+            verified_blocks: 0,
+            verified_txns: 0,
+            verified_state_nodes: 0,
+            issues: vec![],
+        };
+        let mut parent = if from_block == 0 {
+            None
+        } else {
+            let parent_id = accumulator
+                .get_leaf(from_block - 1)?
+                .ok_or_else(|| format_err!("Main chain block {} is none.", from_block - 1))?;
+            let parent_info = self
+                .storage
+                .get_block_info(parent_id)?
+                .ok_or_else(|| format_err!("The block info of block {} is none.", parent_id))?;
+            Some((parent_id, parent_info))
+        };
+        let mut state_nodes = VerifiedStateNodes::new(VERIFIED_STATE_NODES_CACHE_SIZE);
+        for number in from_block..=head_number {
+            // The genesis block is never pruned.
+            let pruned = number > 0
+                && report
+                    .pruned_block_number
+                    .map_or(false, |pruned| number <= pruned);
+            let mut checker = BlockChecker {
+                number,
+                block_id: None,
+                issues: &mut report.issues,
+            };
+            parent = self.verify_block(
+                &accumulator,
+                number,
+                pruned,
+                parent.as_ref(),
+                &mut state_nodes,
+                &mut report.verified_txns,
+                &mut checker,
+            );
+            report.verified_blocks += 1;
+            report.verified_state_nodes = state_nodes.verified;
+            if report.verified_blocks % PROGRESS_BLOCKS == 0 {
+                progress(&report);
+            }
+        }
+        info!(
+            "Verified blocks [{}, {}], {} issues found.",
+            from_block,
+            head_number,
+            report.issues.len()
+        );
+        Ok(report)
+    }
+
+    /// Verify a main chain block, return the block id and info as the parent of the next block,
+    /// or None if they are unavailable.
+    fn verify_block(
+        &self,
+        accumulator: &MerkleAccumulator,
+        number: BlockNumber,
+        pruned: bool,
+        parent: Option<&(HashValue, BlockInfo)>,
+        state_nodes: &mut VerifiedStateNodes,
+        verified_txns: &mut u64,
+        checker: &mut BlockChecker,
+    ) -> Option<(HashValue, BlockInfo)> {
+        let block_id = checker.load("block accumulator leaf", accumulator.get_leaf(number))?;
+        checker.block_id = Some(block_id);
+
+        let block = checker.load("block", self.storage.get_block(block_id));
+        let header = checker.load(
+            "block header",
+            self.storage.get_block_header_by_hash(block_id),
+        );
+        let body = checker.load("block body", self.storage.get_body(block_id));
+        if let Some(block) = &block {
+            checker.ensure(
+                block.id() == block_id,
+                format!("the recomputed block id is {}", block.id()),
+            );
+            checker.ensure(
+                block.header().number() == number,
+                format!("the block number is {}", block.header().number()),
+            );
+            if let Some((parent_id, _)) = parent {
+                checker.ensure(
+                    block.header().parent_hash() == *parent_id,
+                    format!(
+                        "the parent hash {} is not the previous block {}",
+                        block.header().parent_hash(),
+                        parent_id
+                    ),
+                );
+            }
+            if let Some(header) = &header {
+                checker.ensure(
+                    header == block.header(),
+                    "the block header is not the header of the block".to_string(),
+                );
+            }
+            if let Some(body) = &body {
+                checker.ensure(
+                    body.hash() == block.header().body_hash(),
+                    format!(
+                        "the recomputed body hash is {}, the header body hash is {}",
+                        body.hash(),
+                        block.header().body_hash()
+                    ),
+                );
+            }
+        }
+
+        let block_info = checker.load("block info", self.storage.get_block_info(block_id));
+        if let Some(block_info) = &block_info {
+            checker.ensure(
+                block_info.block_id == block_id,
+                format!("the block id of the block info is {}", block_info.block_id),
+            );
+            let parent_info = parent.map(|(_, info)| info);
+            self.verify_block_accumulator(block_id, block_info, parent_info, checker);
+            if let Some(block) = &block {
+                if let Some(parent_info) = parent_info {
+                    checker.ensure(
+                        parent_info.total_difficulty + block.header().difficulty()
+                            == block_info.total_difficulty,
+                        format!(
+                            "the total difficulty {} is not the parent's plus the block's",
+                            block_info.total_difficulty
+                        ),
+                    );
+                }
+                let parent_root = match parent_info {
+                    Some(info) => Some(info.block_accumulator_info.accumulator_root),
+                    None if number == 0 => Some(*ACCUMULATOR_PLACEHOLDER_HASH),
+                    None => None,
+                };
+                if let Some(parent_root) = parent_root {
+                    checker.ensure(
+                        block.header().block_accumulator_root() == parent_root,
+                        format!(
+                            "the block accumulator root of the header is {}, the parent's is {}",
+                            block.header().block_accumulator_root(),
+                            parent_root
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(block) = &block {
+            *verified_txns += self.verify_transactions(
+                number,
+                pruned,
+                block,
+                block_info.as_ref(),
+                parent.map(|(_, info)| info),
+                checker,
+            );
+            if !pruned {
+                self.verify_state(block.header().state_root(), state_nodes, checker);
+                for uncle in block.uncles().unwrap_or_default() {
+                    self.verify_state(uncle.state_root(), state_nodes, checker);
+                }
+            }
+        }
+        block_info.map(|info| (block_id, info))
+    }
+
+    fn new_accumulator(
+        &self,
+        store_type: AccumulatorStoreType,
+        parent_info: Option<&BlockInfo>,
+    ) -> MerkleAccumulator {
+        let store = self.storage.get_accumulator_store(store_type);
+        match parent_info {
+            Some(info) => MerkleAccumulator::new_with_info(
+                match store_type {
+                    AccumulatorStoreType::Block => info.block_accumulator_info.clone(),
+                    AccumulatorStoreType::Transaction => info.txn_accumulator_info.clone(),
+                },
+                store,
+            ),
+            None => MerkleAccumulator::new_empty(store),
+        }
+    }
+
+    fn verify_block_accumulator(
+        &self,
+        block_id: HashValue,
+        block_info: &BlockInfo,
+        parent_info: Option<&BlockInfo>,
+        checker: &mut BlockChecker,
+    ) {
+        if checker.number > 0 && parent_info.is_none() {
+            return;
+        }
+        // The appended nodes are kept in memory and never flushed.
+        let accumulator = self.new_accumulator(AccumulatorStoreType::Block, parent_info);
+        match accumulator.append(&[block_id]) {
+            Ok(root) => {
+                let info = &block_info.block_accumulator_info;
+                checker.ensure(
+                    root == info.accumulator_root && info.num_leaves == checker.number + 1,
+                    format!(
+                        "the recomputed block accumulator root is {}, the block info's is {} with {} leaves",
+                        root, info.accumulator_root, info.num_leaves
+                    ),
+                );
+            }
+            Err(e) => checker.push(
+                IssueKind::Corrupt,
+                format!("can not recompute the block accumulator: {}", e),
+            ),
+        }
+    }
+
+    /// Verify the transactions, transaction infos and events of the block, return the count of
+    /// the verified transactions.
+    fn verify_transactions(
+        &self,
+        number: BlockNumber,
+        pruned: bool,
+        block: &Block,
+        block_info: Option<&BlockInfo>,
+        parent_info: Option<&BlockInfo>,
+        checker: &mut BlockChecker,
+    ) -> u64 {
+        let block_id = block.id();
+        let txn_ids = match checker.load(
+            "block txn ids",
+            self.storage.block_storage.get_transaction_ids(block_id),
+        ) {
+            Some(txn_ids) => txn_ids,
+            None => return 0,
+        };
+        for txn in block.transactions() {
+            checker.ensure(
+                txn_ids.contains(&txn.id()),
+                format!("the user txn {} is not in the block txn ids", txn.id()),
+            );
+        }
+        for txn_id in &txn_ids {
+            checker.load(
+                &format!("txn {}", txn_id),
+                self.storage.get_transaction(*txn_id),
+            );
+        }
+        if pruned {
+            return txn_ids.len() as u64;
+        }
+
+        let txn_info_ids = match checker.load(
+            "block txn info ids",
+            self.storage
+                .block_storage
+                .get_transaction_info_ids(block_id),
+        ) {
+            Some(txn_info_ids) => txn_info_ids,
+            None => return txn_ids.len() as u64,
+        };
+        checker.ensure(
+            txn_info_ids.len() == txn_ids.len(),
+            format!(
+                "{} txn infos for {} txns",
+                txn_info_ids.len(),
+                txn_ids.len()
+            ),
+        );
+        for (txn_info_id, txn_id) in txn_info_ids.iter().zip(txn_ids.iter()) {
+            let txn_info = match checker.load(
+                &format!("txn info {}", txn_info_id),
+                self.storage.get_transaction_info(*txn_info_id),
+            ) {
+                Some(txn_info) => txn_info,
+                None => continue,
+            };
+            checker.ensure(
+                txn_info.id() == *txn_info_id,
+                format!(
+                    "the recomputed id of txn info {} is {}",
+                    txn_info_id,
+                    txn_info.id()
+                ),
+            );
+            checker.ensure(
+                txn_info.block_id() == block_id,
+                format!(
+                    "the block id of txn info {} is {}",
+                    txn_info_id,
+                    txn_info.block_id()
+                ),
+            );
+            checker.ensure(
+                txn_info.transaction_hash() == *txn_id,
+                format!(
+                    "the txn of txn info {} is {}, not {}",
+                    txn_info_id,
+                    txn_info.transaction_hash(),
+                    txn_id
+                ),
+            );
+            let indexed = self
+                .storage
+                .get_transaction_info_ids_by_hash(txn_info.transaction_hash());
+            if let Some(indexed) = checker.load("txn hash index", indexed.map(Some)) {
+                checker.ensure(
+                    indexed.contains(txn_info_id),
+                    format!(
+                        "txn info {} is not in the txn hash index of txn {}",
+                        txn_info_id,
+                        txn_info.transaction_hash()
+                    ),
+                );
+            }
+            if let Some(events) = checker.load(
+                &format!("events of txn info {}", txn_info_id),
+                self.storage.get_contract_events(*txn_info_id),
+            ) {
+                let event_hashes: Vec<_> = events.iter().map(|e| e.crypto_hash()).collect();
+                let event_root =
+                    InMemoryAccumulator::from_leaves(event_hashes.as_slice()).root_hash();
+                checker.ensure(
+                    event_root == txn_info.event_root_hash(),
+                    format!(
+                        "the recomputed event root of txn info {} is {}",
+                        txn_info_id, event_root
+                    ),
+                );
+            }
+        }
+
+        if number > 0 && parent_info.is_none() {
+            return txn_ids.len() as u64;
+        }
+        let accumulator = self.new_accumulator(AccumulatorStoreType::Transaction, parent_info);
+        match accumulator.append(&txn_info_ids) {
+            Ok(root) => {
+                checker.ensure(
+                    root == block.header().txn_accumulator_root(),
+                    format!(
+                        "the recomputed txn accumulator root is {}, the header's is {}",
+                        root,
+                        block.header().txn_accumulator_root()
+                    ),
+                );
+                if let Some(block_info) = block_info {
+                    checker.ensure(
+                        root == block_info.txn_accumulator_info.accumulator_root,
+                        format!(
+                            "the recomputed txn accumulator root is {}, the block info's is {}",
+                            root, block_info.txn_accumulator_info.accumulator_root
+                        ),
+                    );
+                }
+            }
+            Err(e) => checker.push(
+                IssueKind::Corrupt,
+                format!("can not recompute the txn accumulator: {}", e),
+            ),
+        }
+        txn_ids.len() as u64
+    }
+
+    fn verify_state(
+        &self,
+        state_root: HashValue,
+        state_nodes: &mut VerifiedStateNodes,
+        checker: &mut BlockChecker,
+    ) {
+        if !self.verify_state {
+            if state_root != *SPARSE_MERKLE_PLACEHOLDER_HASH && !state_nodes.contains(&state_root) {
+                let node = StateNodeStore::get(self.storage.as_ref(), &state_root);
+                if checker
+                    .load(&format!("state root {}", state_root), node)
+                    .is_some()
+                {
+                    state_nodes.insert(state_root);
+                }
+            }
+            return;
+        }
+        for blob in self.verify_tree::<AccountAddress>(state_root, state_nodes, checker) {
+            let account_state = match AccountState::try_from(blob.as_slice()) {
+                Ok(account_state) => account_state,
+                Err(e) => {
+                    checker.push(
+                        IssueKind::Corrupt,
+                        format!("can not decode the account state: {}", e),
+                    );
+                    continue;
+                }
+            };
+            if let Some(code_root) = account_state.code_root() {
+                self.verify_tree::<Identifier>(code_root, state_nodes, checker);
+            }
+            self.verify_tree::<StructTag>(account_state.resource_root(), state_nodes, checker);
+        }
+    }
+
+    /// Verify the nodes of the tree which are not verified yet, return the blobs of the leaves.
+    fn verify_tree<K: RawKey>(
+        &self,
+        root: HashValue,
+        state_nodes: &mut VerifiedStateNodes,
+        checker: &mut BlockChecker,
+    ) -> Vec<Vec<u8>> {
+        let mut blobs = vec![];
+        let mut pending = vec![root];
+        while let Some(hash) = pending.pop() {
+            // The placeholder of the empty tree is not stored.
+            if hash == *SPARSE_MERKLE_PLACEHOLDER_HASH || state_nodes.contains(&hash) {
+                continue;
+            }
+            let node = StateNodeStore::get(self.storage.as_ref(), &hash);
+            let node = match checker.load(&format!("state node {}", hash), node) {
+                Some(node) => node,
+                None => continue,
+            };
+            let node = match Node::<K>::decode(&node.0) {
+                Ok(node) => node,
+                Err(e) => {
+                    checker.push(
+                        IssueKind::Corrupt,
+                        format!("can not decode state node {}: {}", hash, e),
+                    );
+                    continue;
+                }
+            };
+            if node.hash() != hash {
+                checker.push(
+                    IssueKind::Mismatch,
+                    format!(
+                        "the recomputed hash of state node {} is {}",
+                        hash,
+                        node.hash()
+                    ),
+                );
+                continue;
+            }
+            state_nodes.insert(hash);
+            match node {
+                Node::Internal(internal) => pending.extend(internal.all_child()),
+                Node::Leaf(leaf) => blobs.push(leaf.blob().as_ref().to_vec()),
+                Node::Null => {}
+            }
+        }
+        blobs
+    }
+
+    /// Move the entries of the bad blocks in the `report` to the `dir`, and reset the head to the
+    /// parent of the first bad block, so the node syncs the blocks from there again.
+    /// Return None if there are no bad blocks.
+    pub fn quarantine(
+        &self,
+        report: &VerifyReport,
+        dir: &Path,
+    ) -> Result<Option<QuarantineReport>> {
+        let first_bad = match report.first_bad_block() {
+            Some(number) => number,
+            None => return Ok(None),
+        };
+        if first_bad == 0 {
+            bail!("The genesis block is bad, please clean the data dir and sync again.");
+        }
+        let db = self
+            .storage
+            .db()
+            .ok_or_else(|| format_err!("Only the db storage can be quarantined."))?;
+        let head_info = self
+            .storage
+            .get_block_info(report.head_hash)?
+            .ok_or_else(|| format_err!("The block info of the head block is none."))?;
+        let accumulator = MerkleAccumulator::new_with_info(
+            head_info.block_accumulator_info,
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Block),
+        );
+        let head_hash = accumulator
+            .get_leaf(first_bad - 1)?
+            .ok_or_else(|| format_err!("Main chain block {} is none.", first_bad - 1))?;
+
+        let blocks = report.bad_block_ids();
+        for block_id in &blocks {
+            let block_dir = dir.join(block_id.to_hex());
+            fs::create_dir_all(block_dir.as_path())?;
+            let issues: Vec<_> = report
+                .issues
+                .iter()
+                .filter(|issue| issue.block_id.as_ref() == Some(block_id))
+                .collect();
+            fs::write(
+                block_dir.join("issues.json"),
+                serde_json::to_vec_pretty(&issues)?,
+            )?;
+            let key = block_id.encode_key()?;
+            for cf in BLOCK_PREFIX_NAMES.iter() {
+                // The quarantined values are kept encrypted if the db is encrypted.
+                if let Some(value) = db.get_raw(cf, key.clone())? {
+                    fs::write(block_dir.join(cf), value)?;
+                    db.remove(cf, key.clone())?;
+                }
+            }
+        }
+        self.storage
+            .save_startup_info(StartupInfo::new(head_hash))?;
+        info!(
+            "Quarantined {} blocks to {:?}, the head is reset to block {}.",
+            blocks.len(),
+            dir,
+            first_bad - 1
+        );
+        Ok(Some(QuarantineReport {
+            blocks,
+            head_number: first_bad - 1,
+            head_hash,
+        }))
+    }
+}
+
+/// The recently verified state nodes, the whole subtree of a node is verified with it.
+struct VerifiedStateNodes {
+    cache: LruCache<HashValue, ()>,
+    /// The count of the verifications.
+    verified: u64,
+}
+
+impl VerifiedStateNodes {
+    fn new(size: usize) -> Self {
+        Self {
+            cache: LruCache::new(size),
+            verified: 0,
+        }
+    }
+
+    fn contains(&mut self, hash: &HashValue) -> bool {
+        self.cache.get(hash).is_some()
+    }
+
+    fn insert(&mut self, hash: HashValue) {
+        self.cache.put(hash, ());
+        self.verified += 1;
+    }
+}
+
+/// Collects the issues of a block.
+struct BlockChecker<'a> {
+    number: BlockNumber,
+    block_id: Option<HashValue>,
+    issues: &'a mut Vec<VerifyIssue>,
+}
+
+impl<'a> BlockChecker<'a> {
+    fn push(&mut self, kind: IssueKind, detail: String) {
+        warn!(
+            "Block {} ({:?}) {:?}: {}",
+            self.number, self.block_id, kind, detail
+        );
+        self.issues.push(VerifyIssue {
+            block_number: self.number,
+            block_id: self.block_id,
+            kind,
+            detail,
+        });
+    }
+
+    fn ensure(&mut self, condition: bool, detail: String) {
+        if !condition {
+            self.push(IssueKind::Mismatch, detail);
+        }
+    }
+
+    /// Record a `Missing` issue if the entry is none, or a `Corrupt` issue if it can not be read.
+    fn load<T>(&mut self, what: &str, result: Result<Option<T>>) -> Option<T> {
+        match result {
+            Ok(Some(value)) => Some(value),
+            Ok(None) => {
+                self.push(IssueKind::Missing, format!("{} is missing", what));
+                None
+            }
+            Err(e) => {
+                self.push(IssueKind::Corrupt, format!("can not read {}: {}", what, e));
+                None
+            }
+        }
+    }
+}
@@ -192,6 +192,7 @@ impl SyncService {
                     self_ref.clone(),
                     network.clone(),
                     config.sync.max_retry_times(),
+                    config.sync.write_batch_blocks(),
                 )?;
 
                 self_ref.notify(SyncBeginEvent {
@@ -12,6 +12,7 @@ use network_api::PeerProvider;
 use starcoin_accumulator::{Accumulator, MerkleAccumulator};
 use starcoin_chain::{verifier::BasicVerifier, BlockChain};
 use starcoin_chain_api::{ChainReader, ChainWriter, ConnectBlockError, ExecutedBlock};
+use starcoin_storage::storage::PendingWriteBatch;
 use starcoin_sync_api::SyncTarget;
 use starcoin_types::block::{Block, BlockIdAndNumber, BlockInfo, BlockNumber};
 use starcoin_types::peer_info::PeerId;
@@ -187,6 +188,15 @@ pub struct BlockCollector<N, H> {
     event_handle: H,
     peer_provider: N,
     skip_pow_verify: bool,
+    /// The applied blocks are written to the db in one write batch every `write_batch_blocks` blocks.
+    write_batch_blocks: u64,
+    /// The blocks applied in the write batch.
+    batched_blocks: u64,
+    /// The write batch, detached from the thread between the collects.
+    write_batch: Option<PendingWriteBatch>,
+    /// The connected events of the blocks in the write batch, which are handled after the batch is
+    /// written, so the block connector reads the blocks from the db.
+    pending_events: Vec<BlockConnectedEvent>,
 }
 
 impl<N, H> BlockCollector<N, H>
@@ -201,6 +211,7 @@ where
         event_handle: H,
         peer_provider: N,
         skip_pow_verify: bool,
+        write_batch_blocks: u64,
     ) -> Self {
         Self {
             current_block_info,
@@ -209,6 +220,10 @@ where
             event_handle,
             peer_provider,
             skip_pow_verify,
+            write_batch_blocks,
+            batched_blocks: 0,
+            write_batch: None,
+            pending_events: vec![],
         }
     }
 
@@ -257,18 +272,37 @@ where
             Ok(())
         }
     }
-}
 
-impl<N, H> TaskResultCollector<SyncBlockData> for BlockCollector<N, H>
-where
-    N: PeerProvider + 'static,
-    H: BlockConnectedEventHandle + 'static,
-{
-    type Output = BlockChain;
+    fn handle_block_connected_event(&mut self, event: BlockConnectedEvent) {
+        if self.write_batch_blocks > 1 {
+            self.pending_events.push(event);
+        } else {
+            self.send_block_connected_event(event);
+        }
+    }
 
-    fn collect(&mut self, item: SyncBlockData) -> Result<CollectorState> {
+    fn send_block_connected_event(&mut self, event: BlockConnectedEvent) {
+        let block_id = event.block.id();
+        if let Err(e) = self.event_handle.handle(event) {
+            error!(
+                "Send BlockConnectedEvent error: {:?}, block_id: {}",
+                e, block_id
+            );
+        }
+    }
+
+    /// Write the write batch to the db, and handle the connected events of the batched blocks.
+    fn commit_write_batch(&mut self) -> Result<()> {
+        self.chain.get_storage().commit_write_batch()?;
+        self.batched_blocks = 0;
+        for event in std::mem::take(&mut self.pending_events) {
+            self.send_block_connected_event(event);
+        }
+        Ok(())
+    }
+
+    fn collect_block(&mut self, item: SyncBlockData) -> Result<CollectorState> {
         let (block, block_info, peer_id) = item.into();
-        let block_id = block.id();
         let timestamp = block.header().timestamp();
         let block_info = match block_info {
             Some(block_info) => {
@@ -289,12 +323,7 @@ where
                 let total_difficulty = block_info.get_total_difficulty();
                 // only try connect block when sync chain total_difficulty > node's current chain.
                 if total_difficulty > self.current_block_info.total_difficulty {
-                    if let Err(e) = self.event_handle.handle(BlockConnectedEvent { block }) {
-                        error!(
-                            "Send BlockConnectedEvent error: {:?}, block_id: {}",
-                            e, block_id
-                        );
-                    }
+                    self.handle_block_connected_event(BlockConnectedEvent { block });
                 }
                 block_info
             }
@@ -324,8 +353,42 @@ where
             Ok(CollectorState::Need)
         }
     }
+}
+
+impl<N, H> TaskResultCollector<SyncBlockData> for BlockCollector<N, H>
+where
+    N: PeerProvider + 'static,
+    H: BlockConnectedEventHandle + 'static,
+{
+    type Output = BlockChain;
+
+    fn collect(&mut self, item: SyncBlockData) -> Result<CollectorState> {
+        if self.write_batch_blocks <= 1 {
+            return self.collect_block(item);
+        }
+        let storage = self.chain.get_storage();
+        match self.write_batch.take() {
+            Some(batch) => storage.attach_write_batch(batch)?,
+            None => storage.begin_write_batch(),
+        }
+        let result = self.collect_block(item);
+        self.batched_blocks += 1;
+        // The batch is written on error too, the blocks applied before the failed block are kept.
+        if !matches!(result, Ok(CollectorState::Need))
+            || self.batched_blocks >= self.write_batch_blocks
+        {
+            self.commit_write_batch()?;
+        } else {
+            self.write_batch = storage.detach_write_batch();
+        }
+        result
+    }
 
-    fn finish(self) -> Result<Self::Output> {
+    fn finish(mut self) -> Result<Self::Output> {
+        if let Some(batch) = self.write_batch.take() {
+            self.chain.get_storage().attach_write_batch(batch)?;
+            self.commit_write_batch()?;
+        }
         Ok(self.chain)
     }
 }
@@ -80,10 +80,15 @@ where
         max_retry_times: u64,
         delay_milliseconds_on_error: u64,
         skip_pow_verify_when_sync: bool,
+        write_batch_blocks: u64,
     ) -> Result<(BlockChain, TaskHandle), TaskError> {
         let buffer_size = self.target.peers.len();
 
         let ancestor_block_info = self.ancestor_block_info().map_err(TaskError::BreakError)?;
+        let imported_block_number = self
+            .storage
+            .get_imported_block_number()
+            .map_err(TaskError::BreakError)?;
         let accumulator_sync_task = BlockAccumulatorSyncTask::new(
             // start_number is include, so start from ancestor.number + 1
             self.ancestor.number.saturating_add(1),
@@ -114,8 +119,13 @@ where
             self.custom_error_handle.clone(),
         )
         .and_then(move |(ancestor, accumulator), event_handle| {
-            let check_local_store =
-                ancestor_block_info.total_difficulty < current_block_info.total_difficulty;
+            // The local store also has the blocks after the head imported by
+            // `db import-chain --verify-only`.
+            let check_local_store = ancestor_block_info.total_difficulty
+                < current_block_info.total_difficulty
+                || imported_block_number
+                    .map(|number| number > ancestor.number)
+                    .unwrap_or(false);
 
             let block_sync_task = BlockSyncTask::new(
                 accumulator,
@@ -134,6 +144,7 @@ where
                 self.block_event_handle.clone(),
                 self.peer_provider.clone(),
                 skip_pow_verify_when_sync,
+                write_batch_blocks,
             );
             Ok(TaskGenerator::new(
                 block_sync_task,
@@ -11,7 +11,9 @@ use crate::tasks::{
 use crate::verified_rpc_client::RpcVerifyError;
 use anyhow::Context;
 use anyhow::{format_err, Result};
-use config::{BuiltinNetworkID, ChainNetwork};
+use config::{BuiltinNetworkID, ChainNetwork, RocksdbConfig};
+use executor::{peer_to_peer_txn_sent_as_association, DEFAULT_EXPIRATION_TIME};
+use forkable_jellyfish_merkle::PARALLEL_UPDATE_THRESHOLD;
 use futures::channel::mpsc::unbounded;
 use futures::future::BoxFuture;
 use futures::FutureExt;
@@ -19,6 +21,7 @@ use futures_timer::Delay;
 use logger::prelude::*;
 use network_api::{PeerId, PeerSelector, PeerStrategy};
 use pin_utils::core_reexport::time::Duration;
+use starcoin_account_api::AccountInfo;
 use starcoin_accumulator::accumulator_info::AccumulatorInfo;
 use starcoin_accumulator::tree_store::mock::MockAccumulatorStore;
 use starcoin_accumulator::{Accumulator, MerkleAccumulator};
@@ -27,7 +30,10 @@ use starcoin_chain_api::ChainReader;
 use starcoin_chain_mock::MockChain;
 use starcoin_crypto::HashValue;
 use starcoin_genesis::Genesis;
-use starcoin_storage::BlockStore;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
 use starcoin_sync_api::SyncTarget;
 use starcoin_types::peer_info::PeerInfo;
 use starcoin_types::{
@@ -71,6 +77,7 @@ pub async fn test_full_sync_new_node() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver_1).await;
     let branch = sync_task.await?;
@@ -100,6 +107,7 @@ pub async fn test_full_sync_new_node() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver_1).await;
     let branch = sync_task.await?;
@@ -116,6 +124,81 @@ pub async fn test_full_sync_new_node() -> Result<()> {
     Ok(())
 }
 
+#[stest::test(timeout = 240)]
+pub async fn test_full_sync_parallel_put_in_write_batch() -> Result<()> {
+    let net1 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let mut node1 = SyncNodeMocker::new(net1.clone(), 1, 0)?;
+    // Every block writes enough accounts for the state tree to be updated in parallel, the workers
+    // read the nodes of the previous blocks from the pending write batch.
+    let txn_count = PARALLEL_UPDATE_THRESHOLD as u64;
+    for i in 0..2 {
+        let txns = (0..txn_count)
+            .map(|j| {
+                let account = AccountInfo::random();
+                peer_to_peer_txn_sent_as_association(
+                    account.address,
+                    Some(account.public_key.authentication_key()),
+                    i * txn_count + j,
+                    10000,
+                    net1.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+                    &net1,
+                )
+            })
+            .collect::<Vec<_>>();
+        let block = node1.chain_mocker.produce_with_txns(txns)?;
+        assert_eq!(block.transactions().len() as u64, txn_count);
+        node1.chain_mocker.apply(block)?;
+    }
+    node1.produce_block(1)?;
+    let arc_node1 = Arc::new(node1);
+
+    let net2 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let tmpdir = config::temp_path();
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default())?,
+    ))?);
+    let (chain_info, _) = Genesis::init_and_check_storage(&net2, storage.clone(), tmpdir.path())?;
+    let chain = MockChain::new_with_storage(
+        net2.clone(),
+        storage.clone(),
+        chain_info.head().id(),
+        AccountInfo::random(),
+    )?;
+    let node2 = SyncNodeMocker::new_with_chain_selector(
+        PeerId::random(),
+        chain,
+        1,
+        0,
+        PeerSelector::new(vec![], PeerStrategy::default()),
+    );
+
+    let target = arc_node1.sync_target();
+    let current_block_header = node2.chain().current_header();
+    let (sender_1, receiver_1) = unbounded();
+    let (sender_2, _receiver_2) = unbounded();
+    let (sync_task, _task_handle, _task_event_counter) = full_sync_task(
+        current_block_header.id(),
+        target.clone(),
+        false,
+        net2.time_service(),
+        storage.clone(),
+        sender_1,
+        arc_node1.clone(),
+        sender_2,
+        DummyNetworkService::default(),
+        15,
+        10,
+    )?;
+    let join_handle = node2.process_block_connect_event(receiver_1).await;
+    let branch = sync_task.await?;
+    let node2 = join_handle.await;
+    assert_eq!(branch.current_header().id(), target.target_id.id());
+    assert_eq!(node2.chain().current_header().id(), target.target_id.id());
+    assert!(!storage.db().unwrap().has_open_batch());
+    Ok(())
+}
+
 #[stest::test]
 pub async fn test_sync_invalid_target() -> Result<()> {
     let net1 = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
@@ -148,6 +231,7 @@ pub async fn test_sync_invalid_target() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let _join_handle = node2.process_block_connect_event(receiver_1).await;
     let sync_result = sync_task.await;
@@ -186,6 +270,7 @@ pub async fn test_failed_block() -> Result<()> {
         sender,
         DummyNetworkService::default(),
         true,
+        1,
     );
     let header = BlockHeaderBuilder::random().with_number(1).build();
     let body = BlockBody::new(Vec::new(), None);
@@ -229,6 +314,7 @@ pub async fn test_full_sync_fork() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver).await;
     let branch = sync_task.await?;
@@ -260,6 +346,7 @@ pub async fn test_full_sync_fork() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver).await;
     let branch = sync_task.await?;
@@ -307,6 +394,7 @@ pub async fn test_full_sync_fork_from_genesis() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver).await;
     let branch = sync_task.await?;
@@ -359,6 +447,7 @@ pub async fn test_full_sync_continue() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver).await;
     let branch = sync_task.await?;
@@ -392,6 +481,7 @@ pub async fn test_full_sync_continue() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
 
     let join_handle = node2.process_block_connect_event(receiver).await;
@@ -442,6 +532,7 @@ pub async fn test_full_sync_cancel() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let join_handle = node2.process_block_connect_event(receiver).await;
     let sync_join_handle = tokio::task::spawn(sync_task);
@@ -883,6 +974,7 @@ async fn test_net_rpc_err() -> Result<()> {
         sender_2,
         DummyNetworkService::default(),
         15,
+        10,
     )?;
     let _join_handle = node2.process_block_connect_event(receiver).await;
     let sync_join_handle = tokio::task::spawn(sync_task);
@@ -0,0 +1,201 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A local cluster of nodes running in the current process, every node has its own data dir,
+//! ports and network key, and connects to the first node as seed.
+//! The data dirs are temporary, and removed after the cluster is stopped.
+
+use anyhow::{ensure, format_err, Result};
+use futures::executor::block_on;
+use starcoin_chain_api::ChainAsyncService;
+use starcoin_config::{BuiltinNetworkID, NodeConfig, StarcoinOpt};
+use starcoin_crypto::HashValue;
+use starcoin_executor::{build_transfer_from_association, Account, DEFAULT_EXPIRATION_TIME};
+use starcoin_logger::prelude::*;
+use starcoin_node::NodeHandle;
+use starcoin_node_api::node_service::NodeAsyncService;
+use starcoin_state_api::AccountStateReader;
+use starcoin_statedb::ChainStateDB;
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_vm_types::account_config::association_address;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+pub struct LocalCluster {
+    nodes: Vec<NodeHandle>,
+    auto_mine: bool,
+}
+
+impl LocalCluster {
+    /// Start a cluster of `node_count` nodes, and wait until all nodes are connected to the seed node.
+    /// Only the builtin test and dev networks are supported, whose association account key is known.
+    /// If `auto_mine` is false, blocks are only generated by `generate_block`, which is useful for tests.
+    pub fn start(
+        net: BuiltinNetworkID,
+        node_count: usize,
+        auto_mine: bool,
+        timeout: Duration,
+    ) -> Result<Self> {
+        ensure!(node_count > 0, "node count should be greater than 0");
+        ensure!(
+            net == BuiltinNetworkID::Test || net == BuiltinNetworkID::Dev,
+            "local cluster only supports test and dev network, but got {}",
+            net
+        );
+        let mut cluster = Self {
+            nodes: vec![],
+            auto_mine,
+        };
+        for i in 0..node_count {
+            let opt = StarcoinOpt {
+                net: Some(net.into()),
+                ..StarcoinOpt::default()
+            };
+            // the data dir of test and dev network is a temp dir by default,
+            // and the ports are allocated when the config is loaded,
+            // so load the config after the previous node started to avoid port conflict.
+            let mut config = NodeConfig::load_with_opt(&opt)?;
+            if let Some(seed) = cluster.nodes.first() {
+                config.network.seeds = vec![seed.config().network.self_address()].into();
+            }
+            let node =
+                starcoin_node::run_node(Arc::new(config)).map_err(|e| format_err!("{}", e))?;
+            if !auto_mine {
+                block_on(async { node.node_service().stop_pacemaker().await })?;
+            }
+            info!(
+                "Local cluster node {} started, peer id: {}, data dir: {:?}",
+                i,
+                node.config().network.self_peer_id(),
+                node.config().data_dir()
+            );
+            cluster.nodes.push(node);
+        }
+        cluster.wait_connected(timeout)?;
+        Ok(cluster)
+    }
+
+    pub fn nodes(&self) -> &[NodeHandle] {
+        self.nodes.as_slice()
+    }
+
+    /// The first node, which other nodes connect to.
+    pub fn seed_node(&self) -> &NodeHandle {
+        &self.nodes[0]
+    }
+
+    fn wait_connected(&self, timeout: Duration) -> Result<()> {
+        let seed_peer_id = self.seed_node().config().network.self_peer_id();
+        let start = Instant::now();
+        for node in self.nodes.iter().skip(1) {
+            let network = node.network();
+            while !block_on(network.is_connected(seed_peer_id.clone())) {
+                ensure!(
+                    start.elapsed() < timeout,
+                    "wait node {} connect to seed node timeout",
+                    node.config().network.self_peer_id()
+                );
+                sleep(Duration::from_millis(200));
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait until all nodes synced to the head block of the seed node.
+    pub fn wait_synced(&self, timeout: Duration) -> Result<()> {
+        let seed_head = block_on(self.seed_node().chain_service()?.main_head_header())?;
+        let start = Instant::now();
+        for node in self.nodes.iter().skip(1) {
+            let chain_service = node.chain_service()?;
+            while block_on(chain_service.main_head_header())?.number() < seed_head.number() {
+                ensure!(
+                    start.elapsed() < timeout,
+                    "wait node {} sync to block {} timeout",
+                    node.config().network.self_peer_id(),
+                    seed_head.number()
+                );
+                sleep(Duration::from_millis(200));
+            }
+        }
+        Ok(())
+    }
+
+    /// Transfer `amount` STC to the `address` from the association account,
+    /// the txn is submitted to the seed node, and packaged in the next block.
+    pub fn fund(
+        &self,
+        address: AccountAddress,
+        auth_key: Option<AuthenticationKey>,
+        amount: u128,
+    ) -> Result<HashValue> {
+        let node = self.seed_node();
+        let config = node.config();
+        let net = config.net();
+        let txpool = node.txpool();
+        let sequence_number = match txpool.next_sequence_number(association_address()) {
+            Some(sequence_number) => sequence_number,
+            None => self.chain_sequence_number(association_address())?,
+        };
+        let txn = build_transfer_from_association(
+            address,
+            auth_key,
+            sequence_number,
+            amount,
+            net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+            net,
+        );
+        let txn = txn.as_signed_user_txn()?.clone();
+        let txn_hash = txn.id();
+        txpool
+            .add_txns(vec![txn])
+            .pop()
+            .expect("txpool should return the result of every txn")?;
+        Ok(txn_hash)
+    }
+
+    /// Create `count` new accounts, fund each of them with `amount` STC, and generate a block
+    /// to package the funding txns if the cluster does not mine automatically.
+    pub fn fund_new_accounts(&self, count: usize, amount: u128) -> Result<Vec<Account>> {
+        let accounts = (0..count).map(|_| Account::new()).collect::<Vec<_>>();
+        for account in accounts.iter() {
+            self.fund(*account.address(), Some(account.auth_key()), amount)?;
+        }
+        if !self.auto_mine {
+            self.seed_node().generate_block()?;
+        }
+        Ok(accounts)
+    }
+
+    fn chain_sequence_number(&self, address: AccountAddress) -> Result<u64> {
+        let node = self.seed_node();
+        let storage = node.storage();
+        let head = block_on(node.chain_service()?.main_head_header())?;
+        let state_db = ChainStateDB::new(storage, Some(head.state_root()));
+        AccountStateReader::new(&state_db).get_sequence_number(address)
+    }
+
+    /// Stop all nodes, the seed node is stopped at last.
+    pub fn stop(mut self) -> Result<()> {
+        self.stop_nodes()
+    }
+
+    fn stop_nodes(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        while let Some(node) = self.nodes.pop() {
+            if let Err(e) = node.stop() {
+                error!("Stop local cluster node error: {:?}", e);
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+impl Drop for LocalCluster {
+    fn drop(&mut self) {
+        let _ = self.stop_nodes();
+    }
+}
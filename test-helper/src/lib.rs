@@ -2,19 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod chain;
+pub mod cluster;
 pub mod dao;
 pub mod dummy_network_service;
 pub mod executor;
 pub mod network;
 pub mod node;
 pub mod protest;
+pub mod reorg;
 pub mod txn;
 pub mod txpool;
 
 pub use chain::gen_blockchain_for_test;
+pub use cluster::LocalCluster;
 pub use dummy_network_service::DummyNetworkService;
 pub use network::{build_network, build_network_cluster, build_network_pair};
 pub use node::{run_node_by_config, run_test_node};
+pub use reorg::ReorgHarness;
 pub use starcoin_executor::Account;
 pub use starcoin_genesis::Genesis;
 pub use starcoin_node::NodeHandle;
@@ -0,0 +1,342 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A harness for end-to-end reorg tests, built on the `LocalCluster`.
+//! The competing branches are built from a fork point of the seed node's main chain and connected to
+//! the seed node as mined blocks, so the seed node switches its main chain to the heavier branch,
+//! and the other nodes follow the seed node by sync. The cluster does not mine automatically,
+//! main chain blocks are only generated by `generate_blocks`.
+//! Integrations can extend the scenarios in `tests/reorg_test.rs` by the assertion helpers here.
+
+use crate::cluster::LocalCluster;
+use anyhow::{ensure, format_err, Result};
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use starcoin_account_api::AccountInfo;
+use starcoin_chain::BlockChain;
+use starcoin_chain_api::ChainAsyncService;
+use starcoin_config::BuiltinNetworkID;
+use starcoin_consensus::Consensus;
+use starcoin_crypto::HashValue;
+use starcoin_executor::{build_transfer_from_association, Account, DEFAULT_EXPIRATION_TIME};
+use starcoin_node::NodeHandle;
+use starcoin_service_registry::bus::Bus;
+use starcoin_state_api::AccountStateReader;
+use starcoin_statedb::ChainStateDB;
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::block::{Block, BlockHeader};
+use starcoin_types::system_events::{MinedBlock, NewHeadBlock};
+use starcoin_types::transaction::SignedUserTransaction;
+use starcoin_vm_types::account_config::association_address;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// The max length of the branch built by `force_reorg`.
+const MAX_BRANCH_LENGTH: usize = 64;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct ReorgHarness {
+    cluster: LocalCluster,
+    miner: AccountInfo,
+    timeout: Duration,
+}
+
+impl ReorgHarness {
+    /// Start a test network cluster of `node_count` nodes without auto mining,
+    /// `timeout` is used by every wait of the harness.
+    pub fn start(node_count: usize, timeout: Duration) -> Result<Self> {
+        let cluster = LocalCluster::start(BuiltinNetworkID::Test, node_count, false, timeout)?;
+        Ok(Self {
+            cluster,
+            miner: AccountInfo::random(),
+            timeout,
+        })
+    }
+
+    pub fn cluster(&self) -> &LocalCluster {
+        &self.cluster
+    }
+
+    /// The node which the branches are connected to.
+    pub fn node(&self) -> &NodeHandle {
+        self.cluster.seed_node()
+    }
+
+    pub fn head(&self, node: &NodeHandle) -> Result<BlockHeader> {
+        block_on(node.chain_service()?.main_head_header())
+    }
+
+    /// Generate `count` blocks on the main chain of the seed node, the pending txns are packaged.
+    pub fn generate_blocks(&self, count: usize) -> Result<Vec<Block>> {
+        (0..count).map(|_| self.node().generate_block()).collect()
+    }
+
+    /// Build `count` transfer txns from the association account to new accounts, the sequence numbers
+    /// start from the association account's sequence number at block `parent_id`, so the txns can be
+    /// packaged in a branch from `parent_id`. The txns are not submitted.
+    pub fn association_transfer_txns(
+        &self,
+        parent_id: HashValue,
+        count: usize,
+        amount: u128,
+    ) -> Result<Vec<SignedUserTransaction>> {
+        let node = self.node();
+        let parent = block_on(node.chain_service()?.get_header_by_hash(&parent_id))?
+            .ok_or_else(|| format_err!("block {} not exists", parent_id))?;
+        let state_db = ChainStateDB::new(node.storage(), Some(parent.state_root()));
+        let sequence_number =
+            AccountStateReader::new(&state_db).get_sequence_number(association_address())?;
+        let config = node.config();
+        let net = config.net();
+        (0..count as u64)
+            .map(|i| {
+                let account = Account::new();
+                let txn = build_transfer_from_association(
+                    *account.address(),
+                    Some(account.auth_key()),
+                    sequence_number + i,
+                    amount,
+                    net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+                    net,
+                );
+                Ok(txn.as_signed_user_txn()?.clone())
+            })
+            .collect()
+    }
+
+    /// Build a block on `parent_id` with `txns`, the block is not connected.
+    pub fn build_block(
+        &self,
+        parent_id: HashValue,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Result<Block> {
+        let node = self.node();
+        let config = node.config();
+        let net = config.net();
+        let chain = BlockChain::new(net.time_service(), parent_id, node.storage())?;
+        let (template, excluded) = chain.create_block_template(
+            *self.miner.address(),
+            Some(self.miner.auth_key()),
+            None,
+            txns,
+            vec![],
+            None,
+        )?;
+        ensure!(
+            excluded.discarded_txns.is_empty() && excluded.untouched_txns.is_empty(),
+            "txns excluded from the branch block, discarded: {:?}, untouched: {:?}",
+            excluded.discarded_txns,
+            excluded.untouched_txns
+        );
+        chain
+            .consensus()
+            .create_block(template, net.time_service().as_ref())
+    }
+
+    /// Connect `block` to the seed node as a mined block, and wait until it is saved.
+    pub fn connect_block(&self, block: Block) -> Result<()> {
+        let node = self.node();
+        let block_id = block.id();
+        node.bus()?
+            .broadcast(MinedBlock(Arc::new(block)))
+            .map_err(|e| format_err!("broadcast mined block error: {:?}", e))?;
+        let chain_service = node.chain_service()?;
+        self.wait(|| Ok(block_on(chain_service.get_header_by_hash(&block_id))?.is_some()))
+            .map_err(|e| format_err!("wait block {} connected: {}", block_id, e))
+    }
+
+    /// Build and connect a branch of `count` blocks from `fork_point`, `txns` are packaged in the
+    /// first block. The seed node switches to the branch only if the branch is heavier.
+    pub fn fork(
+        &self,
+        fork_point: HashValue,
+        count: usize,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Result<Vec<Block>> {
+        let mut blocks = vec![];
+        let mut parent_id = fork_point;
+        let mut txns = Some(txns);
+        for _ in 0..count {
+            let block = self.build_block(parent_id, txns.take().unwrap_or_default())?;
+            parent_id = block.id();
+            self.connect_block(block.clone())?;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Extend a branch from `fork_point` block by block until the seed node switches its main chain
+    /// to the branch, returns the branch blocks. `txns` are packaged in the first block.
+    pub fn force_reorg(
+        &self,
+        fork_point: HashValue,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Result<Vec<Block>> {
+        let head = self.head(self.node())?;
+        ensure!(
+            head.id() != fork_point,
+            "the fork point {} is the head, no block to retract",
+            fork_point
+        );
+        let mut branch = self.fork(fork_point, 1, txns)?;
+        loop {
+            let tip = branch.last().expect("branch should not be empty").id();
+            if self.head(self.node())?.id() == tip {
+                return Ok(branch);
+            }
+            ensure!(
+                branch.len() < MAX_BRANCH_LENGTH,
+                "the node does not switch to the branch of {} blocks from {}",
+                branch.len(),
+                fork_point
+            );
+            branch.extend(self.fork(tip, 1, vec![])?);
+        }
+    }
+
+    /// Subscribe the new head notifications of `node`, which the RPC new block subscriptions are built on.
+    pub fn subscribe_new_heads(&self, node: &NodeHandle) -> Result<NewHeadRecorder> {
+        let receiver = block_on(node.bus()?.channel::<NewHeadBlock>())?;
+        Ok(NewHeadRecorder {
+            receiver,
+            timeout: self.timeout,
+        })
+    }
+
+    /// Wait until the head of every node is `block_id`.
+    pub fn wait_all_head(&self, block_id: HashValue) -> Result<()> {
+        for node in self.cluster.nodes() {
+            self.wait(|| Ok(self.head(node)?.id() == block_id))
+                .map_err(|e| {
+                    format_err!(
+                        "wait node {} head to {}: {}",
+                        node.config().network.self_peer_id(),
+                        block_id,
+                        e
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
+    /// The main chain block which includes the txn on `node`, None if the txn is not on the main chain.
+    pub fn txn_block(&self, node: &NodeHandle, txn_hash: HashValue) -> Result<Option<HashValue>> {
+        Ok(
+            block_on(node.chain_service()?.get_transaction_info(txn_hash))?
+                .map(|txn_info| txn_info.block_id()),
+        )
+    }
+
+    /// The main chain block at `number` on `node`.
+    pub fn main_block_id(&self, node: &NodeHandle, number: u64) -> Result<Option<HashValue>> {
+        Ok(
+            block_on(node.chain_service()?.main_block_header_by_number(number))?
+                .map(|header| header.id()),
+        )
+    }
+
+    /// Assert the txns of the retracted blocks are reinjected into the txpool of `node`.
+    pub fn assert_txns_reinjected(
+        &self,
+        node: &NodeHandle,
+        txn_hashes: &[HashValue],
+    ) -> Result<()> {
+        let txpool = node.txpool();
+        self.wait(|| {
+            Ok(txn_hashes
+                .iter()
+                .all(|hash| txpool.find_txn(hash).is_some()))
+        })
+        .map_err(|e| format_err!("wait txns {:?} reinjected: {}", txn_hashes, e))
+    }
+
+    /// Assert the txn index of `node` is rolled back to `expect`, None means the txn is not on the main chain.
+    pub fn assert_txn_index(
+        &self,
+        node: &NodeHandle,
+        txn_hash: HashValue,
+        expect: Option<HashValue>,
+    ) -> Result<()> {
+        let real = self.txn_block(node, txn_hash)?;
+        ensure!(
+            real == expect,
+            "the block of txn {} expect {:?}, but got {:?}",
+            txn_hash,
+            expect,
+            real
+        );
+        Ok(())
+    }
+
+    /// Assert the block number index of `node` points to the `blocks`.
+    pub fn assert_main_blocks(&self, node: &NodeHandle, blocks: &[Block]) -> Result<()> {
+        for block in blocks {
+            let real = self.main_block_id(node, block.header().number())?;
+            ensure!(
+                real == Some(block.id()),
+                "the main block at {} expect {}, but got {:?}",
+                block.header().number(),
+                block.id(),
+                real
+            );
+        }
+        Ok(())
+    }
+
+    fn wait<F>(&self, mut condition: F) -> Result<()>
+    where
+        F: FnMut() -> Result<bool>,
+    {
+        let start = Instant::now();
+        while !condition()? {
+            ensure!(
+                start.elapsed() < self.timeout,
+                "timeout after {:?}",
+                self.timeout
+            );
+            sleep(POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
+    pub fn stop(self) -> Result<()> {
+        self.cluster.stop()
+    }
+}
+
+/// Record the new head notifications of a node.
+pub struct NewHeadRecorder {
+    receiver: mpsc::UnboundedReceiver<NewHeadBlock>,
+    timeout: Duration,
+}
+
+impl NewHeadRecorder {
+    /// Wait until the new head `block_id` is notified, returns the ids of the new heads notified
+    /// before it and itself, in the notified order.
+    pub fn wait_for(&mut self, block_id: HashValue) -> Result<Vec<HashValue>> {
+        let start = Instant::now();
+        let mut heads = vec![];
+        loop {
+            match self.receiver.try_next() {
+                Ok(Some(NewHeadBlock(block))) => {
+                    let id = block.header().id();
+                    heads.push(id);
+                    if id == block_id {
+                        return Ok(heads);
+                    }
+                }
+                Ok(None) => return Err(format_err!("the new head subscription is closed")),
+                Err(_) => {
+                    ensure!(
+                        start.elapsed() < self.timeout,
+                        "wait new head {} timeout, notified: {:?}",
+                        block_id,
+                        heads
+                    );
+                    sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::executor::block_on;
+use starcoin_chain_api::ChainAsyncService;
+use starcoin_config::BuiltinNetworkID;
+use starcoin_state_api::AccountStateReader;
+use starcoin_statedb::ChainStateDB;
+use std::time::Duration;
+use test_helper::LocalCluster;
+
+#[stest::test(timeout = 120)]
+fn test_local_cluster() {
+    let timeout = Duration::from_secs(60);
+    let cluster = LocalCluster::start(BuiltinNetworkID::Test, 2, false, timeout).unwrap();
+    assert_eq!(cluster.nodes().len(), 2);
+    let amount = 1_000_000_000u128;
+    let accounts = cluster.fund_new_accounts(2, amount).unwrap();
+    cluster.wait_synced(timeout).unwrap();
+
+    let node = &cluster.nodes()[1];
+    let head = block_on(node.chain_service().unwrap().main_head_header()).unwrap();
+    let state_db = ChainStateDB::new(node.storage(), Some(head.state_root()));
+    let reader = AccountStateReader::new(&state_db);
+    for account in accounts {
+        assert_eq!(reader.get_balance(account.address()).unwrap(), Some(amount));
+    }
+    cluster.stop().unwrap();
+}
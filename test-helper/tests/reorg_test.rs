@@ -0,0 +1,100 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The reorg scenarios, run against a two nodes cluster, the seed node reorgs by the branches
+//! connected by the harness, and the other node follows it by sync.
+
+use starcoin_txpool_api::TxPoolSyncService;
+use std::time::Duration;
+use test_helper::ReorgHarness;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const AMOUNT: u128 = 1_000_000_000;
+
+#[stest::test(timeout = 180)]
+fn test_reorg_switch_to_heavier_branch() {
+    let harness = ReorgHarness::start(2, TIMEOUT).unwrap();
+    let fork_point = harness.head(harness.node()).unwrap().id();
+    let retracted = harness.generate_blocks(2).unwrap();
+    harness
+        .wait_all_head(retracted.last().unwrap().id())
+        .unwrap();
+
+    let mut new_heads = harness.subscribe_new_heads(harness.node()).unwrap();
+    let branch = harness.force_reorg(fork_point, vec![]).unwrap();
+    assert!(branch.len() >= retracted.len());
+    let tip = branch.last().unwrap().id();
+    let notified = new_heads.wait_for(tip).unwrap();
+    assert!(retracted
+        .iter()
+        .all(|block| !notified.contains(&block.id())));
+
+    harness.wait_all_head(tip).unwrap();
+    for node in harness.cluster().nodes() {
+        harness.assert_main_blocks(node, branch.as_slice()).unwrap();
+    }
+    harness.stop().unwrap();
+}
+
+#[stest::test(timeout = 180)]
+fn test_reorg_reinject_retracted_txns() {
+    let harness = ReorgHarness::start(2, TIMEOUT).unwrap();
+    let fork_point = harness.head(harness.node()).unwrap().id();
+    let txns = harness
+        .association_transfer_txns(fork_point, 2, AMOUNT)
+        .unwrap();
+    let txn_hashes = txns.iter().map(|txn| txn.id()).collect::<Vec<_>>();
+    let retracted = harness.fork(fork_point, 1, txns).unwrap();
+    let retracted_id = retracted[0].id();
+    assert_eq!(harness.head(harness.node()).unwrap().id(), retracted_id);
+    for txn_hash in txn_hashes.iter() {
+        harness
+            .assert_txn_index(harness.node(), *txn_hash, Some(retracted_id))
+            .unwrap();
+    }
+
+    let branch = harness.force_reorg(fork_point, vec![]).unwrap();
+    harness.wait_all_head(branch.last().unwrap().id()).unwrap();
+    harness
+        .assert_txns_reinjected(harness.node(), txn_hashes.as_slice())
+        .unwrap();
+    for node in harness.cluster().nodes() {
+        for txn_hash in txn_hashes.iter() {
+            harness.assert_txn_index(node, *txn_hash, None).unwrap();
+        }
+    }
+
+    // the reinjected txns are packaged again on the new main chain.
+    let block = harness.generate_blocks(1).unwrap().pop().unwrap();
+    for txn_hash in txn_hashes.iter() {
+        harness
+            .assert_txn_index(harness.node(), *txn_hash, Some(block.id()))
+            .unwrap();
+    }
+    harness.stop().unwrap();
+}
+
+#[stest::test(timeout = 180)]
+fn test_reorg_txn_moved_to_branch() {
+    let harness = ReorgHarness::start(2, TIMEOUT).unwrap();
+    let fork_point = harness.head(harness.node()).unwrap().id();
+    let txns = harness
+        .association_transfer_txns(fork_point, 1, AMOUNT)
+        .unwrap();
+    let txn_hash = txns[0].id();
+    let retracted = harness.fork(fork_point, 1, txns.clone()).unwrap();
+    harness
+        .assert_txn_index(harness.node(), txn_hash, Some(retracted[0].id()))
+        .unwrap();
+
+    // the same txn is packaged in the branch, so it is not reinjected.
+    let branch = harness.force_reorg(fork_point, txns).unwrap();
+    harness.wait_all_head(branch.last().unwrap().id()).unwrap();
+    for node in harness.cluster().nodes() {
+        harness
+            .assert_txn_index(node, txn_hash, Some(branch[0].id()))
+            .unwrap();
+    }
+    assert!(harness.node().txpool().find_txn(&txn_hash).is_none());
+    harness.stop().unwrap();
+}
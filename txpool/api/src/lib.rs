@@ -8,6 +8,7 @@ use starcoin_crypto::hash::HashValue;
 use starcoin_types::{
     account_address::AccountAddress, block::Block, transaction, transaction::SignedUserTransaction,
 };
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -23,6 +24,49 @@ pub struct TxPoolStatus {
     pub is_full: bool,
 }
 
+/// All the txns in the txpool, split by whether they are ready to be packaged.
+#[derive(Clone, Debug, Default)]
+pub struct TxPoolContent {
+    /// The txns ready to be packaged into a block.
+    pub pending: Vec<SignedUserTransaction>,
+    /// The txns not ready yet, such as waiting for the txns with smaller sequence number of the sender.
+    pub queued: Vec<SignedUserTransaction>,
+}
+
+impl TxPoolContent {
+    /// Keep the txns of at most `limit` senders after the `cursor` sender, in the order of sender
+    /// address. Returns the last kept sender as the cursor of the next page if more senders remain.
+    pub fn page(
+        self,
+        cursor: Option<AccountAddress>,
+        limit: usize,
+    ) -> (TxPoolContent, Option<AccountAddress>) {
+        let senders: BTreeSet<AccountAddress> = self
+            .pending
+            .iter()
+            .chain(self.queued.iter())
+            .map(|txn| txn.sender())
+            .filter(|sender| cursor.map_or(true, |cursor| *sender > cursor))
+            .collect();
+        let kept: BTreeSet<AccountAddress> = senders.iter().take(limit).cloned().collect();
+        let next_cursor = if senders.len() > kept.len() {
+            kept.iter().next_back().cloned()
+        } else {
+            None
+        };
+        let keep = |txns: Vec<SignedUserTransaction>| {
+            txns.into_iter()
+                .filter(|txn| kept.contains(&txn.sender()))
+                .collect()
+        };
+        let content = TxPoolContent {
+            pending: keep(self.pending),
+            queued: keep(self.queued),
+        };
+        (content, next_cursor)
+    }
+}
+
 pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
     fn add_txns(
         &self,
@@ -67,6 +111,10 @@ pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
         sender: &AccountAddress,
         max_len: Option<usize>,
     ) -> Vec<SignedUserTransaction>;
+
+    /// Get all txns in the pool, split into the pending and queued txns.
+    /// `now` is the current timestamp in secs, if it's None, it default to real world's current timestamp.
+    fn content(&self, now: Option<u64>) -> TxPoolContent;
 }
 
 #[derive(Clone, Debug)]
@@ -4,8 +4,9 @@
 use anyhow::Result;
 use crypto::hash::HashValue;
 use futures_channel::mpsc;
-use starcoin_txpool_api::{TxPoolStatus, TxPoolSyncService};
+use starcoin_txpool_api::{TxPoolContent, TxPoolStatus, TxPoolSyncService};
 use std::{
+    collections::HashMap,
     iter::Iterator,
     sync::{Arc, Mutex},
 };
@@ -16,6 +17,7 @@ use types::{
 #[derive(Clone, Default)]
 pub struct MockTxPoolService {
     pool: Arc<Mutex<Vec<SignedUserTransaction>>>,
+    rejected: Arc<Mutex<HashMap<HashValue, transaction::TransactionError>>>,
 }
 
 impl MockTxPoolService {
@@ -26,20 +28,32 @@ impl MockTxPoolService {
     pub fn new_with_txns(txns: Vec<SignedUserTransaction>) -> Self {
         MockTxPoolService {
             pool: Arc::new(Mutex::new(txns)),
+            ..Default::default()
         }
     }
+
+    /// Reject the txn of the hash by the `error` when it is added.
+    pub fn reject_txn(&self, txn_hash: HashValue, error: transaction::TransactionError) {
+        self.rejected.lock().unwrap().insert(txn_hash, error);
+    }
 }
 
 impl TxPoolSyncService for MockTxPoolService {
     fn add_txns(
         &self,
-        mut txns: Vec<SignedUserTransaction>,
+        txns: Vec<SignedUserTransaction>,
     ) -> Vec<Result<(), transaction::TransactionError>> {
-        let len = txns.len();
-        self.pool.lock().unwrap().append(&mut txns);
-        let mut results = vec![];
-        results.resize_with(len, || Ok(()));
-        results
+        let rejected = self.rejected.lock().unwrap();
+        let mut pool = self.pool.lock().unwrap();
+        txns.into_iter()
+            .map(|txn| match rejected.get(&txn.id()) {
+                Some(error) => Err(error.clone()),
+                None => {
+                    pool.push(txn);
+                    Ok(())
+                }
+            })
+            .collect()
     }
 
     /// Removes transaction from the pool.
@@ -101,6 +115,13 @@ impl TxPoolSyncService for MockTxPoolService {
     ) -> Vec<SignedUserTransaction> {
         todo!()
     }
+
+    fn content(&self, _now: Option<u64>) -> TxPoolContent {
+        TxPoolContent {
+            pending: self.pool.lock().unwrap().clone(),
+            queued: vec![],
+        }
+    }
 }
 
 #[cfg(test)]
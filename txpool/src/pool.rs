@@ -257,6 +257,9 @@ pub enum PendingOrdering {
     Priority,
     /// Get pending transactions without any care of particular ordering (cheaper).
     Unordered,
+    /// Get pending transactions grouped by gas price bands of the given width,
+    /// higher band first, and round-robin between senders within a band.
+    GasPriceBandRoundRobin(GasPrice),
 }
 
 /// Pending set query settings
@@ -15,14 +15,14 @@ use parking_lot::RwLock;
 use starcoin_txpool_api::TxPoolStatus;
 use std::{
     cmp,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fmt,
     sync::{
         atomic::{self, AtomicUsize},
         Arc,
     },
 };
-use tx_pool::{self, Verifier};
+use tx_pool::{self, VerifiedTransaction as _, Verifier};
 use types::{account_address::AccountAddress as Address, transaction};
 
 type Listener = (
@@ -359,6 +359,32 @@ impl TransactionQueue {
             .collect()
     }
 
+    /// Returns all transactions in the pool, split into the pending ones which are ready to be
+    /// packaged and the queued ones, both ordered by priority.
+    pub fn content<C>(
+        &self,
+        client: C,
+        current_timestamp: u64,
+    ) -> (
+        Vec<Arc<pool::VerifiedTransaction>>,
+        Vec<Arc<pool::VerifiedTransaction>>,
+    )
+    where
+        C: client::AccountSeqNumberClient,
+    {
+        let pool = self.pool.read();
+        let pending: Vec<_> = pool
+            .pending(Self::ready(client, u64::max_value(), current_timestamp))
+            .collect();
+        let pending_hashes: HashSet<HashValue> = pending.iter().map(|tx| *tx.hash()).collect();
+        // always ready
+        let queued = pool
+            .pending(Expiration::new(0))
+            .filter(|tx| !pending_hashes.contains(tx.hash()))
+            .collect();
+        (pending, queued)
+    }
+
     /// Returns current pending transactions ordered by priority.
     ///
     /// NOTE: This may return a cached version of pending transaction set.
@@ -391,6 +417,13 @@ impl TransactionQueue {
                 .take(max_len)
                 .collect(),
             PendingOrdering::Priority => self.pool.read().pending(ready).take(max_len).collect(),
+            PendingOrdering::GasPriceBandRoundRobin(gas_price_band) => {
+                round_robin_by_gas_price_band(
+                    self.pool.read().pending(ready),
+                    gas_price_band,
+                    max_len,
+                )
+            }
         }
     }
 
@@ -575,6 +608,76 @@ impl TransactionQueue {
     }
 }
 
+/// Reorder the `pending` transactions, which are ordered by priority, into gas price bands of
+/// `gas_price_band` width. Higher band is taken first, and transactions in the same band are
+/// taken by per-sender round-robin, so a single sender can not monopolize the block.
+/// The sequence number order of every sender's transactions is kept.
+pub(crate) fn round_robin_by_gas_price_band<I>(
+    pending: I,
+    gas_price_band: u64,
+    max_len: usize,
+) -> Vec<Arc<pool::VerifiedTransaction>>
+where
+    I: IntoIterator<Item = Arc<pool::VerifiedTransaction>>,
+{
+    let gas_price_band = cmp::max(gas_price_band, 1);
+    let band_of = |txn: &pool::VerifiedTransaction| txn.signed().gas_unit_price() / gas_price_band;
+
+    // senders keep the order of their best transaction.
+    // the pending is ordered by priority, so a sender comes after `max_len` senders can not be taken
+    // before the first transaction of each of them, and a sender provides at most `max_len` transactions.
+    let mut sender_indexes: HashMap<Address, usize> = HashMap::new();
+    let mut sender_txns: Vec<VecDeque<Arc<pool::VerifiedTransaction>>> = vec![];
+    for txn in pending {
+        let sender = txn.signed().sender();
+        let index = match sender_indexes.get(&sender) {
+            Some(index) => *index,
+            None => {
+                if sender_txns.len() >= max_len {
+                    break;
+                }
+                sender_indexes.insert(sender, sender_txns.len());
+                sender_txns.push(VecDeque::new());
+                sender_txns.len() - 1
+            }
+        };
+        if sender_txns[index].len() < max_len {
+            sender_txns[index].push_back(txn);
+        }
+    }
+
+    // only the first transaction of a sender can be taken, the band of it decides
+    // which band the sender is in.
+    let mut bands: BTreeMap<u64, BTreeSet<usize>> = BTreeMap::new();
+    for (index, txns) in sender_txns.iter().enumerate() {
+        if let Some(txn) = txns.front() {
+            bands.entry(band_of(txn)).or_default().insert(index);
+        }
+    }
+
+    let mut result = vec![];
+    // every round takes one transaction from each sender of the highest band,
+    // the senders are moved to the band of their next transaction for the later rounds.
+    while result.len() < max_len {
+        let band = match bands.keys().next_back() {
+            Some(band) => *band,
+            None => break,
+        };
+        let senders = bands.remove(&band).unwrap_or_default();
+        for index in senders {
+            if result.len() >= max_len {
+                break;
+            }
+            let txns = &mut sender_txns[index];
+            result.extend(txns.pop_front());
+            if let Some(txn) = txns.front() {
+                bands.entry(band_of(txn)).or_default().insert(index);
+            }
+        }
+    }
+    result
+}
+
 fn convert_error<H: fmt::Debug + fmt::LowerHex>(
     err: tx_pool::Error<H>,
 ) -> transaction::TransactionError {
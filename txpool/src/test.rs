@@ -1,9 +1,11 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::pool::AccountSeqNumberClient;
+use crate::pool::queue::round_robin_by_gas_price_band;
+use crate::pool::{AccountSeqNumberClient, VerifiedTransaction};
 use crate::TxStatus;
 use anyhow::Result;
+use crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use crypto::keygen::KeyGen;
 use network_api::messages::{PeerTransactionsMessage, TransactionsMessage};
 use network_api::PeerId;
@@ -17,6 +19,7 @@ use starcoin_open_block::OpenedBlock;
 use starcoin_state_api::ChainStateWriter;
 use starcoin_statedb::ChainStateDB;
 use starcoin_txpool_api::{TxPoolSyncService, TxnStatusFullEvent};
+use std::cmp;
 use std::time::Duration;
 use std::{collections::HashMap, sync::Arc};
 use stest::actix_export::time::delay_for;
@@ -24,8 +27,11 @@ use storage::BlockStore;
 use types::{
     account_address::{self, AccountAddress},
     account_config,
+    genesis_config::ChainId,
     transaction::authenticator::AuthenticationKey,
-    transaction::{SignedUserTransaction, Transaction, TransactionPayload},
+    transaction::{
+        RawUserTransaction, Script, SignedUserTransaction, Transaction, TransactionPayload,
+    },
     U256,
 };
 
@@ -95,6 +101,26 @@ async fn test_tx_pool() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+async fn test_txpool_content() -> Result<()> {
+    let (txpool_service, _storage, config, _, _) = test_helper::start_txpool().await;
+    let txn0 = generate_txn(config.clone(), 0);
+    // the txn with seq 1 is missing, so the txn with seq 2 is queued.
+    let txn2 = generate_txn(config, 2);
+    for result in txpool_service.add_txns(vec![txn0.clone(), txn2.clone()]) {
+        result?;
+    }
+    let content = txpool_service.content(Some(0));
+    assert_eq!(content.pending, vec![txn0]);
+    assert_eq!(content.queued, vec![txn2]);
+
+    // the expired txns are not pending.
+    let content = txpool_service.content(Some(2));
+    assert!(content.pending.is_empty());
+    assert_eq!(content.queued.len(), 2);
+    Ok(())
+}
+
 #[stest::test]
 async fn test_subscribe_txns() {
     let (pool, ..) = test_helper::start_txpool().await;
@@ -133,6 +159,101 @@ async fn test_pool_pending() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_round_robin_by_gas_price_band() {
+    let spammer = KeyGen::from_os_rng().generate_keypair();
+    let others = (0..2)
+        .map(|_| KeyGen::from_os_rng().generate_keypair())
+        .collect::<Vec<_>>();
+    let mut pending = vec![];
+    // the spammer's txns are ordered first by priority.
+    for seq in 0..4 {
+        pending.push(generate_verified_txn(&spammer, seq, 1));
+    }
+    for keypair in &others {
+        pending.push(generate_verified_txn(keypair, 0, 1));
+    }
+    let spammer_address = account_address::from_public_key(&spammer.1);
+
+    let packed = round_robin_by_gas_price_band(pending.clone(), 1, 3);
+    let senders = packed
+        .iter()
+        .map(|t| t.signed().sender())
+        .collect::<Vec<_>>();
+    assert_eq!(senders.len(), 3);
+    assert_eq!(senders[0], spammer_address);
+    assert!(!senders[1..].contains(&spammer_address));
+
+    // the sequence number order of a sender is kept.
+    let packed = round_robin_by_gas_price_band(pending.clone(), 1, usize::MAX);
+    assert_eq!(packed.len(), pending.len());
+    let spammer_seqs = packed
+        .iter()
+        .filter(|t| t.signed().sender() == spammer_address)
+        .map(|t| t.signed().sequence_number())
+        .collect::<Vec<_>>();
+    assert_eq!(spammer_seqs, vec![0, 1, 2, 3]);
+
+    // higher band is packed first.
+    let rich = KeyGen::from_os_rng().generate_keypair();
+    pending.insert(0, generate_verified_txn(&rich, 0, 10));
+    let packed = round_robin_by_gas_price_band(pending, 5, 2);
+    assert_eq!(
+        packed[0].signed().sender(),
+        account_address::from_public_key(&rich.1)
+    );
+    assert_eq!(packed[1].signed().sender(), spammer_address);
+}
+
+#[test]
+fn test_round_robin_by_gas_price_band_with_many_senders() {
+    let band_count = 5;
+    let txns_per_sender = 3;
+    let keypairs = (0..30)
+        .map(|_| KeyGen::from_os_rng().generate_keypair())
+        .collect::<Vec<_>>();
+    let band_of_sender = |index: usize| (band_count - 1 - index % band_count) as u64;
+    let mut senders = (0..keypairs.len()).collect::<Vec<_>>();
+    // the pending is ordered by priority, the higher band comes first.
+    senders.sort_by_key(|index| cmp::Reverse(band_of_sender(*index)));
+    let mut pending = vec![];
+    for index in &senders {
+        for seq in 0..txns_per_sender {
+            pending.push(generate_verified_txn(
+                &keypairs[*index],
+                seq,
+                band_of_sender(*index) * 10 + 1,
+            ));
+        }
+    }
+
+    // every band is packed round by round, a round takes one txn of each sender in the band.
+    let mut expected = vec![];
+    for band in (0..band_count as u64).rev() {
+        for seq in 0..txns_per_sender {
+            for index in senders
+                .iter()
+                .filter(|index| band_of_sender(**index) == band)
+            {
+                expected.push((account_address::from_public_key(&keypairs[*index].1), seq));
+            }
+        }
+    }
+    let order_of = |packed: Vec<Arc<VerifiedTransaction>>| {
+        packed
+            .iter()
+            .map(|t| (t.signed().sender(), t.signed().sequence_number()))
+            .collect::<Vec<_>>()
+    };
+
+    let packed = round_robin_by_gas_price_band(pending.clone(), 10, usize::MAX);
+    assert_eq!(order_of(packed), expected);
+    for max_len in &[1, 4, 10, 25, 60] {
+        let packed = round_robin_by_gas_price_band(pending.clone(), 10, *max_len);
+        assert_eq!(order_of(packed), expected[..*max_len].to_vec());
+    }
+}
+
 #[stest::test]
 async fn test_rollback() -> Result<()> {
     let (pool, storage, config, _, _) = test_helper::start_txpool().await;
@@ -268,3 +389,25 @@ fn generate_txn(config: Arc<NodeConfig>, seq: u64) -> SignedUserTransaction {
     );
     txn
 }
+
+fn generate_verified_txn(
+    keypair: &(Ed25519PrivateKey, Ed25519PublicKey),
+    seq: u64,
+    gas_price: u64,
+) -> Arc<VerifiedTransaction> {
+    let (private_key, public_key) = keypair;
+    let raw_txn = RawUserTransaction::new_with_default_gas_token(
+        account_address::from_public_key(public_key),
+        seq,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        DEFAULT_MAX_GAS_AMOUNT,
+        gas_price,
+        DEFAULT_EXPIRATION_TIME,
+        ChainId::test(),
+    );
+    let txn = raw_txn
+        .sign(private_key, public_key.clone())
+        .expect("sign txn should ok")
+        .into_inner();
+    Arc::new(VerifiedTransaction::from_pending_block_transaction(txn))
+}
@@ -18,7 +18,7 @@ use futures_channel::mpsc;
 use parking_lot::RwLock;
 use starcoin_config::NodeConfig;
 use starcoin_statedb::ChainStateDB;
-use starcoin_txpool_api::{TxPoolStatus, TxPoolSyncService};
+use starcoin_txpool_api::{TxPoolContent, TxPoolStatus, TxPoolSyncService};
 use std::sync::Arc;
 use storage::Store;
 use types::{
@@ -179,6 +179,18 @@ impl TxPoolSyncService for TxPoolService {
             .map(|t| t.signed().clone())
             .collect()
     }
+
+    fn content(&self, now: Option<u64>) -> TxPoolContent {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["content"])
+            .start_timer();
+        let now = now.unwrap_or_else(|| self.inner.node_config.net().time_service().now_secs());
+        let (pending, queued) = self.inner.queue.content(self.inner.get_pool_client(), now);
+        TxPoolContent {
+            pending: pending.into_iter().map(|t| t.signed().clone()).collect(),
+            queued: queued.into_iter().map(|t| t.signed().clone()).collect(),
+        }
+    }
 }
 
 pub(crate) type TxnQueue = TransactionQueue;
@@ -256,11 +268,17 @@ impl Inner {
         max_len: u64,
         current_timestamp_secs: u64,
     ) -> Vec<Arc<VerifiedTransaction>> {
+        let gas_price_band = self.node_config.tx_pool.gas_price_band();
+        let ordering = if gas_price_band > 0 {
+            PendingOrdering::GasPriceBandRoundRobin(gas_price_band)
+        } else {
+            PendingOrdering::Priority
+        };
         let pending_settings = PendingSettings {
             block_number: u64::max_value(),
             current_timestamp: current_timestamp_secs,
             max_len: max_len as usize,
-            ordering: PendingOrdering::Priority,
+            ordering,
         };
         self.queue.inner_status(
             self.get_pool_client(),
@@ -0,0 +1,112 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The bloom filter of the events of a block, used to skip the blocks which can not match an event
+//! filter without reading their events.
+
+use crate::account_address::AccountAddress;
+use crate::contract_event::ContractEvent;
+use crate::event::EventKey;
+use crate::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+
+/// The bloom size in bytes, 2048 bits.
+pub const BLOCK_BLOOM_SIZE: usize = 256;
+/// The number of bits set for every item.
+const BLOOM_HASHES: usize = 3;
+
+/// The kinds of the items accrued to the bloom, so an address as the event creator and as the txn
+/// sender are different items.
+const EVENT_KEY_ITEM: u8 = 0;
+const ADDRESS_ITEM: u8 = 1;
+const TYPE_TAG_ITEM: u8 = 2;
+const SENDER_ITEM: u8 = 3;
+
+/// The bloom of the event keys, the event creator addresses, the event type tags and the senders of
+/// the user txns of a block. A false `contains_*` means the block has no such item, a true one may
+/// be a false positive.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockBloom(Vec<u8>);
+
+impl Default for BlockBloom {
+    fn default() -> Self {
+        Self(vec![0u8; BLOCK_BLOOM_SIZE])
+    }
+}
+
+impl BlockBloom {
+    /// Build the bloom of the (sender, events) of every txn of a block, the sender is None for the
+    /// block metadata txn.
+    pub fn from_txn_events<'a>(
+        txn_events: impl IntoIterator<Item = (Option<AccountAddress>, &'a [ContractEvent])>,
+    ) -> Self {
+        let mut bloom = Self::default();
+        for (sender, events) in txn_events {
+            if let Some(sender) = sender {
+                bloom.accrue(SENDER_ITEM, sender.as_ref());
+            }
+            for event in events {
+                bloom.accrue(EVENT_KEY_ITEM, event.key().as_bytes());
+                bloom.accrue(ADDRESS_ITEM, event.key().get_creator_address().as_ref());
+                if let Ok(type_tag) = bcs_ext::to_bytes(event.type_tag()) {
+                    bloom.accrue(TYPE_TAG_ITEM, type_tag.as_slice());
+                }
+            }
+        }
+        bloom
+    }
+
+    pub fn contains_event_key(&self, event_key: &EventKey) -> bool {
+        self.contains(EVENT_KEY_ITEM, event_key.as_bytes())
+    }
+
+    pub fn contains_address(&self, address: &AccountAddress) -> bool {
+        self.contains(ADDRESS_ITEM, address.as_ref())
+    }
+
+    pub fn contains_type_tag(&self, type_tag: &TypeTag) -> bool {
+        bcs_ext::to_bytes(type_tag)
+            .map(|type_tag| self.contains(TYPE_TAG_ITEM, type_tag.as_slice()))
+            .unwrap_or(true)
+    }
+
+    pub fn contains_sender(&self, sender: &AccountAddress) -> bool {
+        self.contains(SENDER_ITEM, sender.as_ref())
+    }
+
+    fn accrue(&mut self, kind: u8, item: &[u8]) {
+        if self.0.len() != BLOCK_BLOOM_SIZE {
+            return;
+        }
+        for (byte, mask) in Self::bits(kind, item).iter() {
+            self.0[*byte] |= *mask;
+        }
+    }
+
+    /// A bloom of an unexpected size contains everything, so the block is never skipped.
+    fn contains(&self, kind: u8, item: &[u8]) -> bool {
+        self.0.len() != BLOCK_BLOOM_SIZE
+            || Self::bits(kind, item)
+                .iter()
+                .all(|(byte, mask)| self.0[*byte] & *mask == *mask)
+    }
+
+    /// The (byte index, bit mask) of the bits of the item, every bit is taken from two bytes of the
+    /// sha3 hash of the item.
+    fn bits(kind: u8, item: &[u8]) -> [(usize, u8); BLOOM_HASHES] {
+        #![allow(clippy::integer_arithmetic)]
+
+        let mut data = Vec::with_capacity(item.len() + 1);
+        data.push(kind);
+        data.extend_from_slice(item);
+        let hash = HashValue::sha3_256_of(data.as_slice()).to_vec();
+        let mut bits = [(0usize, 0u8); BLOOM_HASHES];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let index =
+                (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) % (BLOCK_BLOOM_SIZE * 8);
+            *bit = (index / 8, 1u8 << (index % 8));
+        }
+        bits
+    }
+}
@@ -1,8 +1,11 @@
 //! Blockchain filter
 
+use crate::account_address::AccountAddress;
 use crate::block::BlockNumber;
+use crate::block_bloom::BlockBloom;
 use crate::contract_event::ContractEvent;
 use crate::event::EventKey;
+use crate::language_storage::TypeTag;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Filter {
@@ -16,6 +19,26 @@ pub struct Filter {
     /// If empty, match all.
     /// If specified, event must produced from one of the event keys.
     pub event_keys: Vec<EventKey>,
+    /// Search events.
+    ///
+    /// If empty, match all.
+    /// If specified, event must be emitted to the event handle of one of the addresses.
+    pub addresses: Vec<AccountAddress>,
+    /// Search events.
+    ///
+    /// If empty, match all.
+    /// If specified, event must be one of the types.
+    pub type_tags: Vec<TypeTag>,
+    /// Search events.
+    ///
+    /// If empty, match all.
+    /// If specified, event must be emitted by a user txn sent by one of the addresses.
+    pub senders: Vec<AccountAddress>,
+    /// Search events.
+    ///
+    /// If empty, match all.
+    /// If specified, event must also match at least one of the criteria.
+    pub any_of: Vec<EventCriteria>,
     /// Events limit
     ///
     /// If None, return all events
@@ -31,6 +54,10 @@ impl Default for Filter {
             from_block: 0,
             to_block: 0,
             event_keys: vec![],
+            addresses: vec![],
+            type_tags: vec![],
+            senders: vec![],
+            any_of: vec![],
             limit: None,
             reverse: true,
         }
@@ -38,13 +65,79 @@ impl Default for Filter {
 }
 
 impl Filter {
-    pub fn matching(&self, block_number: BlockNumber, e: &ContractEvent) -> bool {
-        if self.from_block <= block_number
+    /// `sender` is the sender of the txn which emits the event, None for the block metadata txn.
+    pub fn matching(
+        &self,
+        block_number: BlockNumber,
+        sender: Option<AccountAddress>,
+        e: &ContractEvent,
+    ) -> bool {
+        self.from_block <= block_number
             && block_number <= self.to_block
-            && (self.event_keys.is_empty() || self.event_keys.contains(e.key()))
-        {
-            return true;
-        }
-        false
+            && matching_any(&self.event_keys, e.key())
+            && matching_any(&self.addresses, &e.key().get_creator_address())
+            && matching_any(&self.type_tags, e.type_tag())
+            && matching_sender(&self.senders, sender)
+            && (self.any_of.is_empty()
+                || self
+                    .any_of
+                    .iter()
+                    .any(|criteria| criteria.matching(sender, e)))
+    }
+
+    /// Whether a block with the `bloom` may contain the matched events, false means the block can
+    /// be skipped.
+    pub fn bloom_matching(&self, bloom: &BlockBloom) -> bool {
+        bloom_matching_any(&self.event_keys, |key| bloom.contains_event_key(key))
+            && bloom_matching_any(&self.addresses, |address| bloom.contains_address(address))
+            && bloom_matching_any(&self.type_tags, |type_tag| {
+                bloom.contains_type_tag(type_tag)
+            })
+            && bloom_matching_any(&self.senders, |sender| bloom.contains_sender(sender))
+            && (self.any_of.is_empty()
+                || self
+                    .any_of
+                    .iter()
+                    .any(|criteria| criteria.bloom_matching(bloom)))
     }
 }
+
+/// A group of event conditions used in `Filter::any_of`, the specified fields are all required
+/// to match, and the groups are combined by OR.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventCriteria {
+    pub event_keys: Vec<EventKey>,
+    pub addresses: Vec<AccountAddress>,
+    pub type_tags: Vec<TypeTag>,
+    pub senders: Vec<AccountAddress>,
+}
+
+impl EventCriteria {
+    pub fn matching(&self, sender: Option<AccountAddress>, e: &ContractEvent) -> bool {
+        matching_any(&self.event_keys, e.key())
+            && matching_any(&self.addresses, &e.key().get_creator_address())
+            && matching_any(&self.type_tags, e.type_tag())
+            && matching_sender(&self.senders, sender)
+    }
+
+    pub fn bloom_matching(&self, bloom: &BlockBloom) -> bool {
+        bloom_matching_any(&self.event_keys, |key| bloom.contains_event_key(key))
+            && bloom_matching_any(&self.addresses, |address| bloom.contains_address(address))
+            && bloom_matching_any(&self.type_tags, |type_tag| {
+                bloom.contains_type_tag(type_tag)
+            })
+            && bloom_matching_any(&self.senders, |sender| bloom.contains_sender(sender))
+    }
+}
+
+fn matching_any<T: PartialEq>(candidates: &[T], value: &T) -> bool {
+    candidates.is_empty() || candidates.contains(value)
+}
+
+fn matching_sender(senders: &[AccountAddress], sender: Option<AccountAddress>) -> bool {
+    senders.is_empty() || sender.map_or(false, |sender| senders.contains(&sender))
+}
+
+fn bloom_matching_any<T>(candidates: &[T], contains: impl Fn(&T) -> bool) -> bool {
+    candidates.is_empty() || candidates.iter().any(contains)
+}
@@ -4,6 +4,7 @@
 #![deny(clippy::integer_arithmetic)]
 
 mod event_info;
+mod transaction_proof;
 
 pub mod access_path {
     pub use starcoin_vm_types::access_path::{AccessPath, DataPath, DataType};
@@ -21,6 +22,7 @@ pub mod account_state;
 
 #[allow(clippy::too_many_arguments)]
 pub mod block;
+pub mod block_bloom;
 pub mod cmpact_block;
 
 pub mod block_metadata {
@@ -56,6 +58,7 @@ pub mod state_set;
 pub mod system_events;
 
 pub mod transaction {
+    pub use crate::transaction_proof::TransactionInfoWithProof;
     pub use starcoin_vm_types::transaction::*;
 }
 
@@ -87,6 +90,7 @@ pub mod sync_status;
 
 pub mod proof {
     pub use forkable_jellyfish_merkle::proof::SparseMerkleProof;
+    pub use starcoin_accumulator::AccumulatorProof;
 }
 
 pub mod receipt_identifier;
@@ -5,6 +5,7 @@ use crate::block::{Block, BlockHeaderExtra, ExecutedBlock};
 use crate::sync_status::SyncStatus;
 use crate::U256;
 use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
 use starcoin_vm_types::genesis_config::ConsensusStrategy;
 use std::sync::Arc;
 
@@ -44,6 +45,9 @@ pub struct MintBlockEvent {
     pub minting_blob: Vec<u8>,
     pub difficulty: U256,
     pub block_number: u64,
+    /// The txns added to the previous template of the same parent block,
+    /// None if it is a new template on a new parent block.
+    pub added_txns: Option<Vec<HashValue>>,
 }
 
 impl MintBlockEvent {
@@ -58,8 +62,19 @@ impl MintBlockEvent {
             minting_blob,
             difficulty,
             block_number,
+            added_txns: None,
         }
     }
+
+    /// Mark the event as an update of the previous template, which adds the txns.
+    pub fn with_added_txns(mut self, added_txns: Vec<HashValue>) -> Self {
+        self.added_txns = Some(added_txns);
+        self
+    }
+
+    pub fn is_update(&self) -> bool {
+        self.added_txns.is_some()
+    }
 }
 
 #[derive(Clone, Debug)]
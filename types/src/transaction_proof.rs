@@ -0,0 +1,58 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::contract_event::ContractEvent;
+use crate::proof::AccumulatorProof;
+use crate::transaction::TransactionInfo;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::inmemory::InMemoryAccumulator;
+use starcoin_crypto::hash::CryptoHash;
+use starcoin_crypto::HashValue;
+
+/// The txn info with the proof of its inclusion in the txn accumulator, so a light client can
+/// verify a txn is executed in a block by the `txn_accumulator_root` of the block header only.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionInfoWithProof {
+    pub transaction_info: TransactionInfo,
+    /// The index of the txn info in the txn accumulator, the global index of the txn on the chain.
+    pub leaf_index: u64,
+    pub proof: AccumulatorProof,
+}
+
+impl TransactionInfoWithProof {
+    pub fn new(
+        transaction_info: TransactionInfo,
+        leaf_index: u64,
+        proof: AccumulatorProof,
+    ) -> Self {
+        Self {
+            transaction_info,
+            leaf_index,
+            proof,
+        }
+    }
+
+    /// Verify the txn info is in the txn accumulator with the `txn_accumulator_root`, such as
+    /// the `txn_accumulator_root` of the block header or of a later block header.
+    pub fn verify(&self, txn_accumulator_root: HashValue) -> Result<()> {
+        self.proof.verify(
+            txn_accumulator_root,
+            self.transaction_info.id(),
+            self.leaf_index,
+        )
+    }
+
+    /// Verify the `events` are all the events emitted by the txn, in order.
+    pub fn verify_events(&self, events: &[ContractEvent]) -> Result<()> {
+        let event_hashes: Vec<HashValue> = events.iter().map(|e| e.crypto_hash()).collect();
+        let event_root_hash = InMemoryAccumulator::from_leaves(event_hashes.as_slice()).root_hash();
+        ensure!(
+            event_root_hash == self.transaction_info.event_root_hash(),
+            "Event root hashes do not match. Actual: {:x}, expected: {:x}.",
+            event_root_hash,
+            self.transaction_info.event_root_hash()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Disassemble the module bytecode to the readable text, so the explorers can show the code of
+//! the contracts on chain.
+
+use anyhow::Result;
+use bytecode_source_map::mapping::SourceMapping;
+use disassembler::disassembler::{Disassembler, DisassemblerOptions};
+use move_ir_types::location::Spanned;
+use starcoin_vm_types::file_format::CompiledModule;
+use vm::binary_views::BinaryIndexedView;
+
+/// Disassemble the module code, with the bytecode of the functions.
+pub fn disassemble_module(code: &[u8]) -> Result<String> {
+    let module = CompiledModule::deserialize(code)?;
+    let source_mapping = SourceMapping::new_from_view(
+        BinaryIndexedView::Module(&module),
+        Spanned::unsafe_no_loc(()).loc,
+    )?;
+    let mut options = DisassemblerOptions::new();
+    options.print_code = true;
+    Disassembler::new(source_mapping, options).disassemble()
+}
@@ -7,13 +7,22 @@ use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValu
 use starcoin_state_api::StateNodeStore;
 use starcoin_statedb::ChainStateDB;
 use starcoin_vm_runtime::starcoin_vm::StarcoinVM;
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::account_config::{BLOCK_PROLOGUE_NAME, TRANSACTION_MANAGER_MODULE};
 use starcoin_vm_types::identifier::{IdentStr, Identifier};
-use starcoin_vm_types::language_storage::{ModuleId, StructTag, TypeTag};
+use starcoin_vm_types::language_storage::{FunctionId, ModuleId, StructTag, TypeTag};
+use starcoin_vm_types::module_abi::{self, CompiledModuleABI, ModuleABI};
 use starcoin_vm_types::state_view::StateView;
-use starcoin_vm_types::transaction::{DryRunTransaction, TransactionOutput};
+use starcoin_vm_types::transaction::{
+    DryRunTransaction, GasBreakdown, ScriptFunction, ScriptFunctionABI, Transaction,
+    TransactionOutput, TransactionPayload,
+};
 use starcoin_vm_types::transaction_argument::convert_txn_args;
 use starcoin_vm_types::transaction_argument::TransactionArgument;
 use starcoin_vm_types::vm_status::VMStatus;
+use starcoin_vm_types::write_set::{WriteOp, WriteSet};
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -37,6 +46,16 @@ impl PlaygroudService {
         dry_run(&state_view, txn)
     }
 
+    /// Dry run the txn, and annotate the write set and events of the output.
+    pub fn dry_run_annotated(
+        &self,
+        state_root: HashValue,
+        txn: DryRunTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, TxnOutputAnnotation)> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        dry_run_annotated(&state_view, txn)
+    }
+
     pub fn call_contract(
         &self,
         state_root: HashValue,
@@ -49,6 +68,16 @@ impl PlaygroudService {
         let rets = call_contract(&state_view, module_id, func.as_str(), type_args, args)?;
         Ok(rets)
     }
+    /// Get the raw state at the `state_root`.
+    pub fn get_state(
+        &self,
+        state_root: HashValue,
+        access_path: &AccessPath,
+    ) -> Result<Option<Vec<u8>>> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        state_view.get(access_path)
+    }
+
     pub fn view_resource(
         &self,
         state_root: HashValue,
@@ -58,6 +87,46 @@ impl PlaygroudService {
         let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
         view_resource(&state_view, struct_tag.clone(), data)
     }
+
+    /// Get the ABI attached to the module by the `ABIRegistry`.
+    pub fn get_module_abi(
+        &self,
+        state_root: HashValue,
+        module_id: &ModuleId,
+    ) -> Result<Option<ModuleABI>> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        module_abi::get_module_abi(&state_view, module_id)
+    }
+
+    /// Parse the ABI of the module from the module code.
+    pub fn get_compiled_module_abi(
+        &self,
+        state_root: HashValue,
+        module_id: &ModuleId,
+    ) -> Result<Option<CompiledModuleABI>> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        module_abi::get_compiled_module_abi(&state_view, module_id)
+    }
+
+    /// Re-execute the txn on the state before it, and trace the execution.
+    pub fn trace_transaction(
+        &self,
+        state_root: HashValue,
+        txn: Transaction,
+    ) -> Result<TransactionTrace> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        trace_transaction(&state_view, txn)
+    }
+
+    /// Resolve the ABI of the script function, the ABI in the `ABIRegistry` is preferred.
+    pub fn resolve_function_abi(
+        &self,
+        state_root: HashValue,
+        function_id: &FunctionId,
+    ) -> Result<ScriptFunctionABI> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        module_abi::resolve_script_function_abi(&state_view, function_id)
+    }
 }
 
 pub fn view_resource(
@@ -74,10 +143,260 @@ pub fn dry_run(
     state_view: &dyn StateView,
     txn: DryRunTransaction,
 ) -> Result<(VMStatus, TransactionOutput)> {
+    let (status, output, _) = dry_run_with_gas_breakdown(state_view, txn)?;
+    Ok((status, output))
+}
+
+/// Dry run the txn, and split the gas used of the output by category.
+pub fn dry_run_with_gas_breakdown(
+    state_view: &dyn StateView,
+    txn: DryRunTransaction,
+) -> Result<(VMStatus, TransactionOutput, GasBreakdown)> {
     let mut vm = StarcoinVM::new();
     vm.dry_run_transaction(state_view, txn)
 }
 
+/// Dry run the txn, and annotate the write set, events and gas of the output.
+pub fn dry_run_annotated(
+    state_view: &dyn StateView,
+    txn: DryRunTransaction,
+) -> Result<(VMStatus, TransactionOutput, TxnOutputAnnotation)> {
+    let (status, output, gas_breakdown) = dry_run_with_gas_breakdown(state_view, txn)?;
+    let mut annotation = annotate_txn_output(state_view, &output);
+    annotation.gas_breakdown = Some(gas_breakdown);
+    Ok((status, output, annotation))
+}
+
+/// The resources of the write set and the event data of a txn output, decoded by the move types,
+/// and the gas breakdown of the dry run output.
+#[derive(Clone, Debug)]
+pub struct TxnOutputAnnotation {
+    /// In the same order as the write set, `None` for the code, the deletion,
+    /// and the resource which can not be decoded.
+    pub write_set: Vec<Option<AnnotatedMoveStruct>>,
+    /// In the same order as the events, `None` for the event data which can not be decoded.
+    pub events: Vec<Option<AnnotatedMoveValue>>,
+    /// The gas used split by category, only for the output of a dry run.
+    pub gas_breakdown: Option<GasBreakdown>,
+}
+
+/// The state after the write set applied on the base state.
+struct WriteSetStateView<'a> {
+    base: &'a dyn StateView,
+    write_set: &'a WriteSet,
+}
+
+impl<'a> StateView for WriteSetStateView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        match self
+            .write_set
+            .iter()
+            .rev()
+            .find(|(path, _)| path == access_path)
+        {
+            Some((_, WriteOp::Value(value))) => Ok(Some(value.clone())),
+            Some((_, WriteOp::Deletion)) => Ok(None),
+            None => self.base.get(access_path),
+        }
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths.iter().map(|path| self.get(path)).collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+}
+
+/// Decode the resources of the write set and the event data of the `output` executed on the `state_view`,
+/// the types are resolved on the state after the write set applied, so the modules published by the txn are resolved too.
+pub fn annotate_txn_output(
+    state_view: &dyn StateView,
+    output: &TransactionOutput,
+) -> TxnOutputAnnotation {
+    let state_view = WriteSetStateView {
+        base: state_view,
+        write_set: output.write_set(),
+    };
+    let annotator = MoveValueAnnotator::new(&state_view);
+    let write_set = output
+        .write_set()
+        .iter()
+        .map(
+            |(access_path, write_op)| match (access_path.path.as_struct_tag(), write_op) {
+                (Some(struct_tag), WriteOp::Value(value)) => {
+                    annotator.view_struct(struct_tag.clone(), value).ok()
+                }
+                _ => None,
+            },
+        )
+        .collect();
+    let events = output
+        .events()
+        .iter()
+        .map(|event| {
+            annotator
+                .view_value(event.type_tag(), event.event_data())
+                .ok()
+        })
+        .collect();
+    TxnOutputAnnotation {
+        write_set,
+        events,
+        gas_breakdown: None,
+    }
+}
+
+/// A Move call frame of a txn trace.
+#[derive(Clone, Debug)]
+pub struct CallFrame {
+    /// The depth of the frame, the entry function is 0.
+    pub depth: u32,
+    /// The module of the function, `None` for a script.
+    pub module: Option<ModuleId>,
+    pub function: Identifier,
+    pub ty_args: Vec<TypeTag>,
+    /// The bcs encoded arguments.
+    pub args: Vec<Vec<u8>>,
+    /// The execution gas of the frame, including its nested calls.
+    pub gas_used: u64,
+}
+
+/// The trace of a re-executed txn.
+#[derive(Clone, Debug)]
+pub struct TransactionTrace {
+    pub status: VMStatus,
+    pub output: TransactionOutput,
+    pub annotation: TxnOutputAnnotation,
+    /// Only the entry function is traced, the pinned move vm runtime has no hook on its
+    /// interpreter calls, so the nested frames need a vm upgrade to be recorded.
+    pub frames: Vec<CallFrame>,
+    /// The states read by the execution, in the order of the first read.
+    pub reads: Vec<(AccessPath, Option<Vec<u8>>)>,
+}
+
+/// The state view records the states read from the base state view.
+struct RecordingStateView<'a> {
+    base: &'a dyn StateView,
+    /// The access paths have been recorded, a txn may read the same state many times.
+    read_paths: RefCell<HashSet<AccessPath>>,
+    reads: RefCell<Vec<(AccessPath, Option<Vec<u8>>)>>,
+}
+
+impl<'a> RecordingStateView<'a> {
+    fn new(base: &'a dyn StateView) -> Self {
+        Self {
+            base,
+            read_paths: RefCell::new(HashSet::new()),
+            reads: RefCell::new(vec![]),
+        }
+    }
+
+    fn into_reads(self) -> Vec<(AccessPath, Option<Vec<u8>>)> {
+        self.reads.into_inner()
+    }
+}
+
+impl<'a> StateView for RecordingStateView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let value = self.base.get(access_path)?;
+        if self.read_paths.borrow_mut().insert(access_path.clone()) {
+            self.reads
+                .borrow_mut()
+                .push((access_path.clone(), value.clone()));
+        }
+        Ok(value)
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths.iter().map(|path| self.get(path)).collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+}
+
+fn script_function_frame(function: &ScriptFunction, gas_used: u64) -> CallFrame {
+    CallFrame {
+        depth: 0,
+        module: Some(function.module().clone()),
+        function: function.function().to_owned(),
+        ty_args: function.ty_args().to_vec(),
+        args: function.args().to_vec(),
+        gas_used,
+    }
+}
+
+/// Re-execute the `txn` on the `state_view`, which should be the state before the txn,
+/// and record the states it read, the entry function and the gas used.
+pub fn trace_transaction(state_view: &dyn StateView, txn: Transaction) -> Result<TransactionTrace> {
+    let recording_view = RecordingStateView::new(state_view);
+    let (status, output, gas_breakdown, frames) = match txn {
+        Transaction::UserTransaction(txn) => {
+            // the txn on chain has been verified, so it is executed as a dry run without the signature.
+            let payload = txn.payload().clone();
+            let (status, output, gas_breakdown) = dry_run_with_gas_breakdown(
+                &recording_view,
+                DryRunTransaction {
+                    raw_txn: txn.raw_txn().clone(),
+                    public_key: txn.authenticator().public_key(),
+                },
+            )?;
+            let gas_used = gas_breakdown.execution_gas;
+            let frames = match &payload {
+                TransactionPayload::ScriptFunction(function) => {
+                    vec![script_function_frame(function, gas_used)]
+                }
+                TransactionPayload::Script(script) => vec![CallFrame {
+                    depth: 0,
+                    module: None,
+                    function: Identifier::new("main")?,
+                    ty_args: script.ty_args().to_vec(),
+                    args: script.args().to_vec(),
+                    gas_used,
+                }],
+                TransactionPayload::Package(package) => package
+                    .init_script()
+                    .map(|function| script_function_frame(function, gas_used))
+                    .into_iter()
+                    .collect(),
+            };
+            (status, output, Some(gas_breakdown), frames)
+        }
+        Transaction::BlockMetadata(block_metadata) => {
+            let mut vm = StarcoinVM::new();
+            let (status, output) = vm
+                .execute_block_transactions(
+                    &recording_view,
+                    vec![Transaction::BlockMetadata(block_metadata)],
+                    None,
+                )?
+                .pop()
+                .ok_or_else(|| anyhow::format_err!("No output of the block metadata txn"))?;
+            let frames = vec![CallFrame {
+                depth: 0,
+                module: Some(TRANSACTION_MANAGER_MODULE.clone()),
+                function: BLOCK_PROLOGUE_NAME.clone(),
+                ty_args: vec![],
+                args: vec![],
+                gas_used: output.gas_used(),
+            }];
+            (status, output, None, frames)
+        }
+    };
+    let mut annotation = annotate_txn_output(state_view, &output);
+    annotation.gas_breakdown = gas_breakdown;
+    Ok(TransactionTrace {
+        status,
+        output,
+        annotation,
+        frames,
+        reads: recording_view.into_reads(),
+    })
+}
+
 pub fn call_contract(
     state_view: &dyn StateView,
     module_id: ModuleId,
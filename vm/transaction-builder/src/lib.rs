@@ -238,6 +238,17 @@ pub fn encode_create_account_script_function(
     )
 }
 
+pub fn encode_rotate_authentication_key_script_function(
+    new_auth_key: AuthenticationKey,
+) -> ScriptFunction {
+    ScriptFunction::new(
+        ModuleId::new(core_code_address(), Identifier::new("Account").unwrap()),
+        Identifier::new("rotate_authentication_key").unwrap(),
+        vec![],
+        vec![bcs_ext::to_bytes(&new_auth_key.to_vec()).unwrap()],
+    )
+}
+
 pub fn encode_transfer_script_function(
     version: StdlibVersion,
     recipient: AccountAddress,
@@ -830,6 +841,100 @@ pub fn build_module_upgrade_queue(
     )
 }
 
+fn build_dao_proposal_script_function(
+    module: &str,
+    function: &str,
+    ty_args: Vec<TypeTag>,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+    extra_args: Vec<Vec<u8>>,
+) -> ScriptFunction {
+    let mut args = vec![
+        bcs_ext::to_bytes(&proposer_address).unwrap(),
+        bcs_ext::to_bytes(&proposal_id).unwrap(),
+    ];
+    args.extend(extra_args);
+    ScriptFunction::new(
+        ModuleId::new(core_code_address(), Identifier::new(module).unwrap()),
+        Identifier::new(function).unwrap(),
+        ty_args,
+        args,
+    )
+}
+
+/// Cast vote on the proposal of any action type, `votes` of `token` are staked until the proposal end.
+pub fn build_dao_cast_vote(
+    token: TypeTag,
+    action: TypeTag,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+    agree: bool,
+    votes: u128,
+) -> ScriptFunction {
+    build_dao_proposal_script_function(
+        "DaoVoteScripts",
+        "cast_vote",
+        vec![token, action],
+        proposer_address,
+        proposal_id,
+        vec![
+            bcs_ext::to_bytes(&agree).unwrap(),
+            bcs_ext::to_bytes(&votes).unwrap(),
+        ],
+    )
+}
+
+/// Revoke the vote on an active proposal, and get back the staked token.
+pub fn build_dao_revoke_vote(
+    token: TypeTag,
+    action: TypeTag,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+) -> ScriptFunction {
+    build_dao_proposal_script_function(
+        "DaoVoteScripts",
+        "revoke_vote",
+        vec![token, action],
+        proposer_address,
+        proposal_id,
+        vec![],
+    )
+}
+
+/// Unstake the votes after the voting of proposal is end.
+pub fn build_dao_unstake_vote(
+    token: TypeTag,
+    action: TypeTag,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+) -> ScriptFunction {
+    build_dao_proposal_script_function(
+        "DaoVoteScripts",
+        "unstake_vote",
+        vec![token, action],
+        proposer_address,
+        proposal_id,
+        vec![],
+    )
+}
+
+/// Queue the agreed proposal of any action type.
+pub fn build_dao_queue_proposal_action(
+    token: TypeTag,
+    action: TypeTag,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+) -> ScriptFunction {
+    build_dao_proposal_script_function(
+        "Dao",
+        "queue_proposal_action",
+        vec![token, action],
+        proposer_address,
+        proposal_id,
+        vec![],
+    )
+}
+
 pub fn build_vm_config_upgrade_proposal(vm_config: VMConfig, exec_delay: u64) -> ScriptFunction {
     let gas_constants = &vm_config.gas_schedule.gas_constants;
     ScriptFunction::new(
@@ -862,6 +967,50 @@ pub fn build_vm_config_upgrade_proposal(vm_config: VMConfig, exec_delay: u64) ->
     )
 }
 
+/// Propose to withdraw `amount` of `token` from the treasury to `receiver`, the token is released linearly in `period` seconds.
+pub fn build_treasury_withdraw_proposal(
+    token: TypeTag,
+    receiver: AccountAddress,
+    amount: u128,
+    period: u64,
+    exec_delay: u64,
+) -> ScriptFunction {
+    ScriptFunction::new(
+        ModuleId::new(
+            core_code_address(),
+            Identifier::new("TreasuryScripts").unwrap(),
+        ),
+        Identifier::new("propose_withdraw").unwrap(),
+        vec![token],
+        vec![
+            bcs_ext::to_bytes(&receiver).unwrap(),
+            bcs_ext::to_bytes(&amount).unwrap(),
+            bcs_ext::to_bytes(&period).unwrap(),
+            bcs_ext::to_bytes(&exec_delay).unwrap(),
+        ],
+    )
+}
+
+/// Execute the agreed treasury withdraw proposal, the txn must be sent by the receiver of the proposal.
+pub fn build_treasury_withdraw_execute(
+    token: TypeTag,
+    proposer_address: AccountAddress,
+    proposal_id: u64,
+) -> ScriptFunction {
+    ScriptFunction::new(
+        ModuleId::new(
+            core_code_address(),
+            Identifier::new("TreasuryScripts").unwrap(),
+        ),
+        Identifier::new("execute_withdraw_proposal").unwrap(),
+        vec![token],
+        vec![
+            bcs_ext::to_bytes(&proposer_address).unwrap(),
+            bcs_ext::to_bytes(&proposal_id).unwrap(),
+        ],
+    )
+}
+
 pub fn build_empty_script() -> ScriptFunction {
     ScriptFunction::new(
         ModuleId::new(
@@ -42,6 +42,7 @@ use crate::account_address::AccountAddress;
 use crate::identifier::Identifier;
 use crate::parser::parse_struct_tag;
 use anyhow::{bail, Result};
+#[cfg(feature = "full")]
 use forkable_jellyfish_merkle::RawKey;
 use move_core_types::language_storage::{ModuleId, ResourceKey, StructTag};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -295,6 +296,7 @@ impl DataPath {
         }
     }
 
+    #[cfg(feature = "full")]
     pub fn key_hash(&self) -> HashValue {
         match self {
             DataPath::Resource(struct_tag) => struct_tag.key_hash(),
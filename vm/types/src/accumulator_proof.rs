@@ -0,0 +1,68 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use starcoin_accumulator::node::InternalNode;
+use starcoin_accumulator::node_index::NodeIndex;
+pub use starcoin_accumulator::AccumulatorProof;
+use starcoin_accumulator::MAX_ACCUMULATOR_PROOF_DEPTH;
+use starcoin_crypto::HashValue;
+use std::collections::HashMap;
+
+/// Verify many leaves exist in the accumulator whose root hash is `expected_root_hash`, every item
+/// is the (leaf index, leaf hash, proof) of a leaf, such as the proofs of `Accumulator::get_proofs`.
+///
+/// The nodes computed from the verified proofs are remembered by their positions, so a proof is
+/// only folded until it reaches a verified node, instead of to the root.
+pub fn verify_accumulator_proofs<'a>(
+    expected_root_hash: HashValue,
+    leaves: impl IntoIterator<Item = (u64, HashValue, &'a AccumulatorProof)>,
+) -> Result<()> {
+    // (level, index at the level) => node hash
+    let mut verified: HashMap<(usize, u64), HashValue> = HashMap::new();
+    for (leaf_index, leaf_hash, proof) in leaves {
+        let siblings = proof.siblings();
+        ensure!(
+            siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+            "Accumulator proof of leaf {} has more than {} ({}) siblings.",
+            leaf_index,
+            MAX_ACCUMULATOR_PROOF_DEPTH,
+            siblings.len()
+        );
+        let mut path = vec![];
+        let (mut hash, mut index) = (leaf_hash, leaf_index);
+        let mut reached_verified = false;
+        for (level, sibling_hash) in siblings.iter().enumerate() {
+            if let Some(verified_hash) = verified.get(&(level, index)) {
+                ensure!(
+                    *verified_hash == hash,
+                    "The proof of leaf {} mismatch with the verified node at level {}.",
+                    leaf_index,
+                    level
+                );
+                reached_verified = true;
+                break;
+            }
+            path.push(((level, index), hash));
+            hash = if index % 2 == 0 {
+                // the current node is a left child.
+                InternalNode::new(NodeIndex::new(index), hash, *sibling_hash).hash()
+            } else {
+                // the current node is a right child.
+                InternalNode::new(NodeIndex::new(index), *sibling_hash, hash).hash()
+            };
+            index /= 2;
+        }
+        if !reached_verified {
+            ensure!(
+                hash == expected_root_hash,
+                "Root hashes do not match for leaf {}. Actual root hash: {:x}. Expected root hash: {:x}.",
+                leaf_index,
+                hash,
+                expected_root_hash
+            );
+        }
+        verified.extend(path);
+    }
+    Ok(())
+}
@@ -2,14 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 //TODO FIXME for fuzzing Arbitrary;
 #![allow(clippy::unit_arg)]
+
+#[cfg(not(any(feature = "full", feature = "minimal")))]
+compile_error!("One of the features full or minimal must be enabled.");
+
 mod language_storage_ext;
 
 pub mod account_address;
 
 pub mod gas_schedule {
     pub use move_core_types::gas_schedule::*;
+    #[cfg(feature = "full")]
     pub use move_vm_types::gas_schedule::*;
 }
+#[cfg(feature = "full")]
 pub mod location {
     pub use move_ir_types::location::Loc;
 }
@@ -57,42 +63,56 @@ pub mod value {
     pub use move_core_types::value::*;
 }
 
+#[cfg(feature = "full")]
 pub mod values {
     pub use move_vm_types::values::*;
 }
 
+#[cfg(feature = "full")]
 pub mod loaded_data {
     pub mod runtime_types {
         pub use move_vm_types::loaded_data::runtime_types::{StructType, Type};
     }
 }
 
+#[cfg(feature = "full")]
 pub mod data_store {
     pub use move_vm_types::data_store::DataStore;
 }
 
+#[cfg(feature = "full")]
 pub mod file_format {
     pub use vm::file_format::*;
 }
 
+#[cfg(feature = "full")]
+pub mod file_format_common {
+    pub use vm::file_format_common::*;
+}
+
+#[cfg(feature = "full")]
 pub mod normalized {
     pub use vm::normalized::*;
 }
 
+#[cfg(feature = "full")]
 pub mod compatibility {
     pub use vm::compatibility::*;
 }
 
+#[cfg(feature = "full")]
 pub mod views {
     pub use vm::views::*;
 }
 
 pub mod data_cache {}
 
+#[cfg(feature = "full")]
 pub mod access {
     pub use vm::access::{ModuleAccess, ScriptAccess};
 }
 
+#[cfg(feature = "full")]
 pub mod errors {
     pub use vm::errors::*;
     pub use vm::IndexKind;
@@ -115,15 +135,20 @@ pub mod vm_status {
 pub mod effects {
     pub use move_core_types::effects::*;
 }
+#[cfg(feature = "full")]
 pub mod bytecode_verifier {
     pub use bytecode_verifier::{dependencies, script_signature, verify_module, verify_script};
 }
 
 pub mod access_path;
+#[cfg(feature = "full")]
+pub mod accumulator_proof;
 pub mod account_config;
 pub mod block_metadata;
 pub mod event;
 pub mod genesis_config;
+#[cfg(feature = "full")]
+pub mod module_abi;
 pub mod on_chain_config;
 pub mod on_chain_resource;
 pub mod serde_helper;
@@ -0,0 +1,488 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The ABI of a module attached on chain by the `ABIRegistry` module published at the module address,
+//! so the named args of script functions and the event schemas of third-party modules can be resolved
+//! without the ABI files.
+//! The `CompiledModuleABI` is parsed from the module bytecode instead, it includes the signatures of
+//! all the exposed functions and the struct layouts, but the names of the args are not kept.
+
+use crate::access::ModuleAccess;
+use crate::access_path::AccessPath;
+use crate::account_address::AccountAddress;
+use crate::file_format::{
+    Ability, AbilitySet, CompiledModule, FunctionDefinition, SignatureIndex, SignatureToken,
+    StructFieldInformation, Visibility,
+};
+use crate::identifier::{IdentStr, Identifier};
+use crate::language_storage::{FunctionId, ModuleId, StructTag, TypeTag};
+use crate::state_view::StateView;
+use crate::transaction::{ArgumentABI, ScriptFunctionABI, TypeArgumentABI};
+use anyhow::{bail, ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+
+pub const ABI_REGISTRY_MODULE_NAME: &str = "ABIRegistry";
+pub const ABI_REGISTRY_STRUCT_NAME: &str = "ABIRegistry";
+
+/// The `ABIRegistry::ABIRegistry` resource at the module address.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ABIRegistry {
+    pub abis: Vec<ModuleABIEntry>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ModuleABIEntry {
+    pub module_name: Vec<u8>,
+    /// The bcs encoded `ModuleABI`.
+    pub abi: Vec<u8>,
+}
+
+impl ABIRegistry {
+    pub fn struct_tag(address: AccountAddress) -> StructTag {
+        StructTag {
+            address,
+            module: Identifier::new(ABI_REGISTRY_MODULE_NAME).expect("valid identifier"),
+            name: Identifier::new(ABI_REGISTRY_STRUCT_NAME).expect("valid identifier"),
+            type_params: vec![],
+        }
+    }
+
+    pub fn module_id(address: AccountAddress) -> ModuleId {
+        ModuleId::new(
+            address,
+            Identifier::new(ABI_REGISTRY_MODULE_NAME).expect("valid identifier"),
+        )
+    }
+
+    pub fn access_path(address: AccountAddress) -> AccessPath {
+        AccessPath::resource_access_path(address, Self::struct_tag(address))
+    }
+
+    pub fn get_abi(&self, module_id: &ModuleId) -> Result<Option<ModuleABI>> {
+        match self
+            .abis
+            .iter()
+            .find(|entry| entry.module_name.as_slice() == module_id.name().as_str().as_bytes())
+        {
+            Some(entry) => {
+                let abi: ModuleABI = bcs_ext::from_bytes(entry.abi.as_slice())
+                    .map_err(|e| format_err!("invalid abi of module {}: {}", module_id, e))?;
+                ensure!(
+                    &abi.module_id == module_id,
+                    "the abi registered for module {} is the abi of {}",
+                    module_id,
+                    abi.module_id
+                );
+                Ok(Some(abi))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// The ABI of a module, includes the script functions and the event structs.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ModuleABI {
+    pub module_id: ModuleId,
+    pub functions: Vec<ScriptFunctionABI>,
+    pub events: Vec<EventABI>,
+}
+
+/// The schema of an event struct, the fields are in the declared order.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct EventABI {
+    pub name: String,
+    pub fields: Vec<ArgumentABI>,
+}
+
+impl ModuleABI {
+    pub fn function(&self, name: &IdentStr) -> Option<&ScriptFunctionABI> {
+        self.functions
+            .iter()
+            .find(|function| function.name() == name.as_str())
+    }
+
+    pub fn event(&self, name: &IdentStr) -> Option<&EventABI> {
+        self.events.iter().find(|event| event.name == name.as_str())
+    }
+}
+
+/// Get the ABI of `module_id` from the `ABIRegistry` at the module address.
+pub fn get_module_abi(
+    state_view: &dyn StateView,
+    module_id: &ModuleId,
+) -> Result<Option<ModuleABI>> {
+    match state_view.get(&ABIRegistry::access_path(*module_id.address()))? {
+        Some(bytes) => bcs_ext::from_bytes::<ABIRegistry>(bytes.as_slice())?.get_abi(module_id),
+        None => Ok(None),
+    }
+}
+
+/// Resolve the ABI of a script function, use the ABI in the registry if it is attached,
+/// otherwise resolve from the module deployed on chain.
+pub fn resolve_script_function_abi(
+    state_view: &dyn StateView,
+    function_id: &FunctionId,
+) -> Result<ScriptFunctionABI> {
+    if let Some(abi) = get_module_abi(state_view, &function_id.module)?
+        .and_then(|abi| abi.function(function_id.function.as_ident_str()).cloned())
+    {
+        return Ok(abi);
+    }
+    resolve_script_function_abi_from_code(state_view, function_id)
+}
+
+fn get_module(state_view: &dyn StateView, module_id: &ModuleId) -> Result<CompiledModule> {
+    let code = state_view
+        .get(&AccessPath::from(module_id))?
+        .ok_or_else(|| format_err!("module {} not exists on chain", module_id))?;
+    CompiledModule::deserialize(code.as_slice())
+        .map_err(|e| format_err!("deserialize module {} error: {:?}", module_id, e))
+}
+
+/// Resolve the ABI of a script function from the module deployed on chain.
+/// The compiled module does not keep the names of arguments, so the arguments are named by
+/// their position, and the leading signer argument is omitted, same as the generated ABI files.
+pub fn resolve_script_function_abi_from_code(
+    state_view: &dyn StateView,
+    function_id: &FunctionId,
+) -> Result<ScriptFunctionABI> {
+    let module = get_module(state_view, &function_id.module)?;
+    let function_def = module
+        .function_defs()
+        .iter()
+        .find(|def| {
+            module.identifier_at(module.function_handle_at(def.function).name)
+                == function_id.function.as_ident_str()
+        })
+        .ok_or_else(|| format_err!("function {} not exists", function_id))?;
+    ensure!(
+        function_def.visibility == Visibility::Script,
+        "function {} is not a script function",
+        function_id
+    );
+    function_abi(&module, function_def)
+}
+
+/// Resolve the ABIs of all the script functions of the compiled module, the args are named by position.
+pub fn script_function_abis_from_module(module: &CompiledModule) -> Result<Vec<ScriptFunctionABI>> {
+    module
+        .function_defs()
+        .iter()
+        .filter(|def| def.visibility == Visibility::Script)
+        .map(|def| function_abi(module, def))
+        .collect()
+}
+
+fn function_abi(
+    module: &CompiledModule,
+    function_def: &FunctionDefinition,
+) -> Result<ScriptFunctionABI> {
+    let handle = module.function_handle_at(function_def.function);
+    let ty_args = (0..handle.type_parameters.len())
+        .map(|i| TypeArgumentABI::new(format!("T{}", i)))
+        .collect();
+    let args = module
+        .signature_at(handle.parameters)
+        .0
+        .iter()
+        .filter(|token| !is_signer(token))
+        .enumerate()
+        .map(|(i, token)| {
+            Ok(ArgumentABI::new(
+                format!("arg{}", i),
+                to_type_tag(module, token)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScriptFunctionABI::new(
+        module.identifier_at(handle.name).to_string(),
+        module.self_id(),
+        String::new(),
+        ty_args,
+        args,
+    ))
+}
+
+/// Build the schema of the event struct `name` from the compiled module, generic structs are not supported.
+pub fn event_abi_from_module(module: &CompiledModule, name: &IdentStr) -> Result<EventABI> {
+    let struct_def = module
+        .struct_defs()
+        .iter()
+        .find(|def| module.identifier_at(module.struct_handle_at(def.struct_handle).name) == name)
+        .ok_or_else(|| format_err!("struct {} not exists in module {}", name, module.self_id()))?;
+    ensure!(
+        module
+            .struct_handle_at(struct_def.struct_handle)
+            .type_parameters
+            .is_empty(),
+        "generic struct {} is not supported as event",
+        name
+    );
+    let fields = match &struct_def.field_information {
+        StructFieldInformation::Native => bail!("native struct {} can not be event", name),
+        StructFieldInformation::Declared(fields) => fields
+            .iter()
+            .map(|field| {
+                Ok(ArgumentABI::new(
+                    module.identifier_at(field.name).to_string(),
+                    to_type_tag(module, &field.signature.0)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+    Ok(EventABI {
+        name: name.to_string(),
+        fields,
+    })
+}
+
+/// The ABI of a module parsed from the bytecode deployed on chain, includes the exposed
+/// (public, friend and script) functions and the layouts of all the structs.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CompiledModuleABI {
+    pub module_id: ModuleId,
+    pub functions: Vec<FunctionABI>,
+    pub structs: Vec<StructABI>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionVisibility {
+    Public,
+    Script,
+    Friend,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TypeParameterABI {
+    pub name: String,
+    pub abilities: Vec<String>,
+}
+
+/// The signature of an exposed function, signer and reference params are kept as declared.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct FunctionABI {
+    pub name: String,
+    pub visibility: FunctionVisibility,
+    pub type_params: Vec<TypeParameterABI>,
+    pub params: Vec<TypeABI>,
+    pub returns: Vec<TypeABI>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct FieldABI {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_abi: TypeABI,
+}
+
+/// The layout of a struct, the fields are in the declared order and empty for native structs.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StructABI {
+    pub name: String,
+    pub abilities: Vec<String>,
+    pub type_params: Vec<TypeParameterABI>,
+    pub is_native: bool,
+    pub fields: Vec<FieldABI>,
+}
+
+/// A type in the function signatures and struct fields, unlike `TypeTag`,
+/// it can be a type parameter or a reference.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeABI {
+    Bool,
+    U8,
+    U64,
+    U128,
+    Address,
+    Signer,
+    Vector(Box<TypeABI>),
+    Struct {
+        address: AccountAddress,
+        module: Identifier,
+        name: Identifier,
+        type_params: Vec<TypeABI>,
+    },
+    /// The index of the type parameter of the function or the struct.
+    TypeParameter(u16),
+    Reference(Box<TypeABI>),
+    MutableReference(Box<TypeABI>),
+}
+
+/// Parse the ABI of `module_id` from the module code on chain, return None if the module not exists.
+pub fn get_compiled_module_abi(
+    state_view: &dyn StateView,
+    module_id: &ModuleId,
+) -> Result<Option<CompiledModuleABI>> {
+    match state_view.get(&AccessPath::from(module_id))? {
+        Some(code) => {
+            let module = CompiledModule::deserialize(code.as_slice())
+                .map_err(|e| format_err!("deserialize module {} error: {:?}", module_id, e))?;
+            Ok(Some(compiled_module_abi(&module)))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn compiled_module_abi(module: &CompiledModule) -> CompiledModuleABI {
+    let functions = module
+        .function_defs()
+        .iter()
+        .filter_map(|def| {
+            let visibility = match def.visibility {
+                Visibility::Public => FunctionVisibility::Public,
+                Visibility::Script => FunctionVisibility::Script,
+                Visibility::Friend => FunctionVisibility::Friend,
+                Visibility::Private => return None,
+            };
+            let handle = module.function_handle_at(def.function);
+            Some(FunctionABI {
+                name: module.identifier_at(handle.name).to_string(),
+                visibility,
+                type_params: type_params_abi(handle.type_parameters.as_slice()),
+                params: signature_abi(module, handle.parameters),
+                returns: signature_abi(module, handle.return_),
+            })
+        })
+        .collect();
+    let structs = module
+        .struct_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            let (is_native, fields) = match &def.field_information {
+                StructFieldInformation::Native => (true, vec![]),
+                StructFieldInformation::Declared(fields) => (
+                    false,
+                    fields
+                        .iter()
+                        .map(|field| FieldABI {
+                            name: module.identifier_at(field.name).to_string(),
+                            type_abi: to_type_abi(module, &field.signature.0),
+                        })
+                        .collect(),
+                ),
+            };
+            StructABI {
+                name: module.identifier_at(handle.name).to_string(),
+                abilities: abilities(handle.abilities),
+                type_params: type_params_abi(handle.type_parameters.as_slice()),
+                is_native,
+                fields,
+            }
+        })
+        .collect();
+    CompiledModuleABI {
+        module_id: module.self_id(),
+        functions,
+        structs,
+    }
+}
+
+fn abilities(ability_set: AbilitySet) -> Vec<String> {
+    [
+        (Ability::Copy, "copy"),
+        (Ability::Drop, "drop"),
+        (Ability::Store, "store"),
+        (Ability::Key, "key"),
+    ]
+    .iter()
+    .filter(|(ability, _)| ability_set.has(*ability))
+    .map(|(_, name)| name.to_string())
+    .collect()
+}
+
+fn type_params_abi(type_params: &[AbilitySet]) -> Vec<TypeParameterABI> {
+    type_params
+        .iter()
+        .enumerate()
+        .map(|(i, ability_set)| TypeParameterABI {
+            name: format!("T{}", i),
+            abilities: abilities(*ability_set),
+        })
+        .collect()
+}
+
+fn signature_abi(module: &CompiledModule, index: SignatureIndex) -> Vec<TypeABI> {
+    module
+        .signature_at(index)
+        .0
+        .iter()
+        .map(|token| to_type_abi(module, token))
+        .collect()
+}
+
+fn to_type_abi(module: &CompiledModule, token: &SignatureToken) -> TypeABI {
+    let struct_abi = |handle_index, type_params: &[SignatureToken]| {
+        let handle = module.struct_handle_at(handle_index);
+        let module_handle = module.module_handle_at(handle.module);
+        TypeABI::Struct {
+            address: *module.address_identifier_at(module_handle.address),
+            module: module.identifier_at(module_handle.name).to_owned(),
+            name: module.identifier_at(handle.name).to_owned(),
+            type_params: type_params
+                .iter()
+                .map(|token| to_type_abi(module, token))
+                .collect(),
+        }
+    };
+    match token {
+        SignatureToken::Bool => TypeABI::Bool,
+        SignatureToken::U8 => TypeABI::U8,
+        SignatureToken::U64 => TypeABI::U64,
+        SignatureToken::U128 => TypeABI::U128,
+        SignatureToken::Address => TypeABI::Address,
+        SignatureToken::Signer => TypeABI::Signer,
+        SignatureToken::Vector(inner) => TypeABI::Vector(Box::new(to_type_abi(module, inner))),
+        SignatureToken::Struct(handle_index) => struct_abi(*handle_index, &[]),
+        SignatureToken::StructInstantiation(handle_index, type_params) => {
+            struct_abi(*handle_index, type_params.as_slice())
+        }
+        SignatureToken::TypeParameter(index) => TypeABI::TypeParameter(*index),
+        SignatureToken::Reference(inner) => {
+            TypeABI::Reference(Box::new(to_type_abi(module, inner)))
+        }
+        SignatureToken::MutableReference(inner) => {
+            TypeABI::MutableReference(Box::new(to_type_abi(module, inner)))
+        }
+    }
+}
+
+fn is_signer(token: &SignatureToken) -> bool {
+    match token {
+        SignatureToken::Signer => true,
+        SignatureToken::Reference(inner) => matches!(**inner, SignatureToken::Signer),
+        _ => false,
+    }
+}
+
+fn to_type_tag(module: &CompiledModule, token: &SignatureToken) -> Result<TypeTag> {
+    let struct_tag = |handle_index, type_params: &[SignatureToken]| -> Result<TypeTag> {
+        let handle = module.struct_handle_at(handle_index);
+        let module_handle = module.module_handle_at(handle.module);
+        Ok(TypeTag::Struct(StructTag {
+            address: *module.address_identifier_at(module_handle.address),
+            module: module.identifier_at(module_handle.name).to_owned(),
+            name: module.identifier_at(handle.name).to_owned(),
+            type_params: type_params
+                .iter()
+                .map(|token| to_type_tag(module, token))
+                .collect::<Result<Vec<_>>>()?,
+        }))
+    };
+    Ok(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(to_type_tag(module, inner)?)),
+        SignatureToken::Struct(handle_index) => struct_tag(*handle_index, &[])?,
+        SignatureToken::StructInstantiation(handle_index, type_params) => {
+            struct_tag(*handle_index, type_params.as_slice())?
+        }
+        _ => bail!("unsupported type {:?}", token),
+    })
+}
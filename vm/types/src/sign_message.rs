@@ -1,9 +1,12 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{ensure, Error, Result};
+use crate::account_address::AccountAddress;
+use anyhow::{bail, ensure, format_err, Error, Result};
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::hash::{CryptoHash, CryptoHasher};
+use starcoin_crypto::HashValue;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
 /// SigningMessage is a message to be signed and encapsulates the salt
@@ -22,3 +25,279 @@ impl FromStr for SigningMessage {
         })
     }
 }
+
+/// The domain of a typed message, it separates the signatures of the same message on different
+/// chains and apps, so a signature for one app can not be replayed to another.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TypedMessageDomain {
+    pub chain_id: u8,
+    pub app_name: String,
+}
+
+/// A field of a struct type in the typed message schema.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TypedMessageField {
+    pub name: String,
+    /// One of `bool`, `u8`, `u64`, `u128`, `address`, `string`, `bytes`, `vector<T>`
+    /// or a struct name defined in the `types`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+/// A schema described message, the `message` json value is encoded by the `primary_type`
+/// and the struct `types`, then signed together with the domain.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TypedMessage {
+    pub domain: TypedMessageDomain,
+    pub types: BTreeMap<String, Vec<TypedMessageField>>,
+    pub primary_type: String,
+    pub message: serde_json::Value,
+}
+
+impl TypedMessage {
+    /// The canonical schema of the primary type, as `Name(type1 field1,type2 field2)`,
+    /// followed by the referenced struct types sorted by name.
+    pub fn encode_type(&self) -> Result<String> {
+        let mut deps = BTreeSet::new();
+        self.collect_deps(&self.primary_type, &mut deps)?;
+        deps.remove(&self.primary_type);
+        let mut encoded = self.encode_struct_type(&self.primary_type)?;
+        for dep in deps {
+            encoded.push_str(self.encode_struct_type(&dep)?.as_str());
+        }
+        Ok(encoded)
+    }
+
+    pub fn type_hash(&self) -> Result<HashValue> {
+        Ok(HashValue::sha3_256_of(self.encode_type()?.as_bytes()))
+    }
+
+    /// Encode the message value by the schema, struct fields are encoded in the schema order,
+    /// vectors, strings and bytes are prefixed by their uleb128 length, like bcs.
+    pub fn encode_message(&self) -> Result<Vec<u8>> {
+        let mut output = vec![];
+        self.encode_value(&self.primary_type, &self.message, &mut output)?;
+        Ok(output)
+    }
+
+    pub fn to_signing_message(&self) -> Result<TypedSigningMessage> {
+        Ok(TypedSigningMessage {
+            domain: self.domain.clone(),
+            type_hash: self.type_hash()?,
+            message: self.encode_message()?,
+        })
+    }
+
+    fn struct_fields(&self, name: &str) -> Result<&Vec<TypedMessageField>> {
+        self.types
+            .get(name)
+            .ok_or_else(|| format_err!("Unknown type {} in typed message", name))
+    }
+
+    fn collect_deps(&self, type_name: &str, deps: &mut BTreeSet<String>) -> Result<()> {
+        if let Some(element_type) = vector_element_type(type_name) {
+            return self.collect_deps(element_type, deps);
+        }
+        if is_primitive_type(type_name) || deps.contains(type_name) {
+            return Ok(());
+        }
+        deps.insert(type_name.to_string());
+        for field in self.struct_fields(type_name)? {
+            self.collect_deps(field.type_name.as_str(), deps)?;
+        }
+        Ok(())
+    }
+
+    fn encode_struct_type(&self, name: &str) -> Result<String> {
+        let fields = self
+            .struct_fields(name)?
+            .iter()
+            .map(|field| format!("{} {}", field.type_name, field.name))
+            .collect::<Vec<_>>();
+        Ok(format!("{}({})", name, fields.join(",")))
+    }
+
+    fn encode_value(
+        &self,
+        type_name: &str,
+        value: &serde_json::Value,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        if let Some(element_type) = vector_element_type(type_name) {
+            let elements = value
+                .as_array()
+                .ok_or_else(|| format_err!("Expect an array for type {}", type_name))?;
+            write_uleb128(elements.len(), output);
+            for element in elements {
+                self.encode_value(element_type, element, output)?;
+            }
+            return Ok(());
+        }
+        match type_name {
+            "bool" => {
+                let v = value
+                    .as_bool()
+                    .ok_or_else(|| format_err!("Expect a bool, got {}", value))?;
+                output.push(v as u8);
+            }
+            "u8" => output.push(parse_integer::<u8>(value)?),
+            "u64" => output.extend(parse_integer::<u64>(value)?.to_le_bytes().iter()),
+            "u128" => output.extend(parse_integer::<u128>(value)?.to_le_bytes().iter()),
+            "address" => {
+                let address = AccountAddress::from_str(expect_str(value)?)?;
+                output.extend(address.to_vec());
+            }
+            "string" => {
+                let v = expect_str(value)?;
+                write_uleb128(v.len(), output);
+                output.extend(v.as_bytes());
+            }
+            "bytes" => {
+                let v = hex::decode(expect_str(value)?.trim_start_matches("0x"))?;
+                write_uleb128(v.len(), output);
+                output.extend(v);
+            }
+            _ => {
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| format_err!("Expect an object for type {}", type_name))?;
+                let fields = self.struct_fields(type_name)?;
+                ensure!(
+                    object.len() == fields.len(),
+                    "Type {} expect {} fields, but got {}",
+                    type_name,
+                    fields.len(),
+                    object.len()
+                );
+                for field in fields {
+                    let field_value = object.get(field.name.as_str()).ok_or_else(|| {
+                        format_err!("Missing field {} of type {}", field.name, type_name)
+                    })?;
+                    self.encode_value(field.type_name.as_str(), field_value, output)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_primitive_type(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "bool" | "u8" | "u64" | "u128" | "address" | "string" | "bytes"
+    )
+}
+
+fn vector_element_type(type_name: &str) -> Option<&str> {
+    type_name
+        .strip_prefix("vector<")
+        .and_then(|t| t.strip_suffix('>'))
+}
+
+fn expect_str(value: &serde_json::Value) -> Result<&str> {
+    value
+        .as_str()
+        .ok_or_else(|| format_err!("Expect a string, got {}", value))
+}
+
+/// Integers can be json numbers or strings, u128 is usually passed as string.
+fn parse_integer<T: FromStr>(value: &serde_json::Value) -> Result<T> {
+    let literal = match value {
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        _ => bail!("Expect an integer, got {}", value),
+    };
+    literal
+        .parse::<T>()
+        .map_err(|_| format_err!("Invalid integer {}", literal))
+}
+
+fn write_uleb128(mut value: usize, output: &mut Vec<u8>) {
+    while value >= 0x80 {
+        output.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// TypedSigningMessage is the signed form of a TypedMessage, it has a different salt from
+/// SigningMessage and transactions.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, CryptoHash)]
+pub struct TypedSigningMessage {
+    pub domain: TypedMessageDomain,
+    pub type_hash: HashValue,
+    pub message: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mail_message(message: serde_json::Value) -> TypedMessage {
+        serde_json::from_value(serde_json::json!({
+            "domain": {"chain_id": 254, "app_name": "mail"},
+            "types": {
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "vector<Person>"},
+                    {"name": "contents", "type": "string"},
+                    {"name": "amount", "type": "u128"},
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"},
+                ],
+            },
+            "primary_type": "Mail",
+            "message": message,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_typed_message_encode() {
+        let message = mail_message(serde_json::json!({
+            "from": {"name": "alice", "wallet": "0x1"},
+            "to": [{"name": "bob", "wallet": "0x2"}],
+            "contents": "hello",
+            "amount": "100",
+        }));
+        assert_eq!(
+            message.encode_type().unwrap(),
+            "Mail(Person from,vector<Person> to,string contents,u128 amount)Person(string name,address wallet)"
+        );
+        let mut expect = vec![];
+        expect.extend(bcs_ext::to_bytes("alice").unwrap());
+        expect.extend(AccountAddress::from_hex_literal("0x1").unwrap().to_vec());
+        expect.push(1);
+        expect.extend(bcs_ext::to_bytes("bob").unwrap());
+        expect.extend(AccountAddress::from_hex_literal("0x2").unwrap().to_vec());
+        expect.extend(bcs_ext::to_bytes("hello").unwrap());
+        expect.extend(bcs_ext::to_bytes(&100u128).unwrap());
+        assert_eq!(message.encode_message().unwrap(), expect);
+
+        let mut other_app = message.clone();
+        other_app.domain.app_name = "other".to_string();
+        assert_ne!(
+            message.to_signing_message().unwrap().crypto_hash(),
+            other_app.to_signing_message().unwrap().crypto_hash()
+        );
+    }
+
+    #[test]
+    fn test_typed_message_invalid() {
+        let missing_field = mail_message(serde_json::json!({
+            "from": {"name": "alice", "wallet": "0x1"},
+            "to": [],
+            "amount": "100",
+        }));
+        assert!(missing_field.encode_message().is_err());
+        let invalid_amount = mail_message(serde_json::json!({
+            "from": {"name": "alice", "wallet": "0x1"},
+            "to": [],
+            "contents": "hello",
+            "amount": "-1",
+        }));
+        assert!(invalid_amount.encode_message().is_err());
+    }
+}
@@ -9,6 +9,10 @@ use anyhow::{ensure, Error, Result};
 use proptest_derive::Arbitrary;
 use rand::{rngs::OsRng, Rng};
 use serde::{Deserialize, Serialize};
+use starcoin_crypto::bls12381::{
+    Bls12381PrivateKey, Bls12381PublicKey, Bls12381Signature, BLS12381_PRIVATE_KEY_LENGTH,
+    BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
+};
 use starcoin_crypto::ed25519::{
     Ed25519PrivateKey, ED25519_PRIVATE_KEY_LENGTH, ED25519_PUBLIC_KEY_LENGTH,
     ED25519_SIGNATURE_LENGTH,
@@ -39,12 +43,18 @@ use std::{convert::TryFrom, fmt, str::FromStr};
 /// `TransactionAuthenticator`'s `AuthenticationKeyPreimage` matches the `AuthenticationKey` stored
 /// under the transaction's sender account address (2).
 
+/// The on-chain `Version` since which the VM accepts the `Scheme::Bls12381` authenticator. The
+/// nodes before the scheme can not decode it, so it is enabled by the framework upgrade after the
+/// nodes are upgraded.
+pub const BLS12381_SCHEME_VERSION: u64 = 5;
+
 // TODO: in the future, can tie these to the TransactionAuthenticator enum directly with https://github.com/rust-lang/rust/issues/60553
 #[derive(Debug)]
 #[repr(u8)]
 pub enum Scheme {
     Ed25519 = 0,
     MultiEd25519 = 1,
+    Bls12381 = 2,
     // ... add more schemes here
 }
 
@@ -53,6 +63,7 @@ impl fmt::Display for Scheme {
         let display = match self {
             Scheme::Ed25519 => "Ed25519",
             Scheme::MultiEd25519 => "MultiEd25519",
+            Scheme::Bls12381 => "Bls12381",
         };
         write!(f, "Scheme::{}", display)
     }
@@ -70,6 +81,11 @@ pub enum TransactionAuthenticator {
         public_key: MultiEd25519PublicKey,
         signature: MultiEd25519Signature,
     },
+    /// BLS12-381 signature, the public key and signature may be aggregated from several signers.
+    Bls12381 {
+        public_key: Bls12381PublicKey,
+        signature: Bls12381Signature,
+    },
     // ... add more schemes here
 }
 
@@ -79,6 +95,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { .. } => Scheme::Ed25519,
             Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::Bls12381 { .. } => Scheme::Bls12381,
         }
     }
 
@@ -101,6 +118,14 @@ impl TransactionAuthenticator {
         }
     }
 
+    /// Create a BLS12-381 authenticator
+    pub fn bls12381(public_key: Bls12381PublicKey, signature: Bls12381Signature) -> Self {
+        Self::Bls12381 {
+            public_key,
+            signature,
+        }
+    }
+
     /// Return Ok if the authenticator's public key matches its signature, Err otherwise
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
@@ -112,6 +137,10 @@ impl TransactionAuthenticator {
                 public_key,
                 signature,
             } => signature.verify(message, public_key),
+            Self::Bls12381 {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
         }
     }
 
@@ -120,6 +149,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
             Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::Bls12381 { public_key, .. } => public_key.to_bytes(),
         }
     }
 
@@ -127,6 +157,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => AccountPublicKey::Single(public_key.clone()),
             Self::MultiEd25519 { public_key, .. } => AccountPublicKey::Multi(public_key.clone()),
+            Self::Bls12381 { public_key, .. } => AccountPublicKey::Bls(public_key.clone()),
         }
     }
 
@@ -135,6 +166,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
             Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::Bls12381 { signature, .. } => signature.to_bytes(),
         }
     }
 
@@ -191,6 +223,11 @@ impl AuthenticationKey {
         Self::from_preimage(&AuthenticationKeyPreimage::multi_ed25519(public_key))
     }
 
+    /// Create an authentication key from a BLS12-381 public key
+    pub fn bls12381(public_key: &Bls12381PublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::bls12381(public_key))
+    }
+
     /// Return an address derived from the last `AccountAddress::LENGTH` bytes of this
     /// authentication key.
     pub fn derived_address(&self) -> AccountAddress {
@@ -246,6 +283,11 @@ impl AuthenticationKeyPreimage {
         Self::new(public_key.to_bytes(), Scheme::MultiEd25519)
     }
 
+    /// Construct a preimage from a BLS12-381 public key
+    pub fn bls12381(public_key: &Bls12381PublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes(), Scheme::Bls12381)
+    }
+
     /// Construct a vector from this authentication key
     pub fn into_vec(self) -> Vec<u8> {
         self.0
@@ -320,18 +362,21 @@ impl fmt::Display for AuthenticationKey {
 pub enum AccountPublicKey {
     Single(Ed25519PublicKey),
     Multi(MultiEd25519PublicKey),
+    Bls(Bls12381PublicKey),
 }
 
 #[derive(Eq, PartialEq, Debug, DeserializeKey, SerializeKey)]
 pub enum AccountPrivateKey {
     Single(Ed25519PrivateKey),
     Multi(MultiEd25519KeyShard),
+    Bls(Bls12381PrivateKey),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, DeserializeKey, SerializeKey, Eq)]
 pub enum AccountSignature {
     Single(Ed25519PublicKey, Ed25519Signature),
     Multi(MultiEd25519PublicKey, MultiEd25519SignatureShard),
+    Bls(Bls12381PublicKey, Bls12381Signature),
 }
 impl ValidCryptoMaterial for AccountSignature {
     fn to_bytes(&self) -> Vec<u8> {
@@ -346,6 +391,11 @@ impl ValidCryptoMaterial for AccountSignature {
                 bytes.extend(multi_signed_shard.to_bytes().to_vec());
                 bytes
             }
+            Self::Bls(public_key, signature) => {
+                let mut bytes = public_key.to_bytes();
+                bytes.extend(signature.to_bytes());
+                bytes
+            }
         }
     }
 }
@@ -354,6 +404,7 @@ impl ValidCryptoMaterial for AccountPublicKey {
         match self {
             Self::Single(key) => key.to_bytes().to_vec(),
             Self::Multi(key) => key.to_bytes(),
+            Self::Bls(key) => key.to_bytes(),
         }
     }
 }
@@ -367,6 +418,7 @@ impl AccountPublicKey {
         match self {
             Self::Single(p) => AuthenticationKeyPreimage::ed25519(p),
             Self::Multi(p) => AuthenticationKeyPreimage::multi_ed25519(p),
+            Self::Bls(p) => AuthenticationKeyPreimage::bls12381(p),
         }
     }
 
@@ -380,6 +432,7 @@ impl AccountPublicKey {
         match self {
             Self::Single(public_key) => public_key.to_bytes().to_vec(),
             Self::Multi(public_key) => public_key.to_bytes().to_vec(),
+            Self::Bls(public_key) => public_key.to_bytes(),
         }
     }
 
@@ -388,6 +441,7 @@ impl AccountPublicKey {
         match self {
             Self::Single { .. } => Scheme::Ed25519,
             Self::Multi { .. } => Scheme::MultiEd25519,
+            Self::Bls { .. } => Scheme::Bls12381,
         }
     }
 
@@ -404,6 +458,13 @@ impl AccountPublicKey {
             _ => None,
         }
     }
+
+    pub fn as_bls(&self) -> Option<Bls12381PublicKey> {
+        match self {
+            Self::Bls(key) => Some(key.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<&[u8]> for AccountPublicKey {
@@ -412,6 +473,9 @@ impl TryFrom<&[u8]> for AccountPublicKey {
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         if value.len() == ED25519_PUBLIC_KEY_LENGTH {
             Ed25519PublicKey::try_from(value).map(Self::Single)
+        } else if value.len() == BLS12381_PUBLIC_KEY_LENGTH {
+            // MultiEd25519PublicKey's length is always `32 * n + 1`, so never conflict with it.
+            Bls12381PublicKey::try_from(value).map(Self::Bls)
         } else {
             MultiEd25519PublicKey::try_from(value).map(Self::Multi)
         }
@@ -432,11 +496,25 @@ impl Into<AccountPublicKey> for MultiEd25519PublicKey {
     }
 }
 
+#[allow(clippy::from_over_into)]
+impl Into<AccountPublicKey> for Bls12381PublicKey {
+    fn into(self) -> AccountPublicKey {
+        AccountPublicKey::Bls(self)
+    }
+}
+
 impl ValidCryptoMaterial for AccountPrivateKey {
     fn to_bytes(&self) -> Vec<u8> {
         match self {
             Self::Single(key) => key.to_bytes().to_vec(),
             Self::Multi(key) => key.to_bytes(),
+            // BLS12-381 private key has the same length as Ed25519 private key,
+            // so prefix it with the scheme id to distinguish them.
+            Self::Bls(key) => {
+                let mut bytes = vec![Scheme::Bls12381 as u8];
+                bytes.extend(key.to_bytes());
+                bytes
+            }
         }
     }
 }
@@ -446,6 +524,7 @@ impl AccountPrivateKey {
         match self {
             Self::Single(key) => AccountPublicKey::Single(key.public_key()),
             Self::Multi(key) => AccountPublicKey::Multi(key.public_key()),
+            Self::Bls(key) => AccountPublicKey::Bls(key.public_key()),
         }
     }
 
@@ -453,6 +532,7 @@ impl AccountPrivateKey {
         match self {
             Self::Single(key) => AccountSignature::Single(key.public_key(), key.sign(message)),
             Self::Multi(key) => AccountSignature::Multi(key.public_key(), key.sign(message)),
+            Self::Bls(key) => AccountSignature::Bls(key.public_key(), key.sign(message)),
         }
     }
 
@@ -475,12 +555,23 @@ impl Into<AccountPrivateKey> for MultiEd25519KeyShard {
     }
 }
 
+#[allow(clippy::from_over_into)]
+impl Into<AccountPrivateKey> for Bls12381PrivateKey {
+    fn into(self) -> AccountPrivateKey {
+        AccountPrivateKey::Bls(self)
+    }
+}
+
 impl TryFrom<&[u8]> for AccountPrivateKey {
     type Error = CryptoMaterialError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         if value.len() == ED25519_PRIVATE_KEY_LENGTH {
             Ed25519PrivateKey::try_from(value).map(Self::Single)
+        } else if value.len() == BLS12381_PRIVATE_KEY_LENGTH + 1
+            && value[0] == Scheme::Bls12381 as u8
+        {
+            Bls12381PrivateKey::try_from(&value[1..]).map(Self::Bls)
         } else {
             MultiEd25519KeyShard::try_from(value).map(Self::Multi)
         }
@@ -496,6 +587,10 @@ impl TryFrom<&[u8]> for AccountSignature {
             let public_key = Ed25519PublicKey::try_from(&value[..ED25519_PUBLIC_KEY_LENGTH])?;
             let signature = Ed25519Signature::try_from(&value[ED25519_PUBLIC_KEY_LENGTH..])?;
             Ok(Self::Single(public_key, signature))
+        } else if length == BLS12381_PUBLIC_KEY_LENGTH + BLS12381_SIGNATURE_LENGTH {
+            let public_key = Bls12381PublicKey::try_from(&value[..BLS12381_PUBLIC_KEY_LENGTH])?;
+            let signature = Bls12381Signature::try_from(&value[BLS12381_PUBLIC_KEY_LENGTH..])?;
+            Ok(Self::Bls(public_key, signature))
         } else {
             // 1 is MultiEd25519PublicKey's threshold
             // 4 is  MultiEd25519Signature's bitmap
@@ -530,24 +625,123 @@ impl AccountSignature {
                     )
                 }
             }
+            Self::Bls(public_key, signature) => {
+                SignedUserTransaction::bls12381(raw_txn, public_key, signature)
+            }
         })
     }
 
+    /// Aggregate the BLS12-381 signatures of the same message into one signature,
+    /// which can be verified by the aggregated public key of the signers.
+    /// `proofs` are the proofs of possession of the signers' keys, in the order of `signatures`,
+    /// see `Bls12381PrivateKey::create_proof_of_possession`.
+    pub fn aggregate(
+        signatures: &[AccountSignature],
+        proofs: &[Bls12381Signature],
+    ) -> Result<AccountSignature> {
+        ensure!(
+            signatures.len() == proofs.len(),
+            "Every signer should have a proof of possession, signatures: {}, proofs: {}",
+            signatures.len(),
+            proofs.len()
+        );
+        let mut public_keys = vec![];
+        let mut bls_signatures = vec![];
+        for (signature, proof) in signatures.iter().zip(proofs) {
+            match signature {
+                Self::Bls(public_key, signature) => {
+                    public_keys.push((public_key.clone(), proof.clone()));
+                    bls_signatures.push(signature.clone());
+                }
+                _ => anyhow::bail!("Only BLS12-381 signatures can be aggregated"),
+            }
+        }
+        Ok(Self::Bls(
+            Bls12381PublicKey::aggregate(public_keys.as_slice())?,
+            Bls12381Signature::aggregate(bls_signatures.as_slice())?,
+        ))
+    }
+
+    pub fn public_key(&self) -> AccountPublicKey {
+        match self {
+            Self::Single(public_key, _) => AccountPublicKey::Single(public_key.clone()),
+            Self::Multi(public_key, _) => AccountPublicKey::Multi(public_key.clone()),
+            Self::Bls(public_key, _) => AccountPublicKey::Bls(public_key.clone()),
+        }
+    }
+
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
             Self::Single(public_key, signature) => signature.verify(message, public_key),
             Self::Multi(public_key, signature) => signature.verify(message, public_key),
+            Self::Bls(public_key, signature) => signature.verify(message, public_key),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::transaction::authenticator::AuthenticationKey;
+    use crate::sign_message::SigningMessage;
+    use crate::transaction::authenticator::{
+        AccountPrivateKey, AccountSignature, AuthenticationKey, TransactionAuthenticator,
+    };
+    use starcoin_crypto::bls12381::Bls12381PrivateKey;
+    use starcoin_crypto::ValidCryptoMaterial;
+    use std::convert::TryFrom;
     use std::str::FromStr;
 
     #[test]
     fn test_from_str_should_not_panic_by_given_empty_string() {
         assert!(AuthenticationKey::from_str("").is_err());
     }
+
+    #[test]
+    fn test_bls_account_key_and_aggregated_signature() {
+        let mut rng = rand::thread_rng();
+        let private_keys = (0..3)
+            .map(|_| AccountPrivateKey::Bls(Bls12381PrivateKey::generate(&mut rng)))
+            .collect::<Vec<_>>();
+        for private_key in &private_keys {
+            let bytes = private_key.to_bytes();
+            assert_eq!(
+                &AccountPrivateKey::try_from(bytes.as_slice()).unwrap(),
+                private_key
+            );
+        }
+
+        let message = SigningMessage::from_str("hello").unwrap();
+        let signatures = private_keys
+            .iter()
+            .map(|k| k.sign(&message))
+            .collect::<Vec<_>>();
+        let proofs = private_keys
+            .iter()
+            .map(|k| match k {
+                AccountPrivateKey::Bls(k) => k.create_proof_of_possession(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert!(AccountSignature::aggregate(signatures.as_slice(), &proofs[..2]).is_err());
+        let mut wrong_proofs = proofs.clone();
+        wrong_proofs.swap(0, 1);
+        assert!(
+            AccountSignature::aggregate(signatures.as_slice(), wrong_proofs.as_slice()).is_err()
+        );
+        let signature =
+            AccountSignature::aggregate(signatures.as_slice(), proofs.as_slice()).unwrap();
+        assert!(signature.verify(&message).is_ok());
+        assert_eq!(
+            AccountSignature::try_from(signature.to_bytes().as_slice()).unwrap(),
+            signature
+        );
+        if let AccountSignature::Bls(public_key, signature) = signature {
+            let authenticator = TransactionAuthenticator::bls12381(public_key, signature);
+            assert!(authenticator.verify(&message).is_ok());
+            assert!(authenticator
+                .verify(&SigningMessage::from_str("world").unwrap())
+                .is_err());
+        } else {
+            panic!("aggregated signature should be bls signature");
+        }
+    }
 }
@@ -4,7 +4,9 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_config::{genesis_address, STC_TOKEN_CODE_STR};
+#[cfg(feature = "full")]
+use crate::account_config::genesis_address;
+use crate::account_config::STC_TOKEN_CODE_STR;
 use crate::block_metadata::BlockMetadata;
 use crate::genesis_config::ChainId;
 use crate::transaction::authenticator::{AccountPublicKey, TransactionAuthenticator};
@@ -16,9 +18,12 @@ use crate::{
     write_set::WriteSet,
 };
 use anyhow::{format_err, Error, Result};
+#[cfg(feature = "full")]
 use bcs_ext::Sample;
 use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(feature = "full")]
 use starcoin_accumulator::inmemory::InMemoryAccumulator;
+use starcoin_crypto::bls12381::{Bls12381PublicKey, Bls12381Signature};
 use starcoin_crypto::multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature};
 use starcoin_crypto::{
     ed25519::*,
@@ -38,6 +43,7 @@ pub use script::{
     ArgumentABI, Script, ScriptABI, ScriptFunction, ScriptFunctionABI, TransactionScriptABI,
     TypeArgumentABI,
 };
+#[cfg(feature = "full")]
 use starcoin_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
 pub use transaction_argument::{
     parse_transaction_argument, parse_transaction_arguments, TransactionArgument,
@@ -183,6 +189,7 @@ impl RawUserTransaction {
     ///
     /// A module transaction is the only way to publish code. Only one module per transaction
     /// can be published.
+    #[cfg(feature = "full")]
     pub fn new_module(
         sender: AccountAddress,
         sequence_number: u64,
@@ -287,6 +294,7 @@ impl RawUserTransaction {
     }
 }
 
+#[cfg(feature = "full")]
 impl Sample for RawUserTransaction {
     fn sample() -> Self {
         Self::new_module(
@@ -374,6 +382,22 @@ pub struct DryRunTransaction {
     pub public_key: AccountPublicKey,
 }
 
+/// The gas used of a dry run txn split by category, in gas units,
+/// the sum of the categories is the `gas_used` of the output.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasBreakdown {
+    /// The intrinsic gas charged by the txn size.
+    pub intrinsic_gas: u64,
+    /// The gas of the Move execution, not including the events.
+    pub execution_gas: u64,
+    /// The gas charged by the accounts mutated by the write set.
+    pub write_set_gas: u64,
+    /// The gas of every event of the output, estimated by the emit event native cost and the event data size.
+    pub events_gas: Vec<u64>,
+    /// The on chain version the gas schedule is loaded at.
+    pub gas_schedule_version: u64,
+}
+
 /// A transaction for which the signature has been verified. Created by
 /// [`SignedUserTransaction::check_signature`] and [`RawUserTransaction::sign`].
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -445,6 +469,15 @@ impl SignedUserTransaction {
         Self::new(raw_txn, authenticator)
     }
 
+    pub fn bls12381(
+        raw_txn: RawUserTransaction,
+        public_key: Bls12381PublicKey,
+        signature: Bls12381Signature,
+    ) -> SignedUserTransaction {
+        let authenticator = TransactionAuthenticator::bls12381(public_key, signature);
+        Self::new(raw_txn, authenticator)
+    }
+
     pub fn authenticator(&self) -> TransactionAuthenticator {
         self.authenticator.clone()
     }
@@ -529,6 +562,7 @@ impl<'de> Deserialize<'de> for SignedUserTransaction {
     }
 }
 
+#[cfg(feature = "full")]
 impl Sample for SignedUserTransaction {
     fn sample() -> Self {
         let raw_txn = RawUserTransaction::sample();
@@ -652,6 +686,7 @@ pub struct TransactionInfo {
 impl TransactionInfo {
     /// Constructs a new `TransactionInfo` object using transaction hash, state root hash and event
     /// root hash.
+    #[cfg(feature = "full")]
     pub fn new(
         transaction_hash: HashValue,
         state_root_hash: HashValue,
@@ -702,6 +737,7 @@ impl TransactionInfo {
     }
 }
 
+#[cfg(feature = "full")]
 impl Sample for TransactionInfo {
     fn sample() -> Self {
         Self::new(
@@ -3,14 +3,15 @@
 
 use crate::account_config::genesis_address;
 use crate::transaction::ScriptFunction;
-use crate::{
-    access::ModuleAccess, account_address::AccountAddress, file_format::CompiledModule,
-    transaction::Module,
-};
+#[cfg(feature = "full")]
+use crate::{access::ModuleAccess, file_format::CompiledModule};
+use crate::{account_address::AccountAddress, transaction::Module};
+#[cfg(feature = "full")]
 use anyhow::{ensure, Result};
 use bcs_ext::Sample;
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::hash::{CryptoHash, CryptoHasher};
+#[cfg(feature = "full")]
 use vm::errors::Location;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, CryptoHash)]
@@ -22,6 +23,7 @@ pub struct Package {
 }
 
 impl Package {
+    #[cfg(feature = "full")]
     pub fn new(modules: Vec<Module>, init_script: Option<ScriptFunction>) -> Result<Self> {
         ensure!(!modules.is_empty(), "must at latest one module");
         let package_address = Self::parse_module_address(&modules[0])?;
@@ -36,10 +38,12 @@ impl Package {
         })
     }
 
+    #[cfg(feature = "full")]
     pub fn new_with_modules(modules: Vec<Module>) -> Result<Self> {
         Self::new(modules, None)
     }
 
+    #[cfg(feature = "full")]
     pub fn new_with_module(module: Module) -> Result<Self> {
         Ok(Self {
             package_address: Self::parse_module_address(&module)?,
@@ -48,6 +52,7 @@ impl Package {
         })
     }
 
+    #[cfg(feature = "full")]
     fn parse_module_address(module: &Module) -> Result<AccountAddress> {
         let compiled_module = CompiledModule::deserialize(module.code())
             .map_err(|e| e.finish(Location::Undefined).into_vm_status())?;
@@ -58,6 +63,7 @@ impl Package {
         self.init_script = Some(script);
     }
 
+    #[cfg(feature = "full")]
     fn check_module_address(
         package_address: &AccountAddress,
         module_address: &AccountAddress,
@@ -71,6 +77,7 @@ impl Package {
         Ok(())
     }
 
+    #[cfg(feature = "full")]
     pub fn add_module(&mut self, module: Module) -> Result<()> {
         let module_address = Self::parse_module_address(&module)?;
         Self::check_module_address(&self.package_address, &module_address)?;
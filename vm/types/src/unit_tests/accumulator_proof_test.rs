@@ -0,0 +1,35 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::accumulator_proof::verify_accumulator_proofs;
+use starcoin_accumulator::tree_store::mock::MockAccumulatorStore;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_crypto::HashValue;
+use std::sync::Arc;
+
+#[test]
+fn test_verify_accumulator_proofs() {
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let leaves = (0..100u64)
+        .map(|i| HashValue::sha3_256_of(&i.to_be_bytes()))
+        .collect::<Vec<_>>();
+    let root_hash = accumulator.append(leaves.as_slice()).unwrap();
+    let leaf_indices = vec![0, 1, 50, 99, 1];
+    let proofs = accumulator.get_proofs(&leaf_indices).unwrap();
+    let items = || {
+        leaf_indices
+            .iter()
+            .zip(proofs.iter())
+            .map(|(i, proof)| (*i, leaves[*i as usize], proof))
+    };
+    verify_accumulator_proofs(root_hash, items()).unwrap();
+    assert!(verify_accumulator_proofs(HashValue::random(), items()).is_err());
+
+    // a wrong leaf is rejected even if its path reaches a verified node.
+    let wrong_leaf = leaf_indices
+        .iter()
+        .zip(proofs.iter())
+        .map(|(i, proof)| (*i, leaves[*i as usize], proof))
+        .chain(std::iter::once((1, leaves[2], &proofs[1])));
+    assert!(verify_accumulator_proofs(root_hash, wrong_leaf).is_err());
+}
@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod access_path_test;
+mod accumulator_proof_test;
 mod block_metadata_test;
 mod transaction_test;
@@ -33,10 +33,15 @@ use starcoin_vm_types::account_config::{
 };
 use starcoin_vm_types::contract_event::ContractEvent;
 use starcoin_vm_types::file_format::CompiledModule;
-use starcoin_vm_types::gas_schedule::{zero_cost_schedule, CostStrategy};
+use starcoin_vm_types::gas_schedule::{
+    native_gas, zero_cost_schedule, CostStrategy, NativeCostIndex,
+};
 use starcoin_vm_types::identifier::IdentStr;
 use starcoin_vm_types::language_storage::ModuleId;
-use starcoin_vm_types::transaction::{DryRunTransaction, Module, Package, TransactionPayloadType};
+use starcoin_vm_types::transaction::authenticator::{Scheme, BLS12381_SCHEME_VERSION};
+use starcoin_vm_types::transaction::{
+    DryRunTransaction, GasBreakdown, Module, Package, TransactionPayloadType,
+};
 use starcoin_vm_types::transaction_metadata::TransactionPayloadMetadata;
 use starcoin_vm_types::value::{serialize_values, MoveValue};
 use starcoin_vm_types::vm_status::KeptVMStatus;
@@ -196,6 +201,21 @@ impl StarcoinVM {
         Ok(())
     }
 
+    /// Reject the authenticator schemes which are not enabled by the on-chain version yet.
+    fn check_authenticator_scheme(&self, txn: &SignedUserTransaction) -> Result<(), VMStatus> {
+        if let Scheme::Bls12381 = txn.authenticator().scheme() {
+            let version = self.get_version()?;
+            if version.major < BLS12381_SCHEME_VERSION {
+                warn!(
+                    "[VM] The Bls12381 scheme is enabled since version {}, current version {}",
+                    BLS12381_SCHEME_VERSION, version.major
+                );
+                return Err(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
+            }
+        }
+        Ok(())
+    }
+
     fn verify_transaction_impl(
         &mut self,
         transaction: &SignatureCheckedTransaction,
@@ -248,6 +268,9 @@ impl StarcoinVM {
             warn!("Load config error at verify_transaction: {}", err);
             return Some(VMStatus::Error(StatusCode::VM_STARTUP_FAILURE));
         }
+        if let Err(err) = self.check_authenticator_scheme(&signature_verified_txn) {
+            return Some(err);
+        }
         match self.verify_transaction_impl(&signature_verified_txn, &data_cache) {
             Ok(_) => None,
             Err(err) => {
@@ -331,6 +354,7 @@ impl StarcoinVM {
         cost_strategy: &mut CostStrategy,
         txn_data: &TransactionMetadata,
         package: &Package,
+        gas_breakdown: &mut GasBreakdown,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         let mut session = self.move_vm.new_session(remote_cache);
 
@@ -349,9 +373,13 @@ impl StarcoinVM {
             if !remote_cache.is_genesis() {
                 cost_strategy.enable_metering();
             }
+            let gas_before_intrinsic = cost_strategy.remaining_gas();
             cost_strategy
                 .charge_intrinsic_gas(txn_data.transaction_size())
                 .map_err(|e| e.into_vm_status())?;
+            gas_breakdown.intrinsic_gas = gas_before_intrinsic
+                .sub(cost_strategy.remaining_gas())
+                .get();
 
             let package_address = package.package_address();
             let enforced = match Self::is_enforced(remote_cache, package_address) {
@@ -428,7 +456,9 @@ impl StarcoinVM {
                     )
                     .map_err(|e| e.into_vm_status())?;
             }
+            let gas_before_write = cost_strategy.remaining_gas();
             charge_global_write_gas_usage(cost_strategy, &session, &txn_data.sender())?;
+            gas_breakdown.write_set_gas = gas_before_write.sub(cost_strategy.remaining_gas()).get();
 
             cost_strategy.disable_metering();
             self.success_transaction_cleanup(
@@ -447,6 +477,7 @@ impl StarcoinVM {
         cost_strategy: &mut CostStrategy,
         txn_data: &TransactionMetadata,
         payload: &TransactionPayload,
+        gas_breakdown: &mut GasBreakdown,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         let mut session = self.move_vm.new_session(remote_cache);
 
@@ -462,9 +493,13 @@ impl StarcoinVM {
         {
             //let _timer = TXN_EXECUTION_SECONDS.start_timer();
             cost_strategy.enable_metering();
+            let gas_before_intrinsic = cost_strategy.remaining_gas();
             cost_strategy
                 .charge_intrinsic_gas(txn_data.transaction_size())
                 .map_err(|e| e.into_vm_status())?;
+            gas_breakdown.intrinsic_gas = gas_before_intrinsic
+                .sub(cost_strategy.remaining_gas())
+                .get();
             match payload {
                 TransactionPayload::Script(script) => session.execute_script(
                     script.code().to_vec(),
@@ -488,7 +523,9 @@ impl StarcoinVM {
             }
             .map_err(|e| e.into_vm_status())?;
 
+            let gas_before_write = cost_strategy.remaining_gas();
             charge_global_write_gas_usage(cost_strategy, &session, &txn_data.sender())?;
+            gas_breakdown.write_set_gas = gas_before_write.sub(cost_strategy.remaining_gas()).get();
 
             cost_strategy.disable_metering();
             self.success_transaction_cleanup(
@@ -686,6 +723,9 @@ impl StarcoinVM {
             }
         };
         let mut cost_strategy = CostStrategy::system(gas_schedule, txn_data.max_gas_amount());
+        if let Err(e) = self.check_authenticator_scheme(&txn) {
+            return discard_error_vm_status(e);
+        }
         // check signature
         let signature_checked_txn = match txn.check_signature() {
             Ok(t) => Ok(t),
@@ -703,6 +743,7 @@ impl StarcoinVM {
                             &mut cost_strategy,
                             &txn_data,
                             payload,
+                            &mut GasBreakdown::default(),
                         ),
                     TransactionPayload::Package(p) => self.execute_package(
                         remote_cache,
@@ -710,6 +751,7 @@ impl StarcoinVM {
                         &mut cost_strategy,
                         &txn_data,
                         p,
+                        &mut GasBreakdown::default(),
                     ),
                 };
                 match result {
@@ -743,12 +785,13 @@ impl StarcoinVM {
         }
     }
 
+    /// Dry run the txn, and split the gas used of the output by category.
     pub fn dry_run_transaction(
         &mut self,
 
         state_view: &dyn StateView,
         txn: DryRunTransaction,
-    ) -> Result<(VMStatus, TransactionOutput)> {
+    ) -> Result<(VMStatus, TransactionOutput, GasBreakdown)> {
         let remote_cache = StateViewCache::new(state_view);
         //TODO load config by config change event.
         self.load_configs(&remote_cache)?;
@@ -759,7 +802,8 @@ impl StarcoinVM {
                 if remote_cache.is_genesis() {
                     &INITIAL_GAS_SCHEDULE
                 } else {
-                    return Ok(discard_error_vm_status(e));
+                    let (status, output) = discard_error_vm_status(e);
+                    return Ok((status, output, GasBreakdown::default()));
                 }
             }
         };
@@ -768,9 +812,13 @@ impl StarcoinVM {
             txn.public_key.authentication_key_preimage(),
         ) {
             Ok(txn_data) => txn_data,
-            Err(e) => return Ok(discard_error_vm_status(e)),
+            Err(e) => {
+                let (status, output) = discard_error_vm_status(e);
+                return Ok((status, output, GasBreakdown::default()));
+            }
         };
         let mut cost_strategy = CostStrategy::system(gas_schedule, txn_data.max_gas_amount());
+        let mut gas_breakdown = GasBreakdown::default();
         let result = match txn.raw_txn.payload() {
             payload @ TransactionPayload::Script(_)
             | payload @ TransactionPayload::ScriptFunction(_) => self
@@ -780,6 +828,7 @@ impl StarcoinVM {
                     &mut cost_strategy,
                     &txn_data,
                     payload,
+                    &mut gas_breakdown,
                 ),
             TransactionPayload::Package(p) => self.execute_package(
                 &remote_cache,
@@ -787,9 +836,10 @@ impl StarcoinVM {
                 &mut cost_strategy,
                 &txn_data,
                 p,
+                &mut gas_breakdown,
             ),
         };
-        Ok(match result {
+        let (status, output) = match result {
             Ok(status_and_output) => status_and_output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -805,7 +855,36 @@ impl StarcoinVM {
                     )
                 }
             }
-        })
+        };
+        if output.status().is_discarded() {
+            return Ok((status, output, GasBreakdown::default()));
+        }
+        // the events are emitted by the native function, so they are charged as a part of the execution.
+        gas_breakdown.events_gas = output
+            .events()
+            .iter()
+            .map(|event| {
+                gas_schedule
+                    .gas_constants
+                    .to_external_units(native_gas(
+                        gas_schedule,
+                        NativeCostIndex::EMIT_EVENT,
+                        event.event_data().len(),
+                    ))
+                    .get()
+            })
+            .collect();
+        gas_breakdown.execution_gas = output
+            .gas_used()
+            .saturating_sub(gas_breakdown.intrinsic_gas)
+            .saturating_sub(gas_breakdown.write_set_gas)
+            .saturating_sub(gas_breakdown.events_gas.iter().sum());
+        gas_breakdown.gas_schedule_version = self
+            .version
+            .as_ref()
+            .map(|version| version.major)
+            .unwrap_or_default();
+        Ok((status, output, gas_breakdown))
     }
 
     /// Execute a block transactions with gas_limit,